@@ -24,11 +24,12 @@ const HOOK_EVENT_VARIANTS: &[(ClaudeEventType, &str)] = &[
     (ClaudeEventType::PreCompact, "PreCompact"),
     (ClaudeEventType::Setup, "Setup"),
     (ClaudeEventType::Notification, "Notification"),
+    (ClaudeEventType::PermissionRequest, "PermissionRequest"),
 ];
 
 /// Types of hook events from Claude Code.
 ///
-/// All 12 Claude Code hook events, based on official documentation.
+/// All 13 Claude Code hook events, based on official documentation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub enum ClaudeEventType {
@@ -67,6 +68,10 @@ pub enum ClaudeEventType {
     // === Notifications ===
     /// Informational notification
     Notification,
+
+    // === Permissions ===
+    /// A permission dialog is being shown for a tool call
+    PermissionRequest,
 }
 
 impl ClaudeEventType {
@@ -211,6 +216,12 @@ mod tests {
             ClaudeEventType::from_event_name("Notification"),
             Some(ClaudeEventType::Notification)
         );
+
+        // Permissions
+        assert_eq!(
+            ClaudeEventType::from_event_name("PermissionRequest"),
+            Some(ClaudeEventType::PermissionRequest)
+        );
     }
 
     #[test]
@@ -8,7 +8,7 @@
 //!
 //! ## Layers
 //!
-//! - [`event`] — `ClaudeEventType` enum (the 12 Claude hook event names)
+//! - [`event`] — `ClaudeEventType` enum (the 13 Claude hook event names)
 //! - [`wire`] — `RawHookEvent` struct (deserialized JSON Claude sends
 //!   on stdin to the hook script)
 //! - [`translate`] — translation from raw event to `LifecycleEvent`
@@ -5,7 +5,7 @@
 //! types. The daemon calls this at the connection boundary and
 //! everything downstream sees only `LifecycleEvent`.
 
-use atm_core::{LifecycleEvent, NeedsInputReason, NotificationKind, Tool};
+use atm_core::{LifecycleEvent, NeedsInputReason, NotificationKind, PromptState, Tool};
 
 use crate::event::ClaudeEventType;
 use crate::wire::RawHookEvent;
@@ -27,9 +27,9 @@ impl RawHookEvent {
     /// variants.
     pub fn to_lifecycle_event(&self) -> Option<LifecycleEvent> {
         let ev = self.event_type()?;
-        // Tool-name presence is required for the three tool-shaped
-        // events. A PreToolUse / PostToolUse / PostToolUseFailure
-        // without a tool_name is malformed; returning `None` (treat as
+        // Tool-name presence is required for the tool-shaped events.
+        // A PreToolUse / PostToolUse / PostToolUseFailure /
+        // PermissionRequest without a tool_name is malformed; returning `None` (treat as
         // unknown event) is safer than fabricating `Tool::Other("")`,
         // which would silently inject phantom tool-call records into
         // the registry.
@@ -38,6 +38,7 @@ impl RawHookEvent {
             ClaudeEventType::PreToolUse
                 | ClaudeEventType::PostToolUse
                 | ClaudeEventType::PostToolUseFailure
+                | ClaudeEventType::PermissionRequest
         );
         let tool_name = self.tool_name.as_deref().unwrap_or("");
         if needs_tool && tool_name.is_empty() {
@@ -48,6 +49,7 @@ impl RawHookEvent {
             ClaudeEventType::PreToolUse => {
                 if tool.is_interactive() {
                     LifecycleEvent::NeedsInput {
+                        prompt: PromptState::for_interactive_tool(&tool, self.tool_input.as_ref()),
                         reason: NeedsInputReason::InteractiveTool { tool },
                     }
                 } else {
@@ -58,6 +60,13 @@ impl RawHookEvent {
                     }
                 }
             }
+            // Fires as the permission dialog opens, before the
+            // `Notification(permission_prompt)` that follows it, and is
+            // the only one of the two that says what is being asked.
+            ClaudeEventType::PermissionRequest => LifecycleEvent::NeedsInput {
+                prompt: Some(PromptState::permission(&tool, self.tool_input.as_ref())),
+                reason: NeedsInputReason::PermissionGate { tool },
+            },
             ClaudeEventType::PostToolUse => LifecycleEvent::ToolCallEnd {
                 name: tool,
                 tool_use_id: self.tool_use_id.clone(),
@@ -108,6 +117,9 @@ impl RawHookEvent {
                             // a per-prompt label — only a kind tag.
                             label: None,
                         },
+                        // Any prompt came with the `PermissionRequest`
+                        // or `PreToolUse` before this.
+                        prompt: None,
                     },
                     Some(NotificationKind::IdlePrompt) => LifecycleEvent::Idle,
                     _ => LifecycleEvent::Notification {
@@ -132,6 +144,7 @@ mod tests {
             permission_mode: None,
             pid: None,
             tmux_pane: None,
+            team: None,
             tool_name: None,
             tool_input: None,
             tool_response: None,
@@ -188,16 +201,54 @@ mod tests {
         ] {
             let mut e = raw("PreToolUse");
             e.tool_name = Some(name.into());
+            let prompt = PromptState::for_interactive_tool(&expected, None);
             assert_eq!(
                 e.to_lifecycle_event(),
                 Some(LifecycleEvent::NeedsInput {
-                    reason: NeedsInputReason::InteractiveTool { tool: expected }
+                    reason: NeedsInputReason::InteractiveTool { tool: expected },
+                    prompt,
                 }),
                 "tool {name} should map to NeedsInput"
             );
         }
     }
 
+    #[test]
+    fn ask_user_question_carries_prompt() {
+        let mut e = raw("PreToolUse");
+        e.tool_name = Some("AskUserQuestion".into());
+        e.tool_input = Some(serde_json::json!({
+            "questions": [{"question": "Which one?", "options": [{"label": "A"}, {"label": "B"}]}]
+        }));
+        match e.to_lifecycle_event() {
+            Some(LifecycleEvent::NeedsInput {
+                prompt: Some(prompt),
+                ..
+            }) => {
+                assert_eq!(prompt.question.as_deref(), Some("Which one?"));
+                assert_eq!(prompt.options.len(), 2);
+            }
+            other => panic!("expected NeedsInput with a prompt, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn permission_request_becomes_permission_gate_with_prompt() {
+        let mut e = raw("PermissionRequest");
+        e.tool_name = Some("Bash".into());
+        e.tool_input = Some(serde_json::json!({"command": "rm -rf target"}));
+        assert_eq!(
+            e.to_lifecycle_event(),
+            Some(LifecycleEvent::NeedsInput {
+                reason: NeedsInputReason::PermissionGate { tool: Tool::Bash },
+                prompt: Some(PromptState::permission(
+                    &Tool::Bash,
+                    Some(&serde_json::json!({"command": "rm -rf target"}))
+                )),
+            })
+        );
+    }
+
     #[test]
     fn tool_shaped_event_without_tool_name_returns_none() {
         // PreToolUse / PostToolUse / PostToolUseFailure without a
@@ -205,7 +256,12 @@ mod tests {
         // Tool::Other("") and inject phantom records into the registry.
         // The guard now drops them; verify across both `None` and
         // empty-string forms.
-        for name in ["PreToolUse", "PostToolUse", "PostToolUseFailure"] {
+        for name in [
+            "PreToolUse",
+            "PostToolUse",
+            "PostToolUseFailure",
+            "PermissionRequest",
+        ] {
             assert_eq!(
                 raw(name).to_lifecycle_event(),
                 None,
@@ -221,7 +277,7 @@ mod tests {
         }
 
         // Negative: a non-tool event without tool_name still translates
-        // (the guard scopes to the three tool-shaped events only).
+        // (the guard scopes to the tool-shaped events only).
         assert!(raw("Stop").to_lifecycle_event().is_some());
     }
 
@@ -352,7 +408,8 @@ mod tests {
                 reason: NeedsInputReason::Notification {
                     kind: NotificationKind::PermissionPrompt,
                     label: None,
-                }
+                },
+                prompt: None,
             })
         );
     }
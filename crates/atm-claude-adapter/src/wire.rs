@@ -30,6 +30,8 @@ pub struct RawHookEvent {
     pub pid: Option<u32>,
     #[serde(default)]
     pub tmux_pane: Option<String>,
+    #[serde(default)]
+    pub team: Option<String>,
 
     // === Tool Events (PreToolUse, PostToolUse, PostToolUseFailure) ===
     #[serde(default)]
@@ -0,0 +1,92 @@
+//! Event types from Codex CLI.
+//!
+//! `CodexEventType` is the Codex-specific raw event vocabulary. Names
+//! are kebab-case like Codex's `notify` payloads; the snake_case
+//! spelling used by Codex's protocol `EventMsg` (`agent_turn_complete`)
+//! is accepted too.
+
+use std::fmt;
+
+/// All CodexEventType variants paired with their string names.
+/// Single source of truth for string conversion.
+const CODEX_EVENT_VARIANTS: &[(CodexEventType, &str)] = &[
+    (CodexEventType::SessionStart, "session-start"),
+    (CodexEventType::SessionEnd, "session-end"),
+    (CodexEventType::UserPromptSubmit, "user-prompt-submit"),
+    (CodexEventType::AgentTurnComplete, "agent-turn-complete"),
+    (CodexEventType::ApprovalRequested, "approval-requested"),
+];
+
+/// Types of events from Codex CLI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CodexEventType {
+    // === Session Lifecycle ===
+    /// Session configured (new or resumed thread)
+    SessionStart,
+    /// Session shut down
+    SessionEnd,
+
+    // === Turns ===
+    /// User submitted a prompt
+    UserPromptSubmit,
+    /// Agent finished a turn (the stock `notify` event)
+    AgentTurnComplete,
+    /// Codex is waiting for the user to approve a command or patch
+    ApprovalRequested,
+}
+
+impl CodexEventType {
+    /// Returns the canonical (kebab-case) name for this event type.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        for (variant, name) in CODEX_EVENT_VARIANTS {
+            if variant == self {
+                return name;
+            }
+        }
+        // This is unreachable if CODEX_EVENT_VARIANTS is complete
+        "unknown"
+    }
+
+    /// Parses from an event name, accepting kebab-case or snake_case.
+    #[must_use]
+    pub fn from_event_name(name: &str) -> Option<Self> {
+        let normalized = name.replace('_', "-");
+        CODEX_EVENT_VARIANTS
+            .iter()
+            .find(|(_, s)| *s == normalized)
+            .map(|(v, _)| *v)
+    }
+}
+
+impl fmt::Display for CodexEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_roundtrip() {
+        for (variant, name) in CODEX_EVENT_VARIANTS {
+            assert_eq!(variant.as_str(), *name);
+            assert_eq!(CodexEventType::from_event_name(name), Some(*variant));
+        }
+    }
+
+    #[test]
+    fn test_snake_case_protocol_names_parse() {
+        assert_eq!(
+            CodexEventType::from_event_name("agent_turn_complete"),
+            Some(CodexEventType::AgentTurnComplete)
+        );
+        assert_eq!(
+            CodexEventType::from_event_name("approval_requested"),
+            Some(CodexEventType::ApprovalRequested)
+        );
+        assert_eq!(CodexEventType::from_event_name("exec_command_begin"), None);
+    }
+}
@@ -0,0 +1,34 @@
+//! Codex CLI adapter for ATM.
+//!
+//! All Codex-specific knowledge — the event vocabulary, the wire
+//! payload shape, and the translation into vendor-neutral
+//! `atm_core::LifecycleEvent` — lives in this crate. Symmetric with
+//! `atm_claude_adapter` and `atm_pi_adapter`.
+//!
+//! ## How events arrive
+//!
+//! Codex runs the program configured as `notify` in
+//! `$CODEX_HOME/config.toml` with one JSON argument per event. `atm
+//! setup` points `notify` at the `atm-codex-hook` script, which injects
+//! the Codex pid and tmux pane and forwards the payload to atmd as
+//! `MessageType::CodexEvent { data }`.
+//!
+//! Stock Codex only notifies `agent-turn-complete` (and, on builds with
+//! TUI notifications, `approval-requested`), so Codex sessions are
+//! status-only: ATM shows when a turn ends or an approval is pending,
+//! but not which tool is running or how full the context is. The
+//! session and prompt variants in [`event::CodexEventType`] are there
+//! for forwarders that see more of Codex's event stream.
+//!
+//! ## Layers
+//!
+//! - [`event`] — `CodexEventType` enum (the Codex event names we handle)
+//! - [`wire`] — `RawCodexEvent` struct (the JSON Codex hands to `notify`)
+//! - [`translate`] — translation from raw event to `LifecycleEvent`
+
+pub mod event;
+pub mod translate;
+pub mod wire;
+
+pub use event::CodexEventType;
+pub use wire::RawCodexEvent;
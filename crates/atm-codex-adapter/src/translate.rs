@@ -0,0 +1,174 @@
+//! Translation from Codex `RawCodexEvent` to vendor-neutral
+//! `LifecycleEvent`.
+//!
+//! The single place Codex semantics map to atm-core types.
+//!
+//! ## Mapping table
+//!
+//! | Codex event           | LifecycleEvent                                    |
+//! |-----------------------|---------------------------------------------------|
+//! | `session-start`       | `SessionStart { source }`                         |
+//! | `session-end`         | `SessionEnd { reason }`                           |
+//! | `user-prompt-submit`  | `PromptSubmit { prompt: last input message }`     |
+//! | `agent-turn-complete` | `WorkingEnd`                                      |
+//! | `approval-requested`  | `NeedsInput { Notification(PermissionPrompt) }`   |
+//! | any event with `model`| `ProviderModelChange { provider: "openai" }`      |
+//!
+//! The model rides along on other events (usually `session-start`), so
+//! it comes from [`RawCodexEvent::model_change`], applied after the
+//! event's own translation.
+//!
+//! There are no tool or context rows: `notify` never carries Codex's
+//! exec, patch, MCP or token-count events.
+
+use atm_core::{LifecycleEvent, NeedsInputReason, NotificationKind};
+
+use crate::event::CodexEventType;
+use crate::wire::RawCodexEvent;
+
+/// Provider reported with Codex model changes.
+const PROVIDER: &str = "openai";
+
+impl RawCodexEvent {
+    /// Translates this Codex raw event into a vendor-neutral
+    /// `LifecycleEvent`.
+    ///
+    /// Returns `None` for unknown event names.
+    pub fn to_lifecycle_event(&self) -> Option<LifecycleEvent> {
+        Some(match self.event_type()? {
+            CodexEventType::SessionStart => LifecycleEvent::SessionStart {
+                source: self.source.clone(),
+            },
+            CodexEventType::SessionEnd => LifecycleEvent::SessionEnd {
+                reason: self.reason.clone(),
+            },
+            CodexEventType::UserPromptSubmit => LifecycleEvent::PromptSubmit {
+                prompt: self.input_messages.last().cloned(),
+            },
+            CodexEventType::AgentTurnComplete => LifecycleEvent::WorkingEnd,
+            CodexEventType::ApprovalRequested => LifecycleEvent::NeedsInput {
+                reason: NeedsInputReason::Notification {
+                    kind: NotificationKind::PermissionPrompt,
+                    // The command awaiting approval, when Codex says
+                    label: self.command_line(),
+                },
+                prompt: None,
+            },
+        })
+    }
+
+    /// The model this event reports, as a `ProviderModelChange`.
+    ///
+    /// Returns `None` when the event carries no (or an empty) model.
+    pub fn model_change(&self) -> Option<LifecycleEvent> {
+        let model = self.model.as_deref().filter(|m| !m.is_empty())?;
+        Some(LifecycleEvent::ProviderModelChange {
+            provider: Some(PROVIDER.to_string()),
+            model: Some(model.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(event: &str) -> RawCodexEvent {
+        RawCodexEvent {
+            event: event.into(),
+            thread_id: Some("t".into()),
+            turn_id: None,
+            cwd: None,
+            pid: None,
+            tmux_pane: None,
+            team: None,
+            input_messages: Vec::new(),
+            last_assistant_message: None,
+            command: None,
+            model: None,
+            source: None,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn turn_complete_to_working_end() {
+        assert_eq!(
+            raw("agent-turn-complete").to_lifecycle_event(),
+            Some(LifecycleEvent::WorkingEnd)
+        );
+    }
+
+    #[test]
+    fn prompt_submit_carries_last_input_message() {
+        let mut e = raw("user-prompt-submit");
+        e.input_messages = vec!["first".into(), "fix the build".into()];
+        assert_eq!(
+            e.to_lifecycle_event(),
+            Some(LifecycleEvent::PromptSubmit {
+                prompt: Some("fix the build".into())
+            })
+        );
+    }
+
+    #[test]
+    fn approval_requested_labels_command() {
+        let mut e = raw("approval-requested");
+        e.command = Some(serde_json::json!(["rm", "-rf", "target"]));
+        assert_eq!(
+            e.to_lifecycle_event(),
+            Some(LifecycleEvent::NeedsInput {
+                reason: NeedsInputReason::Notification {
+                    kind: NotificationKind::PermissionPrompt,
+                    label: Some("rm -rf target".into()),
+                },
+                prompt: None,
+            })
+        );
+    }
+
+    #[test]
+    fn session_lifecycle_carries_source_and_reason() {
+        let mut start = raw("session-start");
+        start.source = Some("resume".into());
+        assert_eq!(
+            start.to_lifecycle_event(),
+            Some(LifecycleEvent::SessionStart {
+                source: Some("resume".into())
+            })
+        );
+
+        let mut end = raw("session-end");
+        end.reason = Some("exit".into());
+        assert_eq!(
+            end.to_lifecycle_event(),
+            Some(LifecycleEvent::SessionEnd {
+                reason: Some("exit".into())
+            })
+        );
+    }
+
+    #[test]
+    fn model_reported_as_provider_model_change() {
+        let mut start = raw("session-start");
+        assert_eq!(start.model_change(), None);
+
+        start.model = Some(String::new());
+        assert_eq!(start.model_change(), None);
+
+        start.model = Some("gpt-5-codex".into());
+        assert_eq!(
+            start.model_change(),
+            Some(LifecycleEvent::ProviderModelChange {
+                provider: Some("openai".into()),
+                model: Some("gpt-5-codex".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_event_returns_none() {
+        assert_eq!(raw("agent-message-delta").to_lifecycle_event(), None);
+        assert_eq!(raw("exec_command_begin").to_lifecycle_event(), None);
+    }
+}
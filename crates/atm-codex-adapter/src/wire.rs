@@ -0,0 +1,141 @@
+//! Raw Codex event payload (the JSON Codex passes to its `notify`
+//! program).
+//!
+//! Flat structure with all possible fields as `Option<T>`, like
+//! `atm_claude_adapter::RawHookEvent`. Codex's `notify` payload uses
+//! kebab-case keys (`thread-id`, `input-messages`); snake_case aliases
+//! are accepted for payloads built from protocol `EventMsg`s. Use
+//! [`RawCodexEvent::to_lifecycle_event`] to convert into a
+//! vendor-neutral `LifecycleEvent`.
+
+use atm_core::SessionId;
+use serde::Deserialize;
+
+use crate::event::CodexEventType;
+
+/// Raw event JSON from Codex CLI.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawCodexEvent {
+    // === Common Fields ===
+    /// Event name, e.g. `"agent-turn-complete"`.
+    #[serde(rename = "type")]
+    pub event: String,
+    /// Codex thread (conversation) id; ATM's session id.
+    #[serde(
+        default,
+        rename = "thread-id",
+        alias = "thread_id",
+        alias = "session_id"
+    )]
+    pub thread_id: Option<String>,
+    #[serde(default, rename = "turn-id", alias = "turn_id")]
+    pub turn_id: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+
+    // === Injected by atm-codex-hook ===
+    #[serde(default)]
+    pub pid: Option<u32>,
+    #[serde(default)]
+    pub tmux_pane: Option<String>,
+    #[serde(default)]
+    pub team: Option<String>,
+
+    // === Turns (agent-turn-complete, user-prompt-submit) ===
+    #[serde(default, rename = "input-messages", alias = "input_messages")]
+    pub input_messages: Vec<String>,
+    #[serde(
+        default,
+        rename = "last-assistant-message",
+        alias = "last_assistant_message"
+    )]
+    pub last_assistant_message: Option<String>,
+
+    // === Approvals (approval-requested) ===
+    /// Shell command awaiting approval, as an argv array or a single string.
+    #[serde(default)]
+    pub command: Option<serde_json::Value>,
+
+    // === Session Events ===
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl RawCodexEvent {
+    /// Parses the event type.
+    pub fn event_type(&self) -> Option<CodexEventType> {
+        CodexEventType::from_event_name(&self.event)
+    }
+
+    /// Returns the session ID, if the payload names a thread.
+    pub fn session_id(&self) -> Option<SessionId> {
+        self.thread_id
+            .as_deref()
+            .filter(|id| !id.is_empty())
+            .map(SessionId::new)
+    }
+
+    /// Returns the shell command as a single display string.
+    pub fn command_line(&self) -> Option<String> {
+        match self.command.as_ref()? {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Array(parts) => {
+                let words: Vec<&str> = parts.iter().filter_map(|p| p.as_str()).collect();
+                Some(words.join(" "))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_notify_turn_complete() {
+        let json = r#"{
+            "type": "agent-turn-complete",
+            "thread-id": "b5f6c1c2-1111-2222-3333-444455556666",
+            "turn-id": "12345",
+            "cwd": "/home/me/project",
+            "input-messages": ["Rename `foo` to `bar` and update the callsites."],
+            "last-assistant-message": "Rename complete and verified `cargo build` succeeds."
+        }"#;
+        let event: RawCodexEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.event_type(), Some(CodexEventType::AgentTurnComplete));
+        assert_eq!(
+            event.session_id(),
+            Some(SessionId::new("b5f6c1c2-1111-2222-3333-444455556666"))
+        );
+        assert_eq!(event.input_messages.len(), 1);
+        assert_eq!(event.cwd.as_deref(), Some("/home/me/project"));
+    }
+
+    #[test]
+    fn parse_snake_case_approval_requested() {
+        let json = r#"{
+            "type": "approval_requested",
+            "thread_id": "t-1",
+            "command": ["bash", "-lc", "cargo test"],
+            "pid": 4242,
+            "tmux_pane": "%7"
+        }"#;
+        let event: RawCodexEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.event_type(), Some(CodexEventType::ApprovalRequested));
+        assert_eq!(event.command_line().as_deref(), Some("bash -lc cargo test"));
+        assert_eq!(event.pid, Some(4242));
+        assert_eq!(event.tmux_pane.as_deref(), Some("%7"));
+    }
+
+    #[test]
+    fn missing_or_empty_thread_has_no_session_id() {
+        let event: RawCodexEvent =
+            serde_json::from_str(r#"{"type": "agent-turn-complete", "thread-id": ""}"#).unwrap();
+        assert_eq!(event.session_id(), None);
+    }
+}
@@ -1,9 +1,16 @@
 //! Context window and token tracking.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::fmt;
 use std::ops::{Add, AddAssign};
 
+/// Context usage (percent of the window) at which harnesses compact
+/// automatically. Claude Code triggers somewhere in the low-to-mid 90s;
+/// forecasts count down to this point rather than to a full window.
+pub const AUTO_COMPACT_PERCENT: f64 = 92.0;
+
 /// Represents a count of tokens.
 ///
 /// Used for input tokens, output tokens, cache tokens.
@@ -183,6 +190,13 @@ impl ContextUsage {
         TokenCount::new(limit.saturating_sub(used))
     }
 
+    /// Returns the tokens left before auto-compaction kicks in
+    /// (see [`AUTO_COMPACT_PERCENT`]).
+    pub fn tokens_until_auto_compact(&self) -> TokenCount {
+        let threshold = (self.context_window_size as f64 * AUTO_COMPACT_PERCENT / 100.0) as u64;
+        TokenCount::new(threshold.saturating_sub(self.context_tokens().as_u64()))
+    }
+
     /// Formats usage for display (e.g., "45.2% (26.4K/200K)").
     pub fn format(&self) -> String {
         format!(
@@ -255,7 +269,8 @@ impl ContextAnalyzer {
         }
     }
 
-    /// Estimates remaining "turns" based on average token usage per turn.
+    /// Estimates remaining "turns" before auto-compaction, based on average
+    /// token growth per turn.
     pub fn estimate_remaining_turns(
         context: &ContextUsage,
         avg_tokens_per_turn: u64,
@@ -263,10 +278,23 @@ impl ContextAnalyzer {
         if avg_tokens_per_turn == 0 {
             return None;
         }
-        let remaining = context.remaining_tokens().as_u64();
+        let remaining = context.tokens_until_auto_compact().as_u64();
         Some(remaining / avg_tokens_per_turn)
     }
 
+    /// Forecasts how long `context` lasts before auto-compaction, from the
+    /// growth recorded in `history`.
+    ///
+    /// Returns `None` until at least one turn has grown the context.
+    pub fn forecast(context: &ContextUsage, history: &ContextHistory) -> Option<ContextForecast> {
+        let turns = Self::estimate_remaining_turns(context, history.avg_tokens_per_turn()?)?;
+        let minutes = history.tokens_per_minute().map(|rate| {
+            let remaining = context.tokens_until_auto_compact().as_u64() as f64;
+            (remaining / rate).round() as u64
+        });
+        Some(ContextForecast { turns, minutes })
+    }
+
     /// Calculates cache efficiency (cache reads vs total input).
     pub fn cache_efficiency(context: &ContextUsage) -> f64 {
         let total_input = context.total_input_tokens.as_u64();
@@ -278,6 +306,145 @@ impl ContextAnalyzer {
     }
 }
 
+// ============================================================================
+// Growth Tracking
+// ============================================================================
+
+/// Context size at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextSample {
+    /// When the sample was taken
+    pub at: DateTime<Utc>,
+    /// Tokens in the context window at that time
+    pub tokens: TokenCount,
+}
+
+/// Recent context samples for one session, and how much each turn grew it.
+///
+/// The registry records a sample on every usage update and brackets turns
+/// with `start_turn` (prompt submitted) and `end_turn` (agent done). A
+/// shrinking context (compaction, `/clear`) starts a fresh time series.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextHistory {
+    samples: VecDeque<ContextSample>,
+    turn_start: Option<ContextSample>,
+    turn_growth: VecDeque<u64>,
+}
+
+impl ContextHistory {
+    /// Maximum number of samples kept.
+    const MAX_SAMPLES: usize = 64;
+
+    /// Number of recent turns averaged for the per-turn growth.
+    const MAX_TURNS: usize = 10;
+
+    /// Creates an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the context size reported at `at`.
+    pub fn record(&mut self, context: &ContextUsage, at: DateTime<Utc>) {
+        let sample = ContextSample {
+            at,
+            tokens: context.context_tokens(),
+        };
+
+        if self
+            .samples
+            .back()
+            .is_some_and(|last| sample.tokens < last.tokens)
+        {
+            self.samples.clear();
+            if self.turn_start.is_some() {
+                self.turn_start = Some(sample);
+            }
+        }
+
+        self.samples.push_back(sample);
+        while self.samples.len() > Self::MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Marks the start of a turn at the current context size.
+    pub fn start_turn(&mut self, context: &ContextUsage, at: DateTime<Utc>) {
+        self.turn_start = Some(ContextSample {
+            at,
+            tokens: context.context_tokens(),
+        });
+    }
+
+    /// Closes the current turn, if one was started, and records its growth.
+    ///
+    /// Turns that didn't grow the context are ignored.
+    pub fn end_turn(&mut self, context: &ContextUsage) {
+        let Some(start) = self.turn_start.take() else {
+            return;
+        };
+        let growth = context
+            .context_tokens()
+            .as_u64()
+            .saturating_sub(start.tokens.as_u64());
+        if growth == 0 {
+            return;
+        }
+
+        self.turn_growth.push_back(growth);
+        while self.turn_growth.len() > Self::MAX_TURNS {
+            self.turn_growth.pop_front();
+        }
+    }
+
+    /// Average context growth over recent turns.
+    pub fn avg_tokens_per_turn(&self) -> Option<u64> {
+        let turns = self.turn_growth.len() as u64;
+        if turns == 0 {
+            return None;
+        }
+        Some(self.turn_growth.iter().sum::<u64>() / turns)
+    }
+
+    /// Wall-clock growth rate across the current time series.
+    ///
+    /// Includes idle time between turns, so it answers "how soon at the
+    /// current pace" rather than "how much agent time".
+    pub fn tokens_per_minute(&self) -> Option<f64> {
+        let first = self.samples.front()?;
+        let last = self.samples.back()?;
+        let minutes = (last.at - first.at).num_milliseconds() as f64 / 60_000.0;
+        let growth = last.tokens.as_u64().saturating_sub(first.tokens.as_u64()) as f64;
+        (minutes > 0.0 && growth > 0.0).then(|| growth / minutes)
+    }
+}
+
+/// Estimated headroom before auto-compaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextForecast {
+    /// Turns left at the recent average growth per turn
+    pub turns: u64,
+    /// Minutes left at the recent wall-clock growth rate, if measurable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minutes: Option<u64>,
+}
+
+impl ContextForecast {
+    /// Formats the forecast for display (e.g., "≈4 turns / ≈12 min").
+    pub fn format(&self) -> String {
+        let turns = if self.turns == 1 { "turn" } else { "turns" };
+        match self.minutes {
+            Some(minutes) => format!("\u{2248}{} {turns} / \u{2248}{minutes} min", self.turns),
+            None => format!("\u{2248}{} {turns}", self.turns),
+        }
+    }
+}
+
+impl fmt::Display for ContextForecast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -385,4 +552,58 @@ mod tests {
         // 25000 + 500 + 100 = 25600
         assert_eq!(usage.context_tokens().as_u64(), 25_600);
     }
+
+    fn usage(tokens: u64) -> ContextUsage {
+        ContextUsage {
+            cache_read_tokens: TokenCount::new(tokens),
+            context_window_size: 200_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tokens_until_auto_compact() {
+        // 92% of 200K = 184K
+        assert_eq!(usage(100_000).tokens_until_auto_compact().as_u64(), 84_000);
+        assert_eq!(usage(190_000).tokens_until_auto_compact().as_u64(), 0);
+    }
+
+    #[test]
+    fn test_forecast_from_turn_growth() {
+        let t0 = Utc::now();
+        let mut history = ContextHistory::new();
+        assert_eq!(ContextAnalyzer::forecast(&usage(0), &history), None);
+
+        // Two turns, ten minutes apart, each adding 10K tokens.
+        history.record(&usage(50_000), t0);
+        history.start_turn(&usage(50_000), t0);
+        history.record(&usage(60_000), t0 + chrono::Duration::minutes(5));
+        history.end_turn(&usage(60_000));
+        history.start_turn(&usage(60_000), t0 + chrono::Duration::minutes(8));
+        history.record(&usage(70_000), t0 + chrono::Duration::minutes(10));
+        history.end_turn(&usage(70_000));
+
+        assert_eq!(history.avg_tokens_per_turn(), Some(10_000));
+        let forecast = ContextAnalyzer::forecast(&usage(70_000), &history).unwrap();
+        // 114K left: 11 whole turns, at 2K tokens/minute ≈ 57 minutes.
+        assert_eq!(forecast.turns, 11);
+        assert_eq!(forecast.minutes, Some(57));
+        assert_eq!(forecast.format(), "\u{2248}11 turns / \u{2248}57 min");
+    }
+
+    #[test]
+    fn test_history_restarts_after_compaction() {
+        let t0 = Utc::now();
+        let mut history = ContextHistory::new();
+        history.record(&usage(150_000), t0);
+        history.start_turn(&usage(150_000), t0);
+        // Compacted mid-turn: the turn is re-based on the smaller context.
+        history.record(&usage(20_000), t0 + chrono::Duration::minutes(1));
+        history.record(&usage(25_000), t0 + chrono::Duration::minutes(2));
+        history.end_turn(&usage(25_000));
+
+        assert_eq!(history.avg_tokens_per_turn(), Some(5_000));
+        // Time series only covers the post-compaction samples.
+        assert_eq!(history.tokens_per_minute(), Some(5_000.0));
+    }
 }
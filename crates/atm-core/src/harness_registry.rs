@@ -152,7 +152,7 @@ pub const BUILTIN_HARNESSES: &[HarnessDefinition] = &[
         prompt_mode: PromptMode::KeystrokeInjection,
         version_args: &["--version"],
         process_matchers: CODEX_MATCHERS,
-        discovery_enabled: false,
+        discovery_enabled: true,
         allow_bare_cmdline_match: true,
     },
     HarnessDefinition {
@@ -194,7 +194,7 @@ pub const BUILTIN_HARNESSES: &[HarnessDefinition] = &[
         prompt_mode: PromptMode::KeystrokeInjection,
         version_args: &["--version"],
         process_matchers: GEMINI_MATCHERS,
-        discovery_enabled: false,
+        discovery_enabled: true,
         allow_bare_cmdline_match: true,
     },
 ];
@@ -15,14 +15,16 @@ pub mod harness;
 pub mod harness_registry;
 pub mod lifecycle;
 pub mod model;
+pub mod model_catalog;
 pub mod project;
+pub mod prompt;
 pub mod session;
 pub mod tool;
 pub mod tree;
 
 // Re-exports for convenience
 pub use agent::AgentType;
-pub use context::{ContextUsage, TokenCount};
+pub use context::{ContextForecast, ContextHistory, ContextUsage, TokenCount};
 pub use cost::Money;
 pub use error::{DomainError, DomainResult};
 pub use harness::Harness;
@@ -32,12 +34,20 @@ pub use harness_registry::{
 };
 pub use lifecycle::{LifecycleEvent, NeedsInputReason, NotificationKind};
 pub use model::{derive_display_name, Model};
+pub use model_catalog::{
+    default_models_path, ModelCatalog, ModelCatalogError, ModelPricing, ModelSpec, TokenUsage,
+};
 pub use project::{resolve_project_root, resolve_worktree_info};
+pub use prompt::{
+    prompt_block, PromptKind, PromptOption, PromptSource, PromptState, PANE_PARSER_VERSION,
+};
 pub use session::{
-    ActivityDetail, LinesChanged, SessionDomain, SessionDuration, SessionId, SessionInfrastructure,
-    SessionStatus, SessionView, StatusLineData, ToolUsageRecord, ToolUseId, TranscriptPath,
+    ActivityDetail, LinesChanged, PendingToolCall, SessionDomain, SessionDuration, SessionId,
+    SessionInfrastructure, SessionStatus, SessionView, StatusLineData, ToolUsageRecord, ToolUseId,
+    TranscriptPath,
 };
 pub use tool::Tool;
 pub use tree::{
-    all_node_ids, build_tree, flatten_tree, TreeNode, TreeNodeId, TreeRow, TreeRowKind,
+    all_node_ids, build_tree, build_tree_with, find_node, flatten_tree, GroupBy, SortKey, TreeNode,
+    TreeNodeId, TreeOptions, TreeRow, TreeRowKind,
 };
@@ -28,7 +28,7 @@
 //! so the well-known set is defined once and the open tail of MCP /
 //! vendor-specific names lives in `Tool::Other(String)`.
 
-use crate::Tool;
+use crate::{PromptState, Tool};
 use serde::{Deserialize, Serialize};
 
 /// Sub-kind of a notification, for the cases the daemon special-cases.
@@ -120,7 +120,8 @@ pub enum NeedsInputReason {
     /// Claude `PreToolUse` for an interactive tool
     /// (`AskUserQuestion`, `EnterPlanMode`, `ExitPlanMode`).
     InteractiveTool { tool: Tool },
-    /// Pi extension-mediated `tool_call` permission gate.
+    /// Permission gate for a named tool call (pi extension-mediated
+    /// `tool_call`, Claude `PermissionRequest`).
     PermissionGate { tool: Tool },
     /// Generic notification-driven prompt
     /// (Claude `Notification(permission_prompt|elicitation_dialog)`,
@@ -170,8 +171,15 @@ pub enum LifecycleEvent {
     /// User submitted a prompt.
     PromptSubmit { prompt: Option<String> },
 
-    /// Session is waiting on user input.
-    NeedsInput { reason: NeedsInputReason },
+    /// Session is waiting on user input. `prompt` is the structured
+    /// prompt when the vendor payload describes it (Claude
+    /// `PermissionRequest` / `AskUserQuestion` input, pi `ui.select`);
+    /// otherwise the daemon falls back to parsing the pane.
+    NeedsInput {
+        reason: NeedsInputReason,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prompt: Option<PromptState>,
+    },
 
     /// A tool started executing.
     ///
@@ -271,21 +279,25 @@ mod tests {
                 reason: NeedsInputReason::InteractiveTool {
                     tool: Tool::AskUserQuestion,
                 },
+                prompt: None,
             },
             LifecycleEvent::NeedsInput {
                 reason: NeedsInputReason::PermissionGate { tool: Tool::Bash },
+                prompt: None,
             },
             LifecycleEvent::NeedsInput {
                 reason: NeedsInputReason::Notification {
                     kind: NotificationKind::PermissionPrompt,
                     label: None,
                 },
+                prompt: None,
             },
             LifecycleEvent::NeedsInput {
                 reason: NeedsInputReason::Notification {
                     kind: NotificationKind::PermissionPrompt,
                     label: Some("rm -rf /tmp".into()),
                 },
+                prompt: None,
             },
             LifecycleEvent::ToolCallStart {
                 name: Tool::Bash,
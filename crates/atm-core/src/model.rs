@@ -1,142 +1,109 @@
 //! Model identification and metadata.
 //!
-//! Supports known Anthropic models with pricing/context data,
-//! and gracefully handles unknown models (new Anthropic releases
-//! or non-Anthropic models) by preserving their raw ID for display.
+//! A [`Model`] is a handle to an entry in the [`ModelCatalog`], which
+//! supplies its display name, context window and prices. Ids the
+//! catalog doesn't know (new releases not yet listed, local models)
+//! resolve to [`Model::UNKNOWN`]; callers keep the raw id for display.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::model_catalog::{
+    ModelCatalog, ModelPricing, ModelSpec, TokenUsage, DEFAULT_CONTEXT_WINDOW,
+};
+use crate::Money;
 
 /// Model identifier.
 ///
-/// Parsed from status line JSON: `model.id` field.
-/// Uses prefix matching for forward compatibility with new date variants.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
-#[derive(Default)]
-pub enum Model {
-    /// Claude Opus 4.6 (claude-opus-4-6-*)
-    #[serde(rename = "claude-opus-4-6")]
-    Opus46,
-
-    /// Claude Opus 4.5 (claude-opus-4-5-*)
-    #[serde(rename = "claude-opus-4-5-20251101")]
-    Opus45,
-
-    /// Claude Sonnet 4.5 (claude-sonnet-4-5-*)
-    #[serde(rename = "claude-sonnet-4-5-20250929")]
-    Sonnet45,
-
-    /// Claude Sonnet 4 (claude-sonnet-4-*)
-    #[serde(rename = "claude-sonnet-4-20250514")]
-    Sonnet4,
-
-    /// Claude Haiku 4.5 (claude-haiku-4-5-*)
-    #[serde(rename = "claude-haiku-4-5-20251001")]
-    Haiku45,
-
-    /// Claude Haiku 3.5 (claude-3-5-haiku-*)
-    #[serde(rename = "claude-3-5-haiku-20241022")]
-    Haiku35,
-
-    /// Claude Sonnet 3.5 v2 (claude-3-5-sonnet-*)
-    #[serde(rename = "claude-3-5-sonnet-20241022")]
-    Sonnet35V2,
-
-    /// Unknown or non-Anthropic model
-    #[serde(other)]
-    #[default]
-    Unknown,
-}
+/// Parsed from status line JSON (`model.id`) or adapter events via
+/// [`Model::from_id`], which matches the id against the catalog's
+/// prefixes, so new date variants of a known family resolve without a
+/// catalog change.
+///
+/// Serializes as the matching catalog prefix, or `"unknown"`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Model(Option<&'static ModelSpec>);
 
 impl Model {
+    /// A model with no catalog entry.
+    pub const UNKNOWN: Self = Self(None);
+
     /// Returns a human-readable display name for known models.
     ///
-    /// For `Unknown` models, callers should use [`derive_display_name`]
+    /// For unknown models, callers should use [`derive_display_name`]
     /// with the raw model ID for a better fallback.
     pub fn display_name(&self) -> &'static str {
-        match self {
-            Self::Opus46 => "Opus 4.6",
-            Self::Opus45 => "Opus 4.5",
-            Self::Sonnet45 => "Sonnet 4.5",
-            Self::Sonnet4 => "Sonnet 4",
-            Self::Haiku45 => "Haiku 4.5",
-            Self::Haiku35 => "Haiku 3.5",
-            Self::Sonnet35V2 => "Sonnet 3.5 v2",
-            Self::Unknown => "Unknown",
+        match self.0 {
+            Some(spec) => spec.display_name.as_str(),
+            None => "Unknown",
         }
     }
 
     /// Returns the context window size for this model.
+    ///
+    /// Unknown models assume [`DEFAULT_CONTEXT_WINDOW`].
     pub fn context_window_size(&self) -> u32 {
-        match self {
-            Self::Opus46 => 200_000,
-            Self::Opus45 => 200_000,
-            Self::Sonnet45 => 200_000,
-            Self::Sonnet4 => 200_000,
-            Self::Haiku45 => 200_000,
-            Self::Haiku35 => 200_000,
-            Self::Sonnet35V2 => 200_000,
-            Self::Unknown => 200_000, // Default assumption
-        }
+        self.0
+            .map_or(DEFAULT_CONTEXT_WINDOW, |spec| spec.context_window)
     }
 
-    /// Returns approximate cost per million input tokens (USD).
-    pub fn input_cost_per_million(&self) -> f64 {
-        match self {
-            Self::Opus46 => 5.00,
-            Self::Opus45 => 15.00,
-            Self::Sonnet45 => 3.00,
-            Self::Sonnet4 => 3.00,
-            Self::Haiku45 => 1.00,
-            Self::Haiku35 => 0.80,
-            Self::Sonnet35V2 => 3.00,
-            Self::Unknown => 3.00, // Conservative default
-        }
+    /// Returns the model's token prices, if the catalog lists them.
+    pub fn pricing(&self) -> Option<ModelPricing> {
+        self.0.and_then(|spec| spec.pricing)
     }
 
-    /// Returns approximate cost per million output tokens (USD).
-    pub fn output_cost_per_million(&self) -> f64 {
-        match self {
-            Self::Opus46 => 25.00,
-            Self::Opus45 => 75.00,
-            Self::Sonnet45 => 15.00,
-            Self::Sonnet4 => 15.00,
-            Self::Haiku45 => 5.00,
-            Self::Haiku35 => 4.00,
-            Self::Sonnet35V2 => 15.00,
-            Self::Unknown => 15.00, // Conservative default
-        }
+    /// Estimates what `usage` costs on this model.
+    ///
+    /// `None` for unknown or unpriced models.
+    pub fn estimate_cost(&self, usage: &TokenUsage) -> Option<Money> {
+        self.pricing().map(|pricing| pricing.cost(usage))
     }
 
-    /// Parses a model from its string ID using prefix matching.
+    /// Parses a model from its string ID using prefix matching against
+    /// the installed [`ModelCatalog`].
     ///
-    /// More specific prefixes are checked first to avoid false matches
-    /// (e.g., "claude-sonnet-4-5" before "claude-sonnet-4-").
+    /// The longest matching prefix wins (e.g., "claude-sonnet-4-5"
+    /// over "claude-sonnet-4").
     pub fn from_id(id: &str) -> Self {
-        // More specific (longer) prefixes first to avoid false matches
-        if id.starts_with("claude-opus-4-6") {
-            Self::Opus46
-        } else if id.starts_with("claude-opus-4-5") {
-            Self::Opus45
-        } else if id.starts_with("claude-sonnet-4-5") {
-            Self::Sonnet45
-        } else if id.starts_with("claude-sonnet-4") {
-            Self::Sonnet4
-        } else if id.starts_with("claude-haiku-4-5") {
-            Self::Haiku45
-        } else if id.starts_with("claude-3-5-haiku") {
-            Self::Haiku35
-        } else if id.starts_with("claude-3-5-sonnet") {
-            Self::Sonnet35V2
-        } else {
-            Self::Unknown
-        }
+        Self(ModelCatalog::global().lookup(id))
+    }
+
+    /// Returns the catalog prefix this model matched, if any.
+    pub fn catalog_id(&self) -> Option<&'static str> {
+        self.0.map(|spec| spec.prefix.as_str())
     }
 
     /// Returns true if this is an unknown/unrecognized model.
     pub fn is_unknown(&self) -> bool {
-        matches!(self, Self::Unknown)
+        self.0.is_none()
+    }
+}
+
+impl PartialEq for Model {
+    fn eq(&self, other: &Self) -> bool {
+        self.catalog_id() == other.catalog_id()
+    }
+}
+
+impl Eq for Model {}
+
+impl Hash for Model {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.catalog_id().hash(state);
+    }
+}
+
+impl Serialize for Model {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.catalog_id().unwrap_or("unknown"))
+    }
+}
+
+impl<'de> Deserialize<'de> for Model {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Ok(Self::from_id(&id))
     }
 }
 
@@ -170,95 +137,138 @@ impl fmt::Display for Model {
 mod tests {
     use super::*;
 
+    fn catalog_id(id: &str) -> Option<&'static str> {
+        Model::from_id(id).catalog_id()
+    }
+
     // ── Known model parsing ──
 
     #[test]
     fn test_model_parsing_opus45() {
         let model: Model = serde_json::from_str("\"claude-opus-4-5-20251101\"").unwrap();
-        assert_eq!(model, Model::Opus45);
+        assert_eq!(model.catalog_id(), Some("claude-opus-4-5"));
         assert_eq!(model.display_name(), "Opus 4.5");
     }
 
     #[test]
     fn test_model_parsing_opus46() {
         // Opus 4.6 may come without date suffix
-        assert_eq!(Model::from_id("claude-opus-4-6"), Model::Opus46);
-        assert_eq!(Model::Opus46.display_name(), "Opus 4.6");
+        assert_eq!(catalog_id("claude-opus-4-6"), Some("claude-opus-4-6"));
+        assert_eq!(Model::from_id("claude-opus-4-6").display_name(), "Opus 4.6");
     }
 
     #[test]
     fn test_model_parsing_sonnet45() {
-        assert_eq!(
-            Model::from_id("claude-sonnet-4-5-20250929"),
-            Model::Sonnet45
-        );
-        assert_eq!(Model::Sonnet45.display_name(), "Sonnet 4.5");
+        let model = Model::from_id("claude-sonnet-4-5-20250929");
+        assert_eq!(model.catalog_id(), Some("claude-sonnet-4-5"));
+        assert_eq!(model.display_name(), "Sonnet 4.5");
     }
 
     #[test]
     fn test_model_parsing_haiku45() {
-        assert_eq!(Model::from_id("claude-haiku-4-5-20251001"), Model::Haiku45);
-        assert_eq!(Model::Haiku45.display_name(), "Haiku 4.5");
+        let model = Model::from_id("claude-haiku-4-5-20251001");
+        assert_eq!(model.catalog_id(), Some("claude-haiku-4-5"));
+        assert_eq!(model.display_name(), "Haiku 4.5");
     }
 
     #[test]
     fn test_model_unknown_serde() {
-        let model: Model = serde_json::from_str("\"gpt-4o\"").unwrap();
-        assert_eq!(model, Model::Unknown);
+        let model: Model = serde_json::from_str("\"llama-3-70b\"").unwrap();
+        assert_eq!(model, Model::UNKNOWN);
+        assert_eq!(serde_json::to_string(&model).unwrap(), "\"unknown\"");
     }
 
-    // ── Prefix matching ──
-
     #[test]
-    fn test_from_id_prefix_exact() {
-        assert_eq!(Model::from_id("claude-opus-4-5-20251101"), Model::Opus45);
-        assert_eq!(Model::from_id("claude-sonnet-4-20250514"), Model::Sonnet4);
+    fn test_model_serde_roundtrip() {
+        let model = Model::from_id("claude-sonnet-4-20250514");
+        let json = serde_json::to_string(&model).unwrap();
+        assert_eq!(json, "\"claude-sonnet-4\"");
+        assert_eq!(serde_json::from_str::<Model>(&json).unwrap(), model);
     }
 
+    // ── Prefix matching ──
+
     #[test]
     fn test_from_id_prefix_with_different_date() {
         // Future date variants should still match the right model family
-        assert_eq!(Model::from_id("claude-opus-4-6-20260301"), Model::Opus46);
         assert_eq!(
-            Model::from_id("claude-sonnet-4-5-20261201"),
-            Model::Sonnet45
+            catalog_id("claude-opus-4-6-20260301"),
+            Some("claude-opus-4-6")
+        );
+        assert_eq!(
+            catalog_id("claude-sonnet-4-5-20261201"),
+            Some("claude-sonnet-4-5")
+        );
+        assert_eq!(
+            catalog_id("claude-haiku-4-5-20260601"),
+            Some("claude-haiku-4-5")
+        );
+        assert_eq!(
+            catalog_id("claude-opus-4-5-20260101"),
+            Some("claude-opus-4-5")
         );
-        assert_eq!(Model::from_id("claude-haiku-4-5-20260601"), Model::Haiku45);
-        assert_eq!(Model::from_id("claude-opus-4-5-20260101"), Model::Opus45);
     }
 
     #[test]
-    fn test_from_id_prefix_no_date() {
-        // Model IDs without date suffix
-        assert_eq!(Model::from_id("claude-opus-4-6"), Model::Opus46);
-        assert_eq!(Model::from_id("claude-sonnet-4-5"), Model::Sonnet45);
+    fn test_from_id_sonnet4_not_confused_with_sonnet45() {
+        // "claude-sonnet-4-5" should match Sonnet 4.5, not Sonnet 4
+        assert_eq!(
+            catalog_id("claude-sonnet-4-5-20250929"),
+            Some("claude-sonnet-4-5")
+        );
+        assert_eq!(
+            catalog_id("claude-sonnet-4-20250514"),
+            Some("claude-sonnet-4")
+        );
     }
 
     #[test]
-    fn test_from_id_sonnet4_not_confused_with_sonnet45() {
-        // "claude-sonnet-4-5" should match Sonnet45, not Sonnet4
+    fn test_from_id_non_anthropic_models() {
+        assert_eq!(Model::from_id("gpt-5-codex").display_name(), "GPT-5 Codex");
         assert_eq!(
-            Model::from_id("claude-sonnet-4-5-20250929"),
-            Model::Sonnet45
+            Model::from_id("gemini-2.5-pro").context_window_size(),
+            1_048_576
         );
-        // "claude-sonnet-4-20250514" should match Sonnet4
-        assert_eq!(Model::from_id("claude-sonnet-4-20250514"), Model::Sonnet4);
     }
 
-    // ── Unknown / non-Anthropic models ──
+    // ── Unknown models ──
 
     #[test]
     fn test_from_id_unknown() {
-        assert_eq!(Model::from_id("gpt-4o"), Model::Unknown);
-        assert_eq!(Model::from_id("gemini-1.5-pro"), Model::Unknown);
-        assert_eq!(Model::from_id("llama-3-70b"), Model::Unknown);
-        assert_eq!(Model::from_id("unknown-model"), Model::Unknown);
+        assert_eq!(Model::from_id("gemini-1.5-pro"), Model::UNKNOWN);
+        assert_eq!(Model::from_id("llama-3-70b"), Model::UNKNOWN);
+        assert_eq!(Model::from_id("unknown-model"), Model::UNKNOWN);
+        assert_eq!(Model::from_id(""), Model::UNKNOWN);
     }
 
     #[test]
     fn test_is_unknown() {
-        assert!(Model::Unknown.is_unknown());
-        assert!(!Model::Opus46.is_unknown());
+        assert!(Model::UNKNOWN.is_unknown());
+        assert!(!Model::from_id("claude-opus-4-6").is_unknown());
+    }
+
+    #[test]
+    fn test_unknown_defaults() {
+        assert_eq!(Model::UNKNOWN.display_name(), "Unknown");
+        assert_eq!(Model::UNKNOWN.context_window_size(), DEFAULT_CONTEXT_WINDOW);
+        assert!(Model::UNKNOWN.pricing().is_none());
+    }
+
+    // ── Cost estimation ──
+
+    #[test]
+    fn test_estimate_cost() {
+        let usage = TokenUsage {
+            input: 1_000_000,
+            output: 100_000,
+            ..Default::default()
+        };
+        // 1M * $3 + 100k * $15, per million
+        assert_eq!(
+            Model::from_id("claude-sonnet-4-5").estimate_cost(&usage),
+            Some(Money::from_usd(4.5))
+        );
+        assert_eq!(Model::UNKNOWN.estimate_cost(&usage), None);
     }
 
     // ── Display name derivation for unknown models ──
@@ -0,0 +1,382 @@
+//! Model catalog: context windows and prices by model id prefix.
+//!
+//! The catalog starts from the bundled `models.toml` and is extended by
+//! user overrides in `~/.config/atm/models.toml`:
+//!
+//! ```toml
+//! [[models]]
+//! prefix = "claude-opus-4-7"
+//! display_name = "Opus 4.7"
+//! context_window = 200_000
+//! input = 5.00
+//! output = 25.00
+//! cache_read = 0.50
+//! cache_write = 6.25
+//! ```
+//!
+//! An override whose `prefix` matches a bundled entry replaces only the
+//! fields it sets. Lookups pick the entry with the longest matching
+//! prefix, so entry order does not matter.
+//!
+//! The daemon installs the loaded catalog once at startup with
+//! [`ModelCatalog::install`]; until then (and in tests) [`Model`] lookups
+//! use the bundled catalog.
+//!
+//! [`Model`]: crate::Model
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Override read and parse failures are returned as `ModelCatalogError`
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::model::derive_display_name;
+use crate::Money;
+
+/// The bundled catalog source.
+const BUNDLED_CATALOG: &str = include_str!("../models.toml");
+
+/// Context window assumed for models without a catalog entry.
+pub const DEFAULT_CONTEXT_WINDOW: u32 = 200_000;
+
+/// The catalog `Model` lookups resolve against.
+static GLOBAL_CATALOG: OnceLock<ModelCatalog> = OnceLock::new();
+
+/// Returns the default user override path (`~/.config/atm/models.toml`).
+pub fn default_models_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("atm").join("models.toml"))
+}
+
+/// Token prices for one model, in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    /// Uncached input tokens
+    pub input: f64,
+    /// Output tokens (including reasoning tokens)
+    pub output: f64,
+    /// Input tokens served from the prompt cache
+    pub cache_read: f64,
+    /// Input tokens written to the prompt cache
+    pub cache_write: f64,
+}
+
+/// Token counts to price, split the way providers bill them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TokenUsage {
+    /// Uncached input tokens
+    pub input: u64,
+    /// Output tokens (including reasoning tokens)
+    pub output: u64,
+    /// Input tokens served from the prompt cache
+    pub cache_read: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_write: u64,
+}
+
+impl ModelPricing {
+    /// Estimated cost of `usage` at these prices.
+    pub fn cost(&self, usage: &TokenUsage) -> Money {
+        let per_million = usage.input as f64 * self.input
+            + usage.output as f64 * self.output
+            + usage.cache_read as f64 * self.cache_read
+            + usage.cache_write as f64 * self.cache_write;
+        Money::from_usd(per_million / 1_000_000.0)
+    }
+}
+
+/// One resolved catalog entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSpec {
+    /// Model ids starting with this prefix match the entry
+    pub prefix: String,
+    /// Name shown in the TUI
+    pub display_name: String,
+    /// Context window size in tokens
+    pub context_window: u32,
+    /// Token prices; `None` if the entry has no input/output price
+    pub pricing: Option<ModelPricing>,
+}
+
+/// A `[[models]]` table as written in a catalog file. Every field but
+/// `prefix` is optional so overrides can patch single values.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogEntry {
+    prefix: String,
+    display_name: Option<String>,
+    context_window: Option<u32>,
+    input: Option<f64>,
+    output: Option<f64>,
+    cache_read: Option<f64>,
+    cache_write: Option<f64>,
+}
+
+impl CatalogEntry {
+    /// Overlays the fields `other` sets onto this entry.
+    fn merge(&mut self, other: CatalogEntry) {
+        self.display_name = other.display_name.or_else(|| self.display_name.take());
+        self.context_window = other.context_window.or(self.context_window);
+        self.input = other.input.or(self.input);
+        self.output = other.output.or(self.output);
+        self.cache_read = other.cache_read.or(self.cache_read);
+        self.cache_write = other.cache_write.or(self.cache_write);
+    }
+
+    fn resolve(self) -> ModelSpec {
+        let pricing = match (self.input, self.output) {
+            (Some(input), Some(output)) => Some(ModelPricing {
+                input,
+                output,
+                cache_read: self.cache_read.unwrap_or(input),
+                cache_write: self.cache_write.unwrap_or(input),
+            }),
+            _ => None,
+        };
+        ModelSpec {
+            display_name: self
+                .display_name
+                .unwrap_or_else(|| derive_display_name(&self.prefix)),
+            context_window: self.context_window.unwrap_or(DEFAULT_CONTEXT_WINDOW),
+            pricing,
+            prefix: self.prefix,
+        }
+    }
+}
+
+/// Shape of a catalog file.
+#[derive(Debug, Default, Deserialize)]
+struct CatalogFile {
+    #[serde(default)]
+    models: Vec<CatalogEntry>,
+}
+
+/// Errors loading model overrides.
+#[derive(Debug, Error)]
+pub enum ModelCatalogError {
+    /// The override file exists but could not be read.
+    #[error("failed to read model catalog {path}: {source}")]
+    Io {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The override file is not a valid catalog.
+    #[error("failed to parse model catalog {path}: {source}")]
+    Parse {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying parse error
+        source: toml::de::Error,
+    },
+}
+
+/// Known models, looked up by id prefix.
+#[derive(Debug, Clone, Default)]
+pub struct ModelCatalog {
+    models: Vec<ModelSpec>,
+}
+
+impl ModelCatalog {
+    /// The catalog bundled with ATM.
+    pub fn bundled() -> Self {
+        Self::from_entries(bundled_entries())
+    }
+
+    /// Loads the bundled catalog with the overrides in `path` applied.
+    ///
+    /// A missing override file yields the bundled catalog.
+    pub fn load(path: &Path) -> Result<Self, ModelCatalogError> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::bundled()),
+            Err(source) => {
+                return Err(ModelCatalogError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        let overrides: CatalogFile =
+            toml::from_str(&contents).map_err(|source| ModelCatalogError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        let mut entries = bundled_entries();
+        for entry in overrides.models {
+            match entries.iter_mut().find(|e| e.prefix == entry.prefix) {
+                Some(existing) => existing.merge(entry),
+                None => entries.push(entry),
+            }
+        }
+        Ok(Self::from_entries(entries))
+    }
+
+    fn from_entries(entries: Vec<CatalogEntry>) -> Self {
+        Self {
+            models: entries
+                .into_iter()
+                .filter(|e| !e.prefix.is_empty())
+                .map(CatalogEntry::resolve)
+                .collect(),
+        }
+    }
+
+    /// Makes this the catalog [`crate::Model::from_id`] resolves against.
+    ///
+    /// Only the first call (or first lookup) takes effect; a later call
+    /// returns the catalog back as the error.
+    pub fn install(self) -> Result<(), Self> {
+        GLOBAL_CATALOG.set(self)
+    }
+
+    /// The installed catalog, or the bundled one if none was installed.
+    pub fn global() -> &'static ModelCatalog {
+        GLOBAL_CATALOG.get_or_init(Self::bundled)
+    }
+
+    /// Finds the entry with the longest prefix matching `id`.
+    pub fn lookup(&self, id: &str) -> Option<&ModelSpec> {
+        self.models
+            .iter()
+            .filter(|spec| id.starts_with(spec.prefix.as_str()))
+            .max_by_key(|spec| spec.prefix.len())
+    }
+
+    /// All entries, in file order.
+    pub fn models(&self) -> &[ModelSpec] {
+        &self.models
+    }
+}
+
+/// Parses the bundled catalog. Its validity is covered by tests, so a
+/// failure here degrades to an empty catalog rather than a panic.
+fn bundled_entries() -> Vec<CatalogEntry> {
+    toml::from_str::<CatalogFile>(BUNDLED_CATALOG)
+        .map(|file| file.models)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_bundled_catalog_parses() {
+        let parsed: CatalogFile = toml::from_str(BUNDLED_CATALOG).unwrap();
+        assert!(!parsed.models.is_empty());
+        assert_eq!(ModelCatalog::bundled().models().len(), parsed.models.len());
+    }
+
+    #[test]
+    fn test_bundled_prefixes_are_unique() {
+        let catalog = ModelCatalog::bundled();
+        for (i, spec) in catalog.models().iter().enumerate() {
+            assert!(
+                !catalog.models()[i + 1..]
+                    .iter()
+                    .any(|other| other.prefix == spec.prefix),
+                "duplicate prefix {}",
+                spec.prefix
+            );
+        }
+    }
+
+    #[test]
+    fn test_lookup_prefers_longest_prefix() {
+        let catalog = ModelCatalog::bundled();
+        let spec = catalog.lookup("claude-sonnet-4-5-20250929").unwrap();
+        assert_eq!(spec.display_name, "Sonnet 4.5");
+        let spec = catalog.lookup("claude-sonnet-4-20250514").unwrap();
+        assert_eq!(spec.display_name, "Sonnet 4");
+        let spec = catalog.lookup("gpt-5-codex").unwrap();
+        assert_eq!(spec.display_name, "GPT-5 Codex");
+        let spec = catalog
+            .lookup("gemini-2.5-flash-lite-preview-09-2025")
+            .unwrap();
+        assert_eq!(spec.context_window, 1_048_576);
+        assert!(catalog.lookup("llama-3-70b").is_none());
+    }
+
+    #[test]
+    fn test_cache_prices_default_to_input() {
+        let catalog = ModelCatalog::bundled();
+        let pricing = catalog.lookup("gpt-5").unwrap().pricing.unwrap();
+        assert_eq!(pricing.cache_read, 0.125);
+        assert_eq!(pricing.cache_write, 1.25);
+    }
+
+    #[test]
+    fn test_pricing_cost() {
+        let pricing = ModelPricing {
+            input: 3.0,
+            output: 15.0,
+            cache_read: 0.3,
+            cache_write: 3.75,
+        };
+        let usage = TokenUsage {
+            input: 1_000,
+            output: 2_000,
+            cache_read: 100_000,
+            cache_write: 10_000,
+        };
+        // 0.003 + 0.03 + 0.03 + 0.0375
+        assert_eq!(pricing.cost(&usage), Money::from_usd(0.1005));
+    }
+
+    #[test]
+    fn test_load_merges_overrides() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            r#"
+[[models]]
+prefix = "claude-sonnet-4-5"
+context_window = 1_000_000
+
+[[models]]
+prefix = "qwen3-coder"
+display_name = "Qwen3 Coder"
+context_window = 262_144
+"#
+        )
+        .unwrap();
+
+        let catalog = ModelCatalog::load(file.path()).unwrap();
+        let sonnet = catalog.lookup("claude-sonnet-4-5").unwrap();
+        assert_eq!(sonnet.context_window, 1_000_000);
+        assert_eq!(sonnet.display_name, "Sonnet 4.5", "unset fields kept");
+        assert!(sonnet.pricing.is_some());
+
+        let qwen = catalog.lookup("qwen3-coder-480b").unwrap();
+        assert_eq!(qwen.display_name, "Qwen3 Coder");
+        assert!(qwen.pricing.is_none(), "no prices given");
+    }
+
+    #[test]
+    fn test_load_missing_file_is_bundled() {
+        let dir = tempfile::tempdir().unwrap();
+        let catalog = ModelCatalog::load(&dir.path().join("models.toml")).unwrap();
+        assert_eq!(catalog.models(), ModelCatalog::bundled().models());
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_fields() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "[[models]]\nprefix = \"x\"\nwindow = 5\n").unwrap();
+        assert!(matches!(
+            ModelCatalog::load(file.path()),
+            Err(ModelCatalogError::Parse { .. })
+        ));
+    }
+}
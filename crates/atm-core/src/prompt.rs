@@ -0,0 +1,627 @@
+//! Structured model of the prompt an agent is waiting on.
+//!
+//! A `PromptState` describes what a session in `AttentionNeeded` is
+//! asking: the question, its numbered options and which one the cursor
+//! is on. It is built from hook payloads where the harness reports the
+//! prompt (Claude `PermissionRequest` and `AskUserQuestion` input, pi's
+//! `ui.select` title and options), and otherwise from the agent's tmux
+//! pane by [`PromptState::from_pane`].
+//!
+//! The pane parser is a heuristic over Claude Code's rendering and will
+//! drift as that rendering changes. Prompts it produces record
+//! [`PANE_PARSER_VERSION`] so consumers can tell which rules read them;
+//! bump it whenever the rules change.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Tool;
+
+/// Version of the pane parsing rules in this module.
+pub const PANE_PARSER_VERSION: u32 = 1;
+
+/// Longest tool-input summary kept in a permission question.
+const MAX_SUMMARY_CHARS: usize = 120;
+
+/// What kind of answer a prompt wants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptKind {
+    /// Allow or deny a tool call
+    Permission,
+    /// A question with options to pick from (`AskUserQuestion`)
+    Question,
+    /// Approve a plan before the agent starts editing
+    PlanApproval,
+}
+
+impl PromptKind {
+    /// Display label for this kind.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Permission => "permission",
+            Self::Question => "question",
+            Self::PlanApproval => "plan approval",
+        }
+    }
+}
+
+/// Where a prompt's structure came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "from", rename_all = "snake_case")]
+pub enum PromptSource {
+    /// Reported by the harness in a hook payload
+    Hook,
+    /// Parsed from the captured tmux pane
+    Pane {
+        /// `PANE_PARSER_VERSION` of the rules that parsed it
+        parser_version: u32,
+    },
+}
+
+/// One numbered option of a prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptOption {
+    /// Option text
+    pub label: String,
+    /// Explanation shown under the option, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl PromptOption {
+    /// Creates an option with no description.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            description: None,
+        }
+    }
+}
+
+/// The prompt a session is waiting on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptState {
+    /// What kind of answer is wanted
+    pub kind: PromptKind,
+    /// Question text, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question: Option<String>,
+    /// Options in display order; option N is `options[N - 1]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<PromptOption>,
+    /// 1-based number of the option under the cursor, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<usize>,
+    /// Where this structure came from
+    pub source: PromptSource,
+}
+
+impl PromptState {
+    /// Creates a hook-reported prompt. The cursor starts on the first
+    /// option, if there are any.
+    pub fn from_hook(
+        kind: PromptKind,
+        question: Option<String>,
+        options: Vec<PromptOption>,
+    ) -> Self {
+        let selected = (!options.is_empty()).then_some(1);
+        Self {
+            kind,
+            question,
+            options,
+            selected,
+            source: PromptSource::Hook,
+        }
+    }
+
+    /// Builds the permission prompt for a gated tool call.
+    ///
+    /// The question names the tool and summarizes its input (the Bash
+    /// command, the edited file, the fetched URL, ...). Option wording
+    /// varies by tool, so none are listed.
+    pub fn permission(tool: &Tool, input: Option<&serde_json::Value>) -> Self {
+        let question = match input.and_then(summarize_tool_input) {
+            Some(summary) => format!("Allow {}: {summary}?", tool.as_str()),
+            None => format!("Allow {}?", tool.as_str()),
+        };
+        Self::from_hook(PromptKind::Permission, Some(question), Vec::new())
+    }
+
+    /// Builds the prompt an interactive tool shows from its input.
+    ///
+    /// `AskUserQuestion` yields its first question and options; the plan
+    /// tools yield a plan approval. Returns `None` for other tools.
+    pub fn for_interactive_tool(tool: &Tool, input: Option<&serde_json::Value>) -> Option<Self> {
+        match tool {
+            Tool::AskUserQuestion => {
+                let first = input
+                    .and_then(|i| i.get("questions"))
+                    .and_then(serde_json::Value::as_array)
+                    .and_then(|questions| questions.first());
+                let question = first
+                    .and_then(|q| q.get("question"))
+                    .and_then(serde_json::Value::as_str)
+                    .map(str::to_string);
+                let options = first
+                    .and_then(|q| q.get("options"))
+                    .and_then(serde_json::Value::as_array)
+                    .map(|options| options.iter().filter_map(option_from_json).collect())
+                    .unwrap_or_default();
+                Some(Self::from_hook(PromptKind::Question, question, options))
+            }
+            Tool::EnterPlanMode | Tool::ExitPlanMode => {
+                Some(Self::from_hook(PromptKind::PlanApproval, None, Vec::new()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses the prompt shown at the bottom of a captured pane.
+    ///
+    /// Returns `None` when no numbered options or y/n question can be
+    /// found.
+    pub fn from_pane(lines: &[String]) -> Option<Self> {
+        let block = lines.get(prompt_block(lines)?)?;
+
+        let mut options: Vec<PromptOption> = Vec::new();
+        let mut selected = None;
+        let mut question = None;
+        let mut yes_no = false;
+        for line in block {
+            let trimmed = line.trim();
+            if let Some((number, label, cursor)) = parse_option_line(trimmed) {
+                // Numbering must run 1, 2, 3...; anything else is output
+                // that happens to start with a digit.
+                if number != options.len() + 1 {
+                    continue;
+                }
+                if cursor {
+                    selected = Some(number);
+                }
+                options.push(PromptOption::new(label));
+            } else if let Some(last) = options.last_mut().filter(|_| is_description(line)) {
+                let text = match last.description.take() {
+                    Some(prev) => format!("{prev} {trimmed}"),
+                    None => trimmed.to_string(),
+                };
+                last.description = Some(text);
+            } else if options.is_empty() && is_question_text(trimmed) {
+                yes_no = is_yes_no(trimmed);
+                question = Some(trimmed.to_string());
+            }
+        }
+
+        if options.is_empty() && !yes_no {
+            return None;
+        }
+        let kind = classify(question.as_deref(), &options);
+        Some(Self {
+            kind,
+            question,
+            options,
+            selected,
+            source: PromptSource::Pane {
+                parser_version: PANE_PARSER_VERSION,
+            },
+        })
+    }
+
+    /// Combines the prompt the harness reported with what the pane
+    /// shows now.
+    ///
+    /// The pane is the authority on options and the cursor (it includes
+    /// entries the harness adds itself, such as Claude's "Type
+    /// something."); the report is the authority on kind and question.
+    pub fn current(known: Option<Self>, pane: &[String]) -> Option<Self> {
+        let parsed = Self::from_pane(pane);
+        let Some(mut known) = known else {
+            return parsed;
+        };
+        if let Some(parsed) = parsed {
+            if !parsed.options.is_empty() {
+                known.options = parsed.options;
+                known.selected = parsed.selected;
+            }
+            if known.question.is_none() {
+                known.question = parsed.question;
+            }
+        }
+        Some(known)
+    }
+
+    /// Renders the prompt as plain lines: the question, then the options
+    /// with the cursor marked `❯`.
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(question) = &self.question {
+            lines.push(question.clone());
+        }
+        for (index, option) in self.options.iter().enumerate() {
+            let number = index + 1;
+            let cursor = if self.selected == Some(number) {
+                "❯"
+            } else {
+                " "
+            };
+            lines.push(format!("{cursor} {number}. {}", option.label));
+            if let Some(description) = &option.description {
+                lines.push(format!("     {description}"));
+            }
+        }
+        lines
+    }
+}
+
+/// Finds the rows of `lines` that make up the prompt at the bottom of a
+/// captured pane.
+///
+/// Scans back from the footer (`Enter to select`, `Esc to cancel`,
+/// `(Y)es`...) over numbered options, indented descriptions, separators,
+/// blank lines, question lines and title blocks (☐/□/■), stopping at the
+/// first line that is clearly agent output. Returns `None` when neither a
+/// footer nor any prompt content is found.
+pub fn prompt_block(lines: &[String]) -> Option<Range<usize>> {
+    let footer_idx = lines.iter().rposition(|l| is_footer(l.trim()));
+    let end = footer_idx.map_or(lines.len(), |i| i + 1);
+    let last = end.checked_sub(1)?;
+
+    let mut start = last;
+    for (i, line) in lines.iter().enumerate().take(last).rev() {
+        let trimmed = line.trim();
+
+        // Title block is the top of the prompt
+        if trimmed.starts_with(['☐', '□', '■']) {
+            start = i;
+            break;
+        }
+
+        if parse_option_line(trimmed).is_some()
+            || is_description(line)
+            || is_separator_line(trimmed)
+            || trimmed.is_empty()
+            || trimmed.contains('?')
+        {
+            start = i;
+            continue;
+        }
+
+        // Past the prompt once some of it has been seen
+        if start < last {
+            break;
+        }
+    }
+
+    if start == last && footer_idx.is_none() {
+        return None;
+    }
+
+    // Trim leading blank/separator lines
+    while lines
+        .get(start)
+        .map(|l| l.trim())
+        .is_some_and(|t| start < end && (t.is_empty() || is_separator_line(t)))
+    {
+        start += 1;
+    }
+
+    Some(start..end)
+}
+
+/// Returns true if a line is a box-drawing separator (────, ════, etc.)
+pub fn is_separator_line(trimmed: &str) -> bool {
+    const RULES: [char; 5] = ['─', '━', '═', '┄', '┈'];
+    trimmed.chars().count() >= 3
+        && trimmed.starts_with(RULES)
+        && trimmed.chars().all(|c| RULES.contains(&c) || c == ' ')
+}
+
+fn is_footer(trimmed: &str) -> bool {
+    trimmed.contains("Enter to select")
+        || trimmed.contains("Esc to cancel")
+        || trimmed.contains("to navigate")
+        || is_yes_no(trimmed)
+}
+
+fn is_yes_no(trimmed: &str) -> bool {
+    trimmed.contains("(Y)es") || trimmed.contains("(y/n)")
+}
+
+/// Parses `"N. label"`, optionally preceded by the `❯` cursor. Returns
+/// the option number, its label and whether the cursor is on it.
+fn parse_option_line(trimmed: &str) -> Option<(usize, &str, bool)> {
+    let (rest, cursor) = match trimmed.strip_prefix('❯') {
+        Some(rest) => (rest.trim_start(), true),
+        None => (trimmed, false),
+    };
+    let (number, label) = rest.split_once(". ")?;
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let label = label.trim();
+    if label.is_empty() {
+        return None;
+    }
+    Some((number.parse().ok()?, label, cursor))
+}
+
+/// Option descriptions are indented further than the options themselves.
+fn is_description(line: &str) -> bool {
+    line.starts_with("    ") && !line.trim().is_empty() && !is_separator_line(line.trim())
+}
+
+/// Lines above the options that can carry the question.
+fn is_question_text(trimmed: &str) -> bool {
+    !trimmed.is_empty() && !is_separator_line(trimmed) && !is_footer_hint(trimmed)
+}
+
+/// Footer hints that aren't questions (`(Y)es/(N)o` lines are).
+fn is_footer_hint(trimmed: &str) -> bool {
+    is_footer(trimmed) && !is_yes_no(trimmed)
+}
+
+/// Tells permission prompts and plan approvals apart from questions by
+/// the wording Claude Code uses for them.
+fn classify(question: Option<&str>, options: &[PromptOption]) -> PromptKind {
+    let any_option = |needle: &str| {
+        options
+            .iter()
+            .any(|o| o.label.to_lowercase().contains(needle))
+    };
+    let question = question.unwrap_or_default();
+    if any_option("keep planning") {
+        PromptKind::PlanApproval
+    } else if is_yes_no(question)
+        || question.starts_with("Do you want to")
+        || question.starts_with("Allow ")
+        || any_option("don't ask again")
+    {
+        PromptKind::Permission
+    } else {
+        PromptKind::Question
+    }
+}
+
+fn option_from_json(value: &serde_json::Value) -> Option<PromptOption> {
+    match value {
+        serde_json::Value::String(label) => Some(PromptOption::new(label.clone())),
+        other => Some(PromptOption {
+            label: other.get("label")?.as_str()?.to_string(),
+            description: other
+                .get("description")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string),
+        }),
+    }
+}
+
+/// One-line summary of what a tool call will touch.
+fn summarize_tool_input(input: &serde_json::Value) -> Option<String> {
+    let text = ["command", "file_path", "path", "url", "pattern", "query"]
+        .iter()
+        .find_map(|key| input.get(key).and_then(serde_json::Value::as_str))?
+        .trim();
+    let line = text.lines().next().unwrap_or_default();
+    let mut summary: String = line.chars().take(MAX_SUMMARY_CHARS).collect();
+    if summary.len() < text.len() {
+        summary.push('…');
+    }
+    Some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_question_with_descriptions() {
+        let pane = lines(&[
+            "Some previous output",
+            "",
+            "☐ Topping",
+            "",
+            "Do you like pineapple on pizza?",
+            "",
+            "❯ 1. Yes",
+            "     Pineapple belongs on pizza",
+            "  2. No",
+            "     Pineapple does not belong on pizza",
+            "  3. Type something.",
+            "",
+            "  4. Chat about this",
+            "",
+            "Enter to select · ↑/↓ to navigate · Esc to cancel",
+        ]);
+
+        let prompt = PromptState::from_pane(&pane).unwrap();
+        assert_eq!(prompt.kind, PromptKind::Question);
+        assert_eq!(
+            prompt.question.as_deref(),
+            Some("Do you like pineapple on pizza?")
+        );
+        assert_eq!(prompt.options.len(), 4);
+        assert_eq!(prompt.options[0].label, "Yes");
+        assert_eq!(
+            prompt.options[1].description.as_deref(),
+            Some("Pineapple does not belong on pizza")
+        );
+        assert_eq!(prompt.options[3].label, "Chat about this");
+        assert_eq!(prompt.selected, Some(1));
+        assert_eq!(
+            prompt.source,
+            PromptSource::Pane {
+                parser_version: PANE_PARSER_VERSION
+            }
+        );
+
+        let block = prompt_block(&pane).unwrap();
+        assert_eq!(pane[block.start], "☐ Topping");
+        assert_eq!(block.end, pane.len());
+    }
+
+    #[test]
+    fn test_parse_permission_prompt() {
+        let pane = lines(&[
+            "● Bash(cargo test)",
+            "────────────────────────────────────────",
+            " Bash command",
+            "",
+            "   cargo test --workspace",
+            "   Run the test suite",
+            "",
+            " Do you want to proceed?",
+            "   1. Yes",
+            " ❯ 2. Yes, and don't ask again for cargo test commands in /src/app",
+            "   3. No, and tell Claude what to do differently (esc)",
+            "",
+            " Esc to cancel",
+        ]);
+
+        let prompt = PromptState::from_pane(&pane).unwrap();
+        assert_eq!(prompt.kind, PromptKind::Permission);
+        assert_eq!(prompt.question.as_deref(), Some("Do you want to proceed?"));
+        assert_eq!(prompt.options.len(), 3);
+        assert_eq!(prompt.selected, Some(2));
+    }
+
+    #[test]
+    fn test_parse_plan_approval() {
+        let pane = lines(&[
+            "Would you like to proceed?",
+            "",
+            "❯ 1. Yes, and auto-accept edits",
+            "  2. Yes, and manually approve edits",
+            "  3. No, keep planning",
+        ]);
+
+        let prompt = PromptState::from_pane(&pane).unwrap();
+        assert_eq!(prompt.kind, PromptKind::PlanApproval);
+        assert_eq!(prompt.options.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_yes_no_prompt() {
+        let pane = lines(&[
+            "lots of code output here",
+            "more code",
+            "",
+            "Allow Bash: git status? (Y)es/(N)o",
+        ]);
+
+        let prompt = PromptState::from_pane(&pane).unwrap();
+        assert_eq!(prompt.kind, PromptKind::Permission);
+        assert_eq!(
+            prompt.question.as_deref(),
+            Some("Allow Bash: git status? (Y)es/(N)o")
+        );
+        assert!(prompt.options.is_empty());
+        assert_eq!(prompt.selected, None);
+    }
+
+    #[test]
+    fn test_parse_ignores_misnumbered_lines() {
+        let pane = lines(&[
+            "Which one?",
+            "❯ 1. First",
+            "  3. Not an option",
+            "  2. Second",
+            "Enter to select",
+        ]);
+
+        let prompt = PromptState::from_pane(&pane).unwrap();
+        let labels: Vec<&str> = prompt.options.iter().map(|o| o.label.as_str()).collect();
+        assert_eq!(labels, vec!["First", "Second"]);
+    }
+
+    #[test]
+    fn test_no_prompt_in_plain_output() {
+        let pane: Vec<String> = (0..20).map(|i| format!("line {i}")).collect();
+        assert!(prompt_block(&pane).is_none());
+        assert!(PromptState::from_pane(&pane).is_none());
+        assert!(PromptState::from_pane(&[]).is_none());
+        assert!(PromptState::from_pane(&lines(&["❯ "])).is_none());
+    }
+
+    #[test]
+    fn test_ask_user_question_from_hook_input() {
+        let input = serde_json::json!({
+            "questions": [{
+                "question": "Which database?",
+                "header": "DB",
+                "options": [
+                    {"label": "Postgres", "description": "Relational"},
+                    {"label": "SQLite"}
+                ],
+                "multiSelect": false
+            }]
+        });
+
+        let prompt =
+            PromptState::for_interactive_tool(&Tool::AskUserQuestion, Some(&input)).unwrap();
+        assert_eq!(prompt.kind, PromptKind::Question);
+        assert_eq!(prompt.question.as_deref(), Some("Which database?"));
+        assert_eq!(prompt.options.len(), 2);
+        assert_eq!(prompt.options[0].description.as_deref(), Some("Relational"));
+        assert_eq!(prompt.selected, Some(1));
+        assert_eq!(prompt.source, PromptSource::Hook);
+
+        let plan = PromptState::for_interactive_tool(&Tool::ExitPlanMode, None).unwrap();
+        assert_eq!(plan.kind, PromptKind::PlanApproval);
+        assert!(PromptState::for_interactive_tool(&Tool::Bash, None).is_none());
+    }
+
+    #[test]
+    fn test_permission_question_summarizes_input() {
+        let input = serde_json::json!({"command": "cargo test\ncargo build"});
+        let prompt = PromptState::permission(&Tool::Bash, Some(&input));
+        assert_eq!(prompt.question.as_deref(), Some("Allow Bash: cargo test…?"));
+        assert_eq!(
+            PromptState::permission(&Tool::Read, None)
+                .question
+                .as_deref(),
+            Some("Allow Read?")
+        );
+    }
+
+    #[test]
+    fn test_current_prefers_pane_options() {
+        let input = serde_json::json!({"command": "cargo test"});
+        let known = PromptState::permission(&Tool::Bash, Some(&input));
+        let pane = lines(&[
+            "Do you want to proceed?",
+            "  1. Yes",
+            "❯ 2. No",
+            "Esc to cancel",
+        ]);
+
+        let merged = PromptState::current(Some(known.clone()), &pane).unwrap();
+        assert_eq!(merged.question, known.question);
+        assert_eq!(merged.source, PromptSource::Hook);
+        assert_eq!(merged.options.len(), 2);
+        assert_eq!(merged.selected, Some(2));
+
+        assert_eq!(PromptState::current(Some(known.clone()), &[]), Some(known));
+        assert_eq!(
+            PromptState::current(None, &pane).map(|p| p.source),
+            Some(PromptSource::Pane {
+                parser_version: PANE_PARSER_VERSION
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_lines_marks_cursor() {
+        let mut prompt = PromptState::from_hook(
+            PromptKind::Question,
+            Some("Pick one".into()),
+            vec![PromptOption::new("A"), PromptOption::new("B")],
+        );
+        prompt.selected = Some(2);
+        assert_eq!(prompt.to_lines(), vec!["Pick one", "  1. A", "❯ 2. B"]);
+    }
+}
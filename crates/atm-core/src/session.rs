@@ -1,7 +1,8 @@
 //! Session domain entities and value objects.
 
+use crate::context::{ContextAnalyzer, ContextForecast, ContextHistory};
 use crate::lifecycle::{LifecycleEvent, NeedsInputReason, NotificationKind};
-use crate::{AgentType, ContextUsage, Model, Money, TokenCount};
+use crate::{AgentType, ContextUsage, Model, Money, PromptSource, PromptState, TokenCount, Tool};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -532,7 +533,7 @@ pub struct SessionDomain {
     #[serde(default)]
     pub harness: crate::Harness,
 
-    /// Claude model being used
+    /// Model being used
     pub model: Model,
 
     /// Display name override for unknown/non-Anthropic models.
@@ -548,6 +549,10 @@ pub struct SessionDomain {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub current_activity: Option<ActivityDetail>,
 
+    /// The prompt being waited on, while `AttentionNeeded` and known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<PromptState>,
+
     /// Context window usage
     pub context: ContextUsage,
 
@@ -603,6 +608,26 @@ pub struct SessionDomain {
     /// First user prompt (captured from the first UserPromptSubmit hook event).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub first_prompt: Option<String>,
+
+    /// Team the session was spawned into (`ATM_TEAM` at launch, set by
+    /// `atm layout` and `atm workspace create`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+
+    /// Set by the registry once a configured cost budget is exceeded.
+    /// Keeps the session flagged for attention for the rest of its life.
+    #[serde(default)]
+    pub over_budget: bool,
+
+    /// Recent context growth, for exhaustion forecasts. Rebuilt from live
+    /// updates rather than persisted.
+    #[serde(skip)]
+    pub context_history: ContextHistory,
+
+    /// Change counter, bumped by the registry each time it publishes an
+    /// update for this session. Clients use it to detect missed deltas.
+    #[serde(default)]
+    pub revision: u64,
 }
 
 impl SessionDomain {
@@ -617,6 +642,7 @@ impl SessionDomain {
             model_display_override: None,
             status: SessionStatus::Idle,
             current_activity: None,
+            prompt: None,
             context: ContextUsage::new(model.context_window_size()),
             cost: Money::zero(),
             duration: SessionDuration::default(),
@@ -632,9 +658,19 @@ impl SessionDomain {
             parent_session_id: None,
             child_session_ids: Vec::new(),
             first_prompt: None,
+            team: None,
+            over_budget: false,
+            context_history: ContextHistory::new(),
+            revision: 0,
         }
     }
 
+    /// Advances the revision counter and returns the new revision.
+    pub fn bump_revision(&mut self) -> u64 {
+        self.revision = self.revision.saturating_add(1);
+        self.revision
+    }
+
     /// Creates a SessionDomain from Claude Code status line data.
     pub fn from_status_line(data: &StatusLineData) -> Self {
         use crate::model::derive_display_name;
@@ -674,6 +710,9 @@ impl SessionDomain {
         session.working_directory = data.cwd.clone();
         session.claude_code_version = data.version.clone();
         session.last_activity = Utc::now();
+        session
+            .context_history
+            .record(&session.context, session.last_activity);
 
         session
     }
@@ -695,6 +734,8 @@ impl SessionDomain {
         self.context.cache_creation_tokens = TokenCount::new(data.cache_creation_tokens);
         self.context.cache_read_tokens = TokenCount::new(data.cache_read_tokens);
         self.last_activity = Utc::now();
+        self.context_history
+            .record(&self.context, self.last_activity);
 
         // Status line update means Claude is working
         // Don't override AttentionNeeded (permission wait)
@@ -739,13 +780,21 @@ impl SessionDomain {
             LifecycleEvent::WorkingEnd | LifecycleEvent::Idle => {
                 self.status = SessionStatus::Idle;
                 self.current_activity = None;
+                self.context_history.end_turn(&self.context);
             }
             LifecycleEvent::PromptSubmit { .. } => {
                 self.status = SessionStatus::Working;
                 self.current_activity = None;
+                self.context_history
+                    .start_turn(&self.context, self.last_activity);
                 // first_prompt is set separately via set_first_prompt()
             }
-            LifecycleEvent::NeedsInput { reason } => {
+            LifecycleEvent::NeedsInput { reason, prompt } => {
+                // Claude follows a described prompt with a bare
+                // notification for the same dialog; keep the description.
+                if prompt.is_some() || self.status != SessionStatus::AttentionNeeded {
+                    self.prompt = prompt.clone();
+                }
                 self.status = SessionStatus::AttentionNeeded;
                 self.current_activity = Some(activity_for_needs_input(reason));
             }
@@ -786,6 +835,8 @@ impl SessionDomain {
                     let count = TokenCount::new(*t);
                     self.context.current_input_tokens = count;
                     self.context.total_input_tokens = count;
+                    self.context_history
+                        .record(&self.context, self.last_activity);
                 }
                 // Status unchanged: cost/token updates don't
                 // imply a state transition.
@@ -795,16 +846,18 @@ impl SessionDomain {
                 // provider/model in pi's UI. Update Session so the TUI
                 // stops showing `[pi] Unknown` once the user has chosen.
                 //
-                // Strategy: try to map the raw id onto our Claude-shaped
-                // `Model` enum first (in case it's a Claude model pi is
-                // talking to); fall back to `Model::Unknown` and stash
+                // Strategy: look the raw id up in the model catalog; if
+                // it isn't listed, fall back to `Model::UNKNOWN` and stash
                 // the raw id in `model_display_override` for rendering.
+                // Known models also bring their context window, which
+                // adapters (unlike Claude's status line) don't report.
                 if let Some(id) = model {
                     let parsed = Model::from_id(id);
                     self.model = parsed;
                     self.model_display_override = if parsed.is_unknown() {
                         Some(crate::model::derive_display_name(id))
                     } else {
+                        self.context.context_window_size = parsed.context_window_size();
                         None
                     };
                 }
@@ -825,6 +878,27 @@ impl SessionDomain {
                 self.status = SessionStatus::Working;
             }
         }
+
+        if self.status != SessionStatus::AttentionNeeded {
+            self.prompt = None;
+        }
+    }
+
+    /// Records a prompt parsed from the pane, unless the session has
+    /// stopped waiting or the harness already described the prompt.
+    ///
+    /// Returns true if the prompt was stored.
+    pub fn set_pane_prompt(&mut self, prompt: PromptState) -> bool {
+        if self.status != SessionStatus::AttentionNeeded
+            || self
+                .prompt
+                .as_ref()
+                .is_some_and(|p| p.source == PromptSource::Hook)
+        {
+            return false;
+        }
+        self.prompt = Some(prompt);
+        true
     }
 
     /// Stores the first user prompt if not already set.
@@ -843,6 +917,18 @@ impl SessionDomain {
         }
     }
 
+    /// Records the team the session was spawned into.
+    ///
+    /// Returns true if the team changed.
+    pub fn set_team(&mut self, team: &str) -> bool {
+        let team = team.trim();
+        if team.is_empty() || self.team.as_deref() == Some(team) {
+            return false;
+        }
+        self.team = Some(team.to_string());
+        true
+    }
+
     /// Returns the session age (time since started).
     pub fn age(&self) -> chrono::Duration {
         Utc::now().signed_duration_since(self.started_at)
@@ -874,7 +960,7 @@ impl Default for SessionDomain {
 // ============================================================================
 
 /// Record of a tool invocation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolUsageRecord {
     /// Name of the tool (e.g., "Bash", "Read", "Write")
     pub tool_name: String,
@@ -884,11 +970,26 @@ pub struct ToolUsageRecord {
     pub timestamp: DateTime<Utc>,
 }
 
+/// A tool call that has started but not finished.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingToolCall {
+    /// Tool being run
+    pub tool: Tool,
+    /// Harness id of the call, when it reports one
+    pub tool_use_id: Option<String>,
+    /// Tool arguments, when the harness reported them
+    pub input: Option<serde_json::Value>,
+}
+
 /// Infrastructure-level data for a session.
 ///
 /// Contains OS/system concerns that don't belong in the domain model.
 /// Owned by RegistryActor alongside SessionDomain.
-#[derive(Debug, Clone)]
+///
+/// Serializable so the daemon can snapshot it to disk and rehydrate it
+/// after a restart; `process_start_time` is what lets a restored entry be
+/// checked against PID reuse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionInfrastructure {
     /// Process ID of the Claude Code process (if known)
     pub pid: Option<u32>,
@@ -914,6 +1015,22 @@ pub struct SessionInfrastructure {
 
     /// Last error encountered (for debugging)
     pub last_error: Option<String>,
+
+    /// Tool calls in flight, oldest first.
+    ///
+    /// Added on `ToolCallStart` and removed on the matching `ToolCallEnd`
+    /// (by `tool_use_id` when the harness reports one), so a permission
+    /// prompt that arrives in between can be matched against what is being
+    /// asked.
+    #[serde(skip)]
+    pub pending_tool_calls: Vec<PendingToolCall>,
+
+    /// Id of the `PermissionRequested` the session is waiting on, if any.
+    ///
+    /// Cleared when the session stops waiting, so a delayed answer can tell
+    /// whether the prompt it evaluated is still the one on screen.
+    #[serde(skip)]
+    pub open_permission: Option<u64>,
 }
 
 impl SessionInfrastructure {
@@ -931,6 +1048,8 @@ impl SessionInfrastructure {
             update_count: 0,
             hook_event_count: 0,
             last_error: None,
+            pending_tool_calls: Vec::new(),
+            open_permission: None,
         }
     }
 
@@ -1136,6 +1255,10 @@ pub struct SessionView {
     /// Activity detail (tool name or context)
     pub activity_detail: Option<String>,
 
+    /// The prompt being waited on, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<PromptState>,
+
     /// Whether this status should blink
     pub should_blink: bool,
 
@@ -1154,6 +1277,11 @@ pub struct SessionView {
     /// Whether context is in critical state
     pub context_critical: bool,
 
+    /// Estimated turns/minutes left before auto-compaction, once the
+    /// session has grown its context over at least one turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_forecast: Option<ContextForecast>,
+
     /// Cost formatted string
     pub cost_display: String,
 
@@ -1172,9 +1300,14 @@ pub struct SessionView {
     /// Working directory (shortened for display)
     pub working_directory: Option<String>,
 
-    /// Whether session needs attention (permission wait, high context)
+    /// Whether session needs attention (permission wait, high context,
+    /// over budget)
     pub needs_attention: bool,
 
+    /// Whether a cost budget has been exceeded
+    #[serde(default)]
+    pub over_budget: bool,
+
     /// Time since last activity (formatted)
     pub last_activity_display: String,
 
@@ -1213,6 +1346,14 @@ pub struct SessionView {
     /// First user prompt (for preview summary)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub first_prompt: Option<String>,
+
+    /// Team the session was spawned into (for grouping in tree view)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+
+    /// Registry revision this view was taken at
+    #[serde(default)]
+    pub revision: u64,
 }
 
 impl SessionView {
@@ -1241,12 +1382,14 @@ impl SessionView {
                 .current_activity
                 .as_ref()
                 .map(|a| a.display().into_owned()),
+            prompt: session.prompt.clone(),
             should_blink: session.status.should_blink(),
             status_icon: session.status.icon().to_string(),
             context_percentage: session.context.usage_percentage(),
             context_display: session.context.format(),
             context_warning: session.context.is_warning(),
             context_critical: session.context.is_critical(),
+            context_forecast: ContextAnalyzer::forecast(&session.context, &session.context_history),
             cost_display: session.cost.format(),
             cost_usd: session.cost.as_usd(),
             duration_display: session.duration.format(),
@@ -1260,7 +1403,10 @@ impl SessionView {
                     p
                 }
             }),
-            needs_attention: session.status.needs_attention() || session.needs_context_attention(),
+            needs_attention: session.status.needs_attention()
+                || session.needs_context_attention()
+                || session.over_budget,
+            over_budget: session.over_budget,
             last_activity_display: format_duration(since_activity),
             age_display: format_duration(age),
             started_at: session.started_at.to_rfc3339(),
@@ -1272,6 +1418,8 @@ impl SessionView {
             parent_session_id: session.parent_session_id.clone(),
             child_session_ids: session.child_session_ids.clone(),
             first_prompt: session.first_prompt.clone(),
+            team: session.team.clone(),
+            revision: session.revision,
         }
     }
 }
@@ -1309,7 +1457,11 @@ mod tests {
 
     /// Creates a test session with default values.
     fn create_test_session(id: &str) -> SessionDomain {
-        SessionDomain::new(SessionId::new(id), AgentType::GeneralPurpose, Model::Opus45)
+        SessionDomain::new(
+            SessionId::new(id),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-opus-4-5"),
+        )
     }
 
     #[test]
@@ -1335,10 +1487,10 @@ mod tests {
         let session = SessionDomain::new(
             SessionId::new("test-123"),
             AgentType::GeneralPurpose,
-            Model::Opus45,
+            Model::from_id("claude-opus-4-5"),
         );
         assert_eq!(session.id.as_str(), "test-123");
-        assert_eq!(session.model, Model::Opus45);
+        assert_eq!(session.model, Model::from_id("claude-opus-4-5"));
         assert!(session.cost.is_zero());
     }
 
@@ -1347,7 +1499,7 @@ mod tests {
         let session = SessionDomain::new(
             SessionId::new("8e11bfb5-7dc2-432b-9206-928fa5c35731"),
             AgentType::Explore,
-            Model::Sonnet4,
+            Model::from_id("claude-sonnet-4"),
         );
         let view = SessionView::from_domain(&session);
 
@@ -1361,7 +1513,7 @@ mod tests {
         let mut session = SessionDomain::new(
             SessionId::new("test-override"),
             AgentType::GeneralPurpose,
-            Model::Unknown,
+            Model::UNKNOWN,
         );
         session.model_display_override = Some("GPT-4o".to_string());
 
@@ -1374,7 +1526,7 @@ mod tests {
         let session = SessionDomain::new(
             SessionId::new("test-no-override"),
             AgentType::GeneralPurpose,
-            Model::Unknown,
+            Model::UNKNOWN,
         );
 
         let view = SessionView::from_domain(&session);
@@ -1386,7 +1538,7 @@ mod tests {
         let mut session = SessionDomain::new(
             SessionId::new("test-known"),
             AgentType::GeneralPurpose,
-            Model::Opus46,
+            Model::from_id("claude-opus-4-6"),
         );
         // Even if override is set, known models use their display_name
         session.model_display_override = Some("something else".to_string());
@@ -1445,10 +1597,10 @@ mod tests {
 
     #[test]
     fn lifecycle_provider_model_change_known_claude_id() {
-        // A pi session targeting a Claude model should map onto the
-        // existing Model variant; no override needed.
+        // A pi session targeting a Claude model should map onto its
+        // catalog entry; no override needed.
         let mut session = create_test_session("test-pmc-known");
-        session.model = Model::Unknown;
+        session.model = Model::UNKNOWN;
         session.model_display_override = Some("stale".to_string());
 
         session.apply_lifecycle_event(&LifecycleEvent::ProviderModelChange {
@@ -1456,7 +1608,7 @@ mod tests {
             model: Some("claude-sonnet-4-5-20250929".to_string()),
         });
 
-        assert_eq!(session.model, Model::Sonnet45);
+        assert_eq!(session.model, Model::from_id("claude-sonnet-4-5"));
         assert!(
             session.model_display_override.is_none(),
             "override must be cleared when the id maps to a known model"
@@ -1465,26 +1617,45 @@ mod tests {
 
     #[test]
     fn lifecycle_provider_model_change_unknown_id() {
-        // A pi session pointed at a non-Claude provider should land
-        // as Unknown with the raw id surfaced via the override field
+        // A pi session pointed at a model missing from the catalog
+        // should land as Unknown with the raw id surfaced via the override field
         // so the TUI shows something meaningful instead of "Unknown".
         let mut session = create_test_session("test-pmc-unknown");
-        session.model = Model::Unknown;
+        session.model = Model::UNKNOWN;
         session.model_display_override = None;
 
+        session.apply_lifecycle_event(&LifecycleEvent::ProviderModelChange {
+            provider: Some("ollama".to_string()),
+            model: Some("llama-3-70b".to_string()),
+        });
+
+        assert_eq!(session.model, Model::UNKNOWN);
+        assert_eq!(
+            session.model_display_override.as_deref(),
+            Some("llama-3-70b")
+        );
+        assert_eq!(session.context.context_window_size, 200_000);
+    }
+
+    #[test]
+    fn lifecycle_provider_model_change_uses_catalog_window() {
+        let mut session = create_test_session("test-pmc-window");
+        session.model = Model::UNKNOWN;
+
         session.apply_lifecycle_event(&LifecycleEvent::ProviderModelChange {
             provider: Some("openai".to_string()),
-            model: Some("gpt-4o".to_string()),
+            model: Some("gpt-5-codex".to_string()),
         });
 
-        assert_eq!(session.model, Model::Unknown);
-        assert_eq!(session.model_display_override.as_deref(), Some("gpt-4o"));
+        assert_eq!(session.model.display_name(), "GPT-5 Codex");
+        assert!(session.model_display_override.is_none());
+        assert_eq!(session.context.context_window_size, 400_000);
     }
 
     #[test]
     fn lifecycle_provider_model_change_no_model_is_noop() {
         let mut session = create_test_session("test-pmc-none");
-        session.model = Model::Sonnet4;
+        session.model = Model::from_id("claude-sonnet-4");
         session.model_display_override = None;
 
         session.apply_lifecycle_event(&LifecycleEvent::ProviderModelChange {
@@ -1492,7 +1663,7 @@ mod tests {
             model: None,
         });
 
-        assert_eq!(session.model, Model::Sonnet4);
+        assert_eq!(session.model, Model::from_id("claude-sonnet-4"));
         assert!(session.model_display_override.is_none());
     }
 
@@ -1504,6 +1675,7 @@ mod tests {
             reason: NeedsInputReason::InteractiveTool {
                 tool: Tool::AskUserQuestion,
             },
+            prompt: None,
         });
         assert_eq!(session.status, SessionStatus::AttentionNeeded);
         assert_eq!(
@@ -1531,6 +1703,7 @@ mod tests {
             reason: NeedsInputReason::InteractiveTool {
                 tool: Tool::EnterPlanMode,
             },
+            prompt: None,
         });
         assert_eq!(session.status, SessionStatus::AttentionNeeded);
         assert_eq!(
@@ -1555,6 +1728,7 @@ mod tests {
                 kind: NotificationKind::PermissionPrompt,
                 label: Some("Allow `rm -rf /tmp/cache`?".into()),
             },
+            prompt: None,
         });
         assert_eq!(session.status, SessionStatus::AttentionNeeded);
         assert_eq!(
@@ -1567,6 +1741,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lifecycle_needs_input_tracks_prompt() {
+        use crate::{PromptKind, PromptSource, PANE_PARSER_VERSION};
+
+        let mut session = create_test_session("test-prompt");
+        let described = PromptState::permission(
+            &Tool::Bash,
+            Some(&serde_json::json!({"command": "cargo test"})),
+        );
+        session.apply_lifecycle_event(&LifecycleEvent::NeedsInput {
+            reason: NeedsInputReason::PermissionGate { tool: Tool::Bash },
+            prompt: Some(described.clone()),
+        });
+        assert_eq!(session.prompt.as_ref(), Some(&described));
+
+        // The bare notification for the same dialog keeps the description,
+        // and a pane parse doesn't replace it either.
+        session.apply_lifecycle_event(&LifecycleEvent::NeedsInput {
+            reason: NeedsInputReason::Notification {
+                kind: NotificationKind::PermissionPrompt,
+                label: None,
+            },
+            prompt: None,
+        });
+        assert_eq!(session.prompt.as_ref(), Some(&described));
+        let parsed = PromptState {
+            kind: PromptKind::Permission,
+            question: None,
+            options: Vec::new(),
+            selected: None,
+            source: PromptSource::Pane {
+                parser_version: PANE_PARSER_VERSION,
+            },
+        };
+        assert!(!session.set_pane_prompt(parsed.clone()));
+
+        let view = SessionView::from_domain(&session);
+        assert_eq!(view.prompt.as_ref(), Some(&described));
+
+        // Leaving AttentionNeeded clears it; pane prompts only apply while waiting.
+        session.apply_lifecycle_event(&LifecycleEvent::ToolCallEnd {
+            name: Tool::Bash,
+            tool_use_id: None,
+            is_error: false,
+        });
+        assert!(session.prompt.is_none());
+        assert!(!session.set_pane_prompt(parsed.clone()));
+
+        session.apply_lifecycle_event(&LifecycleEvent::NeedsInput {
+            reason: NeedsInputReason::Notification {
+                kind: NotificationKind::PermissionPrompt,
+                label: None,
+            },
+            prompt: None,
+        });
+        assert!(session.prompt.is_none());
+        assert!(session.set_pane_prompt(parsed.clone()));
+        assert_eq!(session.prompt, Some(parsed));
+    }
+
+    #[test]
+    fn set_team_ignores_blank_and_unchanged() {
+        let mut session = create_test_session("test-team");
+        assert!(!session.set_team("  "));
+        assert!(session.set_team("review"));
+        assert!(!session.set_team("review"));
+        assert_eq!(
+            SessionView::from_domain(&session).team.as_deref(),
+            Some("review")
+        );
+    }
+
     #[test]
     fn lifecycle_needs_input_notification_falls_back_to_kind_when_label_absent() {
         // Claude `Notification(permission_prompt)` events don't carry a
@@ -1579,6 +1825,7 @@ mod tests {
                 kind: NotificationKind::PermissionPrompt,
                 label: None,
             },
+            prompt: None,
         });
         assert_eq!(session.status, SessionStatus::AttentionNeeded);
         assert_eq!(
@@ -1595,6 +1842,7 @@ mod tests {
                 kind: NotificationKind::ElicitationDialog,
                 label: None,
             },
+            prompt: None,
         });
         assert_eq!(
             session
@@ -1767,7 +2015,7 @@ mod tests {
         let mut session = SessionDomain::new(
             SessionId::new("test"),
             AgentType::GeneralPurpose,
-            Model::Sonnet4,
+            Model::from_id("claude-sonnet-4"),
         );
         session.working_directory = Some("/home/user/repo-a".to_string());
 
@@ -1787,7 +2035,7 @@ mod tests {
         let mut session = SessionDomain::new(
             SessionId::new("test"),
             AgentType::GeneralPurpose,
-            Model::Sonnet4,
+            Model::from_id("claude-sonnet-4"),
         );
         session.working_directory = Some("/home/user/repo".to_string());
 
@@ -1802,7 +2050,7 @@ mod tests {
         let mut session = SessionDomain::new(
             SessionId::new("test"),
             AgentType::GeneralPurpose,
-            Model::Sonnet4,
+            Model::from_id("claude-sonnet-4"),
         );
         // working_directory starts as None
 
@@ -1824,7 +2072,7 @@ mod tests {
         let mut session = SessionDomain::new(
             SessionId::new("test"),
             AgentType::GeneralPurpose,
-            Model::Sonnet4,
+            Model::from_id("claude-sonnet-4"),
         );
         session.working_directory = Some("/home/user/repo".to_string());
 
@@ -1841,4 +2089,28 @@ mod tests {
             "should preserve existing cwd when incoming is None"
         );
     }
+
+    #[test]
+    fn test_turn_growth_feeds_context_forecast() {
+        let mut session = SessionDomain::new(
+            SessionId::new("test"),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-sonnet-4"),
+        );
+        let mut data = make_status_data(None);
+        data.cache_read_tokens = 50_000;
+        session.update_from_status_line(&data);
+        assert!(SessionView::from_domain(&session)
+            .context_forecast
+            .is_none());
+
+        session.apply_lifecycle_event(&LifecycleEvent::PromptSubmit { prompt: None });
+        data.cache_read_tokens = 70_000;
+        session.update_from_status_line(&data);
+        session.apply_lifecycle_event(&LifecycleEvent::WorkingEnd);
+
+        // 184K auto-compact point - 70.8K in use = 113.2K, at 20K per turn.
+        let forecast = SessionView::from_domain(&session).context_forecast;
+        assert_eq!(forecast.map(|f| f.turns), Some(5));
+    }
 }
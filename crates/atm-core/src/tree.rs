@@ -5,26 +5,46 @@
 //!
 //! ```text
 //! Project (git repo root)
+//! ├── Team (agents spawned together)
+//! │   └── Agent
 //! ├── Worktree (branch / checkout path)
 //! │   ├── Agent (session)
 //! │   │   └── Subagent (child session)
+//! │   │       └── Subagent (nested to any depth)
 //! │   └── Agent
 //! └── Worktree
 //!     └── ...
 //! ```
 //!
-//! **Conditional worktree nesting:** When a project has only one worktree,
-//! the worktree level is skipped — agents appear directly under the project.
+//! **Teams:** Sessions tagged with a team (`ATM_TEAM` at spawn time, set
+//! by `atm layout` and `atm workspace create`) are grouped under a Team
+//! node ahead of the project's untagged sessions.
+//!
+//! **Conditional worktree nesting:** When a project's untagged sessions
+//! share one worktree, the worktree level is skipped — agents appear
+//! directly under the project.
+//!
+//! **Subagents:** Subagents nest under their parent to any depth, and
+//! cost and attention roll up to every ancestor. A subagent whose parent
+//! isn't in the list is shown at the top level, and parent links that
+//! form a cycle are broken so each session appears exactly once.
 //!
 //! **Ungrouped sessions:** Sessions without a `project_root` are collected
 //! under a synthetic "Other" project node.
 //!
+//! **Other views:** [`build_tree_with`] can instead group agents by tmux
+//! window, harness or status, or list them flat, and order them by a
+//! [`SortKey`] other than newest first.
+//!
 //! This module is pure logic with no TUI dependency, enabling reuse
 //! in the future web UI.
 
-use std::collections::{BTreeMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
 
-use crate::{SessionId, SessionView};
+use crate::{SessionId, SessionStatus, SessionView};
 
 // ============================================================================
 // Tree Node Types
@@ -37,8 +57,10 @@ pub enum TreeNodeId {
     Project(String),
     /// A worktree node, keyed by worktree path.
     Worktree(String),
-    /// A team node (placeholder for CC Agent Teams).
-    Team(String),
+    /// A team node, keyed by project root and team name.
+    Team(String, String),
+    /// A [`GroupBy`] group node, keyed by mode and label (e.g. "harness:pi").
+    Group(String),
     /// An agent (session) node, keyed by session ID.
     Agent(SessionId),
 }
@@ -55,7 +77,7 @@ pub enum TreeNode {
         name: String,
         /// Full project root path.
         root: String,
-        /// Child nodes (Worktree or Agent when single-worktree).
+        /// Child nodes (Team, then Worktree or Agent when single-worktree).
         children: Vec<TreeNode>,
     },
     /// A git worktree within a project.
@@ -67,10 +89,21 @@ pub enum TreeNode {
         /// Child nodes (Agent).
         children: Vec<TreeNode>,
     },
-    /// Placeholder for CC Agent Teams integration (Phase 6).
+    /// Agents spawned together by `atm layout` or `atm workspace create`.
     Team {
         /// Team name.
         name: String,
+        /// Root of the project the team belongs to.
+        project_root: String,
+        /// Child nodes (Agent).
+        children: Vec<TreeNode>,
+    },
+    /// A group from a [`GroupBy`] mode other than project.
+    Group {
+        /// Unique key: the mode's label and the group label.
+        key: String,
+        /// Display label (e.g. "main:1", "pi", "needs input").
+        label: String,
         /// Child nodes (Agent).
         children: Vec<TreeNode>,
     },
@@ -89,7 +122,8 @@ impl TreeNode {
         match self {
             TreeNode::Project { children, .. }
             | TreeNode::Worktree { children, .. }
-            | TreeNode::Team { children, .. } => children.iter().map(|c| c.agent_count()).sum(),
+            | TreeNode::Team { children, .. }
+            | TreeNode::Group { children, .. } => children.iter().map(|c| c.agent_count()).sum(),
             TreeNode::Agent { subagents, .. } => {
                 1 + subagents.iter().map(|s| s.agent_count()).sum::<usize>()
             }
@@ -101,19 +135,62 @@ impl TreeNode {
         match self {
             TreeNode::Project { children, .. }
             | TreeNode::Worktree { children, .. }
-            | TreeNode::Team { children, .. } => children.iter().any(|c| c.needs_attention()),
+            | TreeNode::Team { children, .. }
+            | TreeNode::Group { children, .. } => children.iter().any(|c| c.needs_attention()),
             TreeNode::Agent { session, subagents } => {
                 session.needs_attention || subagents.iter().any(|s| s.needs_attention())
             }
         }
     }
 
+    /// Returns the total cost in USD of every agent in this subtree.
+    pub fn total_cost_usd(&self) -> f64 {
+        match self {
+            TreeNode::Project { children, .. }
+            | TreeNode::Worktree { children, .. }
+            | TreeNode::Team { children, .. }
+            | TreeNode::Group { children, .. } => children.iter().map(|c| c.total_cost_usd()).sum(),
+            TreeNode::Agent { session, subagents } => {
+                session.cost_usd + subagents.iter().map(|s| s.total_cost_usd()).sum::<f64>()
+            }
+        }
+    }
+
+    /// Returns the IDs of every agent session in this subtree, in tree order.
+    pub fn session_ids(&self) -> Vec<SessionId> {
+        let mut ids = Vec::new();
+        self.collect_session_ids(&mut ids);
+        ids
+    }
+
+    fn collect_session_ids(&self, ids: &mut Vec<SessionId>) {
+        match self {
+            TreeNode::Project { children, .. }
+            | TreeNode::Worktree { children, .. }
+            | TreeNode::Team { children, .. }
+            | TreeNode::Group { children, .. } => {
+                for child in children {
+                    child.collect_session_ids(ids);
+                }
+            }
+            TreeNode::Agent { session, subagents } => {
+                ids.push(session.id.clone());
+                for child in subagents {
+                    child.collect_session_ids(ids);
+                }
+            }
+        }
+    }
+
     /// Returns the [`TreeNodeId`] for this node.
     pub fn node_id(&self) -> TreeNodeId {
         match self {
             TreeNode::Project { root, .. } => TreeNodeId::Project(root.clone()),
             TreeNode::Worktree { path, .. } => TreeNodeId::Worktree(path.clone()),
-            TreeNode::Team { name, .. } => TreeNodeId::Team(name.clone()),
+            TreeNode::Team {
+                name, project_root, ..
+            } => TreeNodeId::Team(project_root.clone(), name.clone()),
+            TreeNode::Group { key, .. } => TreeNodeId::Group(key.clone()),
             TreeNode::Agent { session, .. } => TreeNodeId::Agent(session.id.clone()),
         }
     }
@@ -138,6 +215,9 @@ pub enum TreeRowKind {
     Team {
         name: String,
     },
+    Group {
+        label: String,
+    },
     Agent {
         session: SessionView,
     },
@@ -160,10 +240,153 @@ pub struct TreeRow {
     pub agent_count: usize,
     /// Whether any agent in the subtree needs attention (bubble-up).
     pub needs_attention: bool,
+    /// Total cost in USD of the subtree (shown on team rows and
+    /// collapsed parent agents).
+    pub total_cost_usd: f64,
     /// Whether this node has children (is expandable).
     pub has_children: bool,
 }
 
+// ============================================================================
+// Tree Options
+// ============================================================================
+
+/// How top-level sessions are grouped into the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    /// Project > Team | Worktree > Agent (the default hierarchy).
+    #[default]
+    Project,
+    /// One group per tmux session and window.
+    Tmux,
+    /// One group per harness (claude, pi, codex, ...).
+    Harness,
+    /// One group per status, sessions needing input first.
+    Status,
+    /// No groups: every top-level agent in one list.
+    Flat,
+}
+
+impl GroupBy {
+    /// The mode after this one in the cycle, wrapping back to project.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Project => Self::Tmux,
+            Self::Tmux => Self::Harness,
+            Self::Harness => Self::Status,
+            Self::Status => Self::Flat,
+            Self::Flat => Self::Project,
+        }
+    }
+
+    /// Short label, matching the config value.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Project => "project",
+            Self::Tmux => "tmux",
+            Self::Harness => "harness",
+            Self::Status => "status",
+            Self::Flat => "flat",
+        }
+    }
+}
+
+/// Order of agents within each group.
+///
+/// Ties are broken by start time, newest first. Subagents keep the order
+/// their parent declared them in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    /// Most recently started first.
+    #[default]
+    Newest,
+    /// Highest cost first, including subagents.
+    Cost,
+    /// Fullest context window first.
+    Context,
+    /// Longest running first.
+    Duration,
+    /// Most recently active first.
+    Activity,
+    /// Agents needing input (or with a subagent that does) first.
+    Attention,
+    /// Alphabetical by project, branch and short ID.
+    Name,
+}
+
+impl SortKey {
+    /// The key after this one in the cycle, wrapping back to newest.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Newest => Self::Cost,
+            Self::Cost => Self::Context,
+            Self::Context => Self::Duration,
+            Self::Duration => Self::Activity,
+            Self::Activity => Self::Attention,
+            Self::Attention => Self::Name,
+            Self::Name => Self::Newest,
+        }
+    }
+
+    /// Short label, matching the config value.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Newest => "newest",
+            Self::Cost => "cost",
+            Self::Context => "context",
+            Self::Duration => "duration",
+            Self::Activity => "activity",
+            Self::Attention => "attention",
+            Self::Name => "name",
+        }
+    }
+
+    /// Compares two agent nodes under this key.
+    fn compare(self, a: &TreeNode, b: &TreeNode) -> Ordering {
+        let (TreeNode::Agent { session: sa, .. }, TreeNode::Agent { session: sb, .. }) = (a, b)
+        else {
+            return Ordering::Equal;
+        };
+        let primary = match self {
+            Self::Newest => Ordering::Equal,
+            Self::Cost => b.total_cost_usd().total_cmp(&a.total_cost_usd()),
+            Self::Context => sb.context_percentage.total_cmp(&sa.context_percentage),
+            Self::Duration => sb.duration_seconds.total_cmp(&sa.duration_seconds),
+            Self::Activity => sb.last_activity.cmp(&sa.last_activity),
+            Self::Attention => b.needs_attention().cmp(&a.needs_attention()),
+            Self::Name => name_key(sa).cmp(&name_key(sb)),
+        };
+        primary.then_with(|| sb.started_at.cmp(&sa.started_at))
+    }
+}
+
+/// Project, branch and short ID, for [`SortKey::Name`].
+fn name_key(session: &SessionView) -> (Option<String>, Option<&str>, &str) {
+    (
+        session.project_root.as_deref().map(extract_project_name),
+        session.worktree_branch.as_deref(),
+        session.id_short.as_str(),
+    )
+}
+
+/// Grouping and ordering used by [`build_tree_with`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TreeOptions {
+    /// How top-level sessions are grouped.
+    pub group_by: GroupBy,
+    /// Order of agents within each group.
+    pub sort_by: SortKey,
+    /// tmux session name and window index of each pane, keyed by pane ID.
+    /// Only used by [`GroupBy::Tmux`]; unknown panes group under "Other".
+    pub pane_windows: HashMap<String, (String, u32)>,
+}
+
 // ============================================================================
 // Tree Building
 // ============================================================================
@@ -174,33 +397,109 @@ const UNGROUPED_PROJECT_ROOT: &str = "__ungrouped__";
 
 /// Builds a tree of [`TreeNode`] from a flat slice of sessions.
 ///
-/// Grouping hierarchy: Project > Worktree (conditional) > Agent.
+/// Grouping hierarchy: Project > Team | Worktree (conditional) > Agent.
 /// Sessions without `project_root` are grouped under an "Other" project.
-/// Sessions with `parent_session_id` are nested under their parent.
+/// Subagents are nested under their parent, recursively.
 pub fn build_tree(sessions: &[SessionView]) -> Vec<TreeNode> {
+    build_tree_with(sessions, &TreeOptions::default())
+}
+
+/// Builds a tree like [`build_tree`], grouped and sorted per `options`.
+///
+/// Every mode other than [`GroupBy::Project`] puts agents one level under
+/// a [`TreeNode::Group`], or at the top level for [`GroupBy::Flat`].
+/// Subagents always nest under their parent.
+pub fn build_tree_with(sessions: &[SessionView], options: &TreeOptions) -> Vec<TreeNode> {
     if sessions.is_empty() {
         return Vec::new();
     }
 
-    // Separate parent-level sessions from subagents
-    let child_ids: HashSet<&SessionId> = sessions
+    // Index sessions by ID for subagent lookup
+    let by_id: BTreeMap<&str, &SessionView> = sessions.iter().map(|s| (s.id.as_str(), s)).collect();
+
+    // Subagent links: each parent's listed children, plus any child naming
+    // a parent that didn't list it
+    let mut children_of: HashMap<&str, Vec<&SessionView>> = HashMap::new();
+    for session in sessions {
+        let listed = session
+            .child_session_ids
+            .iter()
+            .filter(|id| **id != session.id)
+            .filter_map(|id| by_id.get(id.as_str()).copied());
+        children_of
+            .entry(session.id.as_str())
+            .or_default()
+            .extend(listed);
+    }
+    for session in sessions {
+        let Some(parent) = session.parent_session_id.as_ref() else {
+            continue;
+        };
+        if *parent == session.id || !by_id.contains_key(parent.as_str()) {
+            continue;
+        }
+        let siblings = children_of.entry(parent.as_str()).or_default();
+        if !siblings.iter().any(|s| s.id == session.id) {
+            siblings.push(session);
+        }
+    }
+
+    // Top-level sessions are those nobody claims as a subagent. Sessions
+    // only reachable through a parent cycle are promoted in input order,
+    // so every session is shown.
+    let child_ids: HashSet<&str> = children_of
+        .values()
+        .flatten()
+        .map(|s| s.id.as_str())
+        .collect();
+    let mut roots: Vec<&SessionView> = sessions
         .iter()
-        .filter(|s| s.parent_session_id.is_some())
-        .map(|s| &s.id)
+        .filter(|s| !child_ids.contains(s.id.as_str()))
         .collect();
+    let mut reached: HashSet<&str> = HashSet::new();
+    for root in &roots {
+        mark_reachable(root, &children_of, &mut reached);
+    }
+    for session in sessions {
+        if !reached.contains(session.id.as_str()) {
+            mark_reachable(session, &children_of, &mut reached);
+            roots.push(session);
+        }
+    }
 
-    // Index sessions by ID for subagent lookup
-    let by_id: BTreeMap<&str, &SessionView> = sessions.iter().map(|s| (s.id.as_str(), s)).collect();
+    // Each session is placed once; this also stops descent into a cycle
+    let mut placed: HashSet<&str> = HashSet::new();
+    let links = Links {
+        children_of: &children_of,
+        sort_by: options.sort_by,
+    };
+
+    match options.group_by {
+        GroupBy::Project => build_project_nodes(&roots, links, &mut placed),
+        GroupBy::Flat => build_agent_nodes(&roots, links, &mut placed),
+        GroupBy::Tmux => build_group_nodes(&roots, GroupBy::Tmux, links, &mut placed, |s| {
+            tmux_slot(s, &options.pane_windows)
+        }),
+        GroupBy::Harness => {
+            build_group_nodes(&roots, GroupBy::Harness, links, &mut placed, harness_slot)
+        }
+        GroupBy::Status => {
+            build_group_nodes(&roots, GroupBy::Status, links, &mut placed, status_slot)
+        }
+    }
+}
 
+/// Builds the Project > Team | Worktree > Agent hierarchy.
+fn build_project_nodes<'a>(
+    roots: &[&'a SessionView],
+    links: Links<'a, '_>,
+    placed: &mut HashSet<&'a str>,
+) -> Vec<TreeNode> {
     // Group top-level sessions by project_root
     // BTreeMap for deterministic alphabetical ordering
     let mut by_project: BTreeMap<&str, Vec<&SessionView>> = BTreeMap::new();
 
-    for session in sessions {
-        // Skip subagents — they'll be nested under their parent
-        if child_ids.contains(&session.id) {
-            continue;
-        }
+    for session in roots {
         let project_key = session
             .project_root
             .as_deref()
@@ -217,90 +516,182 @@ pub fn build_tree(sessions: &[SessionView]) -> Vec<TreeNode> {
             extract_project_name(project_root)
         };
 
-        // Build agent nodes (with subagent nesting)
-        let make_agent_node = |session: &SessionView| -> TreeNode {
-            let subagents: Vec<TreeNode> = session
-                .child_session_ids
-                .iter()
-                .filter_map(|child_id| by_id.get(child_id.as_str()))
-                .map(|child| TreeNode::Agent {
-                    session: (*child).clone(),
-                    subagents: Vec::new(), // No recursive subagent nesting for now
-                })
-                .collect();
-            TreeNode::Agent {
-                session: session.clone(),
-                subagents,
-            }
-        };
-
-        // Group by worktree within this project
+        // Split off team-tagged sessions; the rest group by worktree
+        let mut by_team: BTreeMap<&str, Vec<&SessionView>> = BTreeMap::new();
         let mut by_worktree: BTreeMap<Option<&str>, Vec<&SessionView>> = BTreeMap::new();
         for session in project_sessions {
-            let wt_key = session.worktree_path.as_deref();
-            by_worktree.entry(wt_key).or_default().push(session);
+            match session.team.as_deref() {
+                Some(team) => by_team.entry(team).or_default().push(session),
+                None => {
+                    let wt_key = session.worktree_path.as_deref();
+                    by_worktree.entry(wt_key).or_default().push(session);
+                }
+            }
         }
 
+        let mut children: Vec<TreeNode> = by_team
+            .iter()
+            .map(|(team, team_sessions)| TreeNode::Team {
+                name: team.to_string(),
+                project_root: project_root.to_string(),
+                children: build_agent_nodes(team_sessions, links, placed),
+            })
+            .collect();
+
         // Conditional worktree nesting: skip worktree level if only one
         let skip_worktree = by_worktree.len() <= 1;
 
         if skip_worktree {
             // Agents directly under project
-            let mut agents: Vec<TreeNode> = project_sessions
-                .iter()
-                .map(|s| make_agent_node(s))
-                .collect();
-            sort_agent_nodes(&mut agents);
-
-            project_nodes.push(TreeNode::Project {
-                name: project_name,
-                root: project_root.to_string(),
-                children: agents,
-            });
+            let agents: Vec<&SessionView> = by_worktree.values().flatten().copied().collect();
+            children.extend(build_agent_nodes(&agents, links, placed));
         } else {
             // Worktree grouping
-            let mut worktree_nodes: Vec<TreeNode> = Vec::new();
             for (wt_path, wt_sessions) in &by_worktree {
                 let branch = wt_sessions.first().and_then(|s| s.worktree_branch.clone());
                 let path = wt_path
                     .map(|p| p.to_string())
                     .unwrap_or_else(|| "unknown".to_string());
 
-                let mut agents: Vec<TreeNode> =
-                    wt_sessions.iter().map(|s| make_agent_node(s)).collect();
-                sort_agent_nodes(&mut agents);
-
-                worktree_nodes.push(TreeNode::Worktree {
+                children.push(TreeNode::Worktree {
                     path,
                     branch,
-                    children: agents,
+                    children: build_agent_nodes(wt_sessions, links, placed),
                 });
             }
-
-            project_nodes.push(TreeNode::Project {
-                name: project_name,
-                root: project_root.to_string(),
-                children: worktree_nodes,
-            });
         }
+
+        project_nodes.push(TreeNode::Project {
+            name: project_name,
+            root: project_root.to_string(),
+            children,
+        });
     }
 
     project_nodes
 }
 
-/// Sort agent nodes by started_at descending (newest first).
-fn sort_agent_nodes(nodes: &mut [TreeNode]) {
-    nodes.sort_by(|a, b| {
-        let a_time = match a {
-            TreeNode::Agent { session, .. } => session.started_at.as_str(),
-            _ => "",
-        };
-        let b_time = match b {
-            TreeNode::Agent { session, .. } => session.started_at.as_str(),
-            _ => "",
-        };
-        b_time.cmp(a_time) // newest first
-    });
+/// Where a session falls in a single-level grouping: an ordering rank
+/// and the group's display label.
+type GroupSlot = ((u8, String, u32), String);
+
+/// Builds one [`TreeNode::Group`] per distinct slot, in slot order.
+fn build_group_nodes<'a>(
+    roots: &[&'a SessionView],
+    group_by: GroupBy,
+    links: Links<'a, '_>,
+    placed: &mut HashSet<&'a str>,
+    slot: impl Fn(&SessionView) -> GroupSlot,
+) -> Vec<TreeNode> {
+    let mut groups: BTreeMap<(u8, String, u32), (String, Vec<&SessionView>)> = BTreeMap::new();
+    for session in roots {
+        let (rank, label) = slot(session);
+        groups
+            .entry(rank)
+            .or_insert_with(|| (label, Vec::new()))
+            .1
+            .push(session);
+    }
+    groups
+        .into_values()
+        .map(|(label, group)| TreeNode::Group {
+            key: format!("{}:{label}", group_by.label()),
+            label,
+            children: build_agent_nodes(&group, links, placed),
+        })
+        .collect()
+}
+
+/// tmux session and window of the session's pane; "Other" (last) when
+/// the pane is unknown or the session isn't in tmux.
+fn tmux_slot(session: &SessionView, pane_windows: &HashMap<String, (String, u32)>) -> GroupSlot {
+    match session
+        .tmux_pane
+        .as_ref()
+        .and_then(|pane| pane_windows.get(pane))
+    {
+        Some((name, window)) => ((0, name.clone(), *window), format!("{name}:{window}")),
+        None => ((1, String::new(), 0), UNGROUPED_PROJECT_NAME.to_string()),
+    }
+}
+
+/// Harness name, alphabetically; legacy sessions default to claude.
+fn harness_slot(session: &SessionView) -> GroupSlot {
+    let harness = if session.harness.is_empty() {
+        "claude"
+    } else {
+        session.harness.as_str()
+    };
+    ((0, harness.to_string(), 0), harness.to_string())
+}
+
+/// Status, most urgent first.
+fn status_slot(session: &SessionView) -> GroupSlot {
+    let rank = match session.status {
+        SessionStatus::AttentionNeeded => 0,
+        SessionStatus::Working => 1,
+        SessionStatus::Idle => 2,
+    };
+    ((rank, String::new(), 0), session.status.label().to_string())
+}
+
+/// Subagent links and the sort order, shared while building one tree.
+#[derive(Clone, Copy)]
+struct Links<'a, 'b> {
+    children_of: &'b HashMap<&'a str, Vec<&'a SessionView>>,
+    sort_by: SortKey,
+}
+
+/// Builds sorted agent nodes for `group`, skipping sessions already placed.
+fn build_agent_nodes<'a>(
+    group: &[&'a SessionView],
+    links: Links<'a, '_>,
+    placed: &mut HashSet<&'a str>,
+) -> Vec<TreeNode> {
+    let mut agents = Vec::new();
+    for session in group {
+        if !placed.contains(session.id.as_str()) {
+            agents.push(make_agent_node(session, links.children_of, placed));
+        }
+    }
+    agents.sort_by(|a, b| links.sort_by.compare(a, b));
+    agents
+}
+
+/// Builds an agent node with its subagents nested to any depth.
+///
+/// Children already in `placed` (shown elsewhere, or an ancestor when
+/// parent links form a cycle) are skipped.
+fn make_agent_node<'a>(
+    session: &'a SessionView,
+    children_of: &HashMap<&'a str, Vec<&'a SessionView>>,
+    placed: &mut HashSet<&'a str>,
+) -> TreeNode {
+    placed.insert(session.id.as_str());
+    let mut subagents = Vec::new();
+    for child in children_of.get(session.id.as_str()).into_iter().flatten() {
+        if !placed.contains(child.id.as_str()) {
+            subagents.push(make_agent_node(child, children_of, placed));
+        }
+    }
+    TreeNode::Agent {
+        session: session.clone(),
+        subagents,
+    }
+}
+
+/// Marks every session reachable from `root` through subagent links.
+fn mark_reachable<'a>(
+    root: &'a SessionView,
+    children_of: &HashMap<&'a str, Vec<&'a SessionView>>,
+    reached: &mut HashSet<&'a str>,
+) {
+    let mut stack = vec![root];
+    while let Some(session) = stack.pop() {
+        if reached.insert(session.id.as_str()) {
+            stack.extend(children_of.get(session.id.as_str()).into_iter().flatten());
+        }
+    }
 }
 
 /// Extracts a short project name from a path (last component).
@@ -340,7 +731,8 @@ fn flatten_node(
     let has_children = match node {
         TreeNode::Project { children, .. }
         | TreeNode::Worktree { children, .. }
-        | TreeNode::Team { children, .. } => !children.is_empty(),
+        | TreeNode::Team { children, .. }
+        | TreeNode::Group { children, .. } => !children.is_empty(),
         TreeNode::Agent { subagents, .. } => !subagents.is_empty(),
     };
 
@@ -354,6 +746,9 @@ fn flatten_node(
             branch: branch.clone(),
         },
         TreeNode::Team { name, .. } => TreeRowKind::Team { name: name.clone() },
+        TreeNode::Group { label, .. } => TreeRowKind::Group {
+            label: label.clone(),
+        },
         TreeNode::Agent { session, .. } => TreeRowKind::Agent {
             session: session.clone(),
         },
@@ -366,6 +761,7 @@ fn flatten_node(
         is_expanded,
         agent_count: node.agent_count(),
         needs_attention: node.needs_attention(),
+        total_cost_usd: node.total_cost_usd(),
         has_children,
     });
 
@@ -374,7 +770,8 @@ fn flatten_node(
         let children: &[TreeNode] = match node {
             TreeNode::Project { children, .. }
             | TreeNode::Worktree { children, .. }
-            | TreeNode::Team { children, .. } => children,
+            | TreeNode::Team { children, .. }
+            | TreeNode::Group { children, .. } => children,
             TreeNode::Agent { subagents, .. } => subagents,
         };
         for child in children {
@@ -395,7 +792,8 @@ pub fn all_node_ids(tree: &[TreeNode]) -> HashSet<TreeNodeId> {
         match node {
             TreeNode::Project { children, .. }
             | TreeNode::Worktree { children, .. }
-            | TreeNode::Team { children, .. } => {
+            | TreeNode::Team { children, .. }
+            | TreeNode::Group { children, .. } => {
                 for child in children {
                     collect(child, ids);
                 }
@@ -413,6 +811,22 @@ pub fn all_node_ids(tree: &[TreeNode]) -> HashSet<TreeNodeId> {
     ids
 }
 
+/// Finds the node with the given ID anywhere in the tree.
+pub fn find_node<'a>(tree: &'a [TreeNode], id: &TreeNodeId) -> Option<&'a TreeNode> {
+    tree.iter().find_map(|node| {
+        if &node.node_id() == id {
+            return Some(node);
+        }
+        match node {
+            TreeNode::Project { children, .. }
+            | TreeNode::Worktree { children, .. }
+            | TreeNode::Team { children, .. }
+            | TreeNode::Group { children, .. } => find_node(children, id),
+            TreeNode::Agent { subagents, .. } => find_node(subagents, id),
+        }
+    })
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -622,6 +1036,92 @@ mod tests {
         }
     }
 
+    /// Links `child` under `parent` from both sides.
+    fn link(parent: &mut SessionView, child: &mut SessionView) {
+        parent.child_session_ids.push(child.id.clone());
+        child.parent_session_id = Some(parent.id.clone());
+    }
+
+    #[test]
+    fn test_recursive_subagent_nesting_rolls_up() {
+        let mut root = make_session("root");
+        let mut mid = make_session("mid");
+        let mut leaf = make_session("leaf");
+        root.cost_usd = 1.0;
+        mid.cost_usd = 0.5;
+        leaf.cost_usd = 0.25;
+        leaf.needs_attention = true;
+        link(&mut root, &mut mid);
+        link(&mut mid, &mut leaf);
+
+        let tree = build_tree(&[leaf, mid, root]);
+        let TreeNode::Project { children, .. } = &tree[0] else {
+            panic!("expected Project");
+        };
+        assert_eq!(children.len(), 1);
+        let root = &children[0];
+        assert_eq!(
+            root.session_ids(),
+            vec![
+                SessionId::new("root"),
+                SessionId::new("mid"),
+                SessionId::new("leaf")
+            ]
+        );
+        assert_eq!(root.agent_count(), 3);
+        assert!(root.needs_attention());
+        assert!((root.total_cost_usd() - 1.75).abs() < f64::EPSILON);
+
+        let rows = flatten_tree(&tree, &all_node_ids(&tree));
+        let depths: Vec<u8> = rows.iter().map(|r| r.depth).collect();
+        assert_eq!(depths, vec![0, 1, 2, 3]);
+        assert!(rows[2].needs_attention, "mid should bubble up leaf");
+    }
+
+    #[test]
+    fn test_subagent_links_from_either_side() {
+        // Child names its parent, but the parent never listed it
+        let parent = make_session("parent");
+        let mut child = make_session("child");
+        child.parent_session_id = Some(parent.id.clone());
+        // Orphan: parent isn't tracked, so it is shown at the top level
+        let mut orphan = make_session("orphan");
+        orphan.parent_session_id = Some(SessionId::new("gone"));
+
+        let tree = build_tree(&[parent, child, orphan]);
+        let TreeNode::Project { children, .. } = &tree[0] else {
+            panic!("expected Project");
+        };
+        assert_eq!(tree[0].agent_count(), 3);
+        assert_eq!(children.len(), 2);
+        assert!(children
+            .iter()
+            .any(|c| c.session_ids() == vec![SessionId::new("parent"), SessionId::new("child")]));
+    }
+
+    #[test]
+    fn test_subagent_cycle_shows_each_session_once() {
+        let mut a = make_session("a");
+        let mut b = make_session("b");
+        let mut c = make_session("c");
+        link(&mut a, &mut b);
+        link(&mut b, &mut c);
+        link(&mut c, &mut a);
+        // A self-link is ignored too
+        c.child_session_ids.push(c.id.clone());
+
+        let tree = build_tree(&[a, b, c]);
+        let ids = tree[0].session_ids();
+        assert_eq!(
+            ids,
+            vec![
+                SessionId::new("a"),
+                SessionId::new("b"),
+                SessionId::new("c")
+            ]
+        );
+    }
+
     #[test]
     fn test_agent_count() {
         let sessions = vec![
@@ -678,6 +1178,79 @@ mod tests {
         );
     }
 
+    fn make_team_session(id: &str, team: &str, cost_usd: f64) -> SessionView {
+        SessionView {
+            team: Some(team.to_string()),
+            cost_usd,
+            ..make_session_in_project(
+                id,
+                "/home/user/myapp",
+                "/home/user/myapp",
+                "main",
+                "2026-01-01T00:00:00Z",
+            )
+        }
+    }
+
+    #[test]
+    fn test_team_grouping() {
+        let mut waiting = make_team_session("b", "review", 0.75);
+        waiting.needs_attention = true;
+        let sessions = vec![
+            make_team_session("a", "review", 0.25),
+            waiting,
+            make_team_session("c", "build", 1.0),
+            make_session_in_project(
+                "d",
+                "/home/user/myapp",
+                "/home/user/myapp-wt",
+                "dev",
+                "2026-01-01T00:00:00Z",
+            ),
+        ];
+        let tree = build_tree(&sessions);
+
+        let TreeNode::Project { children, .. } = &tree[0] else {
+            panic!("expected Project");
+        };
+        // Teams first (alphabetical), then the lone untagged agent with
+        // no worktree nesting
+        assert_eq!(children.len(), 3);
+        match &children[0] {
+            TreeNode::Team { name, .. } => assert_eq!(name, "build"),
+            _ => panic!("expected Team"),
+        }
+        let review = &children[1];
+        match review {
+            TreeNode::Team { name, children, .. } => {
+                assert_eq!(name, "review");
+                assert_eq!(children.len(), 2);
+            }
+            _ => panic!("expected Team"),
+        }
+        assert!(review.needs_attention());
+        assert!((review.total_cost_usd() - 1.0).abs() < f64::EPSILON);
+        assert!(!children[0].needs_attention());
+        assert!(matches!(&children[2], TreeNode::Agent { .. }));
+    }
+
+    #[test]
+    fn test_team_ids_are_scoped_to_project() {
+        let mut other = make_team_session("b", "review", 0.0);
+        other.project_root = Some("/home/user/other".to_string());
+        let tree = build_tree(&[make_team_session("a", "review", 0.0), other]);
+
+        let ids = all_node_ids(&tree);
+        assert!(ids.contains(&TreeNodeId::Team(
+            "/home/user/myapp".to_string(),
+            "review".to_string()
+        )));
+        assert!(ids.contains(&TreeNodeId::Team(
+            "/home/user/other".to_string(),
+            "review".to_string()
+        )));
+    }
+
     #[test]
     fn test_agents_sorted_newest_first() {
         let sessions = vec![
@@ -720,6 +1293,167 @@ mod tests {
         }
     }
 
+    // ------------------------------------------------------------------
+    // build_tree_with tests
+    // ------------------------------------------------------------------
+
+    fn options(group_by: GroupBy, sort_by: SortKey) -> TreeOptions {
+        TreeOptions {
+            group_by,
+            sort_by,
+            ..Default::default()
+        }
+    }
+
+    fn top_level_ids(tree: &[TreeNode]) -> Vec<String> {
+        tree.iter()
+            .filter_map(|n| match n {
+                TreeNode::Agent { session, .. } => Some(session.id.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_flat_sorted_by_each_key() {
+        let mut cheap = make_session("cheap");
+        cheap.cost_usd = 0.1;
+        cheap.context_percentage = 90.0;
+        cheap.duration_seconds = 10.0;
+        cheap.last_activity = "2026-01-01T00:09:00Z".to_string();
+        cheap.project_root = Some("/home/user/beta".to_string());
+        let mut pricey = make_session("pricey");
+        pricey.cost_usd = 5.0;
+        pricey.context_percentage = 20.0;
+        pricey.duration_seconds = 600.0;
+        pricey.last_activity = "2026-01-01T00:01:00Z".to_string();
+        pricey.project_root = Some("/home/user/alpha".to_string());
+        pricey.started_at = "2026-01-01T00:05:00Z".to_string();
+        let mut waiting = make_session("waiting");
+        waiting.needs_attention = true;
+        waiting.project_root = Some("/home/user/gamma".to_string());
+        let sessions = vec![cheap, pricey, waiting];
+
+        let order = |sort_by| {
+            top_level_ids(&build_tree_with(
+                &sessions,
+                &options(GroupBy::Flat, sort_by),
+            ))
+        };
+        assert_eq!(order(SortKey::Newest)[0], "pricey");
+        assert_eq!(order(SortKey::Cost), vec!["pricey", "cheap", "waiting"]);
+        assert_eq!(order(SortKey::Context), vec!["cheap", "pricey", "waiting"]);
+        assert_eq!(order(SortKey::Duration), vec!["pricey", "cheap", "waiting"]);
+        assert_eq!(order(SortKey::Activity), vec!["cheap", "pricey", "waiting"]);
+        assert_eq!(order(SortKey::Attention)[0], "waiting");
+        assert_eq!(order(SortKey::Name), vec!["pricey", "cheap", "waiting"]);
+    }
+
+    #[test]
+    fn test_cost_sort_includes_subagents() {
+        let mut parent = make_session("parent");
+        let mut child = make_session("child");
+        child.cost_usd = 3.0;
+        link(&mut parent, &mut child);
+        let mut solo = make_session("solo");
+        solo.cost_usd = 2.0;
+
+        let tree = build_tree_with(
+            &[solo, parent, child],
+            &options(GroupBy::Flat, SortKey::Cost),
+        );
+        assert_eq!(top_level_ids(&tree), vec!["parent", "solo"]);
+    }
+
+    fn group_labels(tree: &[TreeNode]) -> Vec<&str> {
+        tree.iter()
+            .filter_map(|n| match n {
+                TreeNode::Group { label, .. } => Some(label.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_status_puts_attention_first() {
+        let mut idle = make_session("idle");
+        idle.status = SessionStatus::Idle;
+        let mut waiting = make_session("waiting");
+        waiting.status = SessionStatus::AttentionNeeded;
+        let tree = build_tree_with(
+            &[idle, make_session("busy"), waiting],
+            &options(GroupBy::Status, SortKey::Newest),
+        );
+        assert_eq!(group_labels(&tree), vec!["needs input", "working", "idle"]);
+        assert_eq!(
+            tree[0].node_id(),
+            TreeNodeId::Group("status:needs input".to_string())
+        );
+    }
+
+    #[test]
+    fn test_group_by_harness_defaults_to_claude() {
+        let mut pi = make_session("pi");
+        pi.harness = "pi".to_string();
+        let tree = build_tree_with(
+            &[pi, make_session("legacy")],
+            &options(GroupBy::Harness, SortKey::Newest),
+        );
+        assert_eq!(group_labels(&tree), vec!["claude", "pi"]);
+    }
+
+    #[test]
+    fn test_group_by_tmux_window() {
+        let in_pane = |id: &str, pane: &str| SessionView {
+            tmux_pane: Some(pane.to_string()),
+            ..make_session(id)
+        };
+        let mut opts = options(GroupBy::Tmux, SortKey::Newest);
+        opts.pane_windows = HashMap::from([
+            ("%1".to_string(), ("work".to_string(), 2)),
+            ("%2".to_string(), ("work".to_string(), 10)),
+            ("%3".to_string(), ("dev".to_string(), 0)),
+        ]);
+        let sessions = vec![
+            in_pane("a", "%2"),
+            in_pane("b", "%1"),
+            in_pane("c", "%3"),
+            in_pane("d", "%9"),
+            make_session("e"),
+        ];
+        let tree = build_tree_with(&sessions, &opts);
+        assert_eq!(
+            group_labels(&tree),
+            vec!["dev:0", "work:2", "work:10", "Other"]
+        );
+        assert_eq!(tree[3].agent_count(), 2);
+    }
+
+    #[test]
+    fn test_group_by_keeps_subagents_nested() {
+        let mut parent = make_session("parent");
+        parent.harness = "codex".to_string();
+        let mut child = make_session("child");
+        link(&mut parent, &mut child);
+        let tree = build_tree_with(&[child, parent], &options(GroupBy::Harness, SortKey::Name));
+        assert_eq!(group_labels(&tree), vec!["codex"]);
+        assert_eq!(tree[0].agent_count(), 2);
+    }
+
+    #[test]
+    fn test_group_and_sort_cycle_wrap() {
+        let mut group = GroupBy::default();
+        for _ in 0..5 {
+            group = group.next();
+        }
+        assert_eq!(group, GroupBy::Project);
+        let mut sort = SortKey::default();
+        for _ in 0..7 {
+            sort = sort.next();
+        }
+        assert_eq!(sort, SortKey::Newest);
+    }
+
     // ------------------------------------------------------------------
     // flatten_tree tests
     // ------------------------------------------------------------------
@@ -791,6 +1525,24 @@ mod tests {
         assert_eq!(rows[2].depth, 1);
     }
 
+    #[test]
+    fn test_flatten_team_rows() {
+        let sessions = vec![
+            make_team_session("a", "review", 0.5),
+            make_team_session("b", "review", 1.5),
+        ];
+        let tree = build_tree(&sessions);
+        let rows = flatten_tree(&tree, &all_node_ids(&tree));
+
+        // Project + Team + 2 agents
+        assert_eq!(rows.len(), 4);
+        assert!(matches!(&rows[1].kind, TreeRowKind::Team { name } if name == "review"));
+        assert_eq!(rows[1].depth, 1);
+        assert_eq!(rows[1].agent_count, 2);
+        assert!((rows[1].total_cost_usd - 2.0).abs() < f64::EPSILON);
+        assert_eq!(rows[2].depth, 2);
+    }
+
     #[test]
     fn test_flatten_with_worktrees() {
         let sessions = vec![
@@ -910,4 +1662,43 @@ mod tests {
         assert!(names.contains(&"Other"));
         assert!(names.contains(&"app"));
     }
+
+    #[test]
+    fn test_find_node_and_session_ids() {
+        let mut child =
+            make_session_in_project("child", "/repo", "/repo", "main", "2026-01-01T00:02:00Z");
+        child.parent_session_id = Some(SessionId::new("parent"));
+        let mut parent =
+            make_session_in_project("parent", "/repo", "/repo", "main", "2026-01-01T00:01:00Z");
+        parent.child_session_ids = vec![SessionId::new("child")];
+        let sessions = vec![
+            parent,
+            child,
+            make_session_in_project("other", "/repo", "/repo", "main", "2026-01-01T00:00:00Z"),
+        ];
+        let tree = build_tree(&sessions);
+
+        let project = find_node(&tree, &TreeNodeId::Project("/repo".to_string())).unwrap();
+        let mut ids = project.session_ids();
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        assert_eq!(
+            ids,
+            vec![
+                SessionId::new("child"),
+                SessionId::new("other"),
+                SessionId::new("parent")
+            ]
+        );
+
+        let parent = find_node(&tree, &TreeNodeId::Agent(SessionId::new("parent"))).unwrap();
+        assert_eq!(
+            parent.session_ids(),
+            vec![SessionId::new("parent"), SessionId::new("child")]
+        );
+        assert!(find_node(
+            &tree,
+            &TreeNodeId::Team("/nope".to_string(), "nope".to_string())
+        )
+        .is_none());
+    }
 }
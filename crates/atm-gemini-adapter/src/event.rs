@@ -0,0 +1,128 @@
+//! Message and tool-call vocabulary from Gemini CLI's chat recording.
+//!
+//! Gemini records messages rather than events. The message `type` and
+//! each tool call's `status` are what the translation layer dispatches
+//! on to reconstruct lifecycle transitions.
+
+use std::fmt;
+
+/// All GeminiMessageType variants paired with their string names.
+/// Single source of truth for string conversion.
+const MESSAGE_TYPE_VARIANTS: &[(GeminiMessageType, &str)] = &[
+    (GeminiMessageType::User, "user"),
+    (GeminiMessageType::Gemini, "gemini"),
+    (GeminiMessageType::Info, "info"),
+    (GeminiMessageType::Warning, "warning"),
+    (GeminiMessageType::Error, "error"),
+];
+
+/// Kind of a recorded message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GeminiMessageType {
+    /// Prompt typed by the user
+    User,
+    /// Model response; carries tool calls, token usage and model id
+    Gemini,
+    /// Informational message from the CLI itself
+    Info,
+    /// Warning from the CLI
+    Warning,
+    /// Error that ended the turn (API failure, quota, ...)
+    Error,
+}
+
+impl GeminiMessageType {
+    /// Returns the string name used in the session file.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        for (variant, name) in MESSAGE_TYPE_VARIANTS {
+            if variant == self {
+                return name;
+            }
+        }
+        // This is unreachable if MESSAGE_TYPE_VARIANTS is complete
+        "unknown"
+    }
+
+    /// Parses from the session file's `type` string.
+    #[must_use]
+    pub fn from_type_name(name: &str) -> Option<Self> {
+        MESSAGE_TYPE_VARIANTS
+            .iter()
+            .find(|(_, s)| *s == name)
+            .map(|(v, _)| *v)
+    }
+}
+
+impl fmt::Display for GeminiMessageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Lifecycle state of a recorded tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolCallStatus {
+    /// Arguments being validated
+    Validating,
+    /// Queued to run
+    Scheduled,
+    /// Waiting for the user to approve it
+    AwaitingApproval,
+    /// Running
+    Executing,
+    /// Finished successfully
+    Success,
+    /// Finished with an error
+    Error,
+    /// Cancelled by the user
+    Cancelled,
+}
+
+impl ToolCallStatus {
+    /// Parses from the session file's `status` string.
+    #[must_use]
+    pub fn from_status_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "validating" => Self::Validating,
+            "scheduled" => Self::Scheduled,
+            "awaiting_approval" => Self::AwaitingApproval,
+            "executing" => Self::Executing,
+            "success" => Self::Success,
+            "error" => Self::Error,
+            "cancelled" => Self::Cancelled,
+            _ => return None,
+        })
+    }
+
+    /// True once the call has finished, one way or another.
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Success | Self::Error | Self::Cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_types_roundtrip() {
+        for (variant, name) in MESSAGE_TYPE_VARIANTS {
+            assert_eq!(variant.as_str(), *name);
+            assert_eq!(GeminiMessageType::from_type_name(name), Some(*variant));
+        }
+        assert_eq!(GeminiMessageType::from_type_name("model"), None);
+    }
+
+    #[test]
+    fn test_tool_call_status_terminal() {
+        assert_eq!(
+            ToolCallStatus::from_status_name("awaiting_approval"),
+            Some(ToolCallStatus::AwaitingApproval)
+        );
+        assert!(ToolCallStatus::Cancelled.is_terminal());
+        assert!(!ToolCallStatus::Executing.is_terminal());
+        assert_eq!(ToolCallStatus::from_status_name("done"), None);
+    }
+}
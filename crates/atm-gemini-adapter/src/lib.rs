@@ -0,0 +1,37 @@
+//! Gemini CLI adapter for ATM.
+//!
+//! All Gemini-specific knowledge — the on-disk session format, where
+//! sessions live, and the translation into vendor-neutral
+//! `atm_core::LifecycleEvent` — lives in this crate. Symmetric with
+//! `atm_claude_adapter`, `atm_pi_adapter` and `atm_codex_adapter`.
+//!
+//! ## No hooks, so tail the transcript
+//!
+//! Gemini CLI has no hook or notify mechanism to push events to ATM.
+//! It does record every chat to
+//! `~/.gemini/tmp/<project-hash>/chats/session-*.json`, where
+//! `<project-hash>` is the SHA-256 of the project root. The file holds a
+//! single `ConversationRecord` that Gemini rewrites as the conversation
+//! progresses: new messages are appended, and the last model message is
+//! updated in place as its tool calls run and its token usage arrives.
+//!
+//! atmd polls that file for each running Gemini process and feeds each
+//! snapshot through a [`TranscriptCursor`], which remembers what it has
+//! already reported (by message and tool-call id) and returns only the
+//! new `LifecycleEvent`s.
+//!
+//! ## Layers
+//!
+//! - [`event`] — message and tool-call status vocabulary
+//! - [`wire`] — `ConversationRecord` and friends (the JSON on disk)
+//! - [`transcript`] — locating session files for a working directory
+//! - [`translate`] — `TranscriptCursor`, snapshot → `LifecycleEvent`s
+
+pub mod event;
+pub mod transcript;
+pub mod translate;
+pub mod wire;
+
+pub use event::{GeminiMessageType, ToolCallStatus};
+pub use translate::TranscriptCursor;
+pub use wire::ConversationRecord;
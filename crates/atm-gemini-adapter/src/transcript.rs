@@ -0,0 +1,91 @@
+//! Where Gemini CLI records sessions.
+//!
+//! Sessions for a project live in
+//! `~/.gemini/tmp/<sha256(project root)>/chats/session-<time>-<id>.json`.
+//! Gemini takes the project root from the directory it was started in,
+//! which is what `/proc/<pid>/cwd` reports for the running process.
+//!
+//! This module only performs blocking filesystem reads; async callers
+//! should run it via `spawn_blocking`.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+
+/// Gemini CLI's per-user directory (`~/.gemini`).
+pub fn gemini_home() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(|home| PathBuf::from(home).join(".gemini"))
+}
+
+/// Gemini's project hash: hex SHA-256 of the project root path.
+pub fn project_hash(project_root: &Path) -> String {
+    let digest = Sha256::digest(project_root.as_os_str().as_encoded_bytes());
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Directory holding the recorded sessions for `project_root`.
+pub fn chats_dir(gemini_home: &Path, project_root: &Path) -> PathBuf {
+    gemini_home
+        .join("tmp")
+        .join(project_hash(project_root))
+        .join("chats")
+}
+
+/// Returns the most recently written session file in `chats_dir` and its
+/// modification time.
+///
+/// Gemini starts a new file for every session (including `/chat resume`d
+/// ones), so the newest file belongs to the conversation currently in
+/// progress.
+pub fn latest_session_file(chats_dir: &Path) -> Option<(PathBuf, SystemTime)> {
+    std::fs::read_dir(chats_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_name()?.to_str()?;
+            if !name.starts_with("session-") || !name.ends_with(".json") {
+                return None;
+            }
+            let mtime = entry.metadata().ok()?.modified().ok()?;
+            Some((path, mtime))
+        })
+        .max_by_key(|(_, mtime)| *mtime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_hash_is_sha256_of_path() {
+        // echo -n /home/user/project | sha256sum
+        assert_eq!(
+            project_hash(Path::new("/home/user/project")),
+            "9dad1e4e08b0b11cbcd860257e8bdfa6b8e5f01790e10a6a0b1f4870c13e686b"
+        );
+        assert_eq!(
+            chats_dir(Path::new("/home/user/.gemini"), Path::new("/home/user/project")),
+            PathBuf::from(
+                "/home/user/.gemini/tmp/9dad1e4e08b0b11cbcd860257e8bdfa6b8e5f01790e10a6a0b1f4870c13e686b/chats"
+            )
+        );
+    }
+
+    #[test]
+    fn test_latest_session_file_ignores_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(latest_session_file(dir.path()).is_none());
+
+        let session = dir.path().join("session-2026-10-16T09-00-5e0d7a52.json");
+        std::fs::write(&session, "{}").unwrap();
+        std::fs::write(dir.path().join("checkpoint-main.json"), "{}").unwrap();
+
+        let (found, _) = latest_session_file(dir.path()).unwrap();
+        assert_eq!(found, session);
+        assert!(latest_session_file(&dir.path().join("missing")).is_none());
+    }
+}
@@ -0,0 +1,432 @@
+//! Translation from Gemini session snapshots to vendor-neutral
+//! `LifecycleEvent`s.
+//!
+//! Gemini records state, not events, so translation is a diff: a
+//! [`TranscriptCursor`] remembers which prompts, tool calls and token
+//! figures it has already reported and emits events only for what
+//! changed since the previous snapshot.
+//!
+//! ## Mapping table
+//!
+//! | Session file change                          | LifecycleEvent                                  |
+//! |----------------------------------------------|-------------------------------------------------|
+//! | new `user` message                           | `PromptSubmit { prompt }`                       |
+//! | new `model` on a `gemini` message            | `ProviderModelChange { provider: "google" }`    |
+//! | tool call first seen                         | `ToolCallStart { name, tool_use_id, input }`    |
+//! | tool call `awaiting_approval`                | `NeedsInput { Notification(PermissionPrompt) }` |
+//! | tool call `success`/`error`/`cancelled`      | `ToolCallEnd { is_error }`                      |
+//! | `tokens` added or updated                    | `ContextUpdate { tokens, cost_usd }`            |
+//! | last message is a finished text-only reply   | `WorkingEnd`                                    |
+//! | new `error` message                          | `WorkingEnd`                                    |
+//!
+//! `ContextUpdate.tokens` is the latest response's prompt size (the
+//! context currently in use); `cost_usd` is the session's cumulative
+//! estimate from the `atm_core` model catalog, or `None` for models it
+//! has no prices for.
+
+use std::collections::{HashMap, HashSet};
+
+use atm_core::{LifecycleEvent, Model, Money, NeedsInputReason, NotificationKind, Tool};
+
+use crate::event::{GeminiMessageType, ToolCallStatus};
+use crate::wire::{ConversationRecord, MessageRecord, ToolCallRecord};
+
+/// Provider reported with Gemini model changes.
+const PROVIDER: &str = "google";
+
+/// Tracks what has been reported for one session file.
+///
+/// Use one cursor per file; a new session file (a new Gemini session)
+/// needs a fresh cursor.
+#[derive(Debug, Default)]
+pub struct TranscriptCursor {
+    prompts: HashSet<String>,
+    started_calls: HashSet<String>,
+    approval_calls: HashSet<String>,
+    ended_calls: HashSet<String>,
+    /// Messages whose turn end has been reported.
+    finished: HashSet<String>,
+    /// Cost counted so far per message; token usage is filled in (and
+    /// occasionally revised) after the message is first written.
+    message_costs: HashMap<String, Money>,
+    model: Option<String>,
+    context_tokens: Option<u64>,
+    reported_context: Option<(Option<u64>, Option<f64>)>,
+}
+
+impl TranscriptCursor {
+    /// Creates a cursor that has reported nothing yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the events for everything that changed since the last call.
+    pub fn advance(&mut self, record: &ConversationRecord) -> Vec<LifecycleEvent> {
+        let mut events = Vec::new();
+        for message in &record.messages {
+            self.advance_message(message, &mut events);
+        }
+
+        let context = (self.context_tokens, self.cost_usd());
+        if context.0.is_some() && self.reported_context != Some(context) {
+            self.reported_context = Some(context);
+            events.push(LifecycleEvent::ContextUpdate {
+                tokens: context.0,
+                cost_usd: context.1,
+            });
+        }
+
+        if let Some(last) = record.messages.last() {
+            if ends_turn(last) && self.finished.insert(last.id.clone()) {
+                events.push(LifecycleEvent::WorkingEnd);
+            }
+        }
+        events
+    }
+
+    /// Like [`Self::advance`], but for a session that was already under
+    /// way when ATM started watching it.
+    ///
+    /// Replaying the whole history would flash every old tool call
+    /// through the dashboard, so only the events that describe the
+    /// current state survive: the first prompt (the session's title),
+    /// the latest model and context figures, and the most recent status
+    /// transition.
+    pub fn catch_up(&mut self, record: &ConversationRecord) -> Vec<LifecycleEvent> {
+        let events = self.advance(record);
+
+        let first_prompt = events
+            .iter()
+            .find(|e| matches!(e, LifecycleEvent::PromptSubmit { .. }));
+        let model = events
+            .iter()
+            .rfind(|e| matches!(e, LifecycleEvent::ProviderModelChange { .. }));
+        let context = events
+            .iter()
+            .rfind(|e| matches!(e, LifecycleEvent::ContextUpdate { .. }));
+        let status = events.iter().rfind(|e| {
+            !matches!(
+                e,
+                LifecycleEvent::ProviderModelChange { .. } | LifecycleEvent::ContextUpdate { .. }
+            )
+        });
+
+        let mut current: Vec<LifecycleEvent> = Vec::new();
+        for event in [first_prompt, model, context, status].into_iter().flatten() {
+            if !current.contains(event) {
+                current.push(event.clone());
+            }
+        }
+        current
+    }
+
+    /// Cumulative estimated cost, if any priced response has been seen.
+    fn cost_usd(&self) -> Option<f64> {
+        if self.message_costs.is_empty() {
+            None
+        } else {
+            let total = self
+                .message_costs
+                .values()
+                .fold(Money::zero(), |sum, cost| sum + *cost);
+            Some(total.as_usd())
+        }
+    }
+
+    fn advance_message(&mut self, message: &MessageRecord, events: &mut Vec<LifecycleEvent>) {
+        match message.kind() {
+            Some(GeminiMessageType::User) => {
+                if self.prompts.insert(message.id.clone()) {
+                    events.push(LifecycleEvent::PromptSubmit {
+                        prompt: message.text(),
+                    });
+                }
+            }
+            Some(GeminiMessageType::Gemini) => {
+                if let Some(model) = message.model.as_deref().filter(|m| !m.is_empty()) {
+                    if self.model.as_deref() != Some(model) {
+                        self.model = Some(model.to_string());
+                        events.push(LifecycleEvent::ProviderModelChange {
+                            provider: Some(PROVIDER.to_string()),
+                            model: Some(model.to_string()),
+                        });
+                    }
+                }
+                for call in &message.tool_calls {
+                    self.advance_tool_call(call, events);
+                }
+                if let Some(tokens) = &message.tokens {
+                    self.context_tokens = Some(tokens.input);
+                    let cost = message
+                        .model
+                        .as_deref()
+                        .and_then(|id| catalog_model(id).estimate_cost(&tokens.usage()));
+                    if let Some(cost) = cost {
+                        self.message_costs.insert(message.id.clone(), cost);
+                    }
+                }
+            }
+            Some(GeminiMessageType::Error) => {
+                if self.finished.insert(message.id.clone()) {
+                    events.push(LifecycleEvent::WorkingEnd);
+                }
+            }
+            // Info and warnings are CLI chatter (e.g. "Switched to
+            // fallback model"); nothing the dashboard shows.
+            Some(GeminiMessageType::Info | GeminiMessageType::Warning) | None => {}
+        }
+    }
+
+    fn advance_tool_call(&mut self, call: &ToolCallRecord, events: &mut Vec<LifecycleEvent>) {
+        if call.name.is_empty() {
+            return;
+        }
+        let name = tool_for(&call.name);
+
+        if self.started_calls.insert(call.id.clone()) {
+            events.push(LifecycleEvent::ToolCallStart {
+                name: name.clone(),
+                tool_use_id: Some(call.id.clone()),
+                input: call.args.clone(),
+            });
+        }
+
+        match call.status() {
+            Some(ToolCallStatus::AwaitingApproval) if !self.approval_calls.contains(&call.id) => {
+                self.approval_calls.insert(call.id.clone());
+                events.push(LifecycleEvent::NeedsInput {
+                    reason: NeedsInputReason::Notification {
+                        kind: NotificationKind::PermissionPrompt,
+                        label: Some(
+                            call.display_name
+                                .clone()
+                                .unwrap_or_else(|| call.name.clone()),
+                        ),
+                    },
+                    prompt: None,
+                });
+            }
+            Some(status) if status.is_terminal() && !self.ended_calls.contains(&call.id) => {
+                self.ended_calls.insert(call.id.clone());
+                events.push(LifecycleEvent::ToolCallEnd {
+                    name,
+                    tool_use_id: Some(call.id.clone()),
+                    is_error: status == ToolCallStatus::Error,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// True if `message` is a complete model reply that leaves nothing to do.
+///
+/// A reply with successful tool calls is followed by another model call
+/// with the results, so the turn only ends on a text-only reply (token
+/// usage present means it finished streaming) or one whose tool calls
+/// were all cancelled.
+fn ends_turn(message: &MessageRecord) -> bool {
+    if message.kind() != Some(GeminiMessageType::Gemini) || message.tokens.is_none() {
+        return false;
+    }
+    message
+        .tool_calls
+        .iter()
+        .all(|call| call.status() == Some(ToolCallStatus::Cancelled))
+}
+
+/// Looks up a Gemini model id, which is sometimes recorded as
+/// `models/gemini-2.5-pro`, in the model catalog.
+fn catalog_model(id: &str) -> Model {
+    Model::from_id(id.strip_prefix("models/").unwrap_or(id))
+}
+
+/// Maps Gemini's built-in tool names onto the well-known `Tool` variants.
+fn tool_for(name: &str) -> Tool {
+    match name {
+        "run_shell_command" => Tool::Bash,
+        "read_file" | "read_many_files" => Tool::Read,
+        "write_file" => Tool::Write,
+        "replace" => Tool::Edit,
+        "search_file_content" => Tool::Grep,
+        "glob" => Tool::Glob,
+        "google_web_search" => Tool::WebSearch,
+        "web_fetch" => Tool::WebFetch,
+        "write_todos" => Tool::TodoWrite,
+        other => Tool::from(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(messages: serde_json::Value) -> ConversationRecord {
+        serde_json::from_value(serde_json::json!({
+            "sessionId": "s-1",
+            "messages": messages,
+        }))
+        .unwrap()
+    }
+
+    fn user(id: &str, text: &str) -> serde_json::Value {
+        serde_json::json!({"id": id, "type": "user", "content": [{"text": text}]})
+    }
+
+    #[test]
+    fn prompt_is_reported_once() {
+        let mut cursor = TranscriptCursor::new();
+        let snapshot = record(serde_json::json!([user("m1", "fix the tests")]));
+
+        assert_eq!(
+            cursor.advance(&snapshot),
+            vec![LifecycleEvent::PromptSubmit {
+                prompt: Some("fix the tests".into())
+            }]
+        );
+        assert!(cursor.advance(&snapshot).is_empty());
+    }
+
+    #[test]
+    fn tool_call_progresses_through_start_approval_and_end() {
+        let mut cursor = TranscriptCursor::new();
+        let call = |status: &str| {
+            record(serde_json::json!([{
+                "id": "m2", "type": "gemini", "content": "",
+                "toolCalls": [{"id": "c1", "name": "run_shell_command",
+                               "args": {"command": "cargo test"}, "status": status}]
+            }]))
+        };
+
+        let events = cursor.advance(&call("awaiting_approval"));
+        assert_eq!(
+            events,
+            vec![
+                LifecycleEvent::ToolCallStart {
+                    name: Tool::Bash,
+                    tool_use_id: Some("c1".into()),
+                    input: Some(serde_json::json!({"command": "cargo test"})),
+                },
+                LifecycleEvent::NeedsInput {
+                    reason: NeedsInputReason::Notification {
+                        kind: NotificationKind::PermissionPrompt,
+                        label: Some("run_shell_command".into()),
+                    },
+                    prompt: None,
+                },
+            ]
+        );
+
+        assert!(cursor.advance(&call("executing")).is_empty());
+        assert_eq!(
+            cursor.advance(&call("error")),
+            vec![LifecycleEvent::ToolCallEnd {
+                name: Tool::Bash,
+                tool_use_id: Some("c1".into()),
+                is_error: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn finished_text_reply_ends_turn_with_context_and_cost() {
+        let mut cursor = TranscriptCursor::new();
+        let snapshot = record(serde_json::json!([
+            user("m1", "hi"),
+            {"id": "m2", "type": "gemini", "content": "Hello!", "model": "gemini-2.5-flash",
+             "tokens": {"input": 1_000_000, "output": 0, "cached": 0, "thoughts": 0, "total": 1_000_000}}
+        ]));
+
+        let events = cursor.advance(&snapshot);
+        assert_eq!(
+            events,
+            vec![
+                LifecycleEvent::PromptSubmit {
+                    prompt: Some("hi".into())
+                },
+                LifecycleEvent::ProviderModelChange {
+                    provider: Some("google".into()),
+                    model: Some("gemini-2.5-flash".into()),
+                },
+                LifecycleEvent::ContextUpdate {
+                    tokens: Some(1_000_000),
+                    cost_usd: Some(0.30),
+                },
+                LifecycleEvent::WorkingEnd,
+            ]
+        );
+        assert!(cursor.advance(&snapshot).is_empty());
+    }
+
+    #[test]
+    fn reply_with_completed_tools_keeps_working() {
+        let mut cursor = TranscriptCursor::new();
+        let snapshot = record(serde_json::json!([{
+            "id": "m2", "type": "gemini", "content": "", "model": "unpriced-model",
+            "tokens": {"input": 500, "output": 20, "total": 520},
+            "toolCalls": [{"id": "c1", "name": "read_file", "status": "success"}]
+        }]));
+
+        let events = cursor.advance(&snapshot);
+        assert!(!events.contains(&LifecycleEvent::WorkingEnd));
+        assert!(events.contains(&LifecycleEvent::ContextUpdate {
+            tokens: Some(500),
+            cost_usd: None,
+        }));
+    }
+
+    #[test]
+    fn error_message_ends_turn() {
+        let mut cursor = TranscriptCursor::new();
+        let snapshot = record(serde_json::json!([
+            user("m1", "hi"),
+            {"id": "m2", "type": "error", "content": "Quota exceeded"}
+        ]));
+        assert_eq!(
+            cursor.advance(&snapshot).last(),
+            Some(&LifecycleEvent::WorkingEnd)
+        );
+    }
+
+    #[test]
+    fn catch_up_reports_current_state_only() {
+        let mut cursor = TranscriptCursor::new();
+        let snapshot = record(serde_json::json!([
+            user("m1", "first prompt"),
+            {"id": "m2", "type": "gemini", "content": "done", "model": "gemini-2.5-pro",
+             "tokens": {"input": 1000, "output": 10, "total": 1010}},
+            user("m3", "second prompt"),
+            {"id": "m4", "type": "gemini", "content": "", "model": "gemini-2.5-pro",
+             "toolCalls": [
+                 {"id": "c1", "name": "glob", "status": "success"},
+                 {"id": "c2", "name": "replace", "status": "executing"}
+             ]}
+        ]));
+
+        let events = cursor.catch_up(&snapshot);
+        assert_eq!(
+            events,
+            vec![
+                LifecycleEvent::PromptSubmit {
+                    prompt: Some("first prompt".into())
+                },
+                LifecycleEvent::ProviderModelChange {
+                    provider: Some("google".into()),
+                    model: Some("gemini-2.5-pro".into()),
+                },
+                LifecycleEvent::ContextUpdate {
+                    tokens: Some(1000),
+                    // 1000 * $1.25 + 10 * $10, per million
+                    cost_usd: Some(0.00135),
+                },
+                LifecycleEvent::ToolCallStart {
+                    name: Tool::Edit,
+                    tool_use_id: Some("c2".into()),
+                    input: None,
+                },
+            ]
+        );
+        // Everything is now marked as seen
+        assert!(cursor.advance(&snapshot).is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+//! Gemini CLI session file (`chats/session-*.json`).
+//!
+//! Mirrors the `ConversationRecord` that Gemini CLI's chat recording
+//! service writes. Unknown fields are ignored and everything ATM does
+//! not strictly need is optional, so format drift in Gemini degrades to
+//! missing data rather than a parse failure.
+
+use atm_core::{SessionId, TokenUsage};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::event::{GeminiMessageType, ToolCallStatus};
+
+/// One recorded Gemini CLI session.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationRecord {
+    /// Gemini's session UUID; ATM's session id.
+    pub session_id: String,
+    #[serde(default)]
+    pub project_hash: Option<String>,
+    #[serde(default)]
+    pub start_time: Option<String>,
+    #[serde(default)]
+    pub last_updated: Option<String>,
+    #[serde(default)]
+    pub messages: Vec<MessageRecord>,
+}
+
+impl ConversationRecord {
+    /// Parses a session file's contents.
+    pub fn from_json(content: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(content)
+    }
+
+    /// Returns the ATM session id for this conversation.
+    pub fn atm_session_id(&self) -> SessionId {
+        SessionId::new(&self.session_id)
+    }
+}
+
+/// One message in the conversation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageRecord {
+    pub id: String,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    /// `user`, `gemini`, `info`, `warning` or `error`.
+    #[serde(rename = "type")]
+    pub message_type: String,
+    /// Gemini `PartListUnion`: a string, a part, or a list of parts.
+    #[serde(default)]
+    pub content: Value,
+
+    // === Model responses only ===
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallRecord>,
+    #[serde(default)]
+    pub tokens: Option<TokensSummary>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl MessageRecord {
+    /// Parses the message type.
+    pub fn kind(&self) -> Option<GeminiMessageType> {
+        GeminiMessageType::from_type_name(&self.message_type)
+    }
+
+    /// Returns the message's text parts joined with newlines, if any.
+    pub fn text(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        collect_text(&self.content, &mut parts);
+        let text = parts.join("\n");
+        if text.is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    }
+}
+
+/// Gathers `text` from a `PartListUnion` (string, `{text}` part, or list).
+fn collect_text(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => out.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|item| collect_text(item, out)),
+        Value::Object(part) => {
+            if let Some(Value::String(s)) = part.get("text") {
+                out.push(s.clone());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A tool call made by the model within one response.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallRecord {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub args: Option<Value>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+impl ToolCallRecord {
+    /// Parses the call's status.
+    pub fn status(&self) -> Option<ToolCallStatus> {
+        self.status
+            .as_deref()
+            .and_then(ToolCallStatus::from_status_name)
+    }
+}
+
+/// Token usage for one model response (Gemini `usageMetadata`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct TokensSummary {
+    /// Prompt tokens, including cached ones; the context in use.
+    #[serde(default)]
+    pub input: u64,
+    /// Candidate (response) tokens.
+    #[serde(default)]
+    pub output: u64,
+    /// Prompt tokens served from the context cache.
+    #[serde(default)]
+    pub cached: u64,
+    /// Thinking tokens; billed as output.
+    #[serde(default)]
+    pub thoughts: u64,
+    /// Tool-use prompt tokens.
+    #[serde(default)]
+    pub tool: u64,
+    #[serde(default)]
+    pub total: u64,
+}
+
+impl TokensSummary {
+    /// Splits the counts the way Gemini bills them: cached prompt tokens
+    /// at the cache rate, thinking tokens as output.
+    pub fn usage(&self) -> TokenUsage {
+        let cached = self.cached.min(self.input);
+        TokenUsage {
+            input: self.input - cached,
+            output: self.output.saturating_add(self.thoughts),
+            cache_read: cached,
+            cache_write: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION: &str = r#"{
+        "sessionId": "5e0d7a52-8f3b-4d7e-9a51-0c4f5b1e2d3a",
+        "projectHash": "abc123",
+        "startTime": "2026-10-16T09:00:00.000Z",
+        "lastUpdated": "2026-10-16T09:00:05.000Z",
+        "messages": [
+            {"id": "m1", "timestamp": "2026-10-16T09:00:00.000Z", "type": "user",
+             "content": [{"text": "list the files"}]},
+            {"id": "m2", "timestamp": "2026-10-16T09:00:03.000Z", "type": "gemini",
+             "content": "", "model": "gemini-2.5-pro",
+             "tokens": {"input": 9000, "output": 120, "cached": 4000, "thoughts": 80, "tool": 0, "total": 9200},
+             "toolCalls": [{"id": "call-1", "name": "list_directory", "args": {"path": "."},
+                            "status": "success", "timestamp": "2026-10-16T09:00:04.000Z",
+                            "displayName": "ReadFolder", "resultDisplay": "Listed 3 item(s)."}]}
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_session_file() {
+        let record = ConversationRecord::from_json(SESSION).unwrap();
+        assert_eq!(
+            record.atm_session_id(),
+            SessionId::new("5e0d7a52-8f3b-4d7e-9a51-0c4f5b1e2d3a")
+        );
+        assert_eq!(record.messages.len(), 2);
+
+        let user = &record.messages[0];
+        assert_eq!(user.kind(), Some(GeminiMessageType::User));
+        assert_eq!(user.text().as_deref(), Some("list the files"));
+
+        let reply = &record.messages[1];
+        assert_eq!(reply.kind(), Some(GeminiMessageType::Gemini));
+        assert_eq!(reply.text(), None);
+        assert_eq!(reply.tokens.map(|t| t.input), Some(9000));
+        assert_eq!(reply.tool_calls[0].status(), Some(ToolCallStatus::Success));
+    }
+
+    #[test]
+    fn test_usage_splits_cached_and_thinking_tokens() {
+        let tokens = TokensSummary {
+            input: 1_000_000,
+            cached: 400_000,
+            output: 50_000,
+            thoughts: 50_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            tokens.usage(),
+            TokenUsage {
+                input: 600_000,
+                output: 100_000,
+                cache_read: 400_000,
+                cache_write: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_plain_string_content() {
+        let message: MessageRecord =
+            serde_json::from_str(r#"{"id": "m1", "type": "user", "content": "hello"}"#).unwrap();
+        assert_eq!(message.text().as_deref(), Some("hello"));
+        assert!(message.tool_calls.is_empty());
+    }
+}
@@ -19,7 +19,7 @@ fn fresh_session() -> SessionDomain {
     SessionDomain::new(
         SessionId::new("demo"),
         AgentType::GeneralPurpose,
-        Model::Sonnet4,
+        Model::from_id("claude-sonnet-4"),
     )
 }
 
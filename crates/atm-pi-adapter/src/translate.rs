@@ -35,7 +35,7 @@
 //! - The other two return `None` from translation, so the daemon
 //!   ignores them.
 
-use atm_core::{LifecycleEvent, NeedsInputReason, Tool};
+use atm_core::{LifecycleEvent, NeedsInputReason, PromptKind, PromptOption, PromptState, Tool};
 use serde_json::Value;
 
 use crate::event::PiEventType;
@@ -99,6 +99,23 @@ fn extract_context_usage(messages: Option<&Value>) -> (Option<u64>, Option<f64>)
     (None, None)
 }
 
+/// Structured prompt for a `ui.select` dialog from its title and
+/// choices. `None` when the extension sent neither.
+fn select_prompt(title: Option<&str>, options: Option<&[String]>) -> Option<PromptState> {
+    if title.is_none() && options.is_none() {
+        return None;
+    }
+    Some(PromptState::from_hook(
+        PromptKind::Permission,
+        title.map(str::to_string),
+        options
+            .unwrap_or_default()
+            .iter()
+            .map(PromptOption::new)
+            .collect(),
+    ))
+}
+
 impl RawPiEvent {
     /// Translates this pi raw event into a vendor-neutral
     /// `LifecycleEvent`.
@@ -116,7 +133,7 @@ impl RawPiEvent {
     ///
     /// 1. **Synthetic events (used by `pi-atm`).** The pi-atm
     ///    extension wraps `ctx.ui.select` and emits
-    ///    `atm_needs_input_open{title}` / `atm_needs_input_resolved`
+    ///    `atm_needs_input_open{title, options}` / `atm_needs_input_resolved`
     ///    around the dialog — translated to
     ///    `NeedsInputReason::Notification{ kind: PermissionPrompt,
     ///    label }` on open and `LifecycleEvent::WorkingStart` on
@@ -161,6 +178,7 @@ impl RawPiEvent {
                 let tool = Tool::from(tool_name);
                 if p.needs_user_input.unwrap_or(false) {
                     LifecycleEvent::NeedsInput {
+                        prompt: Some(PromptState::permission(&tool, p.input.as_ref())),
                         reason: NeedsInputReason::PermissionGate { tool },
                     }
                 } else {
@@ -209,6 +227,9 @@ impl RawPiEvent {
                     // that *something* is.
                     label: p.title.clone(),
                 },
+                // pi-atm also forwards the choices offered, which is
+                // everything a structured prompt needs.
+                prompt: select_prompt(p.title.as_deref(), p.options.as_deref()),
             },
             // When the dialog closes, pi resumes work. WorkingStart
             // transitions out of NeedsInput; the next agent_end /
@@ -265,6 +286,7 @@ mod tests {
             session_id: None,
             pid: None,
             tmux_pane: None,
+            team: None,
         }
     }
 
@@ -280,7 +302,8 @@ mod tests {
                 reason: atm_core::NeedsInputReason::Notification {
                     kind: atm_core::NotificationKind::PermissionPrompt,
                     label: None,
-                }
+                },
+                prompt: None,
             })
         );
     }
@@ -301,11 +324,38 @@ mod tests {
                 reason: atm_core::NeedsInputReason::Notification {
                     kind: atm_core::NotificationKind::PermissionPrompt,
                     label: Some("Allow `rm -rf /tmp/cache`?".to_string()),
-                }
+                },
+                prompt: Some(PromptState::from_hook(
+                    PromptKind::Permission,
+                    Some("Allow `rm -rf /tmp/cache`?".to_string()),
+                    Vec::new(),
+                )),
             })
         );
     }
 
+    #[test]
+    fn atm_needs_input_open_with_options_builds_prompt() {
+        let payload: PiPayload = serde_json::from_value(serde_json::json!({
+            "title": "Run `make deploy`?",
+            "options": ["Allow", "Allow always", "Deny"]
+        }))
+        .unwrap();
+        let e = raw(PiEventType::AtmNeedsInputOpen, payload);
+        match e.to_lifecycle_event() {
+            Some(LifecycleEvent::NeedsInput {
+                prompt: Some(prompt),
+                ..
+            }) => {
+                assert_eq!(prompt.question.as_deref(), Some("Run `make deploy`?"));
+                let labels: Vec<&str> = prompt.options.iter().map(|o| o.label.as_str()).collect();
+                assert_eq!(labels, vec!["Allow", "Allow always", "Deny"]);
+                assert_eq!(prompt.selected, Some(1));
+            }
+            other => panic!("expected NeedsInput with a prompt, got {other:?}"),
+        }
+    }
+
     #[test]
     fn context_event_extracts_latest_usage_into_context_update() {
         // Real-shape payload mirroring the spike's
@@ -524,7 +574,8 @@ mod tests {
             Some(LifecycleEvent::NeedsInput {
                 reason: NeedsInputReason::PermissionGate {
                     tool: Tool::Other("bash".into())
-                }
+                },
+                prompt: Some(PromptState::permission(&Tool::Other("bash".into()), None)),
             })
         );
     }
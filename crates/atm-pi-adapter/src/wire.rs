@@ -37,6 +37,9 @@ pub struct RawPiEvent {
     /// Tmux pane id when running inside tmux.
     #[serde(default)]
     pub tmux_pane: Option<String>,
+    /// Team from `ATM_TEAM`, when pi was spawned into one.
+    #[serde(default)]
+    pub team: Option<String>,
 }
 
 /// Pi event payload — fields vary per event type.
@@ -99,6 +102,9 @@ pub struct PiPayload {
     /// surfaces in the TUI's activity-detail field.
     #[serde(default)]
     pub title: Option<String>,
+    /// Choices offered by the dialog, in display order.
+    #[serde(default)]
+    pub options: Option<Vec<String>>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,205 @@
+//! Subscription filters evaluated by the daemon.
+//!
+//! A `Subscribe` message may carry a [`SubscriptionFilter`]; the daemon then
+//! only forwards sessions that match it, so narrow clients (a per-workspace
+//! sidebar, a status-bar widget) don't receive traffic they would discard.
+//!
+//! Every criterion that is set must match. An empty filter matches every
+//! session.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Slice access uses `.get()`
+
+use atm_core::{SessionStatus, SessionView};
+use serde::{Deserialize, Serialize};
+
+/// Criteria a session must meet to be sent to a subscriber.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionFilter {
+    /// Glob matched against the session's project root (falling back to its
+    /// working directory). `*` matches any run of characters, `?` one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_root: Option<String>,
+
+    /// Harness tag, e.g. `"claude"` or `"pi"` (case-insensitive).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub harness: Option<String>,
+
+    /// Accepted statuses; empty accepts any status.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub status: Vec<SessionStatus>,
+
+    /// Name of the tmux session the agent's pane must belong to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmux_session: Option<String>,
+}
+
+impl SubscriptionFilter {
+    /// Returns true if no criteria are set.
+    pub fn is_empty(&self) -> bool {
+        self.project_root.is_none()
+            && self.harness.is_none()
+            && self.status.is_empty()
+            && self.tmux_session.is_none()
+    }
+
+    /// Returns true if evaluating this filter needs the session's tmux
+    /// session name.
+    pub fn needs_tmux_session(&self) -> bool {
+        self.tmux_session.is_some()
+    }
+
+    /// Returns true if `session` satisfies every criterion.
+    ///
+    /// `tmux_session` is the name of the tmux session owning the view's
+    /// pane, as resolved by the caller (`None` if unknown or not in tmux).
+    pub fn matches(&self, session: &SessionView, tmux_session: Option<&str>) -> bool {
+        if let Some(ref pattern) = self.project_root {
+            let root = session
+                .project_root
+                .as_deref()
+                .or(session.working_directory.as_deref());
+            if !root.is_some_and(|r| glob_match(pattern, r)) {
+                return false;
+            }
+        }
+
+        if let Some(ref harness) = self.harness {
+            if !session.harness.eq_ignore_ascii_case(harness) {
+                return false;
+            }
+        }
+
+        if !self.status.is_empty() && !self.status.contains(&session.status) {
+            return false;
+        }
+
+        if let Some(ref wanted) = self.tmux_session {
+            if tmux_session != Some(wanted.as_str()) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Matches `text` against a glob `pattern`.
+///
+/// Supports `*` (any run of characters, including `/`) and `?` (exactly one
+/// character). Everything else matches literally.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` seen and the text index it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || Some(&c) == text.get(t) => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    // Let the last `*` swallow one more character
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern
+        .get(p..)
+        .is_some_and(|rest| rest.iter().all(|&c| c == '*'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atm_core::{AgentType, Model, SessionDomain, SessionId};
+
+    fn view(project_root: Option<&str>, status: SessionStatus) -> SessionView {
+        let mut domain = SessionDomain::new(
+            SessionId::new("filter-test"),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-sonnet-4"),
+        );
+        domain.project_root = project_root.map(String::from);
+        domain.status = status;
+        domain.harness = atm_core::Harness::Pi;
+        SessionView::from_domain(&domain)
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("/home/*/src/atm", "/home/me/src/atm"));
+        assert!(glob_match("*/atm", "/home/me/src/atm"));
+        assert!(glob_match("/home/me/*", "/home/me/src/atm"));
+        assert!(glob_match("/srv/app-?", "/srv/app-1"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("/srv/app-?", "/srv/app-10"));
+        assert!(!glob_match("/home/*/web", "/home/me/src/atm"));
+        assert!(!glob_match("", "x"));
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = SubscriptionFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matches(&view(None, SessionStatus::Idle), None));
+    }
+
+    #[test]
+    fn test_project_root_glob() {
+        let filter = SubscriptionFilter {
+            project_root: Some("/work/*".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&view(Some("/work/api"), SessionStatus::Idle), None));
+        assert!(!filter.matches(&view(Some("/home/api"), SessionStatus::Idle), None));
+        assert!(!filter.matches(&view(None, SessionStatus::Idle), None));
+    }
+
+    #[test]
+    fn test_harness_status_and_tmux_session() {
+        let filter = SubscriptionFilter {
+            harness: Some("PI".to_string()),
+            status: vec![SessionStatus::Working, SessionStatus::AttentionNeeded],
+            tmux_session: Some("dev".to_string()),
+            ..Default::default()
+        };
+        let working = view(None, SessionStatus::Working);
+
+        assert!(filter.needs_tmux_session());
+        assert!(filter.matches(&working, Some("dev")));
+        assert!(!filter.matches(&working, Some("other")));
+        assert!(!filter.matches(&working, None));
+        assert!(!filter.matches(&view(None, SessionStatus::Idle), Some("dev")));
+    }
+
+    #[test]
+    fn test_filter_serialization_skips_unset_fields() {
+        let filter = SubscriptionFilter {
+            status: vec![SessionStatus::Working],
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(json, r#"{"status":["working"]}"#);
+
+        let parsed: SubscriptionFilter = serde_json::from_str("{}").unwrap();
+        assert!(parsed.is_empty());
+    }
+}
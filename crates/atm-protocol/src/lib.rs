@@ -3,13 +3,18 @@
 //! This crate provides vendor-neutral message types and parsing for
 //! communication between hook/extension scripts and the daemon, and
 //! between the daemon and TUI clients. Vendor-specific wire payloads
-//! (Claude `RawHookEvent`, pi `RawPiEvent`) live in their respective
-//! adapter crates.
+//! (Claude `RawHookEvent`, pi `RawPiEvent`, Codex `RawCodexEvent`) live in
+//! their respective adapter crates.
 
+pub mod filter;
 pub mod message;
 pub mod parse;
+pub mod patch;
+pub mod socket;
 pub mod version;
 
-pub use message::{ClientMessage, DaemonMessage, MessageType};
+pub use filter::SubscriptionFilter;
+pub use message::{ClientMessage, ControlAction, DaemonMessage, MessageType, ReplyChoice};
 pub use parse::{RawContextWindow, RawCost, RawModel, RawStatusLine, RawWorkspace};
+pub use patch::{apply_session_patch, diff_session_views, PatchError};
 pub use version::ProtocolVersion;
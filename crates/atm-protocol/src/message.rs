@@ -1,5 +1,6 @@
 //! Protocol message types for daemon communication.
 
+use crate::filter::SubscriptionFilter;
 use crate::version::ProtocolVersion;
 use atm_core::{SessionId, SessionView};
 use serde::{Deserialize, Serialize};
@@ -13,6 +14,10 @@ pub enum MessageType {
         /// Client identifier (optional)
         #[serde(skip_serializing_if = "Option::is_none")]
         client_id: Option<String>,
+        /// Access token; required on remote (TCP/WebSocket) listeners,
+        /// ignored on the local Unix socket
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
     },
 
     /// Status line update from Claude Code
@@ -36,6 +41,13 @@ pub enum MessageType {
         data: serde_json::Value,
     },
 
+    /// Event from Codex CLI's `notify` program (raw `RawCodexEvent`
+    /// payload). Translated by `atm-codex-adapter` at the daemon boundary.
+    CodexEvent {
+        /// The raw Codex event JSON (to be parsed)
+        data: serde_json::Value,
+    },
+
     /// Request current session list
     ListSessions,
 
@@ -44,6 +56,9 @@ pub enum MessageType {
         /// Optional filter by session ID
         #[serde(skip_serializing_if = "Option::is_none")]
         session_id: Option<SessionId>,
+        /// Only receive sessions matching these criteria
+        #[serde(default, skip_serializing_if = "SubscriptionFilter::is_empty")]
+        filter: SubscriptionFilter,
     },
 
     /// Unsubscribe from updates
@@ -60,6 +75,77 @@ pub enum MessageType {
 
     /// Request daemon to discover existing Claude sessions
     Discover,
+
+    /// Kill an agent by closing its tmux pane
+    KillSession {
+        /// Session ID, unique session ID prefix, or tmux pane ID (`%N`)
+        target: String,
+    },
+
+    /// Interrupt an agent (Ctrl+C in its pane)
+    InterruptSession {
+        /// Session ID, unique session ID prefix, or tmux pane ID (`%N`)
+        target: String,
+    },
+
+    /// Type text into an agent's pane and submit it with Enter
+    SendInput {
+        /// Session ID, unique session ID prefix, or tmux pane ID (`%N`)
+        target: String,
+        /// Text to send
+        text: String,
+    },
+
+    /// Answer a permission/selection prompt in an agent's pane
+    ReplyPrompt {
+        /// Session ID, unique session ID prefix, or tmux pane ID (`%N`)
+        target: String,
+        /// How to answer the prompt
+        choice: ReplyChoice,
+    },
+}
+
+impl MessageType {
+    /// Returns true if a daemon speaking `version` understands this
+    /// message, so clients can refuse early instead of getting a parse
+    /// error back from an older daemon.
+    pub fn is_supported_by(&self, version: &ProtocolVersion) -> bool {
+        match self {
+            Self::ReplyPrompt {
+                choice: ReplyChoice::Text(_),
+                ..
+            } => version.supports_reply_text(),
+            _ => true,
+        }
+    }
+}
+
+/// Answer to an interactive agent prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyChoice {
+    /// Accept the currently highlighted option (Enter)
+    Accept,
+    /// Dismiss the prompt (Escape)
+    Reject,
+    /// Select a numbered option (1-based)
+    Option(usize),
+    /// Answer a question in its free-text entry ("Type something.")
+    Text(String),
+}
+
+/// Control action reported back in [`DaemonMessage::ControlResult`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlAction {
+    /// Pane was killed
+    Kill,
+    /// Ctrl+C was sent
+    Interrupt,
+    /// Text was typed and submitted
+    SendInput,
+    /// A prompt was answered
+    Reply,
 }
 
 /// Messages sent from client to daemon.
@@ -68,6 +154,13 @@ pub struct ClientMessage {
     /// Protocol version
     pub protocol_version: ProtocolVersion,
 
+    /// Optional client-chosen correlation ID.
+    ///
+    /// When set, the daemon echoes it on every reply to this message so a
+    /// client can keep several requests in flight on one connection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<u64>,
+
     /// Message payload
     #[serde(flatten)]
     pub message: MessageType,
@@ -78,13 +171,31 @@ impl ClientMessage {
     pub fn new(message: MessageType) -> Self {
         Self {
             protocol_version: ProtocolVersion::CURRENT,
+            request_id: None,
             message,
         }
     }
 
+    /// Tags this message with a request ID to be echoed on replies.
+    pub fn with_request_id(mut self, request_id: u64) -> Self {
+        self.request_id = Some(request_id);
+        self
+    }
+
     /// Creates a connect message.
     pub fn connect(client_id: Option<String>) -> Self {
-        Self::new(MessageType::Connect { client_id })
+        Self::new(MessageType::Connect {
+            client_id,
+            token: None,
+        })
+    }
+
+    /// Creates a connection request authenticated with an access token.
+    pub fn connect_with_token(client_id: Option<String>, token: impl Into<String>) -> Self {
+        Self::new(MessageType::Connect {
+            client_id,
+            token: Some(token.into()),
+        })
     }
 
     /// Creates a status update message.
@@ -102,6 +213,11 @@ impl ClientMessage {
         Self::new(MessageType::PiEvent { data })
     }
 
+    /// Creates a Codex event message (raw Codex `notify` payload).
+    pub fn codex_event(data: serde_json::Value) -> Self {
+        Self::new(MessageType::CodexEvent { data })
+    }
+
     /// Creates a list sessions request.
     pub fn list_sessions() -> Self {
         Self::new(MessageType::ListSessions)
@@ -109,7 +225,18 @@ impl ClientMessage {
 
     /// Creates a subscribe message.
     pub fn subscribe(session_id: Option<SessionId>) -> Self {
-        Self::new(MessageType::Subscribe { session_id })
+        Self::new(MessageType::Subscribe {
+            session_id,
+            filter: SubscriptionFilter::default(),
+        })
+    }
+
+    /// Creates a subscribe message that only receives matching sessions.
+    pub fn subscribe_filtered(filter: SubscriptionFilter) -> Self {
+        Self::new(MessageType::Subscribe {
+            session_id: None,
+            filter,
+        })
     }
 
     /// Creates a ping message.
@@ -126,9 +253,43 @@ impl ClientMessage {
     pub fn discover() -> Self {
         Self::new(MessageType::Discover)
     }
+
+    /// Creates a kill session request.
+    pub fn kill_session(target: impl Into<String>) -> Self {
+        Self::new(MessageType::KillSession {
+            target: target.into(),
+        })
+    }
+
+    /// Creates an interrupt session request.
+    pub fn interrupt_session(target: impl Into<String>) -> Self {
+        Self::new(MessageType::InterruptSession {
+            target: target.into(),
+        })
+    }
+
+    /// Creates a send input request.
+    pub fn send_input(target: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::new(MessageType::SendInput {
+            target: target.into(),
+            text: text.into(),
+        })
+    }
+
+    /// Creates a reply prompt request.
+    pub fn reply_prompt(target: impl Into<String>, choice: ReplyChoice) -> Self {
+        Self::new(MessageType::ReplyPrompt {
+            target: target.into(),
+            choice,
+        })
+    }
 }
 
 /// Messages sent from daemon to clients.
+///
+/// Replies carry an optional `request_id` echoing the [`ClientMessage`]
+/// they answer; broadcast events (`SessionUpdated`, `SessionPatch`,
+/// `SessionRemoved`) don't.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum DaemonMessage {
@@ -138,6 +299,9 @@ pub enum DaemonMessage {
         protocol_version: ProtocolVersion,
         /// Assigned client ID
         client_id: String,
+        /// Echo of the request's `request_id`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<u64>,
     },
 
     /// Connection rejected (version mismatch, etc.)
@@ -146,12 +310,18 @@ pub enum DaemonMessage {
         reason: String,
         /// Daemon's protocol version (for client to upgrade)
         protocol_version: ProtocolVersion,
+        /// Echo of the request's `request_id`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<u64>,
     },
 
     /// Full session list response
     SessionList {
         /// All current sessions
         sessions: Vec<SessionView>,
+        /// Echo of the request's `request_id`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<u64>,
     },
 
     /// Session was created or updated
@@ -160,16 +330,38 @@ pub enum DaemonMessage {
         session: Box<SessionView>,
     },
 
+    /// Session changed since the last broadcast; carries only the changed
+    /// `SessionView` fields (see [`crate::patch`]).
+    ///
+    /// Only sent to clients that negotiated protocol 1.2 or later. A client
+    /// whose cached view is not at `revision - 1` has missed an update and
+    /// should resync with `ListSessions`.
+    SessionPatch {
+        /// Session the patch applies to
+        session_id: SessionId,
+        /// Revision the session is at after applying the patch
+        revision: u64,
+        /// Changed `SessionView` fields; removed optional fields are `null`
+        changes: serde_json::Map<String, serde_json::Value>,
+    },
+
     /// Session was removed (stale, disconnected)
     SessionRemoved {
         /// ID of the removed session
         session_id: SessionId,
+        /// ID the session continues under when it was only renamed (a
+        /// pending session learning its real ID)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        replaced_by: Option<SessionId>,
     },
 
     /// Pong response to ping
     Pong {
         /// Sequence number from ping
         seq: u64,
+        /// Echo of the request's `request_id`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<u64>,
     },
 
     /// Error response
@@ -179,6 +371,9 @@ pub enum DaemonMessage {
         /// Error code (optional)
         #[serde(skip_serializing_if = "Option::is_none")]
         code: Option<String>,
+        /// Echo of the request's `request_id`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<u64>,
     },
 
     /// Discovery completed response
@@ -187,6 +382,29 @@ pub enum DaemonMessage {
         discovered: u32,
         /// Number of discovery failures (logged at debug)
         failed: u32,
+        /// Echo of the request's `request_id`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<u64>,
+    },
+
+    /// A control request was executed.
+    ///
+    /// Failed control requests are answered with [`DaemonMessage::Error`]
+    /// carrying a machine-readable `code` instead.
+    ControlResult {
+        /// Which action was performed
+        action: ControlAction,
+        /// Session the target resolved to (None for an untracked pane ID)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        session_id: Option<SessionId>,
+        /// Tmux pane the action was applied to
+        pane_id: String,
+        /// Keystrokes sent to the pane, in order
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        keys: Vec<String>,
+        /// Echo of the request's `request_id`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_id: Option<u64>,
     },
 }
 
@@ -196,6 +414,7 @@ impl DaemonMessage {
         Self::Connected {
             protocol_version: ProtocolVersion::CURRENT,
             client_id,
+            request_id: None,
         }
     }
 
@@ -204,12 +423,16 @@ impl DaemonMessage {
         Self::Rejected {
             reason: reason.to_string(),
             protocol_version: ProtocolVersion::CURRENT,
+            request_id: None,
         }
     }
 
     /// Creates a session list response.
     pub fn session_list(sessions: Vec<SessionView>) -> Self {
-        Self::SessionList { sessions }
+        Self::SessionList {
+            sessions,
+            request_id: None,
+        }
     }
 
     /// Creates a session updated notification.
@@ -219,14 +442,41 @@ impl DaemonMessage {
         }
     }
 
+    /// Creates a session patch notification.
+    pub fn session_patch(
+        session_id: SessionId,
+        revision: u64,
+        changes: serde_json::Map<String, serde_json::Value>,
+    ) -> Self {
+        Self::SessionPatch {
+            session_id,
+            revision,
+            changes,
+        }
+    }
+
     /// Creates a session removed notification.
     pub fn session_removed(session_id: SessionId) -> Self {
-        Self::SessionRemoved { session_id }
+        Self::SessionRemoved {
+            session_id,
+            replaced_by: None,
+        }
+    }
+
+    /// Creates a removal notification for a session renamed to `new_id`.
+    pub fn session_replaced(session_id: SessionId, new_id: SessionId) -> Self {
+        Self::SessionRemoved {
+            session_id,
+            replaced_by: Some(new_id),
+        }
     }
 
     /// Creates a pong response.
     pub fn pong(seq: u64) -> Self {
-        Self::Pong { seq }
+        Self::Pong {
+            seq,
+            request_id: None,
+        }
     }
 
     /// Creates an error response.
@@ -234,6 +484,7 @@ impl DaemonMessage {
         Self::Error {
             message: message.to_string(),
             code: None,
+            request_id: None,
         }
     }
 
@@ -242,12 +493,68 @@ impl DaemonMessage {
         Self::Error {
             message: message.to_string(),
             code: Some(code.to_string()),
+            request_id: None,
         }
     }
 
     /// Creates a discovery complete response.
     pub fn discovery_complete(discovered: u32, failed: u32) -> Self {
-        Self::DiscoveryComplete { discovered, failed }
+        Self::DiscoveryComplete {
+            discovered,
+            failed,
+            request_id: None,
+        }
+    }
+
+    /// Creates a control result response.
+    pub fn control_result(
+        action: ControlAction,
+        session_id: Option<SessionId>,
+        pane_id: String,
+        keys: Vec<String>,
+    ) -> Self {
+        Self::ControlResult {
+            action,
+            session_id,
+            pane_id,
+            keys,
+            request_id: None,
+        }
+    }
+
+    /// Sets the echoed request ID on a reply.
+    ///
+    /// Broadcast events are returned unchanged.
+    pub fn with_request_id(mut self, id: Option<u64>) -> Self {
+        match &mut self {
+            Self::Connected { request_id, .. }
+            | Self::Rejected { request_id, .. }
+            | Self::SessionList { request_id, .. }
+            | Self::Pong { request_id, .. }
+            | Self::Error { request_id, .. }
+            | Self::DiscoveryComplete { request_id, .. }
+            | Self::ControlResult { request_id, .. } => *request_id = id,
+            Self::SessionUpdated { .. }
+            | Self::SessionPatch { .. }
+            | Self::SessionRemoved { .. } => {}
+        }
+        self
+    }
+
+    /// Returns the echoed request ID, if this is a tagged reply.
+    pub fn request_id(&self) -> Option<u64> {
+        match self {
+            Self::Connected { request_id, .. }
+            | Self::Rejected { request_id, .. }
+            | Self::SessionList { request_id, .. }
+            | Self::Pong { request_id, .. }
+            | Self::Error { request_id, .. }
+            | Self::DiscoveryComplete { request_id, .. }
+            | Self::ControlResult { request_id, .. } => *request_id,
+            Self::SessionUpdated { .. }
+            | Self::SessionPatch { .. }
+            | Self::SessionRemoved { .. } => None,
+        }
     }
 }
 
@@ -263,6 +570,14 @@ mod tests {
         assert!(json.contains("\"seq\":42"));
     }
 
+    #[test]
+    fn test_codex_event_serialization() {
+        let msg = ClientMessage::codex_event(serde_json::json!({"type": "agent-turn-complete"}));
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"codex_event\""));
+        assert!(json.contains("\"data\":{\"type\":\"agent-turn-complete\"}"));
+    }
+
     #[test]
     fn test_daemon_message_serialization() {
         let msg = DaemonMessage::connected("client-123".to_string());
@@ -278,7 +593,7 @@ mod tests {
         let parsed: ClientMessage = serde_json::from_str(&json).unwrap();
 
         match parsed.message {
-            MessageType::Subscribe { session_id } => {
+            MessageType::Subscribe { session_id, .. } => {
                 assert_eq!(
                     session_id.map(|s| s.as_str().to_string()),
                     Some("test-session".to_string())
@@ -287,4 +602,133 @@ mod tests {
             _ => panic!("Expected Subscribe message"),
         }
     }
+
+    #[test]
+    fn test_request_id_is_optional_on_the_wire() {
+        let untagged = serde_json::to_string(&ClientMessage::ping(1)).unwrap();
+        assert!(!untagged.contains("request_id"));
+
+        let tagged = ClientMessage::ping(1).with_request_id(7);
+        let json = serde_json::to_string(&tagged).unwrap();
+        assert!(json.contains("\"request_id\":7"));
+
+        // Messages from 1.0 clients (no request_id) still parse
+        let legacy = r#"{"protocol_version":{"major":1,"minor":0},"type":"ping","seq":3}"#;
+        let parsed: ClientMessage = serde_json::from_str(legacy).unwrap();
+        assert_eq!(parsed.request_id, None);
+    }
+
+    #[test]
+    fn test_daemon_reply_echoes_request_id() {
+        let reply = DaemonMessage::pong(1).with_request_id(Some(7));
+        assert_eq!(reply.request_id(), Some(7));
+        let json = serde_json::to_string(&reply).unwrap();
+        assert!(json.contains("\"request_id\":7"));
+
+        // Events never carry a request ID
+        let event = DaemonMessage::session_removed(SessionId::new("gone")).with_request_id(Some(7));
+        assert_eq!(event.request_id(), None);
+        assert!(!serde_json::to_string(&event)
+            .unwrap()
+            .contains("request_id"));
+    }
+
+    #[test]
+    fn test_subscribe_filter_is_optional_on_the_wire() {
+        let json = serde_json::to_string(&ClientMessage::subscribe(None)).unwrap();
+        assert!(!json.contains("filter"));
+
+        let legacy = r#"{"protocol_version":{"major":1,"minor":0},"type":"subscribe"}"#;
+        let parsed: ClientMessage = serde_json::from_str(legacy).unwrap();
+        match parsed.message {
+            MessageType::Subscribe { filter, .. } => assert!(filter.is_empty()),
+            _ => panic!("Expected Subscribe message"),
+        }
+
+        let filtered = ClientMessage::subscribe_filtered(SubscriptionFilter {
+            tmux_session: Some("dev".to_string()),
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&filtered).unwrap();
+        assert!(json.contains("\"filter\":{\"tmux_session\":\"dev\"}"));
+    }
+
+    #[test]
+    fn test_session_replaced_is_optional_on_the_wire() {
+        let json =
+            serde_json::to_string(&DaemonMessage::session_removed(SessionId::new("gone"))).unwrap();
+        assert!(!json.contains("replaced_by"));
+
+        let json = serde_json::to_string(&DaemonMessage::session_replaced(
+            SessionId::new("pending-42"),
+            SessionId::new("real"),
+        ))
+        .unwrap();
+        assert!(json.contains("\"replaced_by\":\"real\""));
+
+        let legacy = r#"{"type":"session_removed","session_id":"gone"}"#;
+        match serde_json::from_str(legacy).unwrap() {
+            DaemonMessage::SessionRemoved { replaced_by, .. } => assert!(replaced_by.is_none()),
+            other => panic!("Expected SessionRemoved, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_session_patch_serialization() {
+        let mut changes = serde_json::Map::new();
+        changes.insert("cost_usd".to_string(), serde_json::json!(0.5));
+        let msg = DaemonMessage::session_patch(SessionId::new("abc"), 3, changes);
+
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"session_patch\""));
+        assert!(json.contains("\"revision\":3"));
+        assert!(json.contains("\"changes\":{\"cost_usd\":0.5}"));
+        assert_eq!(msg.request_id(), None);
+    }
+
+    #[test]
+    fn test_reply_prompt_serialization() {
+        let msg = ClientMessage::reply_prompt("abc123", ReplyChoice::Option(2));
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"reply_prompt\""));
+        assert!(json.contains("\"choice\":{\"option\":2}"));
+
+        let accept = ClientMessage::reply_prompt("abc123", ReplyChoice::Accept);
+        let json = serde_json::to_string(&accept).unwrap();
+        assert!(json.contains("\"choice\":\"accept\""));
+
+        let text = ClientMessage::reply_prompt("abc123", ReplyChoice::Text("C-c".into()));
+        let json = serde_json::to_string(&text).unwrap();
+        assert!(json.contains("\"choice\":{\"text\":\"C-c\"}"));
+    }
+
+    #[test]
+    fn test_reply_text_needs_protocol_1_3() {
+        let old = ProtocolVersion::new(1, 2);
+        let text = ClientMessage::reply_prompt("abc123", ReplyChoice::Text("yes".into()));
+        assert!(!text.message.is_supported_by(&old));
+        assert!(text.message.is_supported_by(&ProtocolVersion::CURRENT));
+
+        let accept = ClientMessage::reply_prompt("abc123", ReplyChoice::Accept);
+        assert!(accept.message.is_supported_by(&old));
+    }
+
+    #[test]
+    fn test_control_result_serialization() {
+        let msg = DaemonMessage::control_result(
+            ControlAction::SendInput,
+            Some(SessionId::new("abc123")),
+            "%5".to_string(),
+            vec!["fix it".to_string(), "Enter".to_string()],
+        );
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"control_result\""));
+        assert!(json.contains("\"action\":\"send_input\""));
+        assert!(json.contains("\"pane_id\":\"%5\""));
+
+        let kill = DaemonMessage::control_result(ControlAction::Kill, None, "%7".into(), vec![]);
+        let json = serde_json::to_string(&kill).unwrap();
+        assert!(!json.contains("session_id"));
+        assert!(!json.contains("keys"));
+    }
 }
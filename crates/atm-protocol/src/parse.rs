@@ -5,7 +5,7 @@
 //! crate; pi-shaped wire data lives in `atm-pi-adapter`. Future:
 //! `RawStatusLine` likely also moves to `atm-claude-adapter`.
 
-use atm_core::{SessionDomain, StatusLineData};
+use atm_core::{Model, SessionDomain, StatusLineData};
 use serde::Deserialize;
 
 /// Raw status line JSON structure from Claude Code.
@@ -72,8 +72,9 @@ pub struct RawContextWindow {
     pub total_input_tokens: u64,
     #[serde(default)]
     pub total_output_tokens: u64,
-    #[serde(default = "default_context_window_size")]
-    pub context_window_size: u32,
+    /// Absent from some payloads; the model catalog's window is used then
+    #[serde(default)]
+    pub context_window_size: Option<u32>,
     /// Pre-calculated percentage of context window used (0-100), provided by Claude Code
     #[serde(default)]
     pub used_percentage: Option<f64>,
@@ -84,10 +85,6 @@ pub struct RawContextWindow {
     pub current_usage: Option<RawCurrentUsage>,
 }
 
-fn default_context_window_size() -> u32 {
-    200_000
-}
-
 #[derive(Debug, Clone, Deserialize)]
 pub struct RawCurrentUsage {
     #[serde(default)]
@@ -121,7 +118,7 @@ impl RawStatusLine {
             lines_removed: cost.map(|c| c.total_lines_removed).unwrap_or(0),
             total_input_tokens: context.map(|c| c.total_input_tokens).unwrap_or(0),
             total_output_tokens: context.map(|c| c.total_output_tokens).unwrap_or(0),
-            context_window_size: context.map(|c| c.context_window_size).unwrap_or(200_000),
+            context_window_size: self.context_window_size(Model::from_id(&model.id)),
             current_input_tokens: current.map(|c| c.input_tokens).unwrap_or(0),
             current_output_tokens: current.map(|c| c.output_tokens).unwrap_or(0),
             cache_creation_tokens: current.map(|c| c.cache_creation_input_tokens).unwrap_or(0),
@@ -142,8 +139,6 @@ impl RawStatusLine {
     /// Only updates fields that are present in this status line.
     /// Returns `true` if the working directory changed.
     pub fn update_session(&self, session: &mut SessionDomain) -> bool {
-        use atm_core::Model;
-
         // Update model if present (fills in Unknown for discovered/hook-created sessions)
         if let Some(model) = &self.model {
             let parsed = Model::from_id(&model.id);
@@ -178,7 +173,7 @@ impl RawStatusLine {
             lines_removed: cost.map(|c| c.total_lines_removed).unwrap_or(0),
             total_input_tokens: context.map(|c| c.total_input_tokens).unwrap_or(0),
             total_output_tokens: context.map(|c| c.total_output_tokens).unwrap_or(0),
-            context_window_size: context.map(|c| c.context_window_size).unwrap_or(200_000),
+            context_window_size: self.context_window_size(session.model),
             current_input_tokens: current.map(|c| c.input_tokens).unwrap_or(0),
             current_output_tokens: current.map(|c| c.output_tokens).unwrap_or(0),
             cache_creation_tokens: current.map(|c| c.cache_creation_input_tokens).unwrap_or(0),
@@ -189,6 +184,14 @@ impl RawStatusLine {
 
         session.update_from_status_line(&data)
     }
+
+    /// The reported context window, or the catalog's window for `model`.
+    fn context_window_size(&self, model: Model) -> u32 {
+        self.context_window
+            .as_ref()
+            .and_then(|c| c.context_window_size)
+            .unwrap_or_else(|| model.context_window_size())
+    }
 }
 
 // `RawHookEvent` and the Claude→LifecycleEvent translation moved to the
@@ -198,7 +201,6 @@ impl RawStatusLine {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use atm_core::Model;
 
     #[test]
     fn test_raw_status_line_parsing() {
@@ -213,7 +215,7 @@ mod tests {
         let session = raw.to_session_domain().expect("should create session");
 
         assert_eq!(session.id.as_str(), "test-123");
-        assert_eq!(session.model, Model::Opus45);
+        assert_eq!(session.model, Model::from_id("claude-opus-4-5"));
         assert!((session.cost.as_usd() - 0.35).abs() < 0.001);
         assert_eq!(session.context.total_input_tokens.as_u64(), 5000);
     }
@@ -311,9 +313,9 @@ mod tests {
         let mut session = SessionDomain::new(
             atm_core::SessionId::new("test-discovered"),
             AgentType::GeneralPurpose,
-            Model::Unknown,
+            Model::UNKNOWN,
         );
-        assert_eq!(session.model, Model::Unknown);
+        assert_eq!(session.model, Model::UNKNOWN);
 
         // Status line arrives with model info
         let json = r#"{
@@ -326,7 +328,7 @@ mod tests {
         raw.update_session(&mut session);
 
         // Model should now be filled in
-        assert_eq!(session.model, Model::Opus45);
+        assert_eq!(session.model, Model::from_id("claude-opus-4-5"));
         // Known model should not have a display override
         assert!(session.model_display_override.is_none());
     }
@@ -338,20 +340,23 @@ mod tests {
         let mut session = SessionDomain::new(
             atm_core::SessionId::new("test-non-anthropic"),
             AgentType::GeneralPurpose,
-            Model::Unknown,
+            Model::UNKNOWN,
         );
 
-        // Non-Anthropic model with display_name
+        // Model missing from the catalog, with display_name
         let json = r#"{
             "session_id": "test-non-anthropic",
-            "model": {"id": "gpt-4o", "display_name": "GPT-4o"}
+            "model": {"id": "deepseek-chat", "display_name": "DeepSeek Chat"}
         }"#;
 
         let raw: RawStatusLine = serde_json::from_str(json).unwrap();
         raw.update_session(&mut session);
 
-        assert_eq!(session.model, Model::Unknown);
-        assert_eq!(session.model_display_override.as_deref(), Some("GPT-4o"));
+        assert_eq!(session.model, Model::UNKNOWN);
+        assert_eq!(
+            session.model_display_override.as_deref(),
+            Some("DeepSeek Chat")
+        );
     }
 
     #[test]
@@ -361,7 +366,7 @@ mod tests {
         let mut session = SessionDomain::new(
             atm_core::SessionId::new("test-unknown"),
             AgentType::GeneralPurpose,
-            Model::Unknown,
+            Model::UNKNOWN,
         );
 
         // Unknown model without display_name - should derive from ID
@@ -373,7 +378,7 @@ mod tests {
         let raw: RawStatusLine = serde_json::from_str(json).unwrap();
         raw.update_session(&mut session);
 
-        assert_eq!(session.model, Model::Unknown);
+        assert_eq!(session.model, Model::UNKNOWN);
         assert_eq!(
             session.model_display_override.as_deref(),
             Some("gemini-1.5-pro")
@@ -390,7 +395,7 @@ mod tests {
         let raw: RawStatusLine = serde_json::from_str(json).unwrap();
         let session = raw.to_session_domain().expect("should create session");
 
-        assert_eq!(session.model, Model::Opus46);
+        assert_eq!(session.model, Model::from_id("claude-opus-4-6"));
         assert!(session.model_display_override.is_none());
     }
 
@@ -398,14 +403,36 @@ mod tests {
     fn test_new_session_non_anthropic_model() {
         let json = r#"{
             "session_id": "test-gpt",
-            "model": {"id": "gpt-4o", "display_name": "GPT-4o"}
+            "model": {"id": "deepseek-chat", "display_name": "DeepSeek Chat"}
         }"#;
 
         let raw: RawStatusLine = serde_json::from_str(json).unwrap();
         let session = raw.to_session_domain().expect("should create session");
 
-        assert_eq!(session.model, Model::Unknown);
-        assert_eq!(session.model_display_override.as_deref(), Some("GPT-4o"));
+        assert_eq!(session.model, Model::UNKNOWN);
+        assert_eq!(
+            session.model_display_override.as_deref(),
+            Some("DeepSeek Chat")
+        );
+    }
+
+    #[test]
+    fn test_missing_context_window_size_uses_catalog_window() {
+        let json = r#"{
+            "session_id": "test-window",
+            "model": {"id": "gpt-5-codex"},
+            "context_window": {"total_input_tokens": 5000}
+        }"#;
+        let raw: RawStatusLine = serde_json::from_str(json).unwrap();
+        let session = raw.to_session_domain().unwrap();
+        assert_eq!(session.context.context_window_size, 400_000);
+
+        // No context block at all, and no model in the update: the
+        // session's resolved model decides
+        let raw: RawStatusLine = serde_json::from_str(r#"{"session_id": "test-window"}"#).unwrap();
+        let mut session = session;
+        raw.update_session(&mut session);
+        assert_eq!(session.context.context_window_size, 400_000);
     }
 
     #[test]
@@ -0,0 +1,157 @@
+//! Field-level deltas between two `SessionView`s.
+//!
+//! The daemon sends `SessionPatch` instead of a full `SessionUpdated` when a
+//! client already holds the previous revision of a session. A patch is the
+//! set of top-level `SessionView` JSON fields whose value changed; optional
+//! fields that disappeared are carried as `null`.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - (De)serialization failures are returned as `PatchError`
+
+use atm_core::SessionView;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// Field carrying the revision; sent alongside the patch, not inside it.
+const REVISION_FIELD: &str = "revision";
+
+/// Errors building or applying a session patch.
+#[derive(Debug, Error)]
+pub enum PatchError {
+    /// A view could not be converted to or from JSON.
+    #[error("session patch JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// A view did not serialize to a JSON object.
+    #[error("session view did not serialize to a JSON object")]
+    NotAnObject,
+}
+
+fn view_fields(view: &SessionView) -> Result<Map<String, Value>, PatchError> {
+    match serde_json::to_value(view)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(PatchError::NotAnObject),
+    }
+}
+
+/// Returns the fields of `new` that differ from `old`.
+///
+/// The `revision` field is never included; it travels as
+/// `SessionPatch::revision`. An empty map means nothing but the revision
+/// changed.
+pub fn diff_session_views(
+    old: &SessionView,
+    new: &SessionView,
+) -> Result<Map<String, Value>, PatchError> {
+    let old_fields = view_fields(old)?;
+    let new_fields = view_fields(new)?;
+
+    let mut changes = Map::new();
+    for (key, value) in &new_fields {
+        if old_fields.get(key) != Some(value) {
+            changes.insert(key.clone(), value.clone());
+        }
+    }
+    for key in old_fields.keys() {
+        if !new_fields.contains_key(key) {
+            changes.insert(key.clone(), Value::Null);
+        }
+    }
+    changes.remove(REVISION_FIELD);
+
+    Ok(changes)
+}
+
+/// Applies `changes` to `view`, producing the view at `revision`.
+pub fn apply_session_patch(
+    view: &SessionView,
+    revision: u64,
+    changes: &Map<String, Value>,
+) -> Result<SessionView, PatchError> {
+    let mut fields = view_fields(view)?;
+    for (key, value) in changes {
+        fields.insert(key.clone(), value.clone());
+    }
+    fields.insert(REVISION_FIELD.to_string(), Value::from(revision));
+
+    Ok(serde_json::from_value(Value::Object(fields))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atm_core::{AgentType, Model, SessionDomain, SessionId, SessionStatus};
+
+    fn sample_view() -> SessionView {
+        let mut domain = SessionDomain::new(
+            SessionId::new("patch-test"),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-sonnet-4"),
+        );
+        domain.tmux_pane = Some("%3".to_string());
+        domain.revision = 4;
+        SessionView::from_domain(&domain)
+    }
+
+    #[test]
+    fn test_diff_identical_views_is_empty() {
+        let view = sample_view();
+        assert!(diff_session_views(&view, &view).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_contains_only_changed_fields() {
+        let old = sample_view();
+        let mut new = old.clone();
+        new.status = SessionStatus::Working;
+        new.cost_usd = 1.25;
+        new.revision = 5;
+
+        let changes = diff_session_views(&old, &new).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes.get("cost_usd"), Some(&Value::from(1.25)));
+        assert!(changes.contains_key("status"));
+        assert!(!changes.contains_key(REVISION_FIELD));
+    }
+
+    #[test]
+    fn test_diff_marks_removed_optional_as_null() {
+        let old = sample_view();
+        let mut new = old.clone();
+        new.tmux_pane = None;
+
+        let changes = diff_session_views(&old, &new).unwrap();
+        assert_eq!(changes.get("tmux_pane"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn test_apply_roundtrips_diff() {
+        let old = sample_view();
+        let mut new = old.clone();
+        new.status = SessionStatus::Working;
+        new.tmux_pane = None;
+        new.first_prompt = Some("fix the tests".to_string());
+        new.revision = 5;
+
+        let changes = diff_session_views(&old, &new).unwrap();
+        let patched = apply_session_patch(&old, 5, &changes).unwrap();
+
+        assert_eq!(patched.status, SessionStatus::Working);
+        assert_eq!(patched.tmux_pane, None);
+        assert_eq!(patched.first_prompt.as_deref(), Some("fix the tests"));
+        assert_eq!(patched.revision, 5);
+        assert_eq!(patched.id, old.id);
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_field_type() {
+        let mut changes = Map::new();
+        changes.insert("cost_usd".to_string(), Value::from("not a number"));
+
+        let result = apply_session_patch(&sample_view(), 5, &changes);
+        assert!(matches!(result, Err(PatchError::Json(_))));
+    }
+}
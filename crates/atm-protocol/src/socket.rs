@@ -0,0 +1,135 @@
+//! Where the daemon's Unix socket lives.
+//!
+//! The socket is per user: `$XDG_RUNTIME_DIR/atm/atm.sock`, or
+//! `/tmp/atm-<uid>/atm.sock` when no runtime directory is set. `ATM_SOCKET`
+//! overrides both (test sandboxes, multiple daemons).
+//!
+//! Releases before the move used a shared `/tmp/atm.sock`. Clients fall back
+//! to it while an old daemon is still running, but only if the legacy socket
+//! belongs to the current user. The `atm-hook` and `atm-codex-hook` scripts
+//! and the pi extension implement the same lookup and must be kept in step
+//! with this module.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Missing or unreadable paths simply don't qualify for the fallback
+
+use std::ffi::OsString;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::path::{Path, PathBuf};
+
+/// Environment variable that overrides the socket path.
+pub const SOCKET_ENV: &str = "ATM_SOCKET";
+
+/// Shared socket path used before sockets became per-user.
+pub const LEGACY_SOCKET_PATH: &str = "/tmp/atm.sock";
+
+/// Returns the effective UID of this process.
+pub fn current_uid() -> u32 {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    unsafe { libc::geteuid() }
+}
+
+/// Returns the per-user default socket path, ignoring `ATM_SOCKET`.
+pub fn default_socket_path() -> PathBuf {
+    socket_path_for(std::env::var_os("XDG_RUNTIME_DIR"), current_uid())
+}
+
+/// Returns the path the daemon should bind: `ATM_SOCKET` if set, otherwise
+/// the per-user default.
+pub fn daemon_socket_path() -> PathBuf {
+    env_override().unwrap_or_else(default_socket_path)
+}
+
+/// Returns the path clients should connect to.
+///
+/// Same as [`daemon_socket_path`], except that when nothing is listening at
+/// the per-user default and a legacy `/tmp/atm.sock` owned by this user
+/// exists, the legacy socket is used instead.
+pub fn client_socket_path() -> PathBuf {
+    if let Some(path) = env_override() {
+        return path;
+    }
+    let preferred = default_socket_path();
+    let legacy = Path::new(LEGACY_SOCKET_PATH);
+    if !is_socket(&preferred) && is_own_socket(legacy, current_uid()) {
+        return legacy.to_path_buf();
+    }
+    preferred
+}
+
+/// `ATM_SOCKET`, treating an empty value as unset.
+fn env_override() -> Option<PathBuf> {
+    std::env::var_os(SOCKET_ENV)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Builds the default path from a runtime directory and UID.
+///
+/// Relative runtime directories are ignored, as the XDG spec requires.
+fn socket_path_for(runtime_dir: Option<OsString>, uid: u32) -> PathBuf {
+    let base = runtime_dir
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .map(|dir| dir.join("atm"))
+        .unwrap_or_else(|| PathBuf::from(format!("/tmp/atm-{uid}")));
+    base.join("atm.sock")
+}
+
+fn is_socket(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|m| m.file_type().is_socket())
+}
+
+fn is_own_socket(path: &Path, uid: u32) -> bool {
+    std::fs::metadata(path).is_ok_and(|m| m.file_type().is_socket() && m.uid() == uid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socket_path_uses_runtime_dir() {
+        assert_eq!(
+            socket_path_for(Some("/run/user/1000".into()), 1000),
+            PathBuf::from("/run/user/1000/atm/atm.sock")
+        );
+    }
+
+    #[test]
+    fn test_socket_path_falls_back_to_per_uid_tmp() {
+        assert_eq!(
+            socket_path_for(None, 1000),
+            PathBuf::from("/tmp/atm-1000/atm.sock")
+        );
+        // Relative and empty runtime dirs are not trusted
+        assert_eq!(
+            socket_path_for(Some("run/user".into()), 42),
+            PathBuf::from("/tmp/atm-42/atm.sock")
+        );
+        assert_eq!(
+            socket_path_for(Some("".into()), 42),
+            PathBuf::from("/tmp/atm-42/atm.sock")
+        );
+    }
+
+    #[test]
+    fn test_is_own_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("s.sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        assert!(is_socket(&path));
+        assert!(is_own_socket(&path, current_uid()));
+        assert!(!is_own_socket(&path, current_uid().wrapping_add(1)));
+
+        // A regular file is not a socket
+        let file = dir.path().join("plain");
+        std::fs::write(&file, "").unwrap();
+        assert!(!is_socket(&file));
+        assert!(!is_own_socket(&dir.path().join("missing"), current_uid()));
+    }
+}
@@ -17,7 +17,11 @@ pub struct ProtocolVersion {
 
 impl ProtocolVersion {
     /// Current protocol version.
-    pub const CURRENT: ProtocolVersion = ProtocolVersion { major: 1, minor: 0 };
+    ///
+    /// 1.1 added the optional `request_id` echoed on replies.
+    /// 1.2 added `SessionPatch` deltas.
+    /// 1.3 added free-text prompt replies (`ReplyChoice::Text`).
+    pub const CURRENT: ProtocolVersion = ProtocolVersion { major: 1, minor: 3 };
 
     /// Creates a new ProtocolVersion.
     pub const fn new(major: u16, minor: u16) -> Self {
@@ -60,6 +64,16 @@ impl ProtocolVersion {
         (self.major, self.minor) > (other.major, other.minor)
     }
 
+    /// Returns true if a peer at this version understands `SessionPatch`.
+    pub fn supports_session_patch(&self) -> bool {
+        (self.major, self.minor) >= (1, 2)
+    }
+
+    /// Returns true if a peer at this version accepts `ReplyChoice::Text`.
+    pub fn supports_reply_text(&self) -> bool {
+        (self.major, self.minor) >= (1, 3)
+    }
+
     /// Returns true if this version is the current version.
     pub fn is_current(&self) -> bool {
         *self == Self::CURRENT
@@ -117,6 +131,20 @@ mod tests {
         assert!(!v1_0.is_compatible_with(&v2_0));
     }
 
+    #[test]
+    fn test_supports_session_patch() {
+        assert!(!ProtocolVersion::new(1, 1).supports_session_patch());
+        assert!(ProtocolVersion::new(1, 2).supports_session_patch());
+        assert!(ProtocolVersion::CURRENT.supports_session_patch());
+    }
+
+    #[test]
+    fn test_supports_reply_text() {
+        assert!(!ProtocolVersion::new(1, 2).supports_reply_text());
+        assert!(ProtocolVersion::new(1, 3).supports_reply_text());
+        assert!(ProtocolVersion::CURRENT.supports_reply_text());
+    }
+
     #[test]
     fn test_version_display() {
         let v = ProtocolVersion::new(1, 2);
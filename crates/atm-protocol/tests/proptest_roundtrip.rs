@@ -7,8 +7,14 @@
 //! `PartialEq` on the protocol types (which contain `serde_json::Value` and
 //! boxed `SessionView` payloads).
 
-use atm_core::{SessionId, SessionStatus, SessionView};
-use atm_protocol::{ClientMessage, DaemonMessage, MessageType, ProtocolVersion};
+use atm_core::{
+    ContextForecast, PromptKind, PromptOption, PromptSource, PromptState, SessionId, SessionStatus,
+    SessionView, PANE_PARSER_VERSION,
+};
+use atm_protocol::{
+    ClientMessage, ControlAction, DaemonMessage, MessageType, ProtocolVersion, ReplyChoice,
+    SubscriptionFilter,
+};
 use proptest::prelude::*;
 use serde_json::Value;
 
@@ -156,6 +162,23 @@ fn arb_session_status() -> impl Strategy<Value = SessionStatus> {
     ]
 }
 
+fn arb_subscription_filter() -> impl Strategy<Value = SubscriptionFilter> {
+    (
+        proptest::option::of(arb_tricky_string()),
+        proptest::option::of(arb_tricky_string()),
+        proptest::collection::vec(arb_session_status(), 0..3),
+        proptest::option::of(arb_tricky_string()),
+    )
+        .prop_map(
+            |(project_root, harness, status, tmux_session)| SubscriptionFilter {
+                project_root,
+                harness,
+                status,
+                tmux_session,
+            },
+        )
+}
+
 /// Strategy for RFC3339-ish timestamp strings.
 ///
 /// These fields are plain `String` on the wire (formatted upstream and
@@ -223,10 +246,10 @@ fn arb_session_view() -> impl Strategy<Value = SessionView> {
     )
         .boxed();
 
-    // Independent booleans (3) — context_warning/critical/needs_attention
+    // Independent booleans (4) — context_warning/critical/needs_attention/over_budget
     // are not derived from the f64 in SessionView; they're carried verbatim
     // from the domain. Vary them independently to catch bool-field bugs.
-    let bools = (any::<bool>(), any::<bool>(), any::<bool>()).boxed();
+    let bools = (any::<bool>(), any::<bool>(), any::<bool>(), any::<bool>()).boxed();
 
     // Optional strings (4)
     let opts = (
@@ -245,18 +268,53 @@ fn arb_session_view() -> impl Strategy<Value = SessionView> {
     )
         .boxed();
 
-    // Session relations
+    // Session relations + revision
     let relations = (
         proptest::option::of(arb_session_id()),
         proptest::collection::vec(arb_session_id(), 0..4),
+        any::<u64>(),
     )
         .boxed();
 
     // Timestamps (2 strings)
     let timestamps = (arb_timestamp_string(), arb_timestamp_string()).boxed();
 
+    // Context exhaustion forecast
+    let forecast = proptest::option::of(
+        (any::<u64>(), proptest::option::of(any::<u64>()))
+            .prop_map(|(turns, minutes)| ContextForecast { turns, minutes }),
+    )
+    .boxed();
+
+    // Structured prompt of a waiting session
+    let prompt = proptest::option::of(
+        (
+            prop_oneof![
+                Just(PromptKind::Permission),
+                Just(PromptKind::Question),
+                Just(PromptKind::PlanApproval),
+            ],
+            proptest::option::of(arb_tricky_string()),
+            proptest::collection::vec(arb_tricky_string(), 0..4),
+            prop_oneof![
+                Just(PromptSource::Hook),
+                Just(PromptSource::Pane {
+                    parser_version: PANE_PARSER_VERSION
+                }),
+            ],
+        )
+            .prop_map(|(kind, question, labels, source)| PromptState {
+                kind,
+                question,
+                selected: (!labels.is_empty()).then_some(1),
+                options: labels.into_iter().map(PromptOption::new).collect(),
+                source,
+            }),
+    )
+    .boxed();
+
     (
-        identity, numerics, displays, bools, opts, git, relations, timestamps,
+        identity, numerics, displays, bools, opts, git, relations, timestamps, forecast, prompt,
     )
         .prop_map(
             |(
@@ -270,11 +328,13 @@ fn arb_session_view() -> impl Strategy<Value = SessionView> {
                     last_activity_display,
                     age_display,
                 ),
-                (context_warning, context_critical, needs_attention),
+                (context_warning, context_critical, needs_attention, over_budget),
                 (activity_detail, working_directory, tmux_pane, first_prompt),
                 (project_root, worktree_path, worktree_branch),
-                (parent_session_id, child_session_ids),
+                (parent_session_id, child_session_ids, revision),
                 (started_at, last_activity),
+                context_forecast,
+                prompt,
             )| SessionView {
                 id_short: id.short().to_string(),
                 id,
@@ -290,6 +350,7 @@ fn arb_session_view() -> impl Strategy<Value = SessionView> {
                 context_display,
                 context_warning,
                 context_critical,
+                context_forecast,
                 cost_display,
                 cost_usd,
                 duration_display,
@@ -297,6 +358,7 @@ fn arb_session_view() -> impl Strategy<Value = SessionView> {
                 lines_display,
                 working_directory,
                 needs_attention,
+                over_budget,
                 last_activity_display,
                 age_display,
                 started_at,
@@ -308,6 +370,9 @@ fn arb_session_view() -> impl Strategy<Value = SessionView> {
                 parent_session_id,
                 child_session_ids,
                 first_prompt,
+                team: None,
+                prompt,
+                revision,
             },
         )
 }
@@ -318,18 +383,24 @@ fn arb_session_view() -> impl Strategy<Value = SessionView> {
 
 fn arb_message_type() -> impl Strategy<Value = MessageType> {
     prop_oneof![
-        // Connect { client_id: Option<String> }
-        proptest::option::of(arb_tricky_string())
-            .prop_map(|client_id| MessageType::Connect { client_id }),
+        // Connect { client_id: Option<String>, token: Option<String> }
+        (
+            proptest::option::of(arb_tricky_string()),
+            proptest::option::of(arb_tricky_string())
+        )
+            .prop_map(|(client_id, token)| MessageType::Connect { client_id, token }),
         // StatusUpdate { data: Value }
         arb_finite_json_value().prop_map(|data| MessageType::StatusUpdate { data }),
         // HookEvent { data: Value }
         arb_finite_json_value().prop_map(|data| MessageType::HookEvent { data }),
         // ListSessions
         Just(MessageType::ListSessions),
-        // Subscribe { session_id: Option<SessionId> }
-        proptest::option::of(arb_session_id())
-            .prop_map(|session_id| MessageType::Subscribe { session_id }),
+        // Subscribe { session_id: Option<SessionId>, filter }
+        (
+            proptest::option::of(arb_session_id()),
+            arb_subscription_filter()
+        )
+            .prop_map(|(session_id, filter)| MessageType::Subscribe { session_id, filter }),
         // Unsubscribe
         Just(MessageType::Unsubscribe),
         // Ping { seq: u64 } — include u64::MAX
@@ -339,29 +410,69 @@ fn arb_message_type() -> impl Strategy<Value = MessageType> {
         Just(MessageType::Disconnect),
         // Discover
         Just(MessageType::Discover),
+        // KillSession { target }
+        arb_tricky_string().prop_map(|target| MessageType::KillSession { target }),
+        // InterruptSession { target }
+        arb_tricky_string().prop_map(|target| MessageType::InterruptSession { target }),
+        // SendInput { target, text }
+        (arb_tricky_string(), arb_tricky_string())
+            .prop_map(|(target, text)| MessageType::SendInput { target, text }),
+        // ReplyPrompt { target, choice }
+        (arb_tricky_string(), arb_reply_choice())
+            .prop_map(|(target, choice)| MessageType::ReplyPrompt { target, choice }),
     ]
 }
 
+fn arb_reply_choice() -> impl Strategy<Value = ReplyChoice> {
+    prop_oneof![
+        Just(ReplyChoice::Accept),
+        Just(ReplyChoice::Reject),
+        any::<usize>().prop_map(ReplyChoice::Option),
+    ]
+}
+
+fn arb_control_action() -> impl Strategy<Value = ControlAction> {
+    prop_oneof![
+        Just(ControlAction::Kill),
+        Just(ControlAction::Interrupt),
+        Just(ControlAction::SendInput),
+        Just(ControlAction::Reply),
+    ]
+}
+
+fn arb_request_id() -> impl Strategy<Value = Option<u64>> {
+    proptest::option::of(prop_oneof![Just(0u64), Just(u64::MAX), any::<u64>()])
+}
+
 fn arb_client_message() -> impl Strategy<Value = ClientMessage> {
-    (arb_protocol_version(), arb_message_type()).prop_map(|(protocol_version, message)| {
-        ClientMessage {
+    (arb_protocol_version(), arb_request_id(), arb_message_type()).prop_map(
+        |(protocol_version, request_id, message)| ClientMessage {
             protocol_version,
+            request_id,
             message,
-        }
-    })
+        },
+    )
 }
 
 // ---------------------------------------------------------------------------
 // DaemonMessage strategy (covers every variant)
 // ---------------------------------------------------------------------------
 
+/// Every variant, with `request_id` layered on afterwards so replies are
+/// exercised both tagged and untagged (events ignore it).
 fn arb_daemon_message() -> impl Strategy<Value = DaemonMessage> {
+    (arb_daemon_message_untagged(), arb_request_id())
+        .prop_map(|(message, request_id)| message.with_request_id(request_id))
+}
+
+fn arb_daemon_message_untagged() -> BoxedStrategy<DaemonMessage> {
     prop_oneof![
         // Connected
         (arb_protocol_version(), arb_tricky_string()).prop_map(|(protocol_version, client_id)| {
             DaemonMessage::Connected {
                 protocol_version,
                 client_id,
+                request_id: None,
             }
         }),
         // Rejected
@@ -369,31 +480,81 @@ fn arb_daemon_message() -> impl Strategy<Value = DaemonMessage> {
             DaemonMessage::Rejected {
                 reason,
                 protocol_version,
+                request_id: None,
             }
         }),
         // SessionList { sessions }
-        proptest::collection::vec(arb_session_view(), 0..4)
-            .prop_map(|sessions| DaemonMessage::SessionList { sessions }),
+        proptest::collection::vec(arb_session_view(), 0..4).prop_map(|sessions| {
+            DaemonMessage::SessionList {
+                sessions,
+                request_id: None,
+            }
+        }),
         // SessionUpdated { session: Box<SessionView> }
         arb_session_view().prop_map(|session| DaemonMessage::SessionUpdated {
             session: Box::new(session),
         }),
-        // SessionRemoved { session_id }
-        arb_session_id().prop_map(|session_id| DaemonMessage::SessionRemoved { session_id }),
+        // SessionPatch { session_id, revision, changes }
+        (
+            arb_session_id(),
+            any::<u64>(),
+            proptest::collection::hash_map("[a-z_]{1,12}", arb_finite_json_value(), 0..6),
+        )
+            .prop_map(
+                |(session_id, revision, changes)| DaemonMessage::SessionPatch {
+                    session_id,
+                    revision,
+                    changes: changes.into_iter().collect(),
+                }
+            ),
+        // SessionRemoved { session_id, replaced_by }
+        (arb_session_id(), proptest::option::of(arb_session_id())).prop_map(
+            |(session_id, replaced_by)| DaemonMessage::SessionRemoved {
+                session_id,
+                replaced_by
+            }
+        ),
         // Pong { seq } — include u64::MAX
-        prop_oneof![Just(0u64), Just(u64::MAX), any::<u64>()]
-            .prop_map(|seq| DaemonMessage::Pong { seq }),
+        prop_oneof![Just(0u64), Just(u64::MAX), any::<u64>()].prop_map(|seq| DaemonMessage::Pong {
+            seq,
+            request_id: None,
+        }),
         // Error { message, code: Option<String> }
         (
             arb_tricky_string(),
             proptest::option::of(arb_tricky_string())
         )
-            .prop_map(|(message, code)| DaemonMessage::Error { message, code }),
+            .prop_map(|(message, code)| DaemonMessage::Error {
+                message,
+                code,
+                request_id: None,
+            }),
         // DiscoveryComplete { discovered, failed }
         (any::<u32>(), any::<u32>()).prop_map(|(discovered, failed)| {
-            DaemonMessage::DiscoveryComplete { discovered, failed }
+            DaemonMessage::DiscoveryComplete {
+                discovered,
+                failed,
+                request_id: None,
+            }
         }),
+        // ControlResult { action, session_id, pane_id, keys }
+        (
+            arb_control_action(),
+            proptest::option::of(arb_session_id()),
+            arb_tricky_string(),
+            proptest::collection::vec(arb_tricky_string(), 0..4),
+        )
+            .prop_map(|(action, session_id, pane_id, keys)| {
+                DaemonMessage::ControlResult {
+                    action,
+                    session_id,
+                    pane_id,
+                    keys,
+                    request_id: None,
+                }
+            }),
     ]
+    .boxed()
 }
 
 // ---------------------------------------------------------------------------
@@ -183,6 +183,11 @@ impl TmuxClient for RealTmuxClient {
         self.run_silent("send-keys", &["-t", pane, keys]).await
     }
 
+    async fn send_text(&self, pane: &str, text: &str) -> Result<(), TmuxError> {
+        self.run_silent("send-keys", &["-t", pane, "-l", "--", text])
+            .await
+    }
+
     async fn list_panes(&self) -> Result<Vec<PaneInfo>, TmuxError> {
         let format = "#{pane_id}\t#{session_name}\t#{window_index}\t#{pane_pid}\t#{pane_width}\t#{pane_height}\t#{pane_active}";
         let output = self.run("list-panes", &["-a", "-F", format]).await?;
@@ -101,6 +101,10 @@ pub trait TmuxClient: Send + Sync {
     /// Sends keystrokes to a pane.
     async fn send_keys(&self, pane: &str, keys: &str) -> Result<(), TmuxError>;
 
+    /// Types `text` into a pane literally, without reading key names
+    /// such as `Enter` or `C-c` out of it.
+    async fn send_text(&self, pane: &str, text: &str) -> Result<(), TmuxError>;
+
     /// Lists all panes across all sessions.
     async fn list_panes(&self) -> Result<Vec<PaneInfo>, TmuxError>;
 
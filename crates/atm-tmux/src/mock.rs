@@ -33,6 +33,10 @@ pub enum MockCall {
         pane: String,
         keys: String,
     },
+    SendText {
+        pane: String,
+        text: String,
+    },
     ListPanes,
     DisplayPopup {
         width: String,
@@ -257,6 +261,13 @@ impl TmuxClient for MockTmuxClient {
         })
     }
 
+    async fn send_text(&self, pane: &str, text: &str) -> Result<(), TmuxError> {
+        self.record(MockCall::SendText {
+            pane: pane.to_string(),
+            text: text.to_string(),
+        })
+    }
+
     async fn list_panes(&self) -> Result<Vec<PaneInfo>, TmuxError> {
         self.record(MockCall::ListPanes)?;
         let panes = self
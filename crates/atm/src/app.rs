@@ -7,12 +7,16 @@
 //! `panic!()`, `unreachable!()`, `todo!()`, or direct indexing `[i]`.
 
 use atm_core::{
-    all_node_ids, build_tree, flatten_tree, SessionId, SessionView, TreeNode, TreeNodeId, TreeRow,
+    all_node_ids, build_tree_with, find_node, flatten_tree, GroupBy, PromptKind, PromptState,
+    SessionId, SessionStatus, SessionView, SortKey, TreeNode, TreeNodeId, TreeOptions, TreeRow,
     TreeRowKind,
 };
 use chrono::{DateTime, Utc};
 use std::collections::{HashMap, HashSet};
 
+use crate::keymap::Keymap;
+use crate::search::{harness_name, session_matches, SearchState, StatusFilter};
+
 // ============================================================================
 // Application State
 // ============================================================================
@@ -36,6 +40,131 @@ pub enum AppState {
     Connecting,
 }
 
+// ============================================================================
+// Reply Popup
+// ============================================================================
+
+/// An answer chosen in the reply popup, ready to send to the daemon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplyAnswer {
+    /// Pick numbered option N (1-based).
+    Option(usize),
+    /// Confirm the prompt as shown (no options were recognized).
+    Accept,
+    /// Dismiss the prompt.
+    Reject,
+    /// Type free text and submit it.
+    Text(String),
+}
+
+/// State of the reply popup for one waiting session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplyPopup {
+    /// Session being answered.
+    pub session_id: SessionId,
+    /// The prompt as known when the popup opened, if any was recognized.
+    pub prompt: Option<PromptState>,
+    /// 1-based option under the popup cursor.
+    pub cursor: usize,
+    /// Text typed so far; `Some` while in free-text entry.
+    pub text: Option<String>,
+}
+
+impl ReplyPopup {
+    /// Number of options the popup offers.
+    pub fn option_count(&self) -> usize {
+        self.prompt.as_ref().map_or(0, |p| p.options.len())
+    }
+
+    /// Whether free text can answer this prompt (questions only).
+    pub fn accepts_text(&self) -> bool {
+        self.prompt
+            .as_ref()
+            .is_some_and(|p| p.kind == PromptKind::Question)
+    }
+
+    /// Moves the cursor down one option, stopping at the last.
+    pub fn move_down(&mut self) {
+        if self.cursor < self.option_count() {
+            self.cursor = self.cursor.saturating_add(1);
+        }
+    }
+
+    /// Moves the cursor up one option, stopping at the first.
+    pub fn move_up(&mut self) {
+        if self.cursor > 1 {
+            self.cursor = self.cursor.saturating_sub(1);
+        }
+    }
+
+    /// Switches to free-text entry if the prompt accepts text.
+    pub fn start_text(&mut self) {
+        if self.accepts_text() && self.text.is_none() {
+            self.text = Some(String::new());
+        }
+    }
+
+    /// Returns option `n` if the popup offers it.
+    pub fn pick(&self, n: usize) -> Option<ReplyAnswer> {
+        (n >= 1 && n <= self.option_count()).then_some(ReplyAnswer::Option(n))
+    }
+
+    /// The answer Enter sends: the typed text, the option under the
+    /// cursor, or a plain confirm when no options were recognized.
+    /// Empty text sends nothing.
+    pub fn answer(&self) -> Option<ReplyAnswer> {
+        match &self.text {
+            Some(text) if text.trim().is_empty() => None,
+            Some(text) => Some(ReplyAnswer::Text(text.clone())),
+            None if self.option_count() == 0 => Some(ReplyAnswer::Accept),
+            None => Some(ReplyAnswer::Option(self.cursor)),
+        }
+    }
+}
+
+// ============================================================================
+// Broadcast Confirmation
+// ============================================================================
+
+/// An action applied to every marked session at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastAction {
+    /// Kill the agents and close their panes.
+    Kill,
+    /// Send Ctrl+C to the agents.
+    Interrupt,
+}
+
+impl BroadcastAction {
+    /// Verb shown in the confirmation popup.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Kill => "Kill",
+            Self::Interrupt => "Interrupt",
+        }
+    }
+}
+
+/// One session a broadcast action will reach.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastTarget {
+    /// Session to act on.
+    pub session_id: SessionId,
+    /// Short session ID for display.
+    pub id_short: String,
+    /// The session's tmux pane.
+    pub pane_id: String,
+}
+
+/// A broadcast action waiting for the user to confirm it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastConfirm {
+    /// What will be done.
+    pub action: BroadcastAction,
+    /// Every session it will be done to, in tree order.
+    pub targets: Vec<BroadcastTarget>,
+}
+
 // ============================================================================
 // Application
 // ============================================================================
@@ -97,6 +226,42 @@ pub struct App {
 
     /// Compact mode: vertical layout optimized for narrow sidebar panes.
     pub compact: bool,
+
+    /// The reply popup, while open.
+    pub reply: Option<ReplyPopup>,
+
+    /// Sessions marked for multi-select actions.
+    pub marked: HashSet<SessionId>,
+
+    /// A broadcast action awaiting confirmation.
+    pub confirm: Option<BroadcastConfirm>,
+
+    /// One-line notice shown in the footer until the next key press.
+    pub status_message: Option<String>,
+
+    /// The `/` search bar.
+    pub search: SearchState,
+
+    /// Status quick filter (`s`), if set.
+    pub status_filter: Option<StatusFilter>,
+
+    /// Harness quick filter (`H`), if set.
+    pub harness_filter: Option<String>,
+
+    /// Sessions that pass the search and quick filters themselves, as
+    /// opposed to ancestors kept only to show where a match lives.
+    search_hits: HashSet<SessionId>,
+
+    /// The folds as they were before a search or quick filter opened them,
+    /// restored once the last filter is cleared.
+    unfiltered_expanded: Option<HashSet<TreeNodeId>>,
+
+    /// Grouping (`v`), sort order (`S`) and tmux pane locations the tree
+    /// is built with.
+    pub tree_options: TreeOptions,
+
+    /// Effective key bindings, listed by the help popup and footer.
+    pub keymap: Keymap,
 }
 
 impl Default for App {
@@ -126,6 +291,17 @@ impl App {
             tmux_session_filter: None,
             filter_pane_ids: HashSet::new(),
             compact: false,
+            reply: None,
+            marked: HashSet::new(),
+            confirm: None,
+            status_message: None,
+            search: SearchState::default(),
+            status_filter: None,
+            harness_filter: None,
+            search_hits: HashSet::new(),
+            unfiltered_expanded: None,
+            tree_options: TreeOptions::default(),
+            keymap: Keymap::default(),
         }
     }
 
@@ -153,6 +329,62 @@ impl App {
         }
     }
 
+    /// Updates the tmux session and window of each pane, used when
+    /// grouping by tmux window.
+    ///
+    /// Triggers a tree rebuild if the locations changed while grouping by
+    /// tmux window.
+    pub fn update_pane_windows(&mut self, pane_windows: HashMap<String, (String, u32)>) {
+        if self.tree_options.pane_windows != pane_windows {
+            self.tree_options.pane_windows = pane_windows;
+            if self.tree_options.group_by == GroupBy::Tmux {
+                self.rebuild_tree();
+            }
+        }
+    }
+
+    /// Sets the grouping mode and sort order, e.g. from the config file.
+    pub fn set_view(&mut self, group_by: GroupBy, sort_by: SortKey) {
+        self.tree_options.group_by = group_by;
+        self.tree_options.sort_by = sort_by;
+        self.regroup();
+    }
+
+    /// Cycles the sort order (`S`).
+    pub fn cycle_sort(&mut self) {
+        self.tree_options.sort_by = self.tree_options.sort_by.next();
+        self.regroup();
+    }
+
+    /// Cycles the grouping mode (`v`).
+    pub fn cycle_group(&mut self) {
+        self.tree_options.group_by = self.tree_options.group_by.next();
+        self.regroup();
+    }
+
+    /// Rebuilds the tree after a view change, opening the groups the new
+    /// view introduces and keeping the cursor on the same row.
+    fn regroup(&mut self) {
+        let selected = self
+            .tree_rows
+            .get(self.selected_index)
+            .map(|r| r.node_id.clone());
+        let previous = all_node_ids(&self.tree);
+        self.rebuild_tree();
+        self.expanded.extend(
+            all_node_ids(&self.tree)
+                .into_iter()
+                .filter(|id| !previous.contains(id)),
+        );
+        self.reflatten();
+        if let Some(pos) =
+            selected.and_then(|id| self.tree_rows.iter().position(|r| r.node_id == id))
+        {
+            self.selected_index = pos;
+        }
+        self.clamp_selection();
+    }
+
     /// Updates the session list with new data from the daemon.
     ///
     /// Merges new sessions with existing ones (upsert behavior).
@@ -212,15 +444,209 @@ impl App {
         } else {
             self.sessions.values().cloned().collect()
         };
-        self.tree = build_tree(&sessions);
+        let sessions = self.apply_filters(sessions);
+        let previous = all_node_ids(&self.tree);
+        self.tree = build_tree_with(&sessions, &self.tree_options);
 
         // On first build, expand everything so the tree starts open
         if self.expanded.is_empty() && !self.tree.is_empty() {
             self.expanded = all_node_ids(&self.tree);
         }
+        // Status and tmux groups come and go as agents move between
+        // them; open each one as it appears
+        self.expanded.extend(
+            all_node_ids(&self.tree)
+                .into_iter()
+                .filter(|id| matches!(id, TreeNodeId::Group(_)) && !previous.contains(id)),
+        );
 
         self.reflatten();
         self.clamp_selection();
+        self.drop_stale_reply();
+        let sessions = &self.sessions;
+        self.marked.retain(|id| sessions.contains_key(id));
+    }
+
+    /// Narrows `sessions` to those passing the search and quick filters,
+    /// plus their parent sessions so subagent matches keep their place in
+    /// the tree. Records the passing sessions as search hits.
+    fn apply_filters(&mut self, sessions: Vec<SessionView>) -> Vec<SessionView> {
+        if !self.has_filter() {
+            self.search_hits.clear();
+            return sessions;
+        }
+        self.search_hits = sessions
+            .iter()
+            .filter(|s| self.status_filter.is_none_or(|f| f.matches(s)))
+            .filter(|s| {
+                self.harness_filter
+                    .as_deref()
+                    .is_none_or(|h| harness_name(s) == h)
+            })
+            .filter(|s| session_matches(s, &self.search.query))
+            .map(|s| s.id.clone())
+            .collect();
+
+        let mut keep = self.search_hits.clone();
+        for id in &self.search_hits {
+            let mut parent = self
+                .sessions
+                .get(id)
+                .and_then(|s| s.parent_session_id.clone());
+            // Bounded walk up the parent chain, in case of a cycle
+            for _ in 0..self.sessions.len() {
+                let Some(id) = parent else { break };
+                parent = self
+                    .sessions
+                    .get(&id)
+                    .and_then(|s| s.parent_session_id.clone());
+                keep.insert(id);
+            }
+        }
+        sessions
+            .into_iter()
+            .filter(|s| keep.contains(&s.id))
+            .collect()
+    }
+
+    /// Whether the search bar or a quick filter is narrowing the tree.
+    pub fn has_filter(&self) -> bool {
+        self.search.is_active() || self.status_filter.is_some() || self.harness_filter.is_some()
+    }
+
+    /// Whether `session` itself passes the active filters.
+    pub fn is_search_hit(&self, session: &SessionView) -> bool {
+        self.has_filter() && self.search_hits.contains(&session.id)
+    }
+
+    /// Number of sessions passing the active filters.
+    pub fn search_hit_count(&self) -> usize {
+        self.search_hits.len()
+    }
+
+    /// Opens the search bar for typing (`/`), keeping any previous query.
+    pub fn start_search(&mut self) {
+        self.search.editing = true;
+    }
+
+    /// Appends a character to the query and narrows the tree.
+    pub fn push_search_char(&mut self, c: char) {
+        self.search.query.push(c);
+        self.refilter();
+    }
+
+    /// Deletes the last query character and widens the tree.
+    pub fn pop_search_char(&mut self) {
+        self.search.query.pop();
+        self.refilter();
+    }
+
+    /// Clears the query while staying in the search bar.
+    pub fn clear_search_line(&mut self) {
+        self.search.query.clear();
+        self.refilter();
+    }
+
+    /// Leaves the search bar, keeping the query as a filter (Enter).
+    pub fn accept_search(&mut self) {
+        self.search.editing = false;
+        if !self.search.is_active() {
+            self.clear_search_line();
+        }
+    }
+
+    /// Leaves the search bar and drops the query (Esc).
+    pub fn cancel_search(&mut self) {
+        self.search = SearchState::default();
+        self.refilter();
+    }
+
+    /// Drops the query and both quick filters.
+    pub fn clear_filters(&mut self) {
+        self.search = SearchState::default();
+        self.status_filter = None;
+        self.harness_filter = None;
+        self.refilter();
+    }
+
+    /// Cycles the status quick filter (`s`).
+    pub fn cycle_status_filter(&mut self) {
+        self.status_filter = StatusFilter::cycle(self.status_filter);
+        self.refilter();
+    }
+
+    /// Cycles the harness quick filter through the harnesses present (`H`).
+    pub fn cycle_harness_filter(&mut self) {
+        let mut harnesses: Vec<&str> = self.sessions.values().map(harness_name).collect();
+        harnesses.sort_unstable();
+        harnesses.dedup();
+        let next = match &self.harness_filter {
+            None => harnesses.first(),
+            Some(current) => harnesses
+                .iter()
+                .position(|h| h == current)
+                .and_then(|i| harnesses.get(i.saturating_add(1))),
+        };
+        self.harness_filter = next.map(|h| h.to_string());
+        self.refilter();
+    }
+
+    /// Moves the selection to the next search hit, wrapping (`n`).
+    pub fn search_next(&mut self) {
+        let count = self.tree_rows.len();
+        let next = (1..=count)
+            .map(|step| self.selected_index.saturating_add(step) % count)
+            .find(|&i| self.is_hit_row(i));
+        if let Some(index) = next {
+            self.selected_index = index;
+        }
+    }
+
+    /// Moves the selection to the previous search hit, wrapping (`N`).
+    pub fn search_previous(&mut self) {
+        let count = self.tree_rows.len();
+        let previous = (1..=count)
+            .map(|step| {
+                (self
+                    .selected_index
+                    .saturating_add(count)
+                    .saturating_sub(step))
+                    % count
+            })
+            .find(|&i| self.is_hit_row(i));
+        if let Some(index) = previous {
+            self.selected_index = index;
+        }
+    }
+
+    /// Whether row `index` is an agent passing the active filters.
+    fn is_hit_row(&self, index: usize) -> bool {
+        match self.tree_rows.get(index).map(|r| &r.kind) {
+            Some(TreeRowKind::Agent { session }) => self.is_search_hit(session),
+            _ => false,
+        }
+    }
+
+    /// Rebuilds the tree after a filter change. Like vim's search, this
+    /// opens the folds hiding matches and moves the cursor to the first.
+    /// The folds are put back as they were when the last filter clears.
+    fn refilter(&mut self) {
+        if self.has_filter() {
+            if self.unfiltered_expanded.is_none() {
+                self.unfiltered_expanded = Some(self.expanded.clone());
+            }
+        } else if let Some(expanded) = self.unfiltered_expanded.take() {
+            self.expanded = expanded;
+        }
+        self.rebuild_tree();
+        if self.has_filter() {
+            self.expanded.extend(all_node_ids(&self.tree));
+            self.reflatten();
+            if let Some(first) = (0..self.tree_rows.len()).find(|&i| self.is_hit_row(i)) {
+                self.selected_index = first;
+            }
+        }
+        self.clamp_selection();
     }
 
     /// Collapses every fold group in the tree (vim `zM`).
@@ -507,6 +933,137 @@ impl App {
         self.show_help = !self.show_help;
     }
 
+    /// Returns the selected session if it is waiting on a prompt in a
+    /// known pane.
+    pub fn selected_waiting_session(&self) -> Option<&SessionView> {
+        self.selected_session()
+            .filter(|s| s.status == SessionStatus::AttentionNeeded && s.tmux_pane.is_some())
+    }
+
+    /// Opens the reply popup for the selected waiting session.
+    ///
+    /// The options come from the session's known prompt merged with the
+    /// captured pane (see [`PromptState::current`]), the same view
+    /// `atm peek --prompt` shows. Returns `false` if the selection isn't
+    /// waiting.
+    pub fn open_reply(&mut self) -> bool {
+        let Some(session) = self.selected_waiting_session() else {
+            return false;
+        };
+        let pane: &[String] = if self.capture_pane_id == session.tmux_pane {
+            &self.captured_output
+        } else {
+            &[]
+        };
+        let prompt = PromptState::current(session.prompt.clone(), pane);
+        let cursor = prompt.as_ref().and_then(|p| p.selected).unwrap_or(1);
+        self.reply = Some(ReplyPopup {
+            session_id: session.id.clone(),
+            prompt,
+            cursor,
+            text: None,
+        });
+        true
+    }
+
+    /// Closes the reply popup.
+    pub fn close_reply(&mut self) {
+        self.reply = None;
+    }
+
+    /// Marks or unmarks the selected row.
+    ///
+    /// On an agent row, marks that agent and its subagents; on a project,
+    /// worktree or team row, every agent beneath it. If all of them are
+    /// already marked they are unmarked instead.
+    pub fn toggle_mark(&mut self) {
+        let ids = self.selected_row_session_ids();
+        if ids.is_empty() {
+            return;
+        }
+        if ids.iter().all(|id| self.marked.contains(id)) {
+            for id in &ids {
+                self.marked.remove(id);
+            }
+        } else {
+            self.marked.extend(ids);
+        }
+    }
+
+    /// Unmarks every session.
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    /// Whether every agent under `row` is marked.
+    pub fn is_row_marked(&self, row: &TreeRow) -> bool {
+        if self.marked.is_empty() {
+            return false;
+        }
+        match &row.kind {
+            TreeRowKind::Agent { session } => self.marked.contains(&session.id),
+            _ => find_node(&self.tree, &row.node_id).is_some_and(|node| {
+                let ids = node.session_ids();
+                !ids.is_empty() && ids.iter().all(|id| self.marked.contains(id))
+            }),
+        }
+    }
+
+    /// Asks for confirmation to apply `action` to every marked session.
+    ///
+    /// Returns `false` when nothing is marked, so the caller falls back
+    /// to acting on the selection alone. Marked sessions without a tmux
+    /// pane are skipped; if none has one, a status message says so.
+    pub fn request_broadcast(&mut self, action: BroadcastAction) -> bool {
+        if self.marked.is_empty() {
+            return false;
+        }
+        let targets: Vec<BroadcastTarget> = self
+            .tree
+            .iter()
+            .flat_map(TreeNode::session_ids)
+            .filter(|id| self.marked.contains(id))
+            .filter_map(|id| {
+                let session = self.sessions.get(&id)?;
+                Some(BroadcastTarget {
+                    pane_id: session.tmux_pane.clone()?,
+                    id_short: session.id_short.clone(),
+                    session_id: id,
+                })
+            })
+            .collect();
+        if targets.is_empty() {
+            self.status_message = Some(format!(
+                "{}: no marked agent has a tmux pane",
+                action.label()
+            ));
+        } else {
+            self.confirm = Some(BroadcastConfirm { action, targets });
+        }
+        true
+    }
+
+    /// Session IDs under the selected row.
+    fn selected_row_session_ids(&self) -> Vec<SessionId> {
+        self.tree_rows
+            .get(self.selected_index)
+            .and_then(|row| find_node(&self.tree, &row.node_id))
+            .map(TreeNode::session_ids)
+            .unwrap_or_default()
+    }
+
+    /// Closes the reply popup once its session stops waiting.
+    fn drop_stale_reply(&mut self) {
+        let waiting = self.reply.as_ref().is_some_and(|popup| {
+            self.sessions
+                .get(&popup.session_id)
+                .is_some_and(|s| s.status == SessionStatus::AttentionNeeded)
+        });
+        if !waiting {
+            self.reply = None;
+        }
+    }
+
     /// Returns the number of sessions currently tracked.
     pub fn session_count(&self) -> usize {
         self.sessions.len()
@@ -1318,4 +1875,422 @@ mod tests {
         dispatch(&mut app, action);
         assert_eq!(app.tree_rows.len(), rows_before);
     }
+
+    // ------------------------------------------------------------------
+    // Reply popup
+    // ------------------------------------------------------------------
+
+    /// App with one session waiting on a prompt in pane %1, selected.
+    fn app_waiting(prompt: Option<PromptState>) -> App {
+        let mut session = create_test_session("waiting-1", "2024-01-15T10:00:00Z");
+        session.status = SessionStatus::AttentionNeeded;
+        session.tmux_pane = Some("%1".to_string());
+        session.prompt = prompt;
+        let mut app = App::new();
+        app.update_sessions(vec![session]);
+        app.selected_index = app
+            .tree_rows
+            .iter()
+            .position(|r| matches!(r.kind, TreeRowKind::Agent { .. }))
+            .unwrap();
+        app
+    }
+
+    #[test]
+    fn test_open_reply_uses_captured_pane() {
+        let mut app = app_waiting(None);
+        app.capture_pane_id = Some("%1".to_string());
+        app.update_capture(
+            "%1",
+            vec![
+                "Do you want to proceed?".into(),
+                "  1. Yes".into(),
+                "❯ 2. No".into(),
+                "Esc to cancel".into(),
+            ],
+        );
+
+        assert!(app.open_reply());
+        let popup = app.reply.as_ref().unwrap();
+        assert_eq!(popup.option_count(), 2);
+        assert_eq!(popup.cursor, 2);
+        assert!(!popup.accepts_text());
+        assert_eq!(popup.answer(), Some(ReplyAnswer::Option(2)));
+    }
+
+    #[test]
+    fn test_open_reply_requires_waiting_session() {
+        let mut app = App::new();
+        app.update_sessions(vec![create_test_session("busy-1", "2024-01-15T10:00:00Z")]);
+        app.selected_index = 1;
+        assert!(!app.open_reply());
+        assert!(app.reply.is_none());
+    }
+
+    #[test]
+    fn test_reply_popup_navigation_and_text() {
+        let prompt = PromptState::from_hook(
+            PromptKind::Question,
+            Some("Which library?".into()),
+            vec![
+                atm_core::PromptOption::new("serde"),
+                atm_core::PromptOption::new("miniserde"),
+            ],
+        );
+        let mut app = app_waiting(Some(prompt));
+        assert!(app.open_reply());
+        let popup = app.reply.as_mut().unwrap();
+
+        popup.move_up();
+        assert_eq!(popup.cursor, 1);
+        popup.move_down();
+        popup.move_down();
+        assert_eq!(popup.cursor, 2);
+        assert_eq!(popup.pick(3), None);
+        assert_eq!(popup.pick(1), Some(ReplyAnswer::Option(1)));
+
+        popup.start_text();
+        assert_eq!(popup.answer(), None);
+        popup.text = Some("toml".into());
+        assert_eq!(popup.answer(), Some(ReplyAnswer::Text("toml".into())));
+    }
+
+    #[test]
+    fn test_reply_without_options_accepts() {
+        let mut app = app_waiting(None);
+        assert!(app.open_reply());
+        let popup = app.reply.as_ref().unwrap();
+        assert!(popup.prompt.is_none());
+        assert_eq!(popup.answer(), Some(ReplyAnswer::Accept));
+    }
+
+    #[test]
+    fn test_reply_popup_closes_when_session_stops_waiting() {
+        let mut app = app_waiting(None);
+        assert!(app.open_reply());
+
+        let mut session = app.sessions.values().next().cloned().unwrap();
+        session.status = SessionStatus::Working;
+        app.update_sessions(vec![session]);
+        assert!(app.reply.is_none());
+    }
+
+    // ------------------------------------------------------------------
+    // Multi-select
+    // ------------------------------------------------------------------
+
+    fn create_panel_session(id: &str, root: &str, pane: Option<&str>) -> SessionView {
+        let mut session = create_test_session(id, "2024-01-15T10:00:00Z");
+        session.project_root = Some(root.to_string());
+        session.worktree_path = Some(root.to_string());
+        session.tmux_pane = pane.map(str::to_string);
+        session
+    }
+
+    fn row_of(app: &App, id: &TreeNodeId) -> usize {
+        app.tree_rows.iter().position(|r| &r.node_id == id).unwrap()
+    }
+
+    #[test]
+    fn test_toggle_mark_on_group_marks_every_agent() {
+        let mut app = App::new();
+        app.update_sessions(vec![
+            create_panel_session("a-1", "/repos/a", Some("%1")),
+            create_panel_session("a-2", "/repos/a", Some("%2")),
+            create_panel_session("b-1", "/repos/b", Some("%3")),
+        ]);
+        let project_a = TreeNodeId::Project("/repos/a".to_string());
+        app.selected_index = row_of(&app, &project_a);
+
+        app.toggle_mark();
+        assert_eq!(app.marked.len(), 2);
+        assert!(app.marked.contains(&SessionId::new("a-1")));
+        assert!(app.marked.contains(&SessionId::new("a-2")));
+        let row = app.tree_rows.get(app.selected_index).unwrap().clone();
+        assert!(app.is_row_marked(&row));
+
+        // Toggling again unmarks the group
+        app.toggle_mark();
+        assert!(app.marked.is_empty());
+    }
+
+    #[test]
+    fn test_toggle_mark_on_agent_and_partial_group() {
+        let mut app = App::new();
+        app.update_sessions(vec![
+            create_panel_session("a-1", "/repos/a", Some("%1")),
+            create_panel_session("a-2", "/repos/a", Some("%2")),
+        ]);
+        app.selected_index = row_of(&app, &TreeNodeId::Agent(SessionId::new("a-1")));
+        app.toggle_mark();
+        assert_eq!(app.marked.len(), 1);
+
+        // A partly marked group isn't shown as marked; toggling completes it
+        let project = TreeNodeId::Project("/repos/a".to_string());
+        app.selected_index = row_of(&app, &project);
+        let row = app.tree_rows.get(app.selected_index).unwrap().clone();
+        assert!(!app.is_row_marked(&row));
+        app.toggle_mark();
+        assert_eq!(app.marked.len(), 2);
+
+        app.clear_marks();
+        assert!(app.marked.is_empty());
+    }
+
+    #[test]
+    fn test_request_broadcast_lists_marked_panes() {
+        let mut app = App::new();
+        assert!(!app.request_broadcast(BroadcastAction::Kill));
+
+        app.update_sessions(vec![
+            create_panel_session("a-1", "/repos/a", Some("%1")),
+            create_panel_session("a-2", "/repos/a", None),
+            create_panel_session("b-1", "/repos/b", Some("%3")),
+        ]);
+        app.marked = [SessionId::new("a-1"), SessionId::new("a-2")]
+            .into_iter()
+            .collect();
+
+        assert!(app.request_broadcast(BroadcastAction::Interrupt));
+        let confirm = app.confirm.clone().unwrap();
+        assert_eq!(confirm.action, BroadcastAction::Interrupt);
+        // The pane-less session is skipped
+        assert_eq!(confirm.targets.len(), 1);
+        assert_eq!(confirm.targets[0].pane_id, "%1");
+    }
+
+    #[test]
+    fn test_request_broadcast_without_panes_says_so() {
+        let mut app = App::new();
+        app.update_sessions(vec![
+            create_panel_session("a-1", "/repos/a", None),
+            create_panel_session("b-1", "/repos/b", Some("%3")),
+        ]);
+        app.marked.insert(SessionId::new("a-1"));
+
+        // Handled, so the caller doesn't act on the selection instead
+        assert!(app.request_broadcast(BroadcastAction::Kill));
+        assert!(app.confirm.is_none());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Kill: no marked agent has a tmux pane")
+        );
+    }
+
+    #[test]
+    fn test_marks_dropped_with_removed_sessions() {
+        let mut app = App::new();
+        app.update_sessions(vec![create_panel_session("a-1", "/repos/a", Some("%1"))]);
+        app.marked.insert(SessionId::new("a-1"));
+        app.remove_session("a-1");
+        assert!(app.marked.is_empty());
+    }
+
+    fn agent_ids(app: &App) -> Vec<String> {
+        app.tree_rows
+            .iter()
+            .filter_map(|r| match &r.kind {
+                TreeRowKind::Agent { session } => Some(session.id.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_search_narrows_tree_as_typed() {
+        let mut app = App::new();
+        app.update_sessions(vec![
+            create_panel_session("a-1", "/repos/alpha", Some("%1")),
+            create_panel_session("b-1", "/repos/beta", Some("%2")),
+        ]);
+        app.start_search();
+        for c in "bet".chars() {
+            app.push_search_char(c);
+        }
+        assert_eq!(agent_ids(&app), vec!["b-1"]);
+        assert_eq!(app.search_hit_count(), 1);
+        assert_eq!(app.selected_session().map(|s| s.id.as_str()), Some("b-1"));
+
+        app.accept_search();
+        assert!(!app.search.editing);
+        assert!(app.has_filter());
+
+        app.cancel_search();
+        assert!(!app.has_filter());
+        assert_eq!(agent_ids(&app).len(), 2);
+    }
+
+    #[test]
+    fn test_clearing_search_restores_folds() {
+        let mut app = app_two_projects();
+        app.selected_index = 0;
+        let proj_a = app.tree_rows[0].node_id.clone();
+        app.close_fold();
+        let folded_rows = app.tree_rows.len();
+
+        app.start_search();
+        for c in "proj-a".chars() {
+            app.push_search_char(c);
+        }
+        assert_eq!(agent_ids(&app).len(), 2);
+
+        app.cancel_search();
+        assert!(!app.expanded.contains(&proj_a));
+        assert_eq!(app.tree_rows.len(), folded_rows);
+    }
+
+    #[test]
+    fn test_search_keeps_parent_of_matching_subagent() {
+        let mut app = App::new();
+        let mut parent = create_panel_session("parent-1", "/repos/a", Some("%1"));
+        parent.child_session_ids = vec![SessionId::new("child-1")];
+        let mut child = create_panel_session("child-1", "/repos/a", None);
+        child.parent_session_id = Some(SessionId::new("parent-1"));
+        child.first_prompt = Some("find the needle".to_string());
+        let other = create_panel_session("other-1", "/repos/a", Some("%2"));
+        app.update_sessions(vec![parent, child, other]);
+
+        app.push_search_char('n');
+        app.push_search_char('e');
+        app.push_search_char('e');
+        assert_eq!(agent_ids(&app), vec!["parent-1", "child-1"]);
+        // Only the subagent itself is a hit
+        assert_eq!(app.search_hit_count(), 1);
+        assert_eq!(
+            app.selected_session().map(|s| s.id.as_str()),
+            Some("child-1")
+        );
+    }
+
+    #[test]
+    fn test_status_filter_composes_with_search_and_cycles_hits() {
+        let mut app = App::new();
+        let mut idle_1 = create_panel_session("idle-1", "/repos/a", Some("%1"));
+        idle_1.status = SessionStatus::Idle;
+        let mut idle_2 = create_panel_session("idle-2", "/repos/a", Some("%2"));
+        idle_2.status = SessionStatus::Idle;
+        let busy = create_panel_session("busy-1", "/repos/a", Some("%3"));
+        app.update_sessions(vec![idle_1, idle_2, busy]);
+
+        // attention -> working -> idle
+        app.cycle_status_filter();
+        app.cycle_status_filter();
+        app.cycle_status_filter();
+        assert_eq!(app.status_filter, Some(StatusFilter::Idle));
+        assert_eq!(app.search_hit_count(), 2);
+
+        let first = app.selected_session().map(|s| s.id.clone());
+        app.search_next();
+        let second = app.selected_session().map(|s| s.id.clone());
+        assert_ne!(first, second);
+        app.search_next();
+        assert_eq!(app.selected_session().map(|s| s.id.clone()), first);
+        app.search_previous();
+        assert_eq!(app.selected_session().map(|s| s.id.clone()), second);
+
+        // The query narrows further within the status filter
+        for c in "idle-2".chars() {
+            app.push_search_char(c);
+        }
+        assert_eq!(agent_ids(&app), vec!["idle-2"]);
+
+        app.clear_filters();
+        assert_eq!(agent_ids(&app).len(), 3);
+    }
+
+    #[test]
+    fn test_harness_filter_cycles_present_harnesses() {
+        let mut app = App::new();
+        let claude = create_panel_session("c-1", "/repos/a", Some("%1"));
+        let mut pi = create_panel_session("p-1", "/repos/a", Some("%2"));
+        pi.harness = "pi".to_string();
+        app.update_sessions(vec![claude, pi]);
+
+        app.cycle_harness_filter();
+        assert_eq!(app.harness_filter.as_deref(), Some("claude"));
+        assert_eq!(agent_ids(&app), vec!["c-1"]);
+        app.cycle_harness_filter();
+        assert_eq!(app.harness_filter.as_deref(), Some("pi"));
+        assert_eq!(agent_ids(&app), vec!["p-1"]);
+        app.cycle_harness_filter();
+        assert_eq!(app.harness_filter, None);
+        assert_eq!(agent_ids(&app).len(), 2);
+    }
+
+    fn group_labels(app: &App) -> Vec<String> {
+        app.tree_rows
+            .iter()
+            .filter_map(|r| match &r.kind {
+                TreeRowKind::Group { label } => Some(label.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cycle_group_opens_new_groups_and_keeps_selection() {
+        let mut app = App::new();
+        let mut waiting = create_test_session("waiting", "2024-01-15T10:00:00Z");
+        waiting.status = SessionStatus::AttentionNeeded;
+        app.update_sessions(vec![
+            waiting,
+            create_test_session("busy", "2024-01-15T10:01:00Z"),
+        ]);
+        // Project, busy, waiting
+        app.selected_index = 2;
+
+        app.cycle_group(); // tmux: no panes, all under Other
+        app.cycle_group(); // harness
+        app.cycle_group(); // status
+        assert_eq!(app.tree_options.group_by, GroupBy::Status);
+        assert_eq!(group_labels(&app), vec!["needs input", "working"]);
+        assert_eq!(app.tree_rows.len(), 4, "new groups start open");
+        assert_eq!(
+            app.selected_session().map(|s| s.id.to_string()).as_deref(),
+            Some("waiting")
+        );
+    }
+
+    #[test]
+    fn test_cycle_sort_reorders_agents() {
+        let mut app = App::new();
+        let mut pricey = create_test_session("pricey", "2024-01-15T10:00:00Z");
+        pricey.cost_usd = 9.0;
+        app.update_sessions(vec![
+            pricey,
+            create_test_session("cheap", "2024-01-15T10:01:00Z"),
+        ]);
+        assert_eq!(agent_ids(&app), vec!["cheap", "pricey"]);
+
+        app.cycle_sort();
+        assert_eq!(app.tree_options.sort_by, SortKey::Cost);
+        assert_eq!(agent_ids(&app), vec!["pricey", "cheap"]);
+    }
+
+    #[test]
+    fn test_status_groups_open_as_they_appear() {
+        let mut app = App::new();
+        app.set_view(GroupBy::Status, SortKey::Newest);
+        app.update_sessions(vec![create_test_session("busy", "2024-01-15T10:00:00Z")]);
+
+        let mut waiting = create_test_session("busy", "2024-01-15T10:00:00Z");
+        waiting.status = SessionStatus::AttentionNeeded;
+        app.update_sessions(vec![waiting]);
+        assert_eq!(group_labels(&app), vec!["needs input"]);
+        assert_eq!(agent_ids(&app), vec!["busy"]);
+    }
+
+    #[test]
+    fn test_pane_windows_regroup_by_tmux_window() {
+        let mut app = App::new();
+        let mut session = create_test_session("a", "2024-01-15T10:00:00Z");
+        session.tmux_pane = Some("%3".to_string());
+        app.update_sessions(vec![session]);
+        app.set_view(GroupBy::Tmux, SortKey::Newest);
+        assert_eq!(group_labels(&app), vec!["Other"]);
+
+        app.update_pane_windows(HashMap::from([("%3".to_string(), ("work".to_string(), 1))]));
+        assert_eq!(group_labels(&app), vec!["work:1"]);
+        assert_eq!(agent_ids(&app), vec!["a"]);
+    }
 }
@@ -4,11 +4,14 @@
 //! - Connection to the daemon via Unix socket
 //! - Automatic reconnection with exponential backoff
 //! - Parsing and forwarding daemon messages to the TUI event loop
+//! - Applying `SessionPatch` deltas, resyncing via `ListSessions` on a gap
 //!
 //! **Panic-Free Policy:** This module follows the project's panic-free guidelines.
 //! No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, or `todo!()`.
 
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -20,23 +23,136 @@ use tracing::{debug, error, info, warn};
 
 use crate::error::{Result, TuiError};
 use crate::input::{ClientCommand, Event};
-use atm_protocol::{ClientMessage, DaemonMessage, ProtocolVersion};
+use atm_core::{SessionId, SessionView};
+use atm_protocol::{
+    apply_session_patch, socket, ClientMessage, DaemonMessage, ProtocolVersion, SubscriptionFilter,
+};
+
+/// Requests awaiting the daemon's answer, keyed by request ID.
+type PendingRequests = HashMap<u64, PendingRequest>;
+
+/// What a tagged request asked for, so its reply can be routed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingRequest {
+    /// `Subscribe`, answered with the initial session list
+    Subscribe,
+    /// `ListSessions`, sent on connect and to resync after a gap
+    ListSessions,
+    /// `Discover`, answered with `DiscoveryComplete`
+    Discover,
+    /// Kill/interrupt/reply/send, with the action's name
+    Control(&'static str),
+}
+
+impl PendingRequest {
+    /// Name used when the daemon refuses the request.
+    fn label(self) -> &'static str {
+        match self {
+            Self::Subscribe => "Subscribe",
+            Self::ListSessions => "Refresh",
+            Self::Discover => "Discover",
+            Self::Control(action) => action,
+        }
+    }
+}
+
+/// Result of applying a `SessionPatch` to the session cache.
+#[derive(Debug)]
+enum PatchOutcome {
+    /// Patch applied; carries the session's new full view
+    Applied(Box<SessionView>),
+    /// Patch is at or behind the cached revision; ignored
+    Stale,
+    /// Cache is missing the base revision (or the patch was unusable)
+    Gap,
+}
+
+/// Last known view of every session, used as the base for `SessionPatch`
+/// deltas.
+#[derive(Debug, Default)]
+struct SessionCache {
+    sessions: HashMap<SessionId, SessionView>,
+}
+
+impl SessionCache {
+    fn replace_all(&mut self, sessions: &[SessionView]) {
+        self.sessions = sessions.iter().map(|s| (s.id.clone(), s.clone())).collect();
+    }
+
+    fn upsert(&mut self, session: &SessionView) {
+        self.sessions.insert(session.id.clone(), session.clone());
+    }
+
+    fn remove(&mut self, session_id: &SessionId) {
+        self.sessions.remove(session_id);
+    }
+
+    fn apply_patch(
+        &mut self,
+        session_id: &SessionId,
+        revision: u64,
+        changes: &serde_json::Map<String, serde_json::Value>,
+    ) -> PatchOutcome {
+        let Some(cached) = self.sessions.get(session_id) else {
+            return PatchOutcome::Gap;
+        };
+        if revision <= cached.revision {
+            return PatchOutcome::Stale;
+        }
+        if cached.revision.checked_add(1) != Some(revision) {
+            return PatchOutcome::Gap;
+        }
+
+        match apply_session_patch(cached, revision, changes) {
+            Ok(view) => {
+                self.sessions.insert(session_id.clone(), view.clone());
+                PatchOutcome::Applied(Box::new(view))
+            }
+            Err(e) => {
+                warn!(session_id = %session_id, error = %e, "Failed to apply session patch");
+                PatchOutcome::Gap
+            }
+        }
+    }
+}
+
+/// State scoped to a single daemon connection; reset on reconnect.
+#[derive(Debug, Default)]
+struct ConnectionState {
+    /// Requests the daemon hasn't answered yet
+    pending: PendingRequests,
+    /// Session views patches are applied to
+    sessions: SessionCache,
+    /// A patch could not be applied; the cache needs a full `ListSessions`
+    gap_detected: bool,
+    /// The resync `ListSessions` has been sent but not yet answered
+    resync_requested: bool,
+    /// Protocol version the daemon reported in its handshake
+    daemon_version: ProtocolVersion,
+}
 
-/// Default Unix socket path the daemon listens on.
-pub const DEFAULT_SOCKET_PATH: &str = "/tmp/atm.sock";
+impl ConnectionState {
+    /// Returns true once per detected gap, when a resync should be sent.
+    fn take_resync_request(&mut self) -> bool {
+        if self.gap_detected && !self.resync_requested {
+            self.resync_requested = true;
+            return true;
+        }
+        false
+    }
+}
 
 /// Resolves the daemon socket path, honoring the `ATM_SOCKET` env var.
 ///
-/// Mirrors `atmd`'s behavior so a daemon and its clients pointed at a
-/// non-default socket (test sandboxes, multi-tenant setups) stay in sync.
+/// Without an override this is the per-user socket
+/// (`$XDG_RUNTIME_DIR/atm/atm.sock`), falling back to a legacy
+/// `/tmp/atm.sock` owned by this user while an older daemon is still
+/// running. See [`atm_protocol::socket`].
 ///
 /// `ATM_SOCKET` set but empty is treated as unset — connecting to an
 /// empty path would just produce a confusing "No such file" error.
 pub fn resolve_socket_path() -> PathBuf {
-    std::env::var_os("ATM_SOCKET")
-        .filter(|v| !v.is_empty())
-        .map(PathBuf::from)
-        .unwrap_or_else(|| PathBuf::from(DEFAULT_SOCKET_PATH))
+    socket::client_socket_path()
 }
 
 // ============================================================================
@@ -73,18 +189,22 @@ pub struct DaemonConfig {
 
     /// Multiplier for exponential backoff (e.g., 2.0 doubles delay each retry).
     pub retry_multiplier: f64,
+
+    /// Sessions to subscribe to; the daemon drops everything else.
+    pub subscription: SubscriptionFilter,
 }
 
 impl Default for DaemonConfig {
     fn default() -> Self {
         // Honor `$ATM_SOCKET` so an isolated test daemon (and its
-        // matching atm TUI) doesn't collide with the system-wide
-        // daemon at `/tmp/atm.sock`. Mirrors `atmd` and `atm-hook`.
+        // matching atm TUI) doesn't collide with the user's regular
+        // daemon. Mirrors `atmd` and `atm-hook`.
         Self {
             socket_path: resolve_socket_path(),
             retry_initial_delay: Duration::from_secs(1),
             retry_max_delay: Duration::from_secs(30),
             retry_multiplier: 2.0,
+            subscription: SubscriptionFilter::default(),
         }
     }
 }
@@ -135,6 +255,9 @@ pub struct DaemonClient {
 
     /// Cancellation token for graceful shutdown.
     cancel_token: CancellationToken,
+
+    /// Source of request IDs (unique across reconnects).
+    next_request_id: AtomicU64,
 }
 
 impl DaemonClient {
@@ -162,6 +285,7 @@ impl DaemonClient {
             event_tx,
             command_rx: tokio::sync::Mutex::new(command_rx),
             cancel_token,
+            next_request_id: AtomicU64::new(1),
         }
     }
 
@@ -330,10 +454,11 @@ impl DaemonClient {
         buf_reader.read_line(&mut line).await?;
 
         let response: DaemonMessage = serde_json::from_str(line.trim())?;
-        match response {
+        let daemon_version = match response {
             DaemonMessage::Connected {
                 protocol_version,
                 client_id,
+                ..
             } => {
                 // Verify protocol version compatibility
                 if !ProtocolVersion::CURRENT.is_compatible_with(&protocol_version) {
@@ -347,10 +472,10 @@ impl DaemonClient {
                     protocol_version = %protocol_version,
                     "Handshake complete"
                 );
+                protocol_version
             }
             DaemonMessage::Rejected {
-                reason: _,
-                protocol_version,
+                protocol_version, ..
             } => {
                 return Err(TuiError::VersionMismatch {
                     client_version: ProtocolVersion::CURRENT.to_string(),
@@ -362,18 +487,36 @@ impl DaemonClient {
                     "Unexpected response to connect: {response:?}"
                 )));
             }
-        }
+        };
+
+        // Dropped on disconnect, which fails any waiters still pending.
+        let mut state = ConnectionState {
+            daemon_version,
+            ..Default::default()
+        };
 
         // Subscribe to session updates
-        let subscribe_msg = ClientMessage::subscribe(None);
-        self.send_message(&mut writer, &subscribe_msg).await?;
+        let subscribe_msg = ClientMessage::subscribe_filtered(self.config.subscription.clone());
+        self.send_request(
+            &mut writer,
+            &mut state,
+            PendingRequest::Subscribe,
+            subscribe_msg,
+        )
+        .await?;
 
         // Request initial session list
         let list_msg = ClientMessage::list_sessions();
-        self.send_message(&mut writer, &list_msg).await?;
+        self.send_request(
+            &mut writer,
+            &mut state,
+            PendingRequest::ListSessions,
+            list_msg,
+        )
+        .await?;
 
         // Read messages and handle commands in a loop
-        self.message_loop(&mut buf_reader, &mut writer).await
+        self.message_loop(&mut buf_reader, &mut writer, state).await
     }
 
     /// Sends a message to the daemon.
@@ -413,12 +556,18 @@ impl DaemonClient {
     ///
     /// * `reader` - Buffered reader for the Unix socket
     /// * `writer` - Writer for sending messages to the daemon
+    /// * `state` - Connection state, with the requests sent so far
     ///
     /// # Returns
     ///
     /// * `Ok(())` - Connection closed gracefully (EOF)
     /// * `Err(TuiError)` - Read or parse error
-    async fn message_loop<R, W>(&self, reader: &mut R, writer: &mut W) -> Result<()>
+    async fn message_loop<R, W>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+        mut state: ConnectionState,
+    ) -> Result<()>
     where
         R: AsyncBufReadExt + Unpin,
         W: AsyncWriteExt + Unpin,
@@ -449,10 +598,18 @@ impl DaemonClient {
                         }
                         Ok(_) => {
                             // Parse and handle message
-                            if let Err(e) = self.handle_message(line.trim()).await {
+                            if let Err(e) = self.handle_message(line.trim(), &mut state) {
                                 warn!(error = %e, line = %line.trim(), "Failed to handle message");
                                 // Continue reading - don't disconnect on single parse error
                             }
+                            if state.take_resync_request() {
+                                debug!("Session revision gap, resyncing");
+                                let list_msg = ClientMessage::list_sessions();
+                                let request = PendingRequest::ListSessions;
+                                if let Err(e) = self.send_request(writer, &mut state, request, list_msg).await {
+                                    warn!(error = %e, "Failed to send resync request");
+                                }
+                            }
                         }
                         Err(e) => {
                             return Err(TuiError::Io(e));
@@ -467,10 +624,36 @@ impl DaemonClient {
                         Some(ClientCommand::Discover) => {
                             debug!("Received discover command from TUI");
                             let discover_msg = ClientMessage::discover();
-                            if let Err(e) = self.send_message(writer, &discover_msg).await {
+                            let request = PendingRequest::Discover;
+                            if let Err(e) = self.send_request(writer, &mut state, request, discover_msg).await {
                                 warn!(error = %e, "Failed to send discover message");
                             }
                         }
+                        Some(ClientCommand::KillSession(target)) => {
+                            let msg = ClientMessage::kill_session(target);
+                            self.send_control(writer, &mut state, "Kill", msg).await;
+                        }
+                        Some(ClientCommand::InterruptSession(target)) => {
+                            let msg = ClientMessage::interrupt_session(target);
+                            self.send_control(writer, &mut state, "Interrupt", msg).await;
+                        }
+                        Some(ClientCommand::ReplyPrompt { target, choice }) => {
+                            let msg = ClientMessage::reply_prompt(target, choice);
+                            if !msg.message.is_supported_by(&state.daemon_version) {
+                                let reason = format!(
+                                    "atmd {} is too old to type replies; restart it to upgrade",
+                                    state.daemon_version
+                                );
+                                warn!(%reason, "Not sending reply");
+                                let _ = self.event_tx.send(Event::ControlFailed(reason));
+                            } else {
+                                self.send_control(writer, &mut state, "Reply", msg).await;
+                            }
+                        }
+                        Some(ClientCommand::SendInput { target, text }) => {
+                            let msg = ClientMessage::send_input(target, text);
+                            self.send_control(writer, &mut state, "Send", msg).await;
+                        }
                         None => {
                             // Command channel closed - TUI shutting down
                             debug!("Command channel closed");
@@ -489,63 +672,154 @@ impl DaemonClient {
         }
     }
 
+    /// Sends `message` tagged with a fresh request ID and records what it
+    /// was for, so the daemon's answer can be routed by that ID.
+    async fn send_request<W: AsyncWriteExt + Unpin>(
+        &self,
+        writer: &mut W,
+        state: &mut ConnectionState,
+        request: PendingRequest,
+        message: ClientMessage,
+    ) -> Result<()> {
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        self.send_message(writer, &message.with_request_id(id))
+            .await?;
+        state.pending.insert(id, request);
+        Ok(())
+    }
+
+    /// Sends a control request via [`Self::send_request`]. Failures to
+    /// send are reported to the TUI like a daemon error would be.
+    async fn send_control<W: AsyncWriteExt + Unpin>(
+        &self,
+        writer: &mut W,
+        state: &mut ConnectionState,
+        action: &'static str,
+        message: ClientMessage,
+    ) {
+        let request = PendingRequest::Control(action);
+        if let Err(e) = self.send_request(writer, state, request, message).await {
+            warn!(error = %e, action, "Failed to send control request");
+            let _ = self
+                .event_tx
+                .send(Event::ControlFailed(format!("{action} failed: {e}")));
+        }
+    }
+
     /// Handles a single message from the daemon.
     ///
-    /// Parses the JSON message and forwards relevant events to the TUI.
+    /// Parses the JSON message and matches a tagged reply to the request
+    /// it answers. An `Error` answering one of our requests is shown in
+    /// the TUI; everything else is forwarded as usual.
     ///
     /// # Arguments
     ///
     /// * `line` - Raw JSON message string
+    /// * `state` - Pending requests and session cache for this connection
     ///
     /// # Returns
     ///
     /// * `Ok(())` - Message handled successfully
     /// * `Err(TuiError)` - Failed to parse message
-    async fn handle_message(&self, line: &str) -> Result<()> {
+    fn handle_message(&self, line: &str, state: &mut ConnectionState) -> Result<()> {
         let message: DaemonMessage = serde_json::from_str(line)?;
 
+        let request = message
+            .request_id()
+            .and_then(|id| state.pending.remove(&id));
+        match (request, message) {
+            (Some(request), DaemonMessage::Error { message, code, .. }) => {
+                let label = request.label();
+                warn!(request = label, error_message = %message, error_code = ?code, "Request failed");
+                if request == PendingRequest::ListSessions {
+                    // Let the next message retry the resync
+                    state.resync_requested = false;
+                }
+                let _ = self
+                    .event_tx
+                    .send(Event::ControlFailed(format!("{label} failed: {message}")));
+            }
+            (_, message) => self.dispatch_message(message, state),
+        }
+        Ok(())
+    }
+
+    /// Forwards an untagged (or unclaimed) daemon message to the TUI,
+    /// keeping the connection's session cache in step.
+    fn dispatch_message(&self, message: DaemonMessage, state: &mut ConnectionState) {
         match message {
-            DaemonMessage::SessionList { sessions } => {
+            DaemonMessage::SessionList { sessions, .. } => {
                 debug!(count = sessions.len(), "Received session list");
+                state.sessions.replace_all(&sessions);
+                state.gap_detected = false;
+                state.resync_requested = false;
                 // Full list replaces all sessions (initial sync)
                 let _ = self.event_tx.send(Event::SessionListReplace(sessions));
             }
             DaemonMessage::SessionUpdated { session } => {
                 debug!(session_id = %session.id, "Received session update");
+                state.sessions.upsert(&session);
                 // Individual update merges with existing sessions
                 let _ = self.event_tx.send(Event::SessionUpdate(vec![*session]));
             }
-            DaemonMessage::SessionRemoved { session_id } => {
+            DaemonMessage::SessionPatch {
+                session_id,
+                revision,
+                changes,
+            } => match state.sessions.apply_patch(&session_id, revision, &changes) {
+                PatchOutcome::Applied(session) => {
+                    debug!(session_id = %session_id, revision, "Applied session patch");
+                    let _ = self.event_tx.send(Event::SessionUpdate(vec![*session]));
+                }
+                PatchOutcome::Stale => {
+                    debug!(session_id = %session_id, revision, "Ignoring stale session patch");
+                }
+                PatchOutcome::Gap => {
+                    debug!(session_id = %session_id, revision, "Missed session revision");
+                    state.gap_detected = true;
+                }
+            },
+            DaemonMessage::SessionRemoved {
+                session_id,
+                replaced_by,
+            } => {
                 debug!(session_id = %session_id, "Session removed");
-                let _ = self
-                    .event_tx
-                    .send(Event::SessionRemoved(session_id.to_string()));
+                state.sessions.remove(&session_id);
+                let _ = self.event_tx.send(Event::SessionRemoved {
+                    session_id: session_id.to_string(),
+                    replaced_by,
+                });
             }
-            DaemonMessage::DiscoveryComplete { discovered, failed } => {
+            DaemonMessage::DiscoveryComplete {
+                discovered, failed, ..
+            } => {
                 debug!(discovered, failed, "Discovery complete");
                 let _ = self
                     .event_tx
                     .send(Event::DiscoveryComplete { discovered, failed });
             }
-            DaemonMessage::Pong { seq } => {
+            DaemonMessage::Pong { seq, .. } => {
                 debug!(seq, "Received pong");
                 // Pong responses are for connection health checks
                 // Currently not used, but could track latency
             }
-            DaemonMessage::Error { message, code } => {
+            DaemonMessage::Error { message, code, .. } => {
                 warn!(
                     error_message = %message,
                     error_code = ?code,
                     "Received error from daemon"
                 );
             }
+            DaemonMessage::ControlResult {
+                action, pane_id, ..
+            } => {
+                debug!(?action, pane_id = %pane_id, "Control action completed");
+            }
             DaemonMessage::Connected { .. } | DaemonMessage::Rejected { .. } => {
                 // These should only appear during handshake
                 warn!("Received unexpected handshake message after connection");
             }
         }
-
-        Ok(())
     }
 }
 
@@ -557,6 +831,7 @@ impl DaemonClient {
 mod tests {
     use super::*;
     use atm_core::{SessionId, SessionView};
+    use atm_protocol::ControlAction;
     use std::time::Duration;
 
     // ------------------------------------------------------------------------
@@ -571,11 +846,13 @@ mod tests {
         // harness.
         let config = DaemonConfig::default();
 
-        let expected_socket = match std::env::var("ATM_SOCKET") {
-            Ok(s) if !s.is_empty() => PathBuf::from(s),
-            _ => PathBuf::from(DEFAULT_SOCKET_PATH),
-        };
-        assert_eq!(config.socket_path, expected_socket);
+        match std::env::var("ATM_SOCKET") {
+            Ok(s) if !s.is_empty() => assert_eq!(config.socket_path, PathBuf::from(s)),
+            _ => assert!(
+                config.socket_path == socket::default_socket_path()
+                    || config.socket_path == std::path::Path::new(socket::LEGACY_SOCKET_PATH)
+            ),
+        }
         assert_eq!(config.retry_initial_delay, Duration::from_secs(1));
         assert_eq!(config.retry_max_delay, Duration::from_secs(30));
         assert!((config.retry_multiplier - 2.0).abs() < f64::EPSILON);
@@ -588,6 +865,7 @@ mod tests {
             retry_initial_delay: Duration::from_millis(500),
             retry_max_delay: Duration::from_secs(60),
             retry_multiplier: 1.5,
+            subscription: SubscriptionFilter::default(),
         };
 
         assert_eq!(config.socket_path, PathBuf::from("/custom/path.sock"));
@@ -692,7 +970,9 @@ mod tests {
         let msg = DaemonMessage::session_list(sessions.clone());
         let json = serde_json::to_string(&msg).unwrap();
 
-        client.handle_message(&json).await.unwrap();
+        client
+            .handle_message(&json, &mut ConnectionState::default())
+            .unwrap();
 
         // Check that event was sent
         let event = rx.try_recv().unwrap();
@@ -713,7 +993,9 @@ mod tests {
         let msg = DaemonMessage::session_updated(session);
         let json = serde_json::to_string(&msg).unwrap();
 
-        client.handle_message(&json).await.unwrap();
+        client
+            .handle_message(&json, &mut ConnectionState::default())
+            .unwrap();
 
         let event = rx.try_recv().unwrap();
         match event {
@@ -733,13 +1015,19 @@ mod tests {
         let json = serde_json::to_string(&msg).unwrap();
 
         // Should not error
-        client.handle_message(&json).await.unwrap();
+        client
+            .handle_message(&json, &mut ConnectionState::default())
+            .unwrap();
 
         // SessionRemoved event is now sent
         let event = rx.try_recv().unwrap();
         match event {
-            Event::SessionRemoved(session_id) => {
+            Event::SessionRemoved {
+                session_id,
+                replaced_by,
+            } => {
                 assert_eq!(session_id, "session-removed");
+                assert!(replaced_by.is_none());
             }
             _ => panic!("Expected SessionRemoved event"),
         }
@@ -752,7 +1040,9 @@ mod tests {
         let msg = DaemonMessage::pong(42);
         let json = serde_json::to_string(&msg).unwrap();
 
-        client.handle_message(&json).await.unwrap();
+        client
+            .handle_message(&json, &mut ConnectionState::default())
+            .unwrap();
 
         // Pong doesn't generate an event
         assert!(rx.try_recv().is_err());
@@ -765,7 +1055,9 @@ mod tests {
         let msg = DaemonMessage::error("test error");
         let json = serde_json::to_string(&msg).unwrap();
 
-        client.handle_message(&json).await.unwrap();
+        client
+            .handle_message(&json, &mut ConnectionState::default())
+            .unwrap();
 
         // Error doesn't generate an event to the TUI
         assert!(rx.try_recv().is_err());
@@ -775,7 +1067,7 @@ mod tests {
     async fn test_handle_message_invalid_json() {
         let (client, _rx) = create_test_client();
 
-        let result = client.handle_message("not valid json").await;
+        let result = client.handle_message("not valid json", &mut ConnectionState::default());
 
         assert!(result.is_err());
     }
@@ -787,7 +1079,9 @@ mod tests {
         let msg = DaemonMessage::discovery_complete(5, 2);
         let json = serde_json::to_string(&msg).unwrap();
 
-        client.handle_message(&json).await.unwrap();
+        client
+            .handle_message(&json, &mut ConnectionState::default())
+            .unwrap();
 
         let event = rx.try_recv().unwrap();
         match event {
@@ -799,6 +1093,172 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_handle_message_reports_control_errors() {
+        let (client, mut rx) = create_test_client();
+        let mut state = ConnectionState::default();
+        state.pending.insert(1, PendingRequest::Control("Kill"));
+        state.pending.insert(2, PendingRequest::Control("Reply"));
+
+        // Replies arrive out of order relative to the requests
+        let reply_b = DaemonMessage::error("no prompt is waiting").with_request_id(Some(2));
+        let reply_a =
+            DaemonMessage::control_result(ControlAction::Kill, None, "%1".to_string(), Vec::new())
+                .with_request_id(Some(1));
+        for msg in [reply_b, reply_a] {
+            let json = serde_json::to_string(&msg).unwrap();
+            client.handle_message(&json, &mut state).unwrap();
+        }
+
+        assert!(state.pending.is_empty());
+        match rx.try_recv().unwrap() {
+            Event::ControlFailed(reason) => {
+                assert_eq!(reason, "Reply failed: no prompt is waiting");
+            }
+            other => panic!("Expected ControlFailed, got {other:?}"),
+        }
+        assert!(
+            rx.try_recv().is_err(),
+            "a successful control is not reported"
+        );
+
+        // Errors for requests we didn't make stay in the log
+        let stray = DaemonMessage::error("stray").with_request_id(Some(99));
+        let json = serde_json::to_string(&stray).unwrap();
+        client.handle_message(&json, &mut state).unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_message_routes_overlapping_list_and_discover() {
+        let (client, mut rx) = create_test_client();
+        let mut state = ConnectionState::default();
+        state.pending.insert(1, PendingRequest::ListSessions);
+        state.pending.insert(2, PendingRequest::Discover);
+        state.pending.insert(3, PendingRequest::ListSessions);
+        state.pending.insert(4, PendingRequest::Discover);
+        state.gap_detected = true;
+        state.resync_requested = true;
+
+        // The second refresh is refused while the first is answered
+        let replies = [
+            DaemonMessage::error("registry busy").with_request_id(Some(3)),
+            DaemonMessage::session_list(vec![create_test_session("listed")])
+                .with_request_id(Some(1)),
+            DaemonMessage::discovery_complete(2, 0).with_request_id(Some(4)),
+            DaemonMessage::error("scan already running").with_request_id(Some(2)),
+        ];
+        for msg in replies {
+            let json = serde_json::to_string(&msg).unwrap();
+            client.handle_message(&json, &mut state).unwrap();
+        }
+        assert!(state.pending.is_empty());
+
+        match rx.try_recv().unwrap() {
+            Event::ControlFailed(reason) => assert_eq!(reason, "Refresh failed: registry busy"),
+            other => panic!("Expected ControlFailed, got {other:?}"),
+        }
+        match rx.try_recv().unwrap() {
+            Event::SessionListReplace(sessions) => assert_eq!(sessions.len(), 1),
+            other => panic!("Expected SessionListReplace, got {other:?}"),
+        }
+        assert!(!state.gap_detected);
+        match rx.try_recv().unwrap() {
+            Event::DiscoveryComplete { discovered, failed } => {
+                assert_eq!((discovered, failed), (2, 0));
+            }
+            other => panic!("Expected DiscoveryComplete, got {other:?}"),
+        }
+        match rx.try_recv().unwrap() {
+            Event::ControlFailed(reason) => {
+                assert_eq!(reason, "Discover failed: scan already running");
+            }
+            other => panic!("Expected ControlFailed, got {other:?}"),
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    fn patch_line(id: &str, revision: u64, cost_usd: f64) -> String {
+        let mut changes = serde_json::Map::new();
+        changes.insert("cost_usd".to_string(), serde_json::json!(cost_usd));
+        let msg = DaemonMessage::session_patch(SessionId::new(id), revision, changes);
+        serde_json::to_string(&msg).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_handle_message_applies_session_patch() {
+        let (client, mut rx) = create_test_client();
+        let mut state = ConnectionState::default();
+
+        let mut base = create_test_session("patched");
+        base.revision = 3;
+        let json = serde_json::to_string(&DaemonMessage::session_updated(base)).unwrap();
+        client.handle_message(&json, &mut state).unwrap();
+        let _ = rx.try_recv().unwrap();
+
+        client
+            .handle_message(&patch_line("patched", 4, 2.5), &mut state)
+            .unwrap();
+
+        match rx.try_recv().unwrap() {
+            Event::SessionUpdate(sessions) => {
+                assert_eq!(sessions.len(), 1);
+                assert_eq!(sessions[0].revision, 4);
+                assert_eq!(sessions[0].cost_usd, 2.5);
+                assert_eq!(sessions[0].model, "Opus 4.5");
+            }
+            _ => panic!("Expected SessionUpdate event"),
+        }
+
+        // Replaying the same revision is ignored
+        client
+            .handle_message(&patch_line("patched", 4, 9.0), &mut state)
+            .unwrap();
+        assert!(rx.try_recv().is_err());
+        assert!(!state.take_resync_request());
+    }
+
+    #[tokio::test]
+    async fn test_session_patch_gap_requests_one_resync() {
+        let (client, mut rx) = create_test_client();
+        let mut state = ConnectionState::default();
+
+        let mut base = create_test_session("gappy");
+        base.revision = 1;
+        let list = DaemonMessage::session_list(vec![base.clone()]);
+        client
+            .handle_message(&serde_json::to_string(&list).unwrap(), &mut state)
+            .unwrap();
+        let _ = rx.try_recv().unwrap();
+
+        // Revision 2 went missing
+        client
+            .handle_message(&patch_line("gappy", 3, 1.0), &mut state)
+            .unwrap();
+        client
+            .handle_message(&patch_line("unknown", 1, 1.0), &mut state)
+            .unwrap();
+        assert!(
+            rx.try_recv().is_err(),
+            "gapped patches must not reach the TUI"
+        );
+        assert!(state.take_resync_request());
+        assert!(!state.take_resync_request(), "only one resync in flight");
+
+        // The resync answer clears the gap
+        base.revision = 3;
+        let list = DaemonMessage::session_list(vec![base]);
+        client
+            .handle_message(&serde_json::to_string(&list).unwrap(), &mut state)
+            .unwrap();
+        assert!(!state.take_resync_request());
+        client
+            .handle_message(&patch_line("gappy", 4, 1.5), &mut state)
+            .unwrap();
+        let _ = rx.try_recv().unwrap();
+        assert!(matches!(rx.try_recv().unwrap(), Event::SessionUpdate(_)));
+    }
+
     // ------------------------------------------------------------------------
     // Exponential Backoff Tests
     // ------------------------------------------------------------------------
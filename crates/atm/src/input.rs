@@ -6,9 +6,10 @@
 //! All code follows the panic-free policy: no `.unwrap()`, `.expect()`,
 //! `panic!()`, `unreachable!()`, `todo!()`, or direct indexing `[i]`.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use atm_core::SessionView;
+use atm_core::{SessionId, SessionView};
+use atm_protocol::ReplyChoice;
 use crossterm::event::KeyEvent;
 
 // ============================================================================
@@ -37,7 +38,12 @@ pub enum Event {
     DaemonDisconnected,
 
     /// A session was removed from the daemon.
-    SessionRemoved(String),
+    SessionRemoved {
+        /// ID of the removed session
+        session_id: String,
+        /// ID the session continues under, when it was only renamed
+        replaced_by: Option<SessionId>,
+    },
 
     /// Updated pane capture output for a specific pane.
     CaptureUpdate { pane_id: String, lines: Vec<String> },
@@ -45,6 +51,13 @@ pub enum Event {
     /// Updated set of tmux pane IDs that belong to the filtered tmux session.
     FilterUpdate(HashSet<String>),
 
+    /// Updated tmux session name and window index of every pane, keyed by
+    /// pane ID.
+    PaneWindows(HashMap<String, (String, u32)>),
+
+    /// A control action (kill, interrupt, reply, input) failed.
+    ControlFailed(String),
+
     /// Discovery operation completed.
     DiscoveryComplete {
         /// Number of sessions discovered.
@@ -59,10 +72,28 @@ pub enum Event {
 // ============================================================================
 
 /// Commands that can be sent to the daemon client from the main loop.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub enum ClientCommand {
     /// Request session discovery from the daemon.
     Discover,
+    /// Ask the daemon to kill the session's pane (target: session ID).
+    KillSession(String),
+    /// Ask the daemon to interrupt the session (target: session ID).
+    InterruptSession(String),
+    /// Ask the daemon to answer the session's prompt.
+    ReplyPrompt {
+        /// Session ID or pane ID.
+        target: String,
+        /// Which answer to give.
+        choice: ReplyChoice,
+    },
+    /// Ask the daemon to type text into the session's pane and submit it.
+    SendInput {
+        /// Session ID or pane ID.
+        target: String,
+        /// Text to type.
+        text: String,
+    },
 }
 
 // ============================================================================
@@ -1,16 +1,20 @@
 //! Vim-style keybinding system for the ATM TUI.
 //!
-//! Implements a 3-state DFA parsing the vim grammar `[count] [motion]`,
-//! backed by a `VimKeyResolver` that maps `KeyEvent` to `KeyMeaning`.
+//! Implements a DFA parsing the vim grammar `[count] chord`, where chords
+//! (`j`, `gg`, `zM`, `<C-d>`) are looked up in a [`Keymap`] — the built-in
+//! bindings, overridden by the user's `[keys]` config table.
 //!
-//! Pending states (count accumulation, `g` prefix) persist until the next
-//! keypress resolves or cancels them. There is no time-based timeout.
+//! Pending states (count accumulation, a partly typed chord) persist until
+//! the next keypress resolves or cancels them. There is no time-based
+//! timeout.
 //!
 //! All code follows the panic-free policy: saturating arithmetic,
 //! no unwrap/expect/panic.
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
+use crate::keymap::{Chord, ChordMatch, Key, Keymap};
+
 /// Maximum count value. Inputs that would exceed this are clamped.
 const MAX_COUNT: usize = 9999;
 
@@ -56,10 +60,17 @@ pub enum UiAction {
     /// Toggle the fold at the cursor (`za`). On a leaf, walks up to the
     /// nearest ancestor and toggles that.
     ToggleFold,
-    /// Kill the selected agent and close its tmux pane.
+    /// Kill the selected agent and close its tmux pane, or every marked
+    /// agent after confirmation.
     KillAgent,
-    /// Interrupt the selected agent (SIGINT).
+    /// Interrupt the selected agent (SIGINT), or every marked agent after
+    /// confirmation.
     InterruptAgent,
+    /// Mark or unmark the selected agent, or every agent in the selected
+    /// group (`m`, `Space`).
+    ToggleMark,
+    /// Unmark every agent (`u`).
+    ClearMarks,
     /// Spawn a new agent using smart placement (largest non-ATM pane, below).
     SpawnAgent,
     /// Spawn a new agent to the left of the target pane.
@@ -70,6 +81,132 @@ pub enum UiAction {
     SpawnAgentAbove,
     /// Spawn a new agent below the target pane.
     SpawnAgentBelow,
+    /// Open the reply popup for the selected waiting agent (`rr`).
+    OpenReply,
+    /// Open the reply popup straight into free-text entry (`ri`).
+    OpenReplyText,
+    /// Confirm the selected agent's prompt without opening the popup (`ry`).
+    ReplyAccept,
+    /// Dismiss the selected agent's prompt without opening the popup (`rn`).
+    ReplyReject,
+    /// Pick numbered option N of the selected agent's prompt (`r1`..`r9`).
+    ReplyOption(usize),
+    /// Open the search bar to filter the tree (`/`).
+    StartSearch,
+    /// Jump to the next search match (`n`).
+    SearchNext,
+    /// Jump to the previous search match (`N`).
+    SearchPrevious,
+    /// Cycle the status quick filter (`s`).
+    CycleStatusFilter,
+    /// Cycle the harness quick filter (`H`).
+    CycleHarnessFilter,
+    /// Cycle the order of agents in the tree (`S`).
+    CycleSort,
+    /// Cycle how the tree groups agents (`v`).
+    CycleGroup,
+}
+
+/// An action inside the reply popup.
+///
+/// While the popup is open, keys bypass the DFA and are resolved by
+/// [`resolve_reply_key`] instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplyPopupAction {
+    /// Move the popup cursor down one option.
+    Down,
+    /// Move the popup cursor up one option.
+    Up,
+    /// Pick numbered option N (1-based) and send it.
+    Pick(usize),
+    /// Send the option under the cursor, or the typed text.
+    Submit,
+    /// Dismiss the prompt in the agent's pane.
+    Reject,
+    /// Switch to free-text entry.
+    StartText,
+    /// Leave free-text entry, or close the popup.
+    Close,
+    /// Append a character to the typed text.
+    Char(char),
+    /// Delete the last typed character.
+    Backspace,
+    /// Quit the application.
+    Quit,
+}
+
+/// Resolves a key pressed while the reply popup is open.
+///
+/// `typing` is true during free-text entry, where printable keys are
+/// text rather than commands.
+#[must_use]
+pub fn resolve_reply_key(key: &KeyEvent, typing: bool) -> Option<ReplyPopupAction> {
+    if key.modifiers == KeyModifiers::CONTROL {
+        return match key.code {
+            KeyCode::Char('c') => Some(ReplyPopupAction::Quit),
+            _ => None,
+        };
+    }
+    match key.code {
+        KeyCode::Esc => Some(ReplyPopupAction::Close),
+        KeyCode::Enter => Some(ReplyPopupAction::Submit),
+        KeyCode::Backspace if typing => Some(ReplyPopupAction::Backspace),
+        KeyCode::Char(c) if typing => Some(ReplyPopupAction::Char(c)),
+        KeyCode::Down | KeyCode::Char('j') => Some(ReplyPopupAction::Down),
+        KeyCode::Up | KeyCode::Char('k') => Some(ReplyPopupAction::Up),
+        KeyCode::Char(c @ '1'..='9') => c.to_digit(10).map(|d| ReplyPopupAction::Pick(d as usize)),
+        KeyCode::Char('i') => Some(ReplyPopupAction::StartText),
+        KeyCode::Char('n') => Some(ReplyPopupAction::Reject),
+        KeyCode::Char('q') => Some(ReplyPopupAction::Close),
+        _ => None,
+    }
+}
+
+/// An action inside the search bar.
+///
+/// While the search bar is being typed into, keys bypass the DFA and are
+/// resolved by [`resolve_search_key`] instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchInputAction {
+    /// Append a character to the query.
+    Char(char),
+    /// Delete the last query character.
+    Backspace,
+    /// Clear the whole query (`Ctrl-u`).
+    ClearLine,
+    /// Keep the query as a filter and leave the bar (`Enter`).
+    Accept,
+    /// Drop the query and leave the bar (`Esc`).
+    Cancel,
+    /// Jump to the next match without leaving the bar.
+    Next,
+    /// Jump to the previous match without leaving the bar.
+    Previous,
+    /// Quit the application.
+    Quit,
+}
+
+/// Resolves a key pressed while typing into the search bar.
+#[must_use]
+pub fn resolve_search_key(key: &KeyEvent) -> Option<SearchInputAction> {
+    if key.modifiers == KeyModifiers::CONTROL {
+        return match key.code {
+            KeyCode::Char('c') => Some(SearchInputAction::Quit),
+            KeyCode::Char('u') => Some(SearchInputAction::ClearLine),
+            KeyCode::Char('n') => Some(SearchInputAction::Next),
+            KeyCode::Char('p') => Some(SearchInputAction::Previous),
+            _ => None,
+        };
+    }
+    match key.code {
+        KeyCode::Esc => Some(SearchInputAction::Cancel),
+        KeyCode::Enter => Some(SearchInputAction::Accept),
+        KeyCode::Backspace => Some(SearchInputAction::Backspace),
+        KeyCode::Down => Some(SearchInputAction::Next),
+        KeyCode::Up => Some(SearchInputAction::Previous),
+        KeyCode::Char(c) => Some(SearchInputAction::Char(c)),
+        _ => None,
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -79,244 +216,496 @@ pub enum UiAction {
 /// Category for grouping keybindings in the help popup.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum HintCategory {
-    /// Navigation bindings (movement, scrolling).
+    /// Navigation bindings (movement, scrolling, folds).
     Navigation,
     /// Action bindings (quit, refresh, jump, help).
     Actions,
+    /// Prompt answering bindings (`r` prefix and the reply popup).
+    Reply,
+    /// Search bar and quick filter bindings.
+    Search,
 }
 
-/// Metadata for a single keybinding, used by both the help popup and footer bar.
+/// A bindable action: its `[keys]` name and how the help popup lists it.
 ///
-/// Entries where `footer_key` is empty are not shown in the footer.
+/// The keys shown next to it come from the effective [`Keymap`].
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct KeybindingHint {
-    /// Key display for the help popup (e.g., "j / \u{2193}").
-    pub help_key: &'static str,
-    /// Description for the help popup (e.g., "Move down").
+pub(crate) struct ActionHint {
+    /// Name used in the `[keys]` config table (e.g., "move_down").
+    pub name: &'static str,
+    /// The action, with a count of 1 for count-taking actions.
+    pub action: UiAction,
+    /// Description for the help popup. Consecutive actions sharing a
+    /// description are listed as one row (`r1..r9`).
     pub help_desc: &'static str,
-    /// Key display for the footer bar (e.g., "j/\u{2193}"). Empty = not shown.
-    pub footer_key: &'static str,
-    /// Description for the footer bar (e.g., "down"). Empty = not shown.
-    pub footer_desc: &'static str,
     /// Category for help popup grouping.
     pub category: HintCategory,
     /// Only show when running inside tmux.
     pub tmux_only: bool,
 }
 
-/// All keybindings with display metadata, in presentation order.
+/// A help popup row for keys outside the keymap (popups, search bar).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FixedHint {
+    /// Key display (e.g., "Enter / Esc").
+    pub help_key: &'static str,
+    /// Description (e.g., "Search bar: keep filter / clear it").
+    pub help_desc: &'static str,
+    /// Category; the row is listed after that category's actions.
+    pub category: HintCategory,
+    /// Only show when running inside tmux.
+    pub tmux_only: bool,
+}
+
+/// A footer bar entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FooterHint {
+    /// Default chords shown for each action, as `[keys]` notation. A chord
+    /// the user rebound is replaced by the action's first effective chord.
+    pub keys: &'static [(&'static str, UiAction)],
+    /// Description (e.g., "down").
+    pub desc: &'static str,
+    /// Category; a separator is drawn between categories.
+    pub category: HintCategory,
+    /// Only show when running inside tmux.
+    pub tmux_only: bool,
+}
+
+const fn action(
+    name: &'static str,
+    action: UiAction,
+    help_desc: &'static str,
+    category: HintCategory,
+) -> ActionHint {
+    ActionHint {
+        name,
+        action,
+        help_desc,
+        category,
+        tmux_only: false,
+    }
+}
+
+const fn tmux_action(
+    name: &'static str,
+    action: UiAction,
+    help_desc: &'static str,
+    category: HintCategory,
+) -> ActionHint {
+    ActionHint {
+        name,
+        action,
+        help_desc,
+        category,
+        tmux_only: true,
+    }
+}
+
+/// Every bindable action, in help popup order.
 ///
-/// This is the single source of truth consumed by both the help popup
-/// and the footer status bar. When adding a new keybinding to the DFA,
-/// add a corresponding entry here.
-pub(crate) static KEYBINDING_HINTS: &[KeybindingHint] = &[
-    // -- Navigation ----------------------------------------------------------
-    KeybindingHint {
-        help_key: "j / \u{2193}",
-        help_desc: "Move down",
-        footer_key: "j/\u{2193}",
-        footer_desc: "down",
-        category: HintCategory::Navigation,
+/// When adding a new `UiAction`, add it here (so it can be named in
+/// `[keys]`) and give it a default chord in `keymap.rs`.
+pub(crate) static ACTION_HINTS: &[ActionHint] = {
+    use HintCategory::{Actions, Navigation, Reply, Search};
+    &[
+        // -- Navigation ------------------------------------------------------
+        action("move_down", UiAction::MoveDown(1), "Move down", Navigation),
+        action("move_up", UiAction::MoveUp(1), "Move up", Navigation),
+        action("go_to_first", UiAction::GoToFirst, "Go to top", Navigation),
+        action("go_to_last", UiAction::GoToLast, "Go to bottom", Navigation),
+        action(
+            "half_page_down",
+            UiAction::HalfPageDown(1),
+            "Half page down",
+            Navigation,
+        ),
+        action(
+            "half_page_up",
+            UiAction::HalfPageUp(1),
+            "Half page up",
+            Navigation,
+        ),
+        action(
+            "expand_node",
+            UiAction::ExpandNode,
+            "Expand collapsed group (open-only)",
+            Navigation,
+        ),
+        action("close_fold", UiAction::CloseFold, "Close fold", Navigation),
+        action(
+            "toggle_fold",
+            UiAction::ToggleFold,
+            "Toggle fold",
+            Navigation,
+        ),
+        action(
+            "collapse_all_folds",
+            UiAction::CollapseAllFolds,
+            "Collapse all folds",
+            Navigation,
+        ),
+        action(
+            "expand_all_folds",
+            UiAction::ExpandAllFolds,
+            "Expand all folds",
+            Navigation,
+        ),
+        // -- Actions ---------------------------------------------------------
+        tmux_action(
+            "jump_to_session",
+            UiAction::JumpToSession,
+            "Jump to session (leaf) / toggle fold (group)",
+            Actions,
+        ),
+        action("refresh", UiAction::Refresh, "Rescan / refresh", Actions),
+        action("quit", UiAction::Quit, "Quit", Actions),
+        action(
+            "toggle_help",
+            UiAction::ToggleHelp,
+            "Toggle this help",
+            Actions,
+        ),
+        action(
+            "toggle_mark",
+            UiAction::ToggleMark,
+            "Mark agent / whole group",
+            Actions,
+        ),
+        action("clear_marks", UiAction::ClearMarks, "Clear marks", Actions),
+        tmux_action(
+            "kill_agent",
+            UiAction::KillAgent,
+            "Kill agent (or marked) + close pane",
+            Actions,
+        ),
+        tmux_action(
+            "interrupt_agent",
+            UiAction::InterruptAgent,
+            "Interrupt agent (or marked)",
+            Actions,
+        ),
+        action(
+            "cycle_sort",
+            UiAction::CycleSort,
+            "Cycle sort (newest/cost/context/...)",
+            Actions,
+        ),
+        action(
+            "cycle_group",
+            UiAction::CycleGroup,
+            "Cycle grouping (project/tmux/...)",
+            Actions,
+        ),
+        tmux_action(
+            "spawn_agent",
+            UiAction::SpawnAgent,
+            "Spawn agent (smart placement)",
+            Actions,
+        ),
+        tmux_action(
+            "spawn_agent_left",
+            UiAction::SpawnAgentLeft,
+            "Spawn agent left",
+            Actions,
+        ),
+        tmux_action(
+            "spawn_agent_below",
+            UiAction::SpawnAgentBelow,
+            "Spawn agent below",
+            Actions,
+        ),
+        tmux_action(
+            "spawn_agent_above",
+            UiAction::SpawnAgentAbove,
+            "Spawn agent above",
+            Actions,
+        ),
+        tmux_action(
+            "spawn_agent_right",
+            UiAction::SpawnAgentRight,
+            "Spawn agent right",
+            Actions,
+        ),
+        // -- Reply -----------------------------------------------------------
+        tmux_action(
+            "open_reply",
+            UiAction::OpenReply,
+            "Reply popup for a waiting agent",
+            Reply,
+        ),
+        tmux_action(
+            "open_reply_text",
+            UiAction::OpenReplyText,
+            "Reply with free text",
+            Reply,
+        ),
+        tmux_action(
+            "reply_accept",
+            UiAction::ReplyAccept,
+            "Accept prompt",
+            Reply,
+        ),
+        tmux_action(
+            "reply_reject",
+            UiAction::ReplyReject,
+            "Dismiss prompt",
+            Reply,
+        ),
+        tmux_action(
+            "reply_option_1",
+            UiAction::ReplyOption(1),
+            "Pick option N",
+            Reply,
+        ),
+        tmux_action(
+            "reply_option_2",
+            UiAction::ReplyOption(2),
+            "Pick option N",
+            Reply,
+        ),
+        tmux_action(
+            "reply_option_3",
+            UiAction::ReplyOption(3),
+            "Pick option N",
+            Reply,
+        ),
+        tmux_action(
+            "reply_option_4",
+            UiAction::ReplyOption(4),
+            "Pick option N",
+            Reply,
+        ),
+        tmux_action(
+            "reply_option_5",
+            UiAction::ReplyOption(5),
+            "Pick option N",
+            Reply,
+        ),
+        tmux_action(
+            "reply_option_6",
+            UiAction::ReplyOption(6),
+            "Pick option N",
+            Reply,
+        ),
+        tmux_action(
+            "reply_option_7",
+            UiAction::ReplyOption(7),
+            "Pick option N",
+            Reply,
+        ),
+        tmux_action(
+            "reply_option_8",
+            UiAction::ReplyOption(8),
+            "Pick option N",
+            Reply,
+        ),
+        tmux_action(
+            "reply_option_9",
+            UiAction::ReplyOption(9),
+            "Pick option N",
+            Reply,
+        ),
+        // -- Search ----------------------------------------------------------
+        action(
+            "start_search",
+            UiAction::StartSearch,
+            "Search project, branch, prompt, model, ID",
+            Search,
+        ),
+        action("search_next", UiAction::SearchNext, "Next match", Search),
+        action(
+            "search_previous",
+            UiAction::SearchPrevious,
+            "Previous match",
+            Search,
+        ),
+        action(
+            "cycle_status_filter",
+            UiAction::CycleStatusFilter,
+            "Cycle status filter",
+            Search,
+        ),
+        action(
+            "cycle_harness_filter",
+            UiAction::CycleHarnessFilter,
+            "Cycle harness filter",
+            Search,
+        ),
+    ]
+};
+
+/// Help rows for keys the keymap does not own.
+pub(crate) static FIXED_HINTS: &[FixedHint] = &[
+    FixedHint {
+        help_key: "Esc",
+        help_desc: "Close help / clear filter / quit",
+        category: HintCategory::Actions,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "k / \u{2191}",
-        help_desc: "Move up",
-        footer_key: "k/\u{2191}",
-        footer_desc: "up",
-        category: HintCategory::Navigation,
-        tmux_only: false,
+    FixedHint {
+        help_key: "j/k 1-9",
+        help_desc: "Popup: choose option (Enter sends)",
+        category: HintCategory::Reply,
+        tmux_only: true,
     },
-    KeybindingHint {
-        help_key: "0 / gg",
-        help_desc: "Go to top",
-        footer_key: "gg",
-        footer_desc: "top",
-        category: HintCategory::Navigation,
+    FixedHint {
+        help_key: "i / n / Esc",
+        help_desc: "Popup: type text / dismiss / close",
+        category: HintCategory::Reply,
+        tmux_only: true,
+    },
+    FixedHint {
+        help_key: "Enter / Esc",
+        help_desc: "Search bar: keep filter / clear it",
+        category: HintCategory::Search,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "G",
-        help_desc: "Go to bottom",
-        footer_key: "G",
-        footer_desc: "end",
+];
+
+/// Footer bar entries, in display order.
+pub(crate) static FOOTER_HINTS: &[FooterHint] = &[
+    // -- Navigation ----------------------------------------------------------
+    FooterHint {
+        keys: &[
+            ("j", UiAction::MoveDown(1)),
+            ("<Down>", UiAction::MoveDown(1)),
+        ],
+        desc: "down",
         category: HintCategory::Navigation,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "Ctrl-d",
-        help_desc: "Half page down",
-        footer_key: "^d/^u",
-        footer_desc: "page",
+    FooterHint {
+        keys: &[("k", UiAction::MoveUp(1)), ("<Up>", UiAction::MoveUp(1))],
+        desc: "up",
         category: HintCategory::Navigation,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "Ctrl-u",
-        help_desc: "Half page up",
-        footer_key: "",
-        footer_desc: "",
+    FooterHint {
+        keys: &[("gg", UiAction::GoToFirst)],
+        desc: "top",
         category: HintCategory::Navigation,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "Ngg",
-        help_desc: "Go to row N",
-        footer_key: "",
-        footer_desc: "",
+    FooterHint {
+        keys: &[("G", UiAction::GoToLast)],
+        desc: "end",
         category: HintCategory::Navigation,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "Nj / Nk",
-        help_desc: "Move N rows",
-        footer_key: "",
-        footer_desc: "",
+    FooterHint {
+        keys: &[
+            ("<C-d>", UiAction::HalfPageDown(1)),
+            ("<C-u>", UiAction::HalfPageUp(1)),
+        ],
+        desc: "page",
         category: HintCategory::Navigation,
         tmux_only: false,
     },
     // -- Actions -------------------------------------------------------------
-    KeybindingHint {
-        help_key: "Enter",
-        help_desc: "Jump to session (leaf) / toggle fold (group)",
-        footer_key: "Enter",
-        footer_desc: "jump",
+    FooterHint {
+        keys: &[("<Enter>", UiAction::JumpToSession)],
+        desc: "jump",
         category: HintCategory::Actions,
         tmux_only: true,
     },
-    KeybindingHint {
-        help_key: "r",
-        help_desc: "Rescan / refresh",
-        footer_key: "r",
-        footer_desc: "rescan",
-        category: HintCategory::Actions,
-        tmux_only: false,
-    },
-    KeybindingHint {
-        help_key: "q",
-        help_desc: "Quit",
-        footer_key: "q",
-        footer_desc: "quit",
-        category: HintCategory::Actions,
-        tmux_only: false,
-    },
-    KeybindingHint {
-        help_key: "?",
-        help_desc: "Toggle this help",
-        footer_key: "?",
-        footer_desc: "help",
+    FooterHint {
+        keys: &[("R", UiAction::Refresh)],
+        desc: "rescan",
         category: HintCategory::Actions,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "Esc",
-        help_desc: "Close help / quit",
-        footer_key: "",
-        footer_desc: "",
+    FooterHint {
+        keys: &[("q", UiAction::Quit)],
+        desc: "quit",
         category: HintCategory::Actions,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "Ctrl-c",
-        help_desc: "Quit",
-        footer_key: "",
-        footer_desc: "",
+    FooterHint {
+        keys: &[("?", UiAction::ToggleHelp)],
+        desc: "help",
         category: HintCategory::Actions,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "h / l",
-        help_desc: "Expand collapsed group (open-only)",
-        footer_key: "h/l",
-        footer_desc: "open",
+    FooterHint {
+        keys: &[("h", UiAction::ExpandNode), ("l", UiAction::ExpandNode)],
+        desc: "open",
         category: HintCategory::Navigation,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "zc/zo/za",
-        help_desc: "Close / Open / Toggle fold (vim)",
-        footer_key: "",
-        footer_desc: "",
+    FooterHint {
+        keys: &[
+            ("zM", UiAction::CollapseAllFolds),
+            ("zR", UiAction::ExpandAllFolds),
+        ],
+        desc: "fold*",
         category: HintCategory::Navigation,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "zM / zR",
-        help_desc: "Collapse all / Expand all folds",
-        footer_key: "zM/zR",
-        footer_desc: "fold*",
-        category: HintCategory::Navigation,
+    FooterHint {
+        keys: &[("m", UiAction::ToggleMark)],
+        desc: "mark",
+        category: HintCategory::Actions,
         tmux_only: false,
     },
-    KeybindingHint {
-        help_key: "dd / x",
-        help_desc: "Kill agent + close pane",
-        footer_key: "dd",
-        footer_desc: "kill",
+    FooterHint {
+        keys: &[("dd", UiAction::KillAgent)],
+        desc: "kill",
         category: HintCategory::Actions,
         tmux_only: true,
     },
-    KeybindingHint {
-        help_key: "I",
-        help_desc: "Interrupt agent (SIGINT)",
-        footer_key: "I",
-        footer_desc: "int",
+    FooterHint {
+        keys: &[("I", UiAction::InterruptAgent)],
+        desc: "int",
         category: HintCategory::Actions,
         tmux_only: true,
     },
-    KeybindingHint {
-        help_key: "oo",
-        help_desc: "Spawn agent (smart placement)",
-        footer_key: "oo",
-        footer_desc: "spawn",
+    FooterHint {
+        keys: &[("oo", UiAction::SpawnAgent)],
+        desc: "spawn",
         category: HintCategory::Actions,
         tmux_only: true,
     },
-    KeybindingHint {
-        help_key: "oh/oj/ok/ol",
-        help_desc: "Spawn agent left/below/above/right",
-        footer_key: "",
-        footer_desc: "",
-        category: HintCategory::Actions,
+    // -- Reply ---------------------------------------------------------------
+    FooterHint {
+        keys: &[("rr", UiAction::OpenReply)],
+        desc: "reply",
+        category: HintCategory::Reply,
         tmux_only: true,
     },
+    // -- Search --------------------------------------------------------------
+    FooterHint {
+        keys: &[("/", UiAction::StartSearch)],
+        desc: "search",
+        category: HintCategory::Search,
+        tmux_only: false,
+    },
 ];
 
+impl FooterHint {
+    /// The keys to show for this entry under `keymap`, or an empty string
+    /// when every action it describes is unbound.
+    pub(crate) fn display_keys(&self, keymap: &Keymap) -> String {
+        let mut shown: Vec<String> = Vec::new();
+        for (default, action) in self.keys {
+            let still_bound = Chord::parse(default)
+                .ok()
+                .filter(|chord| keymap.action(chord) == Some(action));
+            let chord = still_bound.or_else(|| keymap.chords(action).next().cloned());
+            if let Some(chord) = chord {
+                let label = chord.to_string();
+                if !shown.contains(&label) {
+                    shown.push(label);
+                }
+            }
+        }
+        shown.join("/")
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Internal types
 // ---------------------------------------------------------------------------
 
-/// The semantic meaning of a single key press, before DFA processing.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) enum KeyMeaning {
-    /// A decimal digit (0-9).
-    Digit(u8),
-    /// A motion command.
-    Motion(MotionKind),
-    /// The `g` prefix key.
-    GPrefix,
-    /// The `d` prefix key (first `d` of `dd` kill).
-    DPrefix,
-    /// The `o` prefix key for directional spawn.
-    OPrefix,
-    /// The `z` prefix key for vim-style fold commands.
-    ZPrefix,
-    /// A self-contained action that needs no count or prefix.
-    SimpleAction(UiAction),
-    /// A key we don't recognise.
-    Unbound,
-}
-
-/// The set of motions that can be preceded by an optional count.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum MotionKind {
-    Down,
-    Up,
-    GoToBottom,
-    HalfPageDown,
-    HalfPageUp,
-}
-
 /// Internal DFA state.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 enum InputState {
@@ -325,93 +714,12 @@ enum InputState {
     Ready,
     /// Accumulating a numeric count prefix.
     Count(usize),
-    /// Received a `g` prefix, optionally preceded by a count.
-    PendingG { count: Option<usize> },
-    /// Received a `d` prefix, waiting for second `d` to confirm kill.
-    PendingD,
-    /// Received an `o` prefix, waiting for direction key (h/j/k/l).
-    PendingO,
-    /// Received a `z` prefix, waiting for a fold command (M/R/c/o/a).
-    PendingZ,
-}
-
-// ---------------------------------------------------------------------------
-// VimKeyResolver
-// ---------------------------------------------------------------------------
-
-/// Maps raw `KeyEvent` values to their semantic `KeyMeaning`.
-///
-/// This is a stateless, pure function object.
-#[derive(Debug, Clone, Default)]
-pub(crate) struct VimKeyResolver;
-
-impl VimKeyResolver {
-    /// Resolve a crossterm `KeyEvent` into a `KeyMeaning`.
-    #[must_use]
-    pub fn resolve(&self, key: &KeyEvent) -> KeyMeaning {
-        // Handle Ctrl combinations first (exact match to avoid triggering on Ctrl+Alt etc.).
-        if key.modifiers == KeyModifiers::CONTROL {
-            return match key.code {
-                KeyCode::Char('d') => KeyMeaning::Motion(MotionKind::HalfPageDown),
-                KeyCode::Char('u') => KeyMeaning::Motion(MotionKind::HalfPageUp),
-                KeyCode::Char('c') => KeyMeaning::SimpleAction(UiAction::Quit),
-                _ => KeyMeaning::Unbound,
-            };
-        }
-
-        // Reject other modifier combinations (Alt, Super, Hyper, Meta).
-        if key.modifiers.intersects(
-            KeyModifiers::ALT
-                .union(KeyModifiers::SUPER)
-                .union(KeyModifiers::HYPER)
-                .union(KeyModifiers::META),
-        ) {
-            return KeyMeaning::Unbound;
-        }
-
-        // Plain keys (no modifiers, or SHIFT which crossterm folds into the
-        // character for letters).
-        match key.code {
-            KeyCode::Char(c) => Self::resolve_char(c),
-            KeyCode::Down => KeyMeaning::Motion(MotionKind::Down),
-            KeyCode::Up => KeyMeaning::Motion(MotionKind::Up),
-            KeyCode::Enter => KeyMeaning::SimpleAction(UiAction::JumpToSession),
-            KeyCode::Esc => KeyMeaning::SimpleAction(UiAction::Quit),
-            _ => KeyMeaning::Unbound,
-        }
-    }
-
-    /// Resolve a plain character (no Ctrl/Alt).
-    fn resolve_char(c: char) -> KeyMeaning {
-        match c {
-            '0' => KeyMeaning::Digit(0),
-            '1' => KeyMeaning::Digit(1),
-            '2' => KeyMeaning::Digit(2),
-            '3' => KeyMeaning::Digit(3),
-            '4' => KeyMeaning::Digit(4),
-            '5' => KeyMeaning::Digit(5),
-            '6' => KeyMeaning::Digit(6),
-            '7' => KeyMeaning::Digit(7),
-            '8' => KeyMeaning::Digit(8),
-            '9' => KeyMeaning::Digit(9),
-            'j' => KeyMeaning::Motion(MotionKind::Down),
-            'k' => KeyMeaning::Motion(MotionKind::Up),
-            'G' => KeyMeaning::Motion(MotionKind::GoToBottom),
-            'g' => KeyMeaning::GPrefix,
-            'd' => KeyMeaning::DPrefix,
-            // `h` and `l` both open a collapsed fold. They never close —
-            // the z-chord family owns all close/toggle semantics.
-            'h' | 'l' => KeyMeaning::SimpleAction(UiAction::ExpandNode),
-            'o' => KeyMeaning::OPrefix,
-            'z' => KeyMeaning::ZPrefix,
-            'x' => KeyMeaning::SimpleAction(UiAction::KillAgent),
-            'I' => KeyMeaning::SimpleAction(UiAction::InterruptAgent),
-            'q' | 'Q' => KeyMeaning::SimpleAction(UiAction::Quit),
-            'r' | 'R' => KeyMeaning::SimpleAction(UiAction::Refresh),
-            '?' => KeyMeaning::SimpleAction(UiAction::ToggleHelp),
-            _ => KeyMeaning::Unbound,
-        }
-    }
+    /// The keys typed so far start a longer chord, optionally preceded by
+    /// a count.
+    Pending {
+        count: Option<usize>,
+        keys: Vec<Key>,
+    },
 }
 
 // ---------------------------------------------------------------------------
@@ -428,43 +736,54 @@ fn accumulate_digit(current: usize, digit: u8) -> usize {
         .min(MAX_COUNT)
 }
 
+/// The digit a key types, if it is `0`-`9`.
+fn count_digit(key: Key) -> Option<u8> {
+    match key {
+        Key::Char(c) => c.to_digit(10).and_then(|d| u8::try_from(d).ok()),
+        _ => None,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // InputHandler (public API)
 // ---------------------------------------------------------------------------
 
 /// Stateful DFA that consumes `KeyEvent`s and emits `UiAction`s.
 ///
-/// The grammar recognised is: `[count] motion | [count] g motion | action`.
-#[derive(Debug, Clone)]
+/// The grammar recognised is `[count] chord`, where chords come from the
+/// handler's [`Keymap`]. Digits 1-9 always start a count; `0` extends a
+/// count, and is an ordinary key otherwise. The count only affects motions
+/// and is discarded for every other action.
+#[derive(Debug, Clone, Default)]
 pub struct InputHandler {
     state: InputState,
-    resolver: VimKeyResolver,
-}
-
-impl Default for InputHandler {
-    fn default() -> Self {
-        Self {
-            state: InputState::default(),
-            resolver: VimKeyResolver,
-        }
-    }
+    keymap: Keymap,
 }
 
 impl InputHandler {
-    /// Create a new handler in the `Ready` state.
+    /// Create a new handler with the default keymap, in the `Ready` state.
     #[must_use]
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Create a handler that resolves chords with `keymap`.
+    #[must_use]
+    pub fn with_keymap(keymap: Keymap) -> Self {
+        Self {
+            state: InputState::Ready,
+            keymap,
+        }
+    }
+
     /// Reset the DFA to the `Ready` state, discarding any partial input.
     pub fn reset(&mut self) {
         self.state = InputState::Ready;
     }
 
     /// Returns `true` when the handler is **not** in the `Ready` state,
-    /// meaning it is accumulating a count or waiting for the second key of a
-    /// `g`-prefix.
+    /// meaning it is accumulating a count or waiting for the rest of a
+    /// chord.
     #[must_use]
     pub fn is_pending(&self) -> bool {
         self.state != InputState::Ready
@@ -476,163 +795,62 @@ impl InputHandler {
     /// when more input is needed (or the key was unrecognised).
     #[must_use]
     pub fn handle(&mut self, key: KeyEvent) -> Option<UiAction> {
-        let meaning = self.resolver.resolve(&key);
-        self.step(meaning, &key)
-    }
-
-    /// Execute a single DFA transition.
-    fn step(&mut self, meaning: KeyMeaning, key: &KeyEvent) -> Option<UiAction> {
         // Take ownership of current state, replacing with Ready (the most
-        // common target).
-        let prev = std::mem::replace(&mut self.state, InputState::Ready);
+        // common target). Unbindable keys cancel whatever was pending.
+        let prev = std::mem::take(&mut self.state);
+        let key = Key::from_event(&key)?;
 
         match prev {
-            InputState::Ready => self.step_ready(meaning),
-            InputState::Count(n) => self.step_count(n, meaning),
-            InputState::PendingG { count } => self.step_pending_g(count, meaning),
-            InputState::PendingD => self.step_pending_d(meaning),
-            InputState::PendingO => self.step_pending_o(key),
-            InputState::PendingZ => self.step_pending_z(key),
-        }
-    }
-
-    /// Transitions from the `Ready` state.
-    fn step_ready(&mut self, meaning: KeyMeaning) -> Option<UiAction> {
-        match meaning {
-            KeyMeaning::Digit(0) => Some(UiAction::GoToFirst),
-            KeyMeaning::Digit(d) => {
-                self.state = InputState::Count(d as usize);
-                None
-            }
-            KeyMeaning::GPrefix => {
-                self.state = InputState::PendingG { count: None };
-                None
-            }
-            KeyMeaning::DPrefix => {
-                self.state = InputState::PendingD;
-                None
-            }
-            KeyMeaning::OPrefix => {
-                self.state = InputState::PendingO;
-                None
-            }
-            KeyMeaning::ZPrefix => {
-                self.state = InputState::PendingZ;
-                None
+            InputState::Ready => match count_digit(key) {
+                Some(d) if d > 0 => {
+                    self.state = InputState::Count(d as usize);
+                    None
+                }
+                _ => self.step_chord(None, vec![key]),
+            },
+            InputState::Count(n) => match count_digit(key) {
+                Some(d) => {
+                    self.state = InputState::Count(accumulate_digit(n, d));
+                    None
+                }
+                None => self.step_chord(Some(n), vec![key]),
+            },
+            InputState::Pending { count, mut keys } => {
+                keys.push(key);
+                self.step_chord(count, keys)
             }
-            KeyMeaning::Motion(kind) => Self::motion_with_count(1, kind),
-            KeyMeaning::SimpleAction(action) => Some(action),
-            KeyMeaning::Unbound => None,
         }
     }
 
-    /// Transitions from the `Count(n)` state.
-    fn step_count(&mut self, n: usize, meaning: KeyMeaning) -> Option<UiAction> {
-        match meaning {
-            KeyMeaning::Digit(d) => {
-                self.state = InputState::Count(accumulate_digit(n, d));
-                None
-            }
-            KeyMeaning::GPrefix => {
-                self.state = InputState::PendingG { count: Some(n) };
-                None
-            }
-            KeyMeaning::DPrefix => {
-                // Count before dd is ignored (kill is always single target)
-                self.state = InputState::PendingD;
-                None
-            }
-            KeyMeaning::OPrefix => {
-                // Count before o is ignored (spawn is always single)
-                self.state = InputState::PendingO;
-                None
-            }
-            KeyMeaning::ZPrefix => {
-                // Count before z is ignored (fold commands take no count)
-                self.state = InputState::PendingZ;
+    /// Matches the keys typed so far against the keymap: emit on a full
+    /// chord, wait on the start of one, cancel on anything else.
+    fn step_chord(&mut self, count: Option<usize>, keys: Vec<Key>) -> Option<UiAction> {
+        match self.keymap.lookup(&keys) {
+            ChordMatch::Exact(action) => Some(Self::apply_count(action, count)),
+            ChordMatch::Prefix => {
+                self.state = InputState::Pending { count, keys };
                 None
             }
-            KeyMeaning::Motion(kind) => Self::motion_with_count(n, kind),
-            KeyMeaning::SimpleAction(action) => Some(action),
-            KeyMeaning::Unbound => None,
+            ChordMatch::None => None,
         }
     }
 
-    /// Transitions from the `PendingG` state.
-    fn step_pending_g(&mut self, count: Option<usize>, meaning: KeyMeaning) -> Option<UiAction> {
-        match meaning {
-            KeyMeaning::GPrefix => match count {
-                None => Some(UiAction::GoToFirst),
-                Some(n) => Some(UiAction::GoToRow(n.saturating_sub(1))),
+    /// Apply a typed count to the action a chord is bound to.
+    fn apply_count(action: &UiAction, count: Option<usize>) -> UiAction {
+        let n = count.unwrap_or(1);
+        match action {
+            UiAction::MoveDown(_) => UiAction::MoveDown(n),
+            UiAction::MoveUp(_) => UiAction::MoveUp(n),
+            UiAction::HalfPageDown(_) => UiAction::HalfPageDown(n),
+            UiAction::HalfPageUp(_) => UiAction::HalfPageUp(n),
+            // `5gg` goes to row 5.
+            UiAction::GoToFirst => match count {
+                Some(n) => UiAction::GoToRow(n.saturating_sub(1)),
+                None => UiAction::GoToFirst,
             },
-            KeyMeaning::SimpleAction(action) => Some(action),
-            _ => None,
-        }
-    }
-
-    /// Transitions from the `PendingD` state.
-    /// Second `d` confirms the kill. Any other key cancels.
-    fn step_pending_d(&mut self, meaning: KeyMeaning) -> Option<UiAction> {
-        match meaning {
-            KeyMeaning::DPrefix => Some(UiAction::KillAgent),
-            _ => None, // Cancel — return to Ready (already set by step())
-        }
-    }
-
-    /// Transitions from the `PendingO` state.
-    /// Direction key (h/j/k/l) selects directional spawn.
-    /// Second `o` confirms smart spawn (bare `oo`).
-    /// Any other key cancels, consistent with `PendingD`.
-    fn step_pending_o(&mut self, key: &KeyEvent) -> Option<UiAction> {
-        // Use the raw character to avoid semantic collisions (h/l are normally
-        // ExpandNode, j/k are normally MoveDown/MoveUp).
-        match key.code {
-            KeyCode::Char('h') => Some(UiAction::SpawnAgentLeft),
-            KeyCode::Char('j') => Some(UiAction::SpawnAgentBelow),
-            KeyCode::Char('k') => Some(UiAction::SpawnAgentAbove),
-            KeyCode::Char('l') => Some(UiAction::SpawnAgentRight),
-            KeyCode::Char('o') => Some(UiAction::SpawnAgent), // oo → smart spawn
-            _ => None,                                        // Cancel — return to Ready
-        }
-    }
-
-    /// Transitions from the `PendingZ` state.
-    /// Implements vim fold chords with strict directional semantics:
-    ///
-    /// - `zM`/`zR`: bulk collapse/expand
-    /// - `zc`: close-only (never opens; walks up on leaves)
-    /// - `zo`: open-only — same semantics as `h`/`l`, so reuses `ExpandNode`
-    /// - `za`: toggle (walks up on leaves)
-    ///
-    /// Any other key cancels, consistent with `PendingD`/`PendingO`.
-    fn step_pending_z(&mut self, key: &KeyEvent) -> Option<UiAction> {
-        // Use raw character to avoid semantic collisions — notably `R` which
-        // normally means Refresh, and `o` which normally starts an OPrefix chord.
-        match key.code {
-            KeyCode::Char('M') => Some(UiAction::CollapseAllFolds),
-            KeyCode::Char('R') => Some(UiAction::ExpandAllFolds),
-            KeyCode::Char('c') => Some(UiAction::CloseFold),
-            KeyCode::Char('o') => Some(UiAction::ExpandNode),
-            KeyCode::Char('a') => Some(UiAction::ToggleFold),
-            _ => None, // Cancel — return to Ready
-        }
-    }
-
-    /// Convert a motion + count into the appropriate `UiAction`.
-    fn motion_with_count(count: usize, kind: MotionKind) -> Option<UiAction> {
-        match kind {
-            MotionKind::Down => Some(UiAction::MoveDown(count)),
-            MotionKind::Up => Some(UiAction::MoveUp(count)),
-            MotionKind::GoToBottom => {
-                if count == 1 {
-                    // Bare `G` (no explicit count) → go to last.
-                    Some(UiAction::GoToLast)
-                } else {
-                    Some(UiAction::GoToRow(count.saturating_sub(1)))
-                }
-            }
-            MotionKind::HalfPageDown => Some(UiAction::HalfPageDown(count)),
-            MotionKind::HalfPageUp => Some(UiAction::HalfPageUp(count)),
+            // `5G` goes to row 5; bare `G` (or `1G`) goes to the last row.
+            UiAction::GoToLast if n != 1 => UiAction::GoToRow(n.saturating_sub(1)),
+            other => other.clone(),
         }
     }
 }
@@ -656,6 +874,16 @@ mod tests {
         KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
     }
 
+    /// What a single key press means to the default keymap.
+    fn resolve(event: KeyEvent) -> ChordMatch<'static> {
+        static DEFAULT: std::sync::OnceLock<Keymap> = std::sync::OnceLock::new();
+        let keymap = DEFAULT.get_or_init(Keymap::default);
+        match Key::from_event(&event) {
+            Some(key) => keymap.lookup(&[key]),
+            None => ChordMatch::None,
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Layer 1: DFA transition tests (~25)
     // -----------------------------------------------------------------------
@@ -797,7 +1025,7 @@ mod tests {
         let mut h = InputHandler::new();
         assert_eq!(h.handle(key(KeyCode::Char('3'))), None);
         assert!(h.is_pending());
-        // Esc is SimpleAction(Quit), which resets state and emits Quit.
+        // Esc is bound to Quit, which resets state and emits Quit.
         assert_eq!(h.handle(key(KeyCode::Esc)), Some(UiAction::Quit));
         assert!(!h.is_pending());
     }
@@ -821,9 +1049,9 @@ mod tests {
     }
 
     #[test]
-    fn test_r_refreshes() {
+    fn test_capital_r_refreshes() {
         let mut h = InputHandler::new();
-        assert_eq!(h.handle(key(KeyCode::Char('r'))), Some(UiAction::Refresh));
+        assert_eq!(h.handle(key(KeyCode::Char('R'))), Some(UiAction::Refresh));
     }
 
     #[test]
@@ -831,7 +1059,7 @@ mod tests {
         let mut h = InputHandler::new();
         assert_eq!(h.handle(key(KeyCode::Char('g'))), None);
         assert!(h.is_pending());
-        // 'j' is Motion(Down) which is "other" in PendingG → None.
+        // No chord starts with `gj` → cancels with None.
         assert_eq!(h.handle(key(KeyCode::Char('j'))), None);
         assert!(!h.is_pending());
     }
@@ -840,7 +1068,7 @@ mod tests {
     fn test_g_then_shift_g_cancels() {
         let mut h = InputHandler::new();
         assert_eq!(h.handle(key(KeyCode::Char('g'))), None);
-        // 'G' is Motion(GoToBottom) which is "other" in PendingG → None.
+        // No chord starts with `gG` → cancels with None.
         assert_eq!(h.handle(key(KeyCode::Char('G'))), None);
         assert!(!h.is_pending());
     }
@@ -850,7 +1078,7 @@ mod tests {
         let mut h = InputHandler::new();
         assert_eq!(h.handle(key(KeyCode::Char('3'))), None);
         assert!(h.is_pending());
-        // 'w' is Unbound → resets state, emits None.
+        // 'w' is unbound → resets state, emits None.
         assert_eq!(h.handle(key(KeyCode::Char('w'))), None);
         assert!(!h.is_pending());
     }
@@ -943,7 +1171,7 @@ mod tests {
 
     #[test]
     fn test_z_lowercase_m_does_not_collapse() {
-        // Case-sensitive: lowercase 'm' is not bound in PendingZ, so the
+        // Case-sensitive: `zm` is not a chord, so the
         // chord cancels without emitting an action. Matches vim exactly.
         let mut h = InputHandler::new();
         assert_eq!(h.handle(key(KeyCode::Char('z'))), None);
@@ -955,7 +1183,7 @@ mod tests {
     fn test_z_unknown_key_cancels() {
         let mut h = InputHandler::new();
         assert_eq!(h.handle(key(KeyCode::Char('z'))), None);
-        // 'q' would normally quit, but in PendingZ it cancels silently.
+        // 'q' would normally quit, but after `z` it cancels silently.
         assert_eq!(h.handle(key(KeyCode::Char('q'))), None);
         assert!(!h.is_pending());
         // After cancel, 'q' alone should still quit.
@@ -964,8 +1192,8 @@ mod tests {
 
     #[test]
     fn test_count_before_z_is_discarded() {
-        // vim ignores counts on fold commands; our DFA should transition into
-        // PendingZ and drop the pending count.
+        // vim ignores counts on fold commands; our DFA should wait for the
+        // rest of the z-chord and drop the pending count.
         let mut h = InputHandler::new();
         assert_eq!(h.handle(key(KeyCode::Char('3'))), None);
         assert_eq!(h.handle(key(KeyCode::Char('z'))), None);
@@ -1001,138 +1229,129 @@ mod tests {
     }
 
     // -----------------------------------------------------------------------
-    // Layer 2: VimKeyResolver tests (~15)
+    // Layer 2: default keymap tests (~15)
     // -----------------------------------------------------------------------
 
     #[test]
     fn test_resolver_j_is_motion_down() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key(KeyCode::Char('j'))),
-            KeyMeaning::Motion(MotionKind::Down)
+            resolve(key(KeyCode::Char('j'))),
+            ChordMatch::Exact(&UiAction::MoveDown(1))
         );
     }
 
     #[test]
     fn test_resolver_k_is_motion_up() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key(KeyCode::Char('k'))),
-            KeyMeaning::Motion(MotionKind::Up)
+            resolve(key(KeyCode::Char('k'))),
+            ChordMatch::Exact(&UiAction::MoveUp(1))
         );
     }
 
     #[test]
     fn test_resolver_down_arrow() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key(KeyCode::Down)),
-            KeyMeaning::Motion(MotionKind::Down)
+            resolve(key(KeyCode::Down)),
+            ChordMatch::Exact(&UiAction::MoveDown(1))
         );
     }
 
     #[test]
     fn test_resolver_up_arrow() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key(KeyCode::Up)),
-            KeyMeaning::Motion(MotionKind::Up)
+            resolve(key(KeyCode::Up)),
+            ChordMatch::Exact(&UiAction::MoveUp(1))
         );
     }
 
     #[test]
     fn test_resolver_ctrl_d() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key_ctrl('d')),
-            KeyMeaning::Motion(MotionKind::HalfPageDown)
+            resolve(key_ctrl('d')),
+            ChordMatch::Exact(&UiAction::HalfPageDown(1))
         );
     }
 
     #[test]
     fn test_resolver_ctrl_u() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key_ctrl('u')),
-            KeyMeaning::Motion(MotionKind::HalfPageUp)
+            resolve(key_ctrl('u')),
+            ChordMatch::Exact(&UiAction::HalfPageUp(1))
         );
     }
 
     #[test]
     fn test_resolver_digit_5() {
-        let r = VimKeyResolver;
-        assert_eq!(r.resolve(&key(KeyCode::Char('5'))), KeyMeaning::Digit(5));
+        // Digits 1-9 are never bound; the handler reads them as a count
+        assert_eq!(resolve(key(KeyCode::Char('5'))), ChordMatch::None);
+        let mut h = InputHandler::new();
+        assert_eq!(h.handle(key(KeyCode::Char('5'))), None);
+        assert!(h.is_pending());
     }
 
     #[test]
     fn test_resolver_digit_0() {
-        let r = VimKeyResolver;
-        assert_eq!(r.resolve(&key(KeyCode::Char('0'))), KeyMeaning::Digit(0));
+        assert_eq!(
+            resolve(key(KeyCode::Char('0'))),
+            ChordMatch::Exact(&UiAction::GoToFirst)
+        );
     }
 
     #[test]
     fn test_resolver_shift_g() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key(KeyCode::Char('G'))),
-            KeyMeaning::Motion(MotionKind::GoToBottom)
+            resolve(key(KeyCode::Char('G'))),
+            ChordMatch::Exact(&UiAction::GoToLast)
         );
     }
 
     #[test]
     fn test_resolver_g_is_gprefix() {
-        let r = VimKeyResolver;
-        assert_eq!(r.resolve(&key(KeyCode::Char('g'))), KeyMeaning::GPrefix);
+        assert_eq!(resolve(key(KeyCode::Char('g'))), ChordMatch::Prefix);
     }
 
     #[test]
     fn test_resolver_q_is_quit() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key(KeyCode::Char('q'))),
-            KeyMeaning::SimpleAction(UiAction::Quit)
+            resolve(key(KeyCode::Char('q'))),
+            ChordMatch::Exact(&UiAction::Quit)
         );
     }
 
     #[test]
     fn test_resolver_enter_is_jump() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key(KeyCode::Enter)),
-            KeyMeaning::SimpleAction(UiAction::JumpToSession)
+            resolve(key(KeyCode::Enter)),
+            ChordMatch::Exact(&UiAction::JumpToSession)
         );
     }
 
     #[test]
-    fn test_resolver_r_is_refresh() {
-        let r = VimKeyResolver;
+    fn test_resolver_capital_r_is_refresh() {
         assert_eq!(
-            r.resolve(&key(KeyCode::Char('r'))),
-            KeyMeaning::SimpleAction(UiAction::Refresh)
+            resolve(key(KeyCode::Char('R'))),
+            ChordMatch::Exact(&UiAction::Refresh)
         );
     }
 
     #[test]
     fn test_resolver_alt_j_is_unbound() {
-        let r = VimKeyResolver;
         let alt_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::ALT);
-        assert_eq!(r.resolve(&alt_j), KeyMeaning::Unbound);
+        assert_eq!(resolve(alt_j), ChordMatch::None);
     }
 
     #[test]
     fn test_resolver_ctrl_g_is_unbound() {
-        let r = VimKeyResolver;
-        assert_eq!(r.resolve(&key_ctrl('g')), KeyMeaning::Unbound);
+        assert_eq!(resolve(key_ctrl('g')), ChordMatch::None);
     }
 
     #[test]
     fn test_resolver_ctrl_alt_d_is_unbound() {
-        let r = VimKeyResolver;
         let ctrl_alt_d = KeyEvent::new(
             KeyCode::Char('d'),
-            KeyModifiers::CONTROL.union(KeyModifiers::ALT),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
         );
-        assert_eq!(r.resolve(&ctrl_alt_d), KeyMeaning::Unbound);
+        assert_eq!(resolve(ctrl_alt_d), ChordMatch::None);
     }
 
     // -----------------------------------------------------------------------
@@ -1150,10 +1369,9 @@ mod tests {
 
     #[test]
     fn test_resolver_question_mark_is_toggle_help() {
-        let r = VimKeyResolver;
         assert_eq!(
-            r.resolve(&key(KeyCode::Char('?'))),
-            KeyMeaning::SimpleAction(UiAction::ToggleHelp)
+            resolve(key(KeyCode::Char('?'))),
+            ChordMatch::Exact(&UiAction::ToggleHelp)
         );
     }
 
@@ -1161,7 +1379,7 @@ mod tests {
     fn test_count_then_question_mark_discards_count() {
         let mut h = InputHandler::new();
         assert_eq!(h.handle(key(KeyCode::Char('3'))), None);
-        // SimpleAction discards accumulated count
+        // Actions other than motions discard the accumulated count
         assert_eq!(
             h.handle(key(KeyCode::Char('?'))),
             Some(UiAction::ToggleHelp)
@@ -1271,7 +1489,209 @@ mod tests {
 
     #[test]
     fn test_o_resolver_returns_oprefix() {
-        let r = VimKeyResolver;
-        assert_eq!(r.resolve(&key(KeyCode::Char('o'))), KeyMeaning::OPrefix);
+        assert_eq!(resolve(key(KeyCode::Char('o'))), ChordMatch::Prefix);
+    }
+
+    // -----------------------------------------------------------------------
+    // Prompt answering (r-prefix) tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_r_chords() {
+        for (c, action) in [
+            ('r', UiAction::OpenReply),
+            ('i', UiAction::OpenReplyText),
+            ('y', UiAction::ReplyAccept),
+            ('n', UiAction::ReplyReject),
+            ('1', UiAction::ReplyOption(1)),
+            ('9', UiAction::ReplyOption(9)),
+        ] {
+            let mut h = InputHandler::new();
+            assert_eq!(h.handle(key(KeyCode::Char('r'))), None);
+            assert!(h.is_pending());
+            assert_eq!(h.handle(key(KeyCode::Char(c))), Some(action));
+            assert!(!h.is_pending());
+        }
+    }
+
+    #[test]
+    fn test_r_unknown_key_cancels() {
+        let mut h = InputHandler::new();
+        assert_eq!(h.handle(key(KeyCode::Char('r'))), None);
+        assert_eq!(h.handle(key(KeyCode::Char('0'))), None);
+        assert!(!h.is_pending());
+        assert_eq!(h.handle(key(KeyCode::Char('r'))), None);
+        assert_eq!(h.handle(key(KeyCode::Esc)), None);
+        assert!(!h.is_pending());
+    }
+
+    #[test]
+    fn test_count_then_r_ignores_count() {
+        let mut h = InputHandler::new();
+        assert_eq!(h.handle(key(KeyCode::Char('3'))), None);
+        assert_eq!(h.handle(key(KeyCode::Char('r'))), None);
+        assert_eq!(
+            h.handle(key(KeyCode::Char('2'))),
+            Some(UiAction::ReplyOption(2))
+        );
+    }
+
+    #[test]
+    fn test_reply_popup_keys() {
+        let pick = |c| resolve_reply_key(&key(KeyCode::Char(c)), false);
+        assert_eq!(pick('j'), Some(ReplyPopupAction::Down));
+        assert_eq!(pick('k'), Some(ReplyPopupAction::Up));
+        assert_eq!(pick('3'), Some(ReplyPopupAction::Pick(3)));
+        assert_eq!(pick('0'), None);
+        assert_eq!(pick('i'), Some(ReplyPopupAction::StartText));
+        assert_eq!(pick('n'), Some(ReplyPopupAction::Reject));
+        assert_eq!(
+            resolve_reply_key(&key(KeyCode::Enter), false),
+            Some(ReplyPopupAction::Submit)
+        );
+        assert_eq!(
+            resolve_reply_key(&key(KeyCode::Esc), false),
+            Some(ReplyPopupAction::Close)
+        );
+        assert_eq!(
+            resolve_reply_key(&key_ctrl('c'), false),
+            Some(ReplyPopupAction::Quit)
+        );
+    }
+
+    #[test]
+    fn test_reply_popup_keys_while_typing() {
+        let typed = |code| resolve_reply_key(&key(code), true);
+        assert_eq!(typed(KeyCode::Char('j')), Some(ReplyPopupAction::Char('j')));
+        assert_eq!(typed(KeyCode::Char('3')), Some(ReplyPopupAction::Char('3')));
+        assert_eq!(typed(KeyCode::Backspace), Some(ReplyPopupAction::Backspace));
+        assert_eq!(typed(KeyCode::Enter), Some(ReplyPopupAction::Submit));
+        assert_eq!(typed(KeyCode::Esc), Some(ReplyPopupAction::Close));
+        assert_eq!(
+            resolve_reply_key(&key_ctrl('c'), true),
+            Some(ReplyPopupAction::Quit)
+        );
+    }
+
+    #[test]
+    fn test_mark_keys() {
+        let mut h = InputHandler::new();
+        assert_eq!(
+            h.handle(key(KeyCode::Char('m'))),
+            Some(UiAction::ToggleMark)
+        );
+        assert_eq!(
+            h.handle(key(KeyCode::Char(' '))),
+            Some(UiAction::ToggleMark)
+        );
+        assert_eq!(
+            h.handle(key(KeyCode::Char('u'))),
+            Some(UiAction::ClearMarks)
+        );
+    }
+
+    #[test]
+    fn test_search_keys() {
+        let mut h = InputHandler::new();
+        let mut press = |c| h.handle(key(KeyCode::Char(c)));
+        assert_eq!(press('/'), Some(UiAction::StartSearch));
+        assert_eq!(press('n'), Some(UiAction::SearchNext));
+        assert_eq!(press('N'), Some(UiAction::SearchPrevious));
+        assert_eq!(press('s'), Some(UiAction::CycleStatusFilter));
+        assert_eq!(press('H'), Some(UiAction::CycleHarnessFilter));
+    }
+
+    #[test]
+    fn test_view_keys() {
+        let mut h = InputHandler::new();
+        assert_eq!(h.handle(key(KeyCode::Char('S'))), Some(UiAction::CycleSort));
+        assert_eq!(
+            h.handle(key(KeyCode::Char('v'))),
+            Some(UiAction::CycleGroup)
+        );
+    }
+
+    #[test]
+    fn test_n_still_rejects_after_r() {
+        let mut h = InputHandler::new();
+        assert_eq!(h.handle(key(KeyCode::Char('r'))), None);
+        assert_eq!(
+            h.handle(key(KeyCode::Char('n'))),
+            Some(UiAction::ReplyReject)
+        );
+    }
+
+    #[test]
+    fn test_search_bar_keys() {
+        let typed = |code| resolve_search_key(&key(code));
+        assert_eq!(
+            typed(KeyCode::Char('n')),
+            Some(SearchInputAction::Char('n'))
+        );
+        assert_eq!(
+            typed(KeyCode::Char('/')),
+            Some(SearchInputAction::Char('/'))
+        );
+        assert_eq!(
+            typed(KeyCode::Backspace),
+            Some(SearchInputAction::Backspace)
+        );
+        assert_eq!(typed(KeyCode::Enter), Some(SearchInputAction::Accept));
+        assert_eq!(typed(KeyCode::Esc), Some(SearchInputAction::Cancel));
+        assert_eq!(typed(KeyCode::Down), Some(SearchInputAction::Next));
+        assert_eq!(
+            resolve_search_key(&key_ctrl('u')),
+            Some(SearchInputAction::ClearLine)
+        );
+        assert_eq!(
+            resolve_search_key(&key_ctrl('c')),
+            Some(SearchInputAction::Quit)
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Custom keymaps
+    // -----------------------------------------------------------------------
+
+    fn handler_with(entries: &[(&str, &str)]) -> InputHandler {
+        let keys = entries
+            .iter()
+            .map(|(chord, action)| ((*chord).to_string(), (*action).to_string()))
+            .collect();
+        InputHandler::with_keymap(Keymap::from_config(&keys).unwrap())
+    }
+
+    #[test]
+    fn test_custom_chords_take_counts_and_prefixes() {
+        let mut h = handler_with(&[("<C-n>", "move_down"), ("gs", "cycle_sort")]);
+        assert_eq!(h.handle(key(KeyCode::Char('3'))), None);
+        assert_eq!(h.handle(key_ctrl('n')), Some(UiAction::MoveDown(3)));
+        assert_eq!(h.handle(key(KeyCode::Char('g'))), None);
+        assert_eq!(h.handle(key(KeyCode::Char('s'))), Some(UiAction::CycleSort));
+        assert_eq!(h.handle(key(KeyCode::Char('5'))), None);
+        assert_eq!(h.handle(key(KeyCode::Char('g'))), None);
+        assert_eq!(
+            h.handle(key(KeyCode::Char('g'))),
+            Some(UiAction::GoToRow(4))
+        );
+    }
+
+    #[test]
+    fn test_unbound_chord_does_nothing() {
+        let mut h = handler_with(&[("x", "none"), ("dd", "none")]);
+        assert_eq!(h.handle(key(KeyCode::Char('x'))), None);
+        assert!(!h.is_pending());
+        // `d` no longer starts a chord, so it is not left pending.
+        assert_eq!(h.handle(key(KeyCode::Char('d'))), None);
+        assert!(!h.is_pending());
+    }
+
+    #[test]
+    fn test_alt_key_cancels_pending_chord() {
+        let mut h = InputHandler::new();
+        assert_eq!(h.handle(key(KeyCode::Char('g'))), None);
+        let alt_g = KeyEvent::new(KeyCode::Char('g'), KeyModifiers::ALT);
+        assert_eq!(h.handle(alt_g), None);
+        assert!(!h.is_pending());
     }
 }
@@ -0,0 +1,642 @@
+//! User-configurable keymap for the ATM TUI.
+//!
+//! A [`Keymap`] maps key chords (`j`, `gg`, `zM`, `<C-d>`) to the
+//! [`UiAction`]s they trigger. The defaults are the built-in vim-style
+//! bindings; the `[keys]` table in `config.toml` overrides them chord by
+//! chord. Overrides are validated up front, so a typo or a chord that can
+//! never fire is reported at startup rather than discovered by pressing it.
+//!
+//! Chord syntax: printable keys stand for themselves (`gs`, `zM`, `?`),
+//! special keys are written in angle brackets (`<C-n>`, `<Down>`,
+//! `<Enter>`, `<Esc>`, `<Space>`, `<Tab>`, `<lt>` for a literal `<`).
+//!
+//! All code follows the panic-free policy: no unwrap/expect/panic.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use thiserror::Error;
+
+use crate::keybinding::{UiAction, ACTION_HINTS};
+
+/// Config value that removes a chord instead of binding it.
+const UNBIND: &str = "none";
+
+/// Built-in bindings, in the order their chords are listed for an action.
+static DEFAULT_BINDINGS: &[(&str, UiAction)] = &[
+    ("j", UiAction::MoveDown(1)),
+    ("<Down>", UiAction::MoveDown(1)),
+    ("k", UiAction::MoveUp(1)),
+    ("<Up>", UiAction::MoveUp(1)),
+    ("gg", UiAction::GoToFirst),
+    ("0", UiAction::GoToFirst),
+    ("G", UiAction::GoToLast),
+    ("<C-d>", UiAction::HalfPageDown(1)),
+    ("<C-u>", UiAction::HalfPageUp(1)),
+    // `h` and `l` both open a collapsed fold. They never close — the
+    // z-chord family owns all close/toggle semantics.
+    ("h", UiAction::ExpandNode),
+    ("l", UiAction::ExpandNode),
+    ("zo", UiAction::ExpandNode),
+    ("zc", UiAction::CloseFold),
+    ("za", UiAction::ToggleFold),
+    ("zM", UiAction::CollapseAllFolds),
+    ("zR", UiAction::ExpandAllFolds),
+    ("<Enter>", UiAction::JumpToSession),
+    ("R", UiAction::Refresh),
+    ("q", UiAction::Quit),
+    ("Q", UiAction::Quit),
+    ("<C-c>", UiAction::Quit),
+    ("<Esc>", UiAction::Quit),
+    ("?", UiAction::ToggleHelp),
+    ("m", UiAction::ToggleMark),
+    ("<Space>", UiAction::ToggleMark),
+    ("u", UiAction::ClearMarks),
+    ("dd", UiAction::KillAgent),
+    ("x", UiAction::KillAgent),
+    ("I", UiAction::InterruptAgent),
+    ("S", UiAction::CycleSort),
+    ("v", UiAction::CycleGroup),
+    ("oo", UiAction::SpawnAgent),
+    ("oh", UiAction::SpawnAgentLeft),
+    ("oj", UiAction::SpawnAgentBelow),
+    ("ok", UiAction::SpawnAgentAbove),
+    ("ol", UiAction::SpawnAgentRight),
+    ("rr", UiAction::OpenReply),
+    ("ri", UiAction::OpenReplyText),
+    ("ry", UiAction::ReplyAccept),
+    ("rn", UiAction::ReplyReject),
+    ("r1", UiAction::ReplyOption(1)),
+    ("r2", UiAction::ReplyOption(2)),
+    ("r3", UiAction::ReplyOption(3)),
+    ("r4", UiAction::ReplyOption(4)),
+    ("r5", UiAction::ReplyOption(5)),
+    ("r6", UiAction::ReplyOption(6)),
+    ("r7", UiAction::ReplyOption(7)),
+    ("r8", UiAction::ReplyOption(8)),
+    ("r9", UiAction::ReplyOption(9)),
+    ("/", UiAction::StartSearch),
+    ("n", UiAction::SearchNext),
+    ("N", UiAction::SearchPrevious),
+    ("s", UiAction::CycleStatusFilter),
+    ("H", UiAction::CycleHarnessFilter),
+];
+
+// ---------------------------------------------------------------------------
+// Keys and chords
+// ---------------------------------------------------------------------------
+
+/// A single key press within a chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A printable character, with Shift already folded in (`G`, `?`).
+    Char(char),
+    /// A character pressed with Ctrl, stored lowercase.
+    Ctrl(char),
+    Down,
+    Up,
+    Left,
+    Right,
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+}
+
+impl Key {
+    /// Converts a crossterm key event, or returns `None` for keys that can
+    /// never be bound (Alt/Super/Hyper/Meta combinations, function keys).
+    #[must_use]
+    pub fn from_event(key: &KeyEvent) -> Option<Self> {
+        // crossterm folds Shift into the character for letters and
+        // symbols, so it is not a modifier of its own here.
+        let modifiers = key.modifiers.difference(KeyModifiers::SHIFT);
+        if modifiers == KeyModifiers::CONTROL {
+            return match key.code {
+                KeyCode::Char(c) => Some(Self::Ctrl(c.to_ascii_lowercase())),
+                _ => None,
+            };
+        }
+        if !modifiers.is_empty() {
+            return None;
+        }
+        match key.code {
+            KeyCode::Char(c) => Some(Self::Char(c)),
+            KeyCode::Down => Some(Self::Down),
+            KeyCode::Up => Some(Self::Up),
+            KeyCode::Left => Some(Self::Left),
+            KeyCode::Right => Some(Self::Right),
+            KeyCode::Enter => Some(Self::Enter),
+            KeyCode::Esc => Some(Self::Esc),
+            KeyCode::Tab => Some(Self::Tab),
+            KeyCode::Backspace => Some(Self::Backspace),
+            KeyCode::Home => Some(Self::Home),
+            KeyCode::End => Some(Self::End),
+            KeyCode::PageUp => Some(Self::PageUp),
+            KeyCode::PageDown => Some(Self::PageDown),
+            _ => None,
+        }
+    }
+
+    /// Parses the inside of an angle-bracket key name (`C-d`, `Down`).
+    fn from_name(name: &str) -> Option<Self> {
+        let lower = name.to_ascii_lowercase();
+        if let Some(rest) = lower.strip_prefix("c-") {
+            let mut chars = rest.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(Self::Ctrl(c)),
+                _ => None,
+            };
+        }
+        match lower.as_str() {
+            "down" => Some(Self::Down),
+            "up" => Some(Self::Up),
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            "enter" | "cr" | "return" => Some(Self::Enter),
+            "esc" | "escape" => Some(Self::Esc),
+            "tab" => Some(Self::Tab),
+            "bs" | "backspace" => Some(Self::Backspace),
+            "home" => Some(Self::Home),
+            "end" => Some(Self::End),
+            "pageup" | "pgup" => Some(Self::PageUp),
+            "pagedown" | "pgdn" => Some(Self::PageDown),
+            "space" => Some(Self::Char(' ')),
+            "lt" => Some(Self::Char('<')),
+            _ => None,
+        }
+    }
+
+    /// Writes the key the way it is typed in the `[keys]` table.
+    fn write_notation(self, out: &mut String) {
+        let name = match self {
+            Self::Char(' ') => "Space",
+            Self::Char('<') => "lt",
+            Self::Char(c) => {
+                out.push(c);
+                return;
+            }
+            Self::Ctrl(c) => {
+                out.push_str("<C-");
+                out.push(c);
+                out.push('>');
+                return;
+            }
+            Self::Down => "Down",
+            Self::Up => "Up",
+            Self::Left => "Left",
+            Self::Right => "Right",
+            Self::Enter => "Enter",
+            Self::Esc => "Esc",
+            Self::Tab => "Tab",
+            Self::Backspace => "BS",
+            Self::Home => "Home",
+            Self::End => "End",
+            Self::PageUp => "PageUp",
+            Self::PageDown => "PageDown",
+        };
+        out.push('<');
+        out.push_str(name);
+        out.push('>');
+    }
+}
+
+/// Short display form used by the help popup and footer (`^d`, `↓`).
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Char(' ') => f.write_str("Space"),
+            Self::Char(c) => write!(f, "{c}"),
+            Self::Ctrl(c) => write!(f, "^{c}"),
+            Self::Down => f.write_str("\u{2193}"),
+            Self::Up => f.write_str("\u{2191}"),
+            Self::Left => f.write_str("\u{2190}"),
+            Self::Right => f.write_str("\u{2192}"),
+            Self::Enter => f.write_str("Enter"),
+            Self::Esc => f.write_str("Esc"),
+            Self::Tab => f.write_str("Tab"),
+            Self::Backspace => f.write_str("BS"),
+            Self::Home => f.write_str("Home"),
+            Self::End => f.write_str("End"),
+            Self::PageUp => f.write_str("PgUp"),
+            Self::PageDown => f.write_str("PgDn"),
+        }
+    }
+}
+
+/// A sequence of keys bound to one action (`gg`, `<C-d>`, `r1`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chord(Vec<Key>);
+
+impl Chord {
+    /// Parses chord notation, returning a human-readable reason on failure.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut keys = Vec::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c != '<' {
+                keys.push(Key::Char(c));
+                continue;
+            }
+            let mut name = String::new();
+            let mut closed = false;
+            for c in chars.by_ref() {
+                if c == '>' {
+                    closed = true;
+                    break;
+                }
+                name.push(c);
+            }
+            if !closed {
+                return Err("unclosed `<` (write `<lt>` for a literal `<`)".to_string());
+            }
+            let key = Key::from_name(&name).ok_or_else(|| format!("unknown key `<{name}>`"))?;
+            keys.push(key);
+        }
+        if keys.is_empty() {
+            return Err("empty chord".to_string());
+        }
+        Ok(Self(keys))
+    }
+
+    /// The keys of the chord, in the order they are pressed.
+    #[must_use]
+    pub fn keys(&self) -> &[Key] {
+        &self.0
+    }
+
+    /// The chord as it is written in the `[keys]` table (`<C-d>`, `gg`).
+    #[must_use]
+    pub fn notation(&self) -> String {
+        let mut out = String::new();
+        for key in &self.0 {
+            key.write_notation(&mut out);
+        }
+        out
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for key in &self.0 {
+            write!(f, "{key}")?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// A problem with the `[keys]` table, reported at startup.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KeymapError {
+    /// The chord could not be parsed.
+    #[error("invalid chord `{chord}`: {reason}")]
+    InvalidChord {
+        /// The chord as written in the config.
+        chord: String,
+        /// Why it was rejected.
+        reason: String,
+    },
+
+    /// The action name is not one of the bindable actions.
+    #[error("unknown action `{action}` for `{chord}` (use an action name such as `move_down`, or \"none\" to unbind)")]
+    UnknownAction {
+        /// The chord as written in the config.
+        chord: String,
+        /// The unrecognised action name.
+        action: String,
+    },
+
+    /// The chord starts with 1-9, which always begins a count.
+    #[error("`{chord}` starts with a digit, which is reserved for counts like `5j`")]
+    ReservedDigit {
+        /// The chord as written in the config.
+        chord: String,
+    },
+
+    /// Two entries spell the same chord (`<C-d>` and `<c-d>`).
+    #[error("`{first}` and `{second}` are the same chord")]
+    DuplicateChord {
+        /// The spelling that sorts first (entries are checked in key
+        /// order, not the order they appear in the file).
+        first: String,
+        /// The other spelling.
+        second: String,
+    },
+
+    /// One chord is the start of another, so the longer one never fires.
+    #[error("`{prefix}` ({prefix_action}) conflicts with `{chord}` ({action}): a chord cannot also start a longer one; unbind one of them with \"none\"")]
+    PrefixConflict {
+        /// The shorter chord.
+        prefix: String,
+        /// The action bound to the shorter chord.
+        prefix_action: &'static str,
+        /// The longer chord it shadows.
+        chord: String,
+        /// The action bound to the longer chord.
+        action: &'static str,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Keymap
+// ---------------------------------------------------------------------------
+
+/// How a sequence of pressed keys relates to the keymap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ChordMatch<'a> {
+    /// The keys spell a bound chord.
+    Exact(&'a UiAction),
+    /// The keys are the start of at least one longer chord.
+    Prefix,
+    /// No chord starts with these keys.
+    None,
+}
+
+/// The effective chord-to-action bindings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(Chord, UiAction)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .filter_map(|(text, action)| Some((Chord::parse(text).ok()?, action.clone())))
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap from a `[keys]` table of chord → action name.
+    ///
+    /// Each entry replaces whatever the chord was bound to by default;
+    /// the action `"none"` unbinds it. The result is rejected if a chord
+    /// is malformed, names an unknown action, starts with a count digit,
+    /// is spelled twice, or is the start of another chord.
+    pub fn from_config(keys: &BTreeMap<String, String>) -> Result<Self, KeymapError> {
+        let mut keymap = Self::default();
+        let mut seen: Vec<(Chord, &str)> = Vec::new();
+
+        for (text, name) in keys {
+            let chord = Chord::parse(text).map_err(|reason| KeymapError::InvalidChord {
+                chord: text.clone(),
+                reason,
+            })?;
+            if matches!(chord.keys().first(), Some(Key::Char('1'..='9'))) {
+                return Err(KeymapError::ReservedDigit {
+                    chord: text.clone(),
+                });
+            }
+            if let Some((_, first)) = seen.iter().find(|(c, _)| *c == chord) {
+                return Err(KeymapError::DuplicateChord {
+                    first: (*first).to_string(),
+                    second: text.clone(),
+                });
+            }
+            let action = if name == UNBIND {
+                None
+            } else {
+                let action = action_by_name(name).ok_or_else(|| KeymapError::UnknownAction {
+                    chord: text.clone(),
+                    action: name.clone(),
+                })?;
+                Some(action)
+            };
+
+            keymap.bindings.retain(|(c, _)| *c != chord);
+            if let Some(action) = action {
+                keymap.bindings.push((chord.clone(), action));
+            }
+            seen.push((chord, text));
+        }
+
+        keymap.check_prefixes()?;
+        Ok(keymap)
+    }
+
+    /// Rejects chords that start another chord, which would fire before
+    /// the longer one could ever be typed.
+    fn check_prefixes(&self) -> Result<(), KeymapError> {
+        for (prefix, prefix_action) in &self.bindings {
+            for (chord, action) in &self.bindings {
+                if chord.keys().len() > prefix.keys().len()
+                    && chord.keys().starts_with(prefix.keys())
+                {
+                    return Err(KeymapError::PrefixConflict {
+                        prefix: prefix.notation(),
+                        prefix_action: action_name(prefix_action).unwrap_or("?"),
+                        chord: chord.notation(),
+                        action: action_name(action).unwrap_or("?"),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Looks up the keys pressed so far.
+    pub(crate) fn lookup(&self, keys: &[Key]) -> ChordMatch<'_> {
+        let mut prefix = false;
+        for (chord, action) in &self.bindings {
+            if chord.keys() == keys {
+                return ChordMatch::Exact(action);
+            }
+            prefix |= chord.keys().starts_with(keys);
+        }
+        if prefix {
+            ChordMatch::Prefix
+        } else {
+            ChordMatch::None
+        }
+    }
+
+    /// The chords bound to `action`, defaults first.
+    pub fn chords<'a>(&'a self, action: &'a UiAction) -> impl Iterator<Item = &'a Chord> + 'a {
+        self.bindings
+            .iter()
+            .filter(move |(_, bound)| bound == action)
+            .map(|(chord, _)| chord)
+    }
+
+    /// The action `chord` is bound to, if any.
+    #[must_use]
+    pub fn action(&self, chord: &Chord) -> Option<&UiAction> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| bound == chord)
+            .map(|(_, action)| action)
+    }
+}
+
+/// Resolves a `[keys]` action name (`move_down`, `reply_option_3`).
+fn action_by_name(name: &str) -> Option<UiAction> {
+    ACTION_HINTS
+        .iter()
+        .find(|hint| hint.name == name)
+        .map(|hint| hint.action.clone())
+}
+
+/// The `[keys]` name of a bindable action.
+fn action_name(action: &UiAction) -> Option<&'static str> {
+    ACTION_HINTS
+        .iter()
+        .find(|hint| hint.action == *action)
+        .map(|hint| hint.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(chord, action)| ((*chord).to_string(), (*action).to_string()))
+            .collect()
+    }
+
+    fn chord(text: &str) -> Chord {
+        Chord::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_defaults_parse_and_have_no_conflicts() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.bindings.len(), DEFAULT_BINDINGS.len());
+        assert_eq!(keymap.check_prefixes(), Ok(()));
+        for (_, action) in DEFAULT_BINDINGS {
+            assert!(action_name(action).is_some(), "{action:?} has no name");
+        }
+    }
+
+    #[test]
+    fn test_chord_notation_round_trips() {
+        for text in ["gg", "<C-d>", "<Down>", "r1", "<Space>", "<lt>x", "z<Esc>"] {
+            assert_eq!(chord(text).notation(), text);
+        }
+        assert_eq!(chord("<c-D>"), chord("<C-d>"));
+        assert_eq!(chord("<CR>"), chord("<Enter>"));
+        assert_eq!(chord(" "), chord("<Space>"));
+        assert_eq!(chord("<C-d>").to_string(), "^d");
+        assert_eq!(chord("<Down>").to_string(), "\u{2193}");
+    }
+
+    #[test]
+    fn test_invalid_chords_are_rejected() {
+        assert!(Chord::parse("").is_err());
+        assert!(Chord::parse("<C-d").is_err());
+        assert!(Chord::parse("<Nope>").is_err());
+        assert!(Chord::parse("<C-ab>").is_err());
+    }
+
+    #[test]
+    fn test_key_from_event_folds_shift_and_rejects_alt() {
+        let shift_g = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(Key::from_event(&shift_g), Some(Key::Char('G')));
+        let ctrl_d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(Key::from_event(&ctrl_d), Some(Key::Ctrl('d')));
+        let alt_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::ALT);
+        assert_eq!(Key::from_event(&alt_j), None);
+        let ctrl_alt_d = KeyEvent::new(
+            KeyCode::Char('d'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        );
+        assert_eq!(Key::from_event(&ctrl_alt_d), None);
+    }
+
+    #[test]
+    fn test_overrides_rebind_add_and_unbind() {
+        let keymap = Keymap::from_config(&keys(&[
+            ("<C-n>", "move_down"),
+            ("x", "none"),
+            ("gs", "cycle_sort"),
+            ("j", "move_up"),
+        ]))
+        .unwrap();
+        assert_eq!(keymap.action(&chord("<C-n>")), Some(&UiAction::MoveDown(1)));
+        assert_eq!(keymap.action(&chord("x")), None);
+        assert_eq!(keymap.action(&chord("dd")), Some(&UiAction::KillAgent));
+        assert_eq!(keymap.action(&chord("gs")), Some(&UiAction::CycleSort));
+        assert_eq!(keymap.action(&chord("j")), Some(&UiAction::MoveUp(1)));
+        let down: Vec<_> = keymap
+            .chords(&UiAction::MoveDown(1))
+            .map(Chord::notation)
+            .collect();
+        assert_eq!(down, ["<Down>", "<C-n>"]);
+    }
+
+    #[test]
+    fn test_lookup_distinguishes_exact_and_prefix() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.lookup(&[Key::Char('j')]),
+            ChordMatch::Exact(&UiAction::MoveDown(1))
+        );
+        assert_eq!(keymap.lookup(&[Key::Char('g')]), ChordMatch::Prefix);
+        assert_eq!(
+            keymap.lookup(&[Key::Char('g'), Key::Char('j')]),
+            ChordMatch::None
+        );
+    }
+
+    #[test]
+    fn test_prefix_conflicts_are_reported() {
+        let err = Keymap::from_config(&keys(&[("g", "cycle_sort")])).unwrap_err();
+        assert_eq!(
+            err,
+            KeymapError::PrefixConflict {
+                prefix: "g".to_string(),
+                prefix_action: "cycle_sort",
+                chord: "gg".to_string(),
+                action: "go_to_first",
+            }
+        );
+        assert!(err.to_string().contains("unbind one of them"));
+
+        let err = Keymap::from_config(&keys(&[("jj", "go_to_last")])).unwrap_err();
+        assert!(matches!(err, KeymapError::PrefixConflict { ref prefix, .. } if prefix == "j"));
+
+        // Unbinding the longer chord resolves the conflict.
+        let keymap = Keymap::from_config(&keys(&[("g", "cycle_sort"), ("gg", "none")])).unwrap();
+        assert_eq!(keymap.action(&chord("g")), Some(&UiAction::CycleSort));
+    }
+
+    #[test]
+    fn test_invalid_entries_are_reported() {
+        assert!(matches!(
+            Keymap::from_config(&keys(&[("<C-d", "quit")])),
+            Err(KeymapError::InvalidChord { .. })
+        ));
+        assert_eq!(
+            Keymap::from_config(&keys(&[("w", "warp")])),
+            Err(KeymapError::UnknownAction {
+                chord: "w".to_string(),
+                action: "warp".to_string(),
+            })
+        );
+        assert_eq!(
+            Keymap::from_config(&keys(&[("5x", "quit")])),
+            Err(KeymapError::ReservedDigit {
+                chord: "5x".to_string(),
+            })
+        );
+        assert_eq!(
+            Keymap::from_config(&keys(&[("<C-w>", "quit"), ("<c-w>", "refresh")])),
+            Err(KeymapError::DuplicateChord {
+                first: "<C-w>".to_string(),
+                second: "<c-w>".to_string(),
+            })
+        );
+    }
+}
@@ -18,6 +18,8 @@ pub mod daemon;
 pub mod error;
 pub mod input;
 pub mod keybinding;
+pub mod keymap;
+pub mod search;
 pub mod setup;
 pub mod tmux;
 pub mod ui;
@@ -0,0 +1,240 @@
+//! Fuzzy search and quick filters for the session tree.
+//!
+//! The `/` search bar narrows the tree to sessions whose project name,
+//! branch, team, first prompt, model, activity detail or short ID fuzzily
+//! match the query. Status and harness quick filters compose with it.
+//!
+//! All code follows the panic-free policy: no `.unwrap()`, `.expect()`,
+//! `panic!()`, `unreachable!()`, `todo!()`, or direct indexing `[i]`.
+
+use atm_core::{SessionStatus, SessionView};
+
+// ============================================================================
+// Fuzzy Matching
+// ============================================================================
+
+/// Matches `term` against `text`, ignoring case.
+///
+/// A contiguous substring match is preferred; otherwise the characters
+/// of `term` must appear in order (fzf-style subsequence). Returns the
+/// char indices of `text` that matched, or `None` if it doesn't match.
+/// An empty term matches nothing.
+pub fn fuzzy_match(term: &str, text: &str) -> Option<Vec<usize>> {
+    let term: Vec<char> = term.chars().flat_map(char::to_lowercase).collect();
+    if term.is_empty() {
+        return None;
+    }
+    // Lowercasing can expand a char (e.g. 'İ'); keep the source index of each
+    let text: Vec<(usize, char)> = text
+        .chars()
+        .enumerate()
+        .flat_map(|(i, c)| c.to_lowercase().map(move |l| (i, l)))
+        .collect();
+
+    let contiguous = text
+        .windows(term.len())
+        .position(|window| window.iter().map(|(_, c)| c).eq(term.iter()));
+    if let Some(start) = contiguous {
+        let mut positions: Vec<usize> = text
+            .iter()
+            .skip(start)
+            .take(term.len())
+            .map(|(i, _)| *i)
+            .collect();
+        positions.dedup();
+        return Some(positions);
+    }
+
+    let mut positions = Vec::with_capacity(term.len());
+    let mut chars = text.iter();
+    for wanted in &term {
+        let (i, _) = chars.find(|(_, c)| c == wanted)?;
+        positions.push(*i);
+    }
+    positions.dedup();
+    Some(positions)
+}
+
+/// Project name shown in the tree: last component of the project root.
+fn project_name(session: &SessionView) -> Option<&str> {
+    session
+        .project_root
+        .as_deref()
+        .and_then(|root| root.rsplit('/').find(|s| !s.is_empty()))
+}
+
+/// Whether `session` matches every whitespace-separated term of `query`
+/// in at least one of its searchable fields. A blank query matches all.
+pub fn session_matches(session: &SessionView, query: &str) -> bool {
+    let fields = [
+        project_name(session),
+        session.worktree_branch.as_deref(),
+        session.team.as_deref(),
+        session.first_prompt.as_deref(),
+        Some(session.model.as_str()),
+        session.activity_detail.as_deref(),
+        Some(session.id_short.as_str()),
+    ];
+    query.split_whitespace().all(|term| {
+        fields
+            .iter()
+            .flatten()
+            .any(|field| fuzzy_match(term, field).is_some())
+    })
+}
+
+/// Char indices of `text` matched by any term of `query`, for highlighting.
+pub fn highlight_positions(query: &str, text: &str) -> Vec<usize> {
+    let mut positions: Vec<usize> = query
+        .split_whitespace()
+        .filter_map(|term| fuzzy_match(term, text))
+        .flatten()
+        .collect();
+    positions.sort_unstable();
+    positions.dedup();
+    positions
+}
+
+// ============================================================================
+// Quick Filters
+// ============================================================================
+
+/// Status quick filter, cycled with `s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFilter {
+    /// Sessions waiting for the user.
+    Attention,
+    /// Sessions actively working.
+    Working,
+    /// Idle sessions.
+    Idle,
+}
+
+impl StatusFilter {
+    /// The filter after `current` in the cycle (none → attention →
+    /// working → idle → none).
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Self::Attention),
+            Some(Self::Attention) => Some(Self::Working),
+            Some(Self::Working) => Some(Self::Idle),
+            Some(Self::Idle) => None,
+        }
+    }
+
+    /// Label shown in the search bar.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Attention => "attention",
+            Self::Working => "working",
+            Self::Idle => "idle",
+        }
+    }
+
+    /// Whether `session` passes this filter.
+    pub fn matches(self, session: &SessionView) -> bool {
+        match self {
+            Self::Attention => session.status == SessionStatus::AttentionNeeded,
+            Self::Working => session.status == SessionStatus::Working,
+            Self::Idle => session.status == SessionStatus::Idle,
+        }
+    }
+}
+
+/// Harness name as shown in the tree; legacy sessions default to claude.
+pub fn harness_name(session: &SessionView) -> &str {
+    if session.harness.is_empty() {
+        "claude"
+    } else {
+        &session.harness
+    }
+}
+
+// ============================================================================
+// Search Bar State
+// ============================================================================
+
+/// State of the `/` search bar.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchState {
+    /// The current query; empty when no search is active.
+    pub query: String,
+    /// Whether keys are being typed into the query.
+    pub editing: bool,
+}
+
+impl SearchState {
+    /// Whether a non-blank query is narrowing the tree.
+    pub fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> SessionView {
+        SessionView {
+            id_short: "a1b2c3d4".to_string(),
+            model: "Opus 4.5".to_string(),
+            project_root: Some("/home/user/atm".to_string()),
+            worktree_branch: Some("feature/search".to_string()),
+            first_prompt: Some("Fix the flaky reconnect test".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_match_prefers_substring() {
+        assert_eq!(fuzzy_match("arc", "feature/search"), Some(vec![10, 11, 12]));
+        assert_eq!(fuzzy_match("OPUS", "Opus 4.5"), Some(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn test_fuzzy_match_subsequence() {
+        assert_eq!(fuzzy_match("fsr", "feature/search"), Some(vec![0, 8, 11]));
+        assert_eq!(fuzzy_match("xyz", "feature/search"), None);
+        assert_eq!(fuzzy_match("", "anything"), None);
+    }
+
+    #[test]
+    fn test_session_matches_any_field() {
+        let s = session();
+        assert!(session_matches(&s, "atm"));
+        assert!(session_matches(&s, "flaky"));
+        assert!(session_matches(&s, "a1b2"));
+        assert!(session_matches(&s, "opus search"));
+        assert!(!session_matches(&s, "opus sonnet"));
+        assert!(session_matches(&s, "   "));
+    }
+
+    #[test]
+    fn test_highlight_positions_merges_terms() {
+        assert_eq!(highlight_positions("op 4", "Opus 4.5"), vec![0, 1, 5]);
+        assert!(highlight_positions("zzz", "Opus 4.5").is_empty());
+    }
+
+    #[test]
+    fn test_status_filter_cycles_back_to_none() {
+        let mut filter = None;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            filter = StatusFilter::cycle(filter);
+            seen.push(filter);
+        }
+        assert_eq!(
+            seen,
+            vec![
+                Some(StatusFilter::Attention),
+                Some(StatusFilter::Working),
+                Some(StatusFilter::Idle),
+                None
+            ]
+        );
+    }
+}
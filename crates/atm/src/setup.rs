@@ -11,6 +11,10 @@
 //!   `"packages/pi-atm"` to `~/.pi/agent/settings.json`'s `packages`
 //!   array (the entry is resolved relative to pi's `agentDir`).
 //!   Mirrors how pi-amplike documents local-dev installs.
+//! - **Codex CLI**: writes the `atm-codex-hook` bash script to
+//!   `~/.local/bin/`, then points the top-level `notify` key of
+//!   `$CODEX_HOME/config.toml` (default `~/.codex/`) at it. An existing
+//!   `notify` program is left alone, since Codex only runs one.
 
 use std::fs;
 #[cfg(unix)]
@@ -23,6 +27,13 @@ use serde_json::{json, Value};
 /// The atm-hook bash script content (Claude Code), embedded at compile time.
 const ATM_HOOK_SCRIPT: &str = include_str!("../scripts/atm-hook");
 
+/// The atm-codex-hook bash script content (Codex `notify`), embedded at
+/// compile time.
+const ATM_CODEX_HOOK_SCRIPT: &str = include_str!("../scripts/atm-codex-hook");
+
+/// Comment written above the `notify` line so uninstall can find it.
+const CODEX_NOTIFY_MARKER: &str = "# Added by `atm setup`: forward Codex events to atmd";
+
 /// The pi-atm TypeScript extension content, embedded at compile time.
 /// pi loads `.ts` files directly via `@mariozechner/jiti`.
 const PI_ATM_EXTENSION: &str = include_str!("../assets/pi-atm/extensions/pi-atm.ts");
@@ -189,11 +200,14 @@ fn remove_atm_hooks(hooks_array: &mut Vec<Value>) {
 }
 
 /// Installs the atm-hook script to ~/.local/bin/
-///
-/// Creates the directory if it doesn't exist and sets executable permissions.
 fn install_hook_script() -> Result<()> {
-    let hook_path = hook_script_path();
+    install_script(&hook_script_path(), ATM_HOOK_SCRIPT)
+}
 
+/// Writes an embedded hook script to `hook_path`.
+///
+/// Creates the directory if it doesn't exist and sets executable permissions.
+fn install_script(hook_path: &Path, content: &str) -> Result<()> {
     // Create parent directory if needed
     if let Some(parent) = hook_path.parent() {
         fs::create_dir_all(parent)
@@ -201,15 +215,15 @@ fn install_hook_script() -> Result<()> {
     }
 
     // Write the script
-    fs::write(&hook_path, ATM_HOOK_SCRIPT)
+    fs::write(hook_path, content)
         .with_context(|| format!("Failed to write {}", hook_path.display()))?;
 
     // Make executable on Unix
     #[cfg(unix)]
     {
-        let mut perms = fs::metadata(&hook_path)?.permissions();
+        let mut perms = fs::metadata(hook_path)?.permissions();
         perms.set_mode(0o755);
-        fs::set_permissions(&hook_path, perms)
+        fs::set_permissions(hook_path, perms)
             .with_context(|| format!("Failed to set permissions on {}", hook_path.display()))?;
     }
 
@@ -282,6 +296,164 @@ fn detect_pi() -> bool {
         .unwrap_or(false)
 }
 
+/// Codex's config directory: `$CODEX_HOME`, else `~/.codex`.
+fn codex_home() -> Option<PathBuf> {
+    std::env::var_os("CODEX_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".codex")))
+}
+
+/// True if Codex CLI appears to be installed for this user.
+///
+/// Codex creates its home directory (sessions, auth, config) on first
+/// run, so the directory is a better signal than the binary's location.
+fn detect_codex() -> bool {
+    codex_home().map(|dir| dir.exists()).unwrap_or(false)
+}
+
+// ============================================================================
+// Codex setup
+// ============================================================================
+
+/// Returns the path to the atm-codex-hook script
+fn codex_hook_script_path() -> PathBuf {
+    hook_script_path().with_file_name("atm-codex-hook")
+}
+
+/// Outcome of wiring `notify` in Codex's config.toml.
+enum CodexNotify {
+    Added,
+    AlreadyConfigured,
+    /// Another notify program is configured; Codex only runs one.
+    Conflict(String),
+}
+
+/// Points Codex's `notify` at the atm-codex-hook script.
+///
+/// Edits config.toml through `toml_edit` so the user's comments and
+/// layout survive; only the top-level `notify` key is touched.
+fn install_codex_notify(config_path: &Path, hook_path: &Path) -> Result<CodexNotify> {
+    let content = if config_path.exists() {
+        fs::read_to_string(config_path)
+            .with_context(|| format!("Failed to read {}", config_path.display()))?
+    } else {
+        String::new()
+    };
+    let mut doc = parse_codex_config(config_path, &content)?;
+
+    let outcome = add_codex_notify(&mut doc, hook_path);
+    if matches!(outcome, CodexNotify::Added) {
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(config_path, doc.to_string())
+            .with_context(|| format!("Failed to write {}", config_path.display()))?;
+    }
+    Ok(outcome)
+}
+
+/// Removes the `notify` key written by [`install_codex_notify`].
+fn uninstall_codex_notify(config_path: &Path) -> Result<bool> {
+    if !config_path.exists() {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read {}", config_path.display()))?;
+    let mut doc = parse_codex_config(config_path, &content)?;
+
+    if !remove_codex_notify(&mut doc) {
+        return Ok(false);
+    }
+    fs::write(config_path, doc.to_string())
+        .with_context(|| format!("Failed to write {}", config_path.display()))?;
+    Ok(true)
+}
+
+fn parse_codex_config(config_path: &Path, content: &str) -> Result<toml_edit::DocumentMut> {
+    content
+        .parse()
+        .with_context(|| format!("Failed to parse {}", config_path.display()))
+}
+
+/// Sets the top-level `notify` to the hook, unless something already is.
+///
+/// The new key goes in the root table, which `toml_edit` renders ahead of
+/// any `[table]` header, with [`CODEX_NOTIFY_MARKER`] above it.
+fn add_codex_notify(doc: &mut toml_edit::DocumentMut, hook_path: &Path) -> CodexNotify {
+    if let Some(existing) = doc.get("notify") {
+        let existing = existing.to_string().trim().to_string();
+        return if existing.contains("atm-codex-hook") {
+            CodexNotify::AlreadyConfigured
+        } else {
+            CodexNotify::Conflict(existing)
+        };
+    }
+
+    let notify: toml_edit::Array = [hook_path.to_string_lossy().to_string()]
+        .into_iter()
+        .collect();
+    doc.insert("notify", toml_edit::value(notify));
+    if let Some(mut key) = doc.key_mut("notify") {
+        key.leaf_decor_mut()
+            .set_prefix(format!("{CODEX_NOTIFY_MARKER}\n"));
+    }
+    CodexNotify::Added
+}
+
+/// Drops the top-level `notify` if it runs atm-codex-hook.
+///
+/// Keys of the same name inside tables, and a `notify` pointing at
+/// another program, are left alone.
+fn remove_codex_notify(doc: &mut toml_edit::DocumentMut) -> bool {
+    let ours = doc
+        .get("notify")
+        .and_then(|item| item.as_array())
+        .is_some_and(|notify| {
+            notify
+                .iter()
+                .any(|arg| arg.as_str().is_some_and(|a| a.contains("atm-codex-hook")))
+        });
+    if ours {
+        doc.remove("notify");
+    }
+    ours
+}
+
+/// Installs `atm-codex-hook` and registers it as Codex's `notify` program.
+fn setup_codex() -> Result<()> {
+    println!("\nConfiguring Codex CLI...");
+    let hook_path = codex_hook_script_path();
+    print!("  Installing hook script to {}... ", hook_path.display());
+    install_script(&hook_path, ATM_CODEX_HOOK_SCRIPT)?;
+    println!("done");
+
+    let config_path = codex_home()
+        .context("Could not determine home directory")?
+        .join("config.toml");
+    match install_codex_notify(&config_path, &hook_path)? {
+        CodexNotify::Added => {
+            println!("    config.toml - added notify");
+            println!("  Codex configuration written.");
+        }
+        CodexNotify::AlreadyConfigured => println!("  Codex already configured."),
+        CodexNotify::Conflict(existing) => {
+            println!("    config.toml - notify is already set to {existing}");
+            println!(
+                "  Left unchanged. To use ATM, set notify = [\"{}\"] in {}",
+                hook_path.display(),
+                config_path.display()
+            );
+        }
+    }
+    println!(
+        "  Note: Codex only notifies on turn completion and approval requests, so Codex\n  \
+         sessions show status only (no tool activity or context usage)."
+    );
+    Ok(())
+}
+
 // ============================================================================
 // pi setup
 // ============================================================================
@@ -411,6 +583,7 @@ pub fn setup() -> Result<()> {
     // will (and won't) be configured.
     let claude = detect_claude_code();
     let pi = detect_pi();
+    let codex = detect_codex();
 
     println!("Detected coding agents:");
     println!(
@@ -423,9 +596,16 @@ pub fn setup() -> Result<()> {
         if pi { "✓" } else { "✗" },
         if pi { "" } else { " not present" }
     );
+    println!(
+        "  {} Codex CLI    (~/.codex/{})",
+        if codex { "✓" } else { "✗" },
+        if codex { "" } else { " not present" }
+    );
 
-    if !claude && !pi {
-        println!("\nNo supported agent installations found. Install Claude Code or pi first.");
+    if !claude && !pi && !codex {
+        println!(
+            "\nNo supported agent installations found. Install Claude Code, pi or Codex first."
+        );
         return Ok(());
     }
 
@@ -437,6 +617,10 @@ pub fn setup() -> Result<()> {
         setup_pi()?;
     }
 
+    if codex {
+        setup_codex()?;
+    }
+
     // Step N: Install tmux keybindings (vendor-neutral).
     println!();
     install_tmux_bindings()?;
@@ -599,6 +783,110 @@ pub fn uninstall() -> Result<()> {
         }
     }
 
+    // Step 5: Unhook Codex and remove its notify script
+    if let Some(config_path) = codex_home().map(|dir| dir.join("config.toml")) {
+        match uninstall_codex_notify(&config_path) {
+            Ok(true) => println!("\nCodex notify removed from {}", config_path.display()),
+            Ok(false) => {}
+            Err(e) => println!("\nFailed to update {}: {e}", config_path.display()),
+        }
+    }
+    let codex_hook = codex_hook_script_path();
+    if codex_hook.exists() {
+        fs::remove_file(&codex_hook)
+            .with_context(|| format!("Failed to remove {}", codex_hook.display()))?;
+        println!("Removed hook script {}", codex_hook.display());
+    }
+
     println!("\nATM uninstalled successfully!");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOOK: &str = "/home/u/.local/bin/atm-codex-hook";
+
+    #[test]
+    fn test_codex_hook_speaks_current_protocol() {
+        let json = ATM_CODEX_HOOK_SCRIPT
+            .lines()
+            .find_map(|line| line.strip_prefix("PROTOCOL_VERSION='"))
+            .and_then(|rest| rest.strip_suffix('\''))
+            .expect("atm-codex-hook sets PROTOCOL_VERSION");
+        let version: atm_protocol::ProtocolVersion = serde_json::from_str(json).unwrap();
+        assert_eq!(version, atm_protocol::ProtocolVersion::CURRENT);
+    }
+
+    fn doc(content: &str) -> toml_edit::DocumentMut {
+        content.parse().unwrap()
+    }
+
+    #[test]
+    fn test_codex_notify_round_trip_preserves_config() {
+        let original = "# my settings\nmodel = \"o3\" # pinned\n\n[mcp_servers.docs]\ncommand = \"docs-mcp\"\nargs = [\n  \"--port\",\n  \"[not a table]\",\n]\n";
+        let mut config = doc(original);
+
+        assert!(matches!(
+            add_codex_notify(&mut config, Path::new(HOOK)),
+            CodexNotify::Added
+        ));
+        let installed = config.to_string();
+        let parsed: toml::Table = installed.parse().unwrap();
+        assert_eq!(
+            parsed["notify"],
+            toml::Value::Array(vec![toml::Value::String(HOOK.into())])
+        );
+        assert!(installed.contains(CODEX_NOTIFY_MARKER));
+        // Top-level, ahead of the first table header
+        assert!(installed.find("notify").unwrap() < installed.find("[mcp_servers").unwrap());
+
+        let mut config = doc(&installed);
+        assert!(remove_codex_notify(&mut config));
+        assert_eq!(config.to_string(), original);
+    }
+
+    #[test]
+    fn test_codex_notify_install_into_empty_config() {
+        let mut config = doc("");
+        assert!(matches!(
+            add_codex_notify(&mut config, Path::new(HOOK)),
+            CodexNotify::Added
+        ));
+        assert_eq!(
+            config.to_string(),
+            format!("{CODEX_NOTIFY_MARKER}\nnotify = [\"{HOOK}\"]\n")
+        );
+    }
+
+    #[test]
+    fn test_codex_notify_install_is_idempotent_and_respects_others() {
+        let mut config = doc(&format!("notify = [\"{HOOK}\"]\n"));
+        assert!(matches!(
+            add_codex_notify(&mut config, Path::new(HOOK)),
+            CodexNotify::AlreadyConfigured
+        ));
+
+        let mut config = doc("notify = [\"notify-send\", \"codex\"]\n");
+        match add_codex_notify(&mut config, Path::new(HOOK)) {
+            CodexNotify::Conflict(existing) => {
+                assert_eq!(existing, "[\"notify-send\", \"codex\"]");
+            }
+            _ => panic!("expected a conflict"),
+        }
+        assert_eq!(
+            config.to_string(),
+            "notify = [\"notify-send\", \"codex\"]\n"
+        );
+    }
+
+    #[test]
+    fn test_codex_notify_uninstall_only_touches_top_level_key() {
+        let original =
+            format!("notify = [\"notify-send\"]\n\n[profiles.work]\nnotify = [\"{HOOK}\"]\n");
+        let mut config = doc(&original);
+        assert!(!remove_codex_notify(&mut config));
+        assert_eq!(config.to_string(), original);
+    }
+}
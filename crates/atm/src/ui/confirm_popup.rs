@@ -0,0 +1,113 @@
+//! Confirmation popup for actions applied to every marked agent.
+//!
+//! Lists the affected sessions and their panes so a multi-agent kill or
+//! interrupt is never sent blind.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::BroadcastConfirm;
+
+use super::layout::centered_popup;
+
+/// Renders the broadcast confirmation popup overlay.
+///
+/// # Arguments
+/// * `frame` - The frame to render into
+/// * `area` - The full terminal area (popup will be centered within it)
+/// * `confirm` - The pending broadcast action
+pub fn render_confirm_popup(frame: &mut Frame, area: Rect, confirm: &BroadcastConfirm) {
+    let popup_area = centered_popup(60, 50, area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let title = format!(
+        " {} {} agent{}? ",
+        confirm.action.label(),
+        confirm.targets.len(),
+        if confirm.targets.len() == 1 { "" } else { "s" }
+    );
+    let widget = Paragraph::new(build_confirm_lines(confirm)).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red)),
+    );
+
+    frame.render_widget(widget, popup_area);
+}
+
+/// Builds the content lines: one per affected pane, then the key hint.
+fn build_confirm_lines(confirm: &BroadcastConfirm) -> Vec<Line<'static>> {
+    let pane_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let dim_style = Style::default().fg(Color::DarkGray);
+
+    let mut lines = vec![Line::from("")];
+    for target in &confirm.targets {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<6} ", target.pane_id), pane_style),
+            Span::raw(target.id_short.clone()),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  y/Enter confirm · n/Esc cancel",
+        dim_style,
+    )));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{BroadcastAction, BroadcastTarget};
+    use atm_core::SessionId;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn confirm() -> BroadcastConfirm {
+        BroadcastConfirm {
+            action: BroadcastAction::Kill,
+            targets: vec![
+                BroadcastTarget {
+                    session_id: SessionId::new("a1b2c3d4-one"),
+                    id_short: "a1b2c3d4".to_string(),
+                    pane_id: "%3".to_string(),
+                },
+                BroadcastTarget {
+                    session_id: SessionId::new("e5f6a7b8-two"),
+                    id_short: "e5f6a7b8".to_string(),
+                    pane_id: "%12".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_lines_list_every_pane() {
+        let texts: Vec<String> = build_confirm_lines(&confirm())
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert!(texts.iter().any(|t| t == "  %3     a1b2c3d4"));
+        assert!(texts.iter().any(|t| t == "  %12    e5f6a7b8"));
+    }
+
+    #[test]
+    fn test_render_smoke_various_sizes() {
+        let confirm = confirm();
+        for (w, h) in [(80, 24), (30, 10), (10, 5)] {
+            let backend = TestBackend::new(w, h);
+            let mut terminal = Terminal::new(backend).unwrap();
+            terminal
+                .draw(|frame| render_confirm_popup(frame, frame.area(), &confirm))
+                .unwrap();
+        }
+    }
+}
@@ -170,6 +170,17 @@ fn build_detail_lines_inline(session: &SessionView) -> Vec<Line<'static>> {
                 Style::default().fg(Color::DarkGray),
             ),
         ]),
+    ];
+
+    // Forecast, once the session has a turn or two of history
+    if let Some(forecast) = session.context_forecast {
+        lines.push(Line::from(vec![
+            Span::styled("  Compacts in ", label_style),
+            Span::styled(forecast.format(), Style::default().fg(ctx_color)),
+        ]));
+    }
+
+    lines.extend([
         Line::from(""),
         // Duration and activity
         Line::from(vec![
@@ -184,7 +195,7 @@ fn build_detail_lines_inline(session: &SessionView) -> Vec<Line<'static>> {
             Span::styled(session.lines_display.clone(), value_style),
         ]),
         Line::from(""),
-    ];
+    ]);
 
     // Working directory
     if let Some(ref dir) = session.working_directory {
@@ -196,7 +207,13 @@ fn build_detail_lines_inline(session: &SessionView) -> Vec<Line<'static>> {
     }
 
     // Warnings
-    if session.needs_attention {
+    if session.over_budget {
+        lines.push(Line::from(vec![Span::styled(
+            format!("  $ Over budget ({})", session.cost_display),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )]));
+    }
+    if session.status.needs_attention() || (session.needs_attention && !session.over_budget) {
         lines.push(Line::from(vec![Span::styled(
             "  ! Waiting for input",
             Style::default()
@@ -1,8 +1,10 @@
 //! Help popup overlay showing all keybindings.
 //!
 //! Renders a centered popup on top of the existing layout, listing
-//! available keyboard shortcuts grouped by category. Content is derived
-//! from [`KEYBINDING_HINTS`] in `keybinding.rs`.
+//! available keyboard shortcuts grouped by category. Rows come from
+//! [`ACTION_HINTS`] in `keybinding.rs`, with the keys each action is bound
+//! to in the effective [`Keymap`], followed by the [`FIXED_HINTS`] for
+//! keys the keymap does not own.
 
 use ratatui::{
     layout::Rect,
@@ -12,10 +14,14 @@ use ratatui::{
     Frame,
 };
 
-use crate::keybinding::{HintCategory, KEYBINDING_HINTS};
+use crate::keybinding::{HintCategory, UiAction, ACTION_HINTS, FIXED_HINTS};
+use crate::keymap::{Chord, Keymap};
 
 use super::layout::centered_popup;
 
+/// Width of the key column; longer key lists drop the spaces around `/`.
+const KEY_WIDTH: usize = 11;
+
 /// Renders the help popup overlay.
 ///
 /// Clears the background behind the popup and renders a bordered
@@ -25,14 +31,15 @@ use super::layout::centered_popup;
 /// # Arguments
 /// * `frame` - The frame to render into
 /// * `area` - The full terminal area (popup will be centered within it)
-pub fn render_help_popup(frame: &mut Frame, area: Rect) {
+/// * `keymap` - The effective keymap whose chords are listed
+pub fn render_help_popup(frame: &mut Frame, area: Rect, keymap: &Keymap) {
     let popup_area = centered_popup(60, 70, area);
 
     // Clear the area behind the popup
     frame.render_widget(Clear, popup_area);
 
     let in_tmux = crate::tmux::is_in_tmux();
-    let lines = build_help_lines(in_tmux);
+    let lines = build_help_lines(keymap, in_tmux);
 
     let popup = Paragraph::new(lines).block(
         Block::default()
@@ -47,8 +54,9 @@ pub fn render_help_popup(frame: &mut Frame, area: Rect) {
 /// Builds the styled content lines for the help popup.
 ///
 /// Groups keybindings by category with headings, and filters out
-/// tmux-only entries when `in_tmux` is false.
-fn build_help_lines(in_tmux: bool) -> Vec<Line<'static>> {
+/// tmux-only entries when `in_tmux` is false. Unbound actions are left
+/// out.
+fn build_help_lines(keymap: &Keymap, in_tmux: bool) -> Vec<Line<'static>> {
     let key_style = Style::default()
         .fg(Color::Cyan)
         .add_modifier(Modifier::BOLD);
@@ -57,37 +65,127 @@ fn build_help_lines(in_tmux: bool) -> Vec<Line<'static>> {
         .add_modifier(Modifier::BOLD);
 
     let mut lines = vec![Line::from("")];
-    let mut current_category = None;
 
-    for entry in KEYBINDING_HINTS {
-        // Skip tmux-only entries when not in tmux
-        if entry.tmux_only && !in_tmux {
+    for category in [
+        HintCategory::Navigation,
+        HintCategory::Actions,
+        HintCategory::Reply,
+        HintCategory::Search,
+    ] {
+        let rows = category_rows(keymap, category, in_tmux);
+        if rows.is_empty() {
             continue;
         }
 
-        // Insert category heading when category changes
-        if current_category != Some(entry.category) {
-            if current_category.is_some() {
-                lines.push(Line::from(""));
-            }
-            let heading = match entry.category {
-                HintCategory::Navigation => "  Navigation",
-                HintCategory::Actions => "  Actions",
-            };
-            lines.push(Line::from(Span::styled(heading, heading_style)));
+        if lines.len() > 1 {
             lines.push(Line::from(""));
-            current_category = Some(entry.category);
         }
+        let heading = match category {
+            HintCategory::Navigation => "  Navigation",
+            HintCategory::Actions => "  Actions",
+            HintCategory::Reply => "  Reply",
+            HintCategory::Search => "  Search",
+        };
+        lines.push(Line::from(Span::styled(heading, heading_style)));
+        lines.push(Line::from(""));
 
-        lines.push(Line::from(vec![
-            Span::styled(format!("    {:<11} ", entry.help_key), key_style),
-            Span::raw(entry.help_desc),
-        ]));
+        for (keys, desc) in rows {
+            lines.push(Line::from(vec![
+                Span::styled(format!("    {keys:<KEY_WIDTH$} "), key_style),
+                Span::raw(desc),
+            ]));
+        }
     }
 
     lines
 }
 
+/// The (keys, description) rows listed under one category heading.
+fn category_rows(
+    keymap: &Keymap,
+    category: HintCategory,
+    in_tmux: bool,
+) -> Vec<(String, &'static str)> {
+    let visible = |category_of: HintCategory, tmux_only: bool| {
+        category_of == category && (in_tmux || !tmux_only)
+    };
+
+    let hints: Vec<_> = ACTION_HINTS
+        .iter()
+        .filter(|hint| visible(hint.category, hint.tmux_only))
+        .collect();
+
+    let mut rows = Vec::new();
+    for group in hints.chunk_by(|a, b| a.help_desc == b.help_desc) {
+        let chords: Vec<&Chord> = group
+            .iter()
+            .flat_map(|hint| keymap.chords(&hint.action))
+            .collect();
+        let keys = if group.len() > 1 {
+            range_label(&chords)
+        } else {
+            join_label(&chords)
+        };
+        if let (Some(keys), Some(hint)) = (keys, group.first()) {
+            rows.push((keys, hint.help_desc));
+        }
+    }
+
+    if category == HintCategory::Navigation {
+        rows.extend(count_rows(keymap));
+    }
+
+    rows.extend(
+        FIXED_HINTS
+            .iter()
+            .filter(|hint| visible(hint.category, hint.tmux_only))
+            .map(|hint| (hint.help_key.to_string(), hint.help_desc)),
+    );
+
+    rows
+}
+
+/// Rows explaining counts, spelled with the keys they apply to.
+fn count_rows(keymap: &Keymap) -> Vec<(String, &'static str)> {
+    let first = |action: UiAction| keymap.chords(&action).next().map(|c| format!("N{c}"));
+
+    let mut rows = Vec::new();
+    if let Some(top) = first(UiAction::GoToFirst) {
+        rows.push((top, "Go to row N"));
+    }
+    let moves: Vec<String> = [UiAction::MoveDown(1), UiAction::MoveUp(1)]
+        .into_iter()
+        .filter_map(first)
+        .collect();
+    if !moves.is_empty() {
+        rows.push((moves.join(" / "), "Move N rows"));
+    }
+    rows
+}
+
+/// All of an action's chords, spaced out when they fit the key column.
+fn join_label(chords: &[&Chord]) -> Option<String> {
+    if chords.is_empty() {
+        return None;
+    }
+    let labels: Vec<String> = chords.iter().map(ToString::to_string).collect();
+    let spaced = labels.join(" / ");
+    if spaced.chars().count() <= KEY_WIDTH {
+        Some(spaced)
+    } else {
+        Some(labels.join("/"))
+    }
+}
+
+/// A numbered family of actions (`r1..r9`) shown as its first and last chord.
+fn range_label(chords: &[&Chord]) -> Option<String> {
+    match (chords.first(), chords.last()) {
+        (Some(first), Some(last)) if chords.len() > 1 => Some(format!("{first}..{last}")),
+        (Some(only), _) => Some(only.to_string()),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,7 +198,10 @@ mod tests {
 
     /// Helper: build lines and return as plain text strings.
     fn help_texts(in_tmux: bool) -> Vec<String> {
-        build_help_lines(in_tmux).iter().map(line_text).collect()
+        build_help_lines(&Keymap::default(), in_tmux)
+            .iter()
+            .map(line_text)
+            .collect()
     }
 
     #[test]
@@ -125,11 +226,14 @@ mod tests {
     #[test]
     fn test_all_hints_present_when_in_tmux() {
         let texts = help_texts(true);
-        for entry in KEYBINDING_HINTS {
+        let descs = ACTION_HINTS
+            .iter()
+            .map(|e| e.help_desc)
+            .chain(FIXED_HINTS.iter().map(|e| e.help_desc));
+        for desc in descs {
             assert!(
-                texts.iter().any(|t| t.contains(entry.help_desc)),
-                "Missing entry: {:?}",
-                entry.help_desc
+                texts.iter().any(|t| t.contains(desc)),
+                "Missing entry: {desc:?}"
             );
         }
     }
@@ -143,18 +247,27 @@ mod tests {
         assert!(without.len() < with.len());
 
         // tmux-only entries absent
-        for entry in KEYBINDING_HINTS.iter().filter(|e| e.tmux_only) {
+        let descs = ACTION_HINTS
+            .iter()
+            .filter(|e| e.tmux_only)
+            .map(|e| e.help_desc)
+            .chain(
+                FIXED_HINTS
+                    .iter()
+                    .filter(|e| e.tmux_only)
+                    .map(|e| e.help_desc),
+            );
+        for desc in descs {
             assert!(
-                !without.iter().any(|t| t.contains(entry.help_desc)),
-                "tmux-only entry should be filtered: {:?}",
-                entry.help_desc
+                !without.iter().any(|t| t.contains(desc)),
+                "tmux-only entry should be filtered: {desc:?}"
             );
         }
     }
 
     #[test]
     fn test_key_column_width_consistent() {
-        let lines = build_help_lines(true);
+        let lines = build_help_lines(&Keymap::default(), true);
 
         // Entry lines have exactly 2 spans (styled key + raw description).
         // Key column: "    {:<11} " = 16 chars.
@@ -177,8 +290,37 @@ mod tests {
             let backend = TestBackend::new(w, h);
             let mut terminal = Terminal::new(backend).unwrap();
             terminal
-                .draw(|frame| render_help_popup(frame, frame.area()))
+                .draw(|frame| render_help_popup(frame, frame.area(), &Keymap::default()))
                 .unwrap();
         }
     }
+
+    #[test]
+    fn test_rows_follow_the_effective_keymap() {
+        let keys = [("<C-n>", "move_down"), ("x", "none"), ("I", "none")]
+            .into_iter()
+            .map(|(k, a)| (k.to_string(), a.to_string()))
+            .collect();
+        let keymap = Keymap::from_config(&keys).unwrap();
+        let texts: Vec<String> = build_help_lines(&keymap, true)
+            .iter()
+            .map(line_text)
+            .collect();
+        let row = |desc: &str| texts.iter().find(|t| t.ends_with(desc)).cloned();
+
+        assert_eq!(
+            row("Move down").as_deref(),
+            Some("    j / \u{2193} / ^n  Move down")
+        );
+        assert_eq!(
+            row("Kill agent (or marked) + close pane").as_deref(),
+            Some("    dd          Kill agent (or marked) + close pane")
+        );
+        assert_eq!(row("Interrupt agent (or marked)"), None);
+        assert_eq!(
+            row("Pick option N").as_deref(),
+            Some("    r1..r9      Pick option N")
+        );
+        assert_eq!(row("Quit").as_deref(), Some("    q/Q/^c/Esc  Quit"));
+    }
 }
@@ -22,9 +22,11 @@
 //!
 //! The detail panel always shows the selected session's details.
 
+pub mod confirm_popup;
 pub mod detail_panel;
 pub mod help_popup;
 pub mod layout;
+pub mod reply_popup;
 pub mod session_list;
 pub mod status_bar;
 pub mod theme;
@@ -75,9 +77,15 @@ pub fn render(frame: &mut Frame, app: &App) {
         &app.captured_output,
     );
 
-    // Render help popup overlay (on top of everything)
+    // Render popup overlays (help on top of everything)
+    if let Some(popup) = &app.reply {
+        reply_popup::render_reply_popup(frame, frame.area(), popup);
+    }
+    if let Some(confirm) = &app.confirm {
+        confirm_popup::render_confirm_popup(frame, frame.area(), confirm);
+    }
     if app.show_help {
-        help_popup::render_help_popup(frame, frame.area());
+        help_popup::render_help_popup(frame, frame.area(), &app.keymap);
     }
 }
 
@@ -99,8 +107,14 @@ pub fn render_compact(frame: &mut Frame, app: &App) {
         &app.captured_output,
     );
 
+    if let Some(popup) = &app.reply {
+        reply_popup::render_reply_popup(frame, frame.area(), popup);
+    }
+    if let Some(confirm) = &app.confirm {
+        confirm_popup::render_confirm_popup(frame, frame.area(), confirm);
+    }
     if app.show_help {
-        help_popup::render_help_popup(frame, frame.area());
+        help_popup::render_help_popup(frame, frame.area(), &app.keymap);
     }
 }
 
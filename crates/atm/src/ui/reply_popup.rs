@@ -0,0 +1,178 @@
+//! Reply popup overlay for answering a waiting agent's prompt.
+//!
+//! Renders a centered popup listing the prompt's question and numbered
+//! options with the popup cursor, plus a text entry line while typing a
+//! free-text answer. Content comes from the [`ReplyPopup`] in app state.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::ReplyPopup;
+
+use super::layout::centered_popup;
+
+/// Renders the reply popup overlay.
+///
+/// # Arguments
+/// * `frame` - The frame to render into
+/// * `area` - The full terminal area (popup will be centered within it)
+/// * `popup` - The open reply popup
+pub fn render_reply_popup(frame: &mut Frame, area: Rect, popup: &ReplyPopup) {
+    let popup_area = centered_popup(80, 60, area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let title = match &popup.prompt {
+        Some(prompt) => format!(" Reply: {} ", prompt.kind.label()),
+        None => " Reply ".to_string(),
+    };
+    let widget = Paragraph::new(build_reply_lines(popup))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        );
+
+    frame.render_widget(widget, popup_area);
+}
+
+/// Builds the styled content lines for the reply popup.
+fn build_reply_lines(popup: &ReplyPopup) -> Vec<Line<'static>> {
+    let question_style = Style::default().add_modifier(Modifier::BOLD);
+    let cursor_style = Style::default()
+        .fg(Color::Cyan)
+        .add_modifier(Modifier::BOLD);
+    let dim_style = Style::default().fg(Color::DarkGray);
+
+    let mut lines = vec![Line::from("")];
+
+    let question = popup.prompt.as_ref().and_then(|p| p.question.clone());
+    lines.push(Line::from(Span::styled(
+        format!(
+            " {}",
+            question.unwrap_or_else(|| "Waiting for input".to_string())
+        ),
+        question_style,
+    )));
+    lines.push(Line::from(""));
+
+    let options = popup
+        .prompt
+        .as_ref()
+        .map(|p| p.options.as_slice())
+        .unwrap_or_default();
+    if options.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " No options recognized; Enter confirms the prompt as shown",
+            dim_style,
+        )));
+    }
+    for (index, option) in options.iter().enumerate() {
+        let number = index.saturating_add(1);
+        let on_cursor = popup.text.is_none() && popup.cursor == number;
+        let marker = if on_cursor { "❯" } else { " " };
+        let text = format!(" {marker} {number}. {}", option.label);
+        lines.push(if on_cursor {
+            Line::from(Span::styled(text, cursor_style))
+        } else {
+            Line::from(text)
+        });
+        if let Some(description) = &option.description {
+            lines.push(Line::from(Span::styled(
+                format!("      {description}"),
+                dim_style,
+            )));
+        }
+    }
+
+    lines.push(Line::from(""));
+    match &popup.text {
+        Some(text) => {
+            lines.push(Line::from(vec![
+                Span::styled(" > ", cursor_style),
+                Span::raw(text.clone()),
+                Span::styled("█", dim_style),
+            ]));
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                " Enter send · Esc back to options",
+                dim_style,
+            )));
+        }
+        None => {
+            let mut hint = String::from(" j/k move · 1-9 pick · Enter send");
+            if popup.accepts_text() {
+                hint.push_str(" · i type");
+            }
+            hint.push_str(" · n dismiss · Esc close");
+            lines.push(Line::from(Span::styled(hint, dim_style)));
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atm_core::{PromptKind, PromptOption, PromptState, SessionId};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    fn question_popup() -> ReplyPopup {
+        ReplyPopup {
+            session_id: SessionId::new("reply-test"),
+            prompt: Some(PromptState::from_hook(
+                PromptKind::Question,
+                Some("Which library?".into()),
+                vec![PromptOption::new("serde"), PromptOption::new("miniserde")],
+            )),
+            cursor: 2,
+            text: None,
+        }
+    }
+
+    #[test]
+    fn test_lines_mark_cursor_and_offer_text() {
+        let texts: Vec<String> = build_reply_lines(&question_popup())
+            .iter()
+            .map(line_text)
+            .collect();
+        assert!(texts.iter().any(|t| t.contains("Which library?")));
+        assert!(texts.iter().any(|t| t == "   1. serde"));
+        assert!(texts.iter().any(|t| t == " ❯ 2. miniserde"));
+        assert!(texts.iter().any(|t| t.contains("i type")));
+    }
+
+    #[test]
+    fn test_lines_show_typed_text() {
+        let mut popup = question_popup();
+        popup.text = Some("toml".into());
+        let texts: Vec<String> = build_reply_lines(&popup).iter().map(line_text).collect();
+        assert!(texts.iter().any(|t| t.starts_with(" > toml")));
+        // No option carries the cursor while typing
+        assert!(!texts.iter().any(|t| t.contains('❯')));
+    }
+
+    #[test]
+    fn test_render_smoke_various_sizes() {
+        let popup = question_popup();
+        for (w, h) in [(80, 24), (30, 10), (10, 5), (200, 50)] {
+            let backend = TestBackend::new(w, h);
+            let mut terminal = Terminal::new(backend).unwrap();
+            terminal
+                .draw(|frame| render_reply_popup(frame, frame.area(), &popup))
+                .unwrap();
+        }
+    }
+}
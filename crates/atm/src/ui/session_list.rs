@@ -4,8 +4,9 @@
 //! real-time status updates including context usage, cost, and duration.
 
 use crate::app::{App, AppState};
+use crate::search::highlight_positions;
 use crate::ui::theme::{context_color, status_background, status_color, status_icon};
-use atm_core::{SessionView, TreeRow, TreeRowKind};
+use atm_core::{GroupBy, SessionView, SortKey, TreeRow, TreeRowKind};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
@@ -16,7 +17,9 @@ use ratatui::{
 
 /// Renders the session list as a tree in the left panel.
 ///
-/// Displays a grouped tree view: Project > Worktree > Agent.
+/// Displays a grouped tree view: Project > Team/Worktree > Agent >
+/// Subagent, with subagents nested to any depth, or agents grouped by
+/// tmux window, harness or status when another view is selected.
 /// Group rows show collapse indicators (▼/▸) and agent counts.
 /// Agent rows show status icon, context %, short ID, and model.
 ///
@@ -34,17 +37,18 @@ pub fn render_session_list(frame: &mut Frame, area: Rect, app: &App) {
         .tree_rows
         .iter()
         .enumerate()
-        .map(|(idx, row)| create_tree_row_item(row, idx == app.selected_index, app.blink_visible))
+        .map(|(idx, row)| {
+            create_tree_row_item(
+                row,
+                idx == app.selected_index,
+                app.is_row_marked(row),
+                app.blink_visible,
+                &app.search.query,
+            )
+        })
         .collect();
 
-    let title = format!(" Sessions ({}) ", app.session_count());
-
-    let list = List::new(items).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title(title)
-            .border_style(Style::default().fg(Color::White)),
-    );
+    let list = List::new(items).block(list_block(app));
 
     frame.render_widget(list, area);
 }
@@ -72,27 +76,35 @@ pub fn render_compact_session_list(frame: &mut Frame, area: Rect, app: &App) {
         .enumerate()
         .map(|(idx, row)| {
             let is_selected = idx == app.selected_index;
-            let indent = "  ".repeat(row.depth as usize);
+            let indent = row_indent(row.depth);
+            let query = app.search.query.as_str();
 
-            let line = match &row.kind {
+            let mut line = match &row.kind {
                 TreeRowKind::Project { name, .. } => {
-                    create_group_line(&indent, name, row, is_selected)
+                    create_group_line(&indent, name, row, is_selected, query)
                 }
                 TreeRowKind::Worktree { branch, path, .. } => {
                     let label = branch.as_deref().unwrap_or_else(|| {
                         path.rsplit('/').find(|s| !s.is_empty()).unwrap_or(path)
                     });
-                    create_group_line(&indent, label, row, is_selected)
+                    create_group_line(&indent, label, row, is_selected, query)
+                }
+                TreeRowKind::Team { name } | TreeRowKind::Group { label: name } => {
+                    create_group_line(&indent, name, row, is_selected, query)
                 }
-                TreeRowKind::Team { name } => create_group_line(&indent, name, row, is_selected),
                 TreeRowKind::Agent { session } => create_compact_agent_line(
                     &indent,
                     session,
+                    row,
                     is_selected,
                     app.blink_visible,
                     inner_width,
+                    query,
                 ),
             };
+            if app.is_row_marked(row) {
+                mark_line(&mut line, is_selected);
+            }
 
             let bg_style = match &row.kind {
                 TreeRowKind::Agent { session } => get_row_background_style(session, is_selected),
@@ -104,39 +116,155 @@ pub fn render_compact_session_list(frame: &mut Frame, area: Rect, app: &App) {
         })
         .collect();
 
-    let title = format!(" Sessions ({}) ", app.session_count());
-
-    let list = List::new(items).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title(title)
-            .border_style(Style::default().fg(Color::White)),
-    );
+    let list = List::new(items).block(list_block(app));
 
     frame.render_widget(list, area);
 }
 
+/// The bordered block around the session list, with the search bar on
+/// its bottom edge while a search or quick filter is active.
+fn list_block(app: &App) -> Block<'static> {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(list_title(app))
+        .border_style(Style::default().fg(Color::White));
+    match search_bar_line(app) {
+        Some(line) => block.title_bottom(line),
+        None => block,
+    }
+}
+
+/// The search bar: query (with a cursor while typing), quick filters and
+/// match count. `None` when nothing is being searched or filtered.
+fn search_bar_line(app: &App) -> Option<Line<'static>> {
+    if !app.search.editing && !app.has_filter() {
+        return None;
+    }
+    let accent = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let dim = Style::default().fg(Color::DarkGray);
+
+    let mut spans = vec![Span::raw(" ")];
+    if app.search.editing || app.search.is_active() {
+        spans.push(Span::styled("/", accent));
+        spans.push(Span::raw(app.search.query.clone()));
+        if app.search.editing {
+            spans.push(Span::styled("█", dim));
+        }
+        spans.push(Span::raw(" "));
+    }
+    if let Some(status) = app.status_filter {
+        spans.push(Span::styled(format!("[{}] ", status.label()), accent));
+    }
+    if let Some(harness) = &app.harness_filter {
+        spans.push(Span::styled(format!("[{harness}] "), accent));
+    }
+    if app.has_filter() {
+        let count = app.search_hit_count();
+        spans.push(Span::styled(
+            format!("{count} match{} ", if count == 1 { "" } else { "es" }),
+            dim,
+        ));
+    }
+    Some(Line::from(spans))
+}
+
+/// Splits `text` into spans, highlighting the chars matched by `query`.
+fn highlighted_spans(text: String, query: &str, style: Style) -> Vec<Span<'static>> {
+    let positions = highlight_positions(query, &text);
+    if positions.is_empty() {
+        return vec![Span::styled(text, style)];
+    }
+    let match_style = style.fg(Color::Yellow).add_modifier(Modifier::UNDERLINED);
+
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in text.chars().enumerate() {
+        let matched = positions.binary_search(&i).is_ok();
+        if matched != run_matched && !run.is_empty() {
+            let s = if run_matched { match_style } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), s));
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(
+            run,
+            if run_matched { match_style } else { style },
+        ));
+    }
+    spans
+}
+
+/// Title for the session list block, with the mark count when any and
+/// the grouping and sort order when not the defaults.
+fn list_title(app: &App) -> String {
+    let mut title = if app.marked.is_empty() {
+        format!(" Sessions ({}) ", app.session_count())
+    } else {
+        format!(
+            " Sessions ({}, {} marked) ",
+            app.session_count(),
+            app.marked.len()
+        )
+    };
+    let options = &app.tree_options;
+    if options.group_by != GroupBy::default() || options.sort_by != SortKey::default() {
+        title.push_str(&format!(
+            "[{}|{}] ",
+            options.group_by.label(),
+            options.sort_by.label()
+        ));
+    }
+    title
+}
+
+/// Replaces the selection indicator of a marked row with the mark glyph
+/// (`*`, or `>` in mark color when the row is also selected).
+fn mark_line(line: &mut Line<'static>, is_selected: bool) {
+    if let Some(indicator) = line.spans.first_mut() {
+        *indicator = Span::styled(
+            if is_selected { ">" } else { "*" },
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        );
+    }
+}
+
 /// Creates a list item for a single tree row.
 fn create_tree_row_item(
     row: &TreeRow,
     is_selected: bool,
+    is_marked: bool,
     blink_visible: bool,
+    query: &str,
 ) -> ListItem<'static> {
-    let indent = "  ".repeat(row.depth as usize);
+    let indent = row_indent(row.depth);
 
-    let line = match &row.kind {
-        TreeRowKind::Project { name, .. } => create_group_line(&indent, name, row, is_selected),
+    let mut line = match &row.kind {
+        TreeRowKind::Project { name, .. } => {
+            create_group_line(&indent, name, row, is_selected, query)
+        }
         TreeRowKind::Worktree { branch, path, .. } => {
             let label = branch
                 .as_deref()
                 .unwrap_or_else(|| path.rsplit('/').find(|s| !s.is_empty()).unwrap_or(path));
-            create_group_line(&indent, label, row, is_selected)
+            create_group_line(&indent, label, row, is_selected, query)
+        }
+        TreeRowKind::Team { name } | TreeRowKind::Group { label: name } => {
+            create_group_line(&indent, name, row, is_selected, query)
         }
-        TreeRowKind::Team { name } => create_group_line(&indent, name, row, is_selected),
         TreeRowKind::Agent { session } => {
-            create_agent_line(&indent, session, is_selected, blink_visible)
+            create_agent_line(&indent, session, row, is_selected, blink_visible, query)
         }
     };
+    if is_marked {
+        mark_line(&mut line, is_selected);
+    }
 
     let bg_style = match &row.kind {
         TreeRowKind::Agent { session } => get_row_background_style(session, is_selected),
@@ -147,8 +275,16 @@ fn create_tree_row_item(
     ListItem::new(line).style(bg_style)
 }
 
-/// Creates a line for a group header (Project, Worktree, Team).
-fn create_group_line(indent: &str, label: &str, row: &TreeRow, is_selected: bool) -> Line<'static> {
+/// Creates a line for a group header (Project, Worktree, Team, Group).
+///
+/// Characters of the label matched by the search `query` are highlighted.
+fn create_group_line(
+    indent: &str,
+    label: &str,
+    row: &TreeRow,
+    is_selected: bool,
+    query: &str,
+) -> Line<'static> {
     let collapse_icon = if !row.has_children {
         " "
     } else if row.is_expanded {
@@ -168,14 +304,15 @@ fn create_group_line(indent: &str, label: &str, row: &TreeRow, is_selected: bool
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw(format!("{indent}{collapse_icon} ")),
-        // Group name
-        Span::styled(
-            label.to_string(),
-            Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        ),
     ];
+    // Group name
+    spans.extend(highlighted_spans(
+        label.to_string(),
+        query,
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    ));
 
     // Show agent count when collapsed
     if !row.is_expanded && row.agent_count > 0 {
@@ -185,6 +322,17 @@ fn create_group_line(indent: &str, label: &str, row: &TreeRow, is_selected: bool
         ));
     }
 
+    // Teams and view groups show what the whole group has spent
+    if matches!(
+        row.kind,
+        TreeRowKind::Team { .. } | TreeRowKind::Group { .. }
+    ) {
+        spans.push(Span::styled(
+            format!(" ${:.2}", row.total_cost_usd),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
     // Attention marker
     if row.needs_attention {
         spans.push(Span::styled(
@@ -196,19 +344,61 @@ fn create_group_line(indent: &str, label: &str, row: &TreeRow, is_selected: bool
     Line::from(spans)
 }
 
+/// Deepest nesting level that still indents; deeper subagents share
+/// this indent behind a `…` marker so the row content stays visible.
+const MAX_INDENT_DEPTH: usize = 6;
+
+/// Indentation for a row at `depth`, capped at [`MAX_INDENT_DEPTH`].
+fn row_indent(depth: u8) -> String {
+    let depth = depth as usize;
+    if depth <= MAX_INDENT_DEPTH {
+        "  ".repeat(depth)
+    } else {
+        format!("{}… ", "  ".repeat(MAX_INDENT_DEPTH - 1))
+    }
+}
+
+/// What an agent's subagents add up to: a `(+N $cost)` summary while the
+/// agent is collapsed, and `!` when a descendant needs attention.
+fn subagent_rollup_spans(row: &TreeRow, session: &SessionView) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    if !row.has_children {
+        return spans;
+    }
+    if !row.is_expanded {
+        spans.push(Span::styled(
+            format!(
+                " (+{} ${:.2})",
+                row.agent_count.saturating_sub(1),
+                row.total_cost_usd
+            ),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if row.needs_attention && !session.needs_attention {
+        spans.push(Span::styled(" !", Style::default().fg(Color::Yellow)));
+    }
+    spans
+}
+
 /// Creates a line for an agent (session) row.
+///
+/// Characters of the short ID and model matched by the search `query`
+/// are highlighted.
 fn create_agent_line(
     indent: &str,
     session: &SessionView,
+    row: &TreeRow,
     is_selected: bool,
     blink_visible: bool,
+    query: &str,
 ) -> Line<'static> {
     let context_pct = session.context_percentage;
     let ctx_color = context_color(context_pct, session.context_critical);
     let icon = status_icon(session.status, blink_visible);
     let icon_color = status_color(session.status);
 
-    let spans = vec![
+    let mut spans = vec![
         // Selection indicator
         Span::styled(
             if is_selected { ">" } else { " " },
@@ -228,11 +418,14 @@ fn create_agent_line(
             Style::default().fg(ctx_color).add_modifier(Modifier::BOLD),
         ),
         Span::raw(" "),
-        // Short ID
-        Span::styled(
-            session.id_short.clone(),
-            Style::default().fg(Color::DarkGray),
-        ),
+    ];
+    // Short ID
+    spans.extend(highlighted_spans(
+        session.id_short.clone(),
+        query,
+        Style::default().fg(Color::DarkGray),
+    ));
+    spans.extend([
         Span::raw(" "),
         // Harness badge: `[pi]`, `[claude]`, `[?]`. Rendered in
         // a distinct color so vendor differences are scannable.
@@ -241,12 +434,14 @@ fn create_agent_line(
             Style::default().fg(harness_color(&session.harness)),
         ),
         Span::raw(" "),
-        // Model (truncated)
-        Span::styled(
-            truncate_string(&session.model, 8),
-            Style::default().fg(Color::White),
-        ),
-    ];
+    ]);
+    // Model (truncated)
+    spans.extend(highlighted_spans(
+        truncate_string(&session.model, 8),
+        query,
+        Style::default().fg(Color::White),
+    ));
+    spans.extend(subagent_rollup_spans(row, session));
 
     Line::from(spans)
 }
@@ -274,15 +469,17 @@ fn harness_color(harness: &str) -> Color {
 
 /// Creates a compact line for an agent row in narrow sidebars.
 ///
-/// Format: `{selector}{indent}{icon} {ctx%} {name}`
+/// Format: `{selector}{indent}{icon} {ctx%} {name}{subagent rollup}`
 /// Name is resolved from worktree branch (preferred) or short ID (fallback),
 /// then adaptively truncated to fit `available_width`.
 fn create_compact_agent_line(
     indent: &str,
     session: &SessionView,
+    row: &TreeRow,
     is_selected: bool,
     blink_visible: bool,
     available_width: u16,
+    query: &str,
 ) -> Line<'static> {
     let icon = status_icon(session.status, blink_visible);
     let icon_color = status_color(session.status);
@@ -293,11 +490,13 @@ fn create_compact_agent_line(
         .as_deref()
         .unwrap_or(&session.id_short);
 
-    let overhead = 9u16.saturating_add(indent.len() as u16);
-    let name_width = available_width.saturating_sub(overhead) as usize;
+    let rollup = subagent_rollup_spans(row, session);
+    let rollup_width: usize = rollup.iter().map(|s| s.width()).sum();
+    let overhead = 9usize + indent.chars().count() + rollup_width;
+    let name_width = (available_width as usize).saturating_sub(overhead);
     let truncated_name = truncate_string(name, name_width.max(1));
 
-    let spans = vec![
+    let mut spans = vec![
         Span::styled(
             if is_selected { ">" } else { " " },
             Style::default()
@@ -314,8 +513,13 @@ fn create_compact_agent_line(
             Style::default().fg(ctx_color).add_modifier(Modifier::BOLD),
         ),
         Span::raw(" "),
-        Span::styled(truncated_name, Style::default().fg(Color::White)),
     ];
+    spans.extend(highlighted_spans(
+        truncated_name,
+        query,
+        Style::default().fg(Color::White),
+    ));
+    spans.extend(rollup);
 
     Line::from(spans)
 }
@@ -493,6 +697,55 @@ mod tests {
         assert_eq!(status_icon(SessionStatus::Idle, false), "-");
     }
 
+    #[test]
+    fn test_highlighted_spans_split_on_matches() {
+        let style = Style::default();
+        let texts: Vec<String> = highlighted_spans("feature/search".into(), "arc", style)
+            .iter()
+            .map(|s| s.content.to_string())
+            .collect();
+        assert_eq!(texts, vec!["feature/se", "arc", "h"]);
+        assert_eq!(highlighted_spans("main".into(), "", style).len(), 1);
+    }
+
+    #[test]
+    fn test_row_indent_caps_deep_subagents() {
+        assert_eq!(row_indent(0), "");
+        assert_eq!(row_indent(2), "    ");
+        assert_eq!(row_indent(6).chars().count(), 12);
+        assert_eq!(row_indent(9), format!("{}… ", " ".repeat(10)));
+    }
+
+    #[test]
+    fn test_subagent_rollup_spans() {
+        let session = SessionView::default();
+        let mut row = TreeRow {
+            depth: 1,
+            node_id: atm_core::TreeNodeId::Agent(session.id.clone()),
+            kind: TreeRowKind::Agent {
+                session: session.clone(),
+            },
+            is_expanded: false,
+            agent_count: 3,
+            needs_attention: true,
+            total_cost_usd: 1.5,
+            has_children: true,
+        };
+        let text = |row: &TreeRow| -> String {
+            subagent_rollup_spans(row, &session)
+                .iter()
+                .map(|s| s.content.to_string())
+                .collect()
+        };
+        assert_eq!(text(&row), " (+2 $1.50) !");
+
+        row.is_expanded = true;
+        assert_eq!(text(&row), " !");
+
+        row.has_children = false;
+        assert_eq!(text(&row), "");
+    }
+
     #[test]
     fn test_truncate_string_short() {
         assert_eq!(truncate_string("hello", 10), "hello");
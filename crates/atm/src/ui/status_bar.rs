@@ -2,7 +2,8 @@
 //!
 //! The status bar provides:
 //! - Header: Application title and connection status indicator
-//! - Footer: Keybinding hints for user navigation
+//! - Footer: Keybinding hints for user navigation, or the app's status
+//!   message while one is set
 
 use crate::app::{App, AppState};
 use ratatui::{
@@ -95,14 +96,25 @@ pub fn render_header(frame: &mut Frame, area: Rect, app: &App) {
 /// Renders the footer bar with keybinding hints.
 ///
 /// The footer displays available keyboard shortcuts with
-/// highlighted key indicators. Shows different hints based on
-/// whether we're in tmux (for jump functionality).
+/// highlighted key indicators, using the keys from the effective
+/// keymap. Shows different hints based on whether we're in tmux (for
+/// jump functionality). A pending status message replaces the hints.
 ///
 /// # Arguments
 /// * `frame` - The frame to render into
 /// * `area` - The rectangular area for the footer
-/// * `app` - Application state (used for pick_mode indicator)
+/// * `app` - Application state (keymap and pick_mode indicator)
 pub fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
+    if let Some(message) = &app.status_message {
+        let line = Line::from(Span::styled(
+            format!(" {message}"),
+            Style::default().fg(Color::Yellow),
+        ));
+        let footer = Paragraph::new(line).block(Block::default().borders(Borders::ALL));
+        frame.render_widget(footer, area);
+        return;
+    }
+
     let key_style = Style::default()
         .fg(Color::Cyan)
         .add_modifier(Modifier::BOLD);
@@ -115,14 +127,15 @@ pub fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
     let mut hints = Vec::new();
     let mut last_category = None;
 
-    for entry in crate::keybinding::KEYBINDING_HINTS {
-        // Skip entries not shown in footer
-        if entry.footer_key.is_empty() {
+    for entry in crate::keybinding::FOOTER_HINTS {
+        // Skip tmux-only entries when not in tmux
+        if entry.tmux_only && !in_tmux {
             continue;
         }
 
-        // Skip tmux-only entries when not in tmux
-        if entry.tmux_only && !in_tmux {
+        // Skip entries whose actions the user unbound
+        let keys = entry.display_keys(&app.keymap);
+        if keys.is_empty() {
             continue;
         }
 
@@ -136,11 +149,11 @@ pub fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
 
         // Add leading space before first entry
         if hints.is_empty() {
-            hints.push(Span::styled(format!(" {}", entry.footer_key), key_style));
+            hints.push(Span::styled(format!(" {keys}"), key_style));
         } else {
-            hints.push(Span::styled(entry.footer_key, key_style));
+            hints.push(Span::styled(keys, key_style));
         }
-        hints.push(Span::raw(format!(" {}  ", entry.footer_desc)));
+        hints.push(Span::raw(format!(" {}  ", entry.desc)));
     }
 
     // Show pick mode indicator
@@ -161,6 +174,12 @@ pub fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
 
 /// Renders a minimal single-line footer for compact mode.
 pub fn render_compact_footer(frame: &mut Frame, area: Rect, app: &App) {
+    if let Some(message) = &app.status_message {
+        let paragraph = Paragraph::new(message.as_str()).style(Style::default().fg(Color::Yellow));
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
     let text = if app.pick_mode {
         "? help [pick]"
     } else {
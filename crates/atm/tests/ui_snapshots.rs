@@ -14,6 +14,7 @@ use std::sync::Mutex;
 
 use atm_core::{SessionId, SessionStatus, SessionView};
 use atm_tui::app::{App, AppState};
+use atm_tui::keymap::Keymap;
 use atm_tui::ui::{
     detail_panel::{render_compact_preview, render_detail_panel_inline},
     help_popup::render_help_popup,
@@ -431,7 +432,7 @@ fn help_popup_in_tmux() {
     // render in full without clipping.
     let buf = with_tmux(|| {
         render_buffer(120, 40, |frame, area| {
-            render_help_popup(frame, area);
+            render_help_popup(frame, area, &Keymap::default());
         })
     });
     insta::assert_debug_snapshot!(buf);
@@ -0,0 +1,573 @@
+//! Automatic answers to permission prompts.
+//!
+//! The engine subscribes to the registry's `PermissionRequested` events,
+//! evaluates them against the rules in `~/.config/atm/approvals.toml`,
+//! and answers allow/deny through tmux the same way `atm reply --yes/--no`
+//! does:
+//!
+//! ```toml
+//! dry_run = true                # audit what would happen, send nothing
+//!
+//! [[rules]]
+//! name = "never force-push"
+//! action = "deny"
+//! tool = "Bash"
+//! input.command = { regex = "git push.*(-f|--force)" }
+//!
+//! [[rules]]
+//! name = "read-only git"
+//! action = "allow"
+//! harness = "claude"
+//! project = "~/src/**"
+//! tool = "Bash"
+//! input.command = { regex = "git (status|diff|log)( .*)?" }
+//!
+//! [[rules]]
+//! name = "edits in src"
+//! action = "allow"
+//! tool = ["Edit", "Write"]
+//! input.file_path = { glob = "src/**" }   # relative to the project root
+//! ```
+//!
+//! A `regex` in a deny rule is searched anywhere in the value. In an allow
+//! rule it must match the whole value, and values containing shell control
+//! operators (`;`, `&`, `|`, backticks, `$(`, redirections, newlines) never
+//! match it, so `git status && rm -rf ~` is not read-only git.
+//!
+//! Rules are tried in order; the first match decides. Prompts no rule
+//! matches are left alone. Every prompt seen, decided or not, is appended
+//! to the audit log (`$XDG_STATE_HOME/atm/approvals.jsonl`).
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Reply and audit failures are logged and never stop the engine
+
+mod policy;
+
+pub use policy::{
+    default_policy_path, load_approval_policy, ApprovalConfigError, ApprovalPolicy,
+    ApprovalRequest, Decision, Verdict,
+};
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use atm_core::{SessionId, SessionView, Tool};
+use atm_protocol::ReplyChoice;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::control::ControlService;
+use crate::registry::{RegistryHandle, SessionEvent};
+
+/// Audit log file name under the ATM state directory.
+const AUDIT_FILE_NAME: &str = "approvals.jsonl";
+
+/// Pause before answering, so the prompt has rendered in the pane.
+const REPLY_DELAY: Duration = Duration::from_millis(300);
+
+/// Longest string kept from tool input in the audit log.
+const MAX_AUDIT_STRING: usize = 2000;
+
+/// Audit entries queued for the writer before the engine waits on it.
+const AUDIT_BUFFER: usize = 256;
+
+/// Returns the default audit log path (`dirs::state_dir()/atm/approvals.jsonl`).
+pub fn default_audit_path() -> PathBuf {
+    dirs::state_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("atm")
+        .join(AUDIT_FILE_NAME)
+}
+
+// ============================================================================
+// Audit Log
+// ============================================================================
+
+/// What became of a permission prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// No rule matched; left for a human
+    NoRule,
+    /// The answer was sent to the pane
+    Replied,
+    /// Dry run: the answer was not sent
+    DryRun,
+    /// The session stopped waiting on this prompt before the answer was
+    /// sent
+    Skipped,
+    /// Sending the answer failed
+    Failed,
+}
+
+/// One line of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the prompt was evaluated
+    pub ts: DateTime<Utc>,
+    /// Session that asked
+    pub session_id: SessionId,
+    /// Harness short tag
+    pub harness: String,
+    /// Git project root of the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_root: Option<String>,
+    /// Tool being gated
+    pub tool: Tool,
+    /// Harness id of the gated call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_use_id: Option<String>,
+    /// Tool arguments, long strings truncated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<serde_json::Value>,
+    /// Rule that matched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// What the rule answered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision: Option<Decision>,
+    /// Whether the engine was in dry-run mode
+    pub dry_run: bool,
+    /// What happened
+    pub outcome: AuditOutcome,
+    /// Why sending the answer failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Appends `entry` as one JSON line to the audit log at `path`.
+pub fn append_audit(path: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Appends entries from `entries` to `path` until every sender is gone.
+fn run_audit_writer(path: PathBuf, mut entries: mpsc::Receiver<AuditEntry>) {
+    while let Some(entry) = entries.blocking_recv() {
+        if let Err(e) = append_audit(&path, &entry) {
+            warn!(path = %path.display(), error = %e, "Failed to write approval audit log");
+        }
+    }
+}
+
+/// Copies `value` with every string cut to `max_chars` characters.
+pub(crate) fn truncate_strings(value: &serde_json::Value, max_chars: usize) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::String(s) if s.chars().count() > max_chars => {
+            let mut cut: String = s.chars().take(max_chars).collect();
+            cut.push('…');
+            Value::String(cut)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| truncate_strings(v, max_chars))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), truncate_strings(v, max_chars)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+// ============================================================================
+// Engine
+// ============================================================================
+
+/// Spawns the task that answers permission prompts according to `policy`.
+///
+/// Audit entries are written on the blocking pool, so a slow disk never
+/// holds up the next prompt.
+pub fn spawn_approval_engine(
+    registry: RegistryHandle,
+    policy: ApprovalPolicy,
+    control: ControlService,
+    audit_path: PathBuf,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let mut events = registry.subscribe();
+    let (audit_tx, audit_rx) = mpsc::channel(AUDIT_BUFFER);
+    tokio::task::spawn_blocking(move || run_audit_writer(audit_path, audit_rx));
+    tokio::spawn(async move {
+        info!(
+            rules = policy.len(),
+            dry_run = policy.dry_run,
+            "Approval engine started"
+        );
+        loop {
+            let event = tokio::select! {
+                biased;
+
+                _ = cancel_token.cancelled() => {
+                    debug!("Approval engine shutting down");
+                    break;
+                }
+
+                result = events.recv() => match result {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(skipped = n, "Approval engine lagged, skipped events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+
+            if let SessionEvent::PermissionRequested {
+                session,
+                request_id,
+                tool,
+                tool_use_id,
+                input,
+            } = event
+            {
+                let prompt = PermissionPrompt {
+                    session: &session,
+                    request_id,
+                    tool,
+                    tool_use_id,
+                    input: input.as_ref(),
+                };
+                let entry = handle_request(&registry, &policy, &control, prompt).await;
+                if audit_tx.send(entry).await.is_err() {
+                    warn!("Approval audit writer stopped");
+                }
+            }
+        }
+    })
+}
+
+/// A `PermissionRequested` event, as the engine handles it.
+struct PermissionPrompt<'a> {
+    session: &'a SessionView,
+    request_id: u64,
+    tool: Tool,
+    tool_use_id: Option<String>,
+    input: Option<&'a serde_json::Value>,
+}
+
+/// Evaluates one prompt, answers it if a rule says so, and describes what
+/// happened for the audit log.
+async fn handle_request(
+    registry: &RegistryHandle,
+    policy: &ApprovalPolicy,
+    control: &ControlService,
+    prompt: PermissionPrompt<'_>,
+) -> AuditEntry {
+    let PermissionPrompt {
+        session,
+        request_id,
+        tool,
+        tool_use_id,
+        input,
+    } = prompt;
+    let verdict = policy.evaluate(&ApprovalRequest {
+        harness: &session.harness,
+        project_root: session.project_root.as_deref(),
+        tool: &tool,
+        input,
+    });
+
+    let mut entry = AuditEntry {
+        ts: Utc::now(),
+        session_id: session.id.clone(),
+        harness: session.harness.clone(),
+        project_root: session.project_root.clone(),
+        tool,
+        tool_use_id,
+        input: input.map(|v| truncate_strings(v, MAX_AUDIT_STRING)),
+        rule: verdict.as_ref().map(|v| v.rule.clone()),
+        decision: verdict.as_ref().map(|v| v.decision),
+        dry_run: policy.dry_run,
+        outcome: AuditOutcome::NoRule,
+        error: None,
+    };
+    let Some(verdict) = verdict else {
+        return entry;
+    };
+
+    if policy.dry_run {
+        info!(
+            session_id = %session.id,
+            tool = %entry.tool.as_str(),
+            rule = %verdict.rule,
+            decision = %verdict.decision,
+            "Dry run: would have answered permission prompt"
+        );
+        entry.outcome = AuditOutcome::DryRun;
+        return entry;
+    }
+
+    // The user may have answered while the prompt was rendering, and a
+    // different prompt may be on screen by now.
+    tokio::time::sleep(REPLY_DELAY).await;
+    let still_waiting = registry.open_permission(session.id.clone()).await == Some(request_id);
+    if !still_waiting {
+        entry.outcome = AuditOutcome::Skipped;
+        return entry;
+    }
+
+    let choice = match verdict.decision {
+        Decision::Allow => ReplyChoice::Accept,
+        Decision::Deny => ReplyChoice::Reject,
+    };
+    match control.reply(session.id.as_str(), choice).await {
+        Ok(_) => {
+            info!(
+                session_id = %session.id,
+                tool = %entry.tool.as_str(),
+                rule = %verdict.rule,
+                decision = %verdict.decision,
+                "Answered permission prompt"
+            );
+            entry.outcome = AuditOutcome::Replied;
+        }
+        Err(e) => {
+            warn!(session_id = %session.id, error = %e, "Failed to answer permission prompt");
+            entry.outcome = AuditOutcome::Failed;
+            entry.error = Some(e.to_string());
+        }
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::spawn_registry;
+    use atm_core::{
+        AgentType, Harness, LifecycleEvent, Model, NeedsInputReason, NotificationKind,
+        SessionDomain,
+    };
+    use atm_tmux::mock::MockCall;
+    use atm_tmux::MockTmuxClient;
+    use std::sync::Arc;
+
+    const RULES: &str = r#"
+[[rules]]
+name = "git"
+action = "allow"
+tool = "Bash"
+input.command = { regex = "git .*" }
+"#;
+
+    fn load(rules: &str, dir: &Path) -> ApprovalPolicy {
+        let path = dir.join("approvals.toml");
+        std::fs::write(&path, rules).unwrap();
+        load_approval_policy(&path).unwrap()
+    }
+
+    fn read_audit(path: &Path) -> Vec<AuditEntry> {
+        std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    /// Registers a session in pane %4 and walks it into a Bash permission
+    /// prompt for `command`.
+    async fn prompt(registry: &RegistryHandle, id: &str, command: &str) {
+        let mut domain = SessionDomain::new(
+            SessionId::new(id),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-sonnet-4"),
+        );
+        domain.tmux_pane = Some("%4".to_string());
+        registry.register(domain).await.unwrap();
+
+        for event in [
+            LifecycleEvent::ToolCallStart {
+                name: Tool::Bash,
+                tool_use_id: None,
+                input: Some(serde_json::json!({ "command": command })),
+            },
+            LifecycleEvent::NeedsInput {
+                reason: NeedsInputReason::Notification {
+                    kind: NotificationKind::PermissionPrompt,
+                    label: None,
+                },
+                prompt: None,
+            },
+        ] {
+            registry
+                .apply_lifecycle_event(SessionId::new(id), event, Harness::ClaudeCode, None, None)
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn wait_for_audit(path: &Path, count: usize) -> Vec<AuditEntry> {
+        for _ in 0..100 {
+            let entries = read_audit(path);
+            if entries.len() >= count {
+                return entries;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        read_audit(path)
+    }
+
+    #[tokio::test]
+    async fn test_engine_replies_and_audits() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = dir.path().join("approvals.jsonl");
+        let registry = spawn_registry();
+        let mock = MockTmuxClient::new();
+        let control = ControlService::new(registry.clone(), Arc::new(mock.clone()));
+        let cancel = CancellationToken::new();
+        let _handle = spawn_approval_engine(
+            registry.clone(),
+            load(RULES, dir.path()),
+            control,
+            audit.clone(),
+            cancel.clone(),
+        );
+
+        prompt(&registry, "allow-me", "git status").await;
+        let entries = wait_for_audit(&audit, 1).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].rule.as_deref(), Some("git"));
+        assert_eq!(entries[0].decision, Some(Decision::Allow));
+        assert_eq!(entries[0].outcome, AuditOutcome::Replied);
+        assert_eq!(
+            entries[0].input,
+            Some(serde_json::json!({"command": "git status"}))
+        );
+        assert!(mock.calls().iter().any(|c| matches!(
+            c,
+            MockCall::SendKeys { pane, keys } if pane == "%4" && keys == "Enter"
+        )));
+
+        cancel.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_engine_skips_prompt_replaced_during_delay() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = dir.path().join("approvals.jsonl");
+        let registry = spawn_registry();
+        let mock = MockTmuxClient::new();
+        let control = ControlService::new(registry.clone(), Arc::new(mock.clone()));
+        let cancel = CancellationToken::new();
+        let _handle = spawn_approval_engine(
+            registry.clone(),
+            load(RULES, dir.path()),
+            control,
+            audit.clone(),
+            cancel.clone(),
+        );
+
+        // The git prompt is answered by hand and a different one opens
+        // before the engine's reply goes out.
+        prompt(&registry, "replaced", "git status").await;
+        for event in [
+            LifecycleEvent::ToolCallEnd {
+                name: Tool::Bash,
+                tool_use_id: None,
+                is_error: false,
+            },
+            LifecycleEvent::ToolCallStart {
+                name: Tool::Bash,
+                tool_use_id: None,
+                input: Some(serde_json::json!({ "command": "rm -rf target" })),
+            },
+            LifecycleEvent::NeedsInput {
+                reason: NeedsInputReason::Notification {
+                    kind: NotificationKind::PermissionPrompt,
+                    label: None,
+                },
+                prompt: None,
+            },
+        ] {
+            registry
+                .apply_lifecycle_event(
+                    SessionId::new("replaced"),
+                    event,
+                    Harness::ClaudeCode,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        let entries = wait_for_audit(&audit, 2).await;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].decision, Some(Decision::Allow));
+        assert_eq!(entries[0].outcome, AuditOutcome::Skipped);
+        assert_eq!(entries[1].outcome, AuditOutcome::NoRule);
+        assert!(!mock
+            .calls()
+            .iter()
+            .any(|c| matches!(c, MockCall::SendKeys { .. })));
+
+        cancel.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_engine_dry_run_sends_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = dir.path().join("approvals.jsonl");
+        let registry = spawn_registry();
+        let mock = MockTmuxClient::new();
+        let control = ControlService::new(registry.clone(), Arc::new(mock.clone()));
+        let cancel = CancellationToken::new();
+        let _handle = spawn_approval_engine(
+            registry.clone(),
+            load(&format!("dry_run = true\n{RULES}"), dir.path()),
+            control,
+            audit.clone(),
+            cancel.clone(),
+        );
+
+        prompt(&registry, "dry-one", "git log").await;
+        prompt(&registry, "dry-two", "rm -rf target").await;
+        let entries = wait_for_audit(&audit, 2).await;
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.dry_run));
+        assert_eq!(entries[0].outcome, AuditOutcome::DryRun);
+        assert_eq!(entries[0].decision, Some(Decision::Allow));
+        assert_eq!(entries[1].outcome, AuditOutcome::NoRule);
+        assert_eq!(entries[1].rule, None);
+        assert!(!mock
+            .calls()
+            .iter()
+            .any(|c| matches!(c, MockCall::SendKeys { .. })));
+
+        cancel.cancel();
+    }
+
+    #[test]
+    fn test_truncate_strings() {
+        let long = "x".repeat(MAX_AUDIT_STRING + 5);
+        let value = serde_json::json!({ "content": long, "n": 3, "list": ["ok"] });
+        let cut = truncate_strings(&value, MAX_AUDIT_STRING);
+        assert_eq!(
+            cut["content"].as_str().unwrap().chars().count(),
+            MAX_AUDIT_STRING + 1
+        );
+        assert_eq!(cut["n"], 3);
+        assert_eq!(cut["list"][0], "ok");
+    }
+}
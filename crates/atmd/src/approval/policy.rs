@@ -0,0 +1,589 @@
+//! The approval rules file and rule matching.
+
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use atm_core::Tool;
+use regex_automata::meta::{BuildError, Regex};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Rules file name under the ATM config directory.
+const POLICY_FILE_NAME: &str = "approvals.toml";
+
+/// Returns the default rules file path (`~/.config/atm/approvals.toml`).
+pub fn default_policy_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("atm").join(POLICY_FILE_NAME))
+}
+
+/// What a matching rule answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    /// Accept the prompt
+    Allow,
+    /// Reject the prompt
+    Deny,
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+/// A permission prompt, as seen by the rules.
+#[derive(Debug, Clone, Copy)]
+pub struct ApprovalRequest<'a> {
+    /// Harness short tag (`"claude"`, `"pi"`, ...)
+    pub harness: &'a str,
+    /// Git project root of the session
+    pub project_root: Option<&'a str>,
+    /// Tool being gated
+    pub tool: &'a Tool,
+    /// Tool arguments, when the harness reported them
+    pub input: Option<&'a serde_json::Value>,
+}
+
+/// The rule that decided a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    /// Rule name (or `rule #N` for unnamed rules)
+    pub rule: String,
+    /// What the rule answers
+    pub decision: Decision,
+}
+
+// ============================================================================
+// Policy
+// ============================================================================
+
+/// Compiled approval rules.
+///
+/// Rules are tried in file order and the first match wins. A request no
+/// rule matches is left for a human to answer.
+#[derive(Debug, Clone, Default)]
+pub struct ApprovalPolicy {
+    /// Evaluate and audit, but never send keys
+    pub dry_run: bool,
+    rules: Vec<Rule>,
+}
+
+impl ApprovalPolicy {
+    /// Returns true if there is at least one rule.
+    pub fn is_enabled(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    /// Number of rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns true if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Finds the first rule matching `request`.
+    pub fn evaluate(&self, request: &ApprovalRequest<'_>) -> Option<Verdict> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(request))
+            .map(|rule| Verdict {
+                rule: rule.name.clone(),
+                decision: rule.decision,
+            })
+    }
+
+    fn compile(file: PolicyFile) -> Result<Self, ApprovalConfigError> {
+        let rules = file
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, spec)| Rule::compile(index, spec))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            dry_run: file.dry_run,
+            rules,
+        })
+    }
+}
+
+/// One compiled `[[rules]]` entry.
+#[derive(Debug, Clone)]
+struct Rule {
+    name: String,
+    decision: Decision,
+    harness: Option<String>,
+    project: Option<Regex>,
+    tools: Vec<Tool>,
+    input: Vec<(String, FieldMatcher)>,
+}
+
+impl Rule {
+    fn compile(index: usize, spec: RuleSpec) -> Result<Self, ApprovalConfigError> {
+        let name = spec.name.unwrap_or_else(|| format!("rule #{}", index + 1));
+        let project = spec
+            .project
+            .map(|glob| build_regex(&name, &glob_regex(&expand_home(&glob))))
+            .transpose()?;
+        let input = spec
+            .input
+            .into_iter()
+            .map(|(field, matcher)| {
+                let matcher = FieldMatcher::compile(&name, &field, matcher, spec.action)?;
+                Ok((field, matcher))
+            })
+            .collect::<Result<_, ApprovalConfigError>>()?;
+
+        Ok(Self {
+            decision: spec.action,
+            harness: spec.harness,
+            project,
+            tools: spec.tool.into_vec().into_iter().map(Tool::from).collect(),
+            input,
+            name,
+        })
+    }
+
+    fn matches(&self, request: &ApprovalRequest<'_>) -> bool {
+        if self
+            .harness
+            .as_deref()
+            .is_some_and(|h| !h.eq_ignore_ascii_case(request.harness))
+        {
+            return false;
+        }
+        if let Some(project) = &self.project {
+            if !request.project_root.is_some_and(|p| project.is_match(p)) {
+                return false;
+            }
+        }
+        if !self.tools.is_empty() && !self.tools.contains(request.tool) {
+            return false;
+        }
+        self.input.iter().all(|(field, matcher)| {
+            input_field(request.input, field)
+                .is_some_and(|value| matcher.matches(&value, request.project_root))
+        })
+    }
+}
+
+/// Characters and sequences that chain, substitute or redirect shell
+/// commands. A value containing one never satisfies an allow rule's regex.
+const SHELL_CONTROL: &[&str] = &[";", "&", "|", "`", "$(", ">", "<", "\n", "\r"];
+
+/// A test on one tool input field.
+#[derive(Debug, Clone)]
+enum FieldMatcher {
+    /// Regex searched anywhere in the value (deny rules)
+    Regex(Regex),
+    /// Regex that must match the whole value, which must not contain
+    /// shell control operators (allow rules)
+    WholeRegex(Regex),
+    /// Path glob matched against the whole value
+    Glob(Regex),
+}
+
+impl FieldMatcher {
+    /// Compiles a matcher. Allow rules get whole-value regexes, so an
+    /// approved command can't smuggle another one after it.
+    fn compile(
+        rule: &str,
+        field: &str,
+        spec: MatcherSpec,
+        decision: Decision,
+    ) -> Result<Self, ApprovalConfigError> {
+        match (spec.regex, spec.glob) {
+            (Some(pattern), None) if decision == Decision::Allow => {
+                // Check it alone first: `a)|(b` only compiles once wrapped
+                build_regex(rule, &pattern)?;
+                Ok(Self::WholeRegex(build_regex(
+                    rule,
+                    &format!("^(?:{pattern})$"),
+                )?))
+            }
+            (Some(pattern), None) => Ok(Self::Regex(build_regex(rule, &pattern)?)),
+            (None, Some(glob)) => Ok(Self::Glob(build_regex(
+                rule,
+                &glob_regex(&expand_home(&glob)),
+            )?)),
+            _ => Err(ApprovalConfigError::InvalidMatcher {
+                rule: rule.to_string(),
+                field: field.to_string(),
+            }),
+        }
+    }
+
+    /// Tests `value`. Relative globs match absolute paths inside
+    /// `project_root` by their project-relative part. Paths with a `..`
+    /// component never match a glob, so `src/../..` can't pass as `src/**`.
+    fn matches(&self, value: &str, project_root: Option<&str>) -> bool {
+        match self {
+            Self::Regex(re) => re.is_match(value),
+            Self::WholeRegex(re) => {
+                !SHELL_CONTROL.iter().any(|op| value.contains(op)) && re.is_match(value)
+            }
+            Self::Glob(_) if value.split('/').any(|part| part == "..") => false,
+            Self::Glob(re) => {
+                let relative = project_root
+                    .map(|root| root.trim_end_matches('/'))
+                    .and_then(|root| value.strip_prefix(root))
+                    .and_then(|rest| rest.strip_prefix('/'));
+                re.is_match(value) || relative.is_some_and(|rest| re.is_match(rest))
+            }
+        }
+    }
+}
+
+/// Top-level string form of `input[field]`; other scalars use their JSON
+/// text. Missing or null fields don't match anything.
+fn input_field(input: Option<&serde_json::Value>, field: &str) -> Option<String> {
+    match input?.get(field)? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn build_regex(rule: &str, pattern: &str) -> Result<Regex, ApprovalConfigError> {
+    Regex::new(pattern).map_err(|source| ApprovalConfigError::InvalidRegex {
+        rule: rule.to_string(),
+        pattern: pattern.to_string(),
+        source: Box::new(source),
+    })
+}
+
+/// Translates a path glob into an anchored regex.
+///
+/// `*` and `?` stay within one path segment, `**` crosses segments and
+/// `**/` also matches no directory at all.
+fn glob_regex(glob: &str) -> String {
+    let mut out = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    out.push_str("(?:.*/)?");
+                } else {
+                    out.push_str(".*");
+                }
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            c if is_regex_meta(c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('$');
+    out
+}
+
+fn is_regex_meta(c: char) -> bool {
+    matches!(
+        c,
+        '\\' | '.'
+            | '+'
+            | '*'
+            | '?'
+            | '('
+            | ')'
+            | '|'
+            | '['
+            | ']'
+            | '{'
+            | '}'
+            | '^'
+            | '$'
+            | '#'
+            | '&'
+            | '-'
+            | '~'
+    )
+}
+
+fn expand_home(glob: &str) -> String {
+    match (glob.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => format!("{}/{rest}", home.display()),
+        _ => glob.to_string(),
+    }
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+/// Errors loading the approval rules.
+#[derive(Debug, Error)]
+pub enum ApprovalConfigError {
+    /// The rules file exists but could not be read.
+    #[error("failed to read approval rules {path}: {source}")]
+    Io {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The rules file is not valid TOML or a rule is malformed.
+    #[error("failed to parse approval rules {path}: {source}")]
+    Parse {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying parse error
+        source: toml::de::Error,
+    },
+
+    /// A regex (or a glob's translation) does not compile.
+    #[error("{rule}: invalid pattern '{pattern}': {source}")]
+    InvalidRegex {
+        /// Rule the pattern belongs to
+        rule: String,
+        /// The offending pattern
+        pattern: String,
+        /// Underlying build error
+        source: Box<BuildError>,
+    },
+
+    /// An input matcher sets neither or both of `regex` and `glob`.
+    #[error("{rule}: input.{field} needs exactly one of `regex` or `glob`")]
+    InvalidMatcher {
+        /// Rule the matcher belongs to
+        rule: String,
+        /// Input field being matched
+        field: String,
+    },
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PolicyFile {
+    dry_run: bool,
+    rules: Vec<RuleSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    #[serde(default)]
+    name: Option<String>,
+    action: Decision,
+    #[serde(default)]
+    harness: Option<String>,
+    #[serde(default)]
+    project: Option<String>,
+    #[serde(default)]
+    tool: OneOrMany,
+    #[serde(default)]
+    input: std::collections::BTreeMap<String, MatcherSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MatcherSpec {
+    #[serde(default)]
+    regex: Option<String>,
+    #[serde(default)]
+    glob: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    #[default]
+    None,
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::None => Vec::new(),
+            Self::One(s) => vec![s],
+            Self::Many(v) => v,
+        }
+    }
+}
+
+/// Loads and compiles the rules file at `path`.
+///
+/// A missing file yields a policy with no rules.
+pub fn load_approval_policy(path: &Path) -> Result<ApprovalPolicy, ApprovalConfigError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(ApprovalPolicy::default()),
+        Err(source) => {
+            return Err(ApprovalConfigError::Io {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+    };
+
+    let file: PolicyFile =
+        toml::from_str(&contents).map_err(|source| ApprovalConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    ApprovalPolicy::compile(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(toml: &str) -> Result<ApprovalPolicy, ApprovalConfigError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(POLICY_FILE_NAME);
+        std::fs::write(&path, toml).unwrap();
+        load_approval_policy(&path)
+    }
+
+    fn request<'a>(tool: &'a Tool, input: &'a serde_json::Value) -> ApprovalRequest<'a> {
+        ApprovalRequest {
+            harness: "claude",
+            project_root: Some("/src/app"),
+            tool,
+            input: Some(input),
+        }
+    }
+
+    #[test]
+    fn test_glob_regex() {
+        let matches =
+            |glob: &str, path: &str| Regex::new(&glob_regex(glob)).unwrap().is_match(path);
+        assert!(matches("src/*.rs", "src/main.rs"));
+        assert!(!matches("src/*.rs", "src/bin/atm.rs"));
+        assert!(matches("src/**/*.rs", "src/main.rs"));
+        assert!(matches("src/**/*.rs", "src/bin/atm.rs"));
+        assert!(matches("docs/**", "docs/a/b.md"));
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(!matches("file?.txt", "file10.txt"));
+        assert!(matches("a+b(1).md", "a+b(1).md"));
+        assert!(!matches("*.md", "xmd"));
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let policy = policy(
+            r#"
+[[rules]]
+name = "no force push"
+action = "deny"
+tool = "Bash"
+input.command = { regex = "git push.*--force" }
+
+[[rules]]
+name = "git"
+action = "allow"
+harness = "claude"
+project = "/src/*"
+tool = "Bash"
+input.command = { regex = "git (status|diff|log)( .*)?" }
+
+[[rules]]
+action = "allow"
+tool = ["Edit", "Write"]
+input.file_path = { glob = "src/**" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(policy.len(), 3);
+        assert!(!policy.dry_run);
+
+        let bash = Tool::Bash;
+        let verdict =
+            |tool: &Tool, input: serde_json::Value| policy.evaluate(&request(tool, &input));
+
+        let push = verdict(&bash, serde_json::json!({"command": "git push --force"})).unwrap();
+        assert_eq!(push.rule, "no force push");
+        assert_eq!(push.decision, Decision::Deny);
+
+        let status = verdict(&bash, serde_json::json!({"command": "git status"})).unwrap();
+        assert_eq!(status.rule, "git");
+        assert_eq!(status.decision, Decision::Allow);
+
+        assert!(verdict(&bash, serde_json::json!({"command": "rm -rf /"})).is_none());
+        // Allow rules match the whole command, and never a chained one
+        assert!(verdict(
+            &bash,
+            serde_json::json!({"command": "git status && rm -rf ~"})
+        )
+        .is_none());
+        for chained in [
+            "git log; rm -rf ~",
+            "git diff | sh",
+            "git log `rm -rf ~`",
+            "git log $(rm -rf ~)",
+            "git log > ~/.bashrc",
+            "git status\nrm -rf ~",
+        ] {
+            assert!(
+                verdict(&bash, serde_json::json!({ "command": chained })).is_none(),
+                "{chained}"
+            );
+        }
+        assert!(verdict(&bash, serde_json::json!({"command": "echo; git status"})).is_none());
+        assert!(verdict(&bash, serde_json::json!({})).is_none());
+
+        let edit = verdict(
+            &Tool::Edit,
+            serde_json::json!({"file_path": "/src/app/src/lib.rs"}),
+        )
+        .unwrap();
+        assert_eq!(edit.rule, "rule #3");
+        assert!(verdict(
+            &Tool::Write,
+            serde_json::json!({"file_path": "/etc/passwd"})
+        )
+        .is_none());
+        assert!(verdict(
+            &Tool::Write,
+            serde_json::json!({"file_path": "src/../../etc/passwd"})
+        )
+        .is_none());
+
+        let input = serde_json::json!({"command": "git status"});
+        let other_harness = ApprovalRequest {
+            harness: "pi",
+            ..request(&bash, &input)
+        };
+        assert!(policy.evaluate(&other_harness).is_none());
+    }
+
+    #[test]
+    fn test_load_rejects_bad_rules() {
+        assert!(matches!(
+            policy("[[rules]]\naction = \"allow\"\ninput.command = { regex = \"(\" }\n"),
+            Err(ApprovalConfigError::InvalidRegex { .. })
+        ));
+        assert!(matches!(
+            policy("[[rules]]\naction = \"allow\"\ninput.command = {}\n"),
+            Err(ApprovalConfigError::InvalidMatcher { .. })
+        ));
+        assert!(matches!(
+            policy("[[rules]]\naction = \"maybe\"\n"),
+            Err(ApprovalConfigError::Parse { .. })
+        ));
+        assert!(matches!(
+            policy("[[rules]]\naction = \"allow\"\ntools = \"Bash\"\n"),
+            Err(ApprovalConfigError::Parse { .. })
+        ));
+
+        let dir = tempfile::tempdir().unwrap();
+        let empty = load_approval_policy(&dir.path().join("nope.toml")).unwrap();
+        assert!(!empty.is_enabled());
+    }
+}
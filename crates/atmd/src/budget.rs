@@ -0,0 +1,641 @@
+//! Cost budgets and their enforcement.
+//!
+//! Budgets are read from the `[budgets]` table of
+//! `~/.config/atm/config.toml`; every limit is optional and in USD:
+//!
+//! ```toml
+//! [budgets]
+//! session_usd = 20.0    # any single session
+//! project_usd = 50.0    # everything one git project spent today
+//! daily_usd = 100.0     # everything spent today (local time)
+//! interrupt = true      # also Ctrl+C the agent that crossed a limit
+//!
+//! [budgets.projects]    # per-project limits, overriding project_usd
+//! "/home/me/src/monorepo" = 150.0
+//! ```
+//!
+//! The registry actor feeds every status-line cost update through a
+//! [`BudgetTracker`]. When a session crosses a limit it is flagged
+//! `over_budget` (which makes it need attention) and a
+//! `SessionEvent::BudgetExceeded` is published, once per session and
+//! limit. Project and daily breaches, and the flag they set, lapse at
+//! the next local day. [`spawn_budget_enforcer`] turns those events into interrupts.
+//!
+//! Project and daily spend are summed per local day from cost deltas, so
+//! sessions that ended (or were replaced) earlier in the day still count.
+//! At startup the day's totals are seeded from the cost ledger
+//! (see [`crate::ledger`]), which also tells which part of a restored
+//! session's cost was already booked.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Config read and parse failures are returned as `BudgetConfigError`
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use atm_core::{Money, SessionDomain, SessionId};
+use chrono::{Local, NaiveDate};
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::control::ControlService;
+use crate::ledger::CostLedger;
+use crate::registry::{RegistryHandle, SessionEvent};
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// The `[budgets]` config table.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// Limit for any single session
+    pub session_usd: Option<f64>,
+    /// Limit for one project's spend per day
+    pub project_usd: Option<f64>,
+    /// Limit for everything spent today
+    pub daily_usd: Option<f64>,
+    /// Per-project limits keyed by git project root; override `project_usd`
+    pub projects: HashMap<String, f64>,
+    /// Whether to interrupt an agent when it crosses a limit
+    pub interrupt: bool,
+}
+
+impl BudgetConfig {
+    /// Returns true if any limit is set.
+    pub fn is_enabled(&self) -> bool {
+        self.session_usd.is_some()
+            || self.project_usd.is_some()
+            || self.daily_usd.is_some()
+            || !self.projects.is_empty()
+    }
+
+    /// Checks that every limit is a positive amount.
+    pub fn validate(&self) -> Result<(), BudgetConfigError> {
+        let limits = [
+            ("session_usd", self.session_usd),
+            ("project_usd", self.project_usd),
+            ("daily_usd", self.daily_usd),
+        ];
+        let project_limits = self
+            .projects
+            .iter()
+            .map(|(root, limit)| (root.as_str(), Some(*limit)));
+        for (name, limit) in limits.into_iter().chain(project_limits) {
+            if let Some(value) = limit {
+                if !(value.is_finite() && value > 0.0) {
+                    return Err(BudgetConfigError::InvalidLimit {
+                        name: name.to_string(),
+                        value,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The limit that applies to `project_root`, if any.
+    fn project_limit(&self, project_root: &str) -> Option<f64> {
+        self.projects
+            .get(project_root)
+            .copied()
+            .or(self.project_usd)
+    }
+}
+
+/// Errors loading the budget config.
+#[derive(Debug, Error)]
+pub enum BudgetConfigError {
+    /// The config file exists but could not be read.
+    #[error("failed to read config {path}: {source}")]
+    Io {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The config file is not valid TOML or `[budgets]` is malformed.
+    #[error("failed to parse config {path}: {source}")]
+    Parse {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying parse error
+        source: toml::de::Error,
+    },
+
+    /// A limit is zero, negative or not a number.
+    #[error("budget {name} must be a positive amount, got {value}")]
+    InvalidLimit {
+        /// Config key (or project root) of the limit
+        name: String,
+        /// The rejected value
+        value: f64,
+    },
+}
+
+/// Only the part of the config file budgets care about.
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    budgets: BudgetConfig,
+}
+
+/// Loads and validates the `[budgets]` table from `path`.
+///
+/// A missing file or table yields a config with no limits.
+pub fn load_budget_config(path: &Path) -> Result<BudgetConfig, BudgetConfigError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(BudgetConfig::default()),
+        Err(source) => {
+            return Err(BudgetConfigError::Io {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+    };
+
+    let file: ConfigFile =
+        toml::from_str(&contents).map_err(|source| BudgetConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    file.budgets.validate()?;
+
+    Ok(file.budgets)
+}
+
+// ============================================================================
+// Evaluation
+// ============================================================================
+
+/// Which limit was crossed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BudgetScope {
+    /// The session's own cost
+    Session,
+    /// Everything the session's project spent today
+    Project,
+    /// Everything spent today
+    Daily,
+}
+
+impl fmt::Display for BudgetScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Session => "session",
+            Self::Project => "project",
+            Self::Daily => "daily",
+        })
+    }
+}
+
+/// A limit a session has just crossed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetBreach {
+    /// Which limit
+    pub scope: BudgetScope,
+    /// The configured limit
+    pub limit: Money,
+    /// Spend counted against it
+    pub spent: Money,
+}
+
+/// Tracks spend against the configured budgets.
+///
+/// Owned by the registry actor; every method is synchronous.
+#[derive(Debug)]
+pub struct BudgetTracker {
+    config: BudgetConfig,
+    /// Local day `day_spend` covers
+    day: NaiveDate,
+    /// Spend observed so far on `day`
+    day_spend: Money,
+    /// Spend observed so far on `day`, per project root
+    project_spend: HashMap<String, Money>,
+    /// Last cost seen per session, for computing deltas
+    last_cost: HashMap<SessionId, Money>,
+    /// Cost the ledger had booked per session at startup, used as the
+    /// baseline when a restored session is first seen
+    booked: HashMap<SessionId, Money>,
+    /// Limits already reported per session
+    reported: HashSet<(SessionId, BudgetScope)>,
+}
+
+impl BudgetTracker {
+    /// Creates a tracker starting on `today`.
+    pub fn new(config: BudgetConfig, today: NaiveDate) -> Self {
+        Self {
+            config,
+            day: today,
+            day_spend: Money::zero(),
+            project_spend: HashMap::new(),
+            last_cost: HashMap::new(),
+            booked: HashMap::new(),
+            reported: HashSet::new(),
+        }
+    }
+
+    /// Seeds the tracker from the cost ledger: today's total and
+    /// per-project spend, and what each session had already booked.
+    pub fn seed(&mut self, ledger: &CostLedger) {
+        for row in ledger.rows() {
+            if row.key.day != self.day {
+                continue;
+            }
+            let cost = Money::from_usd(row.cost_usd);
+            self.day_spend += cost;
+            if let Some(root) = row.key.project_root {
+                *self.project_spend.entry(root).or_insert(Money::zero()) += cost;
+            }
+        }
+        self.booked = ledger
+            .booked()
+            .map(|(id, cost)| (id.clone(), Money::from_usd(cost)))
+            .collect();
+    }
+
+    /// Whether breaching agents should be interrupted.
+    pub fn interrupts(&self) -> bool {
+        self.config.interrupt
+    }
+
+    /// Records `session`'s current cost and returns the limits it newly
+    /// crossed.
+    pub fn observe(&mut self, session: &SessionDomain, today: NaiveDate) -> Vec<BudgetBreach> {
+        if today != self.day {
+            self.day = today;
+            self.day_spend = Money::zero();
+            self.project_spend.clear();
+            self.reported
+                .retain(|(_, scope)| *scope == BudgetScope::Session);
+        }
+
+        // A session first seen mid-life (e.g. restored after a restart)
+        // counts from what the ledger booked for it; failing that, only
+        // spend from here on counts, unless it started today.
+        let previous = match self.last_cost.get(&session.id) {
+            Some(cost) => *cost,
+            None => match self.booked.remove(&session.id) {
+                Some(cost) => cost,
+                None if session.started_at.with_timezone(&Local).date_naive() == today => {
+                    Money::zero()
+                }
+                None => session.cost,
+            },
+        };
+        let delta = session.cost.as_microdollars() - previous.as_microdollars();
+        if delta > 0 {
+            let delta = Money::from_microdollars(delta);
+            self.day_spend += delta;
+            if let Some(root) = &session.project_root {
+                *self
+                    .project_spend
+                    .entry(root.clone())
+                    .or_insert(Money::zero()) += delta;
+            }
+        }
+        self.last_cost.insert(session.id.clone(), session.cost);
+
+        let project = session.project_root.as_deref();
+        let project_limit = project.and_then(|root| self.config.project_limit(root));
+        let project_spend = project.map(|root| {
+            self.project_spend
+                .get(root)
+                .copied()
+                .unwrap_or(Money::zero())
+        });
+        let checks = [
+            (
+                BudgetScope::Session,
+                self.config.session_usd,
+                Some(session.cost),
+            ),
+            (BudgetScope::Project, project_limit, project_spend),
+            (
+                BudgetScope::Daily,
+                self.config.daily_usd,
+                Some(self.day_spend),
+            ),
+        ];
+
+        let mut breaches = Vec::new();
+        for (scope, limit, spent) in checks {
+            let (Some(limit), Some(spent)) = (limit, spent) else {
+                continue;
+            };
+            let limit = Money::from_usd(limit);
+            if spent > limit && self.reported.insert((session.id.clone(), scope)) {
+                breaches.push(BudgetBreach {
+                    scope,
+                    limit,
+                    spent,
+                });
+            }
+        }
+        breaches
+    }
+
+    /// Whether `session_id` is over any limit reported for the current
+    /// day. Project and daily breaches lapse when the day rolls over.
+    pub fn is_over(&self, session_id: &SessionId) -> bool {
+        self.reported.iter().any(|(id, _)| id == session_id)
+    }
+
+    /// Drops per-session state for a session that left the registry.
+    /// Its spend stays in today's totals.
+    pub fn forget(&mut self, session_id: &SessionId) {
+        self.last_cost.remove(session_id);
+        self.booked.remove(session_id);
+        self.reported.retain(|(id, _)| id != session_id);
+    }
+
+    /// Carries per-session state over to a renamed session.
+    pub fn rename(&mut self, old_id: &SessionId, new_id: &SessionId) {
+        if let Some(cost) = self.last_cost.remove(old_id) {
+            self.last_cost.insert(new_id.clone(), cost);
+        }
+        if let Some(cost) = self.booked.remove(old_id) {
+            self.booked.insert(new_id.clone(), cost);
+        }
+        let renamed: Vec<_> = self
+            .reported
+            .iter()
+            .filter(|(id, _)| id == old_id)
+            .map(|(_, scope)| *scope)
+            .collect();
+        for scope in renamed {
+            self.reported.remove(&(old_id.clone(), scope));
+            self.reported.insert((new_id.clone(), scope));
+        }
+    }
+}
+
+// ============================================================================
+// Enforcement
+// ============================================================================
+
+/// Spawns a task that interrupts agents whose `BudgetExceeded` event
+/// asks for it.
+pub fn spawn_budget_enforcer(
+    registry: RegistryHandle,
+    control: ControlService,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let mut events = registry.subscribe();
+    tokio::spawn(async move {
+        info!("Budget enforcer started");
+        loop {
+            let event = tokio::select! {
+                biased;
+
+                _ = cancel_token.cancelled() => {
+                    debug!("Budget enforcer shutting down");
+                    break;
+                }
+
+                result = events.recv() => match result {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(skipped = n, "Budget enforcer lagged, skipped events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+
+            if let SessionEvent::BudgetExceeded {
+                session_id,
+                scope,
+                interrupt: true,
+                ..
+            } = event
+            {
+                match control.interrupt(session_id.as_str()).await {
+                    Ok(_) => info!(
+                        session_id = %session_id,
+                        scope = %scope,
+                        "Interrupted agent over budget"
+                    ),
+                    Err(e) => warn!(
+                        session_id = %session_id,
+                        error = %e,
+                        "Failed to interrupt agent over budget"
+                    ),
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atm_core::{AgentType, Model, SessionView};
+    use chrono::Duration;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, d).unwrap()
+    }
+
+    fn session(id: &str, cost: f64) -> SessionDomain {
+        let mut session = SessionDomain::new(
+            SessionId::new(id),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-opus-4-6"),
+        );
+        session.cost = Money::from_usd(cost);
+        session.project_root = Some("/work/app".to_string());
+        session
+    }
+
+    fn scopes(breaches: &[BudgetBreach]) -> Vec<BudgetScope> {
+        breaches.iter().map(|b| b.scope).collect()
+    }
+
+    #[test]
+    fn test_session_limit_reported_once() {
+        let config = BudgetConfig {
+            session_usd: Some(5.0),
+            ..Default::default()
+        };
+        let mut tracker = BudgetTracker::new(config, day(16));
+
+        assert!(tracker.observe(&session("a", 4.0), day(16)).is_empty());
+        let breaches = tracker.observe(&session("a", 5.5), day(16));
+        assert_eq!(
+            breaches,
+            vec![BudgetBreach {
+                scope: BudgetScope::Session,
+                limit: Money::from_usd(5.0),
+                spent: Money::from_usd(5.5),
+            }]
+        );
+        assert!(tracker.observe(&session("a", 7.0), day(16)).is_empty());
+    }
+
+    #[test]
+    fn test_project_override_and_spend() {
+        let mut config = BudgetConfig {
+            project_usd: Some(100.0),
+            ..Default::default()
+        };
+        config.projects.insert("/work/app".to_string(), 10.0);
+        let today = Local::now().date_naive();
+        let mut tracker = BudgetTracker::new(config, today);
+
+        // Spend of sessions that ended still counts toward the project
+        assert!(tracker.observe(&session("a", 6.0), today).is_empty());
+        tracker.forget(&SessionId::new("a"));
+        let breaches = tracker.observe(&session("b", 4.5), today);
+        assert_eq!(scopes(&breaches), vec![BudgetScope::Project]);
+        assert_eq!(breaches[0].spent, Money::from_usd(10.5));
+
+        let mut elsewhere = session("c", 12.0);
+        elsewhere.project_root = Some("/work/other".to_string());
+        let breaches = tracker.observe(&elsewhere, today);
+        assert!(breaches.is_empty(), "default project limit is 100");
+
+        // The next day starts from zero
+        let tomorrow = today.succ_opt().unwrap();
+        assert!(tracker.observe(&session("b", 9.0), tomorrow).is_empty());
+    }
+
+    #[test]
+    fn test_seed_counts_spend_booked_before_restart() {
+        let config = BudgetConfig {
+            project_usd: Some(10.0),
+            daily_usd: Some(20.0),
+            ..Default::default()
+        };
+        let today = Local::now().date_naive();
+
+        // Before the restart: a session that ended spent $8, and a
+        // still-running one had $15 booked, $1 of it on an earlier day
+        let mut ledger = CostLedger::new();
+        let mut ended = session("ended", 8.0);
+        ended.project_root = Some("/work/other".to_string());
+        ledger.record(&SessionView::from_domain(&ended), today);
+        ledger.forget(&ended.id);
+        let mut running = session("running", 1.0);
+        running.started_at -= Duration::days(1);
+        ledger.record(
+            &SessionView::from_domain(&running),
+            today.pred_opt().unwrap(),
+        );
+        running.cost = Money::from_usd(15.0);
+        ledger.record(&SessionView::from_domain(&running), today);
+
+        let mut tracker = BudgetTracker::new(config, today);
+        tracker.seed(&ledger);
+
+        // Restored at $15.50: only the unbooked $0.50 is new, but the
+        // project has spent $14.50 today and the day $22.50
+        running.cost = Money::from_usd(15.5);
+        let breaches = tracker.observe(&running, today);
+        assert_eq!(breaches.len(), 2);
+        assert_eq!(breaches[0].scope, BudgetScope::Project);
+        assert_eq!(breaches[0].spent, Money::from_usd(14.5));
+        assert_eq!(breaches[1].scope, BudgetScope::Daily);
+        assert_eq!(breaches[1].spent, Money::from_usd(22.5));
+    }
+
+    #[test]
+    fn test_daily_spend_counts_deltas_and_rolls_over() {
+        let config = BudgetConfig {
+            daily_usd: Some(10.0),
+            ..Default::default()
+        };
+        // "Started today" is judged from `started_at`, which is now
+        let today = Local::now().date_naive();
+        let mut tracker = BudgetTracker::new(config, today);
+
+        // Started days ago: only spend from now on counts
+        let mut old = session("old", 50.0);
+        old.started_at -= Duration::days(3);
+        assert!(tracker.observe(&old, today).is_empty());
+        old.cost = Money::from_usd(56.0);
+        assert!(tracker.observe(&old, today).is_empty());
+
+        // Started today: its whole cost counts, pushing the day to $10.50
+        let breaches = tracker.observe(&session("new", 4.5), today);
+        assert_eq!(scopes(&breaches), vec![BudgetScope::Daily]);
+
+        assert!(tracker.is_over(&SessionId::new("new")));
+
+        // The next day starts from zero
+        let tomorrow = today.succ_opt().unwrap();
+        old.cost = Money::from_usd(58.0);
+        assert!(tracker.observe(&old, tomorrow).is_empty());
+        assert!(tracker.observe(&session("new", 4.6), tomorrow).is_empty());
+        assert!(!tracker.is_over(&SessionId::new("new")));
+    }
+
+    #[test]
+    fn test_forget_clears_reports() {
+        let config = BudgetConfig {
+            session_usd: Some(1.0),
+            ..Default::default()
+        };
+        let mut tracker = BudgetTracker::new(config, day(16));
+        assert_eq!(tracker.observe(&session("a", 2.0), day(16)).len(), 1);
+
+        tracker.rename(&SessionId::new("a"), &SessionId::new("b"));
+        assert!(tracker.observe(&session("b", 2.5), day(16)).is_empty());
+
+        tracker.forget(&SessionId::new("b"));
+        assert_eq!(tracker.observe(&session("b", 3.0), day(16)).len(), 1);
+    }
+
+    #[test]
+    fn test_load_budget_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            r#"
+[budgets]
+session_usd = 20.0
+daily_usd = 100
+interrupt = true
+
+[budgets.projects]
+"/work/app" = 15.5
+"#,
+        )
+        .unwrap();
+
+        let config = load_budget_config(&path).unwrap();
+        assert!(config.is_enabled());
+        assert_eq!(config.session_usd, Some(20.0));
+        assert_eq!(config.daily_usd, Some(100.0));
+        assert!(config.interrupt);
+        assert_eq!(config.project_limit("/work/app"), Some(15.5));
+        assert_eq!(config.project_limit("/work/other"), None);
+
+        assert!(!load_budget_config(&dir.path().join("missing.toml"))
+            .unwrap()
+            .is_enabled());
+    }
+
+    #[test]
+    fn test_load_rejects_non_positive_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[budgets]\nsession_usd = 0.0\n").unwrap();
+        assert!(matches!(
+            load_budget_config(&path),
+            Err(BudgetConfigError::InvalidLimit { .. })
+        ));
+    }
+}
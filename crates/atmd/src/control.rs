@@ -0,0 +1,585 @@
+//! Daemon-side agent control.
+//!
+//! Executes kill / interrupt / send / reply requests against an agent's
+//! tmux pane on behalf of clients. Targets are resolved against the
+//! registry and keystrokes go out through an [`atm_tmux::TmuxClient`], so
+//! every frontend shares one control path and every action passes through
+//! the daemon where it can be logged.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Resolution and tmux failures are returned as `ControlError`
+
+use std::sync::Arc;
+
+use atm_core::{PromptState, SessionId, SessionView};
+use atm_protocol::{ControlAction, ReplyChoice};
+use atm_tmux::{TmuxClient, TmuxError};
+use thiserror::Error;
+
+use crate::registry::RegistryHandle;
+
+// ============================================================================
+// Error Types
+// ============================================================================
+
+/// Errors that can occur while executing a control request.
+#[derive(Debug, Error)]
+pub enum ControlError {
+    /// No session matched the target.
+    #[error("No session matching '{0}'")]
+    NotFound(String),
+
+    /// The target prefix matched more than one session.
+    #[error(
+        "Ambiguous target '{target}' matches {} sessions: {}",
+        .matches.len(),
+        .matches.join(", ")
+    )]
+    Ambiguous {
+        /// The target as given by the client
+        target: String,
+        /// Short IDs of every matching session
+        matches: Vec<String>,
+    },
+
+    /// The session isn't running inside tmux.
+    #[error("Session {0} has no tmux pane")]
+    NoPane(String),
+
+    /// Prompt options are numbered from 1.
+    #[error("Invalid option {0}: options are numbered from 1")]
+    InvalidOption(usize),
+
+    /// The prompt has no free-text entry to type an answer into.
+    #[error("The prompt on {0} has no free-text entry")]
+    NoTextEntry(String),
+
+    /// The prompt on screen has fewer options than the one asked for.
+    #[error("Invalid option {option}: the prompt has {count} options")]
+    NoSuchOption {
+        /// Option asked for
+        option: usize,
+        /// Options the prompt shows
+        count: usize,
+    },
+
+    /// The tmux command failed.
+    #[error("tmux error: {0}")]
+    Tmux(#[from] TmuxError),
+}
+
+impl ControlError {
+    /// Machine-readable code sent in `DaemonMessage::Error`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "SESSION_NOT_FOUND",
+            Self::Ambiguous { .. } => "AMBIGUOUS_TARGET",
+            Self::NoPane(_) => "NO_PANE",
+            Self::InvalidOption(_) | Self::NoSuchOption { .. } | Self::NoTextEntry(_) => {
+                "INVALID_OPTION"
+            }
+            Self::Tmux(_) => "TMUX_ERROR",
+        }
+    }
+}
+
+// ============================================================================
+// Target Resolution
+// ============================================================================
+
+/// A control target resolved to a concrete tmux pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedTarget {
+    /// Session owning the pane.
+    pub session_id: SessionId,
+    /// Tmux pane ID (e.g. "%5").
+    pub pane_id: String,
+}
+
+/// Resolves a target (session ID, unique ID prefix, or `%N` pane ID).
+///
+/// A pane ID only resolves when one of `sessions` is running in that
+/// pane, so control requests can never reach a pane the daemon doesn't
+/// track (a plain shell, say).
+pub fn resolve_target(
+    sessions: &[SessionView],
+    target: &str,
+) -> Result<ResolvedTarget, ControlError> {
+    if target.starts_with('%') {
+        return sessions
+            .iter()
+            .find(|s| s.tmux_pane.as_deref() == Some(target))
+            .map(|s| ResolvedTarget {
+                session_id: s.id.clone(),
+                pane_id: target.to_string(),
+            })
+            .ok_or_else(|| ControlError::NotFound(target.to_string()));
+    }
+
+    if target.is_empty() {
+        return Err(ControlError::NotFound(target.to_string()));
+    }
+
+    let matches: Vec<&SessionView> = sessions
+        .iter()
+        .filter(|s| s.id.as_str().starts_with(target) || s.id_short.starts_with(target))
+        .collect();
+
+    match matches.as_slice() {
+        [] => Err(ControlError::NotFound(target.to_string())),
+        [session] => match &session.tmux_pane {
+            Some(pane) => Ok(ResolvedTarget {
+                session_id: session.id.clone(),
+                pane_id: pane.clone(),
+            }),
+            None => Err(ControlError::NoPane(session.id_short.clone())),
+        },
+        many => Err(ControlError::Ambiguous {
+            target: target.to_string(),
+            matches: many.iter().map(|s| s.id_short.clone()).collect(),
+        }),
+    }
+}
+
+// ============================================================================
+// Prompt Navigation
+// ============================================================================
+
+/// Label prefix of the entry Claude appends to questions for a typed
+/// answer ("Type something.").
+const TEXT_ENTRY_LABEL: &str = "Type something";
+
+/// Keystrokes that move the cursor from option `current` to `desired`.
+fn cursor_keys(current: usize, desired: usize) -> Vec<String> {
+    let (key, steps) = if desired >= current {
+        ("Down", desired - current)
+    } else {
+        ("Up", current - desired)
+    };
+    vec![key.to_string(); steps]
+}
+
+/// Keystrokes that move the cursor from option `current` to `desired` and
+/// select it.
+pub fn option_keys(current: usize, desired: usize) -> Vec<String> {
+    let mut keys = cursor_keys(current, desired);
+    keys.push("Enter".to_string());
+    keys
+}
+
+/// 1-based number of the prompt's free-text entry, if it has one.
+fn text_entry(prompt: &PromptState) -> Option<usize> {
+    prompt
+        .options
+        .iter()
+        .position(|o| o.label.starts_with(TEXT_ENTRY_LABEL))
+        .map(|index| index + 1)
+}
+
+// ============================================================================
+// Control Service
+// ============================================================================
+
+/// Result of a successful control request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlOutcome {
+    /// Which action was performed.
+    pub action: ControlAction,
+    /// Session owning the pane.
+    pub session_id: SessionId,
+    /// Pane the action was applied to.
+    pub pane_id: String,
+    /// Keystrokes sent, in order (empty for kill).
+    pub keys: Vec<String>,
+}
+
+/// Executes control requests against agent panes.
+///
+/// Cheap to clone: holds a registry handle and a shared tmux client.
+#[derive(Clone)]
+pub struct ControlService {
+    registry: RegistryHandle,
+    tmux: Arc<dyn TmuxClient>,
+}
+
+impl ControlService {
+    /// Creates a control service.
+    pub fn new(registry: RegistryHandle, tmux: Arc<dyn TmuxClient>) -> Self {
+        Self { registry, tmux }
+    }
+
+    /// Resolves `target` against the current registry contents.
+    pub async fn resolve(&self, target: &str) -> Result<ResolvedTarget, ControlError> {
+        let sessions = self.registry.get_all_sessions().await;
+        resolve_target(&sessions, target)
+    }
+
+    /// Kills the agent's pane.
+    pub async fn kill(&self, target: &str) -> Result<ControlOutcome, ControlError> {
+        let resolved = self.resolve(target).await?;
+        self.tmux.kill_pane(&resolved.pane_id).await?;
+        Ok(outcome(ControlAction::Kill, resolved, Vec::new()))
+    }
+
+    /// Sends Ctrl+C to the agent's pane.
+    pub async fn interrupt(&self, target: &str) -> Result<ControlOutcome, ControlError> {
+        let resolved = self.resolve(target).await?;
+        // C-c sends SIGINT to the foreground process in the pane
+        let keys = vec!["C-c".to_string()];
+        self.send_all(&resolved.pane_id, &keys).await?;
+        Ok(outcome(ControlAction::Interrupt, resolved, keys))
+    }
+
+    /// Types `text` into the agent's pane and submits it.
+    pub async fn send_input(
+        &self,
+        target: &str,
+        text: &str,
+    ) -> Result<ControlOutcome, ControlError> {
+        let resolved = self.resolve(target).await?;
+        self.tmux.send_text(&resolved.pane_id, text).await?;
+        self.tmux.send_keys(&resolved.pane_id, "Enter").await?;
+        let keys = vec![text.to_string(), "Enter".to_string()];
+        Ok(outcome(ControlAction::SendInput, resolved, keys))
+    }
+
+    /// Answers the prompt currently shown in the agent's pane.
+    ///
+    /// For numbered options the pane is captured and parsed into a
+    /// [`PromptState`] to find where the cursor sits (falling back to the
+    /// session's known prompt, then option 1), then the cursor is moved
+    /// with Up/Down before Enter. Options past the last one on screen are
+    /// rejected.
+    ///
+    /// Text goes into the question's "Type something." entry: the cursor
+    /// is moved there first, then the text is typed literally and
+    /// submitted.
+    pub async fn reply(
+        &self,
+        target: &str,
+        choice: ReplyChoice,
+    ) -> Result<ControlOutcome, ControlError> {
+        if choice == ReplyChoice::Option(0) {
+            return Err(ControlError::InvalidOption(0));
+        }
+
+        let resolved = self.resolve(target).await?;
+        let keys = match choice {
+            // Escape dismisses/cancels the prompt
+            ReplyChoice::Reject => vec!["Escape".to_string()],
+            ReplyChoice::Accept => vec!["Enter".to_string()],
+            ReplyChoice::Option(desired) => {
+                let lines = self.tmux.capture_pane(&resolved.pane_id).await?;
+                let on_screen = PromptState::from_pane(&lines);
+                let count = on_screen.as_ref().map_or(0, |p| p.options.len());
+                if count > 0 && desired > count {
+                    return Err(ControlError::NoSuchOption {
+                        option: desired,
+                        count,
+                    });
+                }
+                let current = match on_screen.and_then(|p| p.selected) {
+                    Some(selected) => selected,
+                    None => self.known_selection(&resolved).await.unwrap_or(1),
+                };
+                option_keys(current, desired)
+            }
+            ReplyChoice::Text(text) => return self.reply_text(resolved, &text).await,
+        };
+        self.send_all(&resolved.pane_id, &keys).await?;
+        Ok(outcome(ControlAction::Reply, resolved, keys))
+    }
+
+    /// Types `text` into the free-text entry of the question on screen.
+    async fn reply_text(
+        &self,
+        resolved: ResolvedTarget,
+        text: &str,
+    ) -> Result<ControlOutcome, ControlError> {
+        let lines = self.tmux.capture_pane(&resolved.pane_id).await?;
+        let prompt = PromptState::current(self.known_prompt(&resolved).await, &lines);
+        let Some((prompt, entry)) = prompt.and_then(|p| text_entry(&p).map(|entry| (p, entry)))
+        else {
+            return Err(ControlError::NoTextEntry(resolved.pane_id));
+        };
+
+        let mut keys = cursor_keys(prompt.selected.unwrap_or(1), entry);
+        self.send_all(&resolved.pane_id, &keys).await?;
+        self.tmux.send_text(&resolved.pane_id, text).await?;
+        self.tmux.send_keys(&resolved.pane_id, "Enter").await?;
+        keys.push(text.to_string());
+        keys.push("Enter".to_string());
+        Ok(outcome(ControlAction::Reply, resolved, keys))
+    }
+
+    /// The prompt the registry knows the session is on.
+    async fn known_prompt(&self, resolved: &ResolvedTarget) -> Option<PromptState> {
+        self.registry
+            .get_session(resolved.session_id.clone())
+            .await?
+            .prompt
+    }
+
+    /// Cursor position of the prompt the registry knows the session is on.
+    async fn known_selection(&self, resolved: &ResolvedTarget) -> Option<usize> {
+        self.known_prompt(resolved).await?.selected
+    }
+
+    async fn send_all(&self, pane_id: &str, keys: &[String]) -> Result<(), ControlError> {
+        for key in keys {
+            self.tmux.send_keys(pane_id, key).await?;
+        }
+        Ok(())
+    }
+}
+
+fn outcome(action: ControlAction, resolved: ResolvedTarget, keys: Vec<String>) -> ControlOutcome {
+    ControlOutcome {
+        action,
+        session_id: resolved.session_id,
+        pane_id: resolved.pane_id,
+        keys,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::spawn_registry;
+    use atm_core::{AgentType, Model, SessionDomain};
+    use atm_tmux::mock::MockCall;
+    use atm_tmux::MockTmuxClient;
+
+    fn session(id: &str, pane: Option<&str>) -> SessionDomain {
+        let mut domain = SessionDomain::new(
+            SessionId::new(id),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-sonnet-4"),
+        );
+        domain.tmux_pane = pane.map(str::to_string);
+        domain
+    }
+
+    fn views(domains: &[SessionDomain]) -> Vec<SessionView> {
+        domains.iter().map(SessionView::from_domain).collect()
+    }
+
+    #[test]
+    fn test_resolve_pane_id_requires_tracked_session() {
+        let sessions = views(&[session("abc12345-one", Some("%5"))]);
+
+        let tracked = resolve_target(&sessions, "%5").unwrap();
+        assert_eq!(tracked.pane_id, "%5");
+        assert_eq!(tracked.session_id, SessionId::new("abc12345-one"));
+
+        let untracked = resolve_target(&sessions, "%9");
+        assert!(matches!(untracked, Err(ControlError::NotFound(ref t)) if t == "%9"));
+    }
+
+    #[test]
+    fn test_resolve_by_prefix() {
+        let sessions = views(&[
+            session("abc12345-one", Some("%5")),
+            session("abd99999-two", Some("%6")),
+            session("fff00000-nopane", None),
+        ]);
+
+        assert_eq!(resolve_target(&sessions, "abc").unwrap().pane_id, "%5");
+        assert!(matches!(
+            resolve_target(&sessions, "ab"),
+            Err(ControlError::Ambiguous { ref matches, .. }) if matches.len() == 2
+        ));
+        assert!(matches!(
+            resolve_target(&sessions, "zzz"),
+            Err(ControlError::NotFound(_))
+        ));
+        assert!(matches!(
+            resolve_target(&sessions, "fff"),
+            Err(ControlError::NoPane(_))
+        ));
+        assert!(matches!(
+            resolve_target(&sessions, ""),
+            Err(ControlError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_option_keys() {
+        assert_eq!(option_keys(1, 1), vec!["Enter"]);
+        assert_eq!(option_keys(1, 3), vec!["Down", "Down", "Enter"]);
+        assert_eq!(option_keys(3, 2), vec!["Up", "Enter"]);
+    }
+
+    #[tokio::test]
+    async fn test_service_reply_navigates_from_cursor() {
+        let registry = spawn_registry();
+        registry
+            .register(session("reply-session", Some("%3")))
+            .await
+            .unwrap();
+
+        let mock = MockTmuxClient::new();
+        mock.set_pane_content(
+            "%3",
+            vec!["❯ 1. Yes".into(), "  2. No".into(), "  3. Other".into()],
+        );
+        let service = ControlService::new(registry, Arc::new(mock.clone()));
+
+        let result = service
+            .reply("reply", ReplyChoice::Option(3))
+            .await
+            .unwrap();
+        assert_eq!(result.action, ControlAction::Reply);
+        assert_eq!(result.session_id, SessionId::new("reply-session"));
+        assert_eq!(result.keys, vec!["Down", "Down", "Enter"]);
+
+        let sent: Vec<MockCall> = mock
+            .calls()
+            .into_iter()
+            .filter(|c| matches!(c, MockCall::SendKeys { .. }))
+            .collect();
+        assert_eq!(sent.len(), 3);
+
+        let err = service
+            .reply("reply", ReplyChoice::Option(4))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ControlError::NoSuchOption {
+                option: 4,
+                count: 3
+            }
+        ));
+        assert_eq!(err.code(), "INVALID_OPTION");
+    }
+
+    #[tokio::test]
+    async fn test_service_reply_text_goes_into_text_entry() {
+        let registry = spawn_registry();
+        registry
+            .register(session("text-session", Some("%6")))
+            .await
+            .unwrap();
+
+        let mock = MockTmuxClient::new();
+        mock.set_pane_content(
+            "%6",
+            vec![
+                "Do you like pineapple on pizza?".into(),
+                "".into(),
+                "❯ 1. Yes".into(),
+                "  2. No".into(),
+                "  3. Type something.".into(),
+                "".into(),
+                "Enter to select · ↑/↓ to navigate · Esc to cancel".into(),
+            ],
+        );
+        let service = ControlService::new(registry, Arc::new(mock.clone()));
+
+        let result = service
+            .reply("text", ReplyChoice::Text("only 2 Enter C-c".into()))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.keys,
+            vec!["Down", "Down", "only 2 Enter C-c", "Enter"]
+        );
+        let key = |keys: &str| MockCall::SendKeys {
+            pane: "%6".into(),
+            keys: keys.into(),
+        };
+        assert_eq!(
+            mock.calls(),
+            vec![
+                MockCall::CapturePane { pane: "%6".into() },
+                key("Down"),
+                key("Down"),
+                MockCall::SendText {
+                    pane: "%6".into(),
+                    text: "only 2 Enter C-c".into()
+                },
+                key("Enter"),
+            ]
+        );
+
+        // A prompt without a text entry can't take a typed answer
+        mock.clear_calls();
+        mock.set_pane_content("%6", vec!["❯ 1. Yes".into(), "  2. No".into()]);
+        let err = service
+            .reply("text", ReplyChoice::Text("hi".into()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ControlError::NoTextEntry(_)));
+        assert_eq!(
+            mock.calls(),
+            vec![MockCall::CapturePane { pane: "%6".into() }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_service_reply_uses_known_prompt_cursor() {
+        use atm_core::{LifecycleEvent, NeedsInputReason, PromptKind, PromptOption, Tool};
+
+        let registry = spawn_registry();
+        registry
+            .register(session("asked-session", Some("%4")))
+            .await
+            .unwrap();
+        let mut prompt = PromptState::from_hook(
+            PromptKind::Question,
+            Some("Which?".into()),
+            vec![PromptOption::new("A"), PromptOption::new("B")],
+        );
+        prompt.selected = Some(2);
+        registry
+            .apply_lifecycle_event(
+                SessionId::new("asked-session"),
+                LifecycleEvent::NeedsInput {
+                    reason: NeedsInputReason::InteractiveTool {
+                        tool: Tool::AskUserQuestion,
+                    },
+                    prompt: Some(prompt),
+                },
+                atm_core::Harness::ClaudeCode,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Nothing parseable on screen: the registry's prompt places the cursor.
+        let mock = MockTmuxClient::new();
+        mock.set_pane_content("%4", vec!["rendering...".into()]);
+        let service = ControlService::new(registry, Arc::new(mock));
+        let result = service
+            .reply("asked", ReplyChoice::Option(1))
+            .await
+            .unwrap();
+        assert_eq!(result.keys, vec!["Up", "Enter"]);
+    }
+
+    #[tokio::test]
+    async fn test_service_reports_tmux_failure() {
+        let registry = spawn_registry();
+        registry
+            .register(session("gone-session", Some("%8")))
+            .await
+            .unwrap();
+        let mock = MockTmuxClient::new();
+        mock.set_next_error(TmuxError::PaneNotFound("%8".into()));
+        let service = ControlService::new(registry, Arc::new(mock));
+
+        let err = service.kill("%8").await.unwrap_err();
+        assert_eq!(err.code(), "TMUX_ERROR");
+
+        let err = service
+            .reply("%8", ReplyChoice::Option(0))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "INVALID_OPTION");
+    }
+}
@@ -81,15 +81,15 @@ pub struct DiscoveryResult {
 /// tag the session so the TUI shows the right vendor badge from the
 /// first frame, before any adapter event arrives.
 #[derive(Debug, Clone)]
-struct DiscoveredProcess {
+pub(crate) struct DiscoveredProcess {
     /// Process ID
-    pid: u32,
+    pub(crate) pid: u32,
     /// Working directory
-    cwd: PathBuf,
+    pub(crate) cwd: PathBuf,
     /// Tmux pane ID if running in tmux
-    tmux_pane: Option<String>,
+    pub(crate) tmux_pane: Option<String>,
     /// Which coding-agent harness this process belongs to.
-    harness: Harness,
+    pub(crate) harness: Harness,
 }
 
 // ============================================================================
@@ -260,7 +260,7 @@ impl DiscoveryService {
 ///
 /// This function performs blocking I/O and should be called via
 /// `spawn_blocking`.
-fn scan_agent_processes() -> Result<Vec<DiscoveredProcess>, DiscoveryError> {
+pub(crate) fn scan_agent_processes() -> Result<Vec<DiscoveredProcess>, DiscoveryError> {
     let mut processes = Vec::new();
 
     // Read /proc directory
@@ -716,6 +716,6 @@ mod tests {
             .filter(|definition| definition.discovery_enabled)
             .map(|definition| definition.id)
             .collect();
-        assert_eq!(enabled, vec!["claude", "pi"]);
+        assert_eq!(enabled, vec!["claude", "pi", "codex", "gemini"]);
     }
 }
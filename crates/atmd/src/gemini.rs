@@ -0,0 +1,368 @@
+//! Gemini CLI transcript tailer.
+//!
+//! Gemini CLI cannot push events to the daemon, so this task pulls them:
+//! it watches each running Gemini process's recorded session file and
+//! feeds changes through `atm_gemini_adapter::TranscriptCursor`, applying
+//! the resulting lifecycle events to the registry like any adapter event.
+//!
+//! Processes are found with the same `/proc` scan discovery uses. A
+//! process's session file is the newest `session-*.json` for its working
+//! directory written since the process started. Two Gemini processes in
+//! the same directory cannot be told apart this way; both follow the
+//! most recently written session.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Unreadable or half-written session files are skipped until the next poll
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use atm_core::{Harness, LifecycleEvent, SessionId};
+use atm_gemini_adapter::transcript::{chats_dir, gemini_home, latest_session_file};
+use atm_gemini_adapter::{ConversationRecord, TranscriptCursor};
+use tokio::time::interval;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::discovery::scan_agent_processes;
+use crate::registry::RegistryHandle;
+use crate::tmux::get_process_start_time;
+
+/// How often session files are checked for changes.
+pub const GEMINI_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Polls between `/proc` rescans for new or exited Gemini processes.
+const PROCESS_RESCAN_POLLS: u32 = 5;
+
+/// A lifecycle event read from a Gemini session file, ready for the
+/// registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TailedEvent {
+    pub session_id: SessionId,
+    pub event: LifecycleEvent,
+    pub pid: u32,
+    pub tmux_pane: Option<String>,
+}
+
+/// Tail state for one Gemini process.
+#[derive(Debug)]
+struct WatchedProcess {
+    cwd: PathBuf,
+    tmux_pane: Option<String>,
+    started_at: Option<SystemTime>,
+    /// Session file being followed, and its (mtime, length) when last read.
+    file: Option<(PathBuf, SystemTime, u64)>,
+    cursor: TranscriptCursor,
+}
+
+/// Follows the session files of a set of Gemini processes.
+///
+/// All methods do blocking filesystem I/O; run them via `spawn_blocking`.
+#[derive(Debug)]
+pub struct GeminiTailer {
+    gemini_home: PathBuf,
+    watched: HashMap<u32, WatchedProcess>,
+}
+
+impl GeminiTailer {
+    /// Creates a tailer reading sessions under `gemini_home` (`~/.gemini`).
+    pub fn new(gemini_home: PathBuf) -> Self {
+        Self {
+            gemini_home,
+            watched: HashMap::new(),
+        }
+    }
+
+    /// Starts following a Gemini process. No-op if already watched.
+    pub fn watch(&mut self, pid: u32, cwd: PathBuf, tmux_pane: Option<String>) {
+        self.watched.entry(pid).or_insert_with(|| WatchedProcess {
+            cwd,
+            tmux_pane,
+            started_at: process_started_at(pid),
+            file: None,
+            cursor: TranscriptCursor::new(),
+        });
+    }
+
+    /// Number of processes being followed.
+    pub fn watched_count(&self) -> usize {
+        self.watched.len()
+    }
+
+    /// Rescans `/proc`: watches new Gemini processes, forgets exited ones.
+    pub fn rescan_processes(&mut self) {
+        let processes = match scan_agent_processes() {
+            Ok(processes) => processes,
+            Err(e) => {
+                debug!(error = %e, "Gemini process scan failed");
+                return;
+            }
+        };
+
+        let running: Vec<_> = processes
+            .into_iter()
+            .filter(|p| p.harness == Harness::Gemini)
+            .collect();
+        self.watched
+            .retain(|pid, _| running.iter().any(|p| p.pid == *pid));
+        for process in running {
+            self.watch(process.pid, process.cwd, process.tmux_pane);
+        }
+    }
+
+    /// Reads every changed session file and returns the new events.
+    pub fn poll(&mut self) -> Vec<TailedEvent> {
+        let mut tailed = Vec::new();
+        for (pid, watched) in &mut self.watched {
+            let dir = chats_dir(&self.gemini_home, &watched.cwd);
+            if let Some(events) = poll_process(&dir, watched) {
+                let (session_id, events) = events;
+                tailed.extend(events.into_iter().map(|event| TailedEvent {
+                    session_id: session_id.clone(),
+                    event,
+                    pid: *pid,
+                    tmux_pane: watched.tmux_pane.clone(),
+                }));
+            }
+        }
+        tailed
+    }
+}
+
+/// Reads one process's session file if it changed.
+fn poll_process(
+    dir: &Path,
+    watched: &mut WatchedProcess,
+) -> Option<(SessionId, Vec<LifecycleEvent>)> {
+    let (path, mtime) = latest_session_file(dir)?;
+    // A file from an earlier Gemini run in the same directory
+    if watched.started_at.is_some_and(|started| mtime < started) {
+        return None;
+    }
+
+    let len = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    let first_read = watched.file.is_none();
+    if let Some((current, seen_mtime, seen_len)) = &watched.file {
+        if *current != path {
+            // `/clear` or a new chat: a different file, a fresh conversation
+            watched.cursor = TranscriptCursor::new();
+        } else if (*seen_mtime, *seen_len) == (mtime, len) {
+            return None;
+        }
+    }
+
+    let content = std::fs::read_to_string(&path).ok()?;
+    let record = match ConversationRecord::from_json(&content) {
+        Ok(record) => record,
+        Err(e) => {
+            // Most likely caught mid-write; retry on the next poll
+            debug!(path = %path.display(), error = %e, "Skipping unreadable Gemini session file");
+            return None;
+        }
+    };
+    watched.file = Some((path, mtime, len));
+
+    let events = if first_read {
+        watched.cursor.catch_up(&record)
+    } else {
+        watched.cursor.advance(&record)
+    };
+    Some((record.atm_session_id(), events))
+}
+
+/// Clock ticks per second in `/proc/{pid}/stat` (`USER_HZ`, fixed at 100
+/// on Linux regardless of the kernel's internal tick rate).
+const USER_HZ: u64 = 100;
+
+/// Wall-clock start time of a process.
+///
+/// `/proc/{pid}/stat` gives the start in ticks since boot; adding the boot
+/// time from `/proc/stat` turns it into a `SystemTime`. `btime` has whole
+/// second resolution, so the result may be up to a second early, which
+/// only errs towards accepting a session file.
+fn process_started_at(pid: u32) -> Option<SystemTime> {
+    let ticks = get_process_start_time(pid)?;
+    let since_boot = Duration::from_millis(ticks.saturating_mul(1000) / USER_HZ);
+    boot_time()?.checked_add(since_boot)
+}
+
+/// System boot time, from the `btime` line of `/proc/stat`.
+fn boot_time() -> Option<SystemTime> {
+    let stat = std::fs::read_to_string("/proc/stat").ok()?;
+    let secs = stat
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+/// Spawns the tailer task. Exits immediately if there is no home
+/// directory to find Gemini's sessions in.
+pub fn spawn_gemini_tailer(
+    registry: RegistryHandle,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let Some(home) = gemini_home() else {
+            warn!("No home directory; Gemini sessions will not be tracked");
+            return;
+        };
+        let mut tailer = Some(GeminiTailer::new(home.clone()));
+        let mut tick = interval(GEMINI_POLL_INTERVAL);
+        let mut polls: u32 = 0;
+
+        info!(
+            interval_ms = GEMINI_POLL_INTERVAL.as_millis() as u64,
+            "Gemini transcript tailer started"
+        );
+
+        loop {
+            tokio::select! {
+                biased;
+
+                _ = cancel_token.cancelled() => {
+                    debug!("Gemini transcript tailer shutting down");
+                    break;
+                }
+
+                _ = tick.tick() => {
+                    let rescan = polls.is_multiple_of(PROCESS_RESCAN_POLLS);
+                    polls = polls.wrapping_add(1);
+
+                    let mut current = tailer.take().unwrap_or_else(|| GeminiTailer::new(home.clone()));
+                    let result = tokio::task::spawn_blocking(move || {
+                        if rescan {
+                            current.rescan_processes();
+                        }
+                        let events = current.poll();
+                        (current, events)
+                    })
+                    .await;
+
+                    let events = match result {
+                        Ok((current, events)) => {
+                            tailer = Some(current);
+                            events
+                        }
+                        Err(e) => {
+                            warn!(error = %e, "Gemini tailer poll panicked; restarting");
+                            continue;
+                        }
+                    };
+
+                    for tailed in events {
+                        if let Err(e) = registry
+                            .apply_lifecycle_event(
+                                tailed.session_id,
+                                tailed.event,
+                                Harness::Gemini,
+                                Some(tailed.pid),
+                                tailed.tmux_pane,
+                            )
+                            .await
+                        {
+                            warn!(error = %e, "Failed to apply Gemini event");
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn session_json(messages: serde_json::Value) -> String {
+        serde_json::json!({"sessionId": "gem-1", "messages": messages}).to_string()
+    }
+
+    #[test]
+    fn test_process_started_at_is_stable_and_in_the_past() {
+        let pid = std::process::id();
+        let started = process_started_at(pid).unwrap();
+        assert!(started <= SystemTime::now());
+        assert_eq!(process_started_at(pid), Some(started));
+        assert_eq!(process_started_at(999_999_999), None);
+    }
+
+    #[test]
+    fn test_poll_catches_up_then_follows_changes() {
+        let home = tempfile::tempdir().unwrap();
+        let project = PathBuf::from("/work/project");
+        let chats = chats_dir(home.path(), &project);
+        fs::create_dir_all(&chats).unwrap();
+
+        // Our own PID: a live process that started before the file exists
+        let pid = std::process::id();
+        let mut tailer = GeminiTailer::new(home.path().to_path_buf());
+        tailer.watch(pid, project, Some("%3".into()));
+        assert!(tailer.poll().is_empty(), "no session file yet");
+
+        let file = chats.join("session-2026-10-16T09-00-gem1.json");
+        fs::write(
+            &file,
+            session_json(serde_json::json!([
+                {"id": "m1", "type": "user", "content": "old prompt"},
+                {"id": "m2", "type": "gemini", "content": "done",
+                 "tokens": {"input": 10, "output": 1, "total": 11}}
+            ])),
+        )
+        .unwrap();
+
+        let events = tailer.poll();
+        assert_eq!(events.len(), 3, "prompt, context, turn end: {events:?}");
+        assert_eq!(events[0].session_id, SessionId::new("gem-1"));
+        assert_eq!(events[0].tmux_pane.as_deref(), Some("%3"));
+        assert!(tailer.poll().is_empty(), "unchanged file");
+
+        fs::write(
+            &file,
+            session_json(serde_json::json!([
+                {"id": "m1", "type": "user", "content": "old prompt"},
+                {"id": "m2", "type": "gemini", "content": "done",
+                 "tokens": {"input": 10, "output": 1, "total": 11}},
+                {"id": "m3", "type": "user", "content": "new prompt"}
+            ])),
+        )
+        .unwrap();
+
+        let events: Vec<_> = tailer.poll().into_iter().map(|t| t.event).collect();
+        assert_eq!(
+            events,
+            vec![LifecycleEvent::PromptSubmit {
+                prompt: Some("new prompt".into())
+            }]
+        );
+    }
+
+    #[test]
+    fn test_half_written_file_is_retried() {
+        let home = tempfile::tempdir().unwrap();
+        let project = PathBuf::from("/work/other");
+        let chats = chats_dir(home.path(), &project);
+        fs::create_dir_all(&chats).unwrap();
+
+        let mut tailer = GeminiTailer::new(home.path().to_path_buf());
+        tailer.watch(std::process::id(), project, None);
+
+        let file = chats.join("session-2026-10-16T10-00-gem1.json");
+        fs::write(&file, r#"{"sessionId": "gem-1", "messages": [{"id""#).unwrap();
+        assert!(tailer.poll().is_empty());
+
+        fs::write(
+            &file,
+            session_json(serde_json::json!([{"id": "m1", "type": "user", "content": "hi"}])),
+        )
+        .unwrap();
+        assert_eq!(tailer.poll().len(), 1);
+    }
+}
@@ -0,0 +1,796 @@
+//! Append-only event journal.
+//!
+//! Every `LifecycleEvent` the registry applies and every `SessionEvent` it
+//! broadcasts is appended as one JSON line to a rotating journal file, so
+//! `atm history` can answer "what did this agent do" long after the session
+//! has left the registry. `Updated` broadcasts are only journaled when the
+//! session's status changes.
+//!
+//! Tool inputs are reduced to the fields that say what the call touched
+//! (`command`, `file_path`, `pattern`), each cut to a short length, so the
+//! journal doesn't keep copies of file contents or patches.
+//!
+//! # File Layout
+//!
+//! ```text
+//! $XDG_STATE_HOME/atm/events.jsonl     (current)
+//! $XDG_STATE_HOME/atm/events.jsonl.1   (previous)
+//! ...
+//! $XDG_STATE_HOME/atm/events.jsonl.N   (oldest)
+//! ```
+//!
+//! When the current file would exceed `max_bytes`, files are shifted up by
+//! one and the oldest is dropped.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Write failures are logged by the journal task and never stop the daemon
+//! - Malformed lines are skipped when reading
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, warn};
+
+use atm_core::{Harness, LifecycleEvent, SessionId};
+
+use crate::approval::truncate_strings;
+use crate::registry::SessionEvent;
+
+/// File name of the current journal inside the ATM state directory.
+pub const JOURNAL_FILE_NAME: &str = "events.jsonl";
+
+/// Default size at which the journal is rotated.
+pub const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default number of rotated files kept alongside the current one.
+pub const DEFAULT_MAX_FILES: usize = 5;
+
+/// Capacity of the registry → journal lifecycle channel.
+pub const JOURNAL_BUFFER: usize = 1000;
+
+/// Tool input fields kept in the journal; the rest of the input is dropped.
+const JOURNALED_INPUT_FIELDS: &[&str] = &["command", "file_path", "pattern"];
+
+/// Longest string kept from a journaled tool input field.
+const MAX_JOURNAL_STRING: usize = 200;
+
+/// Returns the default journal path (`dirs::state_dir()/atm/events.jsonl`).
+pub fn default_journal_path() -> PathBuf {
+    dirs::state_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("atm")
+        .join(JOURNAL_FILE_NAME)
+}
+
+// ============================================================================
+// Entries
+// ============================================================================
+
+/// One line of the journal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// When the event was recorded.
+    pub ts: DateTime<Utc>,
+    /// Session the event belongs to.
+    pub session_id: SessionId,
+    /// Harness short tag (`claude`, `pi`, ...), when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub harness: Option<String>,
+    /// Event name: a `LifecycleEvent` tag (`tool_call_start`, `prompt_submit`,
+    /// ...) or `session_registered` / `session_updated` / `session_removed`.
+    pub event: String,
+    /// Event payload (variant fields), if any.
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub data: serde_json::Value,
+}
+
+impl JournalEntry {
+    /// Builds an entry for a lifecycle event applied to `session_id`.
+    ///
+    /// The event's serde tag becomes `event`; its remaining fields become
+    /// `data`, with any tool `input` trimmed by [`journal_input`].
+    pub fn from_lifecycle(session_id: SessionId, harness: Harness, event: &LifecycleEvent) -> Self {
+        let mut data = serde_json::to_value(event).unwrap_or(serde_json::Value::Null);
+        let name = data
+            .as_object_mut()
+            .and_then(|obj| obj.remove("type"))
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_else(|| "lifecycle".to_string());
+        if let Some(input) = data.get_mut("input") {
+            *input = journal_input(input);
+        }
+        if data.as_object().is_some_and(|obj| obj.is_empty()) {
+            data = serde_json::Value::Null;
+        }
+
+        Self {
+            ts: Utc::now(),
+            session_id,
+            harness: Some(harness.short_tag().to_string()),
+            event: name,
+            data,
+        }
+    }
+
+    /// Builds an entry for a registry broadcast event.
+    ///
+    /// `harness` is supplied by the caller for events that don't carry it
+    /// (`Registered`, `Removed`, `BudgetExceeded`).
+    pub fn from_session_event(event: &SessionEvent, harness: Option<String>) -> Self {
+        let (session_id, harness, name, data) = match event {
+            SessionEvent::Registered {
+                session_id,
+                agent_type,
+            } => (
+                session_id.clone(),
+                harness,
+                "session_registered",
+                serde_json::json!({ "agent_type": agent_type.to_string() }),
+            ),
+            SessionEvent::Updated { session } => (
+                session.id.clone(),
+                Some(session.harness.clone()),
+                "session_updated",
+                serde_json::json!({
+                    "status": session.status_label,
+                    "model": session.model,
+                    "cost_usd": session.cost_usd,
+                    "context_percentage": session.context_percentage,
+                }),
+            ),
+            SessionEvent::Removed { session_id, reason } => (
+                session_id.clone(),
+                harness,
+                "session_removed",
+                serde_json::json!({ "reason": reason.to_string() }),
+            ),
+            SessionEvent::BudgetExceeded {
+                session_id,
+                scope,
+                limit,
+                spent,
+                interrupt,
+            } => (
+                session_id.clone(),
+                harness,
+                "budget_exceeded",
+                serde_json::json!({
+                    "scope": scope.to_string(),
+                    "limit_usd": limit.as_usd(),
+                    "spent_usd": spent.as_usd(),
+                    "interrupt": interrupt,
+                }),
+            ),
+            SessionEvent::PermissionRequested {
+                session,
+                tool,
+                tool_use_id,
+                input,
+                ..
+            } => (
+                session.id.clone(),
+                Some(session.harness.clone()),
+                "permission_requested",
+                serde_json::json!({
+                    "tool": tool.as_str(),
+                    "tool_use_id": tool_use_id,
+                    "input": input.as_ref().map(journal_input),
+                }),
+            ),
+        };
+
+        Self {
+            ts: Utc::now(),
+            session_id,
+            harness,
+            event: name.to_string(),
+            data,
+        }
+    }
+}
+
+/// Reduces a tool input to its [`JOURNALED_INPUT_FIELDS`], each string cut
+/// to [`MAX_JOURNAL_STRING`] characters. `null` if none are present.
+fn journal_input(input: &serde_json::Value) -> serde_json::Value {
+    let kept: serde_json::Map<String, serde_json::Value> = JOURNALED_INPUT_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let value = input.get(*field)?;
+            Some((
+                field.to_string(),
+                truncate_strings(value, MAX_JOURNAL_STRING),
+            ))
+        })
+        .collect();
+    if kept.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::Value::Object(kept)
+    }
+}
+
+// ============================================================================
+// Writer
+// ============================================================================
+
+/// Size-rotating JSONL writer.
+///
+/// All methods do blocking filesystem I/O; the journal task runs the
+/// writer on the blocking pool.
+pub struct JournalWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: Option<File>,
+    size: u64,
+}
+
+impl JournalWriter {
+    /// Creates a writer with the default rotation limits.
+    ///
+    /// The file is opened lazily on the first append.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self::with_limits(path, DEFAULT_MAX_BYTES, DEFAULT_MAX_FILES)
+    }
+
+    /// Creates a writer that rotates at `max_bytes` and keeps `max_files`
+    /// rotated files.
+    pub fn with_limits(path: impl Into<PathBuf>, max_bytes: u64, max_files: usize) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes,
+            max_files,
+            file: None,
+            size: 0,
+        }
+    }
+
+    /// Returns the path of the current journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends one entry, rotating first if it would overflow `max_bytes`.
+    pub fn append(&mut self, entry: &JournalEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry).map_err(io::Error::other)?;
+        line.push(b'\n');
+        let len = line.len() as u64;
+
+        if self.file.is_none() {
+            self.open()?;
+        }
+        if self.size > 0 && self.size.saturating_add(len) > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let Some(file) = self.file.as_mut() else {
+            return Err(io::Error::other("journal file not open"));
+        };
+        file.write_all(&line)?;
+        self.size = self.size.saturating_add(len);
+        Ok(())
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+
+        if self.max_files == 0 {
+            std::fs::remove_file(&self.path)?;
+        } else {
+            // Shift N-1 → N, ..., 1 → 2, current → 1 (oldest falls off).
+            for n in (1..self.max_files).rev() {
+                let from = rotated_path(&self.path, n);
+                if from.exists() {
+                    std::fs::rename(&from, rotated_path(&self.path, n + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+
+        debug!(path = %self.path.display(), "Rotated event journal");
+        self.open()
+    }
+}
+
+/// Path of the `n`th rotated file (`events.jsonl.n`).
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+// ============================================================================
+// Reading & Querying
+// ============================================================================
+
+/// Filter applied when reading the journal.
+#[derive(Debug, Clone, Default)]
+pub struct JournalQuery {
+    /// Session ID or ID prefix (e.g. the 8-char short form).
+    pub session: Option<String>,
+    /// Only entries at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries before this time.
+    pub until: Option<DateTime<Utc>>,
+    /// Only entries with this event name.
+    pub event: Option<String>,
+    /// Keep only the newest this many matches.
+    pub limit: Option<usize>,
+}
+
+impl JournalQuery {
+    /// Returns true if `entry` passes every filter that is set.
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        if let Some(ref prefix) = self.session {
+            if !entry.session_id.as_str().starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+        if self.since.is_some_and(|since| entry.ts < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| entry.ts >= until) {
+            return false;
+        }
+        if let Some(ref event) = self.event {
+            if entry.event != *event {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Passes every journal entry matching `query` to `visit`, oldest first,
+/// without holding more than one entry in memory. `query.limit` is not
+/// applied; see [`read_journal`].
+///
+/// Walks rotated files from oldest to newest, then the current file.
+/// Missing files are skipped; malformed lines are ignored. Stops at the
+/// first error `visit` returns.
+pub fn scan_journal(
+    path: &Path,
+    query: &JournalQuery,
+    mut visit: impl FnMut(JournalEntry) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut files: Vec<PathBuf> = (1..=usize::from(u8::MAX))
+        .map(|n| rotated_path(path, n))
+        .take_while(|p| p.exists())
+        .collect();
+    files.reverse();
+    files.push(path.to_path_buf());
+
+    for file_path in files {
+        let file = match File::open(&file_path) {
+            Ok(f) => f,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if let Ok(entry) = serde_json::from_str::<JournalEntry>(&line) {
+                if query.matches(&entry) {
+                    visit(entry)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the journal entries matching `query`, oldest first.
+///
+/// With `query.limit`, only the newest `limit` matches are kept while
+/// reading; without it, every match is collected.
+pub fn read_journal(path: &Path, query: &JournalQuery) -> io::Result<Vec<JournalEntry>> {
+    let mut entries = VecDeque::new();
+    scan_journal(path, query, |entry| {
+        if query.limit.is_some_and(|limit| entries.len() >= limit) {
+            entries.pop_front();
+        }
+        if query.limit != Some(0) {
+            entries.push_back(entry);
+        }
+        Ok(())
+    })?;
+    Ok(entries.into())
+}
+
+// ============================================================================
+// Journal Task
+// ============================================================================
+
+/// Spawns the task that writes journal entries.
+///
+/// Drains two sources: lifecycle entries sent directly by the registry actor
+/// (preferred, so an event is journaled before the update it caused) and
+/// the registry's `SessionEvent` broadcast. Exits when both are closed.
+///
+/// `Updated` broadcasts are dropped unless the session's status differs from
+/// the last one journaled for it; cost and context ticks would otherwise
+/// fill the journal.
+///
+/// Entries are handed to the writer on the blocking pool, so a slow disk
+/// never stalls a runtime worker.
+pub fn spawn_journal_task(
+    writer: JournalWriter,
+    mut lifecycle_rx: mpsc::Receiver<JournalEntry>,
+    mut event_rx: broadcast::Receiver<SessionEvent>,
+) {
+    let (write_tx, write_rx) = mpsc::channel(JOURNAL_BUFFER);
+    tokio::task::spawn_blocking(move || run_writer(writer, write_rx));
+
+    tokio::spawn(async move {
+        // Remember each session's harness so Registered/Removed entries
+        // (which don't carry one) can still be attributed.
+        let mut harnesses: std::collections::HashMap<SessionId, String> =
+            std::collections::HashMap::new();
+        let mut statuses: std::collections::HashMap<SessionId, String> =
+            std::collections::HashMap::new();
+        let mut lifecycle_open = true;
+
+        loop {
+            let entry = tokio::select! {
+                biased;
+
+                msg = lifecycle_rx.recv(), if lifecycle_open => match msg {
+                    Some(entry) => entry,
+                    None => {
+                        lifecycle_open = false;
+                        continue;
+                    }
+                },
+
+                result = event_rx.recv() => match result {
+                    Ok(event) => {
+                        let known = match &event {
+                            SessionEvent::Registered { session_id, .. } => {
+                                harnesses.get(session_id).cloned()
+                            }
+                            SessionEvent::Removed { session_id, .. } => {
+                                statuses.remove(session_id);
+                                harnesses.remove(session_id)
+                            }
+                            SessionEvent::BudgetExceeded { session_id, .. } => {
+                                harnesses.get(session_id).cloned()
+                            }
+                            SessionEvent::Updated { session } => {
+                                let previous = statuses
+                                    .insert(session.id.clone(), session.status_label.clone());
+                                if previous.as_deref() == Some(session.status_label.as_str()) {
+                                    continue;
+                                }
+                                None
+                            }
+                            SessionEvent::PermissionRequested { .. } => None,
+                        };
+                        JournalEntry::from_session_event(&event, known)
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(skipped = n, "Event journal lagged, skipped events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        debug!("Event journal stopping: registry channel closed");
+                        break;
+                    }
+                },
+            };
+
+            if entry.event != "session_removed" {
+                if let Some(ref harness) = entry.harness {
+                    harnesses.insert(entry.session_id.clone(), harness.clone());
+                }
+            }
+
+            if write_tx.send(entry).await.is_err() {
+                warn!("Event journal writer stopped");
+                break;
+            }
+        }
+    });
+}
+
+/// Appends entries from `entries` until every sender is gone.
+fn run_writer(mut writer: JournalWriter, mut entries: mpsc::Receiver<JournalEntry>) {
+    while let Some(entry) = entries.blocking_recv() {
+        if let Err(e) = writer.append(&entry) {
+            warn!(
+                path = %writer.path().display(),
+                error = %e,
+                "Failed to append to event journal"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::RemovalReason;
+    use atm_core::Tool;
+
+    fn entry(id: &str, event: &str, ts: DateTime<Utc>) -> JournalEntry {
+        JournalEntry {
+            ts,
+            session_id: SessionId::new(id),
+            harness: Some("claude".to_string()),
+            event: event.to_string(),
+            data: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_from_lifecycle_splits_tag_and_payload() {
+        let event = LifecycleEvent::ToolCallStart {
+            name: Tool::Bash,
+            tool_use_id: Some("toolu_1".to_string()),
+            input: None,
+        };
+        let entry = JournalEntry::from_lifecycle(SessionId::new("s1"), Harness::Pi, &event);
+
+        assert_eq!(entry.event, "tool_call_start");
+        assert_eq!(entry.harness.as_deref(), Some("pi"));
+        assert_eq!(entry.data["name"], "Bash");
+        assert!(entry.data.get("type").is_none());
+    }
+
+    #[test]
+    fn test_from_lifecycle_keeps_only_identifying_input() {
+        let event = LifecycleEvent::ToolCallStart {
+            name: Tool::Write,
+            tool_use_id: None,
+            input: Some(serde_json::json!({
+                "file_path": "/repo/.env",
+                "content": "API_KEY=hunter2",
+            })),
+        };
+        let entry = JournalEntry::from_lifecycle(SessionId::new("s1"), Harness::Pi, &event);
+        assert_eq!(
+            entry.data["input"],
+            serde_json::json!({ "file_path": "/repo/.env" })
+        );
+
+        let event = LifecycleEvent::ToolCallStart {
+            name: Tool::Bash,
+            tool_use_id: None,
+            input: Some(serde_json::json!({ "command": "x".repeat(MAX_JOURNAL_STRING + 50) })),
+        };
+        let entry = JournalEntry::from_lifecycle(SessionId::new("s1"), Harness::Pi, &event);
+        let command = entry.data["input"]["command"].as_str().unwrap();
+        assert_eq!(command.chars().count(), MAX_JOURNAL_STRING + 1);
+
+        // Nothing identifying: the input is left out entirely
+        assert!(journal_input(&serde_json::json!({ "old_string": "a" })).is_null());
+    }
+
+    #[test]
+    fn test_from_session_event_removed() {
+        let event = SessionEvent::Removed {
+            session_id: SessionId::new("gone"),
+            reason: RemovalReason::ProcessDied,
+        };
+        let entry = JournalEntry::from_session_event(&event, Some("claude".to_string()));
+
+        assert_eq!(entry.event, "session_removed");
+        assert_eq!(entry.session_id.as_str(), "gone");
+        assert_eq!(entry.harness.as_deref(), Some("claude"));
+        assert!(entry.data["reason"].is_string());
+    }
+
+    #[test]
+    fn test_writer_rotates_and_reader_orders_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+        // Small enough that every entry forces a rotation.
+        let mut writer = JournalWriter::with_limits(&path, 10, 2);
+        let now = Utc::now();
+
+        for i in 0..4 {
+            writer
+                .append(&entry(&format!("s{i}"), "idle", now))
+                .unwrap();
+        }
+
+        assert!(path.exists());
+        assert!(rotated_path(&path, 1).exists());
+        assert!(rotated_path(&path, 2).exists());
+        assert!(!rotated_path(&path, 3).exists());
+
+        let entries = read_journal(&path, &JournalQuery::default()).unwrap();
+        let ids: Vec<&str> = entries.iter().map(|e| e.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["s1", "s2", "s3"]);
+    }
+
+    #[test]
+    fn test_read_limit_keeps_newest_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+        let mut writer = JournalWriter::with_limits(&path, 200, 3);
+        let now = Utc::now();
+        for i in 0..6 {
+            writer
+                .append(&entry(&format!("s{i}"), "idle", now))
+                .unwrap();
+        }
+
+        let query = JournalQuery {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let entries = read_journal(&path, &query).unwrap();
+        let ids: Vec<&str> = entries.iter().map(|e| e.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["s4", "s5"]);
+
+        let mut seen = 0;
+        scan_journal(&path, &query, |_| {
+            seen += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(seen, 6);
+    }
+
+    #[test]
+    fn test_read_missing_journal_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let entries =
+            read_journal(&dir.path().join("nope.jsonl"), &JournalQuery::default()).unwrap();
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn test_read_skips_malformed_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+        let good = serde_json::to_string(&entry("ok", "idle", Utc::now())).unwrap();
+        std::fs::write(&path, format!("not json\n{good}\n")).unwrap();
+
+        let entries = read_journal(&path, &JournalQuery::default()).unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let now = Utc::now();
+        let earlier = now - chrono::Duration::hours(2);
+        let e = entry("abcdef123456", "tool_call_start", earlier);
+
+        assert!(JournalQuery::default().matches(&e));
+        assert!(JournalQuery {
+            session: Some("abcdef12".to_string()),
+            ..Default::default()
+        }
+        .matches(&e));
+        assert!(!JournalQuery {
+            session: Some("zzz".to_string()),
+            ..Default::default()
+        }
+        .matches(&e));
+        assert!(!JournalQuery {
+            since: Some(now - chrono::Duration::hours(1)),
+            ..Default::default()
+        }
+        .matches(&e));
+        assert!(!JournalQuery {
+            until: Some(earlier),
+            ..Default::default()
+        }
+        .matches(&e));
+        assert!(!JournalQuery {
+            event: Some("prompt_submit".to_string()),
+            ..Default::default()
+        }
+        .matches(&e));
+    }
+
+    #[tokio::test]
+    async fn test_journal_task_writes_both_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+        let (lifecycle_tx, lifecycle_rx) = mpsc::channel(16);
+        let (event_tx, event_rx) = broadcast::channel(16);
+
+        spawn_journal_task(JournalWriter::new(&path), lifecycle_rx, event_rx);
+
+        lifecycle_tx
+            .send(JournalEntry::from_lifecycle(
+                SessionId::new("s1"),
+                Harness::ClaudeCode,
+                &LifecycleEvent::WorkingStart,
+            ))
+            .await
+            .unwrap();
+        event_tx
+            .send(SessionEvent::Removed {
+                session_id: SessionId::new("s1"),
+                reason: RemovalReason::SessionEnded,
+            })
+            .unwrap();
+        drop(lifecycle_tx);
+        drop(event_tx);
+
+        // The task exits once both sources close; poll for the file.
+        let mut entries = Vec::new();
+        for _ in 0..50 {
+            entries = read_journal(&path, &JournalQuery::default()).unwrap();
+            if entries.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event, "working_start");
+        assert_eq!(entries[1].event, "session_removed");
+        // Harness carried over from the lifecycle entry.
+        assert_eq!(entries[1].harness.as_deref(), Some("claude"));
+    }
+
+    #[tokio::test]
+    async fn test_journal_task_keeps_only_status_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(JOURNAL_FILE_NAME);
+        let (_lifecycle_tx, lifecycle_rx) = mpsc::channel(16);
+        let (event_tx, event_rx) = broadcast::channel(16);
+
+        spawn_journal_task(JournalWriter::new(&path), lifecycle_rx, event_rx);
+
+        let domain = atm_core::SessionDomain::new(
+            SessionId::new("s1"),
+            atm_core::AgentType::GeneralPurpose,
+            atm_core::Model::from_id("claude-sonnet-4"),
+        );
+        let mut view = atm_core::SessionView::from_domain(&domain);
+        for (status, cost) in [
+            ("idle", 0.1),
+            ("idle", 0.2),
+            ("working", 0.3),
+            ("working", 0.4),
+        ] {
+            view.status_label = status.to_string();
+            view.cost_usd = cost;
+            event_tx
+                .send(SessionEvent::Updated {
+                    session: Box::new(view.clone()),
+                })
+                .unwrap();
+        }
+        drop(event_tx);
+
+        let mut entries = Vec::new();
+        for _ in 0..50 {
+            entries = read_journal(&path, &JournalQuery::default()).unwrap();
+            if entries.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        let statuses: Vec<_> = entries.iter().map(|e| e.data["status"].clone()).collect();
+        assert_eq!(statuses, ["idle", "working"]);
+        assert_eq!(entries[0].data["cost_usd"], 0.1);
+    }
+}
@@ -0,0 +1,662 @@
+//! Daily cost ledger.
+//!
+//! A session's `cost_usd` is cumulative and leaves with the session. The
+//! ledger task watches `SessionEvent::Updated`, books every increase in a
+//! session's cost against today's row for its project, branch, harness and
+//! model, and keeps the totals in a small JSON file so `atm cost` can
+//! report spend for sessions that ended long ago.
+//!
+//! # File Format
+//!
+//! A single JSON document, `$XDG_STATE_HOME/atm/costs.json` by default:
+//!
+//! ```text
+//! { "version": 1,
+//!   "rows": [ { "day": "2026-10-16", "project_root": "/src/app", "worktree_branch": "main",
+//!               "harness": "claude", "model": "Opus 4.6", "cost_usd": 4.20 } ],
+//!   "sessions": { "<session id>": { "cost_usd": 1.10, "seen": "..." } } }
+//! ```
+//!
+//! `sessions` is the last cost booked for each live session, so a daemon
+//! restart (which replays restored sessions at their full cost) doesn't
+//! count anything twice. Because only deltas are booked, a lagged event
+//! subscription loses nothing: the next update carries the difference.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Write failures are logged by the ledger task and never stop the daemon
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tokio::time::interval;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use atm_core::{SessionId, SessionView};
+
+use crate::registry::SessionEvent;
+
+/// Current ledger file format version.
+pub const LEDGER_VERSION: u32 = 1;
+
+/// File name of the ledger inside the ATM state directory.
+pub const LEDGER_FILE_NAME: &str = "costs.json";
+
+/// How often the ledger task writes pending changes to disk.
+pub const FLUSH_INTERVAL_SECS: u64 = 10;
+
+/// Sessions not updated for this long are dropped from the
+/// last-booked-cost table (they ended while the daemon was down).
+const SESSION_MARK_TTL_DAYS: i64 = 7;
+
+/// Returns the default ledger path (`dirs::state_dir()/atm/costs.json`).
+pub fn default_ledger_path() -> PathBuf {
+    dirs::state_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("atm")
+        .join(LEDGER_FILE_NAME)
+}
+
+// ============================================================================
+// Rows
+// ============================================================================
+
+/// What a ledger row is keyed by.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LedgerKey {
+    /// Local calendar day the cost was incurred on.
+    pub day: NaiveDate,
+    /// Git project root, when the session had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_root: Option<String>,
+    /// Git branch of the session's worktree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_branch: Option<String>,
+    /// Harness short tag (`claude`, `pi`, ...).
+    pub harness: String,
+    /// Model display name.
+    pub model: String,
+}
+
+impl LedgerKey {
+    /// Builds the key for `session`'s spend on `day`.
+    pub fn for_session(session: &SessionView, day: NaiveDate) -> Self {
+        Self {
+            day,
+            project_root: session.project_root.clone(),
+            worktree_branch: session.worktree_branch.clone(),
+            harness: session.harness.clone(),
+            model: session.model.clone(),
+        }
+    }
+}
+
+/// One day's spend for one project/branch/harness/model combination.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerRow {
+    /// Row key.
+    #[serde(flatten)]
+    pub key: LedgerKey,
+    /// Total spend in USD.
+    pub cost_usd: f64,
+}
+
+/// Cost last booked for a session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionMark {
+    cost_usd: f64,
+    seen: DateTime<Utc>,
+}
+
+/// On-disk shape of the ledger.
+#[derive(Debug, Serialize, Deserialize)]
+struct LedgerFile {
+    version: u32,
+    #[serde(default)]
+    rows: Vec<LedgerRow>,
+    #[serde(default)]
+    sessions: HashMap<SessionId, SessionMark>,
+}
+
+/// Errors that can occur while reading or writing the ledger.
+#[derive(Debug, Error)]
+pub enum LedgerError {
+    /// Filesystem error reading or writing the ledger file.
+    #[error("cost ledger I/O error at {path}: {source}")]
+    Io {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The ledger file could not be (de)serialized.
+    #[error("cost ledger format error: {0}")]
+    Format(#[from] serde_json::Error),
+
+    /// The ledger was written by an incompatible daemon version.
+    #[error("unsupported cost ledger version {found} (expected {expected})")]
+    UnsupportedVersion {
+        /// Version found in the file
+        found: u32,
+        /// Version this build understands
+        expected: u32,
+    },
+}
+
+// ============================================================================
+// Ledger
+// ============================================================================
+
+/// Per-day cost totals plus the bookkeeping needed to add to them.
+#[derive(Debug, Clone, Default)]
+pub struct CostLedger {
+    totals: BTreeMap<LedgerKey, f64>,
+    sessions: HashMap<SessionId, SessionMark>,
+}
+
+impl CostLedger {
+    /// Creates an empty ledger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the ledger from `path`; a missing file is an empty ledger.
+    pub fn load(path: &Path) -> Result<Self, LedgerError> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::new()),
+            Err(source) => {
+                return Err(LedgerError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+
+        let file: LedgerFile = serde_json::from_str(&contents)?;
+        if file.version != LEDGER_VERSION {
+            return Err(LedgerError::UnsupportedVersion {
+                found: file.version,
+                expected: LEDGER_VERSION,
+            });
+        }
+
+        let mut totals = BTreeMap::new();
+        for row in file.rows {
+            *totals.entry(row.key).or_insert(0.0) += row.cost_usd;
+        }
+        Ok(Self {
+            totals,
+            sessions: file.sessions,
+        })
+    }
+
+    /// Like [`Self::load`], but a ledger that can't be parsed (corrupt, or
+    /// written by a newer daemon) is moved aside to
+    /// `<path>.bad-<timestamp>` and an empty ledger returned, so the next
+    /// save doesn't overwrite the user's history.
+    ///
+    /// Returns the ledger and, if one was set aside, where the old file
+    /// went. I/O errors, including a failed rename, are returned as is.
+    pub fn load_or_set_aside(path: &Path) -> Result<(Self, Option<PathBuf>), LedgerError> {
+        match Self::load(path) {
+            Ok(ledger) => Ok((ledger, None)),
+            Err(LedgerError::Format(_) | LedgerError::UnsupportedVersion { .. }) => {
+                let mut aside = path.as_os_str().to_owned();
+                aside.push(format!(".bad-{}", Utc::now().format("%Y%m%dT%H%M%S")));
+                let aside = PathBuf::from(aside);
+                std::fs::rename(path, &aside).map_err(|source| LedgerError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })?;
+                Ok((Self::new(), Some(aside)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Atomically writes the ledger to `path` (temp file + rename).
+    pub fn save(&self, path: &Path) -> Result<(), LedgerError> {
+        let io_err = |source| LedgerError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+
+        let file = LedgerFile {
+            version: LEDGER_VERSION,
+            rows: self.rows(),
+            sessions: self.sessions.clone(),
+        };
+        let json = serde_json::to_vec(&file)?;
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, json).map_err(io_err)?;
+        std::fs::rename(&tmp_path, path).map_err(io_err)?;
+
+        Ok(())
+    }
+
+    /// Books any increase in `session`'s cost since it was last seen
+    /// against `day`.
+    ///
+    /// A session seen for the first time is booked in full. A cost that
+    /// went down (the harness reset its counter) is taken as the new
+    /// baseline without booking anything.
+    ///
+    /// Returns true if the ledger changed.
+    pub fn record(&mut self, session: &SessionView, day: NaiveDate) -> bool {
+        let cost = session.cost_usd;
+        if !cost.is_finite() || cost < 0.0 {
+            return false;
+        }
+
+        let previous = self
+            .sessions
+            .insert(
+                session.id.clone(),
+                SessionMark {
+                    cost_usd: cost,
+                    seen: Utc::now(),
+                },
+            )
+            .map_or(0.0, |mark| mark.cost_usd);
+
+        let delta = cost - previous;
+        if delta > 0.0 {
+            *self
+                .totals
+                .entry(LedgerKey::for_session(session, day))
+                .or_insert(0.0) += delta;
+        }
+        delta != 0.0
+    }
+
+    /// Stops tracking a session that has left the registry.
+    ///
+    /// Returns true if the session was tracked.
+    pub fn forget(&mut self, session_id: &SessionId) -> bool {
+        self.sessions.remove(session_id).is_some()
+    }
+
+    /// Moves a session's booked mark to its new id, so a renamed session
+    /// carries on from what was already booked instead of starting over.
+    ///
+    /// Returns true if the session was tracked.
+    pub fn rename(&mut self, old_id: &SessionId, new_id: &SessionId) -> bool {
+        match self.sessions.remove(old_id) {
+            Some(mark) => {
+                self.sessions.insert(new_id.clone(), mark);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The cost last booked for each tracked session.
+    pub fn booked(&self) -> impl Iterator<Item = (&SessionId, f64)> {
+        self.sessions.iter().map(|(id, mark)| (id, mark.cost_usd))
+    }
+
+    /// Drops tracking for sessions not seen since before `cutoff`.
+    fn prune_sessions(&mut self, cutoff: DateTime<Utc>) {
+        self.sessions.retain(|_, mark| mark.seen >= cutoff);
+    }
+
+    /// All rows, ordered by day, then project, branch, harness and model.
+    pub fn rows(&self) -> Vec<LedgerRow> {
+        self.totals
+            .iter()
+            .map(|(key, cost)| LedgerRow {
+                key: key.clone(),
+                cost_usd: *cost,
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Reports
+// ============================================================================
+
+/// Dimension a cost report is grouped by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostGrouping {
+    /// Git project root
+    Project,
+    /// Model display name
+    Model,
+    /// Harness short tag
+    Harness,
+    /// Calendar day
+    Day,
+}
+
+/// One line of a cost report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CostReportRow {
+    /// Group label (project root, model, harness or `YYYY-MM-DD`).
+    pub key: String,
+    /// Total spend in USD.
+    pub cost_usd: f64,
+}
+
+/// Sums `rows` from `since` onwards by `by`.
+///
+/// Days are listed chronologically; every other grouping is listed most
+/// expensive first. Rows without a project are grouped under `-`.
+pub fn cost_report(
+    rows: &[LedgerRow],
+    by: CostGrouping,
+    since: Option<NaiveDate>,
+) -> Vec<CostReportRow> {
+    let mut groups: BTreeMap<String, f64> = BTreeMap::new();
+    for row in rows {
+        if since.is_some_and(|since| row.key.day < since) {
+            continue;
+        }
+        let key = match by {
+            CostGrouping::Project => row
+                .key
+                .project_root
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+            CostGrouping::Model => row.key.model.clone(),
+            CostGrouping::Harness => row.key.harness.clone(),
+            CostGrouping::Day => row.key.day.to_string(),
+        };
+        *groups.entry(key).or_insert(0.0) += row.cost_usd;
+    }
+
+    let mut report: Vec<CostReportRow> = groups
+        .into_iter()
+        .map(|(key, cost_usd)| CostReportRow { key, cost_usd })
+        .collect();
+    if by != CostGrouping::Day {
+        report.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+    }
+    report
+}
+
+// ============================================================================
+// Ledger Task
+// ============================================================================
+
+/// Spawns the task that books session costs into the ledger at `path`.
+///
+/// Changes are written every `FLUSH_INTERVAL_SECS` and once more when
+/// `cancel_token` fires.
+pub fn spawn_ledger_task(
+    mut ledger: CostLedger,
+    path: PathBuf,
+    mut events: broadcast::Receiver<SessionEvent>,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut flush = interval(std::time::Duration::from_secs(FLUSH_INTERVAL_SECS));
+        let mut dirty = false;
+        loop {
+            tokio::select! {
+                _ = cancel_token.cancelled() => {
+                    debug!("Cost ledger shutting down");
+                    break;
+                }
+
+                _ = flush.tick() => {
+                    if dirty {
+                        save_ledger(&mut ledger, &path).await;
+                        dirty = false;
+                    }
+                }
+
+                result = events.recv() => match result {
+                    Ok(SessionEvent::Updated { session }) => {
+                        dirty |= ledger.record(&session, Local::now().date_naive());
+                    }
+                    Ok(SessionEvent::Removed { session_id, reason }) => {
+                        dirty |= match reason.replaced_by() {
+                            Some(new_id) => ledger.rename(&session_id, new_id),
+                            None => ledger.forget(&session_id),
+                        };
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        debug!(skipped = n, "Cost ledger lagged; next updates carry the difference");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            }
+        }
+
+        if dirty {
+            save_ledger(&mut ledger, &path).await;
+        }
+    })
+}
+
+/// Writes a snapshot of `ledger` on the blocking pool so a slow disk
+/// doesn't stall a runtime worker.
+async fn save_ledger(ledger: &mut CostLedger, path: &Path) {
+    ledger.prune_sessions(Utc::now() - Duration::days(SESSION_MARK_TTL_DAYS));
+    let snapshot = ledger.clone();
+    let target = path.to_path_buf();
+    match tokio::task::spawn_blocking(move || snapshot.save(&target)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => warn!(path = %path.display(), error = %e, "Failed to write cost ledger"),
+        Err(e) => warn!(path = %path.display(), error = %e, "Cost ledger write task failed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::RemovalReason;
+    use atm_core::{AgentType, Model, SessionDomain};
+
+    fn view(id: &str, project: &str, model: &str, cost: f64) -> SessionView {
+        let mut domain = SessionDomain::new(
+            SessionId::new(id),
+            AgentType::GeneralPurpose,
+            Model::from_id(model),
+        );
+        domain.project_root = Some(project.to_string());
+        let mut view = SessionView::from_domain(&domain);
+        view.cost_usd = cost;
+        view
+    }
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_record_books_deltas_per_day() {
+        let mut ledger = CostLedger::new();
+        let d1 = day("2026-10-15");
+        let d2 = day("2026-10-16");
+
+        assert!(ledger.record(&view("s1", "/src/a", "claude-opus-4-6", 1.0), d1));
+        assert!(ledger.record(&view("s1", "/src/a", "claude-opus-4-6", 1.5), d1));
+        assert!(!ledger.record(&view("s1", "/src/a", "claude-opus-4-6", 1.5), d1));
+        assert!(ledger.record(&view("s1", "/src/a", "claude-opus-4-6", 4.0), d2));
+
+        let rows = ledger.rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].key.day, d1);
+        assert!((rows[0].cost_usd - 1.5).abs() < 1e-9);
+        assert_eq!(rows[1].key.day, d2);
+        assert!((rows[1].cost_usd - 2.5).abs() < 1e-9);
+        assert_eq!(rows[1].key.model, "Opus 4.6");
+    }
+
+    #[test]
+    fn test_record_rebases_when_cost_drops() {
+        let mut ledger = CostLedger::new();
+        let d = day("2026-10-16");
+
+        ledger.record(&view("s1", "/src/a", "claude-opus-4-6", 3.0), d);
+        assert!(ledger.record(&view("s1", "/src/a", "claude-opus-4-6", 0.5), d));
+        ledger.record(&view("s1", "/src/a", "claude-opus-4-6", 1.0), d);
+
+        assert!((ledger.rows()[0].cost_usd - 3.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_save_and_load_does_not_double_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LEDGER_FILE_NAME);
+        let d = day("2026-10-16");
+
+        let mut ledger = CostLedger::new();
+        ledger.record(&view("s1", "/src/a", "claude-opus-4-6", 2.0), d);
+        ledger.save(&path).unwrap();
+        assert!(!path.with_extension("json.tmp").exists());
+
+        // A restarted daemon sees the restored session at its full cost.
+        let mut reloaded = CostLedger::load(&path).unwrap();
+        assert!(!reloaded.record(&view("s1", "/src/a", "claude-opus-4-6", 2.0), d));
+        assert_eq!(reloaded.rows(), ledger.rows());
+    }
+
+    #[test]
+    fn test_load_missing_is_empty_and_rejects_unknown_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LEDGER_FILE_NAME);
+        assert!(CostLedger::load(&path).unwrap().rows().is_empty());
+
+        std::fs::write(&path, r#"{"version": 99}"#).unwrap();
+        assert!(matches!(
+            CostLedger::load(&path),
+            Err(LedgerError::UnsupportedVersion { found: 99, .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_or_set_aside_keeps_unreadable_ledger() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LEDGER_FILE_NAME);
+
+        let (ledger, aside) = CostLedger::load_or_set_aside(&path).unwrap();
+        assert!(ledger.rows().is_empty());
+        assert_eq!(aside, None);
+
+        std::fs::write(&path, r#"{"version": 99, "rows": []}"#).unwrap();
+        let (ledger, aside) = CostLedger::load_or_set_aside(&path).unwrap();
+        assert!(ledger.rows().is_empty());
+        let aside = aside.unwrap();
+        assert!(!path.exists());
+        assert!(aside
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("costs.json.bad-"));
+        assert_eq!(
+            std::fs::read_to_string(&aside).unwrap(),
+            r#"{"version": 99, "rows": []}"#
+        );
+    }
+
+    #[test]
+    fn test_cost_report_groupings() {
+        let mut ledger = CostLedger::new();
+        ledger.record(
+            &view("s1", "/src/a", "claude-opus-4-6", 1.0),
+            day("2026-10-14"),
+        );
+        ledger.record(
+            &view("s2", "/src/b", "claude-sonnet-4-5", 3.0),
+            day("2026-10-15"),
+        );
+        ledger.record(
+            &view("s3", "/src/a", "claude-sonnet-4-5", 0.5),
+            day("2026-10-16"),
+        );
+        let rows = ledger.rows();
+
+        let by_project = cost_report(&rows, CostGrouping::Project, None);
+        assert_eq!(by_project[0].key, "/src/b");
+        assert_eq!(by_project[1].key, "/src/a");
+        assert!((by_project[1].cost_usd - 1.5).abs() < 1e-9);
+
+        let by_model = cost_report(&rows, CostGrouping::Model, Some(day("2026-10-15")));
+        assert_eq!(by_model.len(), 1);
+        assert_eq!(by_model[0].key, "Sonnet 4.5");
+        assert!((by_model[0].cost_usd - 3.5).abs() < 1e-9);
+
+        let by_day = cost_report(&rows, CostGrouping::Day, None);
+        let days: Vec<&str> = by_day.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(days, vec!["2026-10-14", "2026-10-15", "2026-10-16"]);
+    }
+
+    #[tokio::test]
+    async fn test_ledger_task_flushes_on_cancel() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LEDGER_FILE_NAME);
+        let (event_tx, event_rx) = broadcast::channel(16);
+        let cancel = CancellationToken::new();
+
+        let handle = spawn_ledger_task(CostLedger::new(), path.clone(), event_rx, cancel.clone());
+        event_tx
+            .send(SessionEvent::Updated {
+                session: Box::new(view("s1", "/src/a", "claude-opus-4-6", 0.75)),
+            })
+            .unwrap();
+        // Give the task a chance to see the update before cancelling.
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        cancel.cancel();
+        handle.await.unwrap();
+
+        let rows = CostLedger::load(&path).unwrap().rows();
+        assert_eq!(rows.len(), 1);
+        assert!((rows[0].cost_usd - 0.75).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_ledger_task_keeps_booked_cost_across_id_upgrade() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LEDGER_FILE_NAME);
+        let (event_tx, event_rx) = broadcast::channel(16);
+        let cancel = CancellationToken::new();
+
+        let handle = spawn_ledger_task(CostLedger::new(), path.clone(), event_rx, cancel.clone());
+        let events = [
+            SessionEvent::Updated {
+                session: Box::new(view("pending-42", "/src/a", "claude-opus-4-6", 0.75)),
+            },
+            SessionEvent::Removed {
+                session_id: SessionId::new("pending-42"),
+                reason: RemovalReason::Upgraded {
+                    new_id: SessionId::new("s1"),
+                },
+            },
+            SessionEvent::Registered {
+                session_id: SessionId::new("s1"),
+                agent_type: AgentType::GeneralPurpose,
+            },
+            SessionEvent::Updated {
+                session: Box::new(view("s1", "/src/a", "claude-opus-4-6", 1.0)),
+            },
+        ];
+        for event in events {
+            event_tx.send(event).unwrap();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        cancel.cancel();
+        handle.await.unwrap();
+
+        let rows = CostLedger::load(&path).unwrap().rows();
+        assert_eq!(rows.len(), 1);
+        assert!((rows[0].cost_usd - 1.0).abs() < 1e-9);
+    }
+}
@@ -2,8 +2,17 @@
 //!
 //! This crate provides the core infrastructure for the ATM daemon:
 //! - `registry` - Session registry actor for tracking Claude Code sessions
+//! - `budget` - Cost budgets evaluated by the registry, and their enforcement
 //! - `server` - Unix socket server for client connections
 //! - `monitor` - Process monitoring for CPU/memory tracking
+//! - `approval` - Rule-based automatic answers to permission prompts, with an audit log
+//! - `prompt` - Reads prompts the harness didn't describe from the agent's pane
+//! - `notify` - Desktop, command and webhook notifications on attention events
+//! - `journal` - Rotating JSONL journal of lifecycle and session events
+//! - `ledger` - Per-day cost totals by project, branch, harness and model
+//! - `control` - Kill/interrupt/send/reply executed against agent tmux panes
+//! - `remote` - Token-gated TCP/WebSocket access configuration
+//! - `gemini` - Tails Gemini CLI session files into lifecycle events
 //!
 //! # Architecture
 //!
@@ -34,8 +43,17 @@
 //! - All fallible operations return `Result` or `Option`
 //! - Channel operations handle closure gracefully
 
+pub mod approval;
+pub mod budget;
+pub mod control;
 pub mod discovery;
+pub mod gemini;
+pub mod journal;
+pub mod ledger;
 pub mod monitor;
+pub mod notify;
+pub mod prompt;
 pub mod registry;
+pub mod remote;
 pub mod server;
 pub mod tmux;
@@ -0,0 +1,370 @@
+//! The `[notify]` config table.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::NaiveTime;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::AttentionKind;
+
+/// Debounce applied to sinks that don't set their own.
+pub const DEFAULT_DEBOUNCE_SECS: u64 = 60;
+
+/// The `[notify]` config table.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Default debounce for every sink, in seconds
+    pub debounce_secs: Option<u64>,
+    /// Default quiet hours for every sink
+    pub quiet_hours: Option<QuietHours>,
+    /// Where notifications go
+    pub sinks: Vec<SinkConfig>,
+}
+
+impl NotifyConfig {
+    /// Returns true if at least one sink is configured.
+    pub fn is_enabled(&self) -> bool {
+        !self.sinks.is_empty()
+    }
+
+    /// Checks that every sink has what it needs to run.
+    pub fn validate(&self) -> Result<(), NotifyConfigError> {
+        for (index, sink) in self.sinks.iter().enumerate() {
+            let problem = match &sink.kind {
+                SinkKind::Desktop => None,
+                SinkKind::Command { command } if command.trim().is_empty() => {
+                    Some("command is empty")
+                }
+                SinkKind::Command { .. } => None,
+                SinkKind::Webhook { url, .. }
+                    if !(url.starts_with("http://") || url.starts_with("https://")) =>
+                {
+                    Some("url must start with http:// or https://")
+                }
+                SinkKind::Webhook { .. } => None,
+            };
+            if let Some(reason) = problem {
+                return Err(NotifyConfigError::InvalidSink {
+                    index,
+                    reason: reason.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Debounce window for `sink`, falling back to the table default.
+    pub fn debounce_secs(&self, sink: &SinkConfig) -> u64 {
+        sink.debounce_secs
+            .or(self.debounce_secs)
+            .unwrap_or(DEFAULT_DEBOUNCE_SECS)
+    }
+
+    /// Quiet hours for `sink`, falling back to the table default.
+    pub fn quiet_hours(&self, sink: &SinkConfig) -> Option<QuietHours> {
+        sink.quiet_hours.or(self.quiet_hours)
+    }
+}
+
+/// One `[[notify.sinks]]` entry.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SinkConfig {
+    /// Delivery mechanism and its settings
+    #[serde(flatten)]
+    pub kind: SinkKind,
+    /// Events this sink receives; empty means all of them
+    #[serde(default)]
+    pub events: Vec<AttentionKind>,
+    /// Minimum seconds between notifications for the same session and
+    /// event
+    #[serde(default)]
+    pub debounce_secs: Option<u64>,
+    /// Local time range during which this sink stays silent
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl SinkConfig {
+    /// Returns true if this sink wants `kind` events.
+    pub fn accepts(&self, kind: AttentionKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+/// How a sink delivers notifications.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkKind {
+    /// Freedesktop notification via `notify-send`
+    Desktop,
+    /// Shell command run with `sh -c`, details in `ATM_*` env vars
+    Command {
+        /// Command line
+        command: String,
+    },
+    /// HTTP POST of a JSON body (sent with `curl`)
+    Webhook {
+        /// Endpoint URL
+        url: String,
+        /// Body template with `{{field}}` placeholders; defaults to the
+        /// notification as a JSON object
+        #[serde(default)]
+        template: Option<String>,
+        /// Extra request headers
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+impl SinkKind {
+    /// Short name for logs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Desktop => "desktop",
+            Self::Command { .. } => "command",
+            Self::Webhook { .. } => "webhook",
+        }
+    }
+}
+
+// ============================================================================
+// Quiet Hours
+// ============================================================================
+
+/// A local time-of-day range, written `"22:00-07:00"`.
+///
+/// The range may wrap past midnight. The start is inclusive, the end
+/// exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct QuietHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Returns true if `time` falls inside the range.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid quiet hours '{s}' (expected HH:MM-HH:MM)");
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+        Ok(Self {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+}
+
+impl TryFrom<String> for QuietHours {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+/// Errors loading the notify config.
+#[derive(Debug, Error)]
+pub enum NotifyConfigError {
+    /// The config file exists but could not be read.
+    #[error("failed to read config {path}: {source}")]
+    Io {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The config file is not valid TOML or `[notify]` is malformed.
+    #[error("failed to parse config {path}: {source}")]
+    Parse {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying parse error
+        source: toml::de::Error,
+    },
+
+    /// A sink is missing a usable command or URL.
+    #[error("notify sink #{index}: {reason}")]
+    InvalidSink {
+        /// Position of the sink in `[[notify.sinks]]`
+        index: usize,
+        /// What's wrong with it
+        reason: String,
+    },
+}
+
+/// Only the part of the config file notifications care about.
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    notify: NotifyConfig,
+}
+
+/// Loads and validates the `[notify]` table from `path`.
+///
+/// A missing file or table yields a config with no sinks.
+pub fn load_notify_config(path: &Path) -> Result<NotifyConfig, NotifyConfigError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(NotifyConfig::default()),
+        Err(source) => {
+            return Err(NotifyConfigError::Io {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+    };
+
+    let file: ConfigFile =
+        toml::from_str(&contents).map_err(|source| NotifyConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    file.notify.validate()?;
+
+    Ok(file.notify)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_midnight() {
+        let night: QuietHours = "22:00-07:30".parse().unwrap();
+        assert!(night.contains(time("23:15")));
+        assert!(night.contains(time("03:00")));
+        assert!(!night.contains(time("07:30")));
+        assert!(!night.contains(time("12:00")));
+
+        let lunch: QuietHours = "12:00-13:00".parse().unwrap();
+        assert!(lunch.contains(time("12:30")));
+        assert!(!lunch.contains(time("13:00")));
+        assert_eq!(lunch.to_string(), "12:00-13:00");
+
+        assert!("22:00".parse::<QuietHours>().is_err());
+        assert!("late-early".parse::<QuietHours>().is_err());
+    }
+
+    #[test]
+    fn test_load_sinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            r#"
+[notify]
+quiet_hours = "22:00-07:00"
+
+[[notify.sinks]]
+type = "desktop"
+events = ["permission_prompt", "session_died"]
+
+[[notify.sinks]]
+type = "webhook"
+url = "https://hooks.example.com/atm"
+template = '{"text": "{{title}}"}'
+headers = { Authorization = "Bearer x" }
+debounce_secs = 300
+quiet_hours = "00:00-00:00"
+"#,
+        )
+        .unwrap();
+
+        let config = load_notify_config(&path).unwrap();
+        assert!(config.is_enabled());
+        assert_eq!(config.sinks.len(), 2);
+
+        let desktop = &config.sinks[0];
+        assert_eq!(desktop.kind, SinkKind::Desktop);
+        assert!(desktop.accepts(AttentionKind::SessionDied));
+        assert!(!desktop.accepts(AttentionKind::IdlePrompt));
+        assert_eq!(config.debounce_secs(desktop), DEFAULT_DEBOUNCE_SECS);
+        assert_eq!(config.quiet_hours(desktop), config.quiet_hours);
+
+        let webhook = &config.sinks[1];
+        assert!(webhook.accepts(AttentionKind::IdlePrompt));
+        assert_eq!(config.debounce_secs(webhook), 300);
+        assert_ne!(config.quiet_hours(webhook), config.quiet_hours);
+        let SinkKind::Webhook { headers, .. } = &webhook.kind else {
+            panic!("expected webhook sink");
+        };
+        assert_eq!(
+            headers.get("Authorization").map(String::as_str),
+            Some("Bearer x")
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_bad_sinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        std::fs::write(
+            &path,
+            "[[notify.sinks]]\ntype = \"webhook\"\nurl = \"ftp://x\"\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            load_notify_config(&path),
+            Err(NotifyConfigError::InvalidSink { index: 0, .. })
+        ));
+
+        std::fs::write(&path, "[[notify.sinks]]\ntype = \"pager\"\n").unwrap();
+        assert!(matches!(
+            load_notify_config(&path),
+            Err(NotifyConfigError::Parse { .. })
+        ));
+
+        std::fs::write(&path, "[notify]\nquiet_hours = \"soon\"\n").unwrap();
+        assert!(matches!(
+            load_notify_config(&path),
+            Err(NotifyConfigError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_missing_file_has_no_sinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = load_notify_config(&dir.path().join("nope.toml")).unwrap();
+        assert!(!config.is_enabled());
+    }
+}
@@ -0,0 +1,674 @@
+//! Notifications when a session needs attention.
+//!
+//! A blinking row only helps if someone is looking at the dashboard. The
+//! notifier subscribes to the registry's `SessionEvent`s, picks out
+//! transitions into attention, and hands them to the sinks configured in
+//! the `[notify]` table of `~/.config/atm/config.toml`:
+//!
+//! ```toml
+//! [notify]
+//! quiet_hours = "22:00-07:00"   # default for every sink (local time)
+//! debounce_secs = 60            # default for every sink
+//!
+//! [[notify.sinks]]
+//! type = "desktop"              # notify-send
+//! events = ["permission_prompt", "elicitation", "session_died"]
+//!
+//! [[notify.sinks]]
+//! type = "command"              # sh -c, details in ATM_* env vars
+//! command = "~/bin/page-me"
+//!
+//! [[notify.sinks]]
+//! type = "webhook"              # POSTed with curl
+//! url = "https://hooks.slack.com/services/..."
+//! template = '{"text": "{{title}}: {{message}}"}'
+//! ```
+//!
+//! Events: `permission_prompt`, `elicitation`, `idle_prompt` (the agent
+//! finished and is waiting for the next prompt), `context_critical` and
+//! `session_died`. Each sink drops repeats of the same event for the same
+//! session within its debounce window, and drops everything during its
+//! quiet hours.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Sink failures are logged and never stop the notifier
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use atm_core::{SessionId, SessionStatus, SessionView};
+
+use crate::registry::{RegistryHandle, RemovalReason, SessionEvent};
+
+pub mod config;
+mod sink;
+
+pub use config::{
+    load_notify_config, NotifyConfig, NotifyConfigError, QuietHours, SinkConfig, SinkKind,
+};
+pub use sink::{deliver, render_template, SinkError};
+
+// ============================================================================
+// Events
+// ============================================================================
+
+/// Why a session is being brought to someone's attention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttentionKind {
+    /// Waiting on a permission decision
+    PermissionPrompt,
+    /// Asking the user a question (MCP elicitation, AskUserQuestion)
+    Elicitation,
+    /// Finished its turn and waiting for the next prompt
+    IdlePrompt,
+    /// Context window is nearly full
+    ContextCritical,
+    /// Agent process exited without ending its session
+    SessionDied,
+}
+
+impl AttentionKind {
+    /// Config/wire name of the event.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PermissionPrompt => "permission_prompt",
+            Self::Elicitation => "elicitation",
+            Self::IdlePrompt => "idle_prompt",
+            Self::ContextCritical => "context_critical",
+            Self::SessionDied => "session_died",
+        }
+    }
+
+    /// Human-readable summary used as the notification title.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PermissionPrompt => "Permission needed",
+            Self::Elicitation => "Question waiting",
+            Self::IdlePrompt => "Waiting for input",
+            Self::ContextCritical => "Context almost full",
+            Self::SessionDied => "Agent died",
+        }
+    }
+
+    /// `notify-send` urgency level.
+    fn urgency(&self) -> &'static str {
+        match self {
+            Self::IdlePrompt => "normal",
+            _ => "critical",
+        }
+    }
+
+    /// Classifies a session that is in `AttentionNeeded`.
+    ///
+    /// The view only carries the activity label set by
+    /// `SessionDomain::apply_lifecycle_event`; MCP input and
+    /// `AskUserQuestion` are questions, everything else is a permission
+    /// gate.
+    fn of_waiting(view: &SessionView) -> Self {
+        match view.activity_detail.as_deref() {
+            Some("MCP Input" | "AskUserQuestion") => Self::Elicitation,
+            _ => Self::PermissionPrompt,
+        }
+    }
+}
+
+impl fmt::Display for AttentionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One notification, as rendered to every sink.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    /// What happened
+    #[serde(rename = "event")]
+    pub kind: AttentionKind,
+    /// Full session ID
+    pub session_id: SessionId,
+    /// Short session ID
+    pub session: String,
+    /// Harness short tag
+    pub harness: String,
+    /// Model display name
+    pub model: String,
+    /// Git project root, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Tmux pane, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tmux_pane: Option<String>,
+    /// Tool or prompt the session is waiting on, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// One-line title
+    pub title: String,
+    /// One-line body
+    pub message: String,
+    /// When the transition was seen
+    pub ts: DateTime<Utc>,
+}
+
+impl Notification {
+    /// Builds a `kind` notification about `view`.
+    pub fn new(kind: AttentionKind, view: &SessionView) -> Self {
+        let where_ = view
+            .project_root
+            .as_deref()
+            .and_then(|root| root.rsplit('/').find(|s| !s.is_empty()))
+            .map_or_else(|| view.id_short.clone(), str::to_string);
+        let detail = match kind {
+            AttentionKind::PermissionPrompt | AttentionKind::Elicitation => {
+                view.activity_detail.clone()
+            }
+            AttentionKind::ContextCritical => Some(format!("{:.0}%", view.context_percentage)),
+            AttentionKind::IdlePrompt | AttentionKind::SessionDied => None,
+        };
+        let message = match &detail {
+            Some(detail) => format!("{where_} ({}): {detail}", view.id_short),
+            None => format!("{where_} ({})", view.id_short),
+        };
+
+        Self {
+            kind,
+            session_id: view.id.clone(),
+            session: view.id_short.clone(),
+            harness: view.harness.clone(),
+            model: view.model.clone(),
+            project: view.project_root.clone(),
+            tmux_pane: view.tmux_pane.clone(),
+            detail,
+            title: format!("{}: {}", view.harness, kind.label()),
+            message,
+            ts: Utc::now(),
+        }
+    }
+
+    /// Template/env value for `name`, if it is a known field.
+    fn field(&self, name: &str) -> Option<String> {
+        Some(match name {
+            "event" => self.kind.as_str().to_string(),
+            "session_id" => self.session_id.to_string(),
+            "session" => self.session.clone(),
+            "harness" => self.harness.clone(),
+            "model" => self.model.clone(),
+            "project" => self.project.clone().unwrap_or_default(),
+            "tmux_pane" => self.tmux_pane.clone().unwrap_or_default(),
+            "detail" => self.detail.clone().unwrap_or_default(),
+            "title" => self.title.clone(),
+            "message" => self.message.clone(),
+            "ts" => self.ts.to_rfc3339(),
+            _ => return None,
+        })
+    }
+
+    /// Environment for command sinks (`ATM_EVENT`, `ATM_SESSION`, ...).
+    fn env(&self) -> Vec<(String, String)> {
+        const FIELDS: &[&str] = &[
+            "event",
+            "session_id",
+            "session",
+            "harness",
+            "model",
+            "project",
+            "tmux_pane",
+            "detail",
+            "title",
+            "message",
+            "ts",
+        ];
+        FIELDS
+            .iter()
+            .filter_map(|name| {
+                self.field(name)
+                    .map(|value| (format!("ATM_{}", name.to_uppercase()), value))
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Transition Detection
+// ============================================================================
+
+/// Last state seen for a session.
+struct Watched {
+    view: Box<SessionView>,
+    waiting: Option<AttentionKind>,
+}
+
+/// Turns the registry's stream of session events into attention events.
+#[derive(Default)]
+pub struct AttentionWatcher {
+    sessions: HashMap<SessionId, Watched>,
+}
+
+impl AttentionWatcher {
+    /// Creates a watcher that has seen nothing yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds one registry event; returns the notifications it triggers.
+    pub fn observe(&mut self, event: &SessionEvent) -> Vec<Notification> {
+        match event {
+            SessionEvent::Updated { session } => self.observe_update(session),
+            SessionEvent::Removed { session_id, reason } => {
+                let Some(mut last) = self.sessions.remove(session_id) else {
+                    return Vec::new();
+                };
+                // A renamed session carries on where the old id left off
+                if let Some(new_id) = reason.replaced_by() {
+                    last.view.id = new_id.clone();
+                    self.sessions.insert(new_id.clone(), last);
+                    return Vec::new();
+                }
+                if *reason == RemovalReason::ProcessDied {
+                    vec![Notification::new(AttentionKind::SessionDied, &last.view)]
+                } else {
+                    Vec::new()
+                }
+            }
+            SessionEvent::Registered { .. }
+            | SessionEvent::BudgetExceeded { .. }
+            | SessionEvent::PermissionRequested { .. } => Vec::new(),
+        }
+    }
+
+    fn observe_update(&mut self, view: &SessionView) -> Vec<Notification> {
+        let waiting = (view.status == SessionStatus::AttentionNeeded)
+            .then(|| AttentionKind::of_waiting(view));
+        let previous = self.sessions.get(&view.id);
+
+        let mut out = Vec::new();
+        if let Some(kind) = waiting {
+            if previous.is_none_or(|p| p.waiting != Some(kind)) {
+                out.push(Notification::new(kind, view));
+            }
+        }
+        if let Some(previous) = previous {
+            if view.status == SessionStatus::Idle && previous.view.status == SessionStatus::Working
+            {
+                out.push(Notification::new(AttentionKind::IdlePrompt, view));
+            }
+        }
+        if view.context_critical && previous.is_none_or(|p| !p.view.context_critical) {
+            out.push(Notification::new(AttentionKind::ContextCritical, view));
+        }
+
+        self.sessions.insert(
+            view.id.clone(),
+            Watched {
+                view: Box::new(view.clone()),
+                waiting,
+            },
+        );
+        out
+    }
+}
+
+// ============================================================================
+// Dispatch
+// ============================================================================
+
+/// A configured sink plus its debounce bookkeeping.
+struct SinkState {
+    sink: SinkConfig,
+    debounce: Duration,
+    quiet_hours: Option<QuietHours>,
+    last_sent: HashMap<(SessionId, AttentionKind), Instant>,
+}
+
+impl SinkState {
+    /// Decides whether this sink should deliver `notification` now, and
+    /// if so records it for debouncing.
+    fn admit(&mut self, notification: &Notification, local: NaiveTime, now: Instant) -> bool {
+        if !self.sink.accepts(notification.kind) {
+            return false;
+        }
+        if self.quiet_hours.is_some_and(|q| q.contains(local)) {
+            return false;
+        }
+        let key = (notification.session_id.clone(), notification.kind);
+        if self
+            .last_sent
+            .get(&key)
+            .is_some_and(|sent| now.saturating_duration_since(*sent) < self.debounce)
+        {
+            return false;
+        }
+        self.last_sent.insert(key, now);
+        true
+    }
+
+    fn forget(&mut self, session_id: &SessionId) {
+        self.last_sent.retain(|(id, _), _| id != session_id);
+    }
+
+    fn rename(&mut self, old_id: &SessionId, new_id: &SessionId) {
+        let renamed: Vec<_> = self
+            .last_sent
+            .keys()
+            .filter(|(id, _)| id == old_id)
+            .map(|(_, kind)| *kind)
+            .collect();
+        for kind in renamed {
+            if let Some(sent) = self.last_sent.remove(&(old_id.clone(), kind)) {
+                self.last_sent.insert((new_id.clone(), kind), sent);
+            }
+        }
+    }
+}
+
+/// Routes notifications to the sinks that should receive them.
+pub struct Notifier {
+    sinks: Vec<SinkState>,
+}
+
+impl Notifier {
+    /// Creates a notifier for the sinks in `config`.
+    pub fn new(config: &NotifyConfig) -> Self {
+        let sinks = config
+            .sinks
+            .iter()
+            .map(|sink| SinkState {
+                debounce: Duration::from_secs(config.debounce_secs(sink)),
+                quiet_hours: config.quiet_hours(sink),
+                sink: sink.clone(),
+                last_sent: HashMap::new(),
+            })
+            .collect();
+        Self { sinks }
+    }
+
+    /// Returns the sinks that should deliver `notification` at local time
+    /// `local`.
+    pub fn route(
+        &mut self,
+        notification: &Notification,
+        local: NaiveTime,
+        now: Instant,
+    ) -> Vec<SinkKind> {
+        self.sinks
+            .iter_mut()
+            .filter_map(|state| {
+                state
+                    .admit(notification, local, now)
+                    .then(|| state.sink.kind.clone())
+            })
+            .collect()
+    }
+
+    /// Drops debounce state for a session that has left the registry.
+    pub fn forget(&mut self, session_id: &SessionId) {
+        for state in &mut self.sinks {
+            state.forget(session_id);
+        }
+    }
+
+    /// Carries debounce state over to a renamed session.
+    pub fn rename(&mut self, old_id: &SessionId, new_id: &SessionId) {
+        for state in &mut self.sinks {
+            state.rename(old_id, new_id);
+        }
+    }
+}
+
+/// Spawns the notifier task.
+///
+/// Each delivery runs on its own task so a slow sink doesn't hold up
+/// detection of the next transition.
+pub fn spawn_notifier(
+    registry: RegistryHandle,
+    config: NotifyConfig,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let mut events = registry.subscribe();
+    tokio::spawn(async move {
+        info!(sinks = config.sinks.len(), "Notifier started");
+        let mut watcher = AttentionWatcher::new();
+        let mut notifier = Notifier::new(&config);
+
+        loop {
+            let event = tokio::select! {
+                biased;
+
+                _ = cancel_token.cancelled() => {
+                    debug!("Notifier shutting down");
+                    break;
+                }
+
+                result = events.recv() => match result {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(skipped = n, "Notifier lagged, skipped events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+
+            let notifications = watcher.observe(&event);
+            for notification in notifications {
+                let local = Local::now().time();
+                for sink in notifier.route(&notification, local, Instant::now()) {
+                    let notification = notification.clone();
+                    tokio::spawn(async move {
+                        match deliver(&sink, &notification).await {
+                            Ok(()) => debug!(
+                                sink = sink.name(),
+                                event = %notification.kind,
+                                session_id = %notification.session_id,
+                                "Notification sent"
+                            ),
+                            Err(e) => warn!(
+                                sink = sink.name(),
+                                event = %notification.kind,
+                                error = %e,
+                                "Notification failed"
+                            ),
+                        }
+                    });
+                }
+            }
+
+            if let SessionEvent::Removed { session_id, reason } = &event {
+                match reason.replaced_by() {
+                    Some(new_id) => notifier.rename(session_id, new_id),
+                    None => notifier.forget(session_id),
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atm_core::{AgentType, Model, SessionDomain};
+
+    fn view(status: SessionStatus, detail: Option<&str>) -> SessionView {
+        let domain = SessionDomain::new(
+            SessionId::new("s1"),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-opus-4-6"),
+        );
+        let mut view = SessionView::from_domain(&domain);
+        view.status = status;
+        view.activity_detail = detail.map(str::to_string);
+        view
+    }
+
+    fn updated(view: SessionView) -> SessionEvent {
+        SessionEvent::Updated {
+            session: Box::new(view),
+        }
+    }
+
+    fn kinds(notifications: &[Notification]) -> Vec<AttentionKind> {
+        notifications.iter().map(|n| n.kind).collect()
+    }
+
+    #[test]
+    fn test_watcher_fires_on_transitions_only() {
+        let mut watcher = AttentionWatcher::new();
+
+        assert!(watcher
+            .observe(&updated(view(SessionStatus::Working, None)))
+            .is_empty());
+        let fired = watcher.observe(&updated(view(
+            SessionStatus::AttentionNeeded,
+            Some("Permission"),
+        )));
+        assert_eq!(kinds(&fired), vec![AttentionKind::PermissionPrompt]);
+        assert_eq!(fired[0].detail.as_deref(), Some("Permission"));
+
+        // Still waiting on the same prompt: nothing new.
+        assert!(watcher
+            .observe(&updated(view(
+                SessionStatus::AttentionNeeded,
+                Some("Permission")
+            )))
+            .is_empty());
+        // Different kind of prompt while still waiting.
+        assert_eq!(
+            kinds(&watcher.observe(&updated(view(
+                SessionStatus::AttentionNeeded,
+                Some("MCP Input")
+            )))),
+            vec![AttentionKind::Elicitation]
+        );
+
+        watcher.observe(&updated(view(SessionStatus::Working, None)));
+        assert_eq!(
+            kinds(&watcher.observe(&updated(view(SessionStatus::Idle, None)))),
+            vec![AttentionKind::IdlePrompt]
+        );
+    }
+
+    #[test]
+    fn test_watcher_context_critical_and_death() {
+        let mut watcher = AttentionWatcher::new();
+        let mut critical = view(SessionStatus::Working, None);
+        critical.context_critical = true;
+
+        assert_eq!(
+            kinds(&watcher.observe(&updated(critical.clone()))),
+            vec![AttentionKind::ContextCritical]
+        );
+        assert!(watcher.observe(&updated(critical)).is_empty());
+
+        assert_eq!(
+            kinds(&watcher.observe(&SessionEvent::Removed {
+                session_id: SessionId::new("s1"),
+                reason: RemovalReason::ProcessDied,
+            })),
+            vec![AttentionKind::SessionDied]
+        );
+        // Unknown or normally-ended sessions don't notify.
+        assert!(watcher
+            .observe(&SessionEvent::Removed {
+                session_id: SessionId::new("s1"),
+                reason: RemovalReason::ProcessDied,
+            })
+            .is_empty());
+    }
+
+    #[test]
+    fn test_watcher_follows_renamed_session() {
+        let mut watcher = AttentionWatcher::new();
+        let mut waiting = view(SessionStatus::AttentionNeeded, Some("Permission"));
+        waiting.context_critical = true;
+        assert_eq!(
+            kinds(&watcher.observe(&updated(waiting.clone()))),
+            vec![
+                AttentionKind::PermissionPrompt,
+                AttentionKind::ContextCritical
+            ]
+        );
+
+        assert!(watcher
+            .observe(&SessionEvent::Removed {
+                session_id: SessionId::new("s1"),
+                reason: RemovalReason::Upgraded {
+                    new_id: SessionId::new("s2"),
+                },
+            })
+            .is_empty());
+
+        // Same state under the new id: nothing new to report
+        waiting.id = SessionId::new("s2");
+        assert!(watcher.observe(&updated(waiting)).is_empty());
+        assert!(watcher
+            .observe(&SessionEvent::Removed {
+                session_id: SessionId::new("s1"),
+                reason: RemovalReason::ProcessDied,
+            })
+            .is_empty());
+    }
+
+    #[test]
+    fn test_notifier_debounce_quiet_hours_and_filters() {
+        let config = NotifyConfig {
+            debounce_secs: Some(60),
+            quiet_hours: Some("22:00-07:00".parse().unwrap()),
+            sinks: vec![
+                SinkConfig {
+                    kind: SinkKind::Desktop,
+                    events: vec![AttentionKind::PermissionPrompt],
+                    debounce_secs: None,
+                    quiet_hours: None,
+                },
+                SinkConfig {
+                    kind: SinkKind::Command {
+                        command: "true".to_string(),
+                    },
+                    events: Vec::new(),
+                    debounce_secs: Some(0),
+                    quiet_hours: Some("00:00-00:01".parse().unwrap()),
+                },
+            ],
+        };
+        let mut notifier = Notifier::new(&config);
+        let noon = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+        let night = NaiveTime::from_hms_opt(23, 0, 0).unwrap();
+        let t0 = Instant::now();
+        let permission = Notification::new(
+            AttentionKind::PermissionPrompt,
+            &view(SessionStatus::AttentionNeeded, None),
+        );
+        let idle = Notification::new(AttentionKind::IdlePrompt, &view(SessionStatus::Idle, None));
+
+        assert_eq!(notifier.route(&permission, noon, t0).len(), 2);
+        // Desktop is debounced; the command sink has no debounce.
+        let again = notifier.route(&permission, noon, t0 + Duration::from_secs(30));
+        assert_eq!(again.len(), 1);
+        assert!(matches!(again[0], SinkKind::Command { .. }));
+        assert_eq!(
+            notifier
+                .route(&permission, noon, t0 + Duration::from_secs(61))
+                .len(),
+            2
+        );
+        // Desktop only wants permission prompts.
+        assert_eq!(notifier.route(&idle, noon, t0).len(), 1);
+        // Desktop inherits the global quiet hours; the command sink has its own.
+        assert_eq!(
+            notifier
+                .route(&permission, night, t0 + Duration::from_secs(200))
+                .len(),
+            1
+        );
+    }
+}
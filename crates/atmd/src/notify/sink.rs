@@ -0,0 +1,297 @@
+//! Delivery of a notification to one sink.
+//!
+//! Every sink shells out (`notify-send`, `sh -c`, `curl`) under a timeout,
+//! so a hung endpoint or command can't pile up work in the daemon.
+
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::config::SinkKind;
+use super::Notification;
+
+/// How long a sink gets before it is abandoned.
+pub const SINK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a sink failed to deliver.
+#[derive(Debug, Error)]
+pub enum SinkError {
+    /// The program could not be started (usually: not installed).
+    #[error("failed to run {program}: {source}")]
+    Spawn {
+        /// Program that failed to start
+        program: &'static str,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The program ran but reported failure.
+    #[error("{program} exited with {status}")]
+    Failed {
+        /// Program that failed
+        program: &'static str,
+        /// Exit status as reported by the OS
+        status: std::process::ExitStatus,
+    },
+
+    /// The program did not finish within `SINK_TIMEOUT`.
+    #[error("{program} timed out")]
+    TimedOut {
+        /// Program that was abandoned
+        program: &'static str,
+    },
+}
+
+/// Delivers `notification` through `sink`.
+pub async fn deliver(sink: &SinkKind, notification: &Notification) -> Result<(), SinkError> {
+    match sink {
+        SinkKind::Desktop => {
+            let mut cmd = Command::new("notify-send");
+            cmd.args(notify_send_args(notification));
+            run("notify-send", cmd, None).await
+        }
+        SinkKind::Command { command } => {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(command);
+            for (name, value) in notification.env() {
+                cmd.env(name, value);
+            }
+            run("sh", cmd, None).await
+        }
+        SinkKind::Webhook {
+            url,
+            template,
+            headers,
+        } => {
+            let body = match template {
+                Some(template) => render_template(template, notification),
+                None => serde_json::to_string(notification).unwrap_or_default(),
+            };
+            // Headers (often auth tokens), the URL and the body go to curl
+            // as a config file on stdin: argv is readable by any local user.
+            let mut cmd = Command::new("curl");
+            cmd.args(["--silent", "--show-error", "--fail", "--max-time"])
+                .arg(SINK_TIMEOUT.as_secs().to_string())
+                .args(["--config", "-"]);
+            run("curl", cmd, Some(curl_config(url, headers, &body))).await
+        }
+    }
+}
+
+/// Runs `cmd` to completion under `SINK_TIMEOUT`, feeding it `stdin`.
+async fn run(
+    program: &'static str,
+    mut cmd: Command,
+    stdin: Option<String>,
+) -> Result<(), SinkError> {
+    cmd.stdin(if stdin.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    })
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .kill_on_drop(true);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|source| SinkError::Spawn { program, source })?;
+
+    let finished = async {
+        if let (Some(body), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            // A write error surfaces as the program's own failure below.
+            let _ = pipe.write_all(body.as_bytes()).await;
+        }
+        child.wait().await
+    };
+
+    match tokio::time::timeout(SINK_TIMEOUT, finished).await {
+        Ok(Ok(status)) if status.success() => Ok(()),
+        Ok(Ok(status)) => Err(SinkError::Failed { program, status }),
+        Ok(Err(source)) => Err(SinkError::Spawn { program, source }),
+        Err(_) => Err(SinkError::TimedOut { program }),
+    }
+}
+
+/// Arguments for `notify-send`. Title and message come after `--`, so
+/// session text starting with `-` isn't read as an option.
+fn notify_send_args(notification: &Notification) -> Vec<String> {
+    vec![
+        "--app-name=ATM".to_string(),
+        format!("--urgency={}", notification.kind.urgency()),
+        "--".to_string(),
+        notification.title.clone(),
+        notification.message.clone(),
+    ]
+}
+
+/// Builds the curl config for a JSON POST of `body` to `url`.
+///
+/// `data-raw` rather than `data-binary`, so a body starting with `@` is
+/// sent as is instead of naming a file to upload.
+fn curl_config(url: &str, headers: &BTreeMap<String, String>, body: &str) -> String {
+    let mut config = String::from("header = \"Content-Type: application/json\"\n");
+    for (name, value) in headers {
+        config.push_str(&format!(
+            "header = \"{}\"\n",
+            curl_quote(&format!("{name}: {value}"))
+        ));
+    }
+    config.push_str(&format!("url = \"{}\"\n", curl_quote(url)));
+    config.push_str(&format!("data-raw = \"{}\"\n", curl_quote(body)));
+    config
+}
+
+/// Escapes `value` for a double-quoted curl config string.
+fn curl_quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Fills `{{field}}` placeholders in a webhook template.
+///
+/// Values are JSON-escaped without surrounding quotes, so placeholders
+/// belong inside string literals: `{"text": "{{title}}: {{message}}"}`.
+/// Unknown placeholders are left as they are.
+pub fn render_template(template: &str, notification: &Notification) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest.get(open..).and_then(|s| s.find("}}")) else {
+            break;
+        };
+        let placeholder = rest.get(open..open + close + 2).unwrap_or("");
+        let field = rest.get(open + 2..open + close).unwrap_or("").trim();
+
+        out.push_str(rest.get(..open).unwrap_or(""));
+        match notification.field(field) {
+            Some(value) => out.push_str(&json_escape(&value)),
+            None => out.push_str(placeholder),
+        }
+        rest = rest.get(open + close + 2..).unwrap_or("");
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Escapes `value` for use inside a JSON string literal.
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or("")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::AttentionKind;
+    use atm_core::{AgentType, Model, SessionDomain, SessionId, SessionView};
+
+    fn notification() -> Notification {
+        let mut domain = SessionDomain::new(
+            SessionId::new("abcdef12-3456"),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-opus-4-6"),
+        );
+        domain.project_root = Some("/src/\"quoted\"".to_string());
+        Notification::new(
+            AttentionKind::PermissionPrompt,
+            &SessionView::from_domain(&domain),
+        )
+    }
+
+    #[test]
+    fn test_render_template_escapes_values() {
+        let n = notification();
+        let body = render_template(
+            r#"{"text": "{{ title }} in {{project}}", "id": "{{session}}", "x": "{{nope}}"}"#,
+            &n,
+        );
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            value["text"],
+            format!("{} in /src/\"quoted\"", n.title).as_str()
+        );
+        assert_eq!(value["id"], "abcdef12");
+        assert_eq!(value["x"], "{{nope}}");
+    }
+
+    #[test]
+    fn test_curl_config_quotes_headers_url_and_body() {
+        let headers = BTreeMap::from([("Authorization".to_string(), "Bearer s3cr\"t".to_string())]);
+        let config = curl_config(
+            "https://hooks.example/x?token=abc",
+            &headers,
+            "{\"text\": \"a\\\\b\"}\n",
+        );
+        assert_eq!(
+            config,
+            concat!(
+                "header = \"Content-Type: application/json\"\n",
+                "header = \"Authorization: Bearer s3cr\\\"t\"\n",
+                "url = \"https://hooks.example/x?token=abc\"\n",
+                "data-raw = \"{\\\"text\\\": \\\"a\\\\\\\\b\\\"}\\n\"\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_notify_send_args_end_options_before_text() {
+        let mut n = notification();
+        n.message = "--help".to_string();
+        let args = notify_send_args(&n);
+        assert_eq!(args[2], "--");
+        assert_eq!(args[3..], [n.title.clone(), "--help".to_string()]);
+    }
+
+    #[test]
+    fn test_render_template_unclosed_placeholder() {
+        assert_eq!(render_template("a {{title", &notification()), "a {{title");
+    }
+
+    #[tokio::test]
+    async fn test_command_sink_gets_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let sink = SinkKind::Command {
+            command: format!(
+                "printf '%s %s' \"$ATM_EVENT\" \"$ATM_SESSION\" > {}",
+                out.display()
+            ),
+        };
+
+        deliver(&sink, &notification()).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "permission_prompt abcdef12"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_command_sink_failure_is_reported() {
+        let sink = SinkKind::Command {
+            command: "exit 3".to_string(),
+        };
+        assert!(matches!(
+            deliver(&sink, &notification()).await,
+            Err(SinkError::Failed { program: "sh", .. })
+        ));
+    }
+}
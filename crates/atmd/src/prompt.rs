@@ -0,0 +1,185 @@
+//! Pane fallback for prompts the harness didn't describe.
+//!
+//! Hook payloads describe some prompts (Claude `PermissionRequest`,
+//! `AskUserQuestion`, pi `ui.select`). For the rest, a session enters
+//! `AttentionNeeded` knowing only that it waits. This task captures the
+//! waiting session's tmux pane once per wait, parses it with
+//! [`PromptState::from_pane`] and stores the result on the session.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Capture and parse failures are logged and leave the prompt unset
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use atm_core::{PromptState, SessionId, SessionStatus};
+use atm_tmux::TmuxClient;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::registry::{RegistryHandle, SessionEvent};
+
+/// Pause before capturing, so the prompt has rendered in the pane.
+const RENDER_DELAY: Duration = Duration::from_millis(250);
+
+/// Spawns the task that reads undescribed prompts from agent panes.
+pub fn spawn_prompt_reader(
+    registry: RegistryHandle,
+    tmux: Arc<dyn TmuxClient>,
+    cancel_token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    let mut events = registry.subscribe();
+    tokio::spawn(async move {
+        info!("Prompt reader started");
+        // Sessions whose current wait has already been read
+        let mut read: HashSet<SessionId> = HashSet::new();
+        loop {
+            let event = tokio::select! {
+                biased;
+
+                _ = cancel_token.cancelled() => {
+                    debug!("Prompt reader shutting down");
+                    break;
+                }
+
+                result = events.recv() => match result {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!(skipped = n, "Prompt reader lagged, skipped events");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+
+            match event {
+                SessionEvent::Updated { session } => {
+                    if session.status != SessionStatus::AttentionNeeded {
+                        read.remove(&session.id);
+                        continue;
+                    }
+                    if session.prompt.is_some() || !read.insert(session.id.clone()) {
+                        continue;
+                    }
+                    if let Some(pane) = session.tmux_pane.clone() {
+                        tokio::spawn(read_prompt(
+                            registry.clone(),
+                            Arc::clone(&tmux),
+                            session.id.clone(),
+                            pane,
+                        ));
+                    }
+                }
+                SessionEvent::Removed { session_id, .. } => {
+                    read.remove(&session_id);
+                }
+                _ => {}
+            }
+        }
+    })
+}
+
+/// Captures `pane` and stores the prompt found there on `session_id`.
+async fn read_prompt(
+    registry: RegistryHandle,
+    tmux: Arc<dyn TmuxClient>,
+    session_id: SessionId,
+    pane: String,
+) {
+    tokio::time::sleep(RENDER_DELAY).await;
+    let lines = match tmux.capture_pane(&pane).await {
+        Ok(lines) => lines,
+        Err(e) => {
+            debug!(session_id = %session_id, pane = %pane, error = %e, "Failed to capture pane for prompt");
+            return;
+        }
+    };
+    let Some(prompt) = PromptState::from_pane(&lines) else {
+        debug!(session_id = %session_id, pane = %pane, "No prompt found in pane");
+        return;
+    };
+    if let Err(e) = registry.set_pane_prompt(session_id.clone(), prompt).await {
+        debug!(session_id = %session_id, error = %e, "Failed to store pane prompt");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::spawn_registry;
+    use atm_core::{
+        AgentType, Harness, LifecycleEvent, Model, NeedsInputReason, NotificationKind, PromptKind,
+        PromptSource, SessionDomain, PANE_PARSER_VERSION,
+    };
+    use atm_tmux::MockTmuxClient;
+
+    #[tokio::test]
+    async fn test_reader_fills_prompt_from_pane() {
+        let registry = spawn_registry();
+        let mut domain = SessionDomain::new(
+            SessionId::new("pane-prompt"),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-sonnet-4"),
+        );
+        domain.tmux_pane = Some("%6".to_string());
+        registry.register(domain).await.unwrap();
+
+        let mock = MockTmuxClient::new();
+        mock.set_pane_content(
+            "%6",
+            vec![
+                "Do you want to proceed?".into(),
+                "❯ 1. Yes".into(),
+                "  2. No".into(),
+                "Esc to cancel".into(),
+            ],
+        );
+        let cancel = CancellationToken::new();
+        let _handle = spawn_prompt_reader(registry.clone(), Arc::new(mock), cancel.clone());
+
+        registry
+            .apply_lifecycle_event(
+                SessionId::new("pane-prompt"),
+                LifecycleEvent::NeedsInput {
+                    reason: NeedsInputReason::Notification {
+                        kind: NotificationKind::PermissionPrompt,
+                        label: None,
+                    },
+                    prompt: None,
+                },
+                Harness::ClaudeCode,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let mut prompt = None;
+        for _ in 0..50 {
+            prompt = registry
+                .get_session(SessionId::new("pane-prompt"))
+                .await
+                .and_then(|s| s.prompt);
+            if prompt.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let prompt = prompt.unwrap();
+        assert_eq!(prompt.kind, PromptKind::Permission);
+        assert_eq!(prompt.options.len(), 2);
+        assert_eq!(
+            prompt.source,
+            PromptSource::Pane {
+                parser_version: PANE_PARSER_VERSION
+            }
+        );
+
+        cancel.cancel();
+    }
+}
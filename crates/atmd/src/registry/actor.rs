@@ -14,17 +14,22 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
-use chrono::Utc;
+use chrono::{Local, NaiveDate, Utc};
 use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
 use atm_core::{
-    AgentType, LifecycleEvent, NeedsInputReason, SessionDomain, SessionId, SessionInfrastructure,
-    SessionView,
+    AgentType, LifecycleEvent, NeedsInputReason, NotificationKind, PendingToolCall, PromptState,
+    SessionDomain, SessionId, SessionInfrastructure, SessionStatus, SessionView,
 };
 use atm_protocol::RawStatusLine;
 
 use super::commands::{RegistryCommand, RegistryError, RemovalReason, SessionEvent};
+use super::persist::{self, PersistError, PersistedSession, RegistrySnapshot, SnapshotWriter};
+use crate::budget::{BudgetConfig, BudgetTracker};
+use crate::journal::JournalEntry;
+use crate::ledger::CostLedger;
 
 // ============================================================================
 // Resource Limits (from RESOURCE_LIMITS.md)
@@ -33,6 +38,15 @@ use super::commands::{RegistryCommand, RegistryError, RemovalReason, SessionEven
 /// Maximum number of sessions the registry can hold.
 pub const MAX_SESSIONS: usize = 100;
 
+/// First PID of the synthetic range used for sessions without a real PID.
+///
+/// Real Linux PIDs never reach this value (`pid_max` caps at 2^22).
+const SYNTHETIC_PID_BASE: u32 = 0x8000_0000;
+
+/// Most tool calls tracked as in flight per session; the oldest is
+/// dropped beyond this (its end was lost).
+const MAX_PENDING_TOOL_CALLS: usize = 32;
+
 // ============================================================================
 // Registry Actor
 // ============================================================================
@@ -95,6 +109,21 @@ pub struct RegistryActor {
     /// Uses Vec for deterministic FIFO ordering — when multiple subagents
     /// are pending, the oldest match wins.
     pending_subagents: Vec<(String, PendingSubagent)>,
+
+    /// Writes registry snapshots to the state file (`None` = in-memory only).
+    snapshots: Option<SnapshotWriter>,
+
+    /// Whether session state changed since the last snapshot was taken.
+    dirty: bool,
+
+    /// Sink for lifecycle events destined for the event journal.
+    journal: Option<mpsc::Sender<JournalEntry>>,
+
+    /// Spend tracking against configured cost budgets (`None` = no budgets).
+    budgets: Option<BudgetTracker>,
+
+    /// Last id handed out for a `PermissionRequested`.
+    next_permission_id: u64,
 }
 
 impl RegistryActor {
@@ -114,9 +143,72 @@ impl RegistryActor {
             session_id_to_pid: HashMap::new(),
             event_publisher,
             pending_subagents: Vec::new(),
+            snapshots: None,
+            dirty: false,
+            journal: None,
+            budgets: None,
+            next_permission_id: 0,
         }
     }
 
+    /// Forwards every applied lifecycle event to `journal`.
+    ///
+    /// Sends are non-blocking; if the journal falls behind, entries are
+    /// dropped with a warning rather than stalling the registry.
+    pub fn with_journal(mut self, journal: mpsc::Sender<JournalEntry>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Evaluates `config`'s cost budgets on every status-line update.
+    ///
+    /// With a `ledger`, today's spend starts from what it has booked.
+    pub fn with_budgets(mut self, config: BudgetConfig, ledger: Option<&CostLedger>) -> Self {
+        let mut tracker = BudgetTracker::new(config, Local::now().date_naive());
+        if let Some(ledger) = ledger {
+            tracker.seed(ledger);
+        }
+        self.budgets = Some(tracker);
+        self
+    }
+
+    /// Creates a registry actor backed by a state file.
+    ///
+    /// Any snapshot already at `state_path` is loaded and its live entries
+    /// re-registered (see `restore_snapshot`). A missing, corrupt or
+    /// incompatible file is logged and the actor starts empty.
+    pub fn with_state_path(
+        receiver: mpsc::Receiver<RegistryCommand>,
+        event_publisher: broadcast::Sender<SessionEvent>,
+        state_path: PathBuf,
+    ) -> Self {
+        let mut actor = Self::new(receiver, event_publisher);
+
+        match persist::load_snapshot(&state_path) {
+            Ok(Some(snapshot)) => {
+                let restored = actor.restore_snapshot(snapshot);
+                info!(
+                    restored,
+                    path = %state_path.display(),
+                    "Restored sessions from registry snapshot"
+                );
+            }
+            Ok(None) => {
+                debug!(path = %state_path.display(), "No registry snapshot found");
+            }
+            Err(e) => {
+                warn!(
+                    path = %state_path.display(),
+                    error = %e,
+                    "Ignoring unreadable registry snapshot"
+                );
+            }
+        }
+
+        actor.snapshots = Some(SnapshotWriter::new(state_path));
+        actor
+    }
+
     /// Runs the actor event loop.
     ///
     /// Processes commands until the channel closes (all senders dropped).
@@ -128,6 +220,12 @@ impl RegistryActor {
             self.handle_command(cmd);
         }
 
+        if let Some(write) = self.write_snapshot() {
+            if let Err(e) = finish_snapshot(write).await {
+                warn!(error = %e, "Failed to write final registry snapshot");
+            }
+        }
+
         info!(
             "Registry actor stopped (sessions: {})",
             self.sessions_by_pid.len()
@@ -136,6 +234,21 @@ impl RegistryActor {
 
     /// Dispatches a command to the appropriate handler.
     fn handle_command(&mut self, cmd: RegistryCommand) {
+        // Periodic/read-only commands flag `dirty` themselves (or never do);
+        // everything else may have mutated session state.
+        if !matches!(
+            cmd,
+            RegistryCommand::GetSession { .. }
+                | RegistryCommand::GetOpenPermission { .. }
+                | RegistryCommand::GetAllSessions { .. }
+                | RegistryCommand::CleanupStale
+                | RegistryCommand::RefreshGitInfo
+                | RegistryCommand::PersistSnapshot
+                | RegistryCommand::Flush { .. }
+        ) {
+            self.dirty = true;
+        }
+
         match cmd {
             RegistryCommand::Register {
                 session,
@@ -166,6 +279,22 @@ impl RegistryActor {
                     self.handle_apply_lifecycle_event(session_id, event, harness, pid, tmux_pane);
                 let _ = respond_to.send(result);
             }
+            RegistryCommand::SetPanePrompt {
+                session_id,
+                prompt,
+                respond_to,
+            } => {
+                let result = self.handle_set_pane_prompt(session_id, *prompt);
+                let _ = respond_to.send(result);
+            }
+            RegistryCommand::SetTeam {
+                session_id,
+                team,
+                respond_to,
+            } => {
+                let result = self.handle_set_team(session_id, &team);
+                let _ = respond_to.send(result);
+            }
             RegistryCommand::GetSession {
                 session_id,
                 respond_to,
@@ -173,6 +302,13 @@ impl RegistryActor {
                 let result = self.handle_get_session(&session_id);
                 let _ = respond_to.send(result);
             }
+            RegistryCommand::GetOpenPermission {
+                session_id,
+                respond_to,
+            } => {
+                let result = self.handle_get_open_permission(&session_id);
+                let _ = respond_to.send(result);
+            }
             RegistryCommand::GetAllSessions { respond_to } => {
                 let result = self.handle_get_all_sessions();
                 let _ = respond_to.send(result);
@@ -202,6 +338,31 @@ impl RegistryActor {
                     self.handle_register_discovered(session_id, pid, cwd, tmux_pane, harness);
                 let _ = respond_to.send(result);
             }
+            RegistryCommand::PersistSnapshot => {
+                let retry = self
+                    .snapshots
+                    .as_ref()
+                    .is_some_and(SnapshotWriter::last_write_failed);
+                if self.dirty || retry {
+                    if let Some(write) = self.write_snapshot() {
+                        tokio::spawn(async move {
+                            if let Err(e) = finish_snapshot(write).await {
+                                warn!(error = %e, "Failed to write registry snapshot");
+                            }
+                        });
+                    }
+                }
+            }
+            RegistryCommand::Flush { respond_to } => match self.write_snapshot() {
+                Some(write) => {
+                    tokio::spawn(async move {
+                        let _ = respond_to.send(finish_snapshot(write).await);
+                    });
+                }
+                None => {
+                    let _ = respond_to.send(Ok(()));
+                }
+            },
         }
     }
 
@@ -301,7 +462,7 @@ impl RegistryActor {
     /// Generates a synthetic PID for sessions without a real PID (testing only).
     fn generate_synthetic_pid(&self) -> u32 {
         // Use high PID range unlikely to conflict with real processes
-        let base: u32 = 0x8000_0000;
+        let base = SYNTHETIC_PID_BASE;
         // Find the first unused synthetic PID
         for i in 0..u32::MAX {
             let candidate = base.wrapping_add(i);
@@ -374,6 +535,7 @@ impl RegistryActor {
                 "Re-discovered existing session, refreshed git info (metadata preserved)"
             );
 
+            existing_session.bump_revision();
             let view = SessionView::from_domain(existing_session);
             let _ = self.event_publisher.send(SessionEvent::Updated {
                 session: Box::new(view),
@@ -405,7 +567,7 @@ impl RegistryActor {
         let session = build_session_from_pid(
             session_id.clone(),
             AgentType::GeneralPurpose,
-            Model::Unknown,
+            Model::UNKNOWN,
             harness,
             tmux_pane,
             Some(cwd),
@@ -434,7 +596,8 @@ impl RegistryActor {
         });
 
         // Also publish an initial Updated event so TUI shows it
-        if let Some((session, _)) = self.sessions_by_pid.get(&pid) {
+        if let Some((session, _)) = self.sessions_by_pid.get_mut(&pid) {
+            session.bump_revision();
             let view = SessionView::from_domain(session);
             let _ = self.event_publisher.send(SessionEvent::Updated {
                 session: Box::new(view),
@@ -470,6 +633,9 @@ impl RegistryActor {
 
         self.session_id_to_pid.remove(&old_id);
         self.session_id_to_pid.insert(new_id.clone(), pid);
+        if let Some(budgets) = self.budgets.as_mut() {
+            budgets.rename(&old_id, &new_id);
+        }
 
         info!(
             old_id = %old_id,
@@ -480,7 +646,9 @@ impl RegistryActor {
 
         let _ = self.event_publisher.send(SessionEvent::Removed {
             session_id: old_id,
-            reason: RemovalReason::Upgraded,
+            reason: RemovalReason::Upgraded {
+                new_id: new_id.clone(),
+            },
         });
         let _ = self.event_publisher.send(SessionEvent::Registered {
             session_id: new_id,
@@ -543,10 +711,8 @@ impl RegistryActor {
                     "Session updated from status line (by session_id)"
                 );
 
-                let view = SessionView::from_domain(session);
-                let _ = self.event_publisher.send(SessionEvent::Updated {
-                    session: Box::new(view),
-                });
+                self.check_budgets(pid);
+                self.publish_updated(pid);
             }
             return Ok(());
         }
@@ -607,11 +773,6 @@ impl RegistryActor {
                 cost = %session.cost,
                 "Session updated from status line"
             );
-
-            let view = SessionView::from_domain(session);
-            let _ = self.event_publisher.send(SessionEvent::Updated {
-                session: Box::new(view),
-            });
         } else {
             // Session doesn't exist - create it
             let mut session = match raw_status.to_session_domain() {
@@ -664,18 +825,73 @@ impl RegistryActor {
                 session_id: session_id.clone(),
                 agent_type,
             });
-
-            if let Some((session, _)) = self.sessions_by_pid.get(&pid) {
-                let view = SessionView::from_domain(session);
-                let _ = self.event_publisher.send(SessionEvent::Updated {
-                    session: Box::new(view),
-                });
-            }
         }
 
+        self.check_budgets(pid);
+        self.publish_updated(pid);
+
         Ok(())
     }
 
+    /// Evaluates cost budgets for the session at `pid`.
+    ///
+    /// Each newly crossed limit publishes a `BudgetExceeded` event and
+    /// flags the session `over_budget`; the flag is cleared once no limit
+    /// is breached for the current day. Only the first breach while the
+    /// session is flagged asks for an interrupt, so an agent is never sent
+    /// Ctrl+C twice for the same day.
+    fn check_budgets(&mut self, pid: u32) {
+        self.check_budgets_on(pid, Local::now().date_naive());
+    }
+
+    /// [`Self::check_budgets`] against the local day `today`.
+    fn check_budgets_on(&mut self, pid: u32, today: NaiveDate) {
+        let Some(budgets) = self.budgets.as_mut() else {
+            return;
+        };
+        let Some((session, _)) = self.sessions_by_pid.get_mut(&pid) else {
+            return;
+        };
+
+        let breaches = budgets.observe(session, today);
+        let was_over = session.over_budget;
+        session.over_budget = budgets.is_over(&session.id);
+        if breaches.is_empty() {
+            return;
+        }
+
+        let mut interrupt = budgets.interrupts() && !was_over;
+        for breach in breaches {
+            warn!(
+                session_id = %session.id,
+                scope = %breach.scope,
+                limit = %breach.limit,
+                spent = %breach.spent,
+                interrupt,
+                "Cost budget exceeded"
+            );
+            let _ = self.event_publisher.send(SessionEvent::BudgetExceeded {
+                session_id: session.id.clone(),
+                scope: breach.scope,
+                limit: breach.limit,
+                spent: breach.spent,
+                interrupt,
+            });
+            interrupt = false;
+        }
+    }
+
+    /// Bumps the revision of the session at `pid` and publishes its view.
+    fn publish_updated(&mut self, pid: u32) {
+        if let Some((session, _)) = self.sessions_by_pid.get_mut(&pid) {
+            session.bump_revision();
+            let view = SessionView::from_domain(session);
+            let _ = self.event_publisher.send(SessionEvent::Updated {
+                session: Box::new(view),
+            });
+        }
+    }
+
     /// Handles applying a vendor-neutral lifecycle event to a session.
     ///
     /// With PID as primary key, we can look up by PID when available.
@@ -691,6 +907,13 @@ impl RegistryActor {
         pid: Option<u32>,
         tmux_pane: Option<String>,
     ) -> Result<(), RegistryError> {
+        if let Some(ref journal) = self.journal {
+            let entry = JournalEntry::from_lifecycle(session_id.clone(), harness, &event);
+            if journal.try_send(entry).is_err() {
+                warn!(session_id = %session_id, "Event journal full or closed, dropping entry");
+            }
+        }
+
         // Subagent correlation: ChildSessionStart records, ChildSessionEnd removes.
         match &event {
             LifecycleEvent::ChildSessionStart {
@@ -803,7 +1026,7 @@ impl RegistryActor {
                         let session = build_session_from_pid(
                             session_id.clone(),
                             AgentType::GeneralPurpose,
-                            Model::Unknown,
+                            Model::UNKNOWN,
                             harness,
                             tmux_pane.clone(),
                             proc_cwd,
@@ -818,18 +1041,39 @@ impl RegistryActor {
                         if let Some((session, infra)) = self.sessions_by_pid.get_mut(&p) {
                             session.apply_lifecycle_event(&event);
                             session.set_first_prompt_from_event(&event);
+                            let permission = permission_request(&event, infra);
+                            let request_id = update_permission_ticket(
+                                infra,
+                                session.status,
+                                false,
+                                permission.as_ref(),
+                                &mut self.next_permission_id,
+                            );
+                            track_tool_call(&event, infra);
                             if let Some(name) = tool_name.as_deref() {
                                 infra.record_tool_use(name, None);
                             }
 
+                            session.bump_revision();
                             let view = SessionView::from_domain(session);
                             let _ = self.event_publisher.send(SessionEvent::Registered {
                                 session_id: session_id.clone(),
                                 agent_type: session.agent_type.clone(),
                             });
                             let _ = self.event_publisher.send(SessionEvent::Updated {
-                                session: Box::new(view),
+                                session: Box::new(view.clone()),
                             });
+                            if let (Some(call), Some(request_id)) = (permission, request_id) {
+                                let _ =
+                                    self.event_publisher
+                                        .send(SessionEvent::PermissionRequested {
+                                            session: Box::new(view),
+                                            request_id,
+                                            tool: call.tool,
+                                            tool_use_id: call.tool_use_id,
+                                            input: call.input,
+                                        });
+                            }
                         }
 
                         self.try_correlate_subagent(&session_id, p);
@@ -847,6 +1091,9 @@ impl RegistryActor {
             }
         };
 
+        // Claude announces one dialog twice (`PermissionRequest`, then a
+        // permission notification); only entering the wait is a request.
+        let was_waiting = session.status == SessionStatus::AttentionNeeded;
         session.apply_lifecycle_event(&event);
         session.set_first_prompt_from_event(&event);
 
@@ -861,15 +1108,78 @@ impl RegistryActor {
             "Lifecycle event applied"
         );
 
+        let permission = permission_request(&event, infra).filter(|_| !was_waiting);
+        let request_id = update_permission_ticket(
+            infra,
+            session.status,
+            was_waiting,
+            permission.as_ref(),
+            &mut self.next_permission_id,
+        );
+        track_tool_call(&event, infra);
         if let Some(name) = tool_name.as_deref() {
             infra.record_tool_use(name, None);
         }
 
+        session.bump_revision();
         let view = SessionView::from_domain(session);
         let _ = self.event_publisher.send(SessionEvent::Updated {
-            session: Box::new(view),
+            session: Box::new(view.clone()),
         });
+        if let (Some(call), Some(request_id)) = (permission, request_id) {
+            let _ = self
+                .event_publisher
+                .send(SessionEvent::PermissionRequested {
+                    session: Box::new(view),
+                    request_id,
+                    tool: call.tool,
+                    tool_use_id: call.tool_use_id,
+                    input: call.input,
+                });
+        }
+
+        Ok(())
+    }
+
+    /// Handles storing a prompt parsed from a session's pane.
+    fn handle_set_pane_prompt(
+        &mut self,
+        session_id: SessionId,
+        prompt: PromptState,
+    ) -> Result<(), RegistryError> {
+        let Some((session, _)) = self
+            .session_id_to_pid
+            .get(&session_id)
+            .and_then(|pid| self.sessions_by_pid.get_mut(pid))
+        else {
+            return Err(RegistryError::SessionNotFound(session_id));
+        };
+
+        if session.set_pane_prompt(prompt) {
+            session.bump_revision();
+            let _ = self.event_publisher.send(SessionEvent::Updated {
+                session: Box::new(SessionView::from_domain(session)),
+            });
+        }
+        Ok(())
+    }
+
+    /// Handles recording the team a session was spawned into.
+    fn handle_set_team(&mut self, session_id: SessionId, team: &str) -> Result<(), RegistryError> {
+        let Some((session, _)) = self
+            .session_id_to_pid
+            .get(&session_id)
+            .and_then(|pid| self.sessions_by_pid.get_mut(pid))
+        else {
+            return Err(RegistryError::SessionNotFound(session_id));
+        };
 
+        if session.set_team(team) {
+            session.bump_revision();
+            let _ = self.event_publisher.send(SessionEvent::Updated {
+                session: Box::new(SessionView::from_domain(session)),
+            });
+        }
         Ok(())
     }
 
@@ -881,6 +1191,17 @@ impl RegistryActor {
             .map(|(session, _)| SessionView::from_domain(session))
     }
 
+    /// Handles looking up the permission prompt a session is waiting on.
+    fn handle_get_open_permission(&self, session_id: &SessionId) -> Option<u64> {
+        let (session, infra) = self
+            .session_id_to_pid
+            .get(session_id)
+            .and_then(|pid| self.sessions_by_pid.get(pid))?;
+        infra
+            .open_permission
+            .filter(|_| session.status == SessionStatus::AttentionNeeded)
+    }
+
     /// Handles getting all sessions.
     fn handle_get_all_sessions(&self) -> Vec<SessionView> {
         self.sessions_by_pid
@@ -901,6 +1222,9 @@ impl RegistryActor {
         };
 
         self.sessions_by_pid.remove(&pid);
+        if let Some(budgets) = self.budgets.as_mut() {
+            budgets.forget(&session_id);
+        }
 
         info!(
             session_id = %session_id,
@@ -935,6 +1259,9 @@ impl RegistryActor {
 
         let session_id = session.id.clone();
         self.session_id_to_pid.remove(&session_id);
+        if let Some(budgets) = self.budgets.as_mut() {
+            budgets.forget(&session_id);
+        }
 
         info!(
             session_id = %session_id,
@@ -1035,6 +1362,7 @@ impl RegistryActor {
         }
 
         info!(count = to_remove.len(), "Cleaning up dead-process sessions");
+        self.dirty = true;
 
         // Remove each session
         for (pid, session_id) in to_remove {
@@ -1099,7 +1427,9 @@ impl RegistryActor {
                 session.worktree_path = new_wt_path;
                 session.worktree_branch = new_wt_branch;
                 updated_count += 1;
+                self.dirty = true;
 
+                session.bump_revision();
                 let view = SessionView::from_domain(session);
                 let _ = self.event_publisher.send(SessionEvent::Updated {
                     session: Box::new(view),
@@ -1112,6 +1442,82 @@ impl RegistryActor {
         }
     }
 
+    // ========================================================================
+    // Persistence
+    // ========================================================================
+
+    /// Re-registers sessions from a snapshot, returning how many were kept.
+    ///
+    /// An entry is only restored if its process is provably the same one
+    /// that was tracked before the restart: a real (non-synthetic) PID with
+    /// a recorded start time that still matches `/proc/{pid}/stat`. Anything
+    /// else is dropped — hooks or discovery will re-create it if it's live.
+    fn restore_snapshot(&mut self, snapshot: RegistrySnapshot) -> usize {
+        let mut restored = 0;
+
+        for entry in snapshot.sessions {
+            let PersistedSession { pid, domain, infra } = entry;
+
+            if self.sessions_by_pid.len() >= MAX_SESSIONS {
+                warn!(max = MAX_SESSIONS, "Registry full while restoring snapshot");
+                break;
+            }
+
+            let verifiable = pid != 0
+                && pid < SYNTHETIC_PID_BASE
+                && infra.pid == Some(pid)
+                && infra.process_start_time.is_some();
+            if !verifiable || !infra.is_process_alive() {
+                debug!(
+                    session_id = %domain.id,
+                    pid,
+                    "Dropping snapshot entry: process gone or not verifiable"
+                );
+                continue;
+            }
+
+            if self.sessions_by_pid.contains_key(&pid)
+                || self.session_id_to_pid.contains_key(&domain.id)
+            {
+                debug!(session_id = %domain.id, pid, "Dropping duplicate snapshot entry");
+                continue;
+            }
+
+            self.session_id_to_pid.insert(domain.id.clone(), pid);
+            self.sessions_by_pid.insert(pid, (domain, infra));
+            restored += 1;
+        }
+
+        restored
+    }
+
+    /// Takes a snapshot of the current registry state and hands it to the
+    /// blocking pool for writing, so the actor never waits on the disk.
+    ///
+    /// Returns `None` for in-memory actors. Clears `dirty`; a failed write
+    /// is retried on the next `PersistSnapshot`.
+    fn write_snapshot(&mut self) -> Option<JoinHandle<Result<(), PersistError>>> {
+        let writer = self.snapshots.as_mut()?;
+
+        let sessions = self
+            .sessions_by_pid
+            .iter()
+            .map(|(pid, (domain, infra))| PersistedSession {
+                pid: *pid,
+                domain: domain.clone(),
+                infra: infra.clone(),
+            })
+            .collect();
+
+        debug!(
+            path = %writer.path().display(),
+            sessions = self.sessions_by_pid.len(),
+            "Writing registry snapshot"
+        );
+        self.dirty = false;
+        Some(writer.save(RegistrySnapshot::new(sessions)))
+    }
+
     // ========================================================================
     // Accessors (for testing)
     // ========================================================================
@@ -1129,6 +1535,14 @@ impl RegistryActor {
     }
 }
 
+/// Waits for a snapshot write started by `write_snapshot`.
+async fn finish_snapshot(write: JoinHandle<Result<(), PersistError>>) -> Result<(), RegistryError> {
+    match write.await {
+        Ok(result) => result.map_err(|e| RegistryError::Persistence(e.to_string())),
+        Err(e) => Err(RegistryError::Persistence(e.to_string())),
+    }
+}
+
 /// Builds a fresh `SessionDomain` for a newly-observed PID and resolves
 /// cwd-derived fields (project root, worktree info, working directory).
 ///
@@ -1171,7 +1585,7 @@ fn tool_name_from_event(event: &LifecycleEvent) -> Option<String> {
         LifecycleEvent::ToolCallStart { name, .. } | LifecycleEvent::ToolCallEnd { name, .. } => {
             Some(name.as_str().to_string())
         }
-        LifecycleEvent::NeedsInput { reason } => match reason {
+        LifecycleEvent::NeedsInput { reason, .. } => match reason {
             NeedsInputReason::InteractiveTool { tool }
             | NeedsInputReason::PermissionGate { tool } => Some(tool.as_str().to_string()),
             NeedsInputReason::Notification { .. } => None,
@@ -1180,6 +1594,116 @@ fn tool_name_from_event(event: &LifecycleEvent) -> Option<String> {
     }
 }
 
+/// The tool call a lifecycle event asks permission for, if any.
+///
+/// Pi gates name the tool directly. Notification-style prompts (Claude,
+/// Codex, pi-atm) don't, so they're attributed to the tool call in
+/// flight. With several candidate calls in flight the prompt can't be
+/// attributed, and no request is made: answering it by rule could
+/// approve a call the rule never saw.
+fn permission_request(
+    event: &LifecycleEvent,
+    infra: &SessionInfrastructure,
+) -> Option<PendingToolCall> {
+    let LifecycleEvent::NeedsInput { reason, .. } = event else {
+        return None;
+    };
+    let pending = &infra.pending_tool_calls;
+    match reason {
+        NeedsInputReason::PermissionGate { tool } => {
+            let mut calls = pending.iter().filter(|call| call.tool == *tool);
+            match (calls.next(), calls.next()) {
+                (None, _) => Some(PendingToolCall {
+                    tool: tool.clone(),
+                    tool_use_id: None,
+                    input: None,
+                }),
+                (Some(call), None) => Some(call.clone()),
+                (Some(_), Some(_)) => None,
+            }
+        }
+        NeedsInputReason::Notification {
+            kind: NotificationKind::PermissionPrompt,
+            ..
+        } => match pending.as_slice() {
+            [call] => Some(call.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Keeps `pending_tool_calls` in step with tool calls starting and ending.
+///
+/// Calls are matched by `tool_use_id`; harnesses that don't report one
+/// end the oldest open call of the same tool. A new turn clears the list,
+/// since calls that were interrupted never report an end.
+fn track_tool_call(event: &LifecycleEvent, infra: &mut SessionInfrastructure) {
+    let pending = &mut infra.pending_tool_calls;
+    match event {
+        LifecycleEvent::ToolCallStart {
+            name,
+            tool_use_id,
+            input,
+        } => {
+            if let Some(id) = tool_use_id {
+                pending.retain(|call| call.tool_use_id.as_ref() != Some(id));
+            }
+            if pending.len() >= MAX_PENDING_TOOL_CALLS {
+                pending.remove(0);
+            }
+            pending.push(PendingToolCall {
+                tool: name.clone(),
+                tool_use_id: tool_use_id.clone(),
+                input: input.clone(),
+            });
+        }
+        LifecycleEvent::ToolCallEnd {
+            name, tool_use_id, ..
+        } => {
+            let position = match tool_use_id {
+                Some(id) => pending
+                    .iter()
+                    .position(|call| call.tool_use_id.as_ref() == Some(id)),
+                None => pending
+                    .iter()
+                    .position(|call| call.tool_use_id.is_none() && call.tool == *name),
+            };
+            if let Some(index) = position {
+                pending.remove(index);
+            }
+        }
+        LifecycleEvent::PromptSubmit { .. } | LifecycleEvent::WorkingEnd => pending.clear(),
+        _ => {}
+    }
+}
+
+/// Opens or closes the session's permission ticket after an event.
+///
+/// A session that stops waiting loses its ticket. One that starts waiting
+/// gets a fresh id from `next_id` if the prompt is answerable by rule
+/// (`permission`), and none otherwise. Returns the new id.
+fn update_permission_ticket(
+    infra: &mut SessionInfrastructure,
+    status: SessionStatus,
+    was_waiting: bool,
+    permission: Option<&PendingToolCall>,
+    next_id: &mut u64,
+) -> Option<u64> {
+    if status != SessionStatus::AttentionNeeded {
+        infra.open_permission = None;
+        return None;
+    }
+    if was_waiting {
+        return None;
+    }
+    infra.open_permission = permission.map(|_| {
+        *next_id += 1;
+        *next_id
+    });
+    infra.open_permission
+}
+
 /// Check if `pid` is a descendant of `ancestor_pid` by walking /proc.
 ///
 /// Walks up the process tree via parent PID lookups, with a max depth
@@ -1204,14 +1728,14 @@ fn is_descendant_of(pid: u32, ancestor_pid: u32) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use atm_core::{AgentType, Model, Tool};
+    use atm_core::{AgentType, Model, Money, Tool};
     use tokio::sync::oneshot;
 
     fn create_test_session(id: &str) -> SessionDomain {
         SessionDomain::new(
             SessionId::new(id),
             AgentType::GeneralPurpose,
-            Model::Sonnet4,
+            Model::from_id("claude-sonnet-4"),
         )
     }
 
@@ -1440,6 +1964,45 @@ mod tests {
         assert_eq!(view.activity_detail, Some("Bash".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_each_update_bumps_revision() {
+        let (_, mut actor, mut event_rx) = create_actor();
+
+        let (tx, _) = oneshot::channel();
+        actor.handle_command(RegistryCommand::Register {
+            session: Box::new(create_test_session("rev-test")),
+            respond_to: tx,
+        });
+
+        let mut revisions = Vec::new();
+        for event in [LifecycleEvent::WorkingStart, LifecycleEvent::WorkingEnd] {
+            let (tx, _) = oneshot::channel();
+            actor.handle_command(RegistryCommand::ApplyLifecycleEvent {
+                session_id: SessionId::new("rev-test"),
+                event,
+                harness: atm_core::Harness::Unknown,
+                pid: None,
+                tmux_pane: None,
+                respond_to: tx,
+            });
+            while let Ok(published) = event_rx.try_recv() {
+                if let SessionEvent::Updated { session } = published {
+                    revisions.push(session.revision);
+                }
+            }
+        }
+
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[1], revisions[0] + 1);
+
+        let (tx, rx) = oneshot::channel();
+        actor.handle_command(RegistryCommand::GetSession {
+            session_id: SessionId::new("rev-test"),
+            respond_to: tx,
+        });
+        assert_eq!(rx.await.unwrap().unwrap().revision, revisions[1]);
+    }
+
     #[tokio::test]
     async fn test_apply_hook_event_session_end() {
         let (_, mut actor, mut event_rx) = create_actor();
@@ -1611,6 +2174,84 @@ mod tests {
         assert!(matches!(event, SessionEvent::Registered { .. }));
     }
 
+    #[tokio::test]
+    async fn test_status_line_over_budget_flags_session_once() {
+        let (_, actor, mut event_rx) = create_actor();
+        let mut actor = actor.with_budgets(
+            BudgetConfig {
+                session_usd: Some(1.0),
+                interrupt: true,
+                ..Default::default()
+            },
+            None,
+        );
+        let pid = std::process::id();
+
+        for cost in [0.50, 1.25, 2.00] {
+            let (tx, rx) = oneshot::channel();
+            actor.handle_command(RegistryCommand::UpdateFromStatusLine {
+                session_id: SessionId::new("spender"),
+                data: serde_json::json!({
+                    "session_id": "spender",
+                    "pid": pid,
+                    "model": {"id": "claude-opus-4-6"},
+                    "cost": {"total_cost_usd": cost, "total_duration_ms": 1000}
+                }),
+                respond_to: tx,
+            });
+            rx.await.unwrap().unwrap();
+        }
+
+        let mut breaches = Vec::new();
+        let mut last_view = None;
+        while let Ok(event) = event_rx.try_recv() {
+            match event {
+                SessionEvent::BudgetExceeded {
+                    spent, interrupt, ..
+                } => breaches.push((spent, interrupt)),
+                SessionEvent::Updated { session } => last_view = Some(session),
+                _ => {}
+            }
+        }
+        assert_eq!(breaches, vec![(Money::from_usd(1.25), true)]);
+        let view = last_view.unwrap();
+        assert!(view.over_budget);
+        assert!(view.needs_attention);
+    }
+
+    #[tokio::test]
+    async fn test_daily_over_budget_clears_next_day() {
+        let (_, actor, _event_rx) = create_actor();
+        let mut actor = actor.with_budgets(
+            BudgetConfig {
+                daily_usd: Some(1.0),
+                ..Default::default()
+            },
+            None,
+        );
+        let pid = std::process::id();
+
+        let (tx, rx) = oneshot::channel();
+        actor.handle_command(RegistryCommand::UpdateFromStatusLine {
+            session_id: SessionId::new("spender"),
+            data: serde_json::json!({
+                "session_id": "spender",
+                "pid": pid,
+                "model": {"id": "claude-opus-4-6"},
+                "cost": {"total_cost_usd": 2.0, "total_duration_ms": 1000}
+            }),
+            respond_to: tx,
+        });
+        rx.await.unwrap().unwrap();
+        assert!(actor.sessions_by_pid[&pid].0.over_budget);
+
+        let tomorrow = Local::now().date_naive().succ_opt().unwrap();
+        actor.check_budgets_on(pid, tomorrow);
+        let session = &actor.sessions_by_pid[&pid].0;
+        assert!(!session.over_budget);
+        assert!(!SessionView::from_domain(session).needs_attention);
+    }
+
     #[tokio::test]
     async fn test_cleanup_stale_no_stale_sessions() {
         let (_, mut actor, _) = create_actor();
@@ -1706,9 +2347,10 @@ mod tests {
         while let Ok(event) = event_rx.try_recv() {
             match event {
                 SessionEvent::Removed {
-                    reason: RemovalReason::Upgraded,
+                    reason: RemovalReason::Upgraded { new_id },
                     ..
                 } => {
+                    assert_eq!(new_id.as_str(), "real-session-uuid");
                     found_removed = true;
                 }
                 SessionEvent::Registered { session_id, .. }
@@ -1801,9 +2443,9 @@ mod tests {
         while let Ok(event) = event_rx.try_recv() {
             match event {
                 SessionEvent::Removed {
-                    reason: RemovalReason::Upgraded,
+                    reason: RemovalReason::Upgraded { new_id },
                     ..
-                } => found_removed = true,
+                } if new_id.as_str() == "real-pi-session" => found_removed = true,
                 SessionEvent::Registered { session_id, .. }
                     if session_id.as_str() == "real-pi-session" =>
                 {
@@ -1862,6 +2504,149 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_permission_prompt_publishes_request() {
+        use atm_core::{LifecycleEvent, NotificationKind};
+
+        let (_, mut actor, mut event_rx) = create_actor();
+        let (tx, _) = oneshot::channel();
+        actor.handle_command(RegistryCommand::Register {
+            session: Box::new(create_test_session("gated")),
+            respond_to: tx,
+        });
+
+        let prompt = LifecycleEvent::NeedsInput {
+            reason: NeedsInputReason::Notification {
+                kind: NotificationKind::PermissionPrompt,
+                label: None,
+            },
+            prompt: None,
+        };
+        let mut apply = |event: LifecycleEvent| {
+            let (tx, _) = oneshot::channel();
+            actor.handle_command(RegistryCommand::ApplyLifecycleEvent {
+                session_id: SessionId::new("gated"),
+                event,
+                harness: atm_core::Harness::ClaudeCode,
+                pid: None,
+                tmux_pane: None,
+                respond_to: tx,
+            });
+        };
+        let requests = |rx: &mut broadcast::Receiver<SessionEvent>| {
+            let mut found = Vec::new();
+            while let Ok(event) = rx.try_recv() {
+                if let SessionEvent::PermissionRequested { tool, input, .. } = event {
+                    found.push((tool, input));
+                }
+            }
+            found
+        };
+
+        // No tool in flight: nothing to attribute the prompt to.
+        apply(prompt.clone());
+        assert!(requests(&mut event_rx).is_empty());
+
+        apply(LifecycleEvent::ToolCallStart {
+            name: Tool::Bash,
+            tool_use_id: None,
+            input: Some(serde_json::json!({"command": "cargo test"})),
+        });
+        apply(prompt.clone());
+        assert_eq!(
+            requests(&mut event_rx),
+            vec![(
+                Tool::Bash,
+                Some(serde_json::json!({"command": "cargo test"}))
+            )]
+        );
+
+        // Interactive tools are questions, not permission prompts.
+        apply(LifecycleEvent::NeedsInput {
+            reason: NeedsInputReason::InteractiveTool {
+                tool: Tool::AskUserQuestion,
+            },
+            prompt: None,
+        });
+        assert!(requests(&mut event_rx).is_empty());
+
+        apply(LifecycleEvent::ToolCallEnd {
+            name: Tool::Bash,
+            tool_use_id: None,
+            is_error: false,
+        });
+        apply(prompt);
+        assert!(requests(&mut event_rx).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_permission_prompt_with_parallel_calls() {
+        let (_, mut actor, mut event_rx) = create_actor();
+        let (tx, _) = oneshot::channel();
+        actor.handle_command(RegistryCommand::Register {
+            session: Box::new(create_test_session("parallel")),
+            respond_to: tx,
+        });
+
+        let prompt = LifecycleEvent::NeedsInput {
+            reason: NeedsInputReason::Notification {
+                kind: NotificationKind::PermissionPrompt,
+                label: None,
+            },
+            prompt: None,
+        };
+        let start = |id: &str, command: &str| LifecycleEvent::ToolCallStart {
+            name: Tool::Bash,
+            tool_use_id: Some(id.to_string()),
+            input: Some(serde_json::json!({ "command": command })),
+        };
+        let end = |id: &str| LifecycleEvent::ToolCallEnd {
+            name: Tool::Bash,
+            tool_use_id: Some(id.to_string()),
+            is_error: false,
+        };
+        let mut apply = |event: LifecycleEvent| {
+            let (tx, _) = oneshot::channel();
+            actor.handle_command(RegistryCommand::ApplyLifecycleEvent {
+                session_id: SessionId::new("parallel"),
+                event,
+                harness: atm_core::Harness::ClaudeCode,
+                pid: None,
+                tmux_pane: None,
+                respond_to: tx,
+            });
+        };
+        let requests = |rx: &mut broadcast::Receiver<SessionEvent>| {
+            let mut found = Vec::new();
+            while let Ok(event) = rx.try_recv() {
+                if let SessionEvent::PermissionRequested {
+                    tool_use_id, input, ..
+                } = event
+                {
+                    found.push((tool_use_id, input));
+                }
+            }
+            found
+        };
+
+        // Two calls in flight: the prompt can't be attributed to either.
+        apply(start("call_a", "git status"));
+        apply(start("call_b", "rm -rf ~"));
+        apply(prompt.clone());
+        assert!(requests(&mut event_rx).is_empty());
+
+        // Ending one call by id leaves the other, whatever the order.
+        apply(end("call_a"));
+        apply(prompt.clone());
+        assert_eq!(
+            requests(&mut event_rx),
+            vec![(
+                Some("call_b".to_string()),
+                Some(serde_json::json!({"command": "rm -rf ~"}))
+            )]
+        );
+    }
+
     #[tokio::test]
     async fn test_subagent_start_records_pending() {
         let (_, mut actor, _) = create_actor();
@@ -2406,4 +3191,116 @@ mod tests {
             "project_root should point to repo_b"
         );
     }
+
+    // ------------------------------------------------------------------------
+    // Persistence
+    // ------------------------------------------------------------------------
+
+    fn live_snapshot_entry(id: &str) -> PersistedSession {
+        let pid = std::process::id();
+        let mut domain = create_test_session(id);
+        domain.first_prompt = Some("restore me".to_string());
+        let mut infra = SessionInfrastructure::new();
+        infra.set_pid(pid);
+        infra.record_tool_use("Edit", None);
+        PersistedSession { pid, domain, infra }
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot_keeps_live_process() {
+        let (_cmd_tx, mut actor, _event_rx) = create_actor();
+
+        let restored =
+            actor.restore_snapshot(RegistrySnapshot::new(vec![live_snapshot_entry("live")]));
+
+        assert_eq!(restored, 1);
+        let pid = std::process::id();
+        let (domain, infra) = actor.sessions_by_pid.get(&pid).unwrap();
+        assert_eq!(domain.first_prompt.as_deref(), Some("restore me"));
+        assert_eq!(infra.recent_tools.len(), 1);
+        assert_eq!(
+            actor.session_id_to_pid.get(&SessionId::new("live")),
+            Some(&pid)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_snapshot_drops_unverifiable_entries() {
+        let (_cmd_tx, mut actor, _event_rx) = create_actor();
+
+        // Same PID, but a different start time: the PID was reused.
+        let mut reused = live_snapshot_entry("reused");
+        reused.infra.process_start_time = reused.infra.process_start_time.map(|t| t + 1);
+
+        // Synthetic PID from a PID-less registration.
+        let mut synthetic = live_snapshot_entry("synthetic");
+        synthetic.pid = SYNTHETIC_PID_BASE;
+        synthetic.infra.pid = Some(SYNTHETIC_PID_BASE);
+
+        // No recorded start time, so reuse can't be ruled out.
+        let mut no_start = live_snapshot_entry("no-start");
+        no_start.infra.process_start_time = None;
+
+        let restored =
+            actor.restore_snapshot(RegistrySnapshot::new(vec![reused, synthetic, no_start]));
+
+        assert_eq!(restored, 0);
+        assert_eq!(actor.session_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_state_path_roundtrip_across_actors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        persist::save_snapshot(
+            &path,
+            &RegistrySnapshot::new(vec![live_snapshot_entry("carry-over")]),
+        )
+        .unwrap();
+
+        let (_cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = broadcast::channel(16);
+        let mut actor = RegistryActor::with_state_path(cmd_rx, event_tx, path.clone());
+        assert_eq!(actor.session_count(), 1);
+
+        // Mutate, then flush and verify the file reflects the new state.
+        let (tx, rx) = oneshot::channel();
+        actor.handle_command(RegistryCommand::Register {
+            session: Box::new(create_test_session("pid-less")),
+            respond_to: tx,
+        });
+        rx.await.unwrap().unwrap();
+        assert!(actor.dirty);
+
+        let (tx, rx) = oneshot::channel();
+        actor.handle_command(RegistryCommand::Flush { respond_to: tx });
+        rx.await.unwrap().unwrap();
+        assert!(!actor.dirty);
+
+        let snapshot = persist::load_snapshot(&path).unwrap().unwrap();
+        assert_eq!(snapshot.sessions.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_state_file_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        std::fs::write(&path, "garbage").unwrap();
+
+        let (_cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = broadcast::channel(16);
+        let actor = RegistryActor::with_state_path(cmd_rx, event_tx, path);
+
+        assert_eq!(actor.session_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_flush_without_state_path_is_noop() {
+        let (_cmd_tx, mut actor, _event_rx) = create_actor();
+
+        let (tx, rx) = oneshot::channel();
+        actor.handle_command(RegistryCommand::Flush { respond_to: tx });
+
+        assert!(rx.await.unwrap().is_ok());
+    }
 }
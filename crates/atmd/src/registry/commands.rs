@@ -7,10 +7,15 @@
 //!
 //! All types are designed for async message passing and follow the panic-free policy.
 
-use atm_core::{AgentType, Harness, LifecycleEvent, SessionDomain, SessionId, SessionView};
+use atm_core::{
+    AgentType, Harness, LifecycleEvent, Money, PromptState, SessionDomain, SessionId, SessionView,
+    Tool,
+};
 use thiserror::Error;
 use tokio::sync::oneshot;
 
+use crate::budget::BudgetScope;
+
 // ============================================================================
 // Registry Commands
 // ============================================================================
@@ -89,6 +94,35 @@ pub enum RegistryCommand {
         respond_to: oneshot::Sender<Result<(), RegistryError>>,
     },
 
+    /// Store a prompt parsed from a session's tmux pane.
+    ///
+    /// Ignored (but not an error) once the session has stopped waiting,
+    /// or when the harness already described the prompt.
+    ///
+    /// # Errors
+    /// - `RegistryError::SessionNotFound` if the session doesn't exist
+    SetPanePrompt {
+        /// ID of the waiting session
+        session_id: SessionId,
+        /// The parsed prompt (boxed for size optimization)
+        prompt: Box<PromptState>,
+        /// Channel to send the result
+        respond_to: oneshot::Sender<Result<(), RegistryError>>,
+    },
+
+    /// Record the team a session was spawned into.
+    ///
+    /// # Errors
+    /// - `RegistryError::SessionNotFound` if the session doesn't exist
+    SetTeam {
+        /// ID of the session
+        session_id: SessionId,
+        /// Team name (from `ATM_TEAM` at launch)
+        team: String,
+        /// Channel to send the result
+        respond_to: oneshot::Sender<Result<(), RegistryError>>,
+    },
+
     /// Get a single session by ID.
     ///
     /// Returns `None` if the session doesn't exist.
@@ -99,6 +133,15 @@ pub enum RegistryCommand {
         respond_to: oneshot::Sender<Option<SessionView>>,
     },
 
+    /// Get the id of the `PermissionRequested` a session is still waiting
+    /// on, if any.
+    GetOpenPermission {
+        /// ID of the session to check
+        session_id: SessionId,
+        /// Channel to send the result
+        respond_to: oneshot::Sender<Option<u64>>,
+    },
+
     /// Get all sessions as views.
     ///
     /// Returns an empty vector if no sessions are registered.
@@ -131,6 +174,23 @@ pub enum RegistryCommand {
     /// that happen without a working directory change.
     RefreshGitInfo,
 
+    /// Write a registry snapshot to the state file if anything changed.
+    ///
+    /// This is a fire-and-forget command used by the persistence task.
+    /// No-op when the actor was created without a state path.
+    PersistSnapshot,
+
+    /// Write a registry snapshot to the state file unconditionally.
+    ///
+    /// Used on daemon shutdown so the final state survives the restart.
+    ///
+    /// # Errors
+    /// - `RegistryError::Persistence` if the state file could not be written
+    Flush {
+        /// Channel to send the result
+        respond_to: oneshot::Sender<Result<(), RegistryError>>,
+    },
+
     /// Register a discovered session (minimal data from /proc scan).
     ///
     /// Creates a minimal session with defaults that will be filled in
@@ -188,6 +248,10 @@ pub enum RegistryError {
     /// Failed to parse status line or event data.
     #[error("parse error: {0}")]
     ParseError(String),
+
+    /// Failed to write the registry state file.
+    #[error("persistence error: {0}")]
+    Persistence(String),
 }
 
 impl RegistryError {
@@ -230,10 +294,45 @@ pub enum SessionEvent {
         /// Why the session was removed
         reason: RemovalReason,
     },
+
+    /// A session crossed a configured cost budget.
+    ///
+    /// Published once per session and limit, right before the `Updated`
+    /// event that carries the session's `over_budget` flag.
+    BudgetExceeded {
+        /// ID of the session over budget
+        session_id: SessionId,
+        /// Which limit was crossed
+        scope: BudgetScope,
+        /// The configured limit
+        limit: Money,
+        /// Spend counted against the limit
+        spent: Money,
+        /// Whether the agent should be interrupted
+        interrupt: bool,
+    },
+
+    /// A session stopped at a permission prompt for a tool call.
+    ///
+    /// Published right after the `Updated` event that moves the session
+    /// into `AttentionNeeded`. Interactive tools (questions, plan
+    /// approval) are not permission prompts and don't produce this.
+    PermissionRequested {
+        /// The waiting session (boxed for size optimization)
+        session: Box<SessionView>,
+        /// Identifies this prompt; see `RegistryHandle::open_permission`
+        request_id: u64,
+        /// Tool being gated
+        tool: Tool,
+        /// Harness id of the gated call, when it reports one
+        tool_use_id: Option<String>,
+        /// Tool arguments, when the harness reported them
+        input: Option<serde_json::Value>,
+    },
 }
 
 /// Reason why a session was removed from the registry.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RemovalReason {
     /// Client explicitly requested removal.
     Explicit,
@@ -251,7 +350,20 @@ pub enum RemovalReason {
 
     /// Pending session was upgraded to a real session.
     /// The pending session (with temporary ID) is replaced by the real session.
-    Upgraded,
+    Upgraded {
+        /// ID the session continues under
+        new_id: SessionId,
+    },
+}
+
+impl RemovalReason {
+    /// ID the session lives on under, if it was renamed rather than gone.
+    pub fn replaced_by(&self) -> Option<&SessionId> {
+        match self {
+            Self::Upgraded { new_id } => Some(new_id),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for RemovalReason {
@@ -261,7 +373,7 @@ impl std::fmt::Display for RemovalReason {
             Self::RegistryFull => write!(f, "registry capacity reached"),
             Self::SessionEnded => write!(f, "session ended"),
             Self::ProcessDied => write!(f, "process died without a session-end signal"),
-            Self::Upgraded => write!(f, "upgraded to real session"),
+            Self::Upgraded { .. } => write!(f, "upgraded to real session"),
         }
     }
 }
@@ -319,8 +431,11 @@ mod tests {
         };
         let _cloned = registered.clone();
 
-        let session =
-            SessionDomain::new(SessionId::new("test-2"), AgentType::Explore, Model::Sonnet4);
+        let session = SessionDomain::new(
+            SessionId::new("test-2"),
+            AgentType::Explore,
+            Model::from_id("claude-sonnet-4"),
+        );
         let updated = SessionEvent::Updated {
             session: Box::new(SessionView::from_domain(&session)),
         };
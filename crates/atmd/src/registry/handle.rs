@@ -12,7 +12,7 @@
 
 use tokio::sync::{broadcast, mpsc, oneshot};
 
-use atm_core::{Harness, LifecycleEvent, SessionDomain, SessionId, SessionView};
+use atm_core::{Harness, LifecycleEvent, PromptState, SessionDomain, SessionId, SessionView};
 
 use super::commands::{RegistryCommand, RegistryError, SessionEvent};
 
@@ -154,6 +154,55 @@ impl RegistryHandle {
         rx.await.map_err(|_| RegistryError::ChannelClosed)?
     }
 
+    /// Store a prompt parsed from a session's tmux pane.
+    ///
+    /// Has no effect once the session has stopped waiting or when the
+    /// harness already described the prompt.
+    ///
+    /// # Errors
+    ///
+    /// - `RegistryError::SessionNotFound` if the session doesn't exist
+    /// - `RegistryError::ChannelClosed` if the actor has shut down
+    pub async fn set_pane_prompt(
+        &self,
+        session_id: SessionId,
+        prompt: PromptState,
+    ) -> Result<(), RegistryError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.sender
+            .send(RegistryCommand::SetPanePrompt {
+                session_id,
+                prompt: Box::new(prompt),
+                respond_to: tx,
+            })
+            .await
+            .map_err(|_| RegistryError::ChannelClosed)?;
+
+        rx.await.map_err(|_| RegistryError::ChannelClosed)?
+    }
+
+    /// Record the team a session was spawned into.
+    ///
+    /// # Errors
+    ///
+    /// - `RegistryError::SessionNotFound` if the session doesn't exist
+    /// - `RegistryError::ChannelClosed` if the actor has shut down
+    pub async fn set_team(&self, session_id: SessionId, team: String) -> Result<(), RegistryError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.sender
+            .send(RegistryCommand::SetTeam {
+                session_id,
+                team,
+                respond_to: tx,
+            })
+            .await
+            .map_err(|_| RegistryError::ChannelClosed)?;
+
+        rx.await.map_err(|_| RegistryError::ChannelClosed)?
+    }
+
     /// Get a single session by ID.
     ///
     /// Returns `None` if the session doesn't exist or if communication
@@ -172,6 +221,25 @@ impl RegistryHandle {
         rx.await.ok()?
     }
 
+    /// Returns the `request_id` of the permission prompt `session_id` is
+    /// still waiting on.
+    ///
+    /// `None` once the session has stopped waiting, or if it is waiting on
+    /// a prompt that wasn't offered for automatic answers.
+    pub async fn open_permission(&self, session_id: SessionId) -> Option<u64> {
+        let (tx, rx) = oneshot::channel();
+
+        self.sender
+            .send(RegistryCommand::GetOpenPermission {
+                session_id,
+                respond_to: tx,
+            })
+            .await
+            .ok()?;
+
+        rx.await.ok()?
+    }
+
     /// Get all sessions as views.
     ///
     /// Returns an empty vector if no sessions are registered or if
@@ -220,6 +288,26 @@ impl RegistryHandle {
         let _ = self.sender.send(RegistryCommand::CleanupStale).await;
     }
 
+    /// Write a registry snapshot to the state file now.
+    ///
+    /// Call on shutdown so the last few seconds of state aren't lost.
+    /// Succeeds without writing if the registry has no state file.
+    ///
+    /// # Errors
+    ///
+    /// - `RegistryError::Persistence` if the state file could not be written
+    /// - `RegistryError::ChannelClosed` if the actor has shut down
+    pub async fn flush(&self) -> Result<(), RegistryError> {
+        let (tx, rx) = oneshot::channel();
+
+        self.sender
+            .send(RegistryCommand::Flush { respond_to: tx })
+            .await
+            .map_err(|_| RegistryError::ChannelClosed)?;
+
+        rx.await.map_err(|_| RegistryError::ChannelClosed)?
+    }
+
     /// Register a discovered session (minimal data from /proc scan).
     ///
     /// Creates a minimal session with defaults that will be filled in
@@ -289,7 +377,7 @@ mod tests {
         SessionDomain::new(
             SessionId::new(id),
             AgentType::GeneralPurpose,
-            Model::Sonnet4,
+            Model::from_id("claude-sonnet-4"),
         )
     }
 
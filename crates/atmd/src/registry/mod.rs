@@ -25,13 +25,20 @@
 //! - All fallible operations return `Result` or `Option`
 //! - Channel operations handle closure gracefully
 
+use std::path::PathBuf;
+
 use tokio::sync::{broadcast, mpsc};
 use tokio::time::{interval, Duration};
 use tracing::debug;
 
+use crate::budget::BudgetConfig;
+use crate::journal;
+use crate::ledger::CostLedger;
+
 mod actor;
 mod commands;
 mod handle;
+pub mod persist;
 
 pub use actor::{RegistryActor, MAX_SESSIONS};
 pub use commands::{RegistryCommand, RegistryError, RemovalReason, SessionEvent};
@@ -47,6 +54,9 @@ const CLEANUP_INTERVAL_SECS: u64 = 2;
 /// Git info refresh interval in seconds
 const GIT_REFRESH_INTERVAL_SECS: u64 = 5;
 
+/// Registry snapshot interval in seconds (only writes when state changed)
+const PERSIST_INTERVAL_SECS: u64 = 5;
+
 /// Spawn the registry actor and return a handle for interaction.
 ///
 /// This function:
@@ -73,12 +83,69 @@ const GIT_REFRESH_INTERVAL_SECS: u64 = 5;
 /// }
 /// ```
 pub fn spawn_registry() -> RegistryHandle {
+    spawn_registry_with_options(RegistryOptions::default())
+}
+
+/// Optional on-disk features for a spawned registry.
+///
+/// The default (all `None`) is a purely in-memory registry, identical to
+/// [`spawn_registry`].
+#[derive(Debug, Clone, Default)]
+pub struct RegistryOptions {
+    /// Snapshot file used to persist sessions across restarts
+    /// (see [`persist`]).
+    pub state_path: Option<PathBuf>,
+
+    /// Event journal file (see [`crate::journal`]).
+    pub journal_path: Option<PathBuf>,
+
+    /// Cost budgets to evaluate on status-line updates
+    /// (see [`crate::budget`]).
+    pub budgets: Option<BudgetConfig>,
+
+    /// Cost ledger the budgets' spend for today is seeded from
+    /// (see [`crate::ledger`]).
+    pub cost_ledger: Option<CostLedger>,
+}
+
+/// Spawn a registry actor with optional persistence and journaling.
+///
+/// Behaves like [`spawn_registry`], plus:
+/// - with `state_path`: rehydrates live sessions from an existing snapshot,
+///   then snapshots state back every few seconds (when changed) and on
+///   [`RegistryHandle::flush`]
+/// - with `journal_path`: appends every lifecycle and session event to a
+///   rotating JSONL journal
+/// - with `budgets`: flags sessions that exceed a cost budget and publishes
+///   `SessionEvent::BudgetExceeded`, counting today's spend from
+///   `cost_ledger` when given
+pub fn spawn_registry_with_options(options: RegistryOptions) -> RegistryHandle {
     // Create channels
     let (cmd_tx, cmd_rx) = mpsc::channel(COMMAND_BUFFER);
     let (event_tx, _) = broadcast::channel(EVENT_BUFFER);
 
-    // Create and spawn actor
-    let actor = RegistryActor::new(cmd_rx, event_tx.clone());
+    // Create actor
+    let persistent = options.state_path.is_some();
+    let mut actor = match options.state_path {
+        Some(path) => RegistryActor::with_state_path(cmd_rx, event_tx.clone(), path),
+        None => RegistryActor::new(cmd_rx, event_tx.clone()),
+    };
+
+    // Wire up the journal before the actor starts so no event is missed
+    if let Some(path) = options.journal_path {
+        let (journal_tx, journal_rx) = mpsc::channel(journal::JOURNAL_BUFFER);
+        journal::spawn_journal_task(
+            journal::JournalWriter::new(path),
+            journal_rx,
+            event_tx.subscribe(),
+        );
+        actor = actor.with_journal(journal_tx);
+    }
+
+    if let Some(budgets) = options.budgets {
+        actor = actor.with_budgets(budgets, options.cost_ledger.as_ref());
+    }
+
     tokio::spawn(actor.run());
 
     // Create handle
@@ -87,6 +154,11 @@ pub fn spawn_registry() -> RegistryHandle {
     // Spawn cleanup task
     spawn_cleanup_task(cmd_tx.clone());
 
+    // Spawn snapshot task (only when backed by a state file)
+    if persistent {
+        spawn_persist_task(cmd_tx.clone());
+    }
+
     // Spawn git info refresh task (detects branch switches)
     spawn_git_refresh_task(cmd_tx);
 
@@ -132,3 +204,20 @@ fn spawn_git_refresh_task(sender: mpsc::Sender<RegistryCommand>) {
         }
     });
 }
+
+/// Spawn a background task that periodically asks the actor to snapshot
+/// its state to disk. The actor skips the write when nothing changed.
+fn spawn_persist_task(sender: mpsc::Sender<RegistryCommand>) {
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(PERSIST_INTERVAL_SECS));
+
+        loop {
+            ticker.tick().await;
+
+            if sender.send(RegistryCommand::PersistSnapshot).await.is_err() {
+                debug!("Persist task stopping: registry channel closed");
+                break;
+            }
+        }
+    });
+}
@@ -0,0 +1,333 @@
+//! On-disk snapshots of registry state.
+//!
+//! The registry actor periodically writes every tracked session
+//! (`SessionDomain` + `SessionInfrastructure`) to a JSON state file so a
+//! daemon restart doesn't lose accumulated cost, line counts, tool history
+//! or parent/child links. On startup the snapshot is read back and each
+//! entry is re-validated against `/proc` before being re-registered.
+//!
+//! # File Format
+//!
+//! A single JSON document, `$XDG_STATE_HOME/atm/sessions.json` by default:
+//!
+//! ```text
+//! { "version": 1, "saved_at": "...", "sessions": [ { "pid": 1234, "domain": {...}, "infra": {...} } ] }
+//! ```
+//!
+//! Writes go to a sibling temp file that is synced and renamed into place,
+//! so a crash mid-write leaves the previous snapshot intact. The registry
+//! actor only builds the snapshot; [`SnapshotWriter`] does the file I/O on
+//! the blocking pool.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - All I/O and (de)serialization failures are returned as `PersistError`
+
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+
+use atm_core::{SessionDomain, SessionInfrastructure};
+
+/// Current snapshot format version.
+///
+/// Bump when the snapshot shape changes incompatibly; older files are then
+/// ignored rather than half-parsed.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// File name of the registry snapshot inside the ATM state directory.
+pub const STATE_FILE_NAME: &str = "sessions.json";
+
+/// Returns the default registry snapshot path.
+///
+/// Lives next to `atmd.pid` and `atm.log` under `dirs::state_dir()/atm`
+/// (honors `$XDG_STATE_HOME`), falling back to `/tmp/atm`.
+pub fn default_state_path() -> PathBuf {
+    dirs::state_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("atm")
+        .join(STATE_FILE_NAME)
+}
+
+/// A single persisted registry entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedSession {
+    /// Registry primary key (the agent process PID).
+    pub pid: u32,
+    /// Domain state: cost, context, lines changed, links, etc.
+    pub domain: SessionDomain,
+    /// Infrastructure state: PID start time, tool history, counters.
+    pub infra: SessionInfrastructure,
+}
+
+/// Full registry snapshot as written to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistrySnapshot {
+    /// Snapshot format version (see `SNAPSHOT_VERSION`).
+    pub version: u32,
+    /// When the snapshot was taken.
+    pub saved_at: DateTime<Utc>,
+    /// All sessions tracked at snapshot time.
+    pub sessions: Vec<PersistedSession>,
+}
+
+impl RegistrySnapshot {
+    /// Creates a snapshot stamped with the current time and format version.
+    pub fn new(sessions: Vec<PersistedSession>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            saved_at: Utc::now(),
+            sessions,
+        }
+    }
+}
+
+/// Errors that can occur while reading or writing a snapshot.
+#[derive(Debug, Error)]
+pub enum PersistError {
+    /// Filesystem error reading or writing the state file.
+    #[error("state file I/O error at {path}: {source}")]
+    Io {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The state file could not be (de)serialized.
+    #[error("state file format error: {0}")]
+    Format(#[from] serde_json::Error),
+
+    /// The state file was written by an incompatible daemon version.
+    #[error("unsupported state file version {found} (expected {expected})")]
+    UnsupportedVersion {
+        /// Version found in the file
+        found: u32,
+        /// Version this daemon understands
+        expected: u32,
+    },
+}
+
+/// Loads a registry snapshot from `path`.
+///
+/// Returns `Ok(None)` if the file does not exist (first run).
+pub fn load_snapshot(path: &Path) -> Result<Option<RegistrySnapshot>, PersistError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(source) => {
+            return Err(PersistError::Io {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+    };
+
+    let snapshot: RegistrySnapshot = serde_json::from_str(&contents)?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(PersistError::UnsupportedVersion {
+            found: snapshot.version,
+            expected: SNAPSHOT_VERSION,
+        });
+    }
+
+    Ok(Some(snapshot))
+}
+
+/// Atomically writes a registry snapshot to `path`.
+///
+/// Creates the parent directory if needed, writes to `<path>.tmp`, syncs
+/// it to disk and renames it over the destination.
+pub fn save_snapshot(path: &Path, snapshot: &RegistrySnapshot) -> Result<(), PersistError> {
+    let io_err = |source| PersistError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+
+    let json = serde_json::to_vec(snapshot)?;
+    let tmp_path = path.with_extension("json.tmp");
+    let mut file = std::fs::File::create(&tmp_path).map_err(io_err)?;
+    file.write_all(&json).map_err(io_err)?;
+    file.sync_all().map_err(io_err)?;
+    drop(file);
+    std::fs::rename(&tmp_path, path).map_err(io_err)?;
+
+    Ok(())
+}
+
+/// Saves snapshots to one path on the blocking pool.
+///
+/// Writes may finish out of order, so each snapshot is numbered when it is
+/// handed over and a write is skipped once a newer one has reached disk;
+/// the file never goes back in time.
+#[derive(Debug)]
+pub struct SnapshotWriter {
+    path: PathBuf,
+    /// Number of the last snapshot handed to [`Self::save`]
+    taken: u64,
+    /// Number of the newest snapshot on disk; held for the whole write
+    written: Arc<Mutex<u64>>,
+    /// Whether the most recent write failed
+    failed: Arc<AtomicBool>,
+}
+
+impl SnapshotWriter {
+    /// Creates a writer for the state file at `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            taken: 0,
+            written: Arc::new(Mutex::new(0)),
+            failed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Returns the state file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if the most recent write failed and should be retried.
+    pub fn last_write_failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Writes `snapshot` on the blocking pool. The returned task finishes
+    /// once it is on disk (or superseded by a newer snapshot).
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn save(&mut self, snapshot: RegistrySnapshot) -> JoinHandle<Result<(), PersistError>> {
+        self.taken += 1;
+        let number = self.taken;
+        let path = self.path.clone();
+        let written = Arc::clone(&self.written);
+        let failed = Arc::clone(&self.failed);
+
+        tokio::task::spawn_blocking(move || {
+            let mut newest = written.lock().unwrap_or_else(PoisonError::into_inner);
+            if *newest > number {
+                return Ok(());
+            }
+            let result = save_snapshot(&path, &snapshot);
+            failed.store(result.is_err(), Ordering::Relaxed);
+            if result.is_ok() {
+                *newest = number;
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atm_core::{AgentType, Model, SessionId};
+
+    fn sample_snapshot() -> RegistrySnapshot {
+        let mut domain = SessionDomain::new(
+            SessionId::new("persist-test"),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-sonnet-4"),
+        );
+        domain.first_prompt = Some("fix the tests".to_string());
+        domain.child_session_ids.push(SessionId::new("child-1"));
+
+        let mut infra = SessionInfrastructure::new();
+        infra.pid = Some(4242);
+        infra.process_start_time = Some(99);
+        infra.update_count = 7;
+        infra.record_tool_use("Bash", None);
+
+        RegistrySnapshot::new(vec![PersistedSession {
+            pid: 4242,
+            domain,
+            infra,
+        }])
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_writer_never_goes_back_in_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        let mut writer = SnapshotWriter::new(path.clone());
+
+        let older = writer.save(RegistrySnapshot::new(Vec::new()));
+        let newer = writer.save(sample_snapshot());
+        newer.await.unwrap().unwrap();
+        older.await.unwrap().unwrap();
+
+        let loaded = load_snapshot(&path).unwrap().unwrap();
+        assert_eq!(loaded.sessions.len(), 1);
+        assert!(!writer.last_write_failed());
+        assert!(!dir.path().join("sessions.json.tmp").exists());
+    }
+
+    #[test]
+    fn test_default_state_path_file_name() {
+        assert!(default_state_path().ends_with("atm/sessions.json"));
+    }
+
+    #[test]
+    fn test_load_missing_file_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = load_snapshot(&dir.path().join("missing.json")).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(STATE_FILE_NAME);
+
+        save_snapshot(&path, &sample_snapshot()).unwrap();
+        let loaded = load_snapshot(&path).unwrap().unwrap();
+
+        assert_eq!(loaded.version, SNAPSHOT_VERSION);
+        assert_eq!(loaded.sessions.len(), 1);
+        let entry = &loaded.sessions[0];
+        assert_eq!(entry.pid, 4242);
+        assert_eq!(entry.domain.id.as_str(), "persist-test");
+        assert_eq!(entry.domain.first_prompt.as_deref(), Some("fix the tests"));
+        assert_eq!(entry.domain.child_session_ids.len(), 1);
+        assert_eq!(entry.infra.process_start_time, Some(99));
+        assert_eq!(entry.infra.update_count, 7);
+        assert_eq!(entry.infra.recent_tools.len(), 1);
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_FILE_NAME);
+        let mut snapshot = sample_snapshot();
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        save_snapshot(&path, &snapshot).unwrap();
+
+        let result = load_snapshot(&path);
+        assert!(matches!(
+            result,
+            Err(PersistError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_corrupt_file_is_format_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATE_FILE_NAME);
+        std::fs::write(&path, "{not json").unwrap();
+
+        assert!(matches!(load_snapshot(&path), Err(PersistError::Format(_))));
+    }
+}
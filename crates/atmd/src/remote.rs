@@ -0,0 +1,361 @@
+//! Remote access configuration for the TCP and WebSocket listeners.
+//!
+//! By default the daemon only listens on its Unix socket. Adding a
+//! `[remote]` table to `~/.config/atm/config.toml` opens a TCP listener
+//! (newline-delimited JSON, same as the socket) and/or a WebSocket listener
+//! (one JSON message per text frame):
+//!
+//! ```toml
+//! [remote]
+//! tcp_listen = "127.0.0.1:7878"
+//! websocket_listen = "127.0.0.1:7879"
+//!
+//! [[remote.tokens]]
+//! token = "laptop-dashboard"
+//! scope = "read_only"
+//!
+//! [[remote.tokens]]
+//! token = "ops"
+//! scope = "control"
+//! ```
+//!
+//! The file holds secrets, so the daemon refuses to open remote listeners
+//! while it is readable by group or other (`chmod 600` it).
+//!
+//! Remote clients must present one of the tokens in their `Connect`
+//! message. A `read_only` token can list and subscribe; `control` can also
+//! kill, interrupt, send input and submit harness events.
+//!
+//! The listeners speak plain text, so tokens cross the wire unencrypted.
+//! Keep them on loopback and reach them from another machine through an
+//! SSH tunnel (`ssh -L 7878:127.0.0.1:7878 host`) rather than binding a
+//! public address. Clients that don't authenticate within a few seconds
+//! are disconnected, and only a handful may be mid-handshake at once.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Config read and parse failures are returned as `RemoteConfigError`
+
+use std::fmt;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use atm_protocol::MessageType;
+
+/// Returns the default ATM config file path (`~/.config/atm/config.toml`).
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("atm").join("config.toml"))
+}
+
+/// What a remote token is allowed to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessScope {
+    /// List and subscribe to sessions only
+    ReadOnly,
+    /// Everything a local client can do
+    Control,
+}
+
+impl AccessScope {
+    /// Returns true if a client with this scope may send `message`.
+    pub fn allows(self, message: &MessageType) -> bool {
+        match self {
+            Self::Control => true,
+            Self::ReadOnly => matches!(
+                message,
+                MessageType::Connect { .. }
+                    | MessageType::ListSessions
+                    | MessageType::Subscribe { .. }
+                    | MessageType::Unsubscribe
+                    | MessageType::Ping { .. }
+                    | MessageType::Disconnect
+            ),
+        }
+    }
+}
+
+/// A token accepted on the remote listeners.
+#[derive(Clone, Deserialize)]
+pub struct RemoteToken {
+    /// Shared secret presented in `Connect`
+    pub token: String,
+    /// What the token grants
+    pub scope: AccessScope,
+}
+
+impl fmt::Debug for RemoteToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteToken")
+            .field("token", &"<redacted>")
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+/// The `[remote]` table of the ATM config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RemoteConfig {
+    /// Address for the newline-delimited JSON TCP listener
+    #[serde(default)]
+    pub tcp_listen: Option<SocketAddr>,
+    /// Address for the WebSocket listener
+    #[serde(default)]
+    pub websocket_listen: Option<SocketAddr>,
+    /// Accepted tokens
+    #[serde(default)]
+    pub tokens: Vec<RemoteToken>,
+}
+
+impl RemoteConfig {
+    /// Returns true if any remote listener is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.tcp_listen.is_some() || self.websocket_listen.is_some()
+    }
+
+    /// Returns the scope granted by `presented`, or `None` if it matches no
+    /// configured token.
+    pub fn authenticate(&self, presented: Option<&str>) -> Option<AccessScope> {
+        let presented = presented?;
+        self.tokens
+            .iter()
+            .find(|t| !t.token.is_empty() && constant_time_eq(&t.token, presented))
+            .map(|t| t.scope)
+    }
+
+    /// Checks that an enabled config can actually admit someone.
+    pub fn validate(&self) -> Result<(), RemoteConfigError> {
+        if self.is_enabled() && !self.tokens.iter().any(|t| !t.token.is_empty()) {
+            return Err(RemoteConfigError::NoTokens);
+        }
+        Ok(())
+    }
+}
+
+/// Compares two secrets without short-circuiting on the first difference.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Errors loading the remote access config.
+#[derive(Debug, Error)]
+pub enum RemoteConfigError {
+    /// The config file exists but could not be read.
+    #[error("failed to read config {path}: {source}")]
+    Io {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The config file is not valid TOML or `[remote]` is malformed.
+    #[error("failed to parse config {path}: {source}")]
+    Parse {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying parse error
+        source: toml::de::Error,
+    },
+
+    /// A listener is configured but no usable token is.
+    #[error("remote listeners are configured but [[remote.tokens]] is empty")]
+    NoTokens,
+
+    /// A listener is configured but the file holding its tokens can be
+    /// read by other users.
+    #[error("config {path} holds remote tokens but has mode {mode:o}; run `chmod 600` on it")]
+    InsecurePermissions {
+        /// Path that failed
+        path: PathBuf,
+        /// Permission bits of the file
+        mode: u32,
+    },
+}
+
+/// Only the part of the config file the daemon cares about.
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    remote: RemoteConfig,
+}
+
+/// Loads and validates the `[remote]` table from `path`.
+///
+/// A missing file or table yields a disabled config. An enabled config is
+/// rejected if the file is group- or world-accessible, since it holds the
+/// tokens.
+pub fn load_remote_config(path: &Path) -> Result<RemoteConfig, RemoteConfigError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(RemoteConfig::default()),
+        Err(source) => {
+            return Err(RemoteConfigError::Io {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+    };
+
+    let file: ConfigFile =
+        toml::from_str(&contents).map_err(|source| RemoteConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    file.remote.validate()?;
+
+    if file.remote.is_enabled() {
+        let mode = std::fs::metadata(path)
+            .map_err(|source| RemoteConfigError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?
+            .permissions()
+            .mode()
+            & 0o777;
+        if mode & 0o077 != 0 {
+            return Err(RemoteConfigError::InsecurePermissions {
+                path: path.to_path_buf(),
+                mode,
+            });
+        }
+    }
+
+    Ok(file.remote)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RemoteConfig {
+        RemoteConfig {
+            tcp_listen: Some("127.0.0.1:7878".parse().unwrap()),
+            websocket_listen: None,
+            tokens: vec![
+                RemoteToken {
+                    token: "viewer".to_string(),
+                    scope: AccessScope::ReadOnly,
+                },
+                RemoteToken {
+                    token: "operator".to_string(),
+                    scope: AccessScope::Control,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let config = config();
+        assert_eq!(
+            config.authenticate(Some("viewer")),
+            Some(AccessScope::ReadOnly)
+        );
+        assert_eq!(
+            config.authenticate(Some("operator")),
+            Some(AccessScope::Control)
+        );
+        assert_eq!(config.authenticate(Some("viewer2")), None);
+        assert_eq!(config.authenticate(Some("")), None);
+        assert_eq!(config.authenticate(None), None);
+    }
+
+    #[test]
+    fn test_read_only_scope() {
+        let scope = AccessScope::ReadOnly;
+        assert!(scope.allows(&MessageType::ListSessions));
+        assert!(scope.allows(&MessageType::Ping { seq: 1 }));
+        assert!(!scope.allows(&MessageType::Discover));
+        assert!(!scope.allows(&MessageType::KillSession {
+            target: "abc".to_string()
+        }));
+        assert!(!scope.allows(&MessageType::HookEvent {
+            data: serde_json::json!({})
+        }));
+        assert!(AccessScope::Control.allows(&MessageType::Discover));
+    }
+
+    #[test]
+    fn test_debug_redacts_token() {
+        let debug = format!("{:?}", config());
+        assert!(!debug.contains("viewer"));
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[test]
+    fn test_load_remote_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        assert!(!load_remote_config(&path).unwrap().is_enabled());
+
+        std::fs::write(
+            &path,
+            r#"
+[harness]
+default = "pi"
+
+[remote]
+tcp_listen = "127.0.0.1:7878"
+
+[[remote.tokens]]
+token = "viewer"
+scope = "read_only"
+"#,
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        let config = load_remote_config(&path).unwrap();
+        assert_eq!(config.tcp_listen, Some("127.0.0.1:7878".parse().unwrap()));
+        assert_eq!(config.tokens.len(), 1);
+    }
+
+    #[test]
+    fn test_load_rejects_listener_without_tokens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[remote]\nwebsocket_listen = \"127.0.0.1:7879\"\n").unwrap();
+
+        assert!(matches!(
+            load_remote_config(&path),
+            Err(RemoteConfigError::NoTokens)
+        ));
+    }
+
+    #[test]
+    fn test_load_rejects_shared_config_with_listeners() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[remote]\ntcp_listen = \"127.0.0.1:7878\"\n\n[[remote.tokens]]\ntoken = \"ops\"\nscope = \"control\"\n",
+        )
+        .unwrap();
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(matches!(
+            load_remote_config(&path),
+            Err(RemoteConfigError::InsecurePermissions { mode: 0o644, .. })
+        ));
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        assert!(load_remote_config(&path).unwrap().is_enabled());
+
+        // Without listeners there is nothing to protect
+        std::fs::write(&path, "[harness]\ndefault = \"pi\"\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(!load_remote_config(&path).unwrap().is_enabled());
+    }
+}
@@ -4,6 +4,7 @@
 //! - Performs protocol version negotiation
 //! - Parses incoming messages
 //! - Routes commands to the registry
+//! - Executes control requests (kill/interrupt/send/reply) via `ControlService`
 //! - Sends responses and broadcasts events to subscribers
 //!
 //! # Panic-Free Guarantees
@@ -13,50 +14,163 @@
 //! - All fallible operations use `?`, pattern matching, or `unwrap_or`
 //! - Connection errors are logged and result in graceful disconnect
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
-use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
-use tokio::sync::{Mutex, RwLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+use tokio::sync::{Mutex, OwnedSemaphorePermit, RwLock};
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
 
 use atm_claude_adapter::RawHookEvent;
-use atm_core::SessionId;
+use atm_codex_adapter::RawCodexEvent;
+use atm_core::{SessionId, SessionView};
 use atm_pi_adapter::RawPiEvent;
-use atm_protocol::{ClientMessage, DaemonMessage, MessageType, ProtocolVersion};
+use atm_protocol::{
+    ClientMessage, ControlAction, DaemonMessage, MessageType, ProtocolVersion, SubscriptionFilter,
+};
+use atm_tmux::TmuxClient;
 
+use crate::control::{ControlError, ControlOutcome, ControlService};
 use crate::discovery::{DiscoveryResult, DiscoveryService};
 use crate::registry::{RegistryHandle, SessionEvent};
 
+use crate::remote::{AccessScope, RemoteConfig};
+
+use super::panes::PaneSessions;
+
+/// Read half of any client transport (Unix socket, TCP, WebSocket bridge)
+pub type BoxedReader = Box<dyn AsyncRead + Send + Sync + Unpin>;
+
+/// Write half of any client transport
+pub type BoxedWriter = Box<dyn AsyncWrite + Send + Sync + Unpin>;
+
 /// Type alias for subscriber writer handle
-pub type SubscriberWriter = Arc<Mutex<BufWriter<OwnedWriteHalf>>>;
+pub type SubscriberWriter = Arc<Mutex<BufWriter<BoxedWriter>>>;
 
 /// Information about a subscribed client
 pub struct Subscriber {
+    /// Client ID the connection announced, for logs and duplicate checks
+    pub client_id: String,
+
     /// Writer for sending events
     pub writer: SubscriberWriter,
 
     /// Optional filter for session-specific subscriptions
     pub filter: Option<SessionId>,
+
+    /// Criteria-based filter and the sessions it has let through
+    pub scope: SubscriptionScope,
+
+    /// Whether the client negotiated a protocol that understands
+    /// `SessionPatch` (otherwise it gets full `SessionUpdated` messages)
+    pub supports_patches: bool,
+
+    /// Set while the connection is building and sending the session list;
+    /// broadcasts skip the subscriber so they can't land ahead of the list
+    pub syncing: bool,
+
+    /// Whether an event was skipped while `syncing`, so the list may be stale
+    pub missed_while_syncing: bool,
+}
+
+/// What a subscriber should be sent for a session event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Nothing; the session is outside the subscriber's filter
+    Skip,
+    /// The update. `fresh` means the subscriber has no earlier view of the
+    /// session, so it must get the full view rather than a patch.
+    Update { fresh: bool },
+    /// A `SessionRemoved`, either because the session is gone or because it
+    /// no longer matches the filter
+    Removed,
+}
+
+/// A subscriber's [`SubscriptionFilter`] plus the set of sessions it
+/// currently shows.
+///
+/// Tracking visibility lets the broadcaster tell a filtered client when a
+/// session leaves its filter, and keeps removals of sessions it never saw
+/// off the wire. An empty filter lets everything through unchanged.
+#[derive(Debug, Default)]
+pub struct SubscriptionScope {
+    filter: SubscriptionFilter,
+    visible: HashSet<SessionId>,
+}
+
+impl SubscriptionScope {
+    /// Creates a scope for `filter` with nothing visible yet.
+    pub fn new(filter: SubscriptionFilter) -> Self {
+        Self {
+            filter,
+            visible: HashSet::new(),
+        }
+    }
+
+    /// The filter this scope applies.
+    pub fn filter(&self) -> &SubscriptionFilter {
+        &self.filter
+    }
+
+    /// Records the sessions sent to the client in a full session list.
+    pub fn reset_visible<'a>(&mut self, sessions: impl IntoIterator<Item = &'a SessionView>) {
+        self.visible = sessions.into_iter().map(|s| s.id.clone()).collect();
+    }
+
+    /// Routes an update of `session`, whose pane belongs to `tmux_session`.
+    pub fn on_update(&mut self, session: &SessionView, tmux_session: Option<&str>) -> Delivery {
+        if self.filter.is_empty() {
+            return Delivery::Update { fresh: false };
+        }
+
+        let matches = self.filter.matches(session, tmux_session);
+        match (matches, self.visible.contains(&session.id)) {
+            (true, true) => Delivery::Update { fresh: false },
+            (true, false) => {
+                self.visible.insert(session.id.clone());
+                Delivery::Update { fresh: true }
+            }
+            (false, true) => {
+                self.visible.remove(&session.id);
+                Delivery::Removed
+            }
+            (false, false) => Delivery::Skip,
+        }
+    }
+
+    /// Routes the removal of `session_id`.
+    pub fn on_removed(&mut self, session_id: &SessionId) -> Delivery {
+        if self.filter.is_empty() || self.visible.remove(session_id) {
+            Delivery::Removed
+        } else {
+            Delivery::Skip
+        }
+    }
 }
 
-/// Type alias for the subscribers map
-pub type SubscribersMap = Arc<RwLock<HashMap<String, Subscriber>>>;
+/// Type alias for the subscribers map, keyed by the server-assigned
+/// connection number (client IDs are chosen by clients and can collide)
+pub type SubscribersMap = Arc<RwLock<HashMap<u64, Subscriber>>>;
 
 /// Maximum number of concurrent TUI clients
 const MAX_TUI_CLIENTS: usize = 10;
 
+/// Times a session list is rebuilt when broadcasts keep arriving mid-sync
+const MAX_SYNC_ATTEMPTS: usize = 3;
+
 /// Maximum message size (1 MB)
-const MAX_MESSAGE_SIZE: usize = 1_048_576;
+pub(crate) const MAX_MESSAGE_SIZE: usize = 1_048_576;
 
 /// Read timeout for idle connections (5 minutes)
 const READ_TIMEOUT: Duration = Duration::from_secs(300);
 
+/// Time a remote client has to authenticate (5 seconds)
+pub(super) const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Write timeout (10 seconds)
-const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+pub(super) const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Unique identifier for this connection
 type ClientId = String;
@@ -70,7 +184,7 @@ type ClientId = String;
 /// - Graceful shutdown
 pub struct ConnectionHandler {
     /// Buffered reader for incoming messages
-    reader: BufReader<OwnedReadHalf>,
+    reader: BufReader<BoxedReader>,
 
     /// Buffered writer for outgoing messages (shared for event broadcast)
     writer: SubscriberWriter,
@@ -78,6 +192,9 @@ pub struct ConnectionHandler {
     /// Handle to the session registry
     registry: RegistryHandle,
 
+    /// Executes kill/interrupt/send/reply requests
+    control: ControlService,
+
     /// Shared subscribers map for event broadcasting
     subscribers: SubscribersMap,
 
@@ -90,8 +207,30 @@ pub struct ConnectionHandler {
     /// Session ID filter for subscriptions (None = all sessions)
     subscription_filter: Option<SessionId>,
 
+    /// Criteria filter for subscriptions (empty = all sessions)
+    session_filter: SubscriptionFilter,
+
+    /// Tmux client, used to resolve panes for `tmux_session` filters
+    tmux: Arc<dyn TmuxClient>,
+
     /// Counter for generating client IDs
     connection_number: u64,
+
+    /// Protocol version the client announced in its handshake
+    client_version: ProtocolVersion,
+
+    /// `request_id` of the message being handled, echoed on its replies
+    request_id: Option<u64>,
+
+    /// Token config for remote listeners; `None` on the local socket
+    remote: Option<Arc<RemoteConfig>>,
+
+    /// What this client may do (local clients get `Control`)
+    scope: AccessScope,
+
+    /// Slot among the server's pending remote connections, held until the
+    /// handshake completes
+    handshake_permit: Option<OwnedSemaphorePermit>,
 }
 
 impl ConnectionHandler {
@@ -99,30 +238,61 @@ impl ConnectionHandler {
     ///
     /// # Arguments
     ///
-    /// * `reader` - Read half of the Unix stream
-    /// * `writer` - Write half of the Unix stream
+    /// * `reader` - Read half of the client stream
+    /// * `writer` - Write half of the client stream
     /// * `registry` - Handle to the session registry
+    /// * `tmux` - Tmux client used to execute control requests
     /// * `subscribers` - Shared map of event subscribers
     /// * `connection_number` - Unique number for this connection
-    pub fn new(
-        reader: OwnedReadHalf,
-        writer: OwnedWriteHalf,
+    pub fn new<R, W>(
+        reader: R,
+        writer: W,
         registry: RegistryHandle,
+        tmux: Arc<dyn TmuxClient>,
         subscribers: SubscribersMap,
         connection_number: u64,
-    ) -> Self {
+    ) -> Self
+    where
+        R: AsyncRead + Send + Sync + Unpin + 'static,
+        W: AsyncWrite + Send + Sync + Unpin + 'static,
+    {
+        let reader: BoxedReader = Box::new(reader);
+        let writer: BoxedWriter = Box::new(writer);
         Self {
             reader: BufReader::new(reader),
             writer: Arc::new(Mutex::new(BufWriter::new(writer))),
+            control: ControlService::new(registry.clone(), Arc::clone(&tmux)),
+            tmux,
             registry,
             subscribers,
             client_id: None,
             subscribed: false,
             subscription_filter: None,
+            session_filter: SubscriptionFilter::default(),
             connection_number,
+            client_version: ProtocolVersion::new(1, 0),
+            request_id: None,
+            remote: None,
+            scope: AccessScope::Control,
+            handshake_permit: None,
         }
     }
 
+    /// Requires the client to authenticate with one of `config`'s tokens
+    /// within [`HANDSHAKE_TIMEOUT`] and limits it to that token's scope.
+    /// Used for remote listeners.
+    ///
+    /// `permit` is released once the handshake finishes either way.
+    pub fn with_remote_auth(
+        mut self,
+        config: Arc<RemoteConfig>,
+        permit: OwnedSemaphorePermit,
+    ) -> Self {
+        self.remote = Some(config);
+        self.handshake_permit = Some(permit);
+        self
+    }
+
     /// Returns a clone of the writer for event broadcasting.
     pub fn writer_handle(&self) -> SubscriberWriter {
         Arc::clone(&self.writer)
@@ -135,8 +305,16 @@ impl ConnectionHandler {
     pub async fn run(mut self) -> Option<ClientId> {
         debug!(connection = self.connection_number, "New client connected");
 
-        // Perform protocol handshake
-        match self.handle_handshake().await {
+        // Perform protocol handshake; remote clients get a short deadline
+        let handshake = if self.remote.is_some() {
+            timeout(HANDSHAKE_TIMEOUT, self.handle_handshake())
+                .await
+                .unwrap_or(Err(ConnectionError::Timeout))
+        } else {
+            self.handle_handshake().await
+        };
+        self.handshake_permit = None;
+        match handshake {
             Ok(()) => {
                 debug!(
                     client_id = ?self.client_id,
@@ -173,8 +351,8 @@ impl ConnectionHandler {
     /// Expects a `Connect` message from the client, validates the protocol
     /// version, and responds with `Connected` or `Rejected`.
     async fn handle_handshake(&mut self) -> Result<(), ConnectionError> {
-        // Read first message with timeout
         let msg = self.read_message().await?;
+        self.request_id = msg.request_id;
 
         // Check version compatibility using the top-level protocol_version
         let client_version = msg.protocol_version;
@@ -200,12 +378,22 @@ impl ConnectionHandler {
         }
 
         match msg.message {
-            MessageType::Connect { client_id } => {
+            MessageType::Connect { client_id, token } => {
+                if let Some(ref remote) = self.remote {
+                    let Some(scope) = remote.authenticate(token.as_deref()) else {
+                        self.send_message(DaemonMessage::rejected("Invalid or missing token"))
+                            .await?;
+                        return Err(ConnectionError::Unauthorized);
+                    };
+                    self.scope = scope;
+                }
+
                 // Generate or use provided client ID
                 let assigned_id =
                     client_id.unwrap_or_else(|| format!("client-{}", self.connection_number));
 
                 self.client_id = Some(assigned_id.clone());
+                self.client_version = client_version;
 
                 // Send success response
                 self.send_message(DaemonMessage::connected(assigned_id))
@@ -246,6 +434,7 @@ impl ConnectionHandler {
             };
 
             // Process the message
+            self.request_id = msg.request_id;
             if let Err(e) = self.handle_message(msg).await {
                 error!(
                     client_id = ?self.client_id,
@@ -263,6 +452,20 @@ impl ConnectionHandler {
 
     /// Handles a single client message.
     async fn handle_message(&mut self, msg: ClientMessage) -> Result<(), ConnectionError> {
+        if !self.scope.allows(&msg.message) {
+            warn!(
+                client_id = ?self.client_id,
+                message_type = ?std::mem::discriminant(&msg.message),
+                "Rejected request outside token scope"
+            );
+            self.send_message(DaemonMessage::error_with_code(
+                "Not permitted with a read-only token",
+                "FORBIDDEN",
+            ))
+            .await?;
+            return Ok(());
+        }
+
         match msg.message {
             MessageType::Connect { .. } => {
                 // Already connected - send error
@@ -282,13 +485,32 @@ impl ConnectionHandler {
                 self.handle_pi_event(data).await?;
             }
 
+            MessageType::CodexEvent { data } => {
+                self.handle_codex_event(data).await?;
+            }
+
             MessageType::ListSessions => {
-                let sessions = self.registry.get_all_sessions().await;
-                self.send_message(DaemonMessage::session_list(sessions))
-                    .await?;
+                if self.subscribed && !self.session_filter.is_empty() {
+                    // Filtered subscribers resync through here; keep their
+                    // visible set in step with the list they receive.
+                    if let Some(sub) = self
+                        .subscribers
+                        .write()
+                        .await
+                        .get_mut(&self.connection_number)
+                    {
+                        sub.syncing = true;
+                    }
+                    let filter = self.session_filter.clone();
+                    self.sync_subscriber(&filter).await?;
+                } else {
+                    let sessions = self.registry.get_all_sessions().await;
+                    self.send_message(DaemonMessage::session_list(sessions))
+                        .await?;
+                }
             }
 
-            MessageType::Subscribe { session_id } => {
+            MessageType::Subscribe { session_id, filter } => {
                 // Get client_id - must be connected first
                 let client_id = match &self.client_id {
                     Some(id) => id.clone(),
@@ -299,53 +521,67 @@ impl ConnectionHandler {
                     }
                 };
 
-                // Add to subscribers map
-                {
+                // Register the subscriber up front, held back from broadcasts
+                // until its initial list is out. The lock is only taken for
+                // the map updates, never across the snapshot or the send.
+                let rejection = {
                     let mut subs = self.subscribers.write().await;
-
-                    // Check max clients limit
-                    if subs.len() >= MAX_TUI_CLIENTS && !subs.contains_key(&client_id) {
-                        self.send_message(DaemonMessage::error(&format!(
-                            "Too many subscribers (max: {MAX_TUI_CLIENTS})"
-                        )))
-                        .await?;
-                        return Ok(());
+                    let key = self.connection_number;
+                    if subs
+                        .iter()
+                        .any(|(other, sub)| *other != key && sub.client_id == client_id)
+                    {
+                        Some(format!(
+                            "Client id '{client_id}' is already subscribed on another connection"
+                        ))
+                    } else if subs.len() >= MAX_TUI_CLIENTS && !subs.contains_key(&key) {
+                        Some(format!("Too many subscribers (max: {MAX_TUI_CLIENTS})"))
+                    } else {
+                        subs.insert(
+                            key,
+                            Subscriber {
+                                client_id: client_id.clone(),
+                                writer: Arc::clone(&self.writer),
+                                filter: session_id.clone(),
+                                scope: SubscriptionScope::new(filter.clone()),
+                                supports_patches: self.client_version.supports_session_patch(),
+                                syncing: true,
+                                missed_while_syncing: false,
+                            },
+                        );
+                        None
                     }
-
-                    // Add or update subscription
-                    subs.insert(
-                        client_id.clone(),
-                        Subscriber {
-                            writer: Arc::clone(&self.writer),
-                            filter: session_id.clone(),
-                        },
-                    );
+                };
+                if let Some(reason) = rejection {
+                    self.send_message(DaemonMessage::error(&reason)).await?;
+                    return Ok(());
                 }
 
                 self.subscribed = true;
                 self.subscription_filter = session_id;
+                self.session_filter = filter.clone();
 
                 debug!(
                     client_id = %client_id,
                     filter = ?self.subscription_filter,
+                    criteria = ?self.session_filter,
                     "Client subscribed to updates"
                 );
 
                 // Send current session list as initial state
-                let sessions = self.registry.get_all_sessions().await;
-                self.send_message(DaemonMessage::session_list(sessions))
-                    .await?;
+                self.sync_subscriber(&filter).await?;
             }
 
             MessageType::Unsubscribe => {
                 // Remove from subscribers map
-                if let Some(ref client_id) = self.client_id {
-                    let mut subs = self.subscribers.write().await;
-                    subs.remove(client_id);
-                }
+                self.subscribers
+                    .write()
+                    .await
+                    .remove(&self.connection_number);
 
                 self.subscribed = false;
                 self.subscription_filter = None;
+                self.session_filter = SubscriptionFilter::default();
 
                 debug!(
                     client_id = ?self.client_id,
@@ -371,6 +607,30 @@ impl ConnectionHandler {
                 debug!(client_id = ?self.client_id, "Client requested disconnect");
                 return Err(ConnectionError::Eof);
             }
+
+            MessageType::KillSession { target } => {
+                let result = self.control.kill(&target).await;
+                self.send_control_result(ControlAction::Kill, &target, result)
+                    .await?;
+            }
+
+            MessageType::InterruptSession { target } => {
+                let result = self.control.interrupt(&target).await;
+                self.send_control_result(ControlAction::Interrupt, &target, result)
+                    .await?;
+            }
+
+            MessageType::SendInput { target, text } => {
+                let result = self.control.send_input(&target, &text).await;
+                self.send_control_result(ControlAction::SendInput, &target, result)
+                    .await?;
+            }
+
+            MessageType::ReplyPrompt { target, choice } => {
+                let result = self.control.reply(&target, choice).await;
+                self.send_control_result(ControlAction::Reply, &target, result)
+                    .await?;
+            }
         }
 
         Ok(())
@@ -442,7 +702,7 @@ impl ConnectionHandler {
 
         self.registry
             .apply_lifecycle_event(
-                session_id,
+                session_id.clone(),
                 lifecycle,
                 atm_core::Harness::ClaudeCode,
                 pid,
@@ -451,9 +711,24 @@ impl ConnectionHandler {
             .await
             .map_err(|e| ConnectionError::RegistryError(e.to_string()))?;
 
+        self.tag_team(session_id, raw_event.team).await;
         Ok(())
     }
 
+    /// Records the team a hook script or extension injected, if any.
+    ///
+    /// A failure here never fails the event itself: the lifecycle update
+    /// already landed, and a session that vanished in between has no
+    /// team to record.
+    async fn tag_team(&self, session_id: atm_core::SessionId, team: Option<String>) {
+        let Some(team) = team else {
+            return;
+        };
+        if let Err(e) = self.registry.set_team(session_id.clone(), team).await {
+            debug!(session_id = %session_id, error = %e, "failed to record session team");
+        }
+    }
+
     /// Handles a pi event from the pi extension.
     ///
     /// Symmetric with [`Self::handle_hook_event`] — parses raw pi-shaped
@@ -504,7 +779,7 @@ impl ConnectionHandler {
 
         self.registry
             .apply_lifecycle_event(
-                session_id,
+                session_id.clone(),
                 lifecycle,
                 atm_core::Harness::Pi,
                 raw_event.pid,
@@ -513,6 +788,67 @@ impl ConnectionHandler {
             .await
             .map_err(|e| ConnectionError::RegistryError(e.to_string()))?;
 
+        self.tag_team(session_id, raw_event.team).await;
+        Ok(())
+    }
+
+    /// Handles an event forwarded by the `atm-codex-hook` notify script.
+    ///
+    /// Same shape as [`Self::handle_pi_event`]: translate through
+    /// `atm-codex-adapter`, fall back to a pid-derived pending id when
+    /// the payload carries no thread id, then hand off to the registry.
+    async fn handle_codex_event(&mut self, data: serde_json::Value) -> Result<(), ConnectionError> {
+        debug!(client_id = ?self.client_id, "Received codex event data");
+
+        let raw_event: RawCodexEvent =
+            serde_json::from_value(data).map_err(|e| ConnectionError::ParseError(e.to_string()))?;
+
+        debug!(
+            thread_id = ?raw_event.thread_id,
+            event = %raw_event.event,
+            pid = ?raw_event.pid,
+            tmux_pane = ?raw_event.tmux_pane,
+            "Processing codex event"
+        );
+
+        // The model rides along on other events; apply it after the
+        // event itself so a `session-start` creates the session first.
+        let events: Vec<_> = raw_event
+            .to_lifecycle_event()
+            .into_iter()
+            .chain(raw_event.model_change())
+            .collect();
+        if events.is_empty() {
+            debug!(event = %raw_event.event, "codex event suppressed by adapter");
+            return Ok(());
+        }
+
+        let session_id = match raw_event.session_id() {
+            Some(id) => id,
+            None => raw_event
+                .pid
+                .map(atm_core::SessionId::pending_from_pid)
+                .ok_or_else(|| {
+                    ConnectionError::ParseError(
+                        "codex event missing both thread-id and pid; cannot attribute".to_string(),
+                    )
+                })?,
+        };
+
+        for lifecycle in events {
+            self.registry
+                .apply_lifecycle_event(
+                    session_id.clone(),
+                    lifecycle,
+                    atm_core::Harness::Codex,
+                    raw_event.pid,
+                    raw_event.tmux_pane.clone(),
+                )
+                .await
+                .map_err(|e| ConnectionError::RegistryError(e.to_string()))?;
+        }
+
+        self.tag_team(session_id, raw_event.team).await;
         Ok(())
     }
 
@@ -524,6 +860,49 @@ impl ConnectionHandler {
         discovery.discover().await
     }
 
+    /// Logs a control request and reports its outcome to the client.
+    ///
+    /// Every control action is logged at info so the daemon log doubles as
+    /// an audit trail of who did what to which pane. Failures are answered
+    /// with a coded `Error` rather than tearing down the connection.
+    async fn send_control_result(
+        &self,
+        action: ControlAction,
+        target: &str,
+        result: Result<ControlOutcome, ControlError>,
+    ) -> Result<(), ConnectionError> {
+        match result {
+            Ok(outcome) => {
+                info!(
+                    client_id = ?self.client_id,
+                    ?action,
+                    target,
+                    session_id = %outcome.session_id,
+                    pane_id = %outcome.pane_id,
+                    "Control action executed"
+                );
+                self.send_message(DaemonMessage::control_result(
+                    outcome.action,
+                    Some(outcome.session_id),
+                    outcome.pane_id,
+                    outcome.keys,
+                ))
+                .await
+            }
+            Err(e) => {
+                warn!(
+                    client_id = ?self.client_id,
+                    ?action,
+                    target,
+                    error = %e,
+                    "Control action failed"
+                );
+                self.send_message(DaemonMessage::error_with_code(&e.to_string(), e.code()))
+                    .await
+            }
+        }
+    }
+
     /// Reads a single message from the client.
     async fn read_message(&mut self) -> Result<ClientMessage, ConnectionError> {
         let mut line = String::new();
@@ -557,8 +936,9 @@ impl ConnectionHandler {
         Ok(msg)
     }
 
-    /// Sends a message to the client.
+    /// Sends a reply to the client, tagged with the current `request_id`.
     async fn send_message(&self, msg: DaemonMessage) -> Result<(), ConnectionError> {
+        let msg = msg.with_request_id(self.request_id);
         let json =
             serde_json::to_string(&msg).map_err(|e| ConnectionError::ParseError(e.to_string()))?;
 
@@ -583,6 +963,65 @@ impl ConnectionHandler {
         self.subscribed
     }
 
+    /// Sends a syncing subscriber its session list and releases it to
+    /// broadcasts.
+    ///
+    /// The snapshot and the write happen outside the subscribers lock. If a
+    /// broadcast was held back meanwhile the list may already be stale, so
+    /// it is rebuilt and sent again (a few times at most; clients resync on
+    /// revision gaps after that).
+    async fn sync_subscriber(
+        &mut self,
+        filter: &SubscriptionFilter,
+    ) -> Result<(), ConnectionError> {
+        for attempt in 1..=MAX_SYNC_ATTEMPTS {
+            let sessions = self.filtered_sessions(filter).await;
+            {
+                let mut subs = self.subscribers.write().await;
+                let Some(sub) = subs.get_mut(&self.connection_number) else {
+                    return Ok(());
+                };
+                sub.scope.reset_visible(&sessions);
+                sub.missed_while_syncing = false;
+            }
+
+            self.send_message(DaemonMessage::session_list(sessions))
+                .await?;
+
+            let mut subs = self.subscribers.write().await;
+            let Some(sub) = subs.get_mut(&self.connection_number) else {
+                return Ok(());
+            };
+            if !sub.missed_while_syncing || attempt == MAX_SYNC_ATTEMPTS {
+                sub.syncing = false;
+                sub.missed_while_syncing = false;
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns all sessions matching `filter`.
+    async fn filtered_sessions(&self, filter: &SubscriptionFilter) -> Vec<SessionView> {
+        let sessions = self.registry.get_all_sessions().await;
+        if filter.is_empty() {
+            return sessions;
+        }
+
+        let mut panes = PaneSessions::new(Arc::clone(&self.tmux));
+        let mut matching = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            let tmux_session = match session.tmux_pane.as_deref() {
+                Some(pane) if filter.needs_tmux_session() => panes.session_of(pane).await,
+                _ => None,
+            };
+            if filter.matches(&session, tmux_session.as_deref()) {
+                matching.push(session);
+            }
+        }
+        matching
+    }
+
     /// Checks if an event should be sent to this client based on filter.
     pub fn should_receive_event(&self, session_id: &SessionId) -> bool {
         if !self.subscribed {
@@ -633,6 +1072,9 @@ pub enum ConnectionError {
 
     #[error("Registry error: {0}")]
     RegistryError(String),
+
+    #[error("Client failed token authentication")]
+    Unauthorized,
 }
 
 /// Sends an event to a subscribed client.
@@ -640,7 +1082,7 @@ pub enum ConnectionError {
 /// This is used by the server to broadcast events to all subscribers.
 #[allow(dead_code)]
 pub async fn send_event(
-    writer: &Arc<Mutex<BufWriter<OwnedWriteHalf>>>,
+    writer: &SubscriberWriter,
     event: &SessionEvent,
 ) -> Result<(), ConnectionError> {
     let msg = match event {
@@ -650,9 +1092,14 @@ pub async fn send_event(
             return Ok(());
         }
         SessionEvent::Updated { session } => DaemonMessage::session_updated((**session).clone()),
-        SessionEvent::Removed { session_id, .. } => {
-            DaemonMessage::session_removed(session_id.clone())
-        }
+        SessionEvent::Removed { session_id, reason } => match reason.replaced_by() {
+            Some(new_id) => DaemonMessage::session_replaced(session_id.clone(), new_id.clone()),
+            None => DaemonMessage::session_removed(session_id.clone()),
+        },
+        // Clients see the `over_budget` flag on the following update
+        SessionEvent::BudgetExceeded { .. } => return Ok(()),
+        // Internal to the daemon (auto-approval); the update went out already
+        SessionEvent::PermissionRequested { .. } => return Ok(()),
     };
 
     let json =
@@ -696,4 +1143,54 @@ mod tests {
         };
         assert!(err.to_string().contains("2000000"));
     }
+
+    fn view(id: &str, status: atm_core::SessionStatus) -> SessionView {
+        let mut domain = atm_core::SessionDomain::new(
+            SessionId::new(id),
+            atm_core::AgentType::GeneralPurpose,
+            atm_core::Model::from_id("claude-sonnet-4"),
+        );
+        domain.status = status;
+        SessionView::from_domain(&domain)
+    }
+
+    #[test]
+    fn test_unfiltered_scope_passes_everything() {
+        let mut scope = SubscriptionScope::default();
+        let idle = view("a", atm_core::SessionStatus::Idle);
+
+        assert_eq!(
+            scope.on_update(&idle, None),
+            Delivery::Update { fresh: false }
+        );
+        assert_eq!(
+            scope.on_removed(&SessionId::new("never-seen")),
+            Delivery::Removed
+        );
+    }
+
+    #[test]
+    fn test_filtered_scope_tracks_entry_and_exit() {
+        let mut scope = SubscriptionScope::new(SubscriptionFilter {
+            status: vec![atm_core::SessionStatus::Working],
+            ..Default::default()
+        });
+        let working = view("a", atm_core::SessionStatus::Working);
+        let idle = view("a", atm_core::SessionStatus::Idle);
+
+        assert_eq!(scope.on_update(&idle, None), Delivery::Skip);
+        assert_eq!(
+            scope.on_update(&working, None),
+            Delivery::Update { fresh: true }
+        );
+        assert_eq!(
+            scope.on_update(&working, None),
+            Delivery::Update { fresh: false }
+        );
+        assert_eq!(scope.on_update(&idle, None), Delivery::Removed);
+        assert_eq!(scope.on_removed(&working.id), Delivery::Skip);
+
+        scope.reset_visible([&working]);
+        assert_eq!(scope.on_removed(&working.id), Delivery::Removed);
+    }
 }
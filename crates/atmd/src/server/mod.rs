@@ -2,6 +2,8 @@
 //!
 //! The server:
 //! - Listens on a Unix socket for client connections
+//! - Optionally listens on TCP and WebSocket for token-authenticated
+//!   remote clients (see [`crate::remote`])
 //! - Spawns a ConnectionHandler for each client
 //! - Manages event subscriptions and broadcasts
 //! - Supports graceful shutdown via CancellationToken
@@ -37,32 +39,51 @@
 //! - Server errors are logged and allow continued operation
 
 mod connection;
+mod panes;
+mod websocket;
 
 pub use connection::{
-    ConnectionError, ConnectionHandler, Subscriber, SubscriberWriter, SubscribersMap,
+    BoxedReader, BoxedWriter, ConnectionError, ConnectionHandler, Delivery, Subscriber,
+    SubscriberWriter, SubscribersMap, SubscriptionScope,
 };
+pub use panes::PaneSessions;
+
+use connection::WRITE_TIMEOUT;
 
 use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
-use tokio::net::UnixListener;
-use tokio::sync::{broadcast, RwLock};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream, UnixListener};
+use tokio::sync::{broadcast, OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
-use atm_core::SessionId;
-use atm_protocol::DaemonMessage;
+use atm_core::{SessionId, SessionView};
+use atm_protocol::socket::{current_uid, default_socket_path};
+use atm_protocol::{diff_session_views, DaemonMessage};
+use atm_tmux::{RealTmuxClient, TmuxClient};
 
 use crate::registry::{RegistryHandle, SessionEvent};
+use crate::remote::RemoteConfig;
+
+/// Mode of the directory holding the socket (owner only)
+const SOCKET_DIR_MODE: u32 = 0o700;
 
-/// Default socket path
-pub const DEFAULT_SOCKET_PATH: &str = "/tmp/atm.sock";
+/// Mode of the socket itself (owner read/write)
+const SOCKET_MODE: u32 = 0o600;
 
 /// Maximum number of concurrent TUI clients
 const MAX_TUI_CLIENTS: usize = 10;
 
+/// Maximum number of remote connections that have not authenticated yet
+const MAX_PENDING_REMOTE: usize = 16;
+
 /// Unix socket server for the ATM daemon.
 ///
 /// Manages client connections and event broadcasting.
@@ -79,8 +100,17 @@ pub struct DaemonServer {
     /// Connection counter for generating client IDs
     connection_counter: AtomicU64,
 
-    /// Active TUI subscribers (keyed by client_id)
+    /// Active TUI subscribers (keyed by connection number)
     subscribers: SubscribersMap,
+
+    /// Tmux client shared by all connections for control requests
+    tmux: Arc<dyn TmuxClient>,
+
+    /// Remote listener addresses and tokens; `None` keeps the daemon local
+    remote: Option<Arc<RemoteConfig>>,
+
+    /// Slots for remote connections still in their handshake
+    pending_remote: Arc<Semaphore>,
 }
 
 impl DaemonServer {
@@ -102,12 +132,31 @@ impl DaemonServer {
             cancel_token,
             connection_counter: AtomicU64::new(0),
             subscribers: Arc::new(RwLock::new(HashMap::new())),
+            tmux: Arc::new(RealTmuxClient::new()),
+            remote: None,
+            pending_remote: Arc::new(Semaphore::new(MAX_PENDING_REMOTE)),
         }
     }
 
-    /// Creates a server with the default socket path.
+    /// Replaces the tmux client used to execute control requests.
+    ///
+    /// Defaults to [`RealTmuxClient`]; tests inject a `MockTmuxClient`.
+    pub fn with_tmux_client(mut self, tmux: Arc<dyn TmuxClient>) -> Self {
+        self.tmux = tmux;
+        self
+    }
+
+    /// Enables the TCP and/or WebSocket listeners described by `config`.
+    ///
+    /// Clients on those listeners must authenticate with one of its tokens.
+    pub fn with_remote(mut self, config: RemoteConfig) -> Self {
+        self.remote = Some(Arc::new(config));
+        self
+    }
+
+    /// Creates a server with the per-user default socket path.
     pub fn with_default_path(registry: RegistryHandle, cancel_token: CancellationToken) -> Self {
-        Self::new(DEFAULT_SOCKET_PATH, registry, cancel_token)
+        Self::new(default_socket_path(), registry, cancel_token)
     }
 
     /// Returns the socket path.
@@ -130,12 +179,10 @@ impl DaemonServer {
 
         // Create parent directory if needed
         if let Some(parent) = self.socket_path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent).map_err(|e| ServerError::SocketSetup {
-                    path: self.socket_path.clone(),
-                    error: e.to_string(),
-                })?;
-            }
+            prepare_socket_dir(parent).map_err(|error| ServerError::SocketSetup {
+                path: self.socket_path.clone(),
+                error,
+            })?;
         }
 
         // Bind to the Unix socket
@@ -144,12 +191,24 @@ impl DaemonServer {
                 path: self.socket_path.clone(),
                 error: e.to_string(),
             })?;
+        std::fs::set_permissions(
+            &self.socket_path,
+            std::fs::Permissions::from_mode(SOCKET_MODE),
+        )
+        .map_err(|e| ServerError::SocketSetup {
+            path: self.socket_path.clone(),
+            error: e.to_string(),
+        })?;
 
         info!(
             socket = %self.socket_path.display(),
             "Daemon server listening"
         );
 
+        let remote = self.remote.as_deref();
+        let tcp_listener = bind_remote(remote.and_then(|r| r.tcp_listen), "TCP").await?;
+        let ws_listener = bind_remote(remote.and_then(|r| r.websocket_listen), "WebSocket").await?;
+
         // Spawn event broadcaster
         self.spawn_event_broadcaster();
 
@@ -166,6 +225,9 @@ impl DaemonServer {
                 result = listener.accept() => {
                     match result {
                         Ok((stream, _addr)) => {
+                            if !peer_is_same_user(&stream) {
+                                continue;
+                            }
                             let conn_num = self.connection_counter.fetch_add(1, Ordering::Relaxed);
                             self.handle_connection(stream, conn_num);
                         }
@@ -175,6 +237,38 @@ impl DaemonServer {
                         }
                     }
                 }
+
+                result = accept_remote(tcp_listener.as_ref()) => {
+                    match result {
+                        Ok((stream, peer)) => {
+                            let Some(permit) = self.pending_remote_slot(peer) else {
+                                continue;
+                            };
+                            debug!(%peer, "Accepted TCP connection");
+                            let _ = stream.set_nodelay(true);
+                            let conn_num = self.connection_counter.fetch_add(1, Ordering::Relaxed);
+                            let (reader, writer) = stream.into_split();
+                            self.spawn_handler(reader, writer, conn_num, Some(permit));
+                        }
+                        Err(e) => error!(error = %e, "Failed to accept TCP connection"),
+                    }
+                }
+
+                result = accept_remote(ws_listener.as_ref()) => {
+                    match result {
+                        Ok((stream, peer)) => {
+                            let Some(permit) = self.pending_remote_slot(peer) else {
+                                continue;
+                            };
+                            debug!(%peer, "Accepted WebSocket connection");
+                            let _ = stream.set_nodelay(true);
+                            let conn_num = self.connection_counter.fetch_add(1, Ordering::Relaxed);
+                            let (reader, writer) = tokio::io::split(websocket::bridge(stream, peer));
+                            self.spawn_handler(reader, writer, conn_num, Some(permit));
+                        }
+                        Err(e) => error!(error = %e, "Failed to accept WebSocket connection"),
+                    }
+                }
             }
         }
 
@@ -186,27 +280,63 @@ impl DaemonServer {
     /// Handles a new client connection by spawning a handler task.
     fn handle_connection(&self, stream: tokio::net::UnixStream, connection_number: u64) {
         let (reader, writer) = stream.into_split();
+        self.spawn_handler(reader, writer, connection_number, None);
+    }
+
+    /// Reserves a handshake slot for a remote connection from `peer`, or
+    /// returns `None` (dropping the connection) when all are taken.
+    fn pending_remote_slot(&self, peer: SocketAddr) -> Option<OwnedSemaphorePermit> {
+        match Arc::clone(&self.pending_remote).try_acquire_owned() {
+            Ok(permit) => Some(permit),
+            Err(_) => {
+                warn!(
+                    %peer,
+                    max = MAX_PENDING_REMOTE,
+                    "Too many unauthenticated remote connections; dropping"
+                );
+                None
+            }
+        }
+    }
+
+    /// Spawns a handler task for any transport.
+    ///
+    /// Remote connections carry a `handshake` slot and must authenticate
+    /// against `self.remote` before it is released.
+    fn spawn_handler<R, W>(
+        &self,
+        reader: R,
+        writer: W,
+        connection_number: u64,
+        handshake: Option<OwnedSemaphorePermit>,
+    ) where
+        R: AsyncRead + Send + Sync + Unpin + 'static,
+        W: AsyncWrite + Send + Sync + Unpin + 'static,
+    {
         let registry = self.registry.clone();
+        let tmux = Arc::clone(&self.tmux);
         let subscribers = Arc::clone(&self.subscribers);
+        let remote_auth = handshake.and_then(|permit| Some((self.remote.clone()?, permit)));
 
         tokio::spawn(async move {
-            let handler = ConnectionHandler::new(
+            let mut handler = ConnectionHandler::new(
                 reader,
                 writer,
                 registry,
+                tmux,
                 Arc::clone(&subscribers),
                 connection_number,
             );
+            if let Some((config, permit)) = remote_auth {
+                handler = handler.with_remote_auth(config, permit);
+            }
 
-            // Run the handler and get the client_id when done
-            let client_id = handler.run().await;
+            handler.run().await;
 
             // Remove from subscribers if was subscribed
-            if let Some(id) = client_id {
-                let mut subs = subscribers.write().await;
-                if subs.remove(&id).is_some() {
-                    debug!(client_id = %id, "Removed disconnected subscriber");
-                }
+            let mut subs = subscribers.write().await;
+            if let Some(sub) = subs.remove(&connection_number) {
+                debug!(client_id = %sub.client_id, "Removed disconnected subscriber");
             }
         });
     }
@@ -219,6 +349,10 @@ impl DaemonServer {
         let mut event_rx = self.registry.subscribe();
         let subscribers = Arc::clone(&self.subscribers);
         let cancel_token = self.cancel_token.clone();
+        let mut state = BroadcastState {
+            last_views: HashMap::new(),
+            panes: PaneSessions::new(Arc::clone(&self.tmux)),
+        };
 
         tokio::spawn(async move {
             loop {
@@ -231,7 +365,7 @@ impl DaemonServer {
                     result = event_rx.recv() => {
                         match result {
                             Ok(event) => {
-                                broadcast_event(&subscribers, &event).await;
+                                broadcast_event(&subscribers, &mut state, &event).await;
                             }
                             Err(broadcast::error::RecvError::Lagged(n)) => {
                                 warn!(skipped = n, "Event broadcaster lagged, skipped events");
@@ -247,9 +381,10 @@ impl DaemonServer {
         });
     }
 
-    /// Adds a subscriber for event broadcasts.
+    /// Adds a subscriber for event broadcasts under `connection_number`.
     pub async fn add_subscriber(
         &self,
+        connection_number: u64,
         client_id: String,
         writer: SubscriberWriter,
         filter: Option<SessionId>,
@@ -262,17 +397,28 @@ impl DaemonServer {
             });
         }
 
-        subs.insert(client_id.clone(), Subscriber { writer, filter });
+        subs.insert(
+            connection_number,
+            Subscriber {
+                client_id: client_id.clone(),
+                writer,
+                filter,
+                scope: SubscriptionScope::default(),
+                supports_patches: false,
+                syncing: false,
+                missed_while_syncing: false,
+            },
+        );
         debug!(client_id = %client_id, "Added subscriber");
 
         Ok(())
     }
 
-    /// Removes a subscriber.
-    pub async fn remove_subscriber(&self, client_id: &str) {
+    /// Removes the subscriber on connection `connection_number`.
+    pub async fn remove_subscriber(&self, connection_number: u64) {
         let mut subs = self.subscribers.write().await;
-        if subs.remove(client_id).is_some() {
-            debug!(client_id = %client_id, "Removed subscriber");
+        if let Some(sub) = subs.remove(&connection_number) {
+            debug!(client_id = %sub.client_id, "Removed subscriber");
         }
     }
 
@@ -304,40 +450,115 @@ impl DaemonServer {
     }
 }
 
+/// Serializes a daemon message for the wire, logging on failure.
+fn encode_event(msg: &DaemonMessage) -> Option<String> {
+    match serde_json::to_string(msg) {
+        Ok(j) => Some(j),
+        Err(e) => {
+            error!(error = %e, "Failed to serialize event");
+            None
+        }
+    }
+}
+
+/// Builds the `SessionPatch` for an update, if `last_views` holds the
+/// immediately preceding revision of the session.
+fn session_patch(
+    last_views: &HashMap<SessionId, SessionView>,
+    session: &SessionView,
+) -> Option<DaemonMessage> {
+    let previous = last_views.get(&session.id)?;
+    if previous.revision.checked_add(1) != Some(session.revision) {
+        return None;
+    }
+    match diff_session_views(previous, session) {
+        Ok(changes) => Some(DaemonMessage::session_patch(
+            session.id.clone(),
+            session.revision,
+            changes,
+        )),
+        Err(e) => {
+            warn!(session_id = %session.id, error = %e, "Failed to diff session views");
+            None
+        }
+    }
+}
+
+/// State owned by the event broadcaster task.
+struct BroadcastState {
+    /// Last view broadcast per session, the base for `SessionPatch` diffs
+    last_views: HashMap<SessionId, SessionView>,
+    /// Pane → tmux session index for `tmux_session` subscription filters
+    panes: PaneSessions,
+}
+
 /// Broadcasts an event to all subscribed clients.
-async fn broadcast_event(subscribers: &SubscribersMap, event: &SessionEvent) {
+///
+/// Each subscriber's [`SubscriptionScope`] decides whether it gets the
+/// event at all. Clients that support it receive a `SessionPatch` against
+/// the last broadcast view; everyone else, every update that skips a
+/// revision, and every session newly entering a filter get the full
+/// `SessionUpdated`.
+async fn broadcast_event(
+    subscribers: &SubscribersMap,
+    state: &mut BroadcastState,
+    event: &SessionEvent,
+) {
     // Get session_id from event for filtering
-    let session_id = match event {
-        SessionEvent::Registered { session_id, .. } => session_id,
-        SessionEvent::Updated { session } => &session.id,
-        SessionEvent::Removed { session_id, .. } => session_id,
-    };
-
-    // Build the message once
-    let msg = match event {
+    let (session_id, replaced_by) = match event {
         SessionEvent::Registered { .. } => {
             // Don't broadcast registration - wait for first update with data
             return;
         }
-        SessionEvent::Updated { session } => DaemonMessage::session_updated((**session).clone()),
-        SessionEvent::Removed { session_id, .. } => {
-            DaemonMessage::session_removed(session_id.clone())
-        }
+        SessionEvent::Updated { session } => (&session.id, None),
+        SessionEvent::Removed { session_id, reason } => (session_id, reason.replaced_by()),
+        // Clients see the `over_budget` flag on the following update
+        SessionEvent::BudgetExceeded { .. } => return,
+        // Internal to the daemon (auto-approval); the update went out already
+        SessionEvent::PermissionRequested { .. } => return,
     };
 
-    let json = match serde_json::to_string(&msg) {
-        Ok(j) => j,
-        Err(e) => {
-            error!(error = %e, "Failed to serialize event");
+    // Build the messages once
+    let removed_json = encode_event(&match replaced_by {
+        Some(new_id) => DaemonMessage::session_replaced(session_id.clone(), new_id.clone()),
+        None => DaemonMessage::session_removed(session_id.clone()),
+    })
+    .map(Arc::<str>::from);
+    let mut update = None;
+    if let SessionEvent::Updated { session } = event {
+        let patch_json = session_patch(&state.last_views, session)
+            .as_ref()
+            .and_then(encode_event)
+            .map(Arc::<str>::from);
+        state
+            .last_views
+            .insert(session.id.clone(), (**session).clone());
+        let Some(full_json) = encode_event(&DaemonMessage::session_updated((**session).clone()))
+        else {
             return;
-        }
-    };
-
-    // Send to all matching subscribers
-    let subs = subscribers.read().await;
-    let mut failed_clients = Vec::new();
+        };
+        let full_json = Arc::<str>::from(full_json);
+
+        let needs_tmux = subscribers
+            .read()
+            .await
+            .values()
+            .any(|sub| sub.scope.filter().needs_tmux_session());
+        let tmux_session = match session.tmux_pane.as_deref() {
+            Some(pane) if needs_tmux => state.panes.session_of(pane).await,
+            _ => None,
+        };
+        update = Some((session, full_json, patch_json, tmux_session));
+    } else {
+        state.last_views.remove(session_id);
+    }
 
-    for (client_id, sub) in subs.iter() {
+    // Decide what each subscriber gets under the lock, but write after
+    // releasing it: a peer that stops reading must not hold up the others,
+    // or block Subscribe/ListSessions waiting on the lock.
+    let mut deliveries = Vec::new();
+    let mut subs = subscribers.write().await;
+    for (key, sub) in subs.iter_mut() {
         // Check filter
         if let Some(ref filter) = sub.filter {
             if filter != session_id {
@@ -345,45 +566,179 @@ async fn broadcast_event(subscribers: &SubscribersMap, event: &SessionEvent) {
             }
         }
 
-        // Try to send
-        let mut writer = sub.writer.lock().await;
-        let send_result = async {
-            use tokio::io::AsyncWriteExt;
-            writer.write_all(json.as_bytes()).await?;
-            writer.write_all(b"\n").await?;
-            writer.flush().await?;
-            Ok::<(), std::io::Error>(())
+        // The connection is still sending this client its session list
+        if sub.syncing {
+            sub.missed_while_syncing = true;
+            continue;
         }
-        .await;
 
-        if let Err(e) = send_result {
+        let delivery = match update {
+            Some((session, _, _, ref tmux_session)) => {
+                sub.scope.on_update(session, tmux_session.as_deref())
+            }
+            None => sub.scope.on_removed(session_id),
+        };
+        let line = match (delivery, &update) {
+            (Delivery::Skip, _) => continue,
+            (Delivery::Removed, _) => match removed_json {
+                Some(ref json) => json,
+                None => continue,
+            },
+            (Delivery::Update { fresh }, Some((_, full_json, patch_json, _))) => match patch_json {
+                Some(patch) if sub.supports_patches && !fresh => patch,
+                _ => full_json,
+            },
+            (Delivery::Update { .. }, None) => continue,
+        };
+        deliveries.push((
+            *key,
+            sub.client_id.clone(),
+            Arc::clone(&sub.writer),
+            Arc::clone(line),
+        ));
+    }
+    drop(subs);
+
+    let mut sends = JoinSet::new();
+    for (key, client_id, writer, line) in deliveries {
+        sends.spawn(async move {
+            let result = write_event_line(&writer, &line).await;
+            (key, client_id, result)
+        });
+    }
+    let mut failed_clients = Vec::new();
+    while let Some(joined) = sends.join_next().await {
+        if let Ok((key, client_id, Err(e))) = joined {
             debug!(
                 client_id = %client_id,
                 error = %e,
                 "Failed to send event to subscriber"
             );
-            failed_clients.push(client_id.clone());
+            failed_clients.push((key, client_id));
         }
     }
 
-    // Remove failed clients (need to drop read lock first)
-    drop(subs);
-
     if !failed_clients.is_empty() {
         let mut subs = subscribers.write().await;
-        for client_id in failed_clients {
-            subs.remove(&client_id);
+        for (key, client_id) in failed_clients {
+            subs.remove(&key);
             debug!(client_id = %client_id, "Removed failed subscriber");
         }
     }
 }
 
+/// Writes one event line to a subscriber, giving up after
+/// [`WRITE_TIMEOUT`] so a peer that stopped reading gets dropped.
+async fn write_event_line(writer: &SubscriberWriter, line: &str) -> Result<(), ConnectionError> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut writer = writer.lock().await;
+    match tokio::time::timeout(WRITE_TIMEOUT, async {
+        writer.write_all(line.as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+        writer.flush().await?;
+        Ok::<(), std::io::Error>(())
+    })
+    .await
+    {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(ConnectionError::Io(e.to_string())),
+        Err(_) => Err(ConnectionError::WriteTimeout),
+    }
+}
+
+/// Creates the socket directory owner-only, or checks an existing one.
+///
+/// Custom `ATM_SOCKET` locations (a test tempdir, a shared `/tmp`) are
+/// left as they are; the per-user default directory must belong to us and
+/// is tightened to 0700.
+fn prepare_socket_dir(dir: &Path) -> Result<(), String> {
+    if !dir.exists() {
+        return std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(SOCKET_DIR_MODE)
+            .create(dir)
+            .map_err(|e| e.to_string());
+    }
+
+    let is_default_dir = default_socket_path().parent() == Some(dir);
+    if !is_default_dir {
+        return Ok(());
+    }
+
+    let metadata = std::fs::metadata(dir).map_err(|e| e.to_string())?;
+    if metadata.uid() != current_uid() {
+        return Err(format!(
+            "{} is owned by uid {}, not {}",
+            dir.display(),
+            metadata.uid(),
+            current_uid()
+        ));
+    }
+    if metadata.mode() & 0o777 != SOCKET_DIR_MODE {
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(SOCKET_DIR_MODE))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Returns true if the peer on `stream` runs as the daemon's user
+/// (`SO_PEERCRED`). Connections whose credentials can't be read are
+/// refused too.
+fn peer_is_same_user(stream: &tokio::net::UnixStream) -> bool {
+    match stream.peer_cred() {
+        Ok(cred) if cred.uid() == current_uid() => true,
+        Ok(cred) => {
+            warn!(
+                peer_uid = cred.uid(),
+                peer_pid = ?cred.pid(),
+                "Rejected connection from another user"
+            );
+            false
+        }
+        Err(e) => {
+            warn!(error = %e, "Rejected connection with unreadable peer credentials");
+            false
+        }
+    }
+}
+
+/// Binds a remote listener if `addr` is configured.
+async fn bind_remote(
+    addr: Option<SocketAddr>,
+    transport: &str,
+) -> Result<Option<TcpListener>, ServerError> {
+    let Some(addr) = addr else {
+        return Ok(None);
+    };
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| ServerError::RemoteBind {
+            addr,
+            error: e.to_string(),
+        })?;
+    info!(%addr, transport, "Remote listener enabled");
+    Ok(Some(listener))
+}
+
+/// Accepts on `listener`, or waits forever if it isn't configured so the
+/// `select!` branch never fires.
+async fn accept_remote(listener: Option<&TcpListener>) -> std::io::Result<(TcpStream, SocketAddr)> {
+    match listener {
+        Some(listener) => listener.accept().await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Errors that can occur in server operations.
 #[derive(Debug, thiserror::Error)]
 pub enum ServerError {
     #[error("Failed to setup socket at {path}: {error}")]
     SocketSetup { path: PathBuf, error: String },
 
+    #[error("Failed to bind remote listener on {addr}: {error}")]
+    RemoteBind { addr: SocketAddr, error: String },
+
     #[error("Too many TUI clients (max: {max})")]
     TooManyClients { max: usize },
 
@@ -396,8 +751,24 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_default_socket_path() {
-        assert_eq!(DEFAULT_SOCKET_PATH, "/tmp/atm.sock");
+    fn test_default_socket_path_is_per_user() {
+        let path = default_socket_path();
+        assert_eq!(path.file_name().and_then(|n| n.to_str()), Some("atm.sock"));
+        assert_ne!(
+            path,
+            PathBuf::from(atm_protocol::socket::LEGACY_SOCKET_PATH)
+        );
+    }
+
+    #[test]
+    fn test_prepare_socket_dir_creates_owner_only() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("nested").join("atm");
+
+        prepare_socket_dir(&dir).unwrap();
+
+        let mode = std::fs::metadata(&dir).unwrap().mode() & 0o777;
+        assert_eq!(mode, SOCKET_DIR_MODE);
     }
 
     #[test]
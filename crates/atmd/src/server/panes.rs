@@ -0,0 +1,102 @@
+//! Pane → tmux session lookup for subscription filters.
+//!
+//! Sessions only know their pane ID (`%5`); a `tmux_session` subscription
+//! filter needs the name of the tmux session that owns the pane. The index
+//! is filled from `list-panes` and refreshed when a lookup misses (at most
+//! once per `MIN_REFRESH_INTERVAL`) or when it has gone stale.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - tmux failures leave the previous index in place
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use atm_tmux::TmuxClient;
+use tracing::debug;
+
+/// Minimum time between `list-panes` calls triggered by lookup misses.
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Age after which the index is refreshed even on a hit (panes can move
+/// between tmux sessions).
+const STALE_AFTER: Duration = Duration::from_secs(30);
+
+/// Cached mapping from tmux pane ID to owning tmux session name.
+pub struct PaneSessions {
+    tmux: Arc<dyn TmuxClient>,
+    by_pane: HashMap<String, String>,
+    refreshed_at: Option<Instant>,
+}
+
+impl PaneSessions {
+    /// Creates an empty index backed by `tmux`.
+    pub fn new(tmux: Arc<dyn TmuxClient>) -> Self {
+        Self {
+            tmux,
+            by_pane: HashMap::new(),
+            refreshed_at: None,
+        }
+    }
+
+    /// Returns the tmux session owning `pane`, if known.
+    pub async fn session_of(&mut self, pane: &str) -> Option<String> {
+        let age = self.refreshed_at.map(|at| at.elapsed());
+        let refresh = match age {
+            None => true,
+            Some(age) if age >= STALE_AFTER => true,
+            Some(age) => !self.by_pane.contains_key(pane) && age >= MIN_REFRESH_INTERVAL,
+        };
+        if refresh {
+            self.refresh().await;
+        }
+        self.by_pane.get(pane).cloned()
+    }
+
+    async fn refresh(&mut self) {
+        self.refreshed_at = Some(Instant::now());
+        match self.tmux.list_panes().await {
+            Ok(panes) => {
+                self.by_pane = panes
+                    .into_iter()
+                    .map(|p| (p.pane_id, p.session_name))
+                    .collect();
+            }
+            Err(e) => debug!(error = %e, "Failed to list tmux panes for filtering"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atm_tmux::{MockTmuxClient, PaneInfo};
+
+    fn pane(pane_id: &str, session_name: &str) -> PaneInfo {
+        PaneInfo {
+            pane_id: pane_id.to_string(),
+            session_name: session_name.to_string(),
+            window_index: 0,
+            pane_pid: 1,
+            width: 80,
+            height: 24,
+            is_active: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_of_resolves_and_caches() {
+        let mock = MockTmuxClient::new();
+        mock.set_panes(vec![pane("%1", "dev"), pane("%2", "ops")]);
+        let mut index = PaneSessions::new(Arc::new(mock.clone()));
+
+        assert_eq!(index.session_of("%1").await.as_deref(), Some("dev"));
+        assert_eq!(index.session_of("%2").await.as_deref(), Some("ops"));
+        // Unknown pane within the refresh interval doesn't hit tmux again
+        assert_eq!(index.session_of("%9").await, None);
+        assert_eq!(mock.call_count(), 1);
+    }
+}
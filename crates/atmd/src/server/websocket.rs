@@ -0,0 +1,146 @@
+//! WebSocket transport for remote clients.
+//!
+//! Each text frame carries one `ClientMessage` / `DaemonMessage` JSON
+//! document. The bridge turns a WebSocket into the newline-delimited byte
+//! stream `ConnectionHandler` already speaks, so the handler doesn't know
+//! which transport it is on. Frames spanning several lines (pretty-printed
+//! JSON) are re-serialized onto one line first.
+//!
+//! # Panic-Free Guarantees
+//!
+//! This module follows CLAUDE.md panic-free policy:
+//! - No `.unwrap()`, `.expect()`, `panic!()`, `unreachable!()`, `todo!()`
+//! - Handshake and frame errors end the bridge, which the handler sees as EOF
+
+use std::net::SocketAddr;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use tracing::debug;
+
+use atm_protocol::DaemonMessage;
+
+use super::connection::{HANDSHAKE_TIMEOUT, MAX_MESSAGE_SIZE};
+
+/// In-memory buffer between the WebSocket pump and the connection handler.
+const BRIDGE_BUFFER: usize = 64 * 1024;
+
+/// Accepts a WebSocket on `stream` and returns the handler's end of a
+/// newline-delimited bridge to it.
+///
+/// The handshake runs in the background; if it fails or takes longer than
+/// [`HANDSHAKE_TIMEOUT`] the returned stream simply reaches EOF.
+pub(crate) fn bridge(stream: TcpStream, peer: SocketAddr) -> DuplexStream {
+    let (handler_side, pump_side) = tokio::io::duplex(BRIDGE_BUFFER);
+
+    tokio::spawn(async move {
+        let config = WebSocketConfig {
+            max_message_size: Some(MAX_MESSAGE_SIZE),
+            max_frame_size: Some(MAX_MESSAGE_SIZE),
+            ..Default::default()
+        };
+
+        let upgrade = tokio_tungstenite::accept_async_with_config(stream, Some(config));
+        match timeout(HANDSHAKE_TIMEOUT, upgrade).await {
+            Ok(Ok(ws)) => pump(ws, pump_side, peer).await,
+            Ok(Err(e)) => debug!(%peer, error = %e, "WebSocket handshake failed"),
+            Err(_) => debug!(%peer, "WebSocket handshake timed out"),
+        }
+    });
+
+    handler_side
+}
+
+/// Copies text frames to `bridge` as lines and lines back as text frames
+/// until either side closes.
+async fn pump(ws: WebSocketStream<TcpStream>, bridge: DuplexStream, peer: SocketAddr) {
+    let (mut ws_tx, mut ws_rx) = ws.split();
+    let (bridge_rx, mut bridge_tx) = tokio::io::split(bridge);
+    let mut lines = BufReader::new(bridge_rx).lines();
+
+    loop {
+        tokio::select! {
+            frame = ws_rx.next() => match frame {
+                Some(Ok(Message::Text(text))) => {
+                    let Some(line) = single_line(text) else {
+                        // The handler never sees it, so answer here
+                        let reply = serde_json::to_string(&DaemonMessage::error(
+                            "Invalid JSON: multi-line frame is not a JSON document",
+                        ));
+                        if let Ok(reply) = reply {
+                            if ws_tx.send(Message::Text(reply)).await.is_err() {
+                                break;
+                            }
+                        }
+                        continue;
+                    };
+                    let written = async {
+                        bridge_tx.write_all(line.as_bytes()).await?;
+                        bridge_tx.write_all(b"\n").await
+                    };
+                    if written.await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                // Ping/pong are answered by tungstenite; binary isn't part
+                // of the protocol
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    debug!(%peer, error = %e, "WebSocket read failed");
+                    break;
+                }
+            },
+
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => {
+                    if let Err(e) = ws_tx.send(Message::Text(line)).await {
+                        debug!(%peer, error = %e, "WebSocket write failed");
+                        break;
+                    }
+                }
+                Ok(None) | Err(_) => break,
+            },
+        }
+    }
+
+    let _ = ws_tx.close().await;
+}
+
+/// Returns `text` as one line for the bridge: unchanged if it already is
+/// one, re-serialized compactly if it is JSON spread over several lines,
+/// and `None` if it spans lines but isn't JSON.
+fn single_line(text: String) -> Option<String> {
+    if !text.contains(['\n', '\r']) {
+        return Some(text);
+    }
+    serde_json::from_str::<serde_json::Value>(&text)
+        .ok()
+        .map(|value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_line_compacts_multi_line_json() {
+        let pretty = "{\n  \"type\": \"ping\",\n  \"seq\": 1\n}".to_string();
+        let line = single_line(pretty).unwrap();
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&line).unwrap(),
+            serde_json::json!({"type": "ping", "seq": 1})
+        );
+        assert_eq!(
+            single_line(r#"{"type":"ping"}"#.to_string()).as_deref(),
+            Some(r#"{"type":"ping"}"#)
+        );
+        assert_eq!(single_line("not\njson".to_string()), None);
+    }
+}
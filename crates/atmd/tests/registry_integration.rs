@@ -20,13 +20,17 @@ fn create_test_session(id: &str) -> SessionDomain {
     SessionDomain::new(
         SessionId::new(id),
         AgentType::GeneralPurpose,
-        Model::Sonnet4,
+        Model::from_id("claude-sonnet-4"),
     )
 }
 
 /// Helper to create a test session with a specific agent type.
 fn create_test_session_with_type(id: &str, agent_type: AgentType) -> SessionDomain {
-    SessionDomain::new(SessionId::new(id), agent_type, Model::Sonnet4)
+    SessionDomain::new(
+        SessionId::new(id),
+        agent_type,
+        Model::from_id("claude-sonnet-4"),
+    )
 }
 
 // ============================================================================
@@ -787,7 +791,7 @@ async fn test_session_view_fields() {
     let session = SessionDomain::new(
         SessionId::new("8e11bfb5-7dc2-432b-9206-928fa5c35731"),
         AgentType::Explore,
-        Model::Opus45,
+        Model::from_id("claude-opus-4-5"),
     );
     handle.register(session).await.unwrap();
 
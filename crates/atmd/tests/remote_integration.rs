@@ -0,0 +1,508 @@
+//! Integration tests for the token-gated TCP and WebSocket listeners.
+//!
+//! Each test starts a daemon with remote listeners on loopback ports and
+//! talks to it the way a remote dashboard would.
+//!
+//! Per CLAUDE.md: Tests CAN use `.unwrap()` and `.expect()` - this is allowed.
+//! We test the panic-free behavior of production code through assertions.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use atm_core::{AgentType, Model, SessionDomain, SessionId};
+use atm_protocol::{ClientMessage, ControlAction, DaemonMessage};
+use atm_tmux::mock::MockCall;
+use atm_tmux::MockTmuxClient;
+use atmd::registry::{spawn_registry, RegistryHandle};
+use atmd::remote::{AccessScope, RemoteConfig, RemoteToken};
+use atmd::server::DaemonServer;
+use futures_util::{SinkExt, StreamExt};
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::sleep;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+
+const READ_ONLY_TOKEN: &str = "viewer-token";
+const CONTROL_TOKEN: &str = "operator-token";
+
+/// Maximum time to wait for the remote listeners to come up
+const LISTEN_WAIT_TIMEOUT: Duration = Duration::from_millis(500);
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+struct RemoteTestServer {
+    tcp_addr: SocketAddr,
+    ws_addr: SocketAddr,
+    registry: RegistryHandle,
+    tmux: MockTmuxClient,
+    cancel_token: CancellationToken,
+    _temp_dir: TempDir,
+}
+
+/// Returns a loopback address that was free a moment ago.
+fn free_loopback_addr() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind ephemeral port");
+    listener.local_addr().expect("local addr")
+}
+
+impl RemoteTestServer {
+    async fn spawn() -> Self {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let tcp_addr = free_loopback_addr();
+        let ws_addr = free_loopback_addr();
+
+        let remote = RemoteConfig {
+            tcp_listen: Some(tcp_addr),
+            websocket_listen: Some(ws_addr),
+            tokens: vec![
+                RemoteToken {
+                    token: READ_ONLY_TOKEN.to_string(),
+                    scope: AccessScope::ReadOnly,
+                },
+                RemoteToken {
+                    token: CONTROL_TOKEN.to_string(),
+                    scope: AccessScope::Control,
+                },
+            ],
+        };
+
+        let registry = spawn_registry();
+        let cancel_token = CancellationToken::new();
+        let tmux = MockTmuxClient::new();
+        let server = DaemonServer::new(
+            temp_dir.path().join("test.sock"),
+            registry.clone(),
+            cancel_token.clone(),
+        )
+        .with_tmux_client(Arc::new(tmux.clone()))
+        .with_remote(remote);
+
+        tokio::spawn(async move {
+            let _ = server.run().await;
+        });
+
+        let start = tokio::time::Instant::now();
+        while TcpStream::connect(ws_addr).await.is_err() {
+            assert!(
+                start.elapsed() < LISTEN_WAIT_TIMEOUT,
+                "Remote listeners did not come up within {LISTEN_WAIT_TIMEOUT:?}"
+            );
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        Self {
+            tcp_addr,
+            ws_addr,
+            registry,
+            tmux,
+            cancel_token,
+            _temp_dir: temp_dir,
+        }
+    }
+
+    async fn connect_tcp(&self) -> TcpClient {
+        let stream = TcpStream::connect(self.tcp_addr)
+            .await
+            .expect("connect over TCP");
+        TcpClient::new(stream)
+    }
+
+    async fn register_in_pane(&self, id: &str, pane: &str) {
+        let mut session = SessionDomain::new(
+            SessionId::new(id),
+            AgentType::GeneralPurpose,
+            Model::from_id("claude-sonnet-4"),
+        );
+        session.tmux_pane = Some(pane.to_string());
+        self.registry
+            .register(session)
+            .await
+            .expect("register session");
+    }
+
+    fn shutdown(self) {
+        self.cancel_token.cancel();
+    }
+}
+
+struct TcpClient {
+    reader: BufReader<tokio::net::tcp::OwnedReadHalf>,
+    writer: tokio::net::tcp::OwnedWriteHalf,
+}
+
+impl TcpClient {
+    fn new(stream: TcpStream) -> Self {
+        let (reader, writer) = stream.into_split();
+        Self {
+            reader: BufReader::new(reader),
+            writer,
+        }
+    }
+
+    async fn send(&mut self, msg: ClientMessage) {
+        let json = serde_json::to_string(&msg).unwrap();
+        self.writer.write_all(json.as_bytes()).await.unwrap();
+        self.writer.write_all(b"\n").await.unwrap();
+        self.writer.flush().await.unwrap();
+    }
+
+    async fn recv(&mut self) -> DaemonMessage {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).await.unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    /// Connects with `token` and returns the daemon's reply.
+    async fn handshake(&mut self, token: Option<&str>) -> DaemonMessage {
+        let msg = match token {
+            Some(token) => ClientMessage::connect_with_token(None, token),
+            None => ClientMessage::connect(None),
+        };
+        self.send(msg).await;
+        self.recv().await
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_tcp_rejects_missing_or_unknown_token() {
+    let server = RemoteTestServer::spawn().await;
+
+    for token in [None, Some("not-a-token")] {
+        let mut client = server.connect_tcp().await;
+        match client.handshake(token).await {
+            DaemonMessage::Rejected { reason, .. } => assert!(reason.contains("token")),
+            other => panic!("Expected Rejected for {token:?}, got {other:?}"),
+        }
+    }
+
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn test_tcp_read_only_token_cannot_control() {
+    let server = RemoteTestServer::spawn().await;
+    server.register_in_pane("remote-ro", "%3").await;
+
+    let mut client = server.connect_tcp().await;
+    assert!(matches!(
+        client.handshake(Some(READ_ONLY_TOKEN)).await,
+        DaemonMessage::Connected { .. }
+    ));
+
+    client.send(ClientMessage::list_sessions()).await;
+    match client.recv().await {
+        DaemonMessage::SessionList { sessions, .. } => assert_eq!(sessions.len(), 1),
+        other => panic!("Expected SessionList, got {other:?}"),
+    }
+
+    client.send(ClientMessage::kill_session("%3")).await;
+    match client.recv().await {
+        DaemonMessage::Error { code, .. } => assert_eq!(code.as_deref(), Some("FORBIDDEN")),
+        other => panic!("Expected FORBIDDEN error, got {other:?}"),
+    }
+    assert!(server.tmux.calls().is_empty());
+
+    // The connection stays usable after a forbidden request
+    client.send(ClientMessage::ping(7)).await;
+    assert!(matches!(
+        client.recv().await,
+        DaemonMessage::Pong { seq: 7, .. }
+    ));
+
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn test_tcp_control_token_can_kill() {
+    let server = RemoteTestServer::spawn().await;
+    server.register_in_pane("remote-ctl", "%5").await;
+
+    let mut client = server.connect_tcp().await;
+    assert!(matches!(
+        client.handshake(Some(CONTROL_TOKEN)).await,
+        DaemonMessage::Connected { .. }
+    ));
+
+    client.send(ClientMessage::kill_session("%5")).await;
+    match client.recv().await {
+        DaemonMessage::ControlResult { action, .. } => assert_eq!(action, ControlAction::Kill),
+        other => panic!("Expected ControlResult, got {other:?}"),
+    }
+    assert_eq!(
+        server.tmux.calls(),
+        vec![MockCall::KillPane { pane: "%5".into() }]
+    );
+
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn test_tcp_control_token_cannot_reach_untracked_pane() {
+    let server = RemoteTestServer::spawn().await;
+    server.register_in_pane("remote-ctl", "%5").await;
+
+    let mut client = server.connect_tcp().await;
+    assert!(matches!(
+        client.handshake(Some(CONTROL_TOKEN)).await,
+        DaemonMessage::Connected { .. }
+    ));
+
+    // %0 is some other pane on the host, e.g. a plain shell
+    client
+        .send(ClientMessage::send_input("%0", "echo pwned"))
+        .await;
+    match client.recv().await {
+        DaemonMessage::Error { code, .. } => {
+            assert_eq!(code.as_deref(), Some("SESSION_NOT_FOUND"));
+        }
+        other => panic!("Expected SESSION_NOT_FOUND error, got {other:?}"),
+    }
+    assert!(server.tmux.calls().is_empty());
+
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn test_subscriber_that_stops_reading_does_not_stall_others() {
+    let server = RemoteTestServer::spawn().await;
+    server.register_in_pane("remote-busy", "%6").await;
+    let session_id = SessionId::new("remote-busy");
+
+    // Subscribes, then never reads again
+    let mut stalled = server.connect_tcp().await;
+    stalled.handshake(Some(READ_ONLY_TOKEN)).await;
+    stalled.send(ClientMessage::subscribe(None)).await;
+    assert!(matches!(
+        stalled.recv().await,
+        DaemonMessage::SessionList { .. }
+    ));
+
+    // Far more update traffic than the socket buffers hold
+    for n in 0..200 {
+        let team = format!("{n:04}{}", "x".repeat(64 * 1024));
+        server
+            .registry
+            .set_team(session_id.clone(), team)
+            .await
+            .unwrap();
+    }
+
+    // Another client can still subscribe...
+    let mut watcher = server.connect_tcp().await;
+    watcher.handshake(Some(READ_ONLY_TOKEN)).await;
+    let list = tokio::time::timeout(Duration::from_secs(2), async {
+        watcher.send(ClientMessage::subscribe(None)).await;
+        watcher.recv().await
+    })
+    .await
+    .expect("Subscribe should not wait on the stalled client");
+    assert!(matches!(list, DaemonMessage::SessionList { .. }));
+
+    // ...and keeps getting updates once the stalled client times out
+    server
+        .registry
+        .set_team(session_id, "marker".to_string())
+        .await
+        .unwrap();
+    let marker = serde_json::json!("marker");
+    let delivered = tokio::time::timeout(Duration::from_secs(15), async {
+        loop {
+            match watcher.recv().await {
+                DaemonMessage::SessionUpdated { session }
+                    if session.team.as_deref() == Some("marker") =>
+                {
+                    break
+                }
+                DaemonMessage::SessionPatch { changes, .. }
+                    if changes.get("team") == Some(&marker) =>
+                {
+                    break
+                }
+                _ => {}
+            }
+        }
+    })
+    .await;
+    assert!(delivered.is_ok(), "update never reached the reading client");
+
+    drop(stalled);
+    server.shutdown();
+}
+
+type WsClient = tokio_tungstenite::WebSocketStream<TcpStream>;
+
+async fn ws_send(ws: &mut WsClient, msg: ClientMessage) {
+    let json = serde_json::to_string(&msg).unwrap();
+    ws.send(Message::Text(json)).await.unwrap();
+}
+
+async fn ws_recv(ws: &mut WsClient) -> DaemonMessage {
+    match ws.next().await {
+        Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+        other => panic!("Expected text frame, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_subscribe_receives_session_list() {
+    let server = RemoteTestServer::spawn().await;
+    server.register_in_pane("remote-ws", "%1").await;
+
+    let stream = TcpStream::connect(server.ws_addr).await.unwrap();
+    let url = format!("ws://{}/", server.ws_addr);
+    let (mut ws, _) = tokio_tungstenite::client_async(url, stream)
+        .await
+        .expect("WebSocket handshake");
+
+    ws_send(
+        &mut ws,
+        ClientMessage::connect_with_token(None, READ_ONLY_TOKEN),
+    )
+    .await;
+    assert!(matches!(
+        ws_recv(&mut ws).await,
+        DaemonMessage::Connected { .. }
+    ));
+
+    ws_send(&mut ws, ClientMessage::subscribe(None)).await;
+    match ws_recv(&mut ws).await {
+        DaemonMessage::SessionList { sessions, .. } => {
+            assert_eq!(sessions.len(), 1);
+            assert_eq!(sessions[0].id.as_str(), "remote-ws");
+        }
+        other => panic!("Expected SessionList, got {other:?}"),
+    }
+
+    ws.close(None).await.unwrap();
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn test_websocket_accepts_pretty_printed_frames() {
+    let server = RemoteTestServer::spawn().await;
+
+    let stream = TcpStream::connect(server.ws_addr).await.unwrap();
+    let url = format!("ws://{}/", server.ws_addr);
+    let (mut ws, _) = tokio_tungstenite::client_async(url, stream)
+        .await
+        .expect("WebSocket handshake");
+
+    let connect = ClientMessage::connect_with_token(None, READ_ONLY_TOKEN);
+    let pretty = serde_json::to_string_pretty(&connect).unwrap();
+    ws.send(Message::Text(pretty)).await.unwrap();
+    assert!(matches!(
+        ws_recv(&mut ws).await,
+        DaemonMessage::Connected { .. }
+    ));
+
+    ws.close(None).await.unwrap();
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn test_reused_client_id_cannot_take_over_subscription() {
+    let server = RemoteTestServer::spawn().await;
+    server.register_in_pane("remote-owned", "%7").await;
+
+    let mut owner = server.connect_tcp().await;
+    let owner_id = match owner.handshake(Some(READ_ONLY_TOKEN)).await {
+        DaemonMessage::Connected { client_id, .. } => client_id,
+        other => panic!("Expected Connected, got {other:?}"),
+    };
+    owner.send(ClientMessage::subscribe(None)).await;
+    assert!(matches!(
+        owner.recv().await,
+        DaemonMessage::SessionList { .. }
+    ));
+
+    // A second connection claims the same id and tries to subscribe
+    let mut intruder = server.connect_tcp().await;
+    intruder
+        .send(ClientMessage::connect_with_token(
+            Some(owner_id.clone()),
+            READ_ONLY_TOKEN,
+        ))
+        .await;
+    assert!(matches!(
+        intruder.recv().await,
+        DaemonMessage::Connected { .. }
+    ));
+    intruder.send(ClientMessage::subscribe(None)).await;
+    match intruder.recv().await {
+        DaemonMessage::Error { message, .. } => {
+            assert!(message.contains("already subscribed"), "{message}")
+        }
+        other => panic!("Expected Error, got {other:?}"),
+    }
+    drop(intruder);
+    sleep(Duration::from_millis(50)).await;
+
+    // The owner keeps its event stream after the intruder leaves
+    server
+        .registry
+        .set_team(SessionId::new("remote-owned"), "still-mine".to_string())
+        .await
+        .unwrap();
+    let update = tokio::time::timeout(Duration::from_secs(2), owner.recv())
+        .await
+        .expect("owner should still receive updates");
+    assert!(matches!(
+        update,
+        DaemonMessage::SessionUpdated { .. } | DaemonMessage::SessionPatch { .. }
+    ));
+
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn test_remote_client_must_authenticate_promptly() {
+    let server = RemoteTestServer::spawn().await;
+
+    let mut client = server.connect_tcp().await;
+    let mut line = String::new();
+    let read = tokio::time::timeout(Duration::from_secs(10), client.reader.read_line(&mut line))
+        .await
+        .expect("Daemon should drop a client that never sends Connect");
+    assert_eq!(read.unwrap(), 0, "Expected EOF, got {line:?}");
+
+    server.shutdown();
+}
+
+#[tokio::test]
+async fn test_unauthenticated_remote_connections_are_capped() {
+    let server = RemoteTestServer::spawn().await;
+
+    // Fill every pending slot (MAX_PENDING_REMOTE) without authenticating
+    let mut idle = Vec::new();
+    for _ in 0..16 {
+        idle.push(server.connect_tcp().await);
+    }
+    sleep(Duration::from_millis(100)).await;
+
+    let mut extra = server.connect_tcp().await;
+    let mut line = String::new();
+    let read = tokio::time::timeout(Duration::from_secs(1), extra.reader.read_line(&mut line))
+        .await
+        .expect("Connection over the cap should be closed right away");
+    assert_eq!(read.unwrap(), 0, "Expected EOF, got {line:?}");
+
+    // Closing the idle connections frees their slots
+    drop(idle);
+    sleep(Duration::from_millis(100)).await;
+    let mut client = server.connect_tcp().await;
+    assert!(matches!(
+        client.handshake(Some(READ_ONLY_TOKEN)).await,
+        DaemonMessage::Connected { .. }
+    ));
+
+    server.shutdown();
+}
@@ -157,7 +157,7 @@ fn create_test_session(id: &str) -> SessionDomain {
     SessionDomain::new(
         SessionId::new(id),
         AgentType::GeneralPurpose,
-        Model::Sonnet4,
+        Model::from_id("claude-sonnet-4"),
     )
 }
 
@@ -328,7 +328,7 @@ async fn test_many_concurrent_connections() {
 
             client.send(ClientMessage::ping(i as u64)).await;
             match client.recv().await {
-                DaemonMessage::Pong { seq } => assert_eq!(seq, i as u64),
+                DaemonMessage::Pong { seq, .. } => assert_eq!(seq, i as u64),
                 other => panic!("Expected Pong, got {other:?}"),
             }
         });
@@ -371,6 +371,7 @@ async fn test_rapid_status_updates() {
 
         let msg = ClientMessage {
             protocol_version: ProtocolVersion::CURRENT,
+            request_id: None,
             message: MessageType::StatusUpdate { data: status_json },
         };
         client.send(msg).await;
@@ -382,7 +383,7 @@ async fn test_rapid_status_updates() {
     // Query to verify state is consistent
     client.send(ClientMessage::list_sessions()).await;
     match client.recv().await {
-        DaemonMessage::SessionList { sessions } => {
+        DaemonMessage::SessionList { sessions, .. } => {
             assert_eq!(
                 sessions.len(),
                 1,
@@ -421,6 +422,7 @@ async fn test_multiple_sessions_rapid_updates() {
 
             let msg = ClientMessage {
                 protocol_version: ProtocolVersion::CURRENT,
+                request_id: None,
                 message: MessageType::StatusUpdate { data: status_json },
             };
             client.send(msg).await;
@@ -432,7 +434,7 @@ async fn test_multiple_sessions_rapid_updates() {
     // Verify all sessions exist
     client.send(ClientMessage::list_sessions()).await;
     match client.recv().await {
-        DaemonMessage::SessionList { sessions } => {
+        DaemonMessage::SessionList { sessions, .. } => {
             assert_eq!(sessions.len(), 10, "Should have 10 sessions");
         }
         other => panic!("Expected SessionList, got {other:?}"),
@@ -459,6 +461,7 @@ async fn test_client_continues_after_error() {
 
     let msg = ClientMessage {
         protocol_version: ProtocolVersion::CURRENT,
+        request_id: None,
         message: MessageType::StatusUpdate {
             data: invalid_status,
         },
@@ -474,7 +477,7 @@ async fn test_client_continues_after_error() {
     // Client should still be able to send valid messages
     client.send(ClientMessage::ping(42)).await;
     match client.recv().await {
-        DaemonMessage::Pong { seq } => assert_eq!(seq, 42),
+        DaemonMessage::Pong { seq, .. } => assert_eq!(seq, 42),
         other => panic!("Expected Pong after error, got {other:?}"),
     }
 
@@ -493,6 +496,7 @@ async fn test_multiple_errors_dont_break_connection() {
         let invalid = serde_json::json!({ "invalid": "data" });
         let msg = ClientMessage {
             protocol_version: ProtocolVersion::CURRENT,
+            request_id: None,
             message: MessageType::StatusUpdate { data: invalid },
         };
         client.send(msg).await;
@@ -526,7 +530,7 @@ async fn test_subscriber_receives_session_updates() {
 
     // Receive initial session list
     match subscriber.recv().await {
-        DaemonMessage::SessionList { sessions } => {
+        DaemonMessage::SessionList { sessions, .. } => {
             assert_eq!(sessions.len(), 0);
         }
         other => panic!("Expected initial SessionList, got {other:?}"),
@@ -550,6 +554,7 @@ async fn test_subscriber_receives_session_updates() {
 
     let msg = ClientMessage {
         protocol_version: ProtocolVersion::CURRENT,
+        request_id: None,
         message: MessageType::StatusUpdate { data: status_json },
     };
     updater.send(msg).await;
@@ -583,7 +588,7 @@ async fn test_subscribe_before_sessions_exist() {
     client.send(ClientMessage::subscribe(None)).await;
 
     match client.recv().await {
-        DaemonMessage::SessionList { sessions } => {
+        DaemonMessage::SessionList { sessions, .. } => {
             assert!(sessions.is_empty(), "Should be empty initially");
         }
         other => panic!("Expected empty SessionList, got {other:?}"),
@@ -607,7 +612,7 @@ async fn test_unsubscribe_when_not_subscribed() {
     // Should still work normally
     client.send(ClientMessage::ping(1)).await;
     match client.recv().await {
-        DaemonMessage::Pong { seq } => assert_eq!(seq, 1),
+        DaemonMessage::Pong { seq, .. } => assert_eq!(seq, 1),
         other => panic!("Expected Pong, got {other:?}"),
     }
 
@@ -651,6 +656,7 @@ async fn test_empty_session_id() {
 
     let msg = ClientMessage {
         protocol_version: ProtocolVersion::CURRENT,
+        request_id: None,
         message: MessageType::StatusUpdate { data: status_json },
     };
     client.send(msg).await;
@@ -661,7 +667,7 @@ async fn test_empty_session_id() {
 
     client.send(ClientMessage::ping(99)).await;
     match client.recv().await {
-        DaemonMessage::Pong { seq } => assert_eq!(seq, 99),
+        DaemonMessage::Pong { seq, .. } => assert_eq!(seq, 99),
         DaemonMessage::Error { .. } => {} // Also acceptable
         other => panic!("Expected Pong or Error, got {other:?}"),
     }
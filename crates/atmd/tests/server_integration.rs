@@ -7,10 +7,16 @@
 //! We test the panic-free behavior of production code through assertions.
 
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
-use atm_core::{AgentType, Model, SessionDomain, SessionId};
-use atm_protocol::{ClientMessage, DaemonMessage, MessageType, ProtocolVersion};
+use atm_core::{AgentType, Model, SessionDomain, SessionId, SessionStatus};
+use atm_protocol::{
+    ClientMessage, ControlAction, DaemonMessage, MessageType, ProtocolVersion, ReplyChoice,
+    SubscriptionFilter,
+};
+use atm_tmux::mock::MockCall;
+use atm_tmux::MockTmuxClient;
 use atmd::registry::spawn_registry;
 use atmd::server::DaemonServer;
 use tempfile::TempDir;
@@ -44,8 +50,9 @@ struct TestServer {
 }
 
 impl TestServer {
-    /// Internal helper that spawns the server and returns both the test server and registry handle.
-    async fn spawn_internal() -> (Self, atmd::registry::RegistryHandle) {
+    /// Internal helper that spawns the server and returns the test server,
+    /// registry handle, and the mock tmux client control requests go to.
+    async fn spawn_internal() -> (Self, atmd::registry::RegistryHandle, MockTmuxClient) {
         let temp_dir = tempfile::tempdir().expect("create temp dir");
         let socket_path = temp_dir.path().join("test.sock");
 
@@ -53,7 +60,9 @@ impl TestServer {
         let registry_handle = registry.clone();
         let cancel_token = CancellationToken::new();
 
-        let server = DaemonServer::new(socket_path.clone(), registry, cancel_token.clone());
+        let tmux = MockTmuxClient::new();
+        let server = DaemonServer::new(socket_path.clone(), registry, cancel_token.clone())
+            .with_tmux_client(Arc::new(tmux.clone()));
 
         // Spawn server in background
         tokio::spawn(async move {
@@ -81,7 +90,7 @@ impl TestServer {
             _temp_dir: temp_dir,
         };
 
-        (test_server, registry_handle)
+        (test_server, registry_handle, tmux)
     }
 
     /// Spawns a new test server in the background.
@@ -91,6 +100,12 @@ impl TestServer {
 
     /// Spawns a test server with access to the registry handle.
     async fn spawn_with_registry() -> (Self, atmd::registry::RegistryHandle) {
+        let (server, registry, _) = Self::spawn_internal().await;
+        (server, registry)
+    }
+
+    /// Spawns a test server with access to the registry and mock tmux client.
+    async fn spawn_with_tmux() -> (Self, atmd::registry::RegistryHandle, MockTmuxClient) {
         Self::spawn_internal().await
     }
 
@@ -153,7 +168,11 @@ impl TestClient {
     async fn handshake_with_version(&mut self, version: ProtocolVersion) -> DaemonMessage {
         let msg = ClientMessage {
             protocol_version: version,
-            message: MessageType::Connect { client_id: None },
+            request_id: None,
+            message: MessageType::Connect {
+                client_id: None,
+                token: None,
+            },
         };
         self.send(msg).await;
         self.recv().await
@@ -165,7 +184,7 @@ fn create_test_session(id: &str) -> SessionDomain {
     SessionDomain::new(
         SessionId::new(id),
         AgentType::GeneralPurpose,
-        Model::Sonnet4,
+        Model::from_id("claude-sonnet-4"),
     )
 }
 
@@ -183,6 +202,25 @@ async fn test_server_accepts_connection() {
     server.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_socket_is_owner_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let server = TestServer::spawn().await;
+
+    let mode = std::fs::metadata(&server.socket_path)
+        .expect("socket metadata")
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // Same-user peers pass the credential check
+    let mut client = server.connect().await;
+    client.handshake(None).await;
+
+    server.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_handshake_success() {
     let server = TestServer::spawn().await;
@@ -198,6 +236,7 @@ async fn test_handshake_success() {
         DaemonMessage::Connected {
             protocol_version,
             client_id,
+            ..
         } => {
             assert_eq!(protocol_version, ProtocolVersion::CURRENT);
             assert_eq!(client_id, "test-client");
@@ -271,7 +310,7 @@ async fn test_subscribe_unsubscribe_flow() {
 
     // Should receive session list (initially empty)
     match client.recv().await {
-        DaemonMessage::SessionList { sessions } => {
+        DaemonMessage::SessionList { sessions, .. } => {
             assert_eq!(sessions.len(), 0, "Initial session list should be empty");
         }
         other => panic!("Expected SessionList, got {other:?}"),
@@ -343,7 +382,7 @@ async fn test_broadcast_respects_filter() {
 
     // Should receive initial session list (empty)
     match client1.recv().await {
-        DaemonMessage::SessionList { sessions } => {
+        DaemonMessage::SessionList { sessions, .. } => {
             assert_eq!(sessions.len(), 0, "Initial session list should be empty");
         }
         other => panic!("Expected SessionList, got {other:?}"),
@@ -356,7 +395,7 @@ async fn test_broadcast_respects_filter() {
 
     // Should receive initial session list (empty)
     match client2.recv().await {
-        DaemonMessage::SessionList { sessions } => {
+        DaemonMessage::SessionList { sessions, .. } => {
             assert_eq!(sessions.len(), 0, "Initial session list should be empty");
         }
         other => panic!("Expected SessionList, got {other:?}"),
@@ -378,7 +417,7 @@ async fn test_broadcast_respects_filter() {
     // Verify sessions were registered by querying via list_sessions
     client1.send(ClientMessage::list_sessions()).await;
     match client1.recv().await {
-        DaemonMessage::SessionList { sessions } => {
+        DaemonMessage::SessionList { sessions, .. } => {
             assert_eq!(sessions.len(), 2, "Should have 2 sessions registered");
         }
         other => panic!("Expected SessionList with 2 sessions, got {other:?}"),
@@ -421,7 +460,7 @@ async fn test_max_clients_rejection() {
     for (i, client) in clients.iter_mut().enumerate() {
         client.send(ClientMessage::ping(i as u64)).await;
         match client.recv().await {
-            DaemonMessage::Pong { seq } => {
+            DaemonMessage::Pong { seq, .. } => {
                 assert_eq!(seq, i as u64);
             }
             other => panic!("Expected Pong for client {i}, got {other:?}"),
@@ -490,7 +529,7 @@ async fn test_ping_pong() {
 
     // Should receive pong with same seq
     match client.recv().await {
-        DaemonMessage::Pong { seq } => {
+        DaemonMessage::Pong { seq, .. } => {
             assert_eq!(seq, 42, "Pong seq should match ping seq");
         }
         other => panic!("Expected Pong, got {other:?}"),
@@ -511,7 +550,7 @@ async fn test_list_sessions_command() {
 
     // Should receive SessionList
     match client.recv().await {
-        DaemonMessage::SessionList { sessions } => {
+        DaemonMessage::SessionList { sessions, .. } => {
             // Initially empty
             assert_eq!(sessions.len(), 0);
         }
@@ -1134,6 +1173,97 @@ async fn test_e2e_pi_suppressed_event_does_not_panic_or_disrupt() {
     server.shutdown().await;
 }
 
+// ============================================================================
+// Codex Event → LifecycleEvent translation (end-to-end, wire-level)
+//
+// Codex `notify` payloads forwarded by atm-codex-hook as
+// MessageType::CodexEvent.
+// ============================================================================
+
+fn codex_event_json(event: &str, extra: serde_json::Value) -> serde_json::Value {
+    let mut data = serde_json::json!({
+        "type": event,
+        "thread-id": "e2e-codex",
+    });
+    if let (Some(data), Some(extra)) = (data.as_object_mut(), extra.as_object()) {
+        data.extend(extra.clone());
+    }
+    data
+}
+
+#[tokio::test]
+async fn test_e2e_codex_prompt_then_turn_complete() {
+    let (server, registry) = TestServer::spawn_with_registry().await;
+    let mut client = server.connect().await;
+    client.handshake(None).await;
+
+    let session_id = SessionId::new("e2e-codex");
+    registry
+        .register(create_test_session(session_id.as_str()))
+        .await
+        .expect("register session");
+
+    client
+        .send(ClientMessage::codex_event(codex_event_json(
+            "user_prompt_submit",
+            serde_json::json!({"input_messages": ["run the tests"]}),
+        )))
+        .await;
+    sleep(Duration::from_millis(50)).await;
+
+    let view = registry
+        .get_session(session_id.clone())
+        .await
+        .expect("session should exist");
+    assert_eq!(view.status_label, "working");
+
+    // The stock notify event: the turn is over
+    client
+        .send(ClientMessage::codex_event(codex_event_json(
+            "agent-turn-complete",
+            serde_json::json!({"input-messages": ["run the tests"]}),
+        )))
+        .await;
+    sleep(Duration::from_millis(50)).await;
+
+    let view = registry
+        .get_session(session_id.clone())
+        .await
+        .expect("session should exist");
+    assert_ne!(view.status_label, "working");
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_e2e_codex_unknown_event_is_ignored() {
+    let (server, registry) = TestServer::spawn_with_registry().await;
+    let mut client = server.connect().await;
+    client.handshake(None).await;
+
+    let session_id = SessionId::new("e2e-codex");
+    registry
+        .register(create_test_session(session_id.as_str()))
+        .await
+        .expect("register session");
+
+    client
+        .send(ClientMessage::codex_event(codex_event_json(
+            "agent-reasoning-delta",
+            serde_json::json!({}),
+        )))
+        .await;
+    client.send(ClientMessage::ping(9)).await;
+    assert!(matches!(
+        client.recv().await,
+        DaemonMessage::Pong { seq: 9, .. }
+    ));
+
+    assert!(registry.get_session(session_id).await.is_some());
+
+    server.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_concurrent_ping_pong() {
     let server = TestServer::spawn().await;
@@ -1154,7 +1284,7 @@ async fn test_concurrent_ping_pong() {
     // Receive pongs and verify correct seq
     for (i, client) in clients.iter_mut().enumerate() {
         match client.recv().await {
-            DaemonMessage::Pong { seq } => {
+            DaemonMessage::Pong { seq, .. } => {
                 assert_eq!(seq, (i * 100) as u64);
             }
             other => panic!("Expected Pong for client {i}, got {other:?}"),
@@ -1163,3 +1293,358 @@ async fn test_concurrent_ping_pong() {
 
     server.shutdown().await;
 }
+
+// ============================================================================
+// Control Requests
+// ============================================================================
+
+/// Registers a session running in `pane`.
+async fn register_in_pane(registry: &atmd::registry::RegistryHandle, id: &str, pane: &str) {
+    let mut session = create_test_session(id);
+    session.tmux_pane = Some(pane.to_string());
+    registry.register(session).await.expect("register session");
+}
+
+#[tokio::test]
+async fn test_control_kill_and_interrupt_by_prefix() {
+    let (server, registry, tmux) = TestServer::spawn_with_tmux().await;
+    register_in_pane(&registry, "ctl-session-1", "%4").await;
+
+    let mut client = server.connect().await;
+    client.handshake(None).await;
+
+    client.send(ClientMessage::interrupt_session("ctl-")).await;
+    match client.recv().await {
+        DaemonMessage::ControlResult {
+            action,
+            session_id,
+            pane_id,
+            keys,
+            ..
+        } => {
+            assert_eq!(action, ControlAction::Interrupt);
+            assert_eq!(session_id, Some(SessionId::new("ctl-session-1")));
+            assert_eq!(pane_id, "%4");
+            assert_eq!(keys, vec!["C-c"]);
+        }
+        other => panic!("Expected ControlResult, got {other:?}"),
+    }
+
+    client.send(ClientMessage::kill_session("%4")).await;
+    match client.recv().await {
+        DaemonMessage::ControlResult { action, .. } => assert_eq!(action, ControlAction::Kill),
+        other => panic!("Expected ControlResult, got {other:?}"),
+    }
+
+    assert_eq!(
+        tmux.calls(),
+        vec![
+            MockCall::SendKeys {
+                pane: "%4".into(),
+                keys: "C-c".into()
+            },
+            MockCall::KillPane { pane: "%4".into() },
+        ]
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_control_send_input_and_reply() {
+    let (server, registry, tmux) = TestServer::spawn_with_tmux().await;
+    register_in_pane(&registry, "ctl-send", "%2").await;
+    tmux.set_pane_content("%2", vec!["  1. Yes".into(), "❯ 2. No".into()]);
+
+    let mut client = server.connect().await;
+    client.handshake(None).await;
+
+    client
+        .send(ClientMessage::send_input("ctl-send", "run the tests"))
+        .await;
+    match client.recv().await {
+        DaemonMessage::ControlResult { keys, .. } => {
+            assert_eq!(keys, vec!["run the tests", "Enter"]);
+        }
+        other => panic!("Expected ControlResult, got {other:?}"),
+    }
+
+    client
+        .send(ClientMessage::reply_prompt(
+            "ctl-send",
+            ReplyChoice::Option(1),
+        ))
+        .await;
+    match client.recv().await {
+        DaemonMessage::ControlResult { action, keys, .. } => {
+            assert_eq!(action, ControlAction::Reply);
+            assert_eq!(keys, vec!["Up", "Enter"]);
+        }
+        other => panic!("Expected ControlResult, got {other:?}"),
+    }
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_control_errors_are_coded_and_keep_connection() {
+    let (server, registry, tmux) = TestServer::spawn_with_tmux().await;
+    register_in_pane(&registry, "dup-aaa", "%1").await;
+    register_in_pane(&registry, "dup-bbb", "%2").await;
+    registry
+        .register(create_test_session("no-pane"))
+        .await
+        .expect("register session");
+
+    let mut client = server.connect().await;
+    client.handshake(None).await;
+
+    for (msg, expected) in [
+        (ClientMessage::kill_session("missing"), "SESSION_NOT_FOUND"),
+        (ClientMessage::kill_session("dup-"), "AMBIGUOUS_TARGET"),
+        (ClientMessage::interrupt_session("no-pane"), "NO_PANE"),
+    ] {
+        client.send(msg).await;
+        match client.recv().await {
+            DaemonMessage::Error { code, .. } => assert_eq!(code.as_deref(), Some(expected)),
+            other => panic!("Expected Error, got {other:?}"),
+        }
+    }
+    assert_eq!(
+        tmux.call_count(),
+        0,
+        "failed resolution must not touch tmux"
+    );
+
+    // Connection is still usable
+    client.send(ClientMessage::ping(9)).await;
+    assert!(matches!(
+        client.recv().await,
+        DaemonMessage::Pong { seq: 9, .. }
+    ));
+
+    server.shutdown().await;
+}
+
+// ============================================================================
+// Request IDs
+// ============================================================================
+
+#[tokio::test]
+async fn test_replies_echo_request_id() {
+    let server = TestServer::spawn().await;
+    let mut client = server.connect().await;
+    client.handshake(None).await;
+
+    // Pipeline several tagged requests before reading any reply
+    client
+        .send(ClientMessage::ping(1).with_request_id(10))
+        .await;
+    client
+        .send(ClientMessage::list_sessions().with_request_id(11))
+        .await;
+    client
+        .send(ClientMessage::kill_session("nope").with_request_id(12))
+        .await;
+    client.send(ClientMessage::ping(2)).await;
+
+    let replies = [
+        client.recv().await,
+        client.recv().await,
+        client.recv().await,
+        client.recv().await,
+    ];
+    assert!(matches!(replies[0], DaemonMessage::Pong { seq: 1, .. }));
+    assert_eq!(replies[0].request_id(), Some(10));
+    assert!(matches!(replies[1], DaemonMessage::SessionList { .. }));
+    assert_eq!(replies[1].request_id(), Some(11));
+    assert!(matches!(replies[2], DaemonMessage::Error { .. }));
+    assert_eq!(replies[2].request_id(), Some(12));
+    assert_eq!(
+        replies[3].request_id(),
+        None,
+        "untagged request, untagged reply"
+    );
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_protocol_1_0_client_still_accepted() {
+    let server = TestServer::spawn().await;
+    let mut client = server.connect().await;
+
+    match client
+        .handshake_with_version(ProtocolVersion::new(1, 0))
+        .await
+    {
+        DaemonMessage::Connected { .. } => {}
+        other => panic!("Expected Connected, got {other:?}"),
+    }
+
+    server.shutdown().await;
+}
+
+// ============================================================================
+// Session Patch Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_consecutive_updates_are_sent_as_patches() {
+    let (server, registry) = TestServer::spawn_with_registry().await;
+
+    let session_id = SessionId::new("patch-session");
+    registry
+        .register(create_test_session(session_id.as_str()))
+        .await
+        .expect("register session");
+
+    let mut current = server.connect().await;
+    current.handshake(None).await;
+    current.send(ClientMessage::subscribe(None)).await;
+    assert!(matches!(
+        current.recv().await,
+        DaemonMessage::SessionList { .. }
+    ));
+
+    let mut legacy = server.connect().await;
+    assert!(matches!(
+        legacy
+            .handshake_with_version(ProtocolVersion::new(1, 1))
+            .await,
+        DaemonMessage::Connected { .. }
+    ));
+    legacy.send(ClientMessage::subscribe(None)).await;
+    assert!(matches!(
+        legacy.recv().await,
+        DaemonMessage::SessionList { .. }
+    ));
+
+    let mut hooks = server.connect().await;
+    hooks.handshake(None).await;
+    for event in ["PreToolUse", "Stop"] {
+        hooks
+            .send(ClientMessage::hook_event(hook_event_json(
+                session_id.as_str(),
+                event,
+                serde_json::json!({"tool_name": "Bash"}),
+            )))
+            .await;
+        sleep(Duration::from_millis(50)).await;
+    }
+
+    // First broadcast has no base to diff against
+    let first_revision = match current.recv().await {
+        DaemonMessage::SessionUpdated { session } => session.revision,
+        other => panic!("Expected SessionUpdated, got {other:?}"),
+    };
+    match current.recv().await {
+        DaemonMessage::SessionPatch {
+            session_id: id,
+            revision,
+            changes,
+        } => {
+            assert_eq!(id, session_id);
+            assert_eq!(revision, first_revision + 1);
+            assert_eq!(changes.get("status_label"), Some(&"idle".into()));
+            assert!(!changes.contains_key("model"));
+        }
+        other => panic!("Expected SessionPatch, got {other:?}"),
+    }
+
+    // Pre-1.2 clients keep getting full views
+    for _ in 0..2 {
+        assert!(matches!(
+            legacy.recv().await,
+            DaemonMessage::SessionUpdated { .. }
+        ));
+    }
+
+    server.shutdown().await;
+}
+
+// ============================================================================
+// Filtered Subscription Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_filtered_subscription_by_tmux_session_and_status() {
+    let (server, registry, tmux) = TestServer::spawn_with_tmux().await;
+    tmux.set_panes(vec![
+        atm_tmux::PaneInfo {
+            pane_id: "%1".to_string(),
+            session_name: "dev".to_string(),
+            window_index: 0,
+            pane_pid: 1,
+            width: 80,
+            height: 24,
+            is_active: false,
+        },
+        atm_tmux::PaneInfo {
+            pane_id: "%2".to_string(),
+            session_name: "ops".to_string(),
+            window_index: 0,
+            pane_pid: 2,
+            width: 80,
+            height: 24,
+            is_active: false,
+        },
+    ]);
+    register_in_pane(&registry, "in-dev", "%1").await;
+    register_in_pane(&registry, "in-ops", "%2").await;
+
+    let mut client = server.connect().await;
+    client.handshake(None).await;
+    client
+        .send(ClientMessage::subscribe_filtered(SubscriptionFilter {
+            tmux_session: Some("dev".to_string()),
+            status: vec![SessionStatus::Working],
+            ..Default::default()
+        }))
+        .await;
+
+    // Nothing is working yet
+    match client.recv().await {
+        DaemonMessage::SessionList { sessions, .. } => assert!(sessions.is_empty()),
+        other => panic!("Expected SessionList, got {other:?}"),
+    }
+
+    let mut hooks = server.connect().await;
+    hooks.handshake(None).await;
+    for (id, event) in [
+        ("in-ops", "PreToolUse"),
+        ("in-dev", "PreToolUse"),
+        ("in-dev", "Stop"),
+    ] {
+        hooks
+            .send(ClientMessage::hook_event(hook_event_json(
+                id,
+                event,
+                serde_json::json!({"tool_name": "Bash"}),
+            )))
+            .await;
+        sleep(Duration::from_millis(50)).await;
+    }
+
+    // The ops session never shows up; the dev session enters the filter
+    // with a full view and leaves it as a removal.
+    match client.recv().await {
+        DaemonMessage::SessionUpdated { session } => assert_eq!(session.id.as_str(), "in-dev"),
+        other => panic!("Expected SessionUpdated, got {other:?}"),
+    }
+    match client.recv().await {
+        DaemonMessage::SessionRemoved { session_id, .. } => {
+            assert_eq!(session_id.as_str(), "in-dev")
+        }
+        other => panic!("Expected SessionRemoved, got {other:?}"),
+    }
+
+    // A resync returns the filtered list, not every session
+    client.send(ClientMessage::list_sessions()).await;
+    match client.recv().await {
+        DaemonMessage::SessionList { sessions, .. } => assert!(sessions.is_empty()),
+        other => panic!("Expected SessionList, got {other:?}"),
+    }
+
+    server.shutdown().await;
+}
@@ -14,7 +14,8 @@
  *   pi --extension /abs/path/to/pi-atm.ts
  *
  * Config (env):
- *   ATM_SOCKET   — daemon socket path (default: /tmp/atm.sock)
+ *   ATM_SOCKET   — daemon socket path (default: $XDG_RUNTIME_DIR/atm/atm.sock,
+ *                  or /tmp/atm-<uid>/atm.sock without a runtime dir)
  *   ATM_DEBUG=1  — append debug log to /tmp/pi-atm.log
  *
  * NEVER throws or rejects — the contract is "if atmd is unavailable,
@@ -26,9 +27,46 @@
 
 import * as fs from "node:fs";
 import * as net from "node:net";
+import * as path from "node:path";
 import type { ExtensionAPI } from "@mariozechner/pi-coding-agent";
 
-const SOCKET = process.env.ATM_SOCKET ?? "/tmp/atm.sock";
+const LEGACY_SOCKET = "/tmp/atm.sock";
+
+/**
+ * Resolve the daemon socket. Must match `atm_protocol::socket` and the
+ * `atm-hook` script: `$ATM_SOCKET`, else the per-user socket, else a
+ * legacy `/tmp/atm.sock` owned by this user (an older daemon that hasn't
+ * been restarted yet).
+ */
+function resolveSocket(): string {
+	const override = process.env.ATM_SOCKET;
+	if (override) return override;
+
+	const uid = process.getuid?.() ?? 0;
+	const runtimeDir = process.env.XDG_RUNTIME_DIR;
+	const preferred =
+		runtimeDir && path.isAbsolute(runtimeDir)
+			? path.join(runtimeDir, "atm", "atm.sock")
+			: `/tmp/atm-${uid}/atm.sock`;
+
+	if (!isSocket(preferred)) {
+		try {
+			const legacy = fs.statSync(LEGACY_SOCKET);
+			if (legacy.isSocket() && legacy.uid === uid) return LEGACY_SOCKET;
+		} catch {
+			// no legacy socket
+		}
+	}
+	return preferred;
+}
+
+function isSocket(p: string): boolean {
+	try {
+		return fs.statSync(p).isSocket();
+	} catch {
+		return false;
+	}
+}
 const DEBUG = process.env.ATM_DEBUG === "1";
 
 /**
@@ -157,12 +195,15 @@ function enqueue(line: string): void {
 
 function openSocket(): void {
 	if (activeSocket || pendingSocket) return;
-	if (!fs.existsSync(SOCKET)) {
-		logDebug(`socket not found: ${SOCKET}`);
+	// Resolved per attempt so a daemon restarted onto the per-user path
+	// is picked up without restarting pi.
+	const socketPath = resolveSocket();
+	if (!fs.existsSync(socketPath)) {
+		logDebug(`socket not found: ${socketPath}`);
 		return;
 	}
 
-	const sock = net.createConnection({ path: SOCKET });
+	const sock = net.createConnection({ path: socketPath });
 	pendingSocket = sock;
 
 	sock.on("connect", () => {
@@ -301,6 +342,7 @@ function buildEnvelope(eventName: string, payload: unknown, sessionId: string |
 		session_id: sessionId,
 		pid: process.pid,
 		tmux_pane: process.env.TMUX_PANE,
+		team: process.env.ATM_TEAM,
 	};
 }
 
@@ -367,7 +409,7 @@ function patchUiSelectOnce(
 }
 
 export default function (pi: ExtensionAPI): void {
-	logDebug(`pi-atm starting, socket=${SOCKET}`);
+	logDebug(`pi-atm starting, socket=${resolveSocket()}`);
 
 	// `sessionManager.currentSessionId` only becomes available after
 	// `session_start` fires. Cache it from the first event we see.
@@ -99,7 +99,8 @@ main() {
     cp "$extracted_dir/atm" "$INSTALL_DIR/"
     cp "$extracted_dir/atmd" "$INSTALL_DIR/"
     cp "$extracted_dir/atm-hook" "$INSTALL_DIR/"
-    chmod +x "$INSTALL_DIR/atm" "$INSTALL_DIR/atmd" "$INSTALL_DIR/atm-hook"
+    cp "$extracted_dir/atm-codex-hook" "$INSTALL_DIR/"
+    chmod +x "$INSTALL_DIR/atm" "$INSTALL_DIR/atmd" "$INSTALL_DIR/atm-hook" "$INSTALL_DIR/atm-codex-hook"
 
     info "Binaries installed!"
 
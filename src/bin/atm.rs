@@ -13,13 +13,15 @@
 //! atm send <session-id> <text> # Send text to agent pane
 //! atm list                   # List agents (tab-separated)
 //! atm status                 # One-line summary for tmux status bar
+//! atm history [<session-id>] # Query the daemon's event journal
+//! atm cost --by project      # Report spend from the daemon's cost ledger
 //! atm setup                  # Configure Claude Code hooks
 //! atm uninstall              # Remove hooks
 //! ```
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::{self, OpenOptions};
-use std::io::{self, Stdout};
+use std::io::{self, Stdout, Write};
 use std::path::PathBuf;
 use std::sync::Mutex;
 use std::time::Duration;
@@ -44,17 +46,21 @@ static TEST_ENV_LOCK: Mutex<()> = Mutex::new(());
 use tracing_subscriber::EnvFilter;
 
 use atm_core::{
-    builtin_harnesses, default_harness_definition, find_harness_definition, HarnessDefinition,
-    SessionView,
+    builtin_harnesses, default_harness_definition, find_harness_definition, GroupBy,
+    HarnessDefinition, PromptState, SessionId, SessionView, SortKey,
 };
-use atm_protocol::{ClientMessage, DaemonMessage};
+use atm_protocol::{ClientMessage, DaemonMessage, ReplyChoice, SubscriptionFilter};
 use atm_tmux::{RealTmuxClient, TmuxClient};
-use atm_tui::app::App;
-use atm_tui::client::DaemonClient;
+use atm_tui::app::{App, BroadcastAction, ReplyAnswer};
+use atm_tui::client::{DaemonClient, DaemonConfig};
 use atm_tui::daemon;
 use atm_tui::error::{Result as TuiResult, TuiError};
 use atm_tui::input::{ClientCommand, Event};
-use atm_tui::keybinding::{InputHandler, UiAction};
+use atm_tui::keybinding::{
+    resolve_reply_key, resolve_search_key, InputHandler, ReplyPopupAction, SearchInputAction,
+    UiAction,
+};
+use atm_tui::keymap::Keymap;
 use atm_tui::setup;
 use atm_tui::tmux;
 use atm_tui::ui;
@@ -115,19 +121,34 @@ enum Command {
     /// Kill an agent and close its tmux pane
     Kill {
         /// Session ID (short form, e.g., "a1b2c3d4") or tmux pane ID (e.g., "%5")
-        target: String,
+        #[arg(
+            required_unless_present_any = ["all", "project", "status"],
+            conflicts_with_all = ["all", "project", "status"]
+        )]
+        target: Option<String>,
+        #[command(flatten)]
+        selector: SessionSelector,
     },
     /// Send SIGINT to interrupt an agent's current turn
     Interrupt {
         /// Session ID (short form) or tmux pane ID
-        target: String,
+        #[arg(
+            required_unless_present_any = ["all", "project", "status"],
+            conflicts_with_all = ["all", "project", "status"]
+        )]
+        target: Option<String>,
+        #[command(flatten)]
+        selector: SessionSelector,
     },
     /// Send text to an agent's tmux pane
     Send {
-        /// Session ID (short form) or tmux pane ID
-        target: String,
+        /// Session ID (short form) or tmux pane ID; omit when selecting
+        /// agents with --all/--project/--status
+        target: Option<String>,
         /// Text to send
-        text: String,
+        text: Option<String>,
+        #[command(flatten)]
+        selector: SessionSelector,
     },
     /// List agents for scripting
     List {
@@ -137,7 +158,7 @@ enum Command {
         /// Filter by status (working, idle, attention)
         #[arg(long)]
         status: Option<String>,
-        /// Filter by project name
+        /// Filter by project: its root path, or the root's directory name
         #[arg(long)]
         project: Option<String>,
     },
@@ -148,7 +169,7 @@ enum Command {
         /// Only show the last N lines
         #[arg(long, short = 'n')]
         tail: Option<usize>,
-        /// Extract just the active prompt (auto-detects boundaries)
+        /// Show just the active prompt: question, options and cursor
         #[arg(long)]
         prompt: bool,
     },
@@ -165,9 +186,81 @@ enum Command {
         /// Shortcut: reject/deny (press Escape)
         #[arg(long, short = 'n', conflicts_with = "option", conflicts_with = "yes")]
         no: bool,
+        /// Answer a question with this text, typed into its
+        /// "Type something." entry
+        #[arg(long, short = 't', conflicts_with_all = ["option", "yes", "no"])]
+        text: Option<String>,
     },
     /// One-line status summary (for tmux status bar)
     Status,
+    /// Block until agents reach a state.
+    ///
+    /// Exit codes: 0 the condition was met, 3 an agent ended first,
+    /// 124 timed out, 1 any other error.
+    Wait {
+        /// Session ID (short form) or tmux pane ID
+        #[arg(
+            required_unless_present_any = ["all", "project"],
+            conflicts_with_all = ["all", "project"]
+        )]
+        target: Option<String>,
+        /// Wait on every agent tracked right now
+        #[arg(long)]
+        all: bool,
+        /// Wait on the agents in this project: its root path, or the
+        /// root's directory name
+        #[arg(long)]
+        project: Option<String>,
+        /// State to wait for: idle, attention, ended, or context>N
+        /// (also context>=N), N a context usage percentage
+        #[arg(long)]
+        until: WaitCondition,
+        /// Accept agents already idle or waiting for attention; by default
+        /// they must leave that state first, so a wait right after `send`
+        /// sees the turn through
+        #[arg(long)]
+        now: bool,
+        /// Return as soon as one agent meets the condition, rather than all
+        #[arg(long)]
+        any: bool,
+        /// Give up after this long: "90s", "10m", "2h" (bare numbers are seconds)
+        #[arg(long)]
+        timeout: Option<String>,
+    },
+    /// Show past events from the daemon's event journal
+    History {
+        /// Session ID or ID prefix (e.g., "a1b2c3d4"); omit for all sessions
+        session: Option<String>,
+        /// Only events at/after this time: "30m", "2h", "7d", "14:00",
+        /// "2024-01-15", "2024-01-15 14:00", or RFC 3339
+        #[arg(long)]
+        since: Option<String>,
+        /// Only events before this time (same formats as --since)
+        #[arg(long)]
+        until: Option<String>,
+        /// Only this event type (e.g., tool_call_start, prompt_submit, session_removed)
+        #[arg(long)]
+        event: Option<String>,
+        /// Show only the newest N matching events
+        #[arg(long, short = 'n')]
+        limit: Option<usize>,
+        /// Output format
+        #[arg(long, short = 'f', default_value = "table")]
+        format: HistoryFormat,
+    },
+    /// Report spend from the daemon's cost ledger
+    Cost {
+        /// What to group spend by
+        #[arg(long, default_value = "project")]
+        by: CostBy,
+        /// Only spend on/after this day: "7d", "2024-01-15", ... (same
+        /// formats as `history --since`)
+        #[arg(long)]
+        since: Option<String>,
+        /// Output format
+        #[arg(long, short = 'f', default_value = "table")]
+        format: CostFormat,
+    },
     /// Launch or attach to a tmux workspace with ATM sidebar
     Workspace {
         #[command(subcommand)]
@@ -183,9 +276,91 @@ enum Command {
         /// Split the current pane in-place (default: create new window)
         #[arg(long)]
         in_place: bool,
+        /// Team to tag the layout's agent panes with (default: the
+        /// session name, or the layout name plus its first agent pane)
+        #[arg(long)]
+        team: Option<String>,
     },
 }
 
+/// Flags that apply `kill`, `interrupt` or `send` to every matching
+/// agent instead of one target. Filters combine with AND.
+#[derive(clap::Args, Debug, Clone, Default)]
+struct SessionSelector {
+    /// Act on every tracked agent
+    #[arg(long)]
+    all: bool,
+    /// Act on agents in this project: its root path, or the root's
+    /// directory name
+    #[arg(long)]
+    project: Option<String>,
+    /// Act on agents with this status (working, idle, attention)
+    #[arg(long)]
+    status: Option<String>,
+    /// Don't ask before acting on the selected agents
+    #[arg(long, short = 'y')]
+    yes: bool,
+}
+
+impl SessionSelector {
+    /// Whether any selector flag was given.
+    fn is_set(&self) -> bool {
+        self.all || self.project.is_some() || self.status.is_some()
+    }
+
+    /// Whether `session` passes every given filter.
+    fn matches(&self, session: &SessionView) -> bool {
+        let status_ok = self
+            .status
+            .as_deref()
+            .is_none_or(|status| status_matches(status, session.status));
+        let project_ok = self.project.as_deref().is_none_or(|project| {
+            session
+                .project_root
+                .as_deref()
+                .is_some_and(|root| project_matches(project, root))
+        });
+        status_ok && project_ok
+    }
+
+    /// Sessions the selector picks out that have a pane to act on.
+    fn select<'a>(&self, sessions: &'a [SessionView]) -> Result<Vec<&'a SessionView>> {
+        if let Some(status) = &self.status {
+            if parse_status_filter(status).is_none() {
+                bail!("Unknown status '{status}' (expected working, idle or attention)");
+            }
+        }
+        Ok(sessions
+            .iter()
+            .filter(|s| s.tmux_pane.is_some() && self.matches(s))
+            .collect())
+    }
+}
+
+/// Whether a `--project` value names the project at `root`: the whole
+/// path, or its last component. `api` is not `api-gateway` or `rapid`.
+fn project_matches(project: &str, root: &str) -> bool {
+    let project = project.trim_end_matches('/');
+    let root = root.trim_end_matches('/');
+    !project.is_empty() && (root == project || root.rsplit('/').next() == Some(project))
+}
+
+/// Parses a `--status` filter value.
+fn parse_status_filter(status: &str) -> Option<atm_core::SessionStatus> {
+    match status.to_lowercase().as_str() {
+        "working" | "active" => Some(atm_core::SessionStatus::Working),
+        "idle" => Some(atm_core::SessionStatus::Idle),
+        "attention" | "waiting" => Some(atm_core::SessionStatus::AttentionNeeded),
+        _ => None,
+    }
+}
+
+/// Whether `actual` passes a `--status` filter. Unknown filter values
+/// match everything.
+fn status_matches(filter: &str, actual: atm_core::SessionStatus) -> bool {
+    parse_status_filter(filter).is_none_or(|wanted| wanted == actual)
+}
+
 #[derive(Debug, Clone, Subcommand)]
 enum WorkspaceAction {
     /// Create a new tmux workspace with ATM sidebar, agent, and shell
@@ -236,6 +411,38 @@ enum ListFormat {
     Ids,
 }
 
+#[derive(Debug, Clone, ValueEnum)]
+enum HistoryFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CostBy {
+    Project,
+    Model,
+    Harness,
+    Day,
+}
+
+impl From<CostBy> for atmd::ledger::CostGrouping {
+    fn from(by: CostBy) -> Self {
+        match by {
+            CostBy::Project => Self::Project,
+            CostBy::Model => Self::Model,
+            CostBy::Harness => Self::Harness,
+            CostBy::Day => Self::Day,
+        }
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum CostFormat {
+    Table,
+    Json,
+    Csv,
+}
+
 // ============================================================================
 // Terminal Setup / Cleanup
 // ============================================================================
@@ -361,8 +568,8 @@ async fn run_event_loop(
 ) -> Result<()> {
     let tick_rate = Duration::from_millis(100);
 
-    // Vim keybinding handler (DFA state machine)
-    let mut handler = InputHandler::new();
+    // Vim keybinding handler (DFA state machine over the user's keymap)
+    let mut handler = InputHandler::with_keymap(app.keymap.clone());
 
     // Viewport height for half-page navigation (updated each frame)
     let mut viewport_height: u16 = 0;
@@ -388,6 +595,9 @@ async fn run_event_loop(
         match event {
             Ok(Some(received_event)) => match received_event {
                 Event::Key(key) => {
+                    // A status message lasts until the next key press
+                    app.status_message = None;
+
                     // When help is visible, intercept keys before the DFA.
                     // This is necessary because Esc maps to Quit in the DFA,
                     // but we want it to dismiss help instead.
@@ -404,6 +614,111 @@ async fn run_event_loop(
                             }
                             _ => {} // Swallow all other keys
                         }
+                    } else if let Some(confirm) = app.confirm.take() {
+                        // Multi-agent kill/interrupt waits for y or n
+                        match key.code {
+                            KeyCode::Char('y') | KeyCode::Enter => {
+                                for target in &confirm.targets {
+                                    let id = target.session_id.to_string();
+                                    let cmd = match confirm.action {
+                                        BroadcastAction::Kill => ClientCommand::KillSession(id),
+                                        BroadcastAction::Interrupt => {
+                                            ClientCommand::InterruptSession(id)
+                                        }
+                                    };
+                                    if command_tx.send(cmd).is_err() {
+                                        warn!("Failed to send broadcast request");
+                                    }
+                                }
+                                info!(
+                                    action = confirm.action.label(),
+                                    count = confirm.targets.len(),
+                                    "Applied action to marked agents"
+                                );
+                                app.clear_marks();
+                            }
+                            KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => {
+                                app.quit();
+                                cancel_token.cancel();
+                                break;
+                            }
+                            KeyCode::Char('n') | KeyCode::Char('q') | KeyCode::Esc => {}
+                            // Keep asking on any other key
+                            _ => app.confirm = Some(confirm),
+                        }
+                    } else if let Some(popup) = app.reply.as_mut() {
+                        // The reply popup owns the keyboard while open
+                        let typing = popup.text.is_some();
+                        let answer = match resolve_reply_key(&key, typing) {
+                            Some(ReplyPopupAction::Down) => {
+                                popup.move_down();
+                                None
+                            }
+                            Some(ReplyPopupAction::Up) => {
+                                popup.move_up();
+                                None
+                            }
+                            Some(ReplyPopupAction::Pick(n)) => popup.pick(n),
+                            Some(ReplyPopupAction::Submit) => popup.answer(),
+                            Some(ReplyPopupAction::Reject) => Some(ReplyAnswer::Reject),
+                            Some(ReplyPopupAction::StartText) => {
+                                popup.start_text();
+                                None
+                            }
+                            Some(ReplyPopupAction::Char(c)) => {
+                                if let Some(text) = popup.text.as_mut() {
+                                    text.push(c);
+                                }
+                                None
+                            }
+                            Some(ReplyPopupAction::Backspace) => {
+                                if let Some(text) = popup.text.as_mut() {
+                                    text.pop();
+                                }
+                                None
+                            }
+                            Some(ReplyPopupAction::Close) => {
+                                // Esc leaves text entry first, then closes
+                                if typing {
+                                    popup.text = None;
+                                } else {
+                                    app.close_reply();
+                                }
+                                None
+                            }
+                            Some(ReplyPopupAction::Quit) => {
+                                app.quit();
+                                cancel_token.cancel();
+                                break;
+                            }
+                            None => None,
+                        };
+                        if let Some(answer) = answer {
+                            if let Some(popup) = app.reply.take() {
+                                send_reply(command_tx, popup.session_id.to_string(), answer);
+                            }
+                        }
+                    } else if app.search.editing {
+                        // The search bar narrows the tree as keys are typed
+                        match resolve_search_key(&key) {
+                            Some(SearchInputAction::Char(c)) => app.push_search_char(c),
+                            Some(SearchInputAction::Backspace) => app.pop_search_char(),
+                            Some(SearchInputAction::ClearLine) => app.clear_search_line(),
+                            Some(SearchInputAction::Accept) => app.accept_search(),
+                            Some(SearchInputAction::Cancel) => app.cancel_search(),
+                            Some(SearchInputAction::Next) => app.search_next(),
+                            Some(SearchInputAction::Previous) => app.search_previous(),
+                            Some(SearchInputAction::Quit) => {
+                                app.quit();
+                                cancel_token.cancel();
+                                break;
+                            }
+                            None => {}
+                        }
+                    } else if key.code == KeyCode::Esc && app.has_filter() && !handler.is_pending()
+                    {
+                        // Esc drops an active filter before it quits
+                        app.clear_filters();
                     } else if let Some(action) = handler.handle(key) {
                         match action {
                             UiAction::Quit => {
@@ -473,34 +788,72 @@ async fn run_event_loop(
                             UiAction::CollapseAllFolds => app.collapse_all(),
                             UiAction::ExpandAllFolds => app.expand_all(),
                             UiAction::KillAgent => {
-                                if let Some(session) = app.selected_session() {
-                                    if let Some(ref pane_id) = session.tmux_pane {
-                                        let pane_id = pane_id.clone();
-                                        let client = RealTmuxClient::new();
-                                        info!(pane_id = %pane_id, "Killing agent pane");
-                                        tokio::spawn(async move {
-                                            if let Err(e) = client.kill_pane(&pane_id).await {
-                                                warn!(error = %e, "Failed to kill pane");
-                                            }
-                                        });
+                                if app.request_broadcast(BroadcastAction::Kill) {
+                                    debug!("Confirming kill of marked agents");
+                                } else if let Some(session) = app.selected_session() {
+                                    if session.tmux_pane.is_some() {
+                                        info!(session_id = %session.id, "Killing agent pane");
+                                        let cmd =
+                                            ClientCommand::KillSession(session.id.to_string());
+                                        if command_tx.send(cmd).is_err() {
+                                            warn!("Failed to send kill request");
+                                        }
                                     }
                                 }
                             }
                             UiAction::InterruptAgent => {
-                                if let Some(session) = app.selected_session() {
-                                    if let Some(ref pane_id) = session.tmux_pane {
-                                        let pane_id = pane_id.clone();
-                                        let client = RealTmuxClient::new();
-                                        info!(pane_id = %pane_id, "Interrupting agent");
-                                        tokio::spawn(async move {
-                                            if let Err(e) = client.send_keys(&pane_id, "C-c").await
-                                            {
-                                                warn!(error = %e, "Failed to interrupt");
-                                            }
-                                        });
+                                if app.request_broadcast(BroadcastAction::Interrupt) {
+                                    debug!("Confirming interrupt of marked agents");
+                                } else if let Some(session) = app.selected_session() {
+                                    if session.tmux_pane.is_some() {
+                                        info!(session_id = %session.id, "Interrupting agent");
+                                        let cmd =
+                                            ClientCommand::InterruptSession(session.id.to_string());
+                                        if command_tx.send(cmd).is_err() {
+                                            warn!("Failed to send interrupt request");
+                                        }
+                                    }
+                                }
+                            }
+                            UiAction::ToggleMark => app.toggle_mark(),
+                            UiAction::ClearMarks => app.clear_marks(),
+                            UiAction::StartSearch => app.start_search(),
+                            UiAction::SearchNext => app.search_next(),
+                            UiAction::SearchPrevious => app.search_previous(),
+                            UiAction::CycleStatusFilter => app.cycle_status_filter(),
+                            UiAction::CycleHarnessFilter => app.cycle_harness_filter(),
+                            UiAction::CycleSort => {
+                                app.cycle_sort();
+                                save_view_config(app);
+                            }
+                            UiAction::CycleGroup => {
+                                app.cycle_group();
+                                save_view_config(app);
+                            }
+                            UiAction::OpenReply => {
+                                if !app.open_reply() {
+                                    debug!("Selection is not waiting on a prompt");
+                                }
+                            }
+                            UiAction::OpenReplyText => {
+                                if app.open_reply() {
+                                    if let Some(popup) = app.reply.as_mut() {
+                                        popup.start_text();
                                     }
                                 }
                             }
+                            UiAction::ReplyAccept
+                            | UiAction::ReplyReject
+                            | UiAction::ReplyOption(_) => {
+                                if let Some(session) = app.selected_waiting_session() {
+                                    let answer = match action {
+                                        UiAction::ReplyOption(n) => ReplyAnswer::Option(n),
+                                        UiAction::ReplyReject => ReplyAnswer::Reject,
+                                        _ => ReplyAnswer::Accept,
+                                    };
+                                    send_reply(command_tx, session.id.to_string(), answer);
+                                }
+                            }
                             UiAction::SpawnAgent
                             | UiAction::SpawnAgentLeft
                             | UiAction::SpawnAgentRight
@@ -604,16 +957,22 @@ async fn run_event_loop(
                         let _ = capture_pane_tx.send(new_pane);
                     }
                 }
-                Event::SessionRemoved(session_id) => {
+                Event::SessionRemoved { session_id, .. } => {
                     debug!(session_id = %session_id, "Session removed");
                     app.remove_session(&session_id);
                 }
                 Event::FilterUpdate(pane_ids) => {
                     app.update_filter_panes(pane_ids);
                 }
+                Event::PaneWindows(pane_windows) => {
+                    app.update_pane_windows(pane_windows);
+                }
                 Event::DiscoveryComplete { discovered, failed } => {
                     info!(discovered, failed, "Discovery complete");
                 }
+                Event::ControlFailed(reason) => {
+                    app.status_message = Some(reason);
+                }
                 Event::DaemonDisconnected => {
                     warn!("Daemon disconnected");
                     app.mark_disconnected();
@@ -639,6 +998,36 @@ async fn run_event_loop(
     Ok(())
 }
 
+/// Sends a reply popup answer to the daemon for `target`.
+fn send_reply(
+    command_tx: &mpsc::UnboundedSender<ClientCommand>,
+    target: String,
+    answer: ReplyAnswer,
+) {
+    info!(target = %target, ?answer, "Answering prompt");
+    let cmd = match answer {
+        ReplyAnswer::Option(n) => ClientCommand::ReplyPrompt {
+            target,
+            choice: ReplyChoice::Option(n),
+        },
+        ReplyAnswer::Accept => ClientCommand::ReplyPrompt {
+            target,
+            choice: ReplyChoice::Accept,
+        },
+        ReplyAnswer::Reject => ClientCommand::ReplyPrompt {
+            target,
+            choice: ReplyChoice::Reject,
+        },
+        ReplyAnswer::Text(text) => ClientCommand::ReplyPrompt {
+            target,
+            choice: ReplyChoice::Text(text),
+        },
+    };
+    if command_tx.send(cmd).is_err() {
+        warn!("Failed to send reply request");
+    }
+}
+
 // ============================================================================
 // Logging Setup
 // ============================================================================
@@ -679,8 +1068,16 @@ fn create_log_file() -> Option<std::fs::File> {
 // CLI Command Implementations
 // ============================================================================
 
-/// Fetches the current session list from the daemon via one-shot connection.
-async fn fetch_sessions() -> Result<Vec<SessionView>> {
+/// Sends one request to the daemon over a one-shot connection.
+///
+/// Performs the handshake, sends `request` tagged with a request ID, and
+/// returns the first reply to it that `extract` accepts. A daemon `Error`
+/// reply is surfaced as an error, as is a daemon too old to understand
+/// `request`.
+async fn daemon_request<T>(
+    request: ClientMessage,
+    extract: impl Fn(DaemonMessage) -> Option<T>,
+) -> Result<T> {
     let socket_path = atm_tui::client::resolve_socket_path();
 
     let stream = UnixStream::connect(&socket_path)
@@ -710,12 +1107,13 @@ async fn fetch_sessions() -> Result<Vec<SessionView>> {
     )
     .await?;
 
-    // Step 2: Read Connected response, then send ListSessions
-    let mut sessions = Vec::new();
+    // Step 2: Read Connected response, then send the request
+    const REQUEST_ID: u64 = 1;
+    let request = request.with_request_id(REQUEST_ID);
     let mut line = String::new();
     let deadline = Duration::from_secs(5);
 
-    loop {
+    let result = loop {
         line.clear();
         let n = tokio::time::timeout(deadline, buf_reader.read_line(&mut line))
             .await
@@ -723,21 +1121,22 @@ async fn fetch_sessions() -> Result<Vec<SessionView>> {
             .context("Failed to read from daemon")?;
 
         if n == 0 {
-            break;
+            bail!("Daemon closed the connection without replying");
         }
 
         if let Ok(msg) = serde_json::from_str::<DaemonMessage>(line.trim()) {
             match msg {
-                DaemonMessage::Connected { .. } => {
-                    // Now request the session list
-                    send(&mut writer, &ClientMessage::list_sessions()).await?;
-                    continue;
-                }
-                DaemonMessage::SessionList {
-                    sessions: sess_list,
+                DaemonMessage::Connected {
+                    protocol_version, ..
                 } => {
-                    sessions = sess_list;
-                    break;
+                    if !request.message.is_supported_by(&protocol_version) {
+                        bail!(
+                            "atmd speaks protocol {protocol_version}, which is too old for this \
+                             request; restart atmd to upgrade it"
+                        );
+                    }
+                    send(&mut writer, &request).await?;
+                    continue;
                 }
                 DaemonMessage::Rejected { reason, .. } => {
                     bail!("Daemon rejected connection: {reason}");
@@ -745,10 +1144,15 @@ async fn fetch_sessions() -> Result<Vec<SessionView>> {
                 DaemonMessage::Error { message, .. } => {
                     bail!("Daemon error: {message}");
                 }
+                other if other.request_id() == Some(REQUEST_ID) => {
+                    if let Some(value) = extract(other) {
+                        break value;
+                    }
+                }
                 _ => continue,
             }
         }
-    }
+    };
 
     // Send disconnect
     let disconnect_msg = ClientMessage::disconnect();
@@ -756,38 +1160,33 @@ async fn fetch_sessions() -> Result<Vec<SessionView>> {
         let _ = writer.write_all(format!("{json}\n").as_bytes()).await;
     }
 
-    Ok(sessions)
+    Ok(result)
 }
 
-/// Resolves a target (session ID prefix or pane ID) to a tmux pane ID.
-fn resolve_pane_id(sessions: &[SessionView], target: &str) -> Result<String> {
-    // If it starts with %, it's already a pane ID
-    if target.starts_with('%') {
-        return Ok(target.to_string());
-    }
-
-    // Search by session ID prefix
-    let matches: Vec<&SessionView> = sessions
-        .iter()
-        .filter(|s| s.id.as_str().starts_with(target) || s.id_short.starts_with(target))
-        .collect();
+/// Fetches the current session list from the daemon via one-shot connection.
+async fn fetch_sessions() -> Result<Vec<SessionView>> {
+    daemon_request(ClientMessage::list_sessions(), |msg| match msg {
+        DaemonMessage::SessionList { sessions, .. } => Some(sessions),
+        _ => None,
+    })
+    .await
+}
 
-    match matches.len() {
-        0 => bail!("No session matching '{target}'"),
-        1 => match &matches[0].tmux_pane {
-            Some(pane) => Ok(pane.clone()),
-            None => bail!("Session {} has no tmux pane", matches[0].id_short),
-        },
-        n => {
-            let ids: Vec<&str> = matches.iter().map(|s| s.id_short.as_str()).collect();
-            bail!(
-                "Ambiguous target '{target}' matches {n} sessions: {}",
-                ids.join(", ")
-            )
-        }
-    }
+/// Asks the daemon to execute a control request, returning the pane it
+/// acted on and the keys it sent.
+async fn send_control(request: ClientMessage) -> Result<(String, Vec<String>)> {
+    daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
+    daemon_request(request, |msg| match msg {
+        DaemonMessage::ControlResult { pane_id, keys, .. } => Some((pane_id, keys)),
+        _ => None,
+    })
+    .await
 }
 
+/// Env var carrying the team an agent was spawned into. The hook scripts
+/// and pi extension forward it so the daemon can group the team's agents.
+const TEAM_ENV: &str = "ATM_TEAM";
+
 /// POSIX shell-quote `s` by single-quoting and escaping embedded `'`.
 /// Result is exactly one shell token regardless of spaces or specials.
 fn shell_quote(s: &str) -> String {
@@ -821,6 +1220,44 @@ fn shell_quote(s: &str) -> String {
 struct AtmConfig {
     #[serde(default)]
     harness: HarnessConfig,
+    #[serde(default)]
+    view: ViewConfig,
+    /// Key chord → action name overrides from the `[keys]` table.
+    #[serde(default)]
+    keys: BTreeMap<String, String>,
+}
+
+/// Dashboard view from the `[view]` table, saved whenever it is cycled
+/// in the TUI.
+///
+/// Unknown values are logged and ignored rather than failing the whole
+/// config, so a typo here can't break `atm spawn`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ViewConfig {
+    /// Grouping mode: project, tmux, harness, status or flat.
+    #[serde(default, deserialize_with = "lenient_view_value")]
+    group: Option<GroupBy>,
+    /// Sort order: newest, cost, context, duration, activity, attention
+    /// or name.
+    #[serde(default, deserialize_with = "lenient_view_value")]
+    sort: Option<SortKey>,
+}
+
+/// Deserializes a `[view]` value, falling back to `None` with a warning
+/// when it isn't one the TUI knows.
+fn lenient_view_value<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let value = toml::Value::deserialize(deserializer)?;
+    match T::deserialize(value.clone()) {
+        Ok(parsed) => Ok(Some(parsed)),
+        Err(e) => {
+            warn!(value = %value, error = %e, "Ignoring unknown [view] setting; using the default");
+            Ok(None)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -927,6 +1364,34 @@ default = "claude"
 # binary = "custom-agent"
 # default_args = ["--profile", "atm"]
 # model_flag = "--model-id"
+
+# Dashboard grouping and sort order. Cycling them in the TUI (`v`, `S`)
+# saves them here.
+# group: project, tmux, harness, status or flat
+# sort: newest, cost, context, duration, activity, attention or name
+# [view]
+# group = "project"
+# sort = "newest"
+
+# Key bindings. Each entry binds a chord to a TUI action, replacing the
+# built-in binding for that chord; "none" unbinds it. Printable keys stand
+# for themselves (`gs`, `zM`); special keys go in angle brackets: <C-n>,
+# <Down>, <Up>, <Left>, <Right>, <Enter>, <Esc>, <Tab>, <Space>, <lt>.
+# Chords can't start with 1-9 (counts) or start another chord (`g` vs `gg`).
+# Actions: move_down, move_up, go_to_first, go_to_last, half_page_down,
+# half_page_up, expand_node, close_fold, toggle_fold, collapse_all_folds,
+# expand_all_folds, jump_to_session, refresh, quit, toggle_help,
+# toggle_mark, clear_marks, kill_agent, interrupt_agent, cycle_sort,
+# cycle_group, spawn_agent, spawn_agent_left, spawn_agent_below,
+# spawn_agent_above, spawn_agent_right, open_reply, open_reply_text,
+# reply_accept, reply_reject, reply_option_1..reply_option_9,
+# start_search, search_next, search_previous, cycle_status_filter,
+# cycle_harness_filter.
+# [keys]
+# "<C-n>" = "move_down"
+# "<C-p>" = "move_up"
+# gs = "cycle_sort"
+# x = "none"
 "#
 }
 
@@ -938,10 +1403,25 @@ fn ensure_default_config_file(path: &std::path::Path) -> Result<()> {
         fs::create_dir_all(parent)
             .with_context(|| format!("Failed to create config directory {}", parent.display()))?;
     }
-    fs::write(path, default_config_text())
+    write_private_file(path, default_config_text())
         .with_context(|| format!("Failed to write default config {}", path.display()))
 }
 
+/// Writes `contents` to `path`, creating it readable by the owner only.
+///
+/// The config file can hold remote access tokens, so new copies of it are
+/// never group- or world-readable.
+fn write_private_file(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
 fn load_atm_config() -> Result<AtmConfig> {
     let Some(path) = config_path() else {
         return Ok(AtmConfig::default());
@@ -961,6 +1441,101 @@ fn load_atm_config() -> Result<AtmConfig> {
     toml::from_str(&content).with_context(|| format!("Failed to parse config {}", path.display()))
 }
 
+/// Saves the TUI's current view so the next start opens with it.
+fn save_view_config(app: &App) {
+    let options = &app.tree_options;
+    if let Err(e) = write_view_config(options.group_by, options.sort_by) {
+        warn!(error = %e, "Failed to save view to config");
+    }
+}
+
+/// Writes the dashboard view to the `[view]` table of the config file.
+///
+/// Every sidebar shares the file, so the new contents go to a temporary
+/// file that is renamed over it; a reader never sees half a config. A
+/// symlinked config is resolved first so the link survives, and the file
+/// keeps its permissions.
+fn write_view_config(group: GroupBy, sort: SortKey) -> Result<()> {
+    let Some(path) = config_path() else {
+        return Ok(());
+    };
+    let path = match fs::canonicalize(&path) {
+        Ok(real) => real,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => path,
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to resolve config {}", path.display()))
+        }
+    };
+    let permissions = fs::metadata(&path).ok().map(|m| m.permissions());
+    let content = if permissions.is_some() {
+        fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?
+    } else {
+        default_config_text().to_string()
+    };
+    let updated = set_view_keys(&content, group, sort)
+        .with_context(|| format!("Failed to update config {}", path.display()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create config directory {}", parent.display()))?;
+    }
+    let tmp_path = path.with_extension(format!("toml.{}.tmp", std::process::id()));
+    write_private_file(&tmp_path, &updated)
+        .with_context(|| format!("Failed to write config {}", tmp_path.display()))?;
+    if let Some(permissions) = permissions {
+        fs::set_permissions(&tmp_path, permissions).with_context(|| {
+            let _ = fs::remove_file(&tmp_path);
+            format!("Failed to set permissions on {}", tmp_path.display())
+        })?;
+    }
+    fs::rename(&tmp_path, &path).with_context(|| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to replace config {}", path.display())
+    })
+}
+
+/// Sets `group` and `sort` in the `[view]` table of `content`, adding
+/// the keys or the table as needed.
+///
+/// Edits the config through `toml_edit` so the user's comments and layout
+/// survive.
+fn set_view_keys(content: &str, group: GroupBy, sort: SortKey) -> Result<String> {
+    let mut doc: toml_edit::DocumentMut = content.parse()?;
+    if !doc.contains_key("view") {
+        let mut view = toml_edit::Table::new();
+        // Comments after the last table (all of a fresh config) stay ahead
+        // of the new table rather than being rendered after it
+        let trailing = doc.trailing().as_str().unwrap_or("").to_string();
+        if !trailing.is_empty() {
+            view.decor_mut().set_prefix(trailing);
+            doc.set_trailing("");
+        }
+        doc.insert("view", toml_edit::Item::Table(view));
+    }
+    let Some(view) = doc.get_mut("view").and_then(|v| v.as_table_like_mut()) else {
+        bail!("`view` in config is not a table");
+    };
+    set_config_value(view, "group", group.label());
+    set_config_value(view, "sort", sort.label());
+    Ok(doc.to_string())
+}
+
+/// Sets `key` in `table`, keeping the comments around an existing value.
+fn set_config_value(table: &mut dyn toml_edit::TableLike, key: &str, value: &str) {
+    match table.get_mut(key) {
+        Some(item) => {
+            let decor = item.as_value().map(|v| v.decor().clone());
+            *item = toml_edit::value(value);
+            if let (Some(decor), Some(new)) = (decor, item.as_value_mut()) {
+                *new.decor_mut() = decor;
+            }
+        }
+        None => {
+            table.insert(key, toml_edit::value(value));
+        }
+    }
+}
+
 fn resolve_spawn_harness(harness_id: Option<&str>) -> Result<SpawnHarnessDefinition> {
     let config = load_atm_config()?;
     let requested = harness_id
@@ -1051,6 +1626,10 @@ fn build_spawn_command_for_harness(
     if let (Some(flag), Some(m)) = (harness.model_flag.as_deref(), model) {
         cmd.push_str(&format!(" {flag} {}", shell_quote(m)));
     }
+    // Agents spawned from inside a team join it
+    if let Some(team) = non_empty_env(TEAM_ENV) {
+        cmd = format!("{TEAM_ENV}={} {cmd}", shell_quote(&team));
+    }
     if let Some(dir) = cwd {
         cmd = format!("cd {} && {cmd}", shell_quote(dir));
     }
@@ -1132,79 +1711,147 @@ async fn cmd_spawn(
     Ok(())
 }
 
-async fn cmd_kill(target: String) -> Result<()> {
-    daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
-    let sessions = fetch_sessions().await?;
-    let pane_id = resolve_pane_id(&sessions, &target)?;
-    let client = RealTmuxClient::new();
-    client
-        .kill_pane(&pane_id)
-        .await
-        .context(format!("Failed to kill pane {pane_id}"))?;
+async fn cmd_kill(target: Option<String>, selector: SessionSelector) -> Result<()> {
+    let Some(target) = target else {
+        return broadcast(&selector, "Kill", "Killed", ClientMessage::kill_session).await;
+    };
+    let (pane_id, _) = send_control(ClientMessage::kill_session(target)).await?;
     println!("Killed {pane_id}");
     Ok(())
 }
 
-async fn cmd_interrupt(target: String) -> Result<()> {
-    daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
-    let sessions = fetch_sessions().await?;
-    let pane_id = resolve_pane_id(&sessions, &target)?;
-    let client = RealTmuxClient::new();
-    // C-c sends SIGINT to the foreground process in the pane
-    client
-        .send_keys(&pane_id, "C-c")
-        .await
-        .context(format!("Failed to interrupt pane {pane_id}"))?;
+async fn cmd_interrupt(target: Option<String>, selector: SessionSelector) -> Result<()> {
+    let Some(target) = target else {
+        return broadcast(
+            &selector,
+            "Interrupt",
+            "Interrupted",
+            ClientMessage::interrupt_session,
+        )
+        .await;
+    };
+    let (pane_id, _) = send_control(ClientMessage::interrupt_session(target)).await?;
     println!("Interrupted {pane_id}");
     Ok(())
 }
 
-async fn cmd_send(target: String, text: String) -> Result<()> {
-    daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
-    let sessions = fetch_sessions().await?;
-    let pane_id = resolve_pane_id(&sessions, &target)?;
-    let client = RealTmuxClient::new();
-    client
-        .send_keys(&pane_id, &text)
-        .await
-        .context(format!("Failed to send keys to pane {pane_id}"))?;
-    // Send Enter to submit the text
-    client
-        .send_keys(&pane_id, "Enter")
-        .await
-        .context(format!("Failed to send Enter to pane {pane_id}"))?;
+async fn cmd_send(
+    target: Option<String>,
+    text: Option<String>,
+    selector: SessionSelector,
+) -> Result<()> {
+    if selector.is_set() {
+        // With a selector the only positional is the text
+        let text = match (target, text) {
+            (Some(text), None) => text,
+            (None, _) => bail!("No text given"),
+            (Some(_), Some(_)) => bail!("Give a target or selector flags, not both"),
+        };
+        return broadcast(&selector, "Send to", "Sent to", |id| {
+            ClientMessage::send_input(id, text.clone())
+        })
+        .await;
+    }
+    let (Some(target), Some(text)) = (target, text) else {
+        bail!("Usage: atm send <TARGET> <TEXT>, or atm send --all|--project|--status <TEXT>");
+    };
+    send_control(ClientMessage::send_input(target, text)).await?;
     Ok(())
 }
 
-async fn cmd_list(
-    format: ListFormat,
-    status_filter: Option<String>,
-    project_filter: Option<String>,
+/// Applies a control request to every session `selector` picks out.
+///
+/// Prints the affected panes and asks for confirmation first unless
+/// `--yes` was given; without a terminal to ask on, `--yes` is required.
+/// Every session is attempted even if some fail.
+async fn broadcast(
+    selector: &SessionSelector,
+    verb: &str,
+    done: &str,
+    request: impl Fn(String) -> ClientMessage,
 ) -> Result<()> {
     daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
     let sessions = fetch_sessions().await?;
+    let targets = selector.select(&sessions)?;
+    if targets.is_empty() {
+        println!("No matching agents");
+        return Ok(());
+    }
 
-    // Apply filters
-    let filtered: Vec<&SessionView> = sessions
-        .iter()
-        .filter(|s| {
+    let plural = if targets.len() == 1 { "" } else { "s" };
+    println!("{verb} {} agent{plural}:", targets.len());
+    for s in &targets {
+        let project = s
+            .project_root
+            .as_deref()
+            .and_then(|p| p.rsplit('/').find(|s| !s.is_empty()))
+            .unwrap_or("-");
+        println!(
+            "  {:<6} {}\t{}\t{}",
+            s.tmux_pane.as_deref().unwrap_or("-"),
+            s.id_short,
+            s.status_label,
+            project
+        );
+    }
+    if !selector.yes && !confirm_on_terminal("Proceed?")? {
+        println!("Aborted");
+        return Ok(());
+    }
+
+    let mut failed = 0usize;
+    for s in &targets {
+        match send_control(request(s.id.to_string())).await {
+            Ok((pane_id, _)) => println!("{done} {pane_id}"),
+            Err(e) => {
+                eprintln!("{}: {e:#}", s.id_short);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("{failed} of {} agents failed", targets.len());
+    }
+    Ok(())
+}
+
+/// Asks a yes/no question on the terminal; anything but `y`/`yes` is no.
+fn confirm_on_terminal(question: &str) -> Result<bool> {
+    use std::io::{IsTerminal, Write};
+
+    if !io::stdin().is_terminal() {
+        bail!("Not a terminal; pass --yes to act on the selected agents");
+    }
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+async fn cmd_list(
+    format: ListFormat,
+    status_filter: Option<String>,
+    project_filter: Option<String>,
+) -> Result<()> {
+    daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
+    let sessions = fetch_sessions().await?;
+
+    // Apply filters
+    let filtered: Vec<&SessionView> = sessions
+        .iter()
+        .filter(|s| {
             if let Some(ref status) = status_filter {
-                let matches = match status.to_lowercase().as_str() {
-                    "working" | "active" => s.status == atm_core::SessionStatus::Working,
-                    "idle" => s.status == atm_core::SessionStatus::Idle,
-                    "attention" | "waiting" => s.status == atm_core::SessionStatus::AttentionNeeded,
-                    _ => true,
-                };
-                if !matches {
+                if !status_matches(status, s.status) {
                     return false;
                 }
             }
             if let Some(ref project) = project_filter {
-                if let Some(ref root) = s.project_root {
-                    if !root.contains(project.as_str()) {
-                        return false;
-                    }
-                } else {
+                if !s
+                    .project_root
+                    .as_deref()
+                    .is_some_and(|root| project_matches(project, root))
+                {
                     return false;
                 }
             }
@@ -1251,115 +1898,56 @@ async fn cmd_list(
     Ok(())
 }
 
-async fn cmd_reply(target: String, option: Option<usize>, yes: bool, no: bool) -> Result<()> {
-    daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
-    let sessions = fetch_sessions().await?;
-    let pane_id = resolve_pane_id(&sessions, &target)?;
-    let client = RealTmuxClient::new();
-
-    if no {
-        // Escape dismisses/cancels the prompt
-        client
-            .send_keys(&pane_id, "Escape")
-            .await
-            .context("Failed to send Escape")?;
-        println!("Sent Escape to {pane_id}");
-        return Ok(());
-    }
+async fn cmd_reply(
+    target: String,
+    option: Option<usize>,
+    yes: bool,
+    no: bool,
+    text: Option<String>,
+) -> Result<()> {
+    let choice = match (option, text) {
+        (_, Some(text)) => ReplyChoice::Text(text),
+        _ if no => ReplyChoice::Reject,
+        (Some(n), None) if !yes => ReplyChoice::Option(n),
+        _ => ReplyChoice::Accept,
+    };
 
-    if yes || option.is_none() {
-        // Just press Enter to accept current selection
-        client
-            .send_keys(&pane_id, "Enter")
-            .await
-            .context("Failed to send Enter")?;
-        println!("Sent Enter to {pane_id}");
-        return Ok(());
+    let (pane_id, _) = send_control(ClientMessage::reply_prompt(target, choice.clone())).await?;
+    match choice {
+        ReplyChoice::Reject => println!("Sent Escape to {pane_id}"),
+        ReplyChoice::Accept => println!("Sent Enter to {pane_id}"),
+        ReplyChoice::Option(n) => println!("Selected option {n} on {pane_id}"),
+        ReplyChoice::Text(_) => println!("Typed the answer into {pane_id}"),
     }
-
-    // Navigate to the desired option number
-    let desired = option.unwrap_or(1);
-
-    // Capture the pane to find which option the cursor is on
-    let lines = client
-        .capture_pane(&pane_id)
-        .await
-        .context("Failed to capture pane")?;
-
-    let current = find_selected_option(&lines).unwrap_or(1);
-
-    if desired == current {
-        // Already on the right option, just press Enter
-        client
-            .send_keys(&pane_id, "Enter")
-            .await
-            .context("Failed to send Enter")?;
-    } else if desired > current {
-        // Navigate down
-        for _ in 0..(desired - current) {
-            client
-                .send_keys(&pane_id, "Down")
-                .await
-                .context("Failed to send Down")?;
-        }
-        client
-            .send_keys(&pane_id, "Enter")
-            .await
-            .context("Failed to send Enter")?;
-    } else {
-        // Navigate up
-        for _ in 0..(current - desired) {
-            client
-                .send_keys(&pane_id, "Up")
-                .await
-                .context("Failed to send Up")?;
-        }
-        client
-            .send_keys(&pane_id, "Enter")
-            .await
-            .context("Failed to send Enter")?;
-    }
-
-    println!("Selected option {desired} on {pane_id}");
     Ok(())
 }
 
-/// Finds which numbered option currently has the ❯ cursor.
-/// Returns 1-based option number, or None if no cursor found.
-fn find_selected_option(lines: &[String]) -> Option<usize> {
-    for line in lines {
-        let trimmed = line.trim();
-        // Pattern: "❯ N. ..." where N is the option number
-        if let Some(rest) = trimmed.strip_prefix("❯ ") {
-            // Try to parse "N." at the start
-            if let Some(dot_pos) = rest.find('.') {
-                if let Ok(n) = rest[..dot_pos].trim().parse::<usize>() {
-                    return Some(n);
-                }
-            }
-        }
-    }
-    // No cursor found — assume option 1 (default selection)
-    None
-}
-
 async fn cmd_peek(target: String, tail: Option<usize>, prompt: bool) -> Result<()> {
     daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
     let sessions = fetch_sessions().await?;
-    let pane_id = resolve_pane_id(&sessions, &target)?;
+    let resolved = atmd::control::resolve_target(&sessions, &target)?;
+    let pane_id = resolved.pane_id;
     let client = RealTmuxClient::new();
     let lines = client
         .capture_pane(&pane_id)
         .await
         .context(format!("Failed to capture pane {pane_id}"))?;
 
-    let output: &[String] = if prompt {
-        extract_prompt(&lines)
+    let output: Vec<String> = if prompt {
+        let known = sessions
+            .iter()
+            .find(|s| s.id == resolved.session_id)
+            .and_then(|s| s.prompt.clone());
+        match PromptState::current(known, &lines) {
+            Some(state) => state.to_lines(),
+            // No prompt recognized: show the bottom of the pane instead
+            None => lines[lines.len().saturating_sub(15)..].to_vec(),
+        }
     } else if let Some(n) = tail {
         let start = lines.len().saturating_sub(n);
-        &lines[start..]
+        lines[start..].to_vec()
     } else {
-        &lines
+        lines
     };
 
     for line in output {
@@ -1368,167 +1956,536 @@ async fn cmd_peek(target: String, tail: Option<usize>, prompt: bool) -> Result<(
     Ok(())
 }
 
-/// Extracts the active prompt from captured pane content.
-///
-/// Scans backwards from the bottom looking for Claude Code prompt patterns:
-/// - Footer line: "Enter to select" / "Esc to cancel" / "(Y)es/(N)o"
-/// - Numbered options: "1. ...", "2. ..."
-/// - Separator lines (box drawing: ─── or ═══)
-/// - Question/title lines
-/// - Title blocks (☐/□/■)
-///
-/// Falls back to the last 15 lines if no prompt pattern is detected.
-fn extract_prompt(lines: &[String]) -> &[String] {
-    if lines.is_empty() {
-        return lines;
-    }
-
-    // Find the footer line (bottom of prompt)
-    let footer_idx = lines.iter().rposition(|l| {
-        let trimmed = l.trim();
-        trimmed.contains("Enter to select")
-            || trimmed.contains("Esc to cancel")
-            || trimmed.contains("(Y)es")
-            || trimmed.contains("(y/n)")
-            || trimmed.contains("to navigate")
-    });
+async fn cmd_status() -> Result<()> {
+    daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
+    let sessions = fetch_sessions().await?;
 
-    let end = footer_idx.map_or(lines.len(), |i| i + 1);
-
-    // Scan backwards from footer to find the top of the prompt block.
-    // Strategy: keep going as long as lines look like prompt content.
-    // Stop when we hit a line that's clearly not part of the prompt
-    // (e.g., regular output, the ● response marker, the ❯ input line).
-    let mut start = end.saturating_sub(1);
-
-    for i in (0..end.saturating_sub(1)).rev() {
-        let trimmed = lines[i].trim();
-
-        // Part of prompt: numbered options, indented descriptions, cursor
-        if trimmed.starts_with("1.")
-            || trimmed.starts_with("2.")
-            || trimmed.starts_with("3.")
-            || trimmed.starts_with("4.")
-            || trimmed.starts_with("5.")
-            || trimmed.starts_with("6.")
-            || trimmed.starts_with("7.")
-            || trimmed.starts_with("8.")
-            || trimmed.starts_with("9.")
-        {
-            start = i;
-            continue;
+    let active = sessions
+        .iter()
+        .filter(|s| {
+            matches!(
+                s.status,
+                atm_core::SessionStatus::Working | atm_core::SessionStatus::Idle
+            )
+        })
+        .count();
+
+    let attention = sessions
+        .iter()
+        .filter(|s| s.status == atm_core::SessionStatus::AttentionNeeded)
+        .count();
+
+    let total_cost: f64 = sessions.iter().map(|s| s.cost_usd).sum();
+
+    let mut parts = vec![format!("{active}\u{2191}")]; // ↑
+    if attention > 0 {
+        parts.push(format!("{attention}!"));
+    }
+    parts.push(format!("${total_cost:.2}"));
+    println!("{}", parts.join(" "));
+
+    Ok(())
+}
+
+// ============================================================================
+// Wait Command
+// ============================================================================
+
+/// Exit code when an agent ended before meeting the condition.
+const EXIT_WAIT_ENDED: i32 = 3;
+/// Exit code when `--timeout` elapsed (as with `timeout(1)`).
+const EXIT_WAIT_TIMEOUT: i32 = 124;
+
+/// A session state `atm wait --until` blocks on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WaitCondition {
+    Idle,
+    Attention,
+    Ended,
+    /// Context usage above (or, with `inclusive`, at least) a percentage
+    Context {
+        percent: f64,
+        inclusive: bool,
+    },
+}
+
+impl std::str::FromStr for WaitCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "idle" => return Ok(Self::Idle),
+            "attention" | "waiting" => return Ok(Self::Attention),
+            "ended" => return Ok(Self::Ended),
+            _ => {}
         }
+        let threshold = s
+            .strip_prefix("context")
+            .map(str::trim_start)
+            .and_then(|rest| match rest.strip_prefix(">=") {
+                Some(n) => Some((n, true)),
+                None => rest.strip_prefix('>').map(|n| (n, false)),
+            })
+            .and_then(|(n, inclusive)| {
+                let percent = n.trim().trim_end_matches('%').parse::<f64>().ok()?;
+                percent.is_finite().then_some((percent, inclusive))
+            });
+        match threshold {
+            Some((percent, inclusive)) => Ok(Self::Context { percent, inclusive }),
+            None => Err(format!(
+                "invalid condition '{s}' (expected idle, attention, ended or context>N)"
+            )),
+        }
+    }
+}
 
-        // Cursor indicator on option
-        if trimmed.starts_with("❯ ") && trimmed.len() > 2 {
-            // "❯ 1. Yes" is a prompt option; bare "❯ " is the input line
-            let after_cursor = trimmed.get(4..).unwrap_or(""); // skip "❯ "
-            if after_cursor.starts_with("1.")
-                || after_cursor.starts_with("2.")
-                || after_cursor.starts_with("3.")
-                || after_cursor.starts_with("4.")
-                || after_cursor.starts_with("5.")
-            {
-                start = i;
-                continue;
+impl std::fmt::Display for WaitCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Idle => write!(f, "idle"),
+            Self::Attention => write!(f, "attention"),
+            Self::Ended => write!(f, "ended"),
+            Self::Context { percent, inclusive } => {
+                let op = if *inclusive { ">=" } else { ">" };
+                write!(f, "context{op}{percent}")
             }
         }
+    }
+}
 
-        // Indented description line (part of an option)
-        if lines[i].starts_with("    ") && !trimmed.is_empty() {
-            start = i;
-            continue;
+/// Where a wait stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WaitOutcome {
+    /// The condition holds
+    Met,
+    /// An agent ended, so the condition can no longer be met
+    Ended,
+    /// Keep waiting
+    Pending,
+}
+
+impl WaitCondition {
+    /// Where one watched session stands; `None` means it has ended.
+    fn check(self, session: Option<&SessionView>) -> WaitOutcome {
+        let Some(session) = session else {
+            return if self == Self::Ended {
+                WaitOutcome::Met
+            } else {
+                WaitOutcome::Ended
+            };
+        };
+        let met = match self {
+            Self::Idle => session.status == atm_core::SessionStatus::Idle,
+            Self::Attention => session.status == atm_core::SessionStatus::AttentionNeeded,
+            Self::Ended => false,
+            Self::Context { percent, inclusive } => {
+                if inclusive {
+                    session.context_percentage >= percent
+                } else {
+                    session.context_percentage > percent
+                }
+            }
+        };
+        if met {
+            WaitOutcome::Met
+        } else {
+            WaitOutcome::Pending
         }
+    }
 
-        // Separator line (─── or ═══ etc.)
-        if is_separator_line(trimmed) {
-            start = i;
-            continue;
+    /// Whether the condition is a state the agent may be sitting in
+    /// already, rather than something that only happens once.
+    fn is_transition(self) -> bool {
+        matches!(self, Self::Idle | Self::Attention)
+    }
+
+    /// Like [`check`](Self::check), but a transition condition only counts
+    /// once `armed`: after the session was seen not meeting it. Seeing it
+    /// pending arms it.
+    fn check_armed(self, session: Option<&SessionView>, armed: &mut bool) -> WaitOutcome {
+        let outcome = self.check(session);
+        if outcome == WaitOutcome::Pending {
+            *armed = true;
+        }
+        if outcome == WaitOutcome::Met && self.is_transition() && !*armed {
+            WaitOutcome::Pending
+        } else {
+            outcome
         }
+    }
+}
 
-        // Empty line (spacing within prompt)
-        if trimmed.is_empty() {
-            start = i;
-            continue;
+impl WaitOutcome {
+    /// Where the whole wait stands given each watched session's outcome:
+    /// every one must meet the condition, or just one with `any`.
+    fn combine(outcomes: &[WaitOutcome], any: bool) -> WaitOutcome {
+        let all_are = |o: WaitOutcome| outcomes.iter().all(|x| *x == o);
+        let any_is = |o: WaitOutcome| outcomes.contains(&o);
+        if any {
+            if any_is(WaitOutcome::Met) {
+                WaitOutcome::Met
+            } else if all_are(WaitOutcome::Ended) {
+                WaitOutcome::Ended
+            } else {
+                WaitOutcome::Pending
+            }
+        } else if all_are(WaitOutcome::Met) {
+            WaitOutcome::Met
+        } else if any_is(WaitOutcome::Ended) {
+            WaitOutcome::Ended
+        } else {
+            WaitOutcome::Pending
         }
+    }
+}
 
-        // Question line (contains ?)
-        if trimmed.contains('?') {
-            start = i;
-            continue;
+/// Parses a `--timeout` duration: `90s`, `10m`, `2h`, or bare seconds.
+fn parse_duration(spec: &str) -> Result<Duration> {
+    let spec = spec.trim();
+    let (amount, unit) = match spec.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (spec.get(..i).unwrap_or(""), c),
+        _ => (spec, 's'),
+    };
+    let n: u64 = amount
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid duration '{spec}' (try 90s, 10m or 2h)"))?;
+    let secs = match unit {
+        's' => Some(n),
+        'm' => n.checked_mul(60),
+        'h' => n.checked_mul(3600),
+        _ => None,
+    };
+    secs.map(Duration::from_secs)
+        .ok_or_else(|| anyhow::anyhow!("invalid duration '{spec}' (try 90s, 10m or 2h)"))
+}
+
+/// Blocks until the selected agents meet `until`, following the daemon's
+/// event stream. Returns the process exit code.
+///
+/// The agents are fixed when the wait starts. One the daemon renames is
+/// followed under its new ID; one that disappears counts as ended. Unless
+/// `now`, an agent already idle (or waiting for attention) when the wait
+/// starts must leave that state before it counts.
+async fn cmd_wait(
+    target: Option<String>,
+    selector: SessionSelector,
+    until: WaitCondition,
+    now: bool,
+    any: bool,
+    timeout: Option<String>,
+) -> Result<i32> {
+    let timeout = timeout.as_deref().map(parse_duration).transpose()?;
+    daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
+
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+    let (_command_tx, command_rx) = mpsc::unbounded_channel();
+    let cancel_token = CancellationToken::new();
+    let client = DaemonClient::new(
+        DaemonConfig::default(),
+        event_tx,
+        command_rx,
+        cancel_token.clone(),
+    );
+    tokio::spawn(async move { client.run().await });
+
+    let sessions = fetch_sessions().await?;
+    let mut watched: Vec<SessionId> = match &target {
+        Some(target) => vec![atmd::control::resolve_target(&sessions, target)?.session_id],
+        None => sessions
+            .iter()
+            .filter(|s| selector.matches(s))
+            .map(|s| s.id.clone())
+            .collect(),
+    };
+    if watched.is_empty() {
+        bail!("No matching agents to wait on");
+    }
+
+    let mut current: HashMap<SessionId, SessionView> = sessions
+        .into_iter()
+        .filter(|s| watched.contains(&s.id))
+        .map(|s| (s.id.clone(), s))
+        .collect();
+    let mut armed: HashSet<SessionId> = HashSet::new();
+    if now {
+        armed.extend(watched.iter().cloned());
+    }
+    let deadline = timeout.map(|t| tokio::time::Instant::now() + t);
+
+    let outcome = loop {
+        let outcomes: Vec<WaitOutcome> = watched
+            .iter()
+            .map(|id| {
+                let mut is_armed = armed.contains(id);
+                let outcome = until.check_armed(current.get(id), &mut is_armed);
+                if is_armed {
+                    armed.insert(id.clone());
+                }
+                outcome
+            })
+            .collect();
+        match WaitOutcome::combine(&outcomes, any) {
+            WaitOutcome::Pending => {}
+            done => break Some(done),
         }
 
-        // Title block (☐/□/■)
-        if trimmed.starts_with('☐') || trimmed.starts_with('□') || trimmed.starts_with('■') {
-            start = i;
-            break; // Title is the top of the prompt
+        let next = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, event_rx.recv()).await {
+                Ok(event) => event,
+                Err(_) => break None,
+            },
+            None => event_rx.recv().await,
+        };
+        match next {
+            Some(Event::SessionListReplace(list)) => {
+                current = list
+                    .into_iter()
+                    .filter(|s| watched.contains(&s.id))
+                    .map(|s| (s.id.clone(), s))
+                    .collect();
+            }
+            Some(Event::SessionUpdate(list)) => {
+                for session in list.into_iter().filter(|s| watched.contains(&s.id)) {
+                    current.insert(session.id.clone(), session);
+                }
+            }
+            Some(Event::SessionRemoved {
+                session_id,
+                replaced_by: Some(new_id),
+            }) => {
+                // Same agent under its real ID; keep the last view until
+                // the first update for the new ID arrives
+                for id in watched.iter_mut().filter(|id| id.as_str() == session_id) {
+                    if let Some(mut view) = current.remove(id) {
+                        view.id = new_id.clone();
+                        current.insert(new_id.clone(), view);
+                    }
+                    if armed.remove(id) {
+                        armed.insert(new_id.clone());
+                    }
+                    *id = new_id.clone();
+                }
+            }
+            Some(Event::SessionRemoved { session_id, .. }) => {
+                current.retain(|id, _| id.as_str() != session_id);
+            }
+            Some(_) => {}
+            None => bail!("Lost the daemon event stream"),
         }
+    };
+    cancel_token.cancel();
 
-        // If we've already found prompt content (start < end-1),
-        // a non-matching line means we've gone past the prompt
-        if start < end.saturating_sub(1) {
-            break;
+    let describe = |id: &SessionId| match current.get(id) {
+        Some(s) => format!(
+            "{}\t{}\t{:.0}%",
+            s.id_short, s.status_label, s.context_percentage
+        ),
+        None => format!("{}\tended", id.short()),
+    };
+    Ok(match outcome {
+        Some(WaitOutcome::Met) => {
+            for id in &watched {
+                println!("{}", describe(id));
+            }
+            0
         }
-    }
+        Some(_) => {
+            for id in watched.iter().filter(|id| !current.contains_key(*id)) {
+                eprintln!("{} ended before reaching {until}", id.short());
+            }
+            EXIT_WAIT_ENDED
+        }
+        None => {
+            eprintln!("Timed out waiting for {until}");
+            EXIT_WAIT_TIMEOUT
+        }
+    })
+}
 
-    // If we didn't find any prompt markers, fall back to last 15 lines
-    if start == end.saturating_sub(1) && footer_idx.is_none() {
-        let fallback_start = lines.len().saturating_sub(15);
-        return &lines[fallback_start..];
-    }
+// ============================================================================
+// History Command
+// ============================================================================
 
-    // Trim leading blank/separator lines from the extracted prompt
-    while start < end {
-        let trimmed = lines.get(start).map(|l| l.trim()).unwrap_or("");
-        if trimmed.is_empty() || is_separator_line(trimmed) {
-            start += 1;
-        } else {
-            break;
+/// Parses a point in time for `--since`/`--until` style flags.
+///
+/// Accepts a relative age (`45s`, `30m`, `2h`, `7d`), a local wall-clock
+/// time today (`14:00`), a local date (`2024-01-15`), a local date and time
+/// (`2024-01-15 14:00`), or an RFC 3339 timestamp.
+fn parse_time_spec(
+    spec: &str,
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<chrono::DateTime<chrono::Utc>> {
+    use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+    let spec = spec.trim();
+
+    if let Some(unit) = spec.chars().last().filter(|c| c.is_ascii_alphabetic()) {
+        let amount = spec.get(..spec.len().saturating_sub(1)).unwrap_or("");
+        if let Ok(n) = amount.parse::<i64>() {
+            let age = match unit {
+                's' => chrono::Duration::try_seconds(n),
+                'm' => chrono::Duration::try_minutes(n),
+                'h' => chrono::Duration::try_hours(n),
+                'd' => chrono::Duration::try_days(n),
+                _ => None,
+            };
+            if let Some(age) = age {
+                return Ok((now - age).with_timezone(&Utc));
+            }
         }
     }
 
-    &lines[start..end]
+    if let Ok(ts) = DateTime::parse_from_rfc3339(spec) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+
+    let local = if let Ok(time) = NaiveTime::parse_from_str(spec, "%H:%M") {
+        Some(now.date_naive().and_time(time))
+    } else if let Ok(dt) = NaiveDateTime::parse_from_str(spec, "%Y-%m-%d %H:%M") {
+        Some(dt)
+    } else if let Ok(date) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+        Some(date.and_time(NaiveTime::MIN))
+    } else {
+        None
+    };
+
+    local
+        .and_then(|dt| dt.and_local_timezone(Local).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| {
+            anyhow::anyhow!("invalid time '{spec}' (try 30m, 2h, 7d, 14:00 or 2024-01-15)")
+        })
 }
 
-/// Returns true if a line is a box-drawing separator (────, ════, etc.)
-fn is_separator_line(trimmed: &str) -> bool {
-    if trimmed.len() < 3 {
-        return false;
+/// One-line human summary of a journal entry's payload.
+fn history_detail(entry: &atmd::journal::JournalEntry) -> String {
+    let str_field = |key: &str| entry.data.get(key).and_then(|v| v.as_str());
+    match entry.event.as_str() {
+        "tool_call_start" | "tool_call_end" => str_field("name").unwrap_or("").to_string(),
+        "prompt_submit" => {
+            let prompt = str_field("prompt").unwrap_or("").replace('\n', " ");
+            match prompt.char_indices().nth(60) {
+                Some((cut, _)) => format!("{}…", prompt.get(..cut).unwrap_or("")),
+                None => prompt,
+            }
+        }
+        "needs_input" => entry
+            .data
+            .get("reason")
+            .and_then(|r| r.get("reason"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        "session_updated" => format!(
+            "{} ${:.2}",
+            str_field("status").unwrap_or(""),
+            entry
+                .data
+                .get("cost_usd")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0)
+        ),
+        "session_removed" => str_field("reason").unwrap_or("").to_string(),
+        "session_start" => str_field("source").unwrap_or("").to_string(),
+        _ => String::new(),
     }
-    let first_char = trimmed.chars().next().unwrap_or(' ');
-    matches!(first_char, '─' | '━' | '═' | '┄' | '┈')
-        && trimmed
-            .chars()
-            .all(|c| matches!(c, '─' | '━' | '═' | '┄' | '┈' | ' '))
 }
 
-async fn cmd_status() -> Result<()> {
-    daemon::ensure_daemon_running().map_err(|e| anyhow::anyhow!("Failed to start daemon: {e}"))?;
-    let sessions = fetch_sessions().await?;
+fn cmd_history(
+    session: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    event: Option<String>,
+    limit: Option<usize>,
+    format: HistoryFormat,
+) -> Result<()> {
+    let now = chrono::Local::now();
+    let query = atmd::journal::JournalQuery {
+        session,
+        since: since.map(|s| parse_time_spec(&s, now)).transpose()?,
+        until: until.map(|s| parse_time_spec(&s, now)).transpose()?,
+        event,
+        limit,
+    };
 
-    let active = sessions
-        .iter()
-        .filter(|s| {
-            matches!(
-                s.status,
-                atm_core::SessionStatus::Working | atm_core::SessionStatus::Idle
-            )
-        })
-        .count();
+    let print = |entry: atmd::journal::JournalEntry| -> std::io::Result<()> {
+        match format {
+            HistoryFormat::Table => {
+                let ts = entry.ts.with_timezone(&chrono::Local);
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    ts.format("%Y-%m-%d %H:%M:%S"),
+                    entry.session_id.short(),
+                    entry.harness.as_deref().unwrap_or("-"),
+                    entry.event,
+                    history_detail(&entry)
+                );
+            }
+            HistoryFormat::Json => {
+                let json = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+                println!("{json}");
+            }
+        }
+        Ok(())
+    };
 
-    let attention = sessions
-        .iter()
-        .filter(|s| s.status == atm_core::SessionStatus::AttentionNeeded)
-        .count();
+    // Without a limit, entries are printed as they are read rather than
+    // collected first: the journal can span many rotated files.
+    let path = atmd::journal::default_journal_path();
+    let result = match query.limit {
+        Some(_) => atmd::journal::read_journal(&path, &query)
+            .and_then(|entries| entries.into_iter().try_for_each(print)),
+        None => atmd::journal::scan_journal(&path, &query, print),
+    };
+    result.with_context(|| format!("Failed to read event journal at {}", path.display()))
+}
 
-    let total_cost: f64 = sessions.iter().map(|s| s.cost_usd).sum();
+// ============================================================================
+// Cost Command
+// ============================================================================
 
-    let mut parts = vec![format!("{active}\u{2191}")]; // ↑
-    if attention > 0 {
-        parts.push(format!("{attention}!"));
+/// Quotes a CSV field if it contains a separator, quote or newline.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn cmd_cost(by: CostBy, since: Option<String>, format: CostFormat) -> Result<()> {
+    let since = since
+        .map(|s| parse_time_spec(&s, chrono::Local::now()))
+        .transpose()?
+        .map(|ts| ts.with_timezone(&chrono::Local).date_naive());
+
+    let path = atmd::ledger::default_ledger_path();
+    let ledger = atmd::ledger::CostLedger::load(&path)
+        .with_context(|| format!("Failed to read cost ledger at {}", path.display()))?;
+    let report = atmd::ledger::cost_report(&ledger.rows(), by.into(), since);
+
+    match format {
+        CostFormat::Table => {
+            let total: f64 = report.iter().map(|r| r.cost_usd).sum();
+            for row in &report {
+                println!("{}\t${:.2}", row.key, row.cost_usd);
+            }
+            println!("total\t${total:.2}");
+        }
+        CostFormat::Json => {
+            let json =
+                serde_json::to_string_pretty(&report).context("Failed to serialize cost report")?;
+            println!("{json}");
+        }
+        CostFormat::Csv => {
+            let column = format!("{by:?}").to_lowercase();
+            println!("{column},cost_usd");
+            for row in &report {
+                println!("{},{:.4}", csv_field(&row.key), row.cost_usd);
+            }
+        }
     }
-    parts.push(format!("${total_cost:.2}"));
-    println!("{}", parts.join(" "));
 
     Ok(())
 }
@@ -1759,6 +2716,23 @@ fn exec_attach(socket: &Option<String>, session_name: &str) -> Result<()> {
 // Workspace Create
 // ============================================================================
 
+/// Team name for `atm layout`: the explicit `--team`, else the new
+/// session's name, else the layout name suffixed with its first agent
+/// pane number. `None` when the layout has no agent panes.
+fn layout_team_name(
+    team: Option<String>,
+    session: Option<&str>,
+    layout: &str,
+    agent_panes: &[String],
+) -> Option<String> {
+    let first_pane = agent_panes.first()?;
+    Some(team.filter(|t| !t.trim().is_empty()).unwrap_or_else(|| {
+        session
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{layout}-{}", first_pane.trim_start_matches('%')))
+    }))
+}
+
 fn cmd_workspace(name: Option<String>, isolate: bool, editor: bool) -> Result<()> {
     // 1. Determine session name, sanitized to safe characters
     let session_name = name.unwrap_or_else(default_session_name);
@@ -1814,10 +2788,23 @@ fn cmd_workspace(name: Option<String>, isolate: bool, editor: bool) -> Result<()
         ],
     )?;
 
-    // 5. Inject ATM sidebar on the left
+    // 5. Tag the workspace as a team: panes created from here on inherit
+    //    ATM_TEAM from the session environment
+    tmux_run(
+        &socket_name,
+        &[
+            "set-environment",
+            "-t",
+            &session_name,
+            TEAM_ENV,
+            &session_name,
+        ],
+    )?;
+
+    // 6. Inject ATM sidebar on the left
     inject_sidebar(&socket_name, &agent_pane, &session_name, cols)?;
 
-    // 6. Split: shell below the agent pane (20% height)
+    // 7. Split: shell below the agent pane (20% height)
     tmux_run(
         &socket_name,
         &[
@@ -1833,7 +2820,7 @@ fn cmd_workspace(name: Option<String>, isolate: bool, editor: bool) -> Result<()
         ],
     )?;
 
-    // 7. If --editor: split agent pane horizontally, editor on the left
+    // 8. If --editor: split agent pane horizontally, editor on the left
     if editor {
         tmux_run(
             &socket_name,
@@ -1851,17 +2838,18 @@ fn cmd_workspace(name: Option<String>, isolate: bool, editor: bool) -> Result<()
         )?;
     }
 
-    // 8. Launch claude in agent pane
+    // 9. Launch claude in agent pane (created before the team was set)
+    let launch = format!("{TEAM_ENV}={} claude", shell_quote(&session_name));
     tmux_run(
         &socket_name,
-        &["send-keys", "-t", &agent_pane, "claude", "Enter"],
+        &["send-keys", "-t", &agent_pane, &launch, "Enter"],
     )?;
 
-    // 9. Install resize/new-window hooks + keybindings
+    // 10. Install resize/new-window hooks + keybindings
     install_resize_hooks(&socket_name, &session_name)?;
     install_new_window_hook(&socket_name, &session_name)?;
 
-    // 10. Focus the agent pane and attach
+    // 11. Focus the agent pane and attach
     tmux_run(&socket_name, &["select-pane", "-t", &agent_pane])?;
     exec_attach(&socket_name, &session_name)
 }
@@ -1994,13 +2982,14 @@ fn cmd_workspace_attach(session: Option<String>, isolate: bool) -> Result<()> {
 }
 
 // ============================================================================
-// Filter Task
+// Pane Task
 // ============================================================================
 
-/// Spawns a task that periodically polls tmux for pane IDs belonging to the
-/// filtered session and sends FilterUpdate events.
-fn spawn_filter_task(
-    session_name: String,
+/// Spawns a task that periodically polls tmux for panes. Sends the window
+/// of every pane as PaneWindows events and, when filtering to a tmux
+/// session, the pane IDs belonging to it as FilterUpdate events.
+fn spawn_pane_task(
+    session_filter: Option<String>,
     event_tx: mpsc::UnboundedSender<Event>,
     cancel_token: CancellationToken,
 ) -> tokio::task::JoinHandle<()> {
@@ -2014,12 +3003,21 @@ fn spawn_filter_task(
             }
 
             if let Ok(panes) = client.list_panes().await {
-                let pane_ids: HashSet<String> = panes
-                    .iter()
-                    .filter(|p| p.session_name == session_name)
-                    .map(|p| p.pane_id.clone())
+                if let Some(session_name) = &session_filter {
+                    let pane_ids: HashSet<String> = panes
+                        .iter()
+                        .filter(|p| &p.session_name == session_name)
+                        .map(|p| p.pane_id.clone())
+                        .collect();
+                    if event_tx.send(Event::FilterUpdate(pane_ids)).is_err() {
+                        break;
+                    }
+                }
+                let pane_windows: HashMap<String, (String, u32)> = panes
+                    .into_iter()
+                    .map(|p| (p.pane_id, (p.session_name, p.window_index)))
                     .collect();
-                if event_tx.send(Event::FilterUpdate(pane_ids)).is_err() {
+                if event_tx.send(Event::PaneWindows(pane_windows)).is_err() {
                     break;
                 }
             }
@@ -2053,14 +3051,18 @@ async fn main() -> Result<()> {
         }) => {
             return cmd_spawn(harness, model, worktree, direction, size, target_pane).await;
         }
-        Some(Command::Kill { target }) => {
-            return cmd_kill(target).await;
+        Some(Command::Kill { target, selector }) => {
+            return cmd_kill(target, selector).await;
         }
-        Some(Command::Interrupt { target }) => {
-            return cmd_interrupt(target).await;
+        Some(Command::Interrupt { target, selector }) => {
+            return cmd_interrupt(target, selector).await;
         }
-        Some(Command::Send { target, text }) => {
-            return cmd_send(target, text).await;
+        Some(Command::Send {
+            target,
+            text,
+            selector,
+        }) => {
+            return cmd_send(target, text, selector).await;
         }
         Some(Command::List {
             format,
@@ -2081,12 +3083,43 @@ async fn main() -> Result<()> {
             option,
             yes,
             no,
+            text,
         }) => {
-            return cmd_reply(target, option, yes, no).await;
+            return cmd_reply(target, option, yes, no, text).await;
         }
         Some(Command::Status) => {
             return cmd_status().await;
         }
+        Some(Command::Wait {
+            target,
+            all,
+            project,
+            until,
+            now,
+            any,
+            timeout,
+        }) => {
+            let selector = SessionSelector {
+                all,
+                project,
+                ..Default::default()
+            };
+            let code = cmd_wait(target, selector, until, now, any, timeout).await?;
+            std::process::exit(code);
+        }
+        Some(Command::History {
+            session,
+            since,
+            until,
+            event,
+            limit,
+            format,
+        }) => {
+            return cmd_history(session, since, until, event, limit, format);
+        }
+        Some(Command::Cost { by, since, format }) => {
+            return cmd_cost(by, since, format);
+        }
         Some(Command::Workspace { action }) => {
             return match action {
                 WorkspaceAction::Create {
@@ -2103,6 +3136,7 @@ async fn main() -> Result<()> {
             name,
             session,
             in_place,
+            team,
         }) => {
             let layout =
                 atm_tmux::layout::load_layout(&name, None).map_err(|e| anyhow::anyhow!("{e}"))?;
@@ -2125,6 +3159,21 @@ async fn main() -> Result<()> {
             for (role, panes) in &result.panes {
                 println!("{role:?}: {}", panes.join(", "));
             }
+
+            // Agents started in the layout's agent panes join one team
+            let agent_panes = result
+                .panes
+                .get(&atm_tmux::layout::SlotRole::Agent)
+                .cloned()
+                .unwrap_or_default();
+            if let Some(team) = layout_team_name(team, session.as_deref(), &name, &agent_panes) {
+                let export = format!("export {TEAM_ENV}={}", shell_quote(&team));
+                for pane in &agent_panes {
+                    client.send_keys(pane, &export).await?;
+                    client.send_keys(pane, "Enter").await?;
+                }
+                println!("Team: {team}");
+            }
             return Ok(());
         }
         None => {}
@@ -2158,6 +3207,11 @@ async fn main() -> Result<()> {
         bail!("--pick mode requires running inside tmux");
     }
 
+    // Validate the keymap before touching the terminal so a bad `[keys]`
+    // table is reported on a normal screen.
+    let config = load_atm_config()?;
+    let keymap = Keymap::from_config(&config.keys).context("Invalid [keys] in the ATM config")?;
+
     if let Err(e) = daemon::ensure_daemon_running() {
         bail!("Failed to ensure daemon is running: {e}");
     }
@@ -2182,19 +3236,36 @@ async fn main() -> Result<()> {
         App::new()
     };
     app.compact = args.compact;
+    app.keymap = keymap;
+    app.set_view(
+        config.view.group.unwrap_or_default(),
+        config.view.sort.unwrap_or_default(),
+    );
 
-    let daemon_client =
-        DaemonClient::with_defaults(event_tx.clone(), command_rx, cancel_token.clone());
+    // Let the daemon drop sessions from other tmux sessions instead of
+    // shipping them here only for the filter task to hide them.
+    let daemon_config = DaemonConfig {
+        subscription: SubscriptionFilter {
+            tmux_session: args.tmux_session.clone(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let daemon_client = DaemonClient::new(
+        daemon_config,
+        event_tx.clone(),
+        command_rx,
+        cancel_token.clone(),
+    );
     let daemon_handle = tokio::spawn(async move {
         daemon_client.run().await;
     });
 
     let keyboard_handle = spawn_keyboard_task(event_tx.clone(), cancel_token.clone());
 
-    // Spawn filter task if --tmux-session is set
-    let filter_handle = args
-        .tmux_session
-        .map(|session| spawn_filter_task(session, event_tx.clone(), cancel_token.clone()));
+    // Poll tmux for pane windows, and for the filtered session's panes if
+    // --tmux-session is set
+    let pane_handle = spawn_pane_task(args.tmux_session, event_tx.clone(), cancel_token.clone());
 
     // Create watch channel for selected pane ID and spawn capture polling task
     let (capture_pane_tx, capture_pane_rx) = tokio::sync::watch::channel(None::<String>);
@@ -2215,9 +3286,7 @@ async fn main() -> Result<()> {
     let _ = tokio::time::timeout(Duration::from_millis(100), daemon_handle).await;
     let _ = tokio::time::timeout(Duration::from_millis(100), keyboard_handle).await;
     let _ = tokio::time::timeout(Duration::from_millis(100), capture_handle).await;
-    if let Some(handle) = filter_handle {
-        let _ = tokio::time::timeout(Duration::from_millis(100), handle).await;
-    }
+    let _ = tokio::time::timeout(Duration::from_millis(100), pane_handle).await;
 
     if let Err(e) = cleanup_terminal(&mut terminal) {
         error!(error = %e, "Failed to cleanup terminal");
@@ -2230,8 +3299,14 @@ async fn main() -> Result<()> {
 
 #[cfg(test)]
 mod cli_tests {
-    use super::{resolve_spawn_harness, Args, Command};
+    use super::{
+        config_path, default_config_text, layout_team_name, load_atm_config, resolve_spawn_harness,
+        set_view_keys, write_view_config, Args, AtmConfig, Command,
+    };
+    use atm_core::{GroupBy, SortKey};
+    use atm_tui::keymap::{Keymap, KeymapError};
     use clap::Parser;
+    use std::fs;
 
     struct IsolatedConfigHome {
         _guard: std::sync::MutexGuard<'static, ()>,
@@ -2299,81 +3374,180 @@ mod cli_tests {
         assert!(message.contains("claude"));
         assert!(message.contains("pi"));
     }
-}
 
-#[cfg(test)]
-mod prompt_tests {
-    use super::extract_prompt;
+    #[test]
+    fn load_atm_config_ignores_unknown_view_values() {
+        let _config_home = IsolatedConfigHome::new();
+        let path = config_path().unwrap();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "[harness]\ndefault = \"pi\"\n[view]\ngroup = \"Project\"\nsort = \"cost\"\n",
+        )
+        .unwrap();
+
+        let config = load_atm_config().unwrap();
+        assert_eq!(config.view.group, None);
+        assert_eq!(config.view.sort, Some(SortKey::Cost));
+        assert_eq!(config.harness.default.as_deref(), Some("pi"));
+    }
 
     #[test]
-    fn test_extract_numbered_prompt() {
-        let lines: Vec<String> = vec![
-            "Some previous output",
-            "",
-            "Do you like pineapple on pizza?",
-            "",
-            "❯ 1. Yes",
-            "    Pineapple belongs on pizza",
-            "  2. No",
-            "    Pineapple does not belong on pizza",
-            "  3. Type something.",
-            "",
-            "  4. Chat about this",
-            "",
-            "Enter to select · ↑/↓ to navigate · Esc to cancel",
-        ]
-        .into_iter()
-        .map(String::from)
-        .collect();
+    fn write_view_config_replaces_the_file_whole() {
+        let _config_home = IsolatedConfigHome::new();
+        let path = config_path().unwrap();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "# mine\n[harness]\ndefault = \"pi\"\n").unwrap();
+
+        write_view_config(GroupBy::Tmux, SortKey::Cost).unwrap();
+        let config = load_atm_config().unwrap();
+        assert_eq!(config.view.group, Some(GroupBy::Tmux));
+        assert_eq!(config.view.sort, Some(SortKey::Cost));
+        assert_eq!(config.harness.default.as_deref(), Some("pi"));
+        assert!(fs::read_to_string(&path).unwrap().starts_with("# mine\n"));
+        let leftovers: Vec<_> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
 
-        let prompt = extract_prompt(&lines);
-        let text = prompt
-            .iter()
-            .map(|l| l.as_str())
-            .collect::<Vec<_>>()
-            .join("\n");
-        assert!(
-            text.contains("pineapple on pizza"),
-            "should contain the question"
-        );
-        assert!(text.contains("1. Yes"), "should contain option 1");
-        assert!(text.contains("4. Chat"), "should contain option 4");
-        assert!(text.contains("Enter to select"), "should contain footer");
-        assert!(
-            !text.contains("previous output"),
-            "should not contain earlier output"
-        );
+    #[cfg(unix)]
+    #[test]
+    fn write_view_config_keeps_symlink_and_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _config_home = IsolatedConfigHome::new();
+        let path = config_path().unwrap();
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir.join("dotfiles")).unwrap();
+        let target = dir.join("dotfiles").join("atm.toml");
+        fs::write(&target, "[harness]\ndefault = \"pi\"\n").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o600)).unwrap();
+        std::os::unix::fs::symlink(&target, &path).unwrap();
+
+        write_view_config(GroupBy::Status, SortKey::Name).unwrap();
+        assert!(fs::symlink_metadata(&path)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        let config = load_atm_config().unwrap();
+        assert_eq!(config.view.group, Some(GroupBy::Status));
+        assert_eq!(config.harness.default.as_deref(), Some("pi"));
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
     }
 
+    #[cfg(unix)]
     #[test]
-    fn test_extract_yn_prompt() {
-        let lines: Vec<String> = vec![
-            "lots of code output here",
-            "more code",
-            "",
-            "Allow Bash: git status? (Y)es/(N)o",
-        ]
-        .into_iter()
-        .map(String::from)
-        .collect();
+    fn new_config_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
 
-        let prompt = extract_prompt(&lines);
-        assert!(prompt.iter().any(|l| l.contains("(Y)es")));
+        let _config_home = IsolatedConfigHome::new();
+        let path = config_path().unwrap();
+        load_atm_config().unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        fs::remove_file(&path).unwrap();
+        write_view_config(GroupBy::Tmux, SortKey::Cost).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
     }
 
     #[test]
-    fn test_fallback_when_no_prompt() {
-        let lines: Vec<String> = (0..20).map(|i| format!("line {i}")).collect();
+    fn set_view_keys_updates_or_appends_the_view_table() {
+        let parse = |text: &str| -> AtmConfig {
+            toml::from_str(text).unwrap_or_else(|e| panic!("{e}\n{text}"))
+        };
+
+        // Fresh default config: the commented example stays, a table is added
+        let text = set_view_keys(default_config_text(), GroupBy::Status, SortKey::Cost).unwrap();
+        assert!(text.starts_with(default_config_text()));
+        let config = parse(&text);
+        assert_eq!(config.view.group, Some(GroupBy::Status));
+        assert_eq!(config.view.sort, Some(SortKey::Cost));
+
+        // Existing keys are replaced in place, comments and other tables kept
+        let existing = "[view]\n# my view\ngroup = \"flat\"\n\n[harness]\ndefault = \"pi\"\n";
+        let text = set_view_keys(existing, GroupBy::Tmux, SortKey::Name).unwrap();
+        assert_eq!(
+            text,
+            "[view]\n# my view\ngroup = \"tmux\"\nsort = \"name\"\n\n[harness]\ndefault = \"pi\"\n"
+        );
+        assert_eq!(parse(&text).harness.default.as_deref(), Some("pi"));
+
+        // Saving again is stable
+        assert_eq!(
+            set_view_keys(&text, GroupBy::Tmux, SortKey::Name).unwrap(),
+            text
+        );
 
-        let prompt = extract_prompt(&lines);
-        assert_eq!(prompt.len(), 15);
+        // Lines starting with `[` inside values and array tables are not
+        // headers; an inline view table is updated where it is
+        let tricky = "title = \"\"\"\n[view]\nnot a table\n\"\"\"\nhosts = [\n  \"a\",\n]\n\n[[harness.custom]]\nid = \"x\" # keep\n";
+        let text = set_view_keys(tricky, GroupBy::Status, SortKey::Cost).unwrap();
+        assert!(text.starts_with(tricky), "{text}");
+        assert!(
+            text.ends_with("[view]\ngroup = \"status\"\nsort = \"cost\"\n"),
+            "{text}"
+        );
+        let inline = "view = { group = \"flat\" } # mine\n";
+        let text = set_view_keys(inline, GroupBy::Tmux, SortKey::Name).unwrap();
+        assert!(
+            text.starts_with("view = {") && text.ends_with("} # mine\n"),
+            "{text}"
+        );
+        let config = parse(&text);
+        assert_eq!(config.view.group, Some(GroupBy::Tmux));
+        assert_eq!(config.view.sort, Some(SortKey::Name));
+        assert!(set_view_keys("view = 3\n", GroupBy::Tmux, SortKey::Name).is_err());
+        assert!(set_view_keys("[view\n", GroupBy::Tmux, SortKey::Name).is_err());
     }
 
     #[test]
-    fn test_empty_input() {
-        let lines: Vec<String> = vec![];
-        let prompt = extract_prompt(&lines);
-        assert!(prompt.is_empty());
+    fn keys_example_in_default_config_builds_a_keymap() {
+        // Uncomment the `[keys]` example exactly as a user would
+        let example: String = default_config_text()
+            .lines()
+            .skip_while(|line| *line != "# [keys]")
+            .filter_map(|line| line.strip_prefix("# "))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let config: AtmConfig = toml::from_str(&example).unwrap_or_else(|e| panic!("{e}"));
+        assert_eq!(config.keys.len(), 4);
+        let keymap = Keymap::from_config(&config.keys).unwrap_or_else(|e| panic!("{e}"));
+        assert_ne!(keymap, Keymap::default());
+
+        let config: AtmConfig =
+            toml::from_str("[keys]\ng = \"cycle_sort\"\n").unwrap_or_else(|e| panic!("{e}"));
+        let err = Keymap::from_config(&config.keys).unwrap_err();
+        assert!(matches!(err, KeymapError::PrefixConflict { .. }));
+        assert!(err
+            .to_string()
+            .contains("`g` (cycle_sort) conflicts with `gg` (go_to_first)"));
+    }
+
+    #[test]
+    fn layout_team_name_prefers_flag_then_session() {
+        let panes = vec!["%12".to_string(), "%13".to_string()];
+        assert_eq!(
+            layout_team_name(Some("review".into()), Some("work"), "squad", &panes).as_deref(),
+            Some("review")
+        );
+        assert_eq!(
+            layout_team_name(None, Some("work"), "squad", &panes).as_deref(),
+            Some("work")
+        );
+        assert_eq!(
+            layout_team_name(None, None, "squad", &panes).as_deref(),
+            Some("squad-12")
+        );
+        assert_eq!(
+            layout_team_name(Some("review".into()), None, "solo", &[]),
+            None
+        );
     }
 }
 
@@ -2428,6 +3602,7 @@ mod spawn_command_tests {
         let args = EnvGuard::capture("ATM_SPAWN_ARGS");
         let pi_bin = EnvGuard::capture("ATM_SPAWN_PI_BIN");
         let pi_args = EnvGuard::capture("ATM_SPAWN_PI_ARGS");
+        let team = EnvGuard::capture("ATM_TEAM");
         let config_home = EnvGuard::capture("XDG_CONFIG_HOME");
         let config_dir = tempfile::tempdir().unwrap_or_else(|e| panic!("{e}"));
 
@@ -2437,6 +3612,7 @@ mod spawn_command_tests {
         args.unset();
         pi_bin.unset();
         pi_args.unset();
+        team.unset();
         std::env::set_var("XDG_CONFIG_HOME", config_dir.path());
 
         // 1. Unset env/config → default to single-quoted "claude" and
@@ -2584,9 +3760,451 @@ mod spawn_command_tests {
             "'custom-agent' '--profile' 'atm' --model-id 'abc'"
         );
 
+        // 18. Spawning from inside a team passes the team on, inside the cd.
+        team.set("review");
+        assert_eq!(
+            build_spawn_command_for_harness(&custom, Some("/work"), None),
+            "cd '/work' && ATM_TEAM='review' 'custom-agent' '--profile' 'atm'"
+        );
+
         // env's Drop restores the captured values, even if any of the
         // assert_eq!s above panicked.
         let _ = config_home;
         let _ = config_dir;
     }
 }
+
+#[cfg(test)]
+mod history_tests {
+    use super::{parse_time_spec, Args, Command, HistoryFormat};
+    use chrono::{Local, TimeZone, Utc};
+    use clap::Parser;
+
+    fn fixed_now() -> chrono::DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2024, 1, 15, 16, 30, 0)
+            .earliest()
+            .unwrap_or_else(|| panic!("fixed time should be valid"))
+    }
+
+    #[test]
+    fn parses_relative_ages() {
+        let now = fixed_now();
+        let utc_now = now.with_timezone(&Utc);
+        assert_eq!(
+            parse_time_spec("30m", now).unwrap(),
+            utc_now - chrono::Duration::minutes(30)
+        );
+        assert_eq!(
+            parse_time_spec("2h", now).unwrap(),
+            utc_now - chrono::Duration::hours(2)
+        );
+        assert_eq!(
+            parse_time_spec("7d", now).unwrap(),
+            utc_now - chrono::Duration::days(7)
+        );
+    }
+
+    #[test]
+    fn parses_wall_clock_and_dates_as_local_time() {
+        let now = fixed_now();
+        let two_pm = Local
+            .with_ymd_and_hms(2024, 1, 15, 14, 0, 0)
+            .earliest()
+            .unwrap_or_else(|| panic!("valid"));
+        assert_eq!(parse_time_spec("14:00", now).unwrap(), two_pm);
+        assert_eq!(parse_time_spec("2024-01-15 14:00", now).unwrap(), two_pm);
+
+        let midnight = Local
+            .with_ymd_and_hms(2024, 1, 10, 0, 0, 0)
+            .earliest()
+            .unwrap_or_else(|| panic!("valid"));
+        assert_eq!(parse_time_spec("2024-01-10", now).unwrap(), midnight);
+    }
+
+    #[test]
+    fn parses_rfc3339() {
+        let ts = parse_time_spec("2024-01-15T10:00:00Z", fixed_now()).unwrap();
+        assert_eq!(ts, Utc.with_ymd_and_hms(2024, 1, 15, 10, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(parse_time_spec("yesterday-ish", fixed_now()).is_err());
+        assert!(parse_time_spec("5x", fixed_now()).is_err());
+    }
+
+    #[test]
+    fn history_args_parse() {
+        let args = Args::try_parse_from([
+            "atm",
+            "history",
+            "a1b2c3d4",
+            "--since",
+            "14:00",
+            "--event",
+            "tool_call_start",
+            "-n",
+            "50",
+            "-f",
+            "json",
+        ])
+        .unwrap_or_else(|e| panic!("{e}"));
+        match args.command {
+            Some(Command::History {
+                session,
+                since,
+                until,
+                event,
+                limit,
+                format,
+            }) => {
+                assert_eq!(session.as_deref(), Some("a1b2c3d4"));
+                assert_eq!(since.as_deref(), Some("14:00"));
+                assert!(until.is_none());
+                assert_eq!(event.as_deref(), Some("tool_call_start"));
+                assert_eq!(limit, Some(50));
+                assert!(matches!(format, HistoryFormat::Json));
+            }
+            other => panic!("expected history command, got {other:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod cost_tests {
+    use super::{csv_field, Args, Command, CostBy, CostFormat};
+    use clap::Parser;
+
+    #[test]
+    fn cost_args_parse() {
+        let args =
+            Args::try_parse_from(["atm", "cost", "--by", "day", "--since", "7d", "-f", "csv"])
+                .unwrap_or_else(|e| panic!("{e}"));
+        match args.command {
+            Some(Command::Cost { by, since, format }) => {
+                assert!(matches!(by, CostBy::Day));
+                assert_eq!(since.as_deref(), Some("7d"));
+                assert!(matches!(format, CostFormat::Csv));
+            }
+            other => panic!("expected cost command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("/src/app"), "/src/app");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
+
+#[cfg(test)]
+mod broadcast_tests {
+    use super::{Args, Command, SessionSelector};
+    use atm_core::{SessionId, SessionStatus, SessionView};
+    use clap::Parser;
+
+    fn parse(args: &[&str]) -> Command {
+        Args::try_parse_from(args)
+            .unwrap_or_else(|e| panic!("{e}"))
+            .command
+            .unwrap_or_else(|| panic!("no command parsed"))
+    }
+
+    fn session(id: &str, root: &str, status: SessionStatus, pane: Option<&str>) -> SessionView {
+        SessionView {
+            id: SessionId::new(id),
+            project_root: Some(root.to_string()),
+            status,
+            tmux_pane: pane.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn selector_args_parse() {
+        match parse(&[
+            "atm",
+            "send",
+            "--project",
+            "foo",
+            "--status",
+            "idle",
+            "run the tests",
+        ]) {
+            Command::Send {
+                target,
+                text,
+                selector,
+            } => {
+                assert_eq!(target.as_deref(), Some("run the tests"));
+                assert!(text.is_none());
+                assert_eq!(selector.project.as_deref(), Some("foo"));
+                assert_eq!(selector.status.as_deref(), Some("idle"));
+            }
+            other => panic!("expected send, got {other:?}"),
+        }
+
+        match parse(&["atm", "interrupt", "--all", "-y"]) {
+            Command::Interrupt { target, selector } => {
+                assert!(target.is_none());
+                assert!(selector.all && selector.yes);
+            }
+            other => panic!("expected interrupt, got {other:?}"),
+        }
+
+        match parse(&["atm", "kill", "a1b2c3d4"]) {
+            Command::Kill { target, selector } => {
+                assert_eq!(target.as_deref(), Some("a1b2c3d4"));
+                assert!(!selector.is_set());
+            }
+            other => panic!("expected kill, got {other:?}"),
+        }
+
+        // A target and a selector together are ambiguous
+        assert!(Args::try_parse_from(["atm", "kill", "a1b2c3d4", "--all"]).is_err());
+        // Neither is an error too
+        assert!(Args::try_parse_from(["atm", "interrupt"]).is_err());
+    }
+
+    #[test]
+    fn selector_filters_combine() {
+        let sessions = vec![
+            session("a", "/src/foo", SessionStatus::Idle, Some("%1")),
+            session("b", "/src/foo", SessionStatus::Working, Some("%2")),
+            session("c", "/src/bar", SessionStatus::Idle, Some("%3")),
+            session("d", "/src/foo", SessionStatus::Idle, None),
+        ];
+        let ids = |selector: &SessionSelector| -> Vec<String> {
+            selector
+                .select(&sessions)
+                .unwrap()
+                .iter()
+                .map(|s| s.id.to_string())
+                .collect()
+        };
+
+        let all = SessionSelector {
+            all: true,
+            ..Default::default()
+        };
+        // Pane-less sessions can't be acted on
+        assert_eq!(ids(&all), vec!["a", "b", "c"]);
+
+        let idle_foo = SessionSelector {
+            project: Some("foo".into()),
+            status: Some("idle".into()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&idle_foo), vec!["a"]);
+
+        let bogus = SessionSelector {
+            status: Some("sleepy".into()),
+            ..Default::default()
+        };
+        assert!(bogus.select(&sessions).is_err());
+    }
+
+    #[test]
+    fn selector_project_matches_whole_name_or_path() {
+        let sessions = vec![
+            session("a", "/src/api", SessionStatus::Idle, Some("%1")),
+            session("b", "/src/api-gateway", SessionStatus::Idle, Some("%2")),
+            session("c", "/src/rapid", SessionStatus::Idle, Some("%3")),
+            session("d", "/work/api/", SessionStatus::Idle, Some("%4")),
+        ];
+        let ids = |project: &str| -> Vec<String> {
+            SessionSelector {
+                project: Some(project.into()),
+                ..Default::default()
+            }
+            .select(&sessions)
+            .unwrap()
+            .iter()
+            .map(|s| s.id.to_string())
+            .collect()
+        };
+
+        assert_eq!(ids("api"), vec!["a", "d"]);
+        assert_eq!(ids("/src/api"), vec!["a"]);
+        assert_eq!(ids("/src/api/"), vec!["a"]);
+        assert_eq!(ids("api-gateway"), vec!["b"]);
+        assert!(ids("ap").is_empty());
+        assert!(ids("src").is_empty());
+    }
+}
+
+#[cfg(test)]
+mod wait_tests {
+    use super::{parse_duration, Args, Command, WaitCondition, WaitOutcome};
+    use atm_core::{SessionStatus, SessionView};
+    use clap::Parser;
+    use std::time::Duration;
+
+    fn evaluate(cond: WaitCondition, sessions: &[Option<&SessionView>], any: bool) -> WaitOutcome {
+        let outcomes: Vec<WaitOutcome> = sessions.iter().map(|s| cond.check(*s)).collect();
+        WaitOutcome::combine(&outcomes, any)
+    }
+
+    fn view(status: SessionStatus, context: f64) -> SessionView {
+        SessionView {
+            status,
+            context_percentage: context,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_conditions() {
+        assert_eq!("idle".parse(), Ok(WaitCondition::Idle));
+        assert_eq!("Attention".parse(), Ok(WaitCondition::Attention));
+        assert_eq!("ended".parse(), Ok(WaitCondition::Ended));
+        assert_eq!(
+            "context>80".parse(),
+            Ok(WaitCondition::Context {
+                percent: 80.0,
+                inclusive: false
+            })
+        );
+        assert_eq!(
+            "context >= 92.5%".parse(),
+            Ok(WaitCondition::Context {
+                percent: 92.5,
+                inclusive: true
+            })
+        );
+        assert!("context<80".parse::<WaitCondition>().is_err());
+        assert!("busy".parse::<WaitCondition>().is_err());
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert!(parse_duration("2d").is_err());
+        assert!(parse_duration("soon").is_err());
+    }
+
+    #[test]
+    fn evaluates_all_and_any() {
+        let idle = view(SessionStatus::Idle, 10.0);
+        let busy = view(SessionStatus::Working, 85.0);
+        let cond = WaitCondition::Idle;
+
+        assert_eq!(evaluate(cond, &[Some(&idle)], false), WaitOutcome::Met);
+        assert_eq!(
+            evaluate(cond, &[Some(&idle), Some(&busy)], false),
+            WaitOutcome::Pending
+        );
+        assert_eq!(
+            evaluate(cond, &[Some(&idle), Some(&busy)], true),
+            WaitOutcome::Met
+        );
+        // An ended agent can never become idle
+        assert_eq!(
+            evaluate(cond, &[Some(&idle), None], false),
+            WaitOutcome::Ended
+        );
+        assert_eq!(
+            evaluate(cond, &[Some(&busy), None], true),
+            WaitOutcome::Pending
+        );
+        assert_eq!(evaluate(cond, &[None], true), WaitOutcome::Ended);
+
+        assert_eq!(
+            evaluate(WaitCondition::Ended, &[None], false),
+            WaitOutcome::Met
+        );
+        let context = WaitCondition::Context {
+            percent: 80.0,
+            inclusive: false,
+        };
+        assert_eq!(evaluate(context, &[Some(&busy)], false), WaitOutcome::Met);
+        assert_eq!(
+            evaluate(context, &[Some(&idle)], false),
+            WaitOutcome::Pending
+        );
+    }
+
+    #[test]
+    fn transition_conditions_wait_to_be_armed() {
+        let idle = view(SessionStatus::Idle, 10.0);
+        let busy = view(SessionStatus::Working, 85.0);
+
+        // Already idle when the wait starts: not done until it works again
+        let mut armed = false;
+        assert_eq!(
+            WaitCondition::Idle.check_armed(Some(&idle), &mut armed),
+            WaitOutcome::Pending
+        );
+        assert_eq!(
+            WaitCondition::Idle.check_armed(Some(&busy), &mut armed),
+            WaitOutcome::Pending
+        );
+        assert!(armed);
+        assert_eq!(
+            WaitCondition::Idle.check_armed(Some(&idle), &mut armed),
+            WaitOutcome::Met
+        );
+
+        // Armed up front (`--now`)
+        let mut armed = true;
+        assert_eq!(
+            WaitCondition::Idle.check_armed(Some(&idle), &mut armed),
+            WaitOutcome::Met
+        );
+
+        // Thresholds and endings don't need a transition
+        let context = WaitCondition::Context {
+            percent: 80.0,
+            inclusive: false,
+        };
+        let mut armed = false;
+        assert_eq!(
+            context.check_armed(Some(&busy), &mut armed),
+            WaitOutcome::Met
+        );
+        let mut armed = false;
+        assert_eq!(
+            WaitCondition::Attention.check_armed(None, &mut armed),
+            WaitOutcome::Ended
+        );
+    }
+
+    #[test]
+    fn wait_args_parse() {
+        let args = Args::try_parse_from([
+            "atm",
+            "wait",
+            "--project",
+            "foo",
+            "--until",
+            "context>80",
+            "--timeout",
+            "10m",
+        ])
+        .unwrap_or_else(|e| panic!("{e}"));
+        match args.command {
+            Some(Command::Wait {
+                target,
+                project,
+                until,
+                now,
+                timeout,
+                ..
+            }) => {
+                assert!(target.is_none());
+                assert!(!now);
+                assert_eq!(project.as_deref(), Some("foo"));
+                assert_eq!(until.to_string(), "context>80");
+                assert_eq!(timeout.as_deref(), Some("10m"));
+            }
+            other => panic!("expected wait command, got {other:?}"),
+        }
+        assert!(Args::try_parse_from(["atm", "wait", "--until", "idle"]).is_err());
+        assert!(Args::try_parse_from(["atm", "wait", "a1b2", "--until", "nope"]).is_err());
+    }
+}
@@ -24,17 +24,33 @@ use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::PathBuf;
 use std::process;
+use std::sync::Arc;
 
 use anyhow::{bail, Context, Result};
 use clap::{Parser, Subcommand};
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
+use atm_core::{default_models_path, ModelCatalog};
+use atm_protocol::socket::daemon_socket_path;
+use atm_tmux::RealTmuxClient;
+use atmd::approval::{
+    default_audit_path, default_policy_path, load_approval_policy, spawn_approval_engine,
+};
+use atmd::budget::{load_budget_config, spawn_budget_enforcer};
+use atmd::control::ControlService;
 use atmd::discovery::DiscoveryService;
+use atmd::gemini::spawn_gemini_tailer;
+use atmd::journal::default_journal_path;
+use atmd::ledger::{default_ledger_path, spawn_ledger_task, CostLedger};
 use atmd::monitor::spawn_monitor_task;
-use atmd::registry::spawn_registry;
-use atmd::server::{DaemonServer, DEFAULT_SOCKET_PATH};
+use atmd::notify::{load_notify_config, spawn_notifier};
+use atmd::prompt::spawn_prompt_reader;
+use atmd::registry::persist::default_state_path;
+use atmd::registry::{spawn_registry_with_options, RegistryOptions};
+use atmd::remote::{default_config_path, load_remote_config};
+use atmd::server::DaemonServer;
 
 /// ATM daemon - Claude Code session manager
 #[derive(Parser, Debug)]
@@ -173,12 +189,9 @@ fn main() -> Result<()> {
             if let Some(pid) = is_daemon_running() {
                 println!("Daemon is running (PID {pid})");
 
-                let socket_path = env::var("ATM_SOCKET")
-                    .ok()
-                    .filter(|s| !s.is_empty())
-                    .unwrap_or_else(|| DEFAULT_SOCKET_PATH.to_string());
-                if PathBuf::from(&socket_path).exists() {
-                    println!("Socket: {socket_path}");
+                let socket_path = daemon_socket_path();
+                if socket_path.exists() {
+                    println!("Socket: {}", socket_path.display());
                 }
 
                 Ok(())
@@ -229,10 +242,7 @@ async fn run_daemon() -> Result<()> {
         "ATM daemon starting"
     );
 
-    let socket_path = env::var("ATM_SOCKET")
-        .ok()
-        .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| DEFAULT_SOCKET_PATH.to_string());
+    let socket_path = daemon_socket_path();
     let cancel_token = CancellationToken::new();
 
     let shutdown_token = cancel_token.clone();
@@ -244,8 +254,61 @@ async fn run_daemon() -> Result<()> {
         shutdown_token.cancel();
     });
 
-    let registry = spawn_registry();
-    info!("Session registry started");
+    // Before the registry restores any sessions: the first model lookup
+    // pins the catalog.
+    if let Some(models_path) = default_models_path() {
+        match ModelCatalog::load(&models_path) {
+            Ok(catalog) => {
+                info!(models = catalog.models().len(), "Model catalog loaded");
+                let _ = catalog.install();
+            }
+            Err(e) => warn!(error = %e, "Ignoring model overrides; using bundled catalog"),
+        }
+    }
+
+    let config_path = default_config_path();
+    let budgets = match config_path.as_deref().map(load_budget_config) {
+        Some(Ok(budgets)) if budgets.is_enabled() => Some(budgets),
+        Some(Ok(_)) | None => None,
+        Some(Err(e)) => {
+            warn!(error = %e, "Ignoring [budgets] config; cost budgets disabled");
+            None
+        }
+    };
+    let interrupt_over_budget = budgets.as_ref().is_some_and(|b| b.interrupt);
+
+    // Loaded before the registry, whose budgets count today's spend from it
+    let ledger_path = default_ledger_path();
+    let (ledger, set_aside) = CostLedger::load_or_set_aside(&ledger_path)
+        .with_context(|| format!("Failed to load cost ledger {}", ledger_path.display()))?;
+    if let Some(aside) = set_aside {
+        warn!(
+            moved_to = %aside.display(),
+            "Cost ledger was unreadable; kept it aside and started a new one"
+        );
+    }
+
+    let state_path = default_state_path();
+    let journal_path = default_journal_path();
+    let registry = spawn_registry_with_options(RegistryOptions {
+        state_path: Some(state_path.clone()),
+        journal_path: Some(journal_path.clone()),
+        cost_ledger: budgets.is_some().then(|| ledger.clone()),
+        budgets,
+    });
+    info!(
+        state_file = %state_path.display(),
+        journal = %journal_path.display(),
+        "Session registry started"
+    );
+
+    // Subscribe before discovery so sessions found at startup are booked.
+    let ledger_handle = spawn_ledger_task(
+        ledger,
+        ledger_path,
+        registry.subscribe(),
+        cancel_token.clone(),
+    );
 
     let discovery = DiscoveryService::new(registry.clone());
     let discovery_result = discovery.discover().await;
@@ -260,11 +323,68 @@ async fn run_daemon() -> Result<()> {
     let _monitor_handle = spawn_monitor_task(cancel_token.clone());
     info!("Process monitor started");
 
-    let server = DaemonServer::new(&socket_path, registry, cancel_token);
+    let _gemini_handle = spawn_gemini_tailer(registry.clone(), cancel_token.clone());
+
+    let _prompt_handle = spawn_prompt_reader(
+        registry.clone(),
+        Arc::new(RealTmuxClient::new()),
+        cancel_token.clone(),
+    );
+
+    if interrupt_over_budget {
+        let control = ControlService::new(registry.clone(), Arc::new(RealTmuxClient::new()));
+        let _budget_handle = spawn_budget_enforcer(registry.clone(), control, cancel_token.clone());
+    }
+
+    match default_policy_path().as_deref().map(load_approval_policy) {
+        Some(Ok(policy)) if policy.is_enabled() => {
+            let control = ControlService::new(registry.clone(), Arc::new(RealTmuxClient::new()));
+            let _approval_handle = spawn_approval_engine(
+                registry.clone(),
+                policy,
+                control,
+                default_audit_path(),
+                cancel_token.clone(),
+            );
+        }
+        Some(Ok(_)) | None => {}
+        Some(Err(e)) => warn!(error = %e, "Ignoring approval rules; auto-approval disabled"),
+    }
+
+    match config_path.as_deref().map(load_notify_config) {
+        Some(Ok(notify)) if notify.is_enabled() => {
+            let _notify_handle = spawn_notifier(registry.clone(), notify, cancel_token.clone());
+        }
+        Some(Ok(_)) | None => {}
+        Some(Err(e)) => warn!(error = %e, "Ignoring [notify] config; notifications disabled"),
+    }
 
-    info!(socket = %socket_path, "Starting server");
+    let mut server = DaemonServer::new(&socket_path, registry.clone(), cancel_token.clone());
+    if let Some(config_path) = config_path {
+        match load_remote_config(&config_path) {
+            Ok(remote) if remote.is_enabled() => server = server.with_remote(remote),
+            Ok(_) => {}
+            Err(e) => error!(error = %e, "Ignoring [remote] config; remote listeners disabled"),
+        }
+    }
+
+    info!(socket = %socket_path.display(), "Starting server");
+
+    let server_result = server.run().await;
+
+    // Snapshot the registry before exiting so a restart picks up where we
+    // left off (the periodic snapshot may be a few seconds stale).
+    if let Err(e) = registry.flush().await {
+        warn!(error = %e, "Failed to write registry snapshot on shutdown");
+    }
+    // The server may also have stopped on an error; stop the ledger either
+    // way so it writes its final totals.
+    cancel_token.cancel();
+    if let Err(e) = ledger_handle.await {
+        warn!(error = %e, "Cost ledger task failed");
+    }
 
-    if let Err(e) = server.run().await {
+    if let Err(e) = server_result {
         error!(error = %e, "Server error");
         return Err(e.into());
     }
@@ -461,6 +461,7 @@ async fn scenario_protocol_e2e(env: &E2eEnv) -> String {
         DaemonMessage::Connected {
             protocol_version,
             client_id,
+            ..
         } => {
             assert_eq!(protocol_version, ProtocolVersion::CURRENT);
             assert_eq!(client_id, "e2e-harness");
@@ -471,7 +472,7 @@ async fn scenario_protocol_e2e(env: &E2eEnv) -> String {
     // Snapshot baseline so we tolerate sessions discovered from /proc.
     client.send(ClientMessage::list_sessions());
     let baseline_count = match client.recv() {
-        DaemonMessage::SessionList { sessions } => sessions.len(),
+        DaemonMessage::SessionList { sessions, .. } => sessions.len(),
         other => panic!("expected SessionList, got {other:?}"),
     };
 
@@ -501,7 +502,7 @@ async fn scenario_protocol_e2e(env: &E2eEnv) -> String {
     let found = loop {
         client.send(ClientMessage::list_sessions());
         let sessions = match client.recv() {
-            DaemonMessage::SessionList { sessions } => sessions,
+            DaemonMessage::SessionList { sessions, .. } => sessions,
             other => panic!("expected SessionList, got {other:?}"),
         };
 